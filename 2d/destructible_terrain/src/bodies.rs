@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use crate::{
+    terrain::{self, Terrain, PIXEL_SIZE, TERRAIN_SIZE},
+    Explosion,
+};
+
+// 重力加速度 (ワールド座標/秒²)
+const GRAVITY: Vec2 = Vec2::new(0.0, -900.0);
+// 地形に当たったときに残る法線方向の速さの割合 (反発係数) と、接線方向の速さの割合
+const RESTITUTION: f32 = 0.45;
+const FRICTION: f32 = 0.9;
+// 1 回の小ステップで進んでよい距離 (半径に掛ける倍率)。大きいと薄い地形をすり抜ける
+const MAX_STEP: f32 = 0.5;
+// 重なりから押し出すときに試す回数
+const MAX_PUSH_OUT: usize = 16;
+// 爆発で吹き飛ばす速さの最大値 (中心での値で、爆発の半径の 2 倍の距離で 0 になる)
+const BLAST_SPEED: f32 = 900.0;
+
+/// 地形のビットマップと当たり判定をしながら、重力で動く円形の物体を追加するプラグイン
+///
+/// 爆弾は地形に触れると爆発 ([`Explosion`]) し、ボールは跳ね返りながら転がる。
+/// どちらも爆発の近くにあると外向きに吹き飛ばされる。
+pub struct BodiesPlugin;
+
+impl Plugin for BodiesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, move_bodies)
+            .add_systems(Update, blast_bodies);
+    }
+}
+
+/// 地形と当たり判定をする円形の物体
+#[derive(Component)]
+pub struct Body {
+    pub radius: f32,
+    pub velocity: Vec2,
+    pub kind: BodyKind,
+}
+
+/// 物体の種類 (地形に当たったときの振る舞いが違う)
+#[derive(Clone, Copy)]
+pub enum BodyKind {
+    Bomb { blast_radius: f32 }, // 地形に触れると爆発する
+    Ball,                       // 地形で跳ね返る
+}
+
+/// 重力で物体を動かし、地形に重なったら押し出して跳ね返す
+///
+/// 速い物体が薄い地形をすり抜けないように、1 回の移動が半径の `MAX_STEP` 倍以下になるよう小ステップに分ける。
+/// 爆弾は地形に触れた位置で爆発させ、画面の下に落ちた物体は消す。
+fn move_bodies(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    images: Res<Assets<Image>>,
+    mut bodies: Query<(Entity, &mut Transform, &mut Body)>,
+    mut explosions: EventWriter<Explosion>,
+) {
+    let Some(image) = images.get(&terrain.image) else {
+        return;
+    };
+    let bottom = -(TERRAIN_SIZE.y as f32) * PIXEL_SIZE / 2.0;
+    for (entity, mut transform, mut body) in &mut bodies {
+        body.velocity += GRAVITY * time.delta_secs();
+        let travel = body.velocity * time.delta_secs();
+        let steps = (travel.length() / (body.radius * MAX_STEP)).ceil().max(1.0) as usize;
+        let mut position = transform.translation.truncate();
+
+        for _ in 0..steps {
+            position += travel / steps as f32;
+            let Some(normal) = terrain::surface_normal(image, position, body.radius) else {
+                continue;
+            };
+            if let BodyKind::Bomb { blast_radius } = body.kind {
+                explosions.send(Explosion {
+                    center: position,
+                    radius: blast_radius,
+                });
+                commands.entity(entity).despawn();
+                break;
+            }
+            // 地形から抜け出すまで法線の向きに少しずつ押し出す
+            for _ in 0..MAX_PUSH_OUT {
+                position += normal * PIXEL_SIZE / 2.0;
+                if terrain::surface_normal(image, position, body.radius).is_none() {
+                    break;
+                }
+            }
+            let normal_speed = body.velocity.dot(normal);
+            if normal_speed < 0.0 {
+                let tangent = body.velocity - normal * normal_speed;
+                body.velocity = tangent * FRICTION - normal * normal_speed * RESTITUTION;
+            }
+            break;
+        }
+
+        if position.y < bottom - body.radius {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+/// 爆発の近くにある物体を、爆発の中心から外向きに吹き飛ばす
+fn blast_bodies(
+    mut explosions: EventReader<Explosion>,
+    mut bodies: Query<(&Transform, &mut Body)>,
+) {
+    for explosion in explosions.read() {
+        for (transform, mut body) in &mut bodies {
+            let offset = transform.translation.truncate() - explosion.center;
+            let reach = explosion.radius * 2.0;
+            let strength = 1.0 - offset.length() / reach;
+            if strength > 0.0 {
+                body.velocity += offset.normalize_or(Vec2::Y) * BLAST_SPEED * strength;
+            }
+        }
+    }
+}
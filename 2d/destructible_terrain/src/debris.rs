@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use crate::terrain::{self, Terrain};
+
+// 削ったピクセルの何個に 1 個を破片にするか
+const DEBRIS_EVERY: usize = 6;
+// 同時に存在できる破片の最大数 (超えた分は作らない)
+const MAX_DEBRIS: usize = 3000;
+// 破片の大きさ (ワールド座標)
+const DEBRIS_SIZE: f32 = 3.0;
+// 破片が飛び散る速さの範囲 (ワールド座標/秒)
+const DEBRIS_SPEED: (f32, f32) = (150.0, 600.0);
+// 破片が消えるまでの時間の範囲 (秒)
+const DEBRIS_LIFETIME: (f32, f32) = (1.5, 3.0);
+// 破片にかかる重力加速度
+const GRAVITY: Vec2 = Vec2::new(0.0, -900.0);
+// 破片が地形に当たったときに残る縦・横の速さの割合
+const BOUNCE: Vec2 = Vec2::new(0.5, -0.3);
+
+/// 地形を削ったときに飛び散る破片 (パーティクル) を追加するプラグイン
+///
+/// 破片は削ったピクセルの色のスプライトで、重力で落ち、地形のビットマップに当たると跳ねて止まり、
+/// 寿命が近づくと薄くなって消える。
+pub struct DebrisPlugin;
+
+impl Plugin for DebrisPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DebrisRng(0x9e37_79b9))
+            .add_systems(Update, update_debris);
+    }
+}
+
+/// 地形の破片
+#[derive(Component)]
+pub struct Debris {
+    velocity: Vec2,
+    life: Timer,
+}
+
+/// 破片の向きや寿命をばらつかせる乱数 (xorshift)
+#[derive(Resource)]
+pub struct DebrisRng(u32);
+
+impl DebrisRng {
+    /// 0 から 1 の乱数
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        (self.0 & 0x00ff_ffff) as f32 / 0x00ff_ffff as f32
+    }
+
+    /// `range` の範囲の乱数
+    fn range(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.next()
+    }
+}
+
+/// 削ったピクセルから破片を作る (`DEBRIS_EVERY` 個に 1 個だけ)
+///
+/// 破片は爆発の中心から外向きに、少し上向きに寄せて飛ばす。`existing` は今ある破片の数で、作った破片の数を返す。
+pub fn spawn_debris(
+    commands: &mut Commands,
+    rng: &mut DebrisRng,
+    center: Vec2,
+    removed: &[(Vec2, Color)],
+    existing: usize,
+) -> usize {
+    let room = MAX_DEBRIS.saturating_sub(existing);
+    let mut spawned = 0;
+    for &(position, color) in removed.iter().step_by(DEBRIS_EVERY).take(room) {
+        let outward = (position - center).normalize_or(Vec2::Y);
+        let direction = (outward + Vec2::new(rng.range((-0.4, 0.4)), 0.6)).normalize_or(Vec2::Y);
+        commands.spawn((
+            Sprite::from_color(color, Vec2::splat(DEBRIS_SIZE)),
+            Transform::from_translation(position.extend(1.0)),
+            Debris {
+                velocity: direction * rng.range(DEBRIS_SPEED),
+                life: Timer::from_seconds(rng.range(DEBRIS_LIFETIME), TimerMode::Once),
+            },
+        ));
+        spawned += 1;
+    }
+    spawned
+}
+
+/// 破片を重力で動かし、地形に当たったら跳ねさせ、寿命が来たら消す
+fn update_debris(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    images: Res<Assets<Image>>,
+    mut debris: Query<(Entity, &mut Transform, &mut Sprite, &mut Debris)>,
+) {
+    let Some(image) = images.get(&terrain.image) else {
+        return;
+    };
+    for (entity, mut transform, mut sprite, mut piece) in &mut debris {
+        if piece.life.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        piece.velocity += GRAVITY * time.delta_secs();
+        let next = transform.translation.truncate() + piece.velocity * time.delta_secs();
+        if terrain::is_solid(image, next) {
+            // 地形の中には入らず、その場で跳ね返る
+            piece.velocity *= BOUNCE;
+        } else {
+            transform.translation = next.extend(transform.translation.z);
+        }
+        sprite.color.set_alpha(1.0 - piece.life.fraction().powi(3));
+    }
+}
@@ -0,0 +1,308 @@
+use bevy::{
+    color::palettes::css::{ORANGE, YELLOW},
+    input::{common_conditions::input_just_pressed, mouse::AccumulatedMouseScroll},
+    prelude::*,
+};
+
+mod bodies;
+mod debris;
+mod terrain;
+
+use bodies::{Body, BodyKind};
+use debris::{Debris, DebrisRng};
+use terrain::{Terrain, PIXEL_SIZE, TERRAIN_SIZE};
+
+// 空の色
+const SKY_COLOR: Color = Color::srgb(0.45, 0.65, 0.9);
+// 爆発の半径の初期値と範囲 (ワールド座標)、ホイール 1 目盛りで変わる量
+const DEFAULT_BLAST_RADIUS: f32 = 40.0;
+const BLAST_RADIUS_RANGE: (f32, f32) = (10.0, 120.0);
+const BLAST_RADIUS_STEP: f32 = 5.0;
+// 爆弾とボールの半径と色
+const BOMB_RADIUS: f32 = 6.0;
+const BOMB_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const BALL_RADIUS: f32 = 10.0;
+const BALL_COLOR: Color = Color::srgb(0.95, 0.3, 0.3);
+// 爆発の輪を表示する時間 (秒)
+const BLAST_RING_SECONDS: f32 = 0.25;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Destructible Terrain".into(),
+                resolution: (TERRAIN_SIZE.as_vec2() * PIXEL_SIZE).into(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins((bodies::BodiesPlugin, debris::DebrisPlugin))
+        .insert_resource(ClearColor(SKY_COLOR))
+        .insert_resource(BlastRadius(DEFAULT_BLAST_RADIUS))
+        .insert_resource(Seed(1))
+        .add_event::<Explosion>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                (
+                    change_blast_radius,
+                    drop_bomb.run_if(input_just_pressed(MouseButton::Left)),
+                    detonate_at_cursor.run_if(input_just_pressed(MouseButton::Right)),
+                    spawn_ball.run_if(input_just_pressed(KeyCode::KeyB)),
+                    regenerate_terrain.run_if(input_just_pressed(KeyCode::KeyR)),
+                ),
+                explode,
+                (draw_blast_rings, draw_cursor, update_info),
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// 爆発が起きたときのイベント (爆弾が地形に触れたときと右クリックで送る)
+#[derive(Event, Clone, Copy)]
+pub struct Explosion {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+/// 次に起こす爆発の半径
+#[derive(Resource, Deref, DerefMut)]
+struct BlastRadius(f32);
+
+/// 地形を作るシード値 (`R` キーで作り直すたびに増やす)
+#[derive(Resource, Deref, DerefMut)]
+struct Seed(u32);
+
+/// 爆発の輪 (少しの間だけ広がりながら表示する)
+#[derive(Component)]
+struct BlastRing {
+    radius: f32,
+    timer: Timer,
+}
+
+/// 操作方法と地形の状態を表示するテキスト
+#[derive(Component)]
+struct InfoText;
+
+/// カメラ・地形のスプライト・説明のテキストを配置する
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>, seed: Res<Seed>) {
+    commands.spawn(Camera2d);
+
+    let image = images.add(terrain::generate(**seed));
+    commands.spawn(Sprite {
+        image: image.clone(),
+        custom_size: Some(TERRAIN_SIZE.as_vec2() * PIXEL_SIZE),
+        ..default()
+    });
+    commands.insert_resource(Terrain { image });
+
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+}
+
+/// カーソルの位置をワールド座標で返す
+fn cursor_position(window: &Window, camera: (&Camera, &GlobalTransform)) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    camera.0.viewport_to_world_2d(camera.1, cursor).ok()
+}
+
+/// マウスホイールで爆発の半径を変える
+fn change_blast_radius(scroll: Res<AccumulatedMouseScroll>, mut radius: ResMut<BlastRadius>) {
+    if scroll.delta.y != 0.0 {
+        **radius = (**radius + scroll.delta.y.signum() * BLAST_RADIUS_STEP)
+            .clamp(BLAST_RADIUS_RANGE.0, BLAST_RADIUS_RANGE.1);
+    }
+}
+
+/// 左クリックでカーソルの位置から爆弾を落とす (地形に触れると爆発する)
+fn drop_bomb(
+    mut commands: Commands,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    radius: Res<BlastRadius>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(cursor) = cursor_position(&window, *camera) else {
+        return;
+    };
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(BOMB_RADIUS))),
+        MeshMaterial2d(materials.add(BOMB_COLOR)),
+        Transform::from_translation(cursor.extend(2.0)),
+        Body {
+            radius: BOMB_RADIUS,
+            velocity: Vec2::ZERO,
+            kind: BodyKind::Bomb {
+                blast_radius: **radius,
+            },
+        },
+    ));
+}
+
+/// 右クリックでカーソルの位置をその場で爆発させる
+fn detonate_at_cursor(
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    radius: Res<BlastRadius>,
+    mut explosions: EventWriter<Explosion>,
+) {
+    if let Some(cursor) = cursor_position(&window, *camera) {
+        explosions.send(Explosion {
+            center: cursor,
+            radius: **radius,
+        });
+    }
+}
+
+/// `B` キーでカーソルの位置に地形の上を転がるボールを置く
+fn spawn_ball(
+    mut commands: Commands,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(cursor) = cursor_position(&window, *camera) else {
+        return;
+    };
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(BALL_RADIUS))),
+        MeshMaterial2d(materials.add(BALL_COLOR)),
+        Transform::from_translation(cursor.extend(2.0)),
+        Body {
+            radius: BALL_RADIUS,
+            velocity: Vec2::ZERO,
+            kind: BodyKind::Ball,
+        },
+    ));
+}
+
+/// `R` キーで新しいシード値から地形を作り直し、物体と破片を消す
+#[allow(clippy::type_complexity)]
+fn regenerate_terrain(
+    mut commands: Commands,
+    mut seed: ResMut<Seed>,
+    terrain: Res<Terrain>,
+    mut images: ResMut<Assets<Image>>,
+    entities: Query<Entity, Or<(With<Body>, With<Debris>)>>,
+) {
+    **seed += 1;
+    images.insert(&terrain.image, terrain::generate(**seed));
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// 爆発の範囲の地形を削り、削ったピクセルから破片を飛ばす
+///
+/// `get_mut` で取り出した `Image` はアセットが変更されたことになり、書き換えたピクセルが GPU に送り直される。
+fn explode(
+    mut commands: Commands,
+    mut explosions: EventReader<Explosion>,
+    terrain: Res<Terrain>,
+    mut images: ResMut<Assets<Image>>,
+    mut rng: ResMut<DebrisRng>,
+    debris: Query<(), With<Debris>>,
+) {
+    let mut existing = debris.iter().count();
+    for explosion in explosions.read() {
+        let Some(image) = images.get_mut(&terrain.image) else {
+            return;
+        };
+        let mut removed = Vec::new();
+        terrain::carve(
+            image,
+            explosion.center,
+            explosion.radius,
+            |position, color| {
+                removed.push((position, color));
+            },
+        );
+        existing += debris::spawn_debris(
+            &mut commands,
+            &mut rng,
+            explosion.center,
+            &removed,
+            existing,
+        );
+        commands.spawn((
+            Transform::from_translation(explosion.center.extend(0.0)),
+            BlastRing {
+                radius: explosion.radius,
+                timer: Timer::from_seconds(BLAST_RING_SECONDS, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+/// 爆発の輪を広げながら描き、時間が来たら消す
+fn draw_blast_rings(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rings: Query<(Entity, &Transform, &mut BlastRing)>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, transform, mut ring) in &mut rings {
+        if ring.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = ring.timer.fraction();
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            ring.radius * (0.6 + 0.6 * t),
+            YELLOW.with_alpha(1.0 - t),
+        );
+    }
+}
+
+/// カーソルの位置に、次に起こす爆発の範囲を描く
+fn draw_cursor(
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    radius: Res<BlastRadius>,
+    mut gizmos: Gizmos,
+) {
+    if let Some(cursor) = cursor_position(&window, *camera) {
+        gizmos.circle_2d(cursor, **radius, ORANGE);
+    }
+}
+
+/// 操作方法と、残っている地形のピクセル数・物体と破片の数を表示する
+fn update_info(
+    terrain: Res<Terrain>,
+    images: Res<Assets<Image>>,
+    radius: Res<BlastRadius>,
+    seed: Res<Seed>,
+    bodies: Query<(), With<Body>>,
+    debris: Query<(), With<Debris>>,
+    mut text: Single<&mut Text, With<InfoText>>,
+) {
+    let Some(image) = images.get(&terrain.image) else {
+        return;
+    };
+    text.0 = format!(
+        "Left click: drop bomb / Right click: explode / B: ball / Wheel: radius / R: new terrain\n\
+         Blast radius: {:.0}  Seed: {}  Solid pixels: {}  Bodies: {}  Debris: {}",
+        **radius,
+        **seed,
+        terrain::solid_pixel_count(image),
+        bodies.iter().count(),
+        debris.iter().count(),
+    );
+}
@@ -0,0 +1,195 @@
+//! 地形のビットマップ (`Image`) の生成と、ピクセルを読み書きして行う当たり判定・破壊
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+// 地形のビットマップの大きさ (ピクセル)
+pub const TERRAIN_SIZE: UVec2 = UVec2::new(640, 360);
+// ビットマップの 1 ピクセルを描く大きさ (ワールド座標)
+pub const PIXEL_SIZE: f32 = 2.0;
+// 草・土の層の厚さ (ピクセル)。それより深いところは岩にする
+const GRASS_DEPTH: u32 = 5;
+const DIRT_DEPTH: u32 = 40;
+// 爆発の穴のまわりを焦がす幅 (穴の半径に掛ける倍率) と暗くする割合
+const SCORCH_WIDTH: f32 = 1.2;
+const SCORCH_DARKEN: f32 = 0.6;
+
+/// 地形のビットマップ
+///
+/// アルファが 0 でないピクセルを固体として扱う。描画は同じ `Image` をスプライトで表示するだけなので、
+/// ピクセルを書き換えると見た目と当たり判定が同時に変わる。
+#[derive(Resource)]
+pub struct Terrain {
+    pub image: Handle<Image>,
+}
+
+/// ワールド座標をビットマップのピクセル座標 (左上が原点で y は下向き) に直す
+fn to_pixel(position: Vec2) -> IVec2 {
+    let half = TERRAIN_SIZE.as_vec2() * PIXEL_SIZE / 2.0;
+    let pixel = Vec2::new(position.x + half.x, half.y - position.y) / PIXEL_SIZE;
+    pixel.floor().as_ivec2()
+}
+
+/// ピクセルの中心のワールド座標
+fn to_world(pixel: IVec2) -> Vec2 {
+    let half = TERRAIN_SIZE.as_vec2() * PIXEL_SIZE / 2.0;
+    Vec2::new(
+        (pixel.x as f32 + 0.5) * PIXEL_SIZE - half.x,
+        half.y - (pixel.y as f32 + 0.5) * PIXEL_SIZE,
+    )
+}
+
+/// ピクセルの RGBA のバイト列の先頭の添字 (ビットマップの外なら `None`)
+fn byte_index(pixel: IVec2) -> Option<usize> {
+    let size = TERRAIN_SIZE.as_ivec2();
+    if pixel.x < 0 || pixel.y < 0 || pixel.x >= size.x || pixel.y >= size.y {
+        return None;
+    }
+    Some((pixel.y * size.x + pixel.x) as usize * 4)
+}
+
+/// `center` を中心とした半径 `radius` (ワールド座標) の円に含まれるピクセル
+fn pixels_in_circle(center: Vec2, radius: f32) -> impl Iterator<Item = IVec2> {
+    let min = to_pixel(center + Vec2::new(-radius, radius));
+    let max = to_pixel(center + Vec2::new(radius, -radius));
+    (min.y..=max.y)
+        .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+        .filter(move |&pixel| to_world(pixel).distance_squared(center) <= radius * radius)
+}
+
+/// ワールド座標の点が地形の中にあるかどうか (ビットマップの外は空とみなす)
+pub fn is_solid(image: &Image, position: Vec2) -> bool {
+    byte_index(to_pixel(position)).is_some_and(|i| image.data[i + 3] != 0)
+}
+
+/// 円と地形が重なっていれば、重なりから抜け出す向き (地形の表面の法線) を返す
+///
+/// 円の中にある固体のピクセルへ向かうベクトルを足し合わせ、その反対向きを法線とする。
+/// 周りがすべて地形に埋まっていて向きが決まらないときは上向きにする。
+pub fn surface_normal(image: &Image, center: Vec2, radius: f32) -> Option<Vec2> {
+    let mut sum = Vec2::ZERO;
+    let mut count = 0;
+    for pixel in pixels_in_circle(center, radius) {
+        if byte_index(pixel).is_some_and(|i| image.data[i + 3] != 0) {
+            sum += to_world(pixel) - center;
+            count += 1;
+        }
+    }
+    (count > 0).then(|| (-sum).normalize_or(Vec2::Y))
+}
+
+/// 円の中の地形を削り、削ったピクセルごとに位置と色を `on_removed` に渡す
+///
+/// 穴のまわりの少し外側のピクセルは暗くして、焦げ跡にする。削ったピクセルの数を返す。
+pub fn carve(
+    image: &mut Image,
+    center: Vec2,
+    radius: f32,
+    mut on_removed: impl FnMut(Vec2, Color),
+) -> usize {
+    let mut removed = 0;
+    for pixel in pixels_in_circle(center, radius * SCORCH_WIDTH) {
+        let Some(i) = byte_index(pixel) else {
+            continue;
+        };
+        if image.data[i + 3] == 0 {
+            continue;
+        }
+        let position = to_world(pixel);
+        if position.distance_squared(center) <= radius * radius {
+            let [r, g, b, _] = [0, 1, 2, 3].map(|c| image.data[i + c]);
+            on_removed(position, Color::srgb_u8(r, g, b));
+            image.data[i..i + 4].fill(0);
+            removed += 1;
+        } else {
+            for c in 0..3 {
+                image.data[i + c] = (image.data[i + c] as f32 * SCORCH_DARKEN) as u8;
+            }
+        }
+    }
+    removed
+}
+
+/// 地形の中にあるピクセルの数
+pub fn solid_pixel_count(image: &Image) -> usize {
+    image
+        .data
+        .chunks_exact(4)
+        .filter(|rgba| rgba[3] != 0)
+        .count()
+}
+
+/// シード値から丘と洞窟のある地形のビットマップを作る
+///
+/// 地表の高さは周期の違う正弦波の和で、地表からの深さで草・土・岩の色を塗り分ける。
+/// 洞窟は 2 つの正弦波の積が大きいところをくり抜いて作る。
+pub fn generate(seed: u32) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: TERRAIN_SIZE.x,
+            height: TERRAIN_SIZE.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        // 当たり判定でピクセルを読むので、メインワールドにもデータを残す
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+    // ピクセルの境界をぼかさずに拡大する
+    image.sampler = ImageSampler::nearest();
+
+    let phase = hash(seed, 0) * std::f32::consts::TAU;
+    let (width, height) = (TERRAIN_SIZE.x, TERRAIN_SIZE.y);
+    for x in 0..width {
+        let t = x as f32 / width as f32;
+        let surface = height as f32
+            * (0.45
+                + 0.12 * (t * 5.0 + phase).sin()
+                + 0.06 * (t * 13.0 + phase * 2.0).sin()
+                + 0.02 * (t * 37.0 + phase * 3.0).sin());
+        let top = height.saturating_sub(surface as u32);
+        for y in top..height {
+            let depth = y - top;
+            let (xf, yf) = (x as f32, y as f32);
+            let cave = (xf * 0.031 + phase).sin() * (yf * 0.047 - phase).sin()
+                + 0.5 * ((xf + yf) * 0.017).sin();
+            if depth > GRASS_DEPTH * 2 && cave > 1.05 {
+                continue;
+            }
+            let i = ((y * width + x) * 4) as usize;
+            image.data[i..i + 4].copy_from_slice(&ground_color(x, y, depth, seed));
+        }
+    }
+    image
+}
+
+/// 地表からの深さに応じた地面の色 (ピクセルごとに少し明るさを揺らす)
+fn ground_color(x: u32, y: u32, depth: u32, seed: u32) -> [u8; 4] {
+    let base = if depth < GRASS_DEPTH {
+        [86.0, 160.0, 58.0]
+    } else if depth < DIRT_DEPTH {
+        [134.0, 96.0, 62.0]
+    } else if (y / 6 + x / 40).is_multiple_of(5) {
+        // 岩の地層の縞
+        [96.0, 92.0, 98.0]
+    } else {
+        [118.0, 112.0, 116.0]
+    };
+    let shade = 0.85 + 0.3 * hash(seed, y * TERRAIN_SIZE.x + x);
+    let [r, g, b] = base.map(|c: f32| (c * shade).min(255.0) as u8);
+    [r, g, b, 255]
+}
+
+/// シード値と添字から 0 から 1 の擬似乱数を作る
+fn hash(seed: u32, index: u32) -> f32 {
+    let mut h = index.wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    (h & 0x00ff_ffff) as f32 / 0x00ff_ffff as f32
+}
@@ -0,0 +1,88 @@
+//! 格子点でサンプリングしたスカラー場と、場を作る関数 (メタボールとノイズ)
+
+use bevy::prelude::*;
+
+/// 等間隔の格子点で関数をサンプリングした値
+///
+/// セルは `columns` × `rows` 個で、格子点はそれより縦横に 1 つずつ多い。
+/// 格子点 (x, y) の位置は `origin + (x, y) * cell_size` で、y は上向きに増える。
+pub struct ScalarGrid {
+    pub columns: usize,
+    pub rows: usize,
+    pub origin: Vec2,
+    pub cell_size: f32,
+    values: Vec<f32>,
+}
+
+impl ScalarGrid {
+    /// `origin` を左下として `size` の範囲を `cell_size` ごとにサンプリングする
+    pub fn sample(origin: Vec2, size: Vec2, cell_size: f32, field: impl Fn(Vec2) -> f32) -> Self {
+        let columns = (size.x / cell_size).floor().max(1.0) as usize;
+        let rows = (size.y / cell_size).floor().max(1.0) as usize;
+        let mut values = Vec::with_capacity((columns + 1) * (rows + 1));
+        for y in 0..=rows {
+            for x in 0..=columns {
+                values.push(field(origin + Vec2::new(x as f32, y as f32) * cell_size));
+            }
+        }
+        ScalarGrid {
+            columns,
+            rows,
+            origin,
+            cell_size,
+            values,
+        }
+    }
+
+    /// 格子点 (x, y) の値
+    pub fn value(&self, x: usize, y: usize) -> f32 {
+        self.values[y * (self.columns + 1) + x]
+    }
+
+    /// 格子点 (x, y) の位置
+    pub fn point(&self, x: usize, y: usize) -> Vec2 {
+        self.origin + Vec2::new(x as f32, y as f32) * self.cell_size
+    }
+
+    /// 格子の範囲の大きさ
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.columns as f32, self.rows as f32) * self.cell_size
+    }
+}
+
+/// メタボールの場の値
+///
+/// 球ごとに `半径² / 距離²` を足し合わせる。球の中心に近いほど大きく、
+/// 1 つだけのときは値がちょうど 1 になる位置が半径の円になる。近くの球どうしは値が足されて滑らかにつながる。
+pub fn metaballs(point: Vec2, balls: impl IntoIterator<Item = (Vec2, f32)>) -> f32 {
+    balls
+        .into_iter()
+        .map(|(center, radius)| radius * radius / (point.distance_squared(center) + 1.0))
+        .sum()
+}
+
+/// 0 から 1 の値を返す滑らかなノイズ (バリューノイズ)
+///
+/// 整数の格子点ごとにハッシュで乱数を決め、その間をスムーズステップで補間する。
+pub fn value_noise(point: Vec2) -> f32 {
+    let cell = point.floor();
+    let t = point - cell;
+    let t = t * t * (3.0 - 2.0 * t);
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let bottom = lerp(hash(x, y), hash(x + 1, y), t.x);
+    let top = lerp(hash(x, y + 1), hash(x + 1, y + 1), t.x);
+    lerp(bottom, top, t.y)
+}
+
+/// 整数の座標から 0 から 1 の擬似乱数を作る
+fn hash(x: i32, y: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    (h & 0x00ff_ffff) as f32 / 0x00ff_ffff as f32
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
@@ -0,0 +1,399 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    color::palettes::css::{DARK_SLATE_GRAY, ORANGE, WHITE},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    render::mesh::PrimitiveTopology,
+    utils::Instant,
+};
+
+mod field;
+mod marching;
+
+use field::ScalarGrid;
+
+// スカラー場をサンプリングする範囲の大きさ (ピクセル、画面の中央に置く)
+const FIELD_SIZE: Vec2 = Vec2::new(1200.0, 620.0);
+// 選べるセルの大きさ (ピクセル)。小さいほど滑らかになるが、セルの数が増えて重くなる
+const CELL_SIZES: [f32; 7] = [4.0, 6.0, 8.0, 12.0, 16.0, 24.0, 32.0];
+// 最初に使うセルの大きさ (`CELL_SIZES` の添字)
+const DEFAULT_CELL_SIZE: usize = 2;
+// しきい値の初期値・範囲と、キーを押し続けたときに 1 秒で変わる量
+const DEFAULT_THRESHOLD: f32 = 1.0;
+const THRESHOLD_RANGE: (f32, f32) = (0.1, 4.0);
+const THRESHOLD_SPEED: f32 = 1.0;
+// 最初に置くメタボールの数と半径の範囲 (ピクセル)
+const BLOB_COUNT: usize = 6;
+const BLOB_RADIUS: (f32, f32) = (40.0, 80.0);
+// メタボールが漂う速さ (ピクセル/秒)
+const DRIFT_SPEED: f32 = 90.0;
+// 右クリックで追加するメタボールの半径
+const NEW_BLOB_RADIUS: f32 = 60.0;
+// ノイズの大きさ (場の値に足す量の最大) と細かさ (1 ピクセルあたりの周波数)、流れる速さ
+const NOISE_AMPLITUDE: f32 = 0.6;
+const NOISE_FREQUENCY: f32 = 0.012;
+const NOISE_SPEED: f32 = 0.4;
+// 塗りつぶしと輪郭線の色
+const FILL_COLOR: Color = Color::srgb(0.15, 0.45, 0.75);
+const OUTLINE_COLOR: Srgba = WHITE;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ClearColor(Color::srgb(0.05, 0.05, 0.08)))
+        .insert_resource(Settings {
+            threshold: DEFAULT_THRESHOLD,
+            cell_size: DEFAULT_CELL_SIZE,
+            noise: true,
+            drift: true,
+            grid: false,
+        })
+        .init_resource::<Dragging>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                (
+                    change_threshold,
+                    change_cell_size,
+                    toggle_noise.run_if(input_just_pressed(KeyCode::KeyN)),
+                    toggle_drift.run_if(input_just_pressed(KeyCode::Space)),
+                    toggle_grid.run_if(input_just_pressed(KeyCode::KeyG)),
+                    drag_blobs,
+                    add_or_remove_blob.run_if(input_just_pressed(MouseButton::Right)),
+                ),
+                drift_blobs,
+                rebuild_contour,
+                draw_blobs,
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// 表示の設定
+#[derive(Resource)]
+struct Settings {
+    threshold: f32,   // この値以上の部分を塗りつぶす
+    cell_size: usize, // `CELL_SIZES` の添字
+    noise: bool,      // 場にノイズを足すかどうか
+    drift: bool,      // メタボールを漂わせるかどうか
+    grid: bool,       // サンプリングの格子を表示するかどうか
+}
+
+/// メタボール (場の値を盛り上げる円)
+#[derive(Component)]
+struct Blob {
+    radius: f32,
+    velocity: Vec2, // 漂う速度
+}
+
+/// マウスでドラッグしているメタボール
+#[derive(Resource, Default)]
+struct Dragging(Option<Entity>);
+
+/// 輪郭を描くメッシュ (毎フレーム頂点を書き換える)
+#[derive(Resource)]
+struct ContourMesh(Handle<Mesh>);
+
+/// 設定と計測結果を表示するテキスト
+#[derive(Component)]
+struct InfoText;
+
+/// カメラ・メタボール・輪郭のメッシュ・説明のテキストを配置する
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn(Camera2d);
+
+    // メタボールを範囲の中にばらばらに置く (乱数の代わりに黄金角で散らす)
+    for i in 0..BLOB_COUNT {
+        let angle = i as f32 * 2.399_963;
+        let t = (i as f32 + 0.5) / BLOB_COUNT as f32;
+        commands.spawn((
+            Transform::from_translation(
+                (Vec2::from_angle(angle) * FIELD_SIZE * 0.35 * t.sqrt()).extend(0.0),
+            ),
+            Blob {
+                radius: BLOB_RADIUS.0 + (BLOB_RADIUS.1 - BLOB_RADIUS.0) * (i % 3) as f32 / 2.0,
+                velocity: Vec2::from_angle(angle * 1.7) * DRIFT_SPEED,
+            },
+        ));
+    }
+
+    let mesh = meshes.add(Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    ));
+    commands.spawn((
+        Mesh2d(mesh.clone()),
+        MeshMaterial2d(materials.add(FILL_COLOR)),
+    ));
+    commands.insert_resource(ContourMesh(mesh));
+
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+}
+
+/// `↑` / `↓` キーでしきい値を変える
+fn change_threshold(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut settings: ResMut<Settings>,
+) {
+    let mut direction = 0.0;
+    if keyboard_input.pressed(KeyCode::ArrowUp) {
+        direction += 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::ArrowDown) {
+        direction -= 1.0;
+    }
+    if direction != 0.0 {
+        settings.threshold = (settings.threshold + direction * THRESHOLD_SPEED * time.delta_secs())
+            .clamp(THRESHOLD_RANGE.0, THRESHOLD_RANGE.1);
+    }
+}
+
+/// `←` / `→` キーでセルの大きさ (解像度) を変える
+fn change_cell_size(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        settings.cell_size = settings.cell_size.saturating_sub(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        settings.cell_size = (settings.cell_size + 1).min(CELL_SIZES.len() - 1);
+    }
+}
+
+/// `N` キーでノイズの有無を切り替える
+fn toggle_noise(mut settings: ResMut<Settings>) {
+    settings.noise = !settings.noise;
+}
+
+/// `Space` キーでメタボールを漂わせるかどうかを切り替える
+fn toggle_drift(mut settings: ResMut<Settings>) {
+    settings.drift = !settings.drift;
+}
+
+/// `G` キーでサンプリングの格子の表示を切り替える
+fn toggle_grid(mut settings: ResMut<Settings>) {
+    settings.grid = !settings.grid;
+}
+
+/// カーソルの位置をワールド座標で返す
+fn cursor_position(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    camera.viewport_to_world_2d(camera_transform, cursor).ok()
+}
+
+/// 左ボタンでメタボールをつかみ、押している間カーソルに追従させる
+fn drag_blobs(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut dragging: ResMut<Dragging>,
+    mut blobs: Query<(Entity, &mut Transform, &Blob)>,
+) {
+    if mouse_input.just_released(MouseButton::Left) {
+        dragging.0 = None;
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = cursor_position(&window, camera, camera_transform) else {
+        return;
+    };
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        dragging.0 = blob_at(cursor, &blobs);
+    }
+    if let Some((_, mut transform, _)) = dragging.0.and_then(|entity| blobs.get_mut(entity).ok()) {
+        let half = FIELD_SIZE / 2.0;
+        transform.translation = cursor.clamp(-half, half).extend(0.0);
+    }
+}
+
+/// カーソルの下にある (カーソルが半径の中にある) いちばん近いメタボール
+fn blob_at(cursor: Vec2, blobs: &Query<(Entity, &mut Transform, &Blob)>) -> Option<Entity> {
+    blobs
+        .iter()
+        .map(|(entity, transform, blob)| {
+            let distance = transform.translation.truncate().distance(cursor);
+            (entity, distance, blob.radius)
+        })
+        .filter(|(_, distance, radius)| distance <= radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _, _)| entity)
+}
+
+/// 右クリックで、メタボールの上なら消し、何もないところなら追加する
+fn add_or_remove_blob(
+    mut commands: Commands,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    blobs: Query<(Entity, &mut Transform, &Blob)>,
+) {
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = cursor_position(&window, camera, camera_transform) else {
+        return;
+    };
+    if let Some(entity) = blob_at(cursor, &blobs) {
+        commands.entity(entity).despawn();
+        return;
+    }
+    // 追加した位置から漂い出す向きはカーソルの位置から決める
+    let angle = cursor.x * 0.37 + cursor.y * 0.11;
+    commands.spawn((
+        Transform::from_translation(cursor.extend(0.0)),
+        Blob {
+            radius: NEW_BLOB_RADIUS,
+            velocity: Vec2::from_angle(angle) * DRIFT_SPEED,
+        },
+    ));
+}
+
+/// メタボールを漂わせ、範囲の端で跳ね返す (ドラッグしているメタボールは動かさない)
+fn drift_blobs(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    dragging: Res<Dragging>,
+    mut blobs: Query<(Entity, &mut Transform, &mut Blob)>,
+) {
+    if !settings.drift {
+        return;
+    }
+    let half = FIELD_SIZE / 2.0;
+    for (entity, mut transform, mut blob) in &mut blobs {
+        if dragging.0 == Some(entity) {
+            continue;
+        }
+        let mut position = transform.translation.truncate() + blob.velocity * time.delta_secs();
+        if position.x.abs() > half.x - blob.radius {
+            blob.velocity.x = -blob.velocity.x.abs() * position.x.signum();
+            position.x = position
+                .x
+                .clamp(-half.x + blob.radius, half.x - blob.radius);
+        }
+        if position.y.abs() > half.y - blob.radius {
+            blob.velocity.y = -blob.velocity.y.abs() * position.y.signum();
+            position.y = position
+                .y
+                .clamp(-half.y + blob.radius, half.y - blob.radius);
+        }
+        transform.translation = position.extend(0.0);
+    }
+}
+
+/// スカラー場をサンプリングし直し、マーチングスクエア法で作った輪郭をメッシュに書き込む
+///
+/// 場の値は、メタボールの値と (有効なら) 時間とともに流れるノイズの和。
+/// 輪郭線はギズモで、サンプリングの格子は `G` キーで有効にしたときだけ描く。
+fn rebuild_contour(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    blobs: Query<(&Transform, &Blob)>,
+    contour_mesh: Res<ContourMesh>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut gizmos: Gizmos,
+    mut text: Single<&mut Text, With<InfoText>>,
+) {
+    let balls: Vec<(Vec2, f32)> = blobs
+        .iter()
+        .map(|(transform, blob)| (transform.translation.truncate(), blob.radius))
+        .collect();
+    let noise_offset = Vec2::new(time.elapsed_secs() * NOISE_SPEED, 0.0);
+    let field = |point: Vec2| {
+        let mut value = field::metaballs(point, balls.iter().copied());
+        if settings.noise {
+            // ノイズは 0 から 1 なので、-1 から 1 に直してから足す
+            let noise = field::value_noise(point * NOISE_FREQUENCY + noise_offset);
+            value += (noise * 2.0 - 1.0) * NOISE_AMPLITUDE;
+        }
+        value
+    };
+
+    let cell_size = CELL_SIZES[settings.cell_size];
+    let started = Instant::now();
+    let grid = ScalarGrid::sample(-FIELD_SIZE / 2.0, FIELD_SIZE, cell_size, field);
+    let contour = marching::march(&grid, settings.threshold);
+    let elapsed = started.elapsed();
+
+    let bounds = Rect::from_corners(grid.origin, grid.origin + grid.size());
+    if let Some(mesh) = meshes.get_mut(&contour_mesh.0) {
+        contour.write_mesh(mesh, bounds);
+    }
+    for (start, end) in &contour.segments {
+        gizmos.line_2d(*start, *end, OUTLINE_COLOR);
+    }
+    gizmos.rect_2d(
+        Isometry2d::from_translation(bounds.center()),
+        bounds.size(),
+        DARK_SLATE_GRAY,
+    );
+    if settings.grid {
+        gizmos.grid_2d(
+            Isometry2d::from_translation(bounds.center()),
+            UVec2::new(grid.columns as u32, grid.rows as u32),
+            Vec2::splat(cell_size),
+            DARK_SLATE_GRAY,
+        );
+    }
+
+    text.0 = format!(
+        "Threshold: {:.2} (Up/Down)   Cell size: {cell_size}px (Left/Right)   Noise: {} (N)   Drift: {} (Space)   Grid (G)\n\
+         Left drag: move a blob   Right click: add / remove a blob\n\
+         {} x {} cells, {} triangles, {} outline segments, {:.2} ms",
+        settings.threshold,
+        on_off(settings.noise),
+        on_off(settings.drift),
+        grid.columns,
+        grid.rows,
+        contour.triangle_count(),
+        contour.segments.len(),
+        elapsed.as_secs_f64() * 1000.0,
+    );
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// メタボールの中心と半径をギズモで描く
+fn draw_blobs(
+    mut gizmos: Gizmos,
+    dragging: Res<Dragging>,
+    blobs: Query<(Entity, &Transform, &Blob)>,
+) {
+    for (entity, transform, blob) in &blobs {
+        let color = if dragging.0 == Some(entity) {
+            ORANGE
+        } else {
+            DARK_SLATE_GRAY
+        };
+        gizmos.circle_2d(
+            Isometry2d::from_translation(transform.translation.truncate()),
+            blob.radius,
+            color,
+        );
+    }
+}
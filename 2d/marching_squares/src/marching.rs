@@ -0,0 +1,130 @@
+//! マーチングスクエア法で、スカラー場のしきい値以上の領域を三角形と輪郭線にする
+
+use bevy::{
+    prelude::*,
+    render::mesh::{Indices, VertexAttributeValues},
+};
+
+use crate::field::ScalarGrid;
+
+/// しきい値以上の領域を塗りつぶす三角形と、その境界の輪郭線
+#[derive(Default)]
+pub struct Contour {
+    pub positions: Vec<Vec2>,
+    pub indices: Vec<u32>,
+    pub segments: Vec<(Vec2, Vec2)>,
+}
+
+impl Contour {
+    /// 三角形の数
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// 凸多角形を扇形に三角形へ分割して追加する (頂点は反時計回り)
+    fn push_polygon(&mut self, polygon: &[Vec2]) {
+        let start = self.positions.len() as u32;
+        self.positions.extend_from_slice(polygon);
+        for i in 1..polygon.len().saturating_sub(1) as u32 {
+            self.indices
+                .extend_from_slice(&[start, start + i, start + i + 1]);
+        }
+    }
+
+    /// 既存の `Mesh` の頂点と添字を置き換える
+    ///
+    /// 領域が空のときは、頂点のないメッシュにならないように大きさ 0 の三角形を 1 つ入れる。
+    pub fn write_mesh(&self, mesh: &mut Mesh, bounds: Rect) {
+        let (positions, indices) = if self.indices.is_empty() {
+            (vec![Vec2::ZERO; 3], vec![0, 1, 2])
+        } else {
+            (self.positions.clone(), self.indices.clone())
+        };
+        let size = bounds.size().max(Vec2::splat(f32::EPSILON));
+        let uvs: Vec<[f32; 2]> = positions
+            .iter()
+            .map(|p| {
+                let uv = (*p - bounds.min) / size;
+                [uv.x, 1.0 - uv.y]
+            })
+            .collect();
+        let count = positions.len();
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            VertexAttributeValues::Float32x3(positions.iter().map(|p| [p.x, p.y, 0.0]).collect()),
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; count]);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_indices(Indices::U32(indices));
+    }
+}
+
+/// 格子のセルごとに、しきい値以上の部分を多角形にして集める
+///
+/// セルの 4 つの角を反時計回り (左下・右下・右上・左上) にたどり、しきい値以上の角と、
+/// 辺の上で値がしきい値をまたぐ点 (両端の値から線形補間した位置) を順に並べると、
+/// セルの内側の部分を表す凸多角形になる。多角形の中で交点が 2 つ続くところが輪郭線になる。
+///
+/// 対角の 2 つの角だけがしきい値以上のセル (鞍点) は、つながっているかどうかが角の値だけでは決まらないので、
+/// セルの中心の値 (4 つの角の平均) がしきい値以上ならつながった六角形、そうでなければ 2 つの三角形にする。
+pub fn march(grid: &ScalarGrid, threshold: f32) -> Contour {
+    let mut contour = Contour::default();
+    let mut polygon = Vec::with_capacity(8);
+    let mut is_crossing = Vec::with_capacity(8);
+
+    for y in 0..grid.rows {
+        for x in 0..grid.columns {
+            let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+            let points = corners.map(|(cx, cy)| grid.point(cx, cy));
+            let values = corners.map(|(cx, cy)| grid.value(cx, cy));
+            let inside = values.map(|value| value >= threshold);
+            if inside.iter().all(|inside| !inside) {
+                continue;
+            }
+
+            // 辺 i (角 i から角 i+1) の上でしきい値をまたぐ点
+            let crossings: [Option<Vec2>; 4] = std::array::from_fn(|i| {
+                let j = (i + 1) % 4;
+                (inside[i] != inside[j]).then(|| {
+                    let t = ((threshold - values[i]) / (values[j] - values[i])).clamp(0.0, 1.0);
+                    points[i].lerp(points[j], t)
+                })
+            });
+
+            let saddle = inside[0] == inside[2] && inside[1] == inside[3] && inside[0] != inside[1];
+            let center = values.iter().sum::<f32>() / 4.0;
+            if saddle && center < threshold {
+                // 2 つの角をそれぞれ別の三角形にする
+                for i in (0..4).filter(|&i| inside[i]) {
+                    let (Some(from), Some(to)) = (crossings[(i + 3) % 4], crossings[i]) else {
+                        continue;
+                    };
+                    contour.push_polygon(&[from, points[i], to]);
+                    contour.segments.push((to, from));
+                }
+                continue;
+            }
+
+            polygon.clear();
+            is_crossing.clear();
+            for i in 0..4 {
+                if inside[i] {
+                    polygon.push(points[i]);
+                    is_crossing.push(false);
+                }
+                if let Some(crossing) = crossings[i] {
+                    polygon.push(crossing);
+                    is_crossing.push(true);
+                }
+            }
+            for i in 0..polygon.len() {
+                let j = (i + 1) % polygon.len();
+                if is_crossing[i] && is_crossing[j] {
+                    contour.segments.push((polygon[i], polygon[j]));
+                }
+            }
+            contour.push_polygon(&polygon);
+        }
+    }
+    contour
+}
@@ -1,41 +1,34 @@
 use bevy::prelude::*;
+use prelude_kit::{apply_velocity, Velocity};
+use visual_regression::VisualRegressionPlugin;
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        // `tools/visual_regression` から起動したときだけ、画面を撮って正解の画像と比べる
+        .add_plugins(VisualRegressionPlugin)
         .add_systems(Startup, setup)
-        .add_systems(Update, sprite_movement)
+        .add_systems(Update, (apply_velocity, sprite_movement).chain())
         .run();
 }
 
-#[derive(Component)]
-enum Direction {
-    Up,
-    Down,
-}
-
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn(Camera2d);
     commands.spawn((
         Sprite::from_image(asset_server.load("branding/icon.png")),
         Transform::from_xyz(100., 0., 0.),
-        Direction::Up,
+        Velocity(Vec2::new(0., 150.)),
     ));
 }
 
-/// The sprite is animated by changing its tnraslation depending on the time that has passed since
-/// the last frame.
-fn sprite_movement(time: Res<Time>, mut sprite_position: Query<(&mut Direction, &mut Transform)>) {
-    for (mut logo, mut transform) in &mut sprite_position {
-        match *logo {
-            Direction::Up => transform.translation.y += 150. * time.delta_secs(),
-            Direction::Down => transform.translation.y -= 150. * time.delta_secs(),
-        }
-
+/// The sprite is moved by `apply_velocity` depending on the time that has passed since the last
+/// frame. This system only turns it around when it leaves the band between -200 and 200.
+fn sprite_movement(mut sprite_position: Query<(&mut Velocity, &Transform)>) {
+    for (mut velocity, transform) in &mut sprite_position {
         if transform.translation.y > 200. {
-            *logo = Direction::Down;
+            velocity.y = -velocity.y.abs();
         } else if transform.translation.y < -200. {
-            *logo = Direction::Up;
+            velocity.y = velocity.y.abs();
         }
     }
-}
\ No newline at end of file
+}
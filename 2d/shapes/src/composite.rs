@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+use crate::{
+    mesh_builder::{self, PolygonWithHoles},
+    X_EXTENT,
+};
+
+// 基本図形と組み合わせた図形を切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::KeyC;
+// 円の輪郭を近似する多角形の辺の数
+const CIRCLE_SEGMENTS: usize = 48;
+
+/// 穴のあいた図形や凹多角形など、基本図形を組み合わせた図形を表示するプラグイン
+///
+/// `C` キーで `setup` が並べた基本図形と切り替える。
+/// 組み合わせた図形は `mesh_builder` で三角形に分割してメッシュにしている。
+pub struct CompositeShapesPlugin;
+
+impl Plugin for CompositeShapesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_composite_shapes)
+            .add_systems(Update, toggle_composite_shapes);
+    }
+}
+
+/// 基本図形を示すコンポーネント (`setup` が追加する)
+#[derive(Component)]
+pub struct PrimitiveShape;
+
+/// 組み合わせた図形を示すコンポーネント
+#[derive(Component)]
+struct CompositeShape;
+
+/// 組み合わせた図形を作り、非表示の状態で横に並べる
+fn spawn_composite_shapes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let shapes = [
+        // 丸い穴のあいた長方形
+        PolygonWithHoles::new(mesh_builder::rectangle(Vec2::ZERO, Vec2::new(100.0, 100.0)))
+            .with_hole(mesh_builder::circle(Vec2::ZERO, 30.0, CIRCLE_SEGMENTS)),
+        // 四角い穴のあいた円
+        PolygonWithHoles::new(mesh_builder::circle(Vec2::ZERO, 55.0, CIRCLE_SEGMENTS))
+            .with_hole(mesh_builder::rectangle(Vec2::ZERO, Vec2::new(50.0, 50.0))),
+        // 丸い穴が 2 つあるボタン
+        PolygonWithHoles::new(mesh_builder::circle(Vec2::ZERO, 50.0, CIRCLE_SEGMENTS))
+            .with_hole(mesh_builder::circle(
+                Vec2::new(-15.0, 0.0),
+                8.0,
+                CIRCLE_SEGMENTS / 2,
+            ))
+            .with_hole(mesh_builder::circle(
+                Vec2::new(15.0, 0.0),
+                8.0,
+                CIRCLE_SEGMENTS / 2,
+            )),
+        // 星形 (穴のない凹多角形)
+        PolygonWithHoles::new(mesh_builder::star(Vec2::ZERO, 55.0, 22.0, 5)),
+        // 星形の穴のあいた六角形
+        PolygonWithHoles::new(mesh_builder::circle(Vec2::ZERO, 60.0, 6))
+            .with_hole(mesh_builder::star(Vec2::ZERO, 40.0, 16.0, 5)),
+        // 窓枠 (穴が 4 つある長方形)
+        PolygonWithHoles::new(mesh_builder::rectangle(Vec2::ZERO, Vec2::new(90.0, 110.0)))
+            .with_hole(mesh_builder::rectangle(
+                Vec2::new(-20.0, 25.0),
+                Vec2::new(30.0, 40.0),
+            ))
+            .with_hole(mesh_builder::rectangle(
+                Vec2::new(20.0, 25.0),
+                Vec2::new(30.0, 40.0),
+            ))
+            .with_hole(mesh_builder::rectangle(
+                Vec2::new(-20.0, -25.0),
+                Vec2::new(30.0, 40.0),
+            ))
+            .with_hole(mesh_builder::rectangle(
+                Vec2::new(20.0, -25.0),
+                Vec2::new(30.0, 40.0),
+            )),
+    ];
+    let num_shapes = shapes.len(); // 形状の数を取得
+
+    for (i, shape) in shapes.iter().enumerate() {
+        let mesh = match shape.mesh() {
+            Ok(mesh) => mesh,
+            Err(error) => {
+                warn!("could not triangulate composite shape {i}: {error}");
+                continue;
+            }
+        };
+        // 基本図形と同じように虹色に塗り分ける
+        let color = Color::hsl(360. * i as f32 / num_shapes as f32, 0.95, 0.7);
+
+        commands.spawn((
+            Mesh2d(meshes.add(mesh)),
+            MeshMaterial2d(materials.add(color)),
+            Transform::from_xyz(
+                // 基本図形と同じ幅に等間隔で配置
+                -X_EXTENT / 2. + i as f32 / (num_shapes - 1) as f32 * X_EXTENT,
+                0.0,
+                0.0,
+            ),
+            Visibility::Hidden, // 最初は基本図形を表示する
+            CompositeShape,
+        ));
+    }
+
+    commands.spawn((
+        Text::new("Press C to toggle composite shapes"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+/// `C` キーで基本図形と組み合わせた図形の表示を切り替える
+fn toggle_composite_shapes(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut primitives: Query<&mut Visibility, (With<PrimitiveShape>, Without<CompositeShape>)>,
+    mut composites: Query<&mut Visibility, With<CompositeShape>>,
+) {
+    if !keyboard.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+    for mut visibility in &mut primitives {
+        visibility.toggle_visible_hidden();
+    }
+    for mut visibility in &mut composites {
+        visibility.toggle_visible_hidden();
+    }
+}
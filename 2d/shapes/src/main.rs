@@ -1,13 +1,21 @@
 use  bevy::prelude::*;
 #[cfg(not(target_arch = "wasm32"))]
 use bevy::sprite::{Wireframe2dConfig, Wireframe2dPlugin};
+use visual_regression::VisualRegressionPlugin;
+
+mod composite;
+mod mesh_builder;
+
+use composite::PrimitiveShape;
 
 fn main() {
     let mut app = App::new();                               // Bevyアプリケーションを作成
     app.add_plugins((
         DefaultPlugins,                                          // デフォルトのプラグイン（レンダリングやイベント処理を含む）を追加
         #[cfg(not(target_arch = "wasm32"))]                      // WebAssembly環境ではWireframe2dPluginを無効化
-        Wireframe2dPlugin
+        Wireframe2dPlugin,
+        composite::CompositeShapesPlugin,                        // C キーで穴あきの図形などの組み合わせた図形に切り替える
+        VisualRegressionPlugin                                   // `tools/visual_regression` から起動したときだけ、画面を撮って正解の画像と比べる
     ))
     .add_systems(Startup, setup);              // 起動時に setup システムを実行
 
@@ -56,6 +64,8 @@ fn setup(
                 0.0,
                 0.0,
             ),
+            Visibility::Visible, // C キーで組み合わせた図形と表示を切り替える
+            PrimitiveShape,
         ));
     }
 
@@ -0,0 +1,146 @@
+//! 穴あきの多角形から 2D メッシュを作るためのユーティリティ
+//!
+//! Bevy の基本図形 (`Circle` や `Rectangle` など) は 1 つの輪郭しか持てないため、
+//! 外周と穴の輪郭を頂点の列で表し、earcut 法で三角形に分割してメッシュにする。
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+};
+
+/// 外周と 0 個以上の穴からなる多角形
+///
+/// 輪郭は頂点を順に並べた列で、最後の頂点と最初の頂点は自動的につながる。
+/// 頂点の並び (時計回り・反時計回り) はどちらでもよい。
+#[derive(Debug, Clone, Default)]
+pub struct PolygonWithHoles {
+    pub outer: Vec<Vec2>,
+    pub holes: Vec<Vec<Vec2>>,
+}
+
+impl PolygonWithHoles {
+    /// 穴のない多角形
+    pub fn new(outer: Vec<Vec2>) -> Self {
+        Self {
+            outer,
+            holes: Vec::new(),
+        }
+    }
+
+    /// 穴を追加する (穴は外周の内側にあり、ほかの穴と重ならないこと)
+    pub fn with_hole(mut self, hole: Vec<Vec2>) -> Self {
+        self.holes.push(hole);
+        self
+    }
+
+    /// 多角形を三角形に分割し、頂点の添字を 3 つずつ並べて返す
+    ///
+    /// 添字は外周の頂点、1 つ目の穴の頂点、2 つ目の穴の頂点…の順に数える。
+    /// どの三角形も反時計回り (表面が +Z を向く) にそろえる。
+    pub fn triangulate(&self) -> Result<Vec<u32>, earcutr::Error> {
+        let points = self.points();
+        let mut hole_indices = Vec::with_capacity(self.holes.len());
+        let mut start = self.outer.len();
+        for hole in &self.holes {
+            hole_indices.push(start);
+            start += hole.len();
+        }
+
+        let coordinates: Vec<f32> = points.iter().flat_map(|p| [p.x, p.y]).collect();
+        let indices = earcutr::earcut(&coordinates, &hole_indices, 2)?;
+
+        Ok(indices
+            .chunks_exact(3)
+            .flat_map(|triangle| {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+                let clockwise = (points[b] - points[a]).perp_dot(points[c] - points[a]) < 0.0;
+                if clockwise {
+                    [a as u32, c as u32, b as u32]
+                } else {
+                    [a as u32, b as u32, c as u32]
+                }
+            })
+            .collect())
+    }
+
+    /// 三角形に分割した `Mesh` を作る
+    ///
+    /// UV は多角形を囲む矩形の左上を (0, 0)、右下を (1, 1) として割り当てる。
+    pub fn mesh(&self) -> Result<Mesh, earcutr::Error> {
+        let indices = self.triangulate()?;
+        let points = self.points();
+
+        let min = points.iter().copied().reduce(Vec2::min).unwrap_or_default();
+        let max = points.iter().copied().reduce(Vec2::max).unwrap_or_default();
+        let size = (max - min).max(Vec2::splat(f32::EPSILON));
+
+        let positions: Vec<[f32; 3]> = points.iter().map(|p| [p.x, p.y, 0.0]).collect();
+        let normals = vec![[0.0, 0.0, 1.0]; points.len()];
+        let uvs: Vec<[f32; 2]> = points
+            .iter()
+            .map(|p| {
+                let uv = (*p - min) / size;
+                [uv.x, 1.0 - uv.y]
+            })
+            .collect();
+
+        Ok(Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices)))
+    }
+
+    /// 外周と穴の頂点を 1 つの列にまとめる
+    fn points(&self) -> Vec<Vec2> {
+        self.outer
+            .iter()
+            .chain(self.holes.iter().flatten())
+            .copied()
+            .collect()
+    }
+}
+
+/// `center` を中心とする円の輪郭 (`segments` 角形で近似する)
+pub fn circle(center: Vec2, radius: f32, segments: usize) -> Vec<Vec2> {
+    (0..segments)
+        .map(|i| {
+            let angle = std::f32::consts::TAU * i as f32 / segments as f32;
+            center + Vec2::from_angle(angle) * radius
+        })
+        .collect()
+}
+
+/// `center` を中心とする長方形の輪郭
+pub fn rectangle(center: Vec2, size: Vec2) -> Vec<Vec2> {
+    let half = size / 2.0;
+    vec![
+        center + Vec2::new(-half.x, -half.y),
+        center + Vec2::new(half.x, -half.y),
+        center + Vec2::new(half.x, half.y),
+        center + Vec2::new(-half.x, half.y),
+    ]
+}
+
+/// `center` を中心とする星形の輪郭 (とがった先が `points` 個の凹多角形)
+pub fn star(center: Vec2, outer_radius: f32, inner_radius: f32, points: usize) -> Vec<Vec2> {
+    (0..points * 2)
+        .map(|i| {
+            let radius = if i % 2 == 0 {
+                outer_radius
+            } else {
+                inner_radius
+            };
+            // 最初の先端が真上を向くように 90 度回す
+            let angle =
+                std::f32::consts::FRAC_PI_2 + std::f32::consts::PI * i as f32 / points as f32;
+            center + Vec2::from_angle(angle) * radius
+        })
+        .collect()
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use prelude_kit::{apply_velocity, Velocity};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{setup, AnimationIndices, AnimationTimer, CharacterSheet};
+
+// 群衆のキャラクターの数
+const CROWD_SIZE: usize = 150;
+// 群衆のキャラクターの拡大率 (中央のキャラクターより小さくする)
+const CROWD_SCALE: f32 = 2.0;
+// 1 フレームを表示する時間の範囲 (秒)。キャラクターごとに少しずつ変える
+const FRAME_SECONDS: std::ops::Range<f32> = 0.08..0.14;
+// 歩く速さの範囲 (ピクセル/秒)
+const SPEED: std::ops::Range<f32> = 40.0..90.0;
+// 進みたい向きが 1 秒あたりに揺らぐ最大の角度 (rad)
+const WANDER_JITTER: f32 = 3.0;
+// 速度を変えられる最大の加速度 (急に向きを変えないようにする)
+const MAX_STEERING: f32 = 120.0;
+// 画面の端からこの距離より内側を歩き回る
+const EDGE_MARGIN: f32 = 40.0;
+
+/// 色とアニメーションの開始位置をばらばらにしたキャラクターの群衆を歩き回らせるプラグイン
+///
+/// アニメーション自体は `main.rs` の `animate_sprite` がほかのスプライトと同じように進める。
+pub struct CrowdPlugin;
+
+impl Plugin for CrowdPlugin {
+    fn build(&self, app: &mut App) {
+        // `tools/visual_regression` から起動したときは、毎回同じ群衆になるように決まった種を使う
+        let rng = visual_regression::fixed_seed()
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+        app.insert_resource(CrowdRng(rng))
+            .add_systems(Startup, spawn_crowd.after(setup))
+            .add_systems(Update, (wander, apply_velocity, face_and_sort).chain());
+    }
+}
+
+/// 群衆の色や動きを決める乱数生成器
+#[derive(Resource, Deref, DerefMut)]
+struct CrowdRng(StdRng);
+
+/// 歩き回るキャラクターを示すコンポーネント (現在の速度は共通の [`Velocity`] に持つ)
+#[derive(Component)]
+#[require(Velocity)]
+struct Wanderer {
+    heading: f32,   // 進みたい向き (rad)
+    max_speed: f32, // 歩く速さの上限
+}
+
+/// 群衆のキャラクターを画面内のランダムな位置にスポーンする
+fn spawn_crowd(
+    mut commands: Commands,
+    mut rng: ResMut<CrowdRng>,
+    sheet: Res<CharacterSheet>,
+    window: Single<&Window>,
+) {
+    let half_size = window.size() / 2.0 - EDGE_MARGIN;
+
+    for _ in 0..CROWD_SIZE {
+        // 色相だけを変えた明るい色を掛け合わせ、元の絵柄が分かる程度に色を付ける
+        let tint = Color::hsl(rng.gen_range(0.0..360.0), 0.7, 0.8);
+
+        // 再生中のフレームとタイマーの経過時間をずらし、全員が同時に足踏みしないようにする
+        let indices = AnimationIndices { first: 1, last: 6 };
+        let frame = rng.gen_range(indices.first..=indices.last);
+        let frame_seconds = rng.gen_range(FRAME_SECONDS);
+        let mut timer = Timer::from_seconds(frame_seconds, TimerMode::Repeating);
+        timer.set_elapsed(std::time::Duration::from_secs_f32(
+            rng.gen_range(0.0..frame_seconds),
+        ));
+
+        let position = Vec2::new(
+            rng.gen_range(-half_size.x..half_size.x),
+            rng.gen_range(-half_size.y..half_size.y),
+        );
+        let heading = rng.gen_range(0.0..std::f32::consts::TAU);
+        let max_speed = rng.gen_range(SPEED);
+
+        let mut sprite = Sprite::from_atlas_image(
+            sheet.texture.clone(),
+            TextureAtlas {
+                layout: sheet.layout.clone(),
+                index: frame,
+            },
+        );
+        sprite.color = tint;
+
+        commands.spawn((
+            sprite,
+            Transform::from_translation(position.extend(depth(position.y)))
+                .with_scale(Vec3::splat(CROWD_SCALE)),
+            indices,
+            AnimationTimer(timer),
+            Wanderer { heading, max_speed },
+            Velocity(Vec2::from_angle(heading) * max_speed),
+        ));
+    }
+}
+
+/// 進みたい向きを少しずつ揺らしながら歩く速度を決める (ワンダー・ステアリング)
+///
+/// 画面の端に近づいたら中央へ向かう速度を目標にし、目標の速度との差を加速度として少しずつ近づける。
+/// 位置は `apply_velocity` が速度に合わせて動かす。
+fn wander(
+    time: Res<Time>,
+    mut rng: ResMut<CrowdRng>,
+    window: Single<&Window>,
+    mut query: Query<(&mut Wanderer, &mut Velocity, &Transform)>,
+) {
+    let dt = time.delta_secs();
+    let half_size = window.size() / 2.0 - EDGE_MARGIN;
+
+    for (mut wanderer, mut velocity, transform) in &mut query {
+        let position = transform.translation.truncate();
+
+        wanderer.heading += rng.gen_range(-WANDER_JITTER..WANDER_JITTER) * dt;
+        let mut desired = Vec2::from_angle(wanderer.heading) * wanderer.max_speed;
+
+        // 画面の外に出そうなら中央へ引き返す
+        if position.x.abs() > half_size.x || position.y.abs() > half_size.y {
+            desired = -position.normalize_or_zero() * wanderer.max_speed;
+            wanderer.heading = desired.to_angle();
+        }
+
+        let steering = (desired - velocity.0).clamp_length_max(MAX_STEERING);
+        velocity.0 = (velocity.0 + steering * dt).clamp_length_max(wanderer.max_speed);
+    }
+}
+
+/// 歩いた先の高さに合わせて描く順番を決め、歩く向きにスプライトを向ける
+fn face_and_sort(mut query: Query<(&Velocity, &mut Transform, &mut Sprite), With<Wanderer>>) {
+    for (velocity, mut transform, mut sprite) in &mut query {
+        transform.translation.z = depth(transform.translation.y);
+
+        // スプライトシートは右向きなので、左に歩くときは左右反転する
+        sprite.flip_x = velocity.x < 0.0;
+    }
+}
+
+/// 画面の下にいるキャラクターほど手前に描く z 値 (中央のキャラクターより奥)
+fn depth(y: f32) -> f32 {
+    -1.0 - y * 0.001
+}
@@ -1,8 +1,15 @@
 use bevy::prelude::*;
+use visual_regression::VisualRegressionPlugin;
+
+mod crowd;
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest())) // スプライトのぼやけを防ぐ
+        // 色とアニメーションの開始位置がばらばらな群衆を歩き回らせる
+        .add_plugins(crowd::CrowdPlugin)
+        // `tools/visual_regression` から起動したときだけ、画面を撮って正解の画像と比べる
+        .add_plugins(VisualRegressionPlugin)
         .add_systems(Startup, setup)
         .add_systems(Update, animate_sprite)
         .run();
@@ -20,6 +27,13 @@ struct AnimationIndices {
 #[derive(Component, Deref, DerefMut)]
 struct AnimationTimer(Timer);
 
+/// キャラクターのスプライトシートとレイアウト (群衆のキャラクターも同じものを使う)
+#[derive(Resource)]
+struct CharacterSheet {
+    texture: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+}
+
 /// スプライトのアニメーションを制御するシステム
 fn animate_sprite(
     time: Res<Time>, // 時間のリソース (delta time などを取得)
@@ -54,6 +68,12 @@ fn setup(
     // アニメーションの対象となるフレーム範囲
     let animation_indices = AnimationIndices { first: 1, last: 6 };
 
+    // 群衆のキャラクター用にスプライトシートを共有する
+    commands.insert_resource(CharacterSheet {
+        texture: texture.clone(),
+        layout: texture_atlas_layout.clone(),
+    });
+
     // 2D カメラを取得
     commands.spawn(Camera2d);
 
@@ -66,7 +86,7 @@ fn setup(
                 index: animation_indices.first, // 最初のフレーム
             },
         ),
-        Transform::from_scale(Vec3::splat(6.0)), // スプライトのサイズを 6 倍に拡大
+        Transform::from_scale(Vec3::splat(6.0)), // スプライトのサイズを 6 倍に拡大 (群衆より手前に描く)
         animation_indices, // アニメーションの範囲情報
         AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)), // 0.1 秒ごとにフレームを更新
     ));
@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use prelude_kit::{apply_velocity, Velocity};
+
+use crate::{health, wrap, Enemy, BOUNDS, ENEMY_RADIUS};
+
+// 小惑星の形 (正多角形の頂点の数) と色
+const ASTEROID_SIDES: u32 = 7;
+const ASTEROID_COLOR: Color = Color::srgb(0.55, 0.5, 0.45);
+// 割れたときに、かけらを元の進む向きから左右にずらす角度 (ラジアン)
+const SPLIT_ANGLE: f32 = 0.6;
+// 小惑星の自転の速さ (ラジアン/秒。大きさで割って、小さいほど速く回す)
+const SPIN_SPEED: f32 = 0.8;
+
+/// 漂う小惑星を動かし、弾で壊れたときに小さなかけらに割るプラグイン
+///
+/// 小惑星は [`Enemy`] なので、体力・当たり判定・ぶつかったときのダメージは `health.rs` と敵の仕組みをそのまま使う。
+pub struct AsteroidsPlugin;
+
+impl Plugin for AsteroidsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AsteroidAssets>().add_systems(
+            FixedUpdate,
+            (
+                spin_asteroids,
+                bounce_asteroids
+                    .after(apply_velocity)
+                    .run_if(not(wrap::wrapping)),
+                // 体力が 0 になった小惑星が消される前に、かけらを出す
+                split_asteroids
+                    .after(health::apply_damage)
+                    .before(health::despawn_dead),
+            ),
+        );
+    }
+}
+
+/// 小惑星の大きさ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsteroidSize {
+    Large,
+    Medium,
+    Small,
+}
+
+impl AsteroidSize {
+    /// 割れたときのかけらの大きさ (小さいものは割れずに消える)
+    fn smaller(self) -> Option<AsteroidSize> {
+        match self {
+            AsteroidSize::Large => Some(AsteroidSize::Medium),
+            AsteroidSize::Medium => Some(AsteroidSize::Small),
+            AsteroidSize::Small => None,
+        }
+    }
+
+    /// 敵の当たりの半径 ([`ENEMY_RADIUS`]) に対する大きさ
+    fn scale(self) -> f32 {
+        match self {
+            AsteroidSize::Large => 1.4,
+            AsteroidSize::Medium => 0.8,
+            AsteroidSize::Small => 0.45,
+        }
+    }
+
+    /// 漂う速さ (メートル/秒。小さいほど速い)
+    pub fn speed(self) -> f32 {
+        match self {
+            AsteroidSize::Large => 60.0,
+            AsteroidSize::Medium => 90.0,
+            AsteroidSize::Small => 130.0,
+        }
+    }
+
+    /// 壊したときの得点 (小さいほど当てにくいので高い)
+    pub fn points(self) -> u32 {
+        match self {
+            AsteroidSize::Large => 20,
+            AsteroidSize::Medium => 50,
+            AsteroidSize::Small => 100,
+        }
+    }
+}
+
+/// 漂う小惑星のコンポーネント
+///
+/// 移動は [`Velocity`] と `apply_velocity` に任せ、弾で壊れると [`AsteroidSize::smaller`] の 2 つのかけらに割れる。
+#[derive(Component, Debug)]
+#[require(Enemy, Spin)]
+pub struct Asteroid {
+    pub size: AsteroidSize,
+}
+
+/// 小惑星の自転の速さ (ラジアン/秒)
+#[derive(Component, Debug, Default)]
+struct Spin(f32);
+
+/// すべての小惑星で使い回す形と色
+///
+/// 形は [`ENEMY_RADIUS`] の大きさで作り、大きさの違いは `Transform` の拡大縮小で表す。
+/// そうすると、弾の当たり・ぶつかったときの判定・画面の端の回り込みが、敵と同じく拡大縮小に合わせて変わる。
+#[derive(Resource)]
+pub struct AsteroidAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for AsteroidAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(RegularPolygon::new(ENEMY_RADIUS, ASTEROID_SIDES));
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(ASTEROID_COLOR);
+        AsteroidAssets { mesh, material }
+    }
+}
+
+/// `position` に、`velocity` の向きへ漂う小惑星を出す
+pub fn spawn_asteroid(
+    commands: &mut Commands,
+    assets: &AsteroidAssets,
+    size: AsteroidSize,
+    position: Vec2,
+    velocity: Vec2,
+) {
+    // 大きさと進む向きで回る向きと速さを変え、同じ形でも違って見えるようにする
+    let spin = SPIN_SPEED / size.scale() * velocity.x.signum();
+    commands.spawn((
+        Mesh2d(assets.mesh.clone()),
+        MeshMaterial2d(assets.material.clone()),
+        Transform::from_translation(position.extend(0.0)).with_scale(Vec3::splat(size.scale())),
+        Asteroid { size },
+        // どの大きさも 1 発で壊れる (小さいもの以外は、壊れるとかけらに割れる)
+        health::Health::new(1),
+        Velocity(velocity),
+        Spin(spin),
+    ));
+}
+
+/// 小惑星を自転させる
+fn spin_asteroids(time: Res<Time>, mut asteroids: Query<(&mut Transform, &Spin)>) {
+    for (mut transform, spin) in &mut asteroids {
+        transform.rotate_z(spin.0 * time.delta_secs());
+    }
+}
+
+/// 境界で止めるモードでは、小惑星を画面の端で跳ね返す
+fn bounce_asteroids(mut asteroids: Query<(&Transform, &mut Velocity), With<Asteroid>>) {
+    let extents = BOUNDS / 2.0;
+    for (transform, mut velocity) in &mut asteroids {
+        let position = transform.translation.xy();
+        // 外へ向かっているときだけ反転して、端に引っかかり続けないようにする
+        if position.x.abs() > extents.x && position.x * velocity.x > 0.0 {
+            velocity.x = -velocity.x;
+        }
+        if position.y.abs() > extents.y && position.y * velocity.y > 0.0 {
+            velocity.y = -velocity.y;
+        }
+    }
+}
+
+/// 体力が 0 になった小惑星の位置に、1 つ小さな 2 つのかけらを出す
+///
+/// かけらは元の進む向きから左右に [`SPLIT_ANGLE`] ずつずらして、小さいほど速く飛ばす。
+fn split_asteroids(
+    mut commands: Commands,
+    assets: Res<AsteroidAssets>,
+    asteroids: Query<(&Asteroid, &health::Health, &Transform, &Velocity)>,
+) {
+    for (asteroid, health, transform, velocity) in &asteroids {
+        if health.current > 0 {
+            continue;
+        }
+        let Some(size) = asteroid.size.smaller() else {
+            continue;
+        };
+        let direction = velocity.0.try_normalize().unwrap_or(Vec2::Y);
+        for angle in [SPLIT_ANGLE, -SPLIT_ANGLE] {
+            spawn_asteroid(
+                &mut commands,
+                &assets,
+                size,
+                transform.translation.xy(),
+                Vec2::from_angle(angle).rotate(direction) * size.speed(),
+            );
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use bevy::{
+    audio::{Pitch, Volume},
+    math::ops,
+    prelude::*,
+};
+
+use crate::{
+    game::GameState,
+    health::{self, Health, PlayerDestroyed},
+    Bullet, Enemy, Player, PlayerInput,
+};
+
+// エンジン音の高さ (Hz) と、1 回分の長さ (周期の整数倍にして、つなぎ目で音が途切れないようにする)
+const ENGINE_FREQUENCY: f32 = 55.0;
+const ENGINE_LOOP_DURATION: Duration = Duration::from_secs(1);
+// スロットルが 0 のときと全開のときのエンジン音の音量
+const ENGINE_IDLE_VOLUME: f32 = 0.05;
+const ENGINE_FULL_VOLUME: f32 = 0.3;
+// スロットルが全開のときのエンジン音の再生速度 (2.0 で 1 オクターブ高くなる)
+const ENGINE_FULL_SPEED: f32 = 2.0;
+// エンジン音をスロットルに追いつかせる速さ (大きいほど速く追いつく)
+const ENGINE_RESPONSE: f32 = 8.0;
+// 弾を撃ったときの音の高さ (Hz) と長さ
+const LASER_FREQUENCY: f32 = 1320.0;
+const LASER_DURATION: Duration = Duration::from_millis(60);
+// 敵や宇宙船が爆発したときの音の高さ (Hz) と長さ (低く長めの音にする)
+const EXPLOSION_FREQUENCY: f32 = 70.0;
+const EXPLOSION_DURATION: Duration = Duration::from_millis(250);
+// 効果音の音量
+const EFFECT_VOLUME: f32 = 0.4;
+
+/// スロットルに合わせて音量と高さが変わるエンジン音と、弾と爆発の効果音を鳴らすプラグイン
+///
+/// 音はどれも `Pitch` で作るので、音のファイルは使わない。
+/// エンジン音は宇宙船の子の `AudioPlayer` でループ再生し、`AudioSink` で音量と再生速度を変える。
+/// 弾と爆発の音は、鳴らすたびに `PlaybackSettings::DESPAWN` の `AudioPlayer` を生成する。
+pub struct SoundEffectsPlugin;
+
+impl Plugin for SoundEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_sound_effects)
+            .add_systems(
+                FixedUpdate,
+                // 倒した敵が消される前に、爆発の音を鳴らす
+                play_explosions
+                    .after(health::apply_damage)
+                    .before(health::despawn_dead),
+            )
+            .add_systems(
+                Update,
+                (attach_engine_sound, update_engine_sound, play_lasers),
+            )
+            // ゲームオーバー中はエンジン音も止める
+            .add_systems(OnEnter(GameState::GameOver), pause_engine_sound)
+            .add_systems(OnExit(GameState::GameOver), resume_engine_sound);
+    }
+}
+
+/// エンジン音と効果音の音源
+#[derive(Resource)]
+struct SoundEffects {
+    engine: Handle<Pitch>,    // エンジン音 (ループ再生する)
+    laser: Handle<Pitch>,     // 弾を撃ったときの音
+    explosion: Handle<Pitch>, // 敵や宇宙船が爆発したときの音
+}
+
+/// エンジン音を鳴らすエンティティ (宇宙船の子として生成する)
+#[derive(Component, Debug, Default)]
+struct EngineSound {
+    throttle: f32, // 音に反映しているスロットル (操作に少し遅れて追いつく)
+}
+
+/// エンジン音と効果音を用意する
+fn load_sound_effects(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    commands.insert_resource(SoundEffects {
+        engine: pitches.add(Pitch::new(ENGINE_FREQUENCY, ENGINE_LOOP_DURATION)),
+        laser: pitches.add(Pitch::new(LASER_FREQUENCY, LASER_DURATION)),
+        explosion: pitches.add(Pitch::new(EXPLOSION_FREQUENCY, EXPLOSION_DURATION)),
+    });
+}
+
+/// 宇宙船に、エンジン音をループ再生する子を追加する
+fn attach_engine_sound(
+    mut commands: Commands,
+    sounds: Res<SoundEffects>,
+    added: Query<Entity, Added<Player>>,
+) {
+    for entity in &added {
+        commands.entity(entity).with_child((
+            AudioPlayer(sounds.engine.clone()),
+            PlaybackSettings::LOOP.with_volume(Volume::new(ENGINE_IDLE_VOLUME)),
+            EngineSound::default(),
+        ));
+    }
+}
+
+/// 前進の強さ (スロットル) に合わせて、エンジン音の音量と再生速度 (高さ) を変える
+///
+/// `AudioSink` は再生が始まってから追加されるので、それまでは何もしない。
+fn update_engine_sound(
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    mut engines: Query<(&mut EngineSound, &AudioSink)>,
+) {
+    let target = input.movement_factor.clamp(0.0, 1.0);
+    for (mut engine, sink) in &mut engines {
+        // 急に音が変わらないように、指数的に追いつかせる
+        let blend = 1.0 - ops::exp(-ENGINE_RESPONSE * time.delta_secs());
+        engine.throttle += (target - engine.throttle) * blend;
+        sink.set_volume(ENGINE_IDLE_VOLUME.lerp(ENGINE_FULL_VOLUME, engine.throttle));
+        sink.set_speed(1.0_f32.lerp(ENGINE_FULL_SPEED, engine.throttle));
+    }
+}
+
+fn pause_engine_sound(engines: Query<&AudioSink, With<EngineSound>>) {
+    for sink in &engines {
+        sink.pause();
+    }
+}
+
+fn resume_engine_sound(engines: Query<&AudioSink, With<EngineSound>>) {
+    for sink in &engines {
+        sink.play();
+    }
+}
+
+/// 新しく撃った弾があれば、弾を撃った音を鳴らす (同じフレームに何発撃っても 1 回だけ鳴らす)
+fn play_lasers(mut commands: Commands, sounds: Res<SoundEffects>, fired: Query<(), Added<Bullet>>) {
+    if fired.is_empty() {
+        return;
+    }
+    commands.spawn((
+        AudioPlayer(sounds.laser.clone()),
+        PlaybackSettings::DESPAWN.with_volume(Volume::new(EFFECT_VOLUME)),
+    ));
+}
+
+/// 体力が 0 になった敵や小惑星、撃墜された宇宙船があれば、爆発の音を鳴らす
+///
+/// 同じステップにいくつ倒しても 1 回だけ鳴らす (イベントはすべて読み取り、次のステップに引き継がない)。
+fn play_explosions(
+    mut commands: Commands,
+    mut destroyed: EventReader<PlayerDestroyed>,
+    sounds: Res<SoundEffects>,
+    enemies: Query<&Health, With<Enemy>>,
+) {
+    let player_destroyed = destroyed.read().count() > 0;
+    let enemy_defeated = enemies.iter().any(|health| health.current == 0);
+    if player_destroyed || enemy_defeated {
+        commands.spawn((
+            AudioPlayer(sounds.explosion.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(EFFECT_VOLUME)),
+        ));
+    }
+}
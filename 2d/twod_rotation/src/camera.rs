@@ -0,0 +1,107 @@
+use bevy::{math::ops, prelude::*};
+
+use crate::{Player, BOUNDS};
+
+// 追いかける速さ (大きいほど早く追いつく。1 秒あたりの割合)
+const DEFAULT_SMOOTHING: f32 = 5.0;
+// 宇宙船が向いている方向に、カメラの中心を先回りさせる距離
+const DEFAULT_LOOK_AHEAD: f32 = 120.0;
+// 遊ぶ範囲の枠の太さと色
+const BORDER_THICKNESS: f32 = 4.0;
+const BORDER_COLOR: Color = Color::srgb(0.3, 0.35, 0.5);
+// 背景の星の数・大きさ・色 (カメラが動いていることがわかるように並べる)
+const STAR_COUNT: usize = 300;
+const STAR_SIZE: f32 = 2.0;
+const STAR_COLOR: Color = Color::srgb(0.6, 0.6, 0.7);
+
+/// プレイヤーの宇宙船をなめらかに追いかけるカメラと、遊ぶ範囲の枠・背景の星を追加するプラグイン
+///
+/// 遊ぶ範囲 (`BOUNDS`) はウィンドウより広いので、カメラが宇宙船を追いかけて見える範囲を動かす。
+pub struct FollowCameraPlugin;
+
+impl Plugin for FollowCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_playfield)
+            // 宇宙船が動いたあと、位置の伝播の前にカメラを動かす
+            .add_systems(
+                PostUpdate,
+                follow_player.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// プレイヤーの宇宙船を追いかけるカメラの設定
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FollowCamera {
+    pub smoothing: f32,  // 追いかける速さ (0.0 なら動かない。大きいほど早く追いつく)
+    pub look_ahead: f32, // 向いている方向へ先回りする距離 (0.0 なら宇宙船を中心にする)
+}
+
+impl Default for FollowCamera {
+    fn default() -> Self {
+        FollowCamera {
+            smoothing: DEFAULT_SMOOTHING,
+            look_ahead: DEFAULT_LOOK_AHEAD,
+        }
+    }
+}
+
+/// カメラを、宇宙船の少し前 (先回りする位置) へ近づける
+///
+/// 補間の割合は `1 - exp(-smoothing * 経過時間)` にして、フレームレートが変わっても同じ速さで追いつくようにする。
+/// 回り込むモードで宇宙船が反対の端へ移ったときは、カメラも同じだけ移して、遊ぶ範囲を横切らないようにする。
+fn follow_player(
+    time: Res<Time>,
+    player: Single<&Transform, With<Player>>,
+    camera: Single<(&mut Transform, &FollowCamera), Without<Player>>,
+) {
+    let (mut transform, follow) = camera.into_inner();
+    let facing = (player.rotation * Vec3::Y).xy();
+    let target = player.translation.xy() + facing * follow.look_ahead;
+
+    let mut position = transform.translation.xy();
+    let distance = target - position;
+    // 半分より離れているなら、宇宙船が端で回り込んだ
+    if distance.x.abs() > BOUNDS.x / 2.0 {
+        position.x += BOUNDS.x * distance.x.signum();
+    }
+    if distance.y.abs() > BOUNDS.y / 2.0 {
+        position.y += BOUNDS.y * distance.y.signum();
+    }
+
+    let t = 1.0 - ops::exp(-follow.smoothing * time.delta_secs());
+    let position = position.lerp(target, t);
+    transform.translation = position.extend(transform.translation.z);
+}
+
+/// 遊ぶ範囲の枠と、背景の星を配置する
+///
+/// 星は乱数を使わずに、黄金比の端数ずつずらして遊ぶ範囲に散らばらせる。
+fn spawn_playfield(mut commands: Commands) {
+    let extents = BOUNDS / 2.0;
+    let horizontal = Vec2::new(BOUNDS.x + BORDER_THICKNESS, BORDER_THICKNESS);
+    let vertical = Vec2::new(BORDER_THICKNESS, BOUNDS.y + BORDER_THICKNESS);
+    for (size, position) in [
+        (horizontal, Vec2::new(0.0, extents.y)),
+        (horizontal, Vec2::new(0.0, -extents.y)),
+        (vertical, Vec2::new(extents.x, 0.0)),
+        (vertical, Vec2::new(-extents.x, 0.0)),
+    ] {
+        commands.spawn((
+            Sprite::from_color(BORDER_COLOR, size),
+            Transform::from_translation(position.extend(-2.0)),
+        ));
+    }
+
+    for index in 0..STAR_COUNT {
+        let t = Vec2::new(
+            (index as f32 * 0.618_034).fract(),
+            (index as f32 + 0.5) / STAR_COUNT as f32,
+        );
+        let position = (t - Vec2::splat(0.5)) * BOUNDS;
+        commands.spawn((
+            Sprite::from_color(STAR_COLOR, Vec2::splat(STAR_SIZE)),
+            Transform::from_translation(position.extend(-3.0)),
+        ));
+    }
+}
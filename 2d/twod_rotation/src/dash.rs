@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+use prelude_kit::{apply_velocity, Lifetime, Velocity};
+
+use crate::{Player, PlayerInput};
+
+// ダッシュ中に何倍の速さで進むか
+const DASH_MULTIPLIER: f32 = 3.0;
+// ダッシュが続く時間と、次にダッシュできるまでの時間 (秒)
+const DASH_SECONDS: f32 = 0.2;
+const COOLDOWN_SECONDS: f32 = 1.5;
+// ダッシュ中に残像を置く間隔と、残像が消えるまでの時間 (秒)
+const AFTERIMAGE_INTERVAL: f32 = 0.03;
+const AFTERIMAGE_LIFETIME: f32 = 0.25;
+// 残像の色 (宇宙船の画像に掛ける。薄い青)
+const AFTERIMAGE_COLOR: Color = Color::srgba(0.5, 0.8, 1.0, 0.6);
+// 画面左下のクールダウンの表示の大きさと色
+const INDICATOR_SIZE: Vec2 = Vec2::new(120.0, 8.0);
+const INDICATOR_BACKGROUND: Color = Color::srgb(0.2, 0.2, 0.2);
+const INDICATOR_CHARGING: Color = Color::srgb(0.4, 0.5, 0.7);
+const INDICATOR_READY: Color = Color::srgb(0.5, 0.8, 1.0);
+
+/// `Shift` キーで、向いている方向へ短い間だけ速く進むダッシュを追加するプラグイン
+///
+/// ダッシュ中は残像を残し、画面左下に次にダッシュできるまでの時間を表示する。
+pub struct DashPlugin;
+
+impl Plugin for DashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_indicator)
+            .add_systems(
+                FixedUpdate,
+                // 操作で決めた速度を、移動の前にダッシュの速度で上書きする
+                dash.after(crate::player_movement_system)
+                    .before(apply_velocity),
+            )
+            .add_systems(Update, (fade_afterimages, update_indicator));
+    }
+}
+
+/// ダッシュの状態
+#[derive(Component, Debug)]
+pub struct Dash {
+    active: Timer,     // ダッシュが終わるまでの時間
+    cooldown: Timer,   // 次にダッシュできるまでの時間
+    afterimage: Timer, // 次の残像を置くまでの時間
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        let mut active = Timer::from_seconds(DASH_SECONDS, TimerMode::Once);
+        let mut cooldown = Timer::from_seconds(COOLDOWN_SECONDS, TimerMode::Once);
+        // 起動してすぐにダッシュできるように、どちらも終わった状態から始める
+        active.tick(active.duration());
+        cooldown.tick(cooldown.duration());
+        Dash {
+            active,
+            cooldown,
+            afterimage: Timer::from_seconds(AFTERIMAGE_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Dash {
+    /// ダッシュ中かどうか
+    fn is_active(&self) -> bool {
+        !self.active.finished()
+    }
+}
+
+/// ダッシュの残像を示すコンポーネント
+#[derive(Component)]
+struct Afterimage;
+
+/// クールダウンの表示の、溜まっていく部分を示すコンポーネント
+#[derive(Component)]
+struct CooldownFill;
+
+/// ダッシュの操作で、準備ができていればダッシュを始め、ダッシュ中は向いている方向へ速く進ませて残像を置く
+///
+/// 押している間は、準備ができ次第またダッシュする (`FixedUpdate` では `just_pressed` を取りこぼすことがあるため)。
+fn dash(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    player: Single<(&Player, &mut Dash, &Transform, &Sprite, &mut Velocity)>,
+) {
+    let (ship, mut dash, transform, sprite, mut velocity) = player.into_inner();
+    dash.active.tick(time.delta());
+    dash.cooldown.tick(time.delta());
+    if input.dash && dash.cooldown.finished() {
+        dash.active.reset();
+        dash.cooldown.reset();
+        dash.afterimage.reset();
+    }
+    if !dash.is_active() {
+        return;
+    }
+
+    let facing = (transform.rotation * Vec3::Y).xy();
+    velocity.0 = facing * ship.movement_speed * DASH_MULTIPLIER;
+
+    if dash.afterimage.tick(time.delta()).just_finished() {
+        commands.spawn((
+            Sprite {
+                color: AFTERIMAGE_COLOR,
+                ..sprite.clone()
+            },
+            // 宇宙船の下に描く
+            Transform {
+                translation: transform.translation - Vec3::Z * 0.2,
+                ..*transform
+            },
+            Lifetime::from_seconds(AFTERIMAGE_LIFETIME),
+            Afterimage,
+        ));
+    }
+}
+
+/// 残像の寿命を進め、残りに合わせて薄くし、切れたら消す
+fn fade_afterimages(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut afterimages: Query<(Entity, &mut Lifetime, &mut Sprite), With<Afterimage>>,
+) {
+    for (entity, mut lifetime, mut sprite) in &mut afterimages {
+        if lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.color =
+            AFTERIMAGE_COLOR.with_alpha(AFTERIMAGE_COLOR.alpha() * lifetime.fraction_remaining());
+    }
+}
+
+/// 画面左下に、ダッシュのクールダウンの表示を配置する
+fn spawn_indicator(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("DASH (Shift)"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(INDICATOR_SIZE.x),
+                        height: Val::Px(INDICATOR_SIZE.y),
+                        ..default()
+                    },
+                    BackgroundColor(INDICATOR_BACKGROUND),
+                ))
+                .with_children(|bar| {
+                    bar.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(INDICATOR_READY),
+                        CooldownFill,
+                    ));
+                });
+        });
+}
+
+/// クールダウンの進み具合に合わせて表示を伸ばし、ダッシュできるようになったら色を変える
+fn update_indicator(
+    dash: Single<&Dash, With<Player>>,
+    fill: Single<(&mut Node, &mut BackgroundColor), With<CooldownFill>>,
+) {
+    let (mut node, mut color) = fill.into_inner();
+    let width = Val::Percent(dash.cooldown.fraction() * 100.0);
+    if node.width != width {
+        node.width = width;
+    }
+    let target = if dash.cooldown.finished() {
+        INDICATOR_READY
+    } else {
+        INDICATOR_CHARGING
+    };
+    color.set_if_neq(BackgroundColor(target));
+}
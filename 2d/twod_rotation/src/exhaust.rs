@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use prelude_kit::{apply_velocity, Lifetime, Velocity};
+
+use crate::{Player, PlayerInput};
+
+// 全開で前進しているときに、1 秒間に出すパーティクルの数
+const EXHAUST_RATE: f32 = 60.0;
+// パーティクルの速さ (メートル/秒) と、向きのばらつき (ラジアン)
+const EXHAUST_SPEED: f32 = 180.0;
+const EXHAUST_SPREAD: f32 = 0.5;
+// 宇宙船の中心から後ろの端までの距離 (パーティクルを出す位置)
+const SHIP_TAIL_OFFSET: f32 = 32.0;
+// パーティクルの大きさ・色・消えるまでの時間 (秒)
+const EXHAUST_SIZE: f32 = 6.0;
+const EXHAUST_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+const EXHAUST_LIFETIME: f32 = 0.4;
+
+/// 前進している間、宇宙船の後ろから噴射のパーティクルを出すプラグイン
+///
+/// パーティクルは [`Velocity`] で動かし、[`Lifetime`] が切れるまでに薄く小さくなって消える。
+pub struct ExhaustPlugin;
+
+impl Plugin for ExhaustPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            emit_exhaust
+                .after(crate::player_movement_system)
+                .before(apply_velocity),
+        )
+        .add_systems(Update, fade_exhaust);
+    }
+}
+
+/// 噴射のパーティクルを出す、宇宙船の後ろのエミッター
+#[derive(Component, Debug, Default)]
+pub struct Thruster {
+    pending: f32, // 次のパーティクルを出すまでにたまった量 (1.0 で 1 つ出す)
+    emitted: u32, // これまでに出したパーティクルの数 (向きのばらつきに使う)
+}
+
+/// 噴射のパーティクルを示すコンポーネント
+#[derive(Component)]
+struct ExhaustParticle;
+
+/// 前進の操作の強さ (スロットル) に比例した数のパーティクルを、宇宙船の向きと反対へ出す
+///
+/// 1 ステップで出す数に端数が出るので、[`Thruster`] にためておいて 1 つ分たまるごとに出す。
+fn emit_exhaust(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    player: Single<(&Transform, &mut Thruster), With<Player>>,
+) {
+    let (transform, mut thruster) = player.into_inner();
+    let throttle = input.movement_factor.max(0.0);
+    if throttle == 0.0 {
+        thruster.pending = 0.0;
+        return;
+    }
+    thruster.pending += EXHAUST_RATE * throttle * time.delta_secs();
+
+    // 宇宙船の前 (`transform.rotation * Vec3::Y`) と反対の向き
+    let backward = -(transform.rotation * Vec3::Y).xy();
+    let position = transform.translation.xy() + backward * SHIP_TAIL_OFFSET;
+    while thruster.pending >= 1.0 {
+        thruster.pending -= 1.0;
+        thruster.emitted += 1;
+        // 黄金比の端数ずつずらして、乱数を使わずに向きをばらつかせる
+        let spread = ((thruster.emitted as f32 * 0.618_034).fract() - 0.5) * EXHAUST_SPREAD;
+        let direction = Vec2::from_angle(spread).rotate(backward);
+        commands.spawn((
+            Sprite::from_color(EXHAUST_COLOR, Vec2::splat(EXHAUST_SIZE)),
+            // 宇宙船の下に描く
+            Transform::from_translation(position.extend(transform.translation.z - 0.1)),
+            Velocity(direction * EXHAUST_SPEED),
+            Lifetime::from_seconds(EXHAUST_LIFETIME),
+            ExhaustParticle,
+        ));
+    }
+}
+
+/// パーティクルの寿命を進め、残りに合わせて薄く小さくし、切れたら消す
+fn fade_exhaust(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<
+        (Entity, &mut Lifetime, &mut Sprite, &mut Transform),
+        With<ExhaustParticle>,
+    >,
+) {
+    for (entity, mut lifetime, mut sprite, mut transform) in &mut particles {
+        if lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let remaining = lifetime.fraction_remaining();
+        sprite.color = EXHAUST_COLOR.with_alpha(remaining);
+        transform.scale = Vec3::splat(remaining);
+    }
+}
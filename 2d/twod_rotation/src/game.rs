@@ -0,0 +1,270 @@
+use bevy::prelude::*;
+
+use crate::{
+    asteroids::Asteroid,
+    health::{self, Health, PlayerDestroyed},
+    stress,
+    waves::EnemySpawner,
+    Bullet, Enemy, Player,
+};
+
+// 始めたときの残機
+const STARTING_LIVES: u32 = 3;
+// 向きを変える敵を倒したときの得点
+const ENEMY_POINTS: u32 = 150;
+// スコアボードの項目の見出し (それぞれの後ろに値の `TextSpan` を置く)
+const SCOREBOARD_LABELS: [&str; 5] = ["Score: ", "  Best: ", "  Wave: ", "  Health: ", "  Lives: "];
+// スコアボードのフォントサイズと、見出しと値の色
+const SCOREBOARD_FONT_SIZE: f32 = 20.0;
+const SCOREBOARD_LABEL_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const SCOREBOARD_VALUE_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+// ゲームオーバー画面の見出しと本文のフォントサイズ、背景の色
+const TITLE_FONT_SIZE: f32 = 60.0;
+const BODY_FONT_SIZE: f32 = 24.0;
+const OVERLAY_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+// ゲームオーバーからやり直すボタン
+const RESTART_KEY: KeyCode = KeyCode::Enter;
+const RESTART_BUTTON: GamepadButton = GamepadButton::Start;
+
+/// 得点と残機を数え、残機がなくなったらゲームオーバー画面を出して、最初の波からやり直せるようにするプラグイン
+///
+/// 画面左上のスコアボードに、得点・最高得点・波の数・体力・残機を表示する (負荷試験モードでは表示しない)。
+/// ゲームオーバー中は仮想時間を止め、`Enter` キー (ゲームパッドでは `Start` ボタン) でやり直す。
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .enable_state_scoped_entities::<GameState>()
+            .init_resource::<Score>()
+            .insert_resource(Lives(STARTING_LIVES))
+            .add_systems(Startup, spawn_scoreboard.run_if(not(stress::stress_mode)))
+            .add_systems(
+                FixedUpdate,
+                (
+                    // 倒した敵が消される前に、得点に数える
+                    score_defeated
+                        .after(health::apply_damage)
+                        .before(health::despawn_dead),
+                    lose_life.after(health::despawn_dead),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_scoreboard.run_if(not(stress::stress_mode)),
+                    restart.run_if(in_state(GameState::GameOver)),
+                ),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (pause_time, spawn_game_over_screen),
+            )
+            .add_systems(OnExit(GameState::GameOver), (start_new_game, unpause_time));
+    }
+}
+
+/// ゲームの進行状態
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameState {
+    #[default]
+    Playing, // プレイ中
+    GameOver, // 残機がなくなり、結果を表示している
+}
+
+/// 今回の得点と、これまでの最高得点
+#[derive(Resource, Debug, Default)]
+pub struct Score {
+    pub current: u32, // 今回の得点
+    pub best: u32,    // 起動してからの最高得点
+}
+
+/// プレイヤーの残機 (撃墜されるたびに 1 つ減る)
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct Lives(pub u32);
+
+/// スコアボードのテキストを示すコンポーネント
+#[derive(Component)]
+struct Scoreboard;
+
+/// 体力が 0 になった敵と小惑星の得点を加える
+fn score_defeated(
+    mut score: ResMut<Score>,
+    defeated: Query<(&Health, Option<&Asteroid>), With<Enemy>>,
+) {
+    for (health, asteroid) in &defeated {
+        if health.current > 0 {
+            continue;
+        }
+        score.current += asteroid.map_or(ENEMY_POINTS, |asteroid| asteroid.size.points());
+    }
+    score.best = score.best.max(score.current);
+}
+
+/// 撃墜されたら残機を減らし、なくなったらゲームオーバーにする
+fn lose_life(
+    mut destroyed: EventReader<PlayerDestroyed>,
+    mut lives: ResMut<Lives>,
+    mut next_state: ResMut<NextState<GameState>>,
+    score: Res<Score>,
+) {
+    for _ in destroyed.read() {
+        **lives = lives.saturating_sub(1);
+        if **lives > 0 {
+            info!("{} lives left", **lives);
+            continue;
+        }
+        info!("game over: scored {} (best {})", score.current, score.best);
+        next_state.set(GameState::GameOver);
+    }
+}
+
+/// 画面左上に、見出しと値を交互に並べたスコアボードを配置する
+///
+/// 値は見出しの後ろの `TextSpan` に入るので、`update_scoreboard` は値だけを書き換えればよい。
+fn spawn_scoreboard(mut commands: Commands) {
+    let font = TextFont {
+        font_size: SCOREBOARD_FONT_SIZE,
+        ..default()
+    };
+    commands
+        .spawn((
+            Text::new(SCOREBOARD_LABELS[0]),
+            font.clone(),
+            TextColor(SCOREBOARD_LABEL_COLOR),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+            Scoreboard,
+        ))
+        .with_children(|parent| {
+            for (index, label) in SCOREBOARD_LABELS.iter().enumerate() {
+                // 最初の見出しはルートの `Text` に入れてある
+                if index > 0 {
+                    parent.spawn((
+                        TextSpan::new(*label),
+                        font.clone(),
+                        TextColor(SCOREBOARD_LABEL_COLOR),
+                    ));
+                }
+                parent.spawn((
+                    TextSpan::default(),
+                    font.clone(),
+                    TextColor(SCOREBOARD_VALUE_COLOR),
+                ));
+            }
+        });
+}
+
+/// 得点・最高得点・波の数・プレイヤーの体力・残機を、スコアボードの値に書き込む
+fn update_scoreboard(
+    score: Res<Score>,
+    lives: Res<Lives>,
+    spawner: Res<EnemySpawner>,
+    player: Single<&Health, With<Player>>,
+    root: Single<Entity, (With<Scoreboard>, With<Text>)>,
+    mut writer: TextUiWriter,
+) {
+    let values = [
+        score.current.to_string(),
+        score.best.to_string(),
+        spawner.wave().to_string(),
+        format!("{}/{}", player.current, player.max),
+        lives.to_string(),
+    ];
+    for (index, value) in values.into_iter().enumerate() {
+        // ルートの見出しが 0 番、その後ろに値と見出しが交互に並ぶ
+        let mut text = writer.text(*root, 2 * index + 1);
+        if *text != value {
+            *text = value;
+        }
+    }
+}
+
+/// ゲームオーバー中は敵や弾の動きを止める (`FixedUpdate` も仮想時間で進むので止まる)
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// 得点と最高得点、たどり着いた波を表示するゲームオーバー画面を配置する
+fn spawn_game_over_screen(mut commands: Commands, score: Res<Score>, spawner: Res<EnemySpawner>) {
+    let body_font = TextFont {
+        font_size: BODY_FONT_SIZE,
+        ..default()
+    };
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(OVERLAY_COLOR),
+            StateScoped(GameState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("GAME OVER"),
+                TextFont {
+                    font_size: TITLE_FONT_SIZE,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(format!(
+                    "Score: {}  Best: {}  Wave: {}",
+                    score.current,
+                    score.best,
+                    spawner.wave()
+                )),
+                body_font.clone(),
+            ));
+            parent.spawn((Text::new("Press Enter to play again"), body_font));
+        });
+}
+
+/// `Enter` キーかゲームパッドの `Start` ボタンで、最初からやり直す
+fn restart(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(RESTART_KEY)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(RESTART_BUTTON))
+    {
+        next_state.set(GameState::Playing);
+    }
+}
+
+/// 敵と小惑星、飛んでいる弾を消し、得点と残機を戻して最初の波からやり直す
+///
+/// プレイヤーは撃墜されたときに `despawn_dead` が中央に戻して体力を満タンにしてある。
+fn start_new_game(
+    mut commands: Commands,
+    mut lives: ResMut<Lives>,
+    mut score: ResMut<Score>,
+    mut spawner: ResMut<EnemySpawner>,
+    enemies: Query<Entity, With<Enemy>>,
+    bullets: Query<Entity, With<Bullet>>,
+) {
+    for entity in enemies.iter().chain(&bullets) {
+        commands.entity(entity).despawn();
+    }
+    spawner.restart();
+    score.current = 0;
+    **lives = STARTING_LIVES;
+}
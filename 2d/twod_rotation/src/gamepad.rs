@@ -0,0 +1,49 @@
+use bevy::{math::ops, prelude::*};
+
+use crate::PlayerInput;
+
+// スティックとトリガーの遊び (これより小さい傾きは 0 とみなす)
+const DEAD_ZONE: f32 = 0.15;
+// 感度の曲線の指数 (1.0 で傾きに比例、大きいほど小さな傾きで細かく操作できる)
+const SENSITIVITY_EXPONENT: f32 = 2.0;
+// 弾を撃つボタン
+const FIRE_BUTTONS: [GamepadButton; 2] = [GamepadButton::South, GamepadButton::RightTrigger];
+// ダッシュするボタン
+const DASH_BUTTON: GamepadButton = GamepadButton::East;
+
+/// つながっているゲームパッドの操作を、キーボードの操作に重ねる
+///
+/// 左スティックの左右で回転、上で前進し、右トリガー (`RightTrigger2`) の押し込み具合をスロットルにする。
+/// 回転はキーボードと足して `-1.0..=1.0` に収め、前進はキーボード・スティック・トリガーのうち大きい方を使う。
+pub fn apply_gamepads(input: &mut PlayerInput, gamepads: &Query<&Gamepad>) {
+    for gamepad in gamepads {
+        let stick = radial_curve(gamepad.left_stick());
+        let throttle = axis_curve(gamepad.get(GamepadButton::RightTrigger2).unwrap_or(0.0));
+
+        // スティックを右に倒すと右回り (回転の向きは `-1.0`)
+        input.rotation_factor = (input.rotation_factor - stick.x).clamp(-1.0, 1.0);
+        input.movement_factor = input.movement_factor.max(stick.y).max(throttle);
+        input.fire |= gamepad.any_pressed(FIRE_BUTTONS);
+        input.dash |= gamepad.pressed(DASH_BUTTON);
+    }
+}
+
+/// スティックの傾きに遊びと感度の曲線をかける
+///
+/// 軸ごとではなく倒した大きさで判定するので、斜めに倒したときだけ遊びが広がることがない。
+fn radial_curve(stick: Vec2) -> Vec2 {
+    let length = stick.length();
+    if length <= DEAD_ZONE {
+        return Vec2::ZERO;
+    }
+    stick / length * axis_curve(length.min(1.0))
+}
+
+/// `0.0..=1.0` の傾きに遊びと感度の曲線をかける (遊びの端から 0.0 になるように詰め直す)
+fn axis_curve(value: f32) -> f32 {
+    if value <= DEAD_ZONE {
+        return 0.0;
+    }
+    let scaled = ((value - DEAD_ZONE) / (1.0 - DEAD_ZONE)).min(1.0);
+    ops::powf(scaled, SENSITIVITY_EXPONENT)
+}
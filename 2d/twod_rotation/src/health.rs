@@ -0,0 +1,280 @@
+use bevy::{prelude::*, sprite::Anchor};
+use prelude_kit::Velocity;
+
+use crate::{knockback::Knockback, shield::Shield, stress, Enemy, Player, ENEMY_RADIUS};
+
+// プレイヤーの体力
+pub const PLAYER_HEALTH: u32 = 5;
+// 敵の体力 (弾を何発当てれば倒せるか)
+const ENEMY_HEALTH: u32 = 3;
+// 弾 1 発の威力
+pub const BULLET_DAMAGE: u32 = 1;
+// 敵にぶつかったときに受ける威力
+const CONTACT_DAMAGE: u32 = 1;
+// プレイヤーの当たりの円の半径 (敵の半径は拡大縮小した `ENEMY_RADIUS`)
+const PLAYER_RADIUS: f32 = 28.0;
+// ぶつかったときに押し戻される速さ (メートル/秒)
+const PLAYER_KNOCKBACK: f32 = 600.0;
+const ENEMY_KNOCKBACK: f32 = 300.0;
+// ダメージを受けてから、次のダメージを受けるようになるまでの時間 (秒)
+const INVULNERABLE_SECONDS: f32 = 1.0;
+// 撃墜されてやり直してから、ダメージを受けるようになるまでの時間 (秒)
+const RESPAWN_INVULNERABLE_SECONDS: f32 = 2.0;
+// ダメージを受けない間に、宇宙船を点滅させる速さ (1 秒あたりの切り替えの回数)
+const BLINK_RATE: f32 = 10.0;
+// 体力バーの大きさと、プレイヤーの中心からの高さ
+const HEALTH_BAR_SIZE: Vec2 = Vec2::new(60.0, 6.0);
+const HEALTH_BAR_OFFSET: f32 = 50.0;
+const HEALTH_BAR_BACKGROUND: Color = Color::srgb(0.2, 0.2, 0.2);
+const HEALTH_BAR_COLOR: Color = Color::srgb(0.3, 0.9, 0.4);
+
+/// 体力とダメージを扱うプラグイン
+///
+/// ダメージは [`DamageEvent`] で送り、`apply_damage` がまとめて体力を減らす。
+/// 体力が 0 になった敵は消え、プレイヤーは画面の中央で体力を戻してやり直す。
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<PlayerDestroyed>()
+            .add_systems(Startup, spawn_health_bar)
+            .add_systems(
+                FixedUpdate,
+                (
+                    // 負荷試験モードでは、敵の数を揃えて比べられるように接触では倒さない
+                    player_enemy_collision.run_if(not(stress::stress_mode)),
+                    tick_invulnerability,
+                    apply_damage,
+                    despawn_dead,
+                )
+                    .chain()
+                    .after(crate::bullet_hits_enemy),
+            )
+            .add_systems(Update, (update_health_bar, blink_invulnerable));
+    }
+}
+
+/// 体力 (0 になると倒れる)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    /// 最大の体力で始める
+    pub fn new(max: u32) -> Health {
+        Health { current: max, max }
+    }
+}
+
+/// 当たった相手に与える威力 (弾と、ぶつかってきた敵)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Damage(pub u32);
+
+/// `target` の体力を `amount` だけ減らすイベント
+#[derive(Event, Debug)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: u32,
+}
+
+/// プレイヤーの体力が 0 になり、画面の中央からやり直したことを知らせるイベント
+#[derive(Event, Debug)]
+pub struct PlayerDestroyed;
+
+/// ダメージを受けたあと、しばらくダメージを受けない状態
+///
+/// 敵に重なっている間、毎ステップ体力が減ってしまわないようにする。
+#[derive(Component)]
+struct Invulnerable(Timer);
+
+/// 体力バーの背景を示すコンポーネント
+#[derive(Component)]
+struct HealthBar;
+
+/// 体力バーの残りの体力の部分を示すコンポーネント
+#[derive(Component)]
+struct HealthBarFill;
+
+/// [`Enemy`] に必要な体力
+pub fn enemy_health() -> Health {
+    Health::new(ENEMY_HEALTH)
+}
+
+/// [`Enemy`] がぶつかったときの威力
+pub fn enemy_damage() -> Damage {
+    Damage(CONTACT_DAMAGE)
+}
+
+/// ダメージを受けない状態でなければ、プレイヤーの円と重なっている敵の円を探し、ぶつかった処理をする
+///
+/// プレイヤーには敵の威力のダメージを送り、しばらくダメージを受けない状態にする。
+/// プレイヤーと敵は、中心を結ぶ向きに互いに押し戻す ([`Knockback`])。
+#[allow(clippy::type_complexity)]
+fn player_enemy_collision(
+    mut commands: Commands,
+    mut damage_events: EventWriter<DamageEvent>,
+    // ダメージを受けない間は見つからない (`Single` のままでもシステムが飛ばされるだけだが、
+    // 見つからないのはよくあることなので `Option` で受け取ってはっきりさせる)
+    player: Option<Single<(Entity, &Transform), (With<Player>, Without<Invulnerable>)>>,
+    enemies: Query<(Entity, &Transform, &Damage), With<Enemy>>,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    let (player, player_transform) = *player;
+    let position = player_transform.translation.xy();
+    let hit = enemies.iter().find(|(_, enemy_transform, _)| {
+        let enemy_radius = ENEMY_RADIUS * enemy_transform.scale.x;
+        position.distance(enemy_transform.translation.xy()) < PLAYER_RADIUS + enemy_radius
+    });
+    let Some((enemy, enemy_transform, damage)) = hit else {
+        return;
+    };
+    damage_events.send(DamageEvent {
+        target: player,
+        amount: damage.0,
+    });
+
+    // 中心が重なっているときは、宇宙船の後ろへ押し戻す
+    let away = (position - enemy_transform.translation.xy())
+        .try_normalize()
+        .unwrap_or(-(player_transform.rotation * Vec3::Y).xy());
+    commands.entity(player).insert((
+        Invulnerable(Timer::from_seconds(INVULNERABLE_SECONDS, TimerMode::Once)),
+        Knockback(away * PLAYER_KNOCKBACK),
+    ));
+    commands
+        .entity(enemy)
+        .insert(Knockback(-away * ENEMY_KNOCKBACK));
+}
+
+/// ダメージを受けない時間を進め、終わったら外す
+fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable)>,
+) {
+    for (entity, mut invulnerable) in &mut query {
+        if invulnerable.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
+
+/// 届いた [`DamageEvent`] の分だけ体力を減らす (0 より下にはしない)
+///
+/// ダメージを与える側は体力を直接触らずにイベントを送るだけなので、ダメージの種類を増やしてもここは変わらない。
+/// [`Shield`] を持つ相手は、シールドが受け止めきれなかった分だけ体力を減らす。
+pub fn apply_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut healths: Query<(&mut Health, Option<&mut Shield>)>,
+) {
+    for event in damage_events.read() {
+        // 同じステップで先に倒れて消えた相手へのダメージは捨てる
+        if let Ok((mut health, shield)) = healths.get_mut(event.target) {
+            let amount = match shield {
+                Some(mut shield) => shield.absorb(event.amount),
+                None => event.amount,
+            };
+            health.current = health.current.saturating_sub(amount);
+        }
+    }
+}
+
+/// 体力が 0 になった敵を消し、プレイヤーは画面の中央で体力を戻してやり直す
+///
+/// プレイヤーは多くのシステムが `Single` で読むので、消さずに位置と体力を戻し、[`PlayerDestroyed`] を送る。
+#[allow(clippy::type_complexity)]
+pub fn despawn_dead(
+    mut commands: Commands,
+    mut destroyed: EventWriter<PlayerDestroyed>,
+    enemies: Query<(Entity, &Health), With<Enemy>>,
+    player: Single<
+        (Entity, &mut Health, &mut Transform, &mut Velocity),
+        (With<Player>, Without<Enemy>),
+    >,
+) {
+    for (enemy, health) in &enemies {
+        if health.current == 0 {
+            commands.entity(enemy).despawn();
+        }
+    }
+
+    let (player, mut health, mut transform, mut velocity) = player.into_inner();
+    if health.current > 0 {
+        return;
+    }
+    info!("the player ship was destroyed");
+    health.current = health.max;
+    *transform = Transform::IDENTITY;
+    velocity.0 = Vec2::ZERO;
+    // ぶつかった直後に撃墜されたときも、押し戻されたまま流れないようにする
+    commands
+        .entity(player)
+        .insert(Invulnerable(Timer::from_seconds(
+            RESPAWN_INVULNERABLE_SECONDS,
+            TimerMode::Once,
+        )))
+        .remove::<Knockback>();
+    destroyed.send(PlayerDestroyed);
+}
+
+/// ダメージを受けない間は宇宙船を点滅させ、終わったら表示に戻す
+fn blink_invulnerable(player: Single<(&mut Visibility, Option<&Invulnerable>), With<Player>>) {
+    let (mut visibility, invulnerable) = player.into_inner();
+    let visible = invulnerable.is_none_or(|invulnerable| {
+        ((invulnerable.0.elapsed_secs() * BLINK_RATE) as u32).is_multiple_of(2)
+    });
+    let target = if visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    // 変わるときだけ書き込み、毎フレーム変更扱いにしない
+    visibility.set_if_neq(target);
+}
+
+/// プレイヤーの上に表示する体力バーを配置する
+///
+/// 宇宙船と一緒に回転しないように、プレイヤーの子にはせず、[`update_health_bar`] で位置を合わせる。
+fn spawn_health_bar(mut commands: Commands) {
+    commands.spawn((
+        Sprite::from_color(HEALTH_BAR_BACKGROUND, HEALTH_BAR_SIZE),
+        Transform::default(),
+        HealthBar,
+    ));
+    // 左端を基準にして、残りの体力に合わせて幅を縮める
+    commands.spawn((
+        Sprite {
+            color: HEALTH_BAR_COLOR,
+            custom_size: Some(HEALTH_BAR_SIZE),
+            anchor: Anchor::CenterLeft,
+            ..default()
+        },
+        Transform::default(),
+        HealthBarFill,
+    ));
+}
+
+/// 体力バーをプレイヤーの上に動かし、残りの体力の割合に合わせて幅を変える
+#[allow(clippy::type_complexity)]
+fn update_health_bar(
+    player: Single<(&Transform, &Health), With<Player>>,
+    mut background: Single<&mut Transform, (With<HealthBar>, Without<Player>)>,
+    fill: Single<
+        (&mut Transform, &mut Sprite),
+        (With<HealthBarFill>, Without<Player>, Without<HealthBar>),
+    >,
+) {
+    let (player_transform, health) = *player;
+    let center = player_transform.translation + Vec3::new(0.0, HEALTH_BAR_OFFSET, 1.0);
+    background.translation = center;
+
+    let (mut fill_transform, mut sprite) = fill.into_inner();
+    fill_transform.translation = center - Vec3::new(HEALTH_BAR_SIZE.x / 2.0, 0.0, -0.1);
+    let ratio = health.current as f32 / health.max as f32;
+    sprite.custom_size = Some(Vec2::new(HEALTH_BAR_SIZE.x * ratio, HEALTH_BAR_SIZE.y));
+}
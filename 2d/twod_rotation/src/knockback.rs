@@ -0,0 +1,49 @@
+use bevy::{math::ops, prelude::*};
+use prelude_kit::apply_velocity;
+
+use crate::{steering, wrap};
+
+// ノックバックの速さが 1 秒あたりに減る割合 (大きいほど早く止まる)
+const KNOCKBACK_DECAY: f32 = 6.0;
+// これより遅くなったら、ノックバックを終える (メートル/秒)
+const KNOCKBACK_STOP_SPEED: f32 = 5.0;
+
+/// ぶつかったときに押し戻されるノックバックを動かすプラグイン
+///
+/// ノックバックは [`Velocity`](prelude_kit::Velocity) とは別に持つ。
+/// プレイヤーの速度は毎ステップ操作で決め直し、小惑星の速度は漂う向きとして残るので、どちらにも混ぜずに済む。
+pub struct KnockbackPlugin;
+
+impl Plugin for KnockbackPlugin {
+    fn build(&self, app: &mut App) {
+        // 速度での移動のあと、境界に収める・回り込む前に押し戻す
+        app.add_systems(
+            FixedUpdate,
+            apply_knockback
+                .after(apply_velocity)
+                .before(crate::keep_player_in_bounds)
+                .before(wrap::wrap_around_edges)
+                .before(steering::keep_agents_in_bounds),
+        );
+    }
+}
+
+/// 押し戻される速度 (メートル/秒)。だんだん遅くなり、止まったら外す
+#[derive(Component, Debug, Clone, Copy, Deref, DerefMut)]
+pub struct Knockback(pub Vec2);
+
+/// ノックバックの速度で動かし、指数的に減らして、十分に遅くなったら外す
+fn apply_knockback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Transform, &mut Knockback)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut transform, mut knockback) in &mut query {
+        transform.translation += (knockback.0 * delta).extend(0.0);
+        knockback.0 *= ops::exp(-KNOCKBACK_DECAY * delta);
+        if knockback.length() < KNOCKBACK_STOP_SPEED {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
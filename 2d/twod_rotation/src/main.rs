@@ -1,42 +1,172 @@
+use announcer::AnnouncerPlugin;
 use bevy::{math::ops, prelude::*};
+use debug_stepping::SteppingPlugin;
+use frame_pacing::FramePacingPlugin;
+use health::{Damage, Health};
+use prelude_kit::{apply_velocity, Velocity};
+use wrap::Wraps;
 
-// ゲームの境界を定義
-const BOUNDS: Vec2 = Vec2::new(1200.0, 640.0);
+mod asteroids;
+mod audio;
+mod camera;
+mod dash;
+mod exhaust;
+mod game;
+mod gamepad;
+mod health;
+mod knockback;
+mod minimap;
+mod physics;
+mod shield;
+mod steering;
+mod stress;
+mod vignette;
+mod waves;
+mod wrap;
+
+// ゲームの境界を定義 (ウィンドウより広く、カメラが宇宙船を追いかけて動く)
+const BOUNDS: Vec2 = Vec2::new(2400.0, 1280.0);
+// 弾の速さ (メートル/秒)
+const BULLET_SPEED: f32 = 900.0;
+// 弾の大きさ
+const BULLET_SIZE: Vec2 = Vec2::new(4.0, 14.0);
+const BULLET_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+// 次の弾を撃てるまでの時間 (秒)
+const FIRE_INTERVAL: f32 = 0.2;
+// 宇宙船の中心から先端までの距離 (弾を撃ち出す位置)
+const SHIP_NOSE_OFFSET: f32 = 40.0;
+// 弾が当たったとみなす敵の中心からの距離 (拡大縮小する前の大きさ)
+const ENEMY_RADIUS: f32 = 36.0;
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        .add_plugins(FramePacingPlugin) // 1 フレームあたりの固定ステップ数を制限
         .insert_resource(Time::<Fixed>::from_hz(60.0)) // 固定時間ステップを60Hzに設定
+        // `--stress` で大量の敵を配置し、回転システムの処理時間を計る
+        .add_plugins(stress::StressTestPlugin)
+        // 画面の端から、だんだん大きくなる敵の波を出す (負荷試験モードでは出さない)
+        .add_plugins(waves::WavesPlugin)
+        // 弾や敵との接触によるダメージを体力に反映し、プレイヤーの上に体力バーを表示する
+        .add_plugins(health::HealthPlugin)
+        // `W` キー (または起動引数 `--wrap`) で、画面の端で止めるか反対側へ回り込むかを切り替える
+        .add_plugins(wrap::WrapPlugin)
+        // 前進している間、宇宙船の後ろから噴射のパーティクルを出す
+        .add_plugins(exhaust::ExhaustPlugin)
+        // 漂う小惑星 (弾で割れる)・得点と残機・ゲームオーバー画面で、アステロイド風のゲームにする
+        .add_plugins((asteroids::AsteroidsPlugin, game::GamePlugin))
+        // 波の敵を、重み付きの操舵行動 (追う・逃げる・うろつく・近づいて止まる) で動かす
+        .add_plugins(steering::SteeringPlugin)
+        // 宇宙船と敵がぶつかったときに、互いに押し戻す
+        .add_plugins(knockback::KnockbackPlugin)
+        // 宇宙船をなめらかに追いかけるカメラと、遊ぶ範囲の枠・背景の星
+        .add_plugins(camera::FollowCameraPlugin)
+        // 境界で止めるモードで、宇宙船が境界に近づくと近い側の画面の端を暗くする
+        .add_plugins(vignette::EdgeVignettePlugin)
+        // 画面右下に、遊ぶ範囲全体のプレイヤーと敵の位置を表すミニマップを表示する
+        .add_plugins(minimap::MinimapPlugin)
+        // `M` キー (または起動引数 `--inertia`) で、アーケード風と慣性で動く移動を切り替える
+        .add_plugins(physics::ShipPhysicsPlugin)
+        // `Shift` キーで、向いている方向へ短い間だけ速く進むダッシュ
+        .add_plugins(dash::DashPlugin)
+        // 体力より先にダメージを受け止め、しばらくすると回復するシールド
+        .add_plugins(shield::ShieldPlugin)
+        // スロットルで音量と高さが変わるエンジン音と、弾と爆発の効果音
+        .add_plugins(audio::SoundEffectsPlugin)
+        // 新しい波が来たことを、ボイスと字幕で知らせる (`audio/announcer`)
+        .add_plugins(AnnouncerPlugin)
+        // `--features bevy_debug_stepping` で、`FixedUpdate` のシステムを 1 つずつ実行できるようにする (`crates/debug_stepping`)
+        // (UI は、負荷試験モードの左上の表示と重ならないように画面の中ほどに置く)
+        .add_plugins(
+            SteppingPlugin::default()
+                .add_schedule(FixedUpdate)
+                .at(Val::Px(10.0), Val::Percent(40.0)),
+        )
+        .init_resource::<PlayerInput>()
         .add_systems(Startup, setup)
         .add_systems(
             FixedUpdate,
             (
-                player_movement_system, // プレイヤーの移動システム
-                snap_to_player_system,  // 敵がプレイヤーに即座を向くシステム
-                rotate_to_player_system // 敵が徐々にプレイヤーに向くシステム
+                (
+                    read_player_input, // キーボードとゲームパッドの入力の読み取り (記録の再生中は `stress.rs` が上書きする)
+                    player_movement_system, // プレイヤーの回転と速度を決めるシステム
+                    fire_bullets, // 宇宙船の先端から弾を撃つ
+                    apply_velocity, // 速度に合わせて移動する (プレイヤーと弾、`crates/prelude_kit`)
+                    keep_player_in_bounds.run_if(not(wrap::wrapping)), // 画面の境界内に収める
+                    wrap::wrap_around_edges.run_if(wrap::wrapping), // 画面の端を越えたら反対の端へ回り込む
+                    despawn_bullets_out_of_bounds.run_if(not(wrap::wrapping)), // 画面の外に出た弾を消す
+                    wrap::expire_bullets.run_if(wrap::wrapping), // 回り込む弾は一定時間で消す
+                    bullet_hits_enemy, // 弾が当たった敵にダメージを与える
+                )
+                    .chain(),
+                (
+                    snap_to_player_system,  // 敵がプレイヤーに即座を向くシステム
+                    rotate_to_player_system.run_if(stress::naive_rotation), // 敵が徐々にプレイヤーに向くシステム
+                )
+                    .in_set(stress::RotationSystems),
             )
         )
         .run();
 }
 
 /// プレイヤーコンポーネント
+///
+/// 移動は共通の [`Velocity`] に速度を入れ、`apply_velocity` に任せる。
 #[derive(Component)]
+#[require(
+    Velocity,
+    Health(player_health),
+    Wraps(wrap::ship_wraps),
+    exhaust::Thruster,
+    dash::Dash,
+    shield::Shield
+)]
 struct Player {
     movement_speed: f32, // 移動速度 (メートル/秒)
     rotation_speed: f32, // 回転速度 (ラジアン/秒)
+    reload: Timer,       // 次の弾を撃てるまでの時間
 }
 
+/// プレイヤーが撃った弾のコンポーネント
+///
+/// 移動はプレイヤーと同じく [`Velocity`] と `apply_velocity` に任せる。
+#[derive(Component)]
+#[require(
+    Velocity,
+    Damage(bullet_damage),
+    Wraps(wrap::bullet_wraps),
+    wrap::BulletLifetime
+)]
+struct Bullet;
+
+/// 弾で倒せる敵のコンポーネント
+///
+/// [`SnapToPlayer`] と [`RotateToPlayer`] の敵に自動で追加する。
+/// 体力と、プレイヤーにぶつかったときの威力、画面の端での回り込みも一緒に追加する。
+#[derive(Component, Default)]
+#[require(
+    Health(health::enemy_health),
+    Damage(health::enemy_damage),
+    Wraps(wrap::enemy_wraps)
+)]
+struct Enemy;
+
 /// プレイヤーの方向へ即座に向く敵のコンポーネント
 #[derive(Component)]
+#[require(Enemy)]
 struct SnapToPlayer;
 
 /// プレイヤーの方向へ徐々に回転する敵のコンポーネント
+///
+/// 最適化した回転システム (`stress.rs`) が使う、プレイヤーへの向きのキャッシュを自動で追加する。
 #[derive(Component)]
+#[require(Enemy, stress::ToPlayerCache)]
 struct RotateToPlayer {
     rotation_speed: f32, //  回転速度 (rad/s)
 }
 
-/// ゲームのエンティティを追加し、2Dレンダリング用の直交カメラを作成する。
+/// プレイヤーの宇宙船を追加し、2Dレンダリング用の直交カメラを作成する。
+/// 敵と小惑星は `waves.rs` の [`waves::EnemySpawner`] が波に分けて出す。
 /// 
 /// Bevy の座標系は 2D と 3D で共通で、2D では以下のようになる：
 /// 
@@ -44,17 +174,12 @@ struct RotateToPlayer {
 /// * `Y` 軸は下から上へ (`+Y` は上方向)
 /// * `Z` 軸は奥から手前へ (`+Z` は画面外から手前方向)
 /// 
-/// 原点は画面の中心
+/// 原点は遊ぶ範囲 (`BOUNDS`) の中心で、カメラは宇宙船を追いかけて動く
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     let ship_handle = asset_server.load("textures/simplespace/ship_C.png");
-    let enemy_a_handle = asset_server.load("textures/simplespace/enemy_A.png");
-    let enemy_b_handle = asset_server.load("textures/simplespace/enemy_B.png");
-
-    // 2D直交カメラの作成
-    commands.spawn(Camera2d);
 
-    let horizontal_margin = BOUNDS.x / 4.0;
-    let vertical_margin = BOUNDS.y / 4.0;
+    // 2D直交カメラの作成 (宇宙船を追いかける)
+    commands.spawn((Camera2d, camera::FollowCamera::default()));
 
     // プレイヤーの宇宙船
     commands.spawn((
@@ -62,45 +187,26 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         Player {
             movement_speed: 500.0,                        // メートル/秒
             rotation_speed: f32::to_radians(360.0), // 度/秒
+            reload: Timer::from_seconds(FIRE_INTERVAL, TimerMode::Once),
         },
     ));
+}
 
-    // SnapToPlayerの敵を作成 (即座にプレイヤーを向く)
-    commands.spawn((
-        Sprite::from_image(enemy_a_handle.clone()),
-        Transform::from_xyz(0.0 - horizontal_margin, 0.0, 0.0),
-        SnapToPlayer,
-    ));
-    commands.spawn((
-        Sprite::from_image(enemy_a_handle),
-        Transform::from_xyz(0.0, 0.0 - vertical_margin, 0.0),
-        SnapToPlayer,
-    ));
-
-    // RotateToPlayerの敵を生成 (徐々に回転する)
-    commands.spawn((
-        Sprite::from_image(enemy_b_handle.clone()),
-        Transform::from_xyz(0.0 + horizontal_margin, 0.0, 0.0),
-        RotateToPlayer {
-            rotation_speed: f32::to_radians(45.0), // 度/秒
-        },
-    ));
-    commands.spawn((
-        Sprite::from_image(enemy_b_handle),
-        Transform::from_xyz(0.0, 0.0 + vertical_margin, 0.0),
-        RotateToPlayer {
-            rotation_speed: f32::to_radians(90.0), // 度/秒
-        },
-    ));
+/// 1 ステップ分のプレイヤーの操作
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+struct PlayerInput {
+    rotation_factor: f32, // 回転の向きと強さ (1.0 で左回り、-1.0 で右回り。ゲームパッドでは間の値も取る)
+    movement_factor: f32, // 前進の強さ (スロットル。キーボードでは前進するなら 1.0)
+    fire: bool,           // 弾を撃つか
+    dash: bool,           // ダッシュするか
 }
 
-/// キーボード入力に基づいて回転と移動を適用する
-fn player_movement_system(
-    time: Res<Time>,
+/// キーボードとゲームパッドの入力からプレイヤーの操作を決める
+fn read_player_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    query: Single<(&Player, &mut Transform)>,
+    gamepads: Query<&Gamepad>,
+    mut input: ResMut<PlayerInput>,
 ) {
-    let (ship, mut transform) = query.into_inner();
     let mut rotation_factor = 0.0;
     let mut movement_factor = 0.0;
 
@@ -115,16 +221,120 @@ fn player_movement_system(
         movement_factor += 1.0;
     }
 
+    // 押している間は `FIRE_INTERVAL` ごとに撃つ
+    // (`FixedUpdate` では `just_pressed` を取りこぼすことがあるので、押しているかで決める)
+    *input = PlayerInput {
+        rotation_factor,
+        movement_factor,
+        fire: keyboard_input.pressed(KeyCode::Space),
+        dash: keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+    };
+    // 左スティックで回転と前進、右トリガーでスロットル (`gamepad.rs`)
+    gamepad::apply_gamepads(&mut input, &gamepads);
+}
+
+/// プレイヤーの操作に基づいて回転し、向いている方向へ進む速度を決める
+///
+/// 速度の決め方は [`physics::ShipPhysics`] の移動の方式に従う (`movement_speed` は慣性で動くときの最高速度になる)。
+fn player_movement_system(
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    physics: Res<physics::ShipPhysics>,
+    query: Single<(&Player, &mut Transform, &mut Velocity)>,
+) {
+    let (ship, mut transform, mut velocity) = query.into_inner();
+
     // Z軸回転
-    transform.rotate_z(rotation_factor * ship.rotation_speed * time.delta_secs());
+    transform.rotate_z(input.rotation_factor * ship.rotation_speed * time.delta_secs());
+
+    // 現在の向きに基づいて速度を決める (移動は `apply_velocity` が行う)
+    let movement_direction = (transform.rotation * Vec3::Y).xy();
+    velocity.0 = physics.next_velocity(
+        velocity.0,
+        movement_direction,
+        input.movement_factor,
+        ship.movement_speed,
+        time.delta_secs(),
+    );
+}
+
+/// 操作が撃つなら、宇宙船の先端から向いている方向へ弾を撃つ
+fn fire_bullets(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    player: Single<(&mut Player, &Transform)>,
+) {
+    let (mut ship, transform) = player.into_inner();
+    ship.reload.tick(time.delta());
+    if !input.fire || !ship.reload.finished() {
+        return;
+    }
+    ship.reload.reset();
+
+    // 宇宙船の向き (画像の上向きが前) に撃ち出す
+    let direction = transform.rotation * Vec3::Y;
+    commands.spawn((
+        Sprite::from_color(BULLET_COLOR, BULLET_SIZE),
+        Transform::from_translation(transform.translation + direction * SHIP_NOSE_OFFSET)
+            .with_rotation(transform.rotation),
+        Bullet,
+        Velocity(direction.xy() * BULLET_SPEED),
+    ));
+}
+
+/// 画面の境界の外に出た弾を消す
+fn despawn_bullets_out_of_bounds(
+    mut commands: Commands,
+    bullets: Query<(Entity, &Transform), With<Bullet>>,
+) {
+    let extents = BOUNDS / 2.0;
+    for (bullet, transform) in &bullets {
+        let position = transform.translation.xy();
+        if position.abs().cmpgt(extents).any() {
+            commands.entity(bullet).despawn();
+        }
+    }
+}
+
+/// 弾が当たった敵に弾の威力のダメージを送り、弾を消す
+///
+/// 当たりは敵の中心からの距離で判定する (負荷試験の小さな敵や小惑星は、拡大縮小に合わせて半径も変える)。
+fn bullet_hits_enemy(
+    mut commands: Commands,
+    mut damage_events: EventWriter<health::DamageEvent>,
+    bullets: Query<(Entity, &Transform, &Damage), With<Bullet>>,
+    enemies: Query<(Entity, &Transform), With<Enemy>>,
+) {
+    for (bullet, bullet_transform, damage) in &bullets {
+        let position = bullet_transform.translation.xy();
+        let hit = enemies.iter().find(|(_, enemy_transform)| {
+            position.distance(enemy_transform.translation.xy())
+                < ENEMY_RADIUS * enemy_transform.scale.x
+        });
+        if let Some((enemy, _)) = hit {
+            // 敵の体力は `health::apply_damage` で減らし、0 になったら `health.rs` で消す
+            damage_events.send(health::DamageEvent {
+                target: enemy,
+                amount: damage.0,
+            });
+            commands.entity(bullet).despawn();
+        }
+    }
+}
 
-    // 現在の向きに基づいて移動
-    let movement_direction = transform.rotation * Vec3::Y;
-    let movement_distance = movement_factor * ship.movement_speed * time.delta_secs();
-    let translation_delta = movement_direction * movement_distance;
-    transform.translation += translation_delta;
+/// [`Player`] の最初の体力
+fn player_health() -> Health {
+    Health::new(health::PLAYER_HEALTH)
+}
+
+/// [`Bullet`] 1 発の威力
+fn bullet_damage() -> Damage {
+    Damage(health::BULLET_DAMAGE)
+}
 
-    // 画面の境界内に収める
+/// プレイヤーを画面の境界内に収める
+fn keep_player_in_bounds(mut transform: Single<&mut Transform, With<Player>>) {
     let extents = Vec3::from((BOUNDS / 2.0, 0.0));
     transform.translation = transform.translation.min(extents).max(-extents);
 }
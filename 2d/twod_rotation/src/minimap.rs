@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+
+use crate::{asteroids::Asteroid, stress, Enemy, Player, BOUNDS};
+
+// ミニマップの幅 (高さは遊ぶ範囲の縦横比に合わせる)
+const MINIMAP_WIDTH: f32 = 200.0;
+// ミニマップの背景と枠の色
+const MINIMAP_BACKGROUND: Color = Color::srgba(0.05, 0.05, 0.1, 0.7);
+const MINIMAP_BORDER: Color = Color::srgb(0.3, 0.35, 0.5);
+// 点の大きさと色
+const PLAYER_DOT_SIZE: f32 = 6.0;
+const ENEMY_DOT_SIZE: f32 = 4.0;
+const PLAYER_DOT_COLOR: Color = Color::srgb(0.3, 0.9, 0.4);
+const ENEMY_DOT_COLOR: Color = Color::srgb(0.95, 0.3, 0.3);
+const ASTEROID_DOT_COLOR: Color = Color::srgb(0.6, 0.55, 0.5);
+
+/// 画面右下に、遊ぶ範囲全体のプレイヤーと敵の位置を点で表すミニマップを表示するプラグイン
+///
+/// 負荷試験モードでは、敵の数だけ UI のノードが増えてしまうので表示しない。
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_minimap.run_if(not(stress::stress_mode)))
+            .add_systems(Update, update_minimap.run_if(not(stress::stress_mode)));
+    }
+}
+
+/// ミニマップの背景のノードを示すコンポーネント
+#[derive(Component)]
+struct Minimap;
+
+/// プレイヤーの位置を表す点を示すコンポーネント
+#[derive(Component)]
+struct PlayerDot;
+
+/// 敵や小惑星の位置を表す点を示すコンポーネント (敵の数に合わせて使い回す)
+#[derive(Component)]
+struct EnemyDot;
+
+/// ミニマップの大きさ
+fn minimap_size() -> Vec2 {
+    Vec2::new(MINIMAP_WIDTH, MINIMAP_WIDTH * BOUNDS.y / BOUNDS.x)
+}
+
+/// 画面右下にミニマップの背景と、プレイヤーの点を配置する
+fn spawn_minimap(mut commands: Commands) {
+    let size = minimap_size();
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                width: Val::Px(size.x),
+                height: Val::Px(size.y),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(MINIMAP_BACKGROUND),
+            BorderColor(MINIMAP_BORDER),
+            Minimap,
+        ))
+        .with_children(|parent| {
+            // 敵の点より手前に描く
+            parent.spawn((
+                dot_node(PLAYER_DOT_SIZE),
+                BackgroundColor(PLAYER_DOT_COLOR),
+                ZIndex(1),
+                PlayerDot,
+            ));
+        });
+}
+
+/// ミニマップの中に絶対位置で置く、`size` の大きさの点
+fn dot_node(size: f32) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Px(size),
+        height: Val::Px(size),
+        ..default()
+    }
+}
+
+/// ワールドの位置を、ミニマップの左上からの位置に変換する (点の中心が位置に来るように `size` の半分ずらす)
+///
+/// UI は下向きが `+Y` なので、ワールドの `Y` を反転する。
+fn to_minimap(position: Vec2, size: f32) -> (Val, Val) {
+    let extents = BOUNDS / 2.0;
+    // ノックバックで一瞬だけ境界の外に出ても、ミニマップからははみ出さない
+    let normalized = position.clamp(-extents, extents) / BOUNDS + Vec2::splat(0.5);
+    let map = minimap_size() * Vec2::new(normalized.x, 1.0 - normalized.y) - size / 2.0;
+    (Val::Px(map.x), Val::Px(map.y))
+}
+
+/// プレイヤーと敵の点を、毎フレーム今の位置に動かす
+///
+/// 敵の点は敵の数に合わせて使い回し、足りなければ追加し、余れば消す。
+#[allow(clippy::type_complexity)]
+fn update_minimap(
+    mut commands: Commands,
+    minimap: Single<Entity, With<Minimap>>,
+    player: Single<&Transform, With<Player>>,
+    enemies: Query<(&Transform, Has<Asteroid>), With<Enemy>>,
+    mut player_dot: Single<&mut Node, With<PlayerDot>>,
+    mut dots: Query<
+        (Entity, &mut Node, &mut BackgroundColor),
+        (With<EnemyDot>, Without<PlayerDot>),
+    >,
+) {
+    (player_dot.left, player_dot.top) = to_minimap(player.translation.xy(), PLAYER_DOT_SIZE);
+
+    let mut enemies = enemies.iter();
+    for (dot, mut node, mut color) in &mut dots {
+        let Some((transform, asteroid)) = enemies.next() else {
+            // 親のミニマップの子の一覧からも外す
+            commands.entity(dot).despawn_recursive();
+            continue;
+        };
+        (node.left, node.top) = to_minimap(transform.translation.xy(), ENEMY_DOT_SIZE);
+        color.0 = dot_color(asteroid);
+    }
+    let minimap = *minimap;
+    for (transform, asteroid) in enemies {
+        let (left, top) = to_minimap(transform.translation.xy(), ENEMY_DOT_SIZE);
+        let dot = commands
+            .spawn((
+                Node {
+                    left,
+                    top,
+                    ..dot_node(ENEMY_DOT_SIZE)
+                },
+                BackgroundColor(dot_color(asteroid)),
+                EnemyDot,
+            ))
+            .id();
+        commands.entity(minimap).add_child(dot);
+    }
+}
+
+/// 敵と小惑星の点の色
+fn dot_color(asteroid: bool) -> Color {
+    if asteroid {
+        ASTEROID_DOT_COLOR
+    } else {
+        ENEMY_DOT_COLOR
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::{math::ops, prelude::*};
+
+// 慣性で動くモードで起動する起動引数
+const INERTIA_ARG: &str = "--inertia";
+// 移動の方式を切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::KeyM;
+// 慣性で動くモードの、全開で前進したときの加速度 (メートル/秒²)
+const DEFAULT_ACCELERATION: f32 = 600.0;
+// 慣性で動くモードの抵抗 (1 秒あたりに速度が減る割合。0.0 なら減らない)
+const DEFAULT_DRAG: f32 = 0.8;
+
+/// 宇宙船の移動の方式を選ぶ設定のリソースを追加するプラグイン
+///
+/// 起動引数 `--inertia` で慣性で動くモードから始め、`M` キーで切り替えられる。
+pub struct ShipPhysicsPlugin;
+
+impl Plugin for ShipPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ShipPhysics::from_args())
+            .add_systems(Update, toggle_movement_mode);
+    }
+}
+
+/// 宇宙船の移動の方式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MovementMode {
+    /// 前進している間だけ一定の速さで進み、離すとすぐに止まる (アーケード風)
+    #[default]
+    Arcade,
+    /// 前進すると加速し、離しても抵抗で徐々に遅くなるまで進み続ける (慣性)
+    Inertial,
+}
+
+/// 宇宙船の移動の方式と、慣性で動くモードの設定
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ShipPhysics {
+    pub mode: MovementMode, // 移動の方式
+    pub acceleration: f32,  // 全開で前進したときの加速度 (メートル/秒²)
+    pub drag: f32,          // 1 秒あたりに速度が減る割合
+}
+
+impl Default for ShipPhysics {
+    fn default() -> Self {
+        ShipPhysics {
+            mode: MovementMode::default(),
+            acceleration: DEFAULT_ACCELERATION,
+            drag: DEFAULT_DRAG,
+        }
+    }
+}
+
+impl ShipPhysics {
+    /// 起動引数に `--inertia` があれば慣性で動くモードにする
+    fn from_args() -> ShipPhysics {
+        let mode = if std::env::args().any(|arg| arg == INERTIA_ARG) {
+            MovementMode::Inertial
+        } else {
+            MovementMode::Arcade
+        };
+        ShipPhysics { mode, ..default() }
+    }
+
+    /// 向き `direction` へのスロットル `throttle` で、`delta` 秒後の速度を決める (速さは `max_speed` まで)
+    ///
+    /// アーケード風なら速度を直接決め、慣性で動くなら加速度を加えてから抵抗で減らす。
+    pub fn next_velocity(
+        &self,
+        velocity: Vec2,
+        direction: Vec2,
+        throttle: f32,
+        max_speed: f32,
+        delta: f32,
+    ) -> Vec2 {
+        match self.mode {
+            MovementMode::Arcade => direction * throttle * max_speed,
+            MovementMode::Inertial => {
+                let accelerated = velocity + direction * throttle * self.acceleration * delta;
+                // フレームレートが変わっても同じだけ減るように、指数的に減らす
+                (accelerated * ops::exp(-self.drag * delta)).clamp_length_max(max_speed)
+            }
+        }
+    }
+}
+
+/// `M` キーで、アーケード風と慣性で動くモードを切り替える
+fn toggle_movement_mode(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut physics: ResMut<ShipPhysics>,
+) {
+    if !keyboard_input.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+    physics.mode = match physics.mode {
+        MovementMode::Arcade => MovementMode::Inertial,
+        MovementMode::Inertial => MovementMode::Arcade,
+    };
+    info!("movement mode: {:?}", physics.mode);
+}
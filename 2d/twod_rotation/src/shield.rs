@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+
+use crate::health::PlayerDestroyed;
+
+// シールドが受け止められるダメージの量
+const SHIELD_CAPACITY: u32 = 3;
+// ダメージを受けてから、シールドが回復し始めるまでの時間 (秒)
+const REGEN_DELAY: f32 = 3.0;
+// 回復し始めてから、1 ずつ回復する間隔 (秒)
+const REGEN_INTERVAL: f32 = 1.0;
+// ダメージを受け止めたときに、リングを明るくする時間 (秒)
+const FLASH_SECONDS: f32 = 0.15;
+// リングの内側と外側の半径
+const RING_INNER_RADIUS: f32 = 44.0;
+const RING_OUTER_RADIUS: f32 = 48.0;
+// リングの色 (満タンのときの不透明度) と、ダメージを受け止めたときの色
+const RING_COLOR: Color = Color::srgba(0.4, 0.7, 1.0, 0.5);
+const RING_FLASH_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.9);
+
+/// 体力より先にダメージを受け止め、しばらくダメージを受けなければ回復するシールドを追加するプラグイン
+///
+/// 受け止める処理は `health.rs` の `apply_damage` が [`Shield::absorb`] を呼んで行う。
+/// シールドは宇宙船の周りの半透明のリングで表し、受け止めると光り、なくなると消える。
+pub struct ShieldPlugin;
+
+impl Plugin for ShieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, (regenerate_shields, refill_on_respawn))
+            .add_systems(Update, (attach_rings, update_rings).chain());
+    }
+}
+
+/// 体力より先にダメージを受け止めるシールド
+#[derive(Component, Debug)]
+pub struct Shield {
+    pub current: u32,  // 残りのシールド
+    pub capacity: u32, // 満タンのときのシールド
+    delay: Timer,      // 回復し始めるまでの時間
+    regen: Timer,      // 次に 1 回復するまでの時間
+    flash: Timer,      // リングを明るくしている時間
+}
+
+impl Default for Shield {
+    fn default() -> Self {
+        let mut flash = Timer::from_seconds(FLASH_SECONDS, TimerMode::Once);
+        flash.tick(flash.duration());
+        Shield {
+            current: SHIELD_CAPACITY,
+            capacity: SHIELD_CAPACITY,
+            delay: Timer::from_seconds(REGEN_DELAY, TimerMode::Once),
+            regen: Timer::from_seconds(REGEN_INTERVAL, TimerMode::Repeating),
+            flash,
+        }
+    }
+}
+
+impl Shield {
+    /// `amount` のダメージをできるだけ受け止め、受け止めきれなかった分を返す
+    ///
+    /// シールドがなくても、ダメージを受けたら回復し始めるまでの時間を数え直す。
+    pub fn absorb(&mut self, amount: u32) -> u32 {
+        let absorbed = amount.min(self.current);
+        self.current -= absorbed;
+        self.delay.reset();
+        self.regen.reset();
+        if absorbed > 0 {
+            self.flash.reset();
+        }
+        amount - absorbed
+    }
+}
+
+/// シールドのリングを示すコンポーネント (リングごとに色を変えるので、マテリアルも別々に持つ)
+#[derive(Component)]
+struct ShieldRing;
+
+/// しばらくダメージを受けていなければ、シールドを 1 ずつ回復する
+fn regenerate_shields(time: Res<Time>, mut shields: Query<&mut Shield>) {
+    for mut shield in &mut shields {
+        if shield.current >= shield.capacity {
+            continue;
+        }
+        if !shield.delay.tick(time.delta()).finished() {
+            continue;
+        }
+        if shield.regen.tick(time.delta()).just_finished() {
+            shield.current += 1;
+        }
+    }
+}
+
+/// 撃墜されて画面の中央からやり直すときに、シールドを満タンに戻す
+fn refill_on_respawn(
+    mut destroyed: EventReader<PlayerDestroyed>,
+    mut shields: Query<&mut Shield, With<crate::Player>>,
+) {
+    if destroyed.read().count() == 0 {
+        return;
+    }
+    for mut shield in &mut shields {
+        shield.current = shield.capacity;
+    }
+}
+
+/// シールドを持つエンティティに、子としてリングを追加する
+///
+/// リングは回転しても形が変わらないので、宇宙船の子にして位置と回転をそのまま引き継ぐ。
+/// 宇宙船を点滅させるときも、子のリングは一緒に隠れる。
+fn attach_rings(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    added: Query<Entity, Added<Shield>>,
+) {
+    for entity in &added {
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                Mesh2d(meshes.add(Annulus::new(RING_INNER_RADIUS, RING_OUTER_RADIUS))),
+                MeshMaterial2d(materials.add(RING_COLOR)),
+                // 宇宙船の手前に描く
+                Transform::from_xyz(0.0, 0.0, 0.1),
+                ShieldRing,
+            ));
+        });
+    }
+}
+
+/// リングの不透明度を残りのシールドに合わせ、受け止めた直後は光らせ、なくなったら隠す
+fn update_rings(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut shields: Query<&mut Shield>,
+    mut rings: Query<(&Parent, &MeshMaterial2d<ColorMaterial>, &mut Visibility), With<ShieldRing>>,
+) {
+    for (parent, material, mut visibility) in &mut rings {
+        let Ok(mut shield) = shields.get_mut(parent.get()) else {
+            continue;
+        };
+        let flashing = !shield.flash.tick(time.delta()).finished();
+        let target = if shield.current == 0 && !flashing {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        visibility.set_if_neq(target);
+
+        let color = if flashing {
+            RING_FLASH_COLOR
+        } else {
+            let remaining = shield.current as f32 / shield.capacity as f32;
+            RING_COLOR.with_alpha(RING_COLOR.alpha() * remaining)
+        };
+        // 変わるときだけ書き込み、毎フレームマテリアルを作り直さない
+        if let Some(material) = materials.get_mut(&material.0) {
+            if material.color != color {
+                material.color = color;
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use bevy::{math::ops, prelude::*, utils::HashMap};
+use prelude_kit::{apply_velocity, Velocity};
+
+use crate::{wrap, Player, BOUNDS};
+
+// うろつく向きを揺らす速さ (大きいほど細かく向きを変える)
+const WANDER_FREQUENCY: f32 = 1.3;
+// これより近いエージェント同士は押し合う (空間グリッドの 1 マスの大きさにも使う)
+const SEPARATION_RADIUS: f32 = 70.0;
+// 押し合う強さ (重なりきったときに 1 秒あたりに変える速度の大きさ)
+const SEPARATION_STRENGTH: f32 = 400.0;
+
+/// 操舵行動 (ステアリング) で敵を動かすプラグイン
+///
+/// [`SteeringAgent`] に重み付きの行動を組み合わせて持たせると、専用のシステムを書かなくても動き方を変えられる。
+/// 向きは動きと分けて、これまで通り `SnapToPlayer` / `RotateToPlayer` がプレイヤーへ向ける。
+pub struct SteeringPlugin;
+
+impl Plugin for SteeringPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                // 操舵行動で速度を決めたあと、近すぎるエージェント同士を離す
+                (steer_agents, separate_agents)
+                    .chain()
+                    .before(apply_velocity),
+                keep_agents_in_bounds
+                    .after(apply_velocity)
+                    .run_if(not(wrap::wrapping)),
+            ),
+        );
+    }
+}
+
+/// 操舵行動の種類 (目標はプレイヤー)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Behavior {
+    /// 目標へまっすぐ向かう
+    Seek,
+    /// 目標が `panic_radius` より近いときだけ、目標から離れる
+    Flee { panic_radius: f32 },
+    /// 進む向きを `jitter` (ラジアン/秒) の幅で揺らしながら、あてもなく動く
+    Wander { jitter: f32 },
+    /// 目標へ向かい、`slowing_radius` より近づくと減速して止まる
+    Arrive { slowing_radius: f32 },
+}
+
+/// 重み付きの操舵行動を組み合わせて、[`Velocity`] を少しずつ変えるコンポーネント
+///
+/// 行動ごとの「望む速度」と今の速度の差を重みで足し合わせ、`max_force` までの加速で近づける。
+#[derive(Component, Debug, Clone)]
+#[require(Velocity)]
+pub struct SteeringAgent {
+    pub max_speed: f32,                  // 出せる最大の速さ (メートル/秒)
+    pub max_force: f32,                  // 1 秒あたりに変えられる速度の大きさ
+    pub behaviors: Vec<(Behavior, f32)>, // 操舵行動と重み
+    wander_angle: f32,                   // うろつくときに今向かっている向き (ラジアン)
+}
+
+impl SteeringAgent {
+    /// 行動を持たない (止まっている) エージェントを作る
+    pub fn new(max_speed: f32, max_force: f32) -> SteeringAgent {
+        SteeringAgent {
+            max_speed,
+            max_force,
+            behaviors: Vec::new(),
+            wander_angle: 0.0,
+        }
+    }
+
+    /// `weight` の重みで行動を加える
+    pub fn with(mut self, behavior: Behavior, weight: f32) -> SteeringAgent {
+        self.behaviors.push((behavior, weight));
+        self
+    }
+
+    /// うろつき始める向きを決める (同じ波の敵が同じ向きにうろつかないように)
+    pub fn with_wander_angle(mut self, angle: f32) -> SteeringAgent {
+        self.wander_angle = angle;
+        self
+    }
+
+    /// `behavior` の望む速度 (`to_target` は自分から目標への向きと距離)
+    fn desired_velocity(&self, behavior: Behavior, to_target: Vec2) -> Vec2 {
+        let distance = to_target.length();
+        let toward = to_target.normalize_or_zero();
+        match behavior {
+            Behavior::Seek => toward * self.max_speed,
+            Behavior::Flee { panic_radius } if distance < panic_radius => -toward * self.max_speed,
+            Behavior::Flee { .. } => Vec2::ZERO,
+            Behavior::Wander { .. } => Vec2::from_angle(self.wander_angle) * self.max_speed,
+            Behavior::Arrive { slowing_radius } => {
+                toward * self.max_speed * (distance / slowing_radius).min(1.0)
+            }
+        }
+    }
+}
+
+/// すべてのエージェントの速度を、プレイヤーを目標にした操舵行動で更新する
+///
+/// 移動は [`Velocity`] に任せるので、`apply_velocity` の前に実行する。
+fn steer_agents(
+    time: Res<Time>,
+    player: Single<&Transform, With<Player>>,
+    mut agents: Query<(Entity, &Transform, &mut SteeringAgent, &mut Velocity), Without<Player>>,
+) {
+    let target = player.translation.xy();
+    let delta = time.delta_secs();
+    for (entity, transform, mut agent, mut velocity) in &mut agents {
+        // うろつく向きは、エージェントごとに位相をずらしたサイン波で揺らす (乱数を使わずにばらつかせる)
+        let phase = time.elapsed_secs() * WANDER_FREQUENCY + entity.index() as f32;
+        let jitter: f32 = agent
+            .behaviors
+            .iter()
+            .filter_map(|(behavior, _)| match behavior {
+                Behavior::Wander { jitter } => Some(*jitter),
+                _ => None,
+            })
+            .sum();
+        agent.wander_angle += ops::sin(phase) * jitter * delta;
+
+        let to_target = target - transform.translation.xy();
+        let steering: Vec2 = agent
+            .behaviors
+            .iter()
+            .map(|&(behavior, weight)| {
+                (agent.desired_velocity(behavior, to_target) - velocity.0) * weight
+            })
+            .sum();
+        let steering = steering.clamp_length_max(agent.max_force);
+        velocity.0 = (velocity.0 + steering * delta).clamp_length_max(agent.max_speed);
+    }
+}
+
+/// 近すぎるエージェント同士を押し離して、プレイヤーを追う敵が 1 つの塊に重ならないようにする
+///
+/// 全部の組を調べると数の 2 乗になるので、[`SEPARATION_RADIUS`] の大きさのマス (空間グリッド) に分けて、
+/// 自分のマスと周りの 8 マスにいるエージェントだけを調べる。グリッドは `Local` に残して、確保した領域を使い回す。
+fn separate_agents(
+    time: Res<Time>,
+    mut grid: Local<HashMap<IVec2, Vec<(Entity, Vec2)>>>,
+    mut agents: Query<(Entity, &Transform, &SteeringAgent, &mut Velocity)>,
+) {
+    for cell in grid.values_mut() {
+        cell.clear();
+    }
+    for (entity, transform, ..) in &agents {
+        let position = transform.translation.xy();
+        grid.entry(grid_cell(position))
+            .or_default()
+            .push((entity, position));
+    }
+
+    let delta = time.delta_secs();
+    for (entity, transform, agent, mut velocity) in &mut agents {
+        let position = transform.translation.xy();
+        let cell = grid_cell(position);
+        let mut push = Vec2::ZERO;
+        for y in -1..=1 {
+            for x in -1..=1 {
+                let Some(neighbors) = grid.get(&(cell + IVec2::new(x, y))) else {
+                    continue;
+                };
+                for &(other, other_position) in neighbors {
+                    let away = position - other_position;
+                    let distance = away.length();
+                    if other == entity || distance >= SEPARATION_RADIUS {
+                        continue;
+                    }
+                    // 同じ位置に重なっているときは、エンティティごとに決まった向きへ離す
+                    let direction = away
+                        .try_normalize()
+                        .unwrap_or_else(|| Vec2::from_angle(entity.index() as f32));
+                    // 近いほど強く押す
+                    push += direction * (1.0 - distance / SEPARATION_RADIUS);
+                }
+            }
+        }
+        if push != Vec2::ZERO {
+            velocity.0 =
+                (velocity.0 + push * SEPARATION_STRENGTH * delta).clamp_length_max(agent.max_speed);
+        }
+    }
+}
+
+/// 位置が入る空間グリッドのマス
+fn grid_cell(position: Vec2) -> IVec2 {
+    (position / SEPARATION_RADIUS).floor().as_ivec2()
+}
+
+/// 境界で止めるモードでは、エージェントを画面の境界内に収め、外へ向かう速度をなくす
+pub fn keep_agents_in_bounds(
+    mut agents: Query<(&mut Transform, &mut Velocity), With<SteeringAgent>>,
+) {
+    let extents = BOUNDS / 2.0;
+    for (mut transform, mut velocity) in &mut agents {
+        let position = transform.translation.xy();
+        let clamped = position.clamp(-extents, extents);
+        if clamped == position {
+            continue;
+        }
+        transform.translation = clamped.extend(transform.translation.z);
+        if clamped.x != position.x {
+            velocity.x = 0.0;
+        }
+        if clamped.y != position.y {
+            velocity.y = 0.0;
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use std::time::Instant;
+
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin,
+        RegisterDiagnostic,
+    },
+    math::ops,
+    prelude::*,
+};
+
+use crate::{Player, PlayerInput, RotateToPlayer, BOUNDS};
+
+// 負荷試験モードを有効にする起動引数 (`--stress=10000` のように敵の数も指定できる)
+const STRESS_ARG: &str = "--stress";
+// 敵の数を指定しなかったときの数
+const DEFAULT_ENEMY_COUNT: usize = 5000;
+// 敵のスプライトの縮小率 (大量に並べても重なりすぎないようにする)
+const ENEMY_SCALE: f32 = 0.25;
+// 最適化した回転システムで、プレイヤーへの向きを計算し直す間隔 (ステップ数)
+const CACHE_REFRESH_INTERVAL: u32 = 4;
+// 回転システムの処理時間の診断のパス
+const ROTATION_TIME: DiagnosticPath = DiagnosticPath::const_new("twod_rotation/rotation_time");
+
+// 回転システムを切り替えるキー
+const VARIANT_KEY: KeyCode = KeyCode::KeyV;
+// 操作の記録を開始・終了するキー
+const RECORD_KEY: KeyCode = KeyCode::KeyR;
+// 記録した操作の再生を開始・終了するキー
+const REPLAY_KEY: KeyCode = KeyCode::KeyP;
+
+/// 回転システムの負荷試験モードを追加するプラグイン
+///
+/// `--stress` を付けて起動すると、[`RotateToPlayer`] の敵を大量に配置し、
+/// [`RotationSystems`] の 1 ステップあたりの処理時間を診断 (`Diagnostics`) として計る。
+/// `V` キーで素直な実装と最適化した実装を切り替えて処理時間を比べられる。
+/// 同じ条件で比べられるように、プレイヤーの操作を記録 (`R` キー) して繰り返し再生 (`P` キー) できる。
+pub struct StressTestPlugin;
+
+impl Plugin for StressTestPlugin {
+    fn build(&self, app: &mut App) {
+        // 回転システムの切り替えは通常の起動でも使えるようにしておく
+        app.init_resource::<RotationVariant>().add_systems(
+            FixedUpdate,
+            rotate_to_player_cached
+                .in_set(RotationSystems)
+                .run_if(not(naive_rotation)),
+        );
+
+        let Some(enemy_count) = enemy_count_from_args() else {
+            return;
+        };
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.register_diagnostic(Diagnostic::new(ROTATION_TIME).with_suffix("ms"))
+            .insert_resource(StressTest { enemy_count })
+            .init_resource::<InputRecorder>()
+            .add_systems(Startup, (spawn_enemies, spawn_hud))
+            .add_systems(
+                FixedUpdate,
+                (
+                    record_or_replay_input
+                        .after(crate::read_player_input)
+                        .before(crate::player_movement_system),
+                    start_measurement.before(RotationSystems),
+                    finish_measurement.after(RotationSystems),
+                ),
+            )
+            .add_systems(
+                Update,
+                (switch_variant, toggle_recording, toggle_replay, update_hud).chain(),
+            );
+    }
+}
+
+/// 敵をプレイヤーに向けるシステムのセット (処理時間はこのセット全体で計る)
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RotationSystems;
+
+/// [`RotateToPlayer`] の敵を回転させる実装
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RotationVariant {
+    #[default]
+    Naive, // 毎ステップすべての敵でプレイヤーへの向きを正規化して求める (`rotate_to_player_system`)
+    Cached, // プレイヤーへの向きをキャッシュし、数ステップに分けて計算し直す (`rotate_to_player_cached`)
+}
+
+/// 素直な実装で回転させるかどうかを判定する条件関数
+pub fn naive_rotation(variant: Res<RotationVariant>) -> bool {
+    *variant == RotationVariant::Naive
+}
+
+/// 負荷試験モード (`--stress`) で起動したかどうかを判定する条件関数
+pub fn stress_mode(stress: Option<Res<StressTest>>) -> bool {
+    stress.is_some()
+}
+
+/// 最適化した実装が使う、正規化済みのプレイヤーへの向き
+#[derive(Component, Debug, Default)]
+pub struct ToPlayerCache(Option<Vec2>);
+
+/// 負荷試験の設定
+#[derive(Resource)]
+pub struct StressTest {
+    enemy_count: usize,
+}
+
+/// 回転システムの処理を始めた時刻
+#[derive(Resource)]
+struct MeasurementStart(Instant);
+
+/// プレイヤーの操作の記録
+#[derive(Resource, Default)]
+enum InputRecorder {
+    #[default]
+    Idle, // 記録も再生もしていない
+    Recording {
+        start: Transform,         // 記録を始めたときのプレイヤーの位置と向き
+        inputs: Vec<PlayerInput>, // ステップごとの操作
+    },
+    Stopped {
+        start: Transform,
+        inputs: Vec<PlayerInput>,
+    },
+    Replaying {
+        start: Transform,
+        inputs: Vec<PlayerInput>,
+        cursor: usize, // 次に再生するステップ
+    },
+}
+
+impl InputRecorder {
+    /// 状態を表示する文字列
+    fn label(&self) -> String {
+        match self {
+            InputRecorder::Idle => "idle".to_string(),
+            InputRecorder::Recording { inputs, .. } => {
+                format!("recording ({} ticks)", inputs.len())
+            }
+            InputRecorder::Stopped { inputs, .. } => format!("recorded {} ticks", inputs.len()),
+            InputRecorder::Replaying { inputs, cursor, .. } => {
+                format!("replaying {cursor}/{}", inputs.len())
+            }
+        }
+    }
+}
+
+/// 負荷試験の情報を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct StressHud;
+
+/// 起動引数から敵の数を読み取る (`--stress` がなければ `None`)
+fn enemy_count_from_args() -> Option<usize> {
+    std::env::args().find_map(|arg| {
+        let rest = arg.strip_prefix(STRESS_ARG)?;
+        match rest.strip_prefix('=') {
+            Some(count) => count.parse().ok(),
+            None if rest.is_empty() => Some(DEFAULT_ENEMY_COUNT),
+            None => None,
+        }
+    })
+}
+
+/// 画面全体に敵を格子状に並べる
+///
+/// 回転の速さは敵ごとに 45〜135 度/秒の間で変える。
+fn spawn_enemies(mut commands: Commands, asset_server: Res<AssetServer>, stress: Res<StressTest>) {
+    let enemy_handle = asset_server.load("textures/simplespace/enemy_B.png");
+
+    // 画面の縦横比に合わせて列数と行数を決める
+    let columns = ((stress.enemy_count as f32 * BOUNDS.x / BOUNDS.y)
+        .sqrt()
+        .ceil() as usize)
+        .max(1);
+    let rows = stress.enemy_count.div_ceil(columns);
+    let spacing = BOUNDS / Vec2::new(columns as f32, rows as f32);
+
+    for index in 0..stress.enemy_count {
+        let (column, row) = (index % columns, index / columns);
+        let position = -BOUNDS / 2.0 + spacing * Vec2::new(column as f32 + 0.5, row as f32 + 0.5);
+        let speed_degrees = 45.0 + (index % 10) as f32 * 10.0;
+        commands.spawn((
+            Sprite::from_image(enemy_handle.clone()),
+            Transform::from_translation(position.extend(-1.0)).with_scale(Vec3::splat(ENEMY_SCALE)),
+            RotateToPlayer {
+                rotation_speed: f32::to_radians(speed_degrees),
+            },
+        ));
+    }
+    info!("spawned {} enemies for the stress test", stress.enemy_count);
+}
+
+/// 画面左上に負荷試験の情報を表示する
+fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(5.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        StressHud,
+    ));
+}
+
+/// 最適化した実装で敵を徐々にプレイヤーに向ける
+///
+/// 正規化したプレイヤーへの向きを [`ToPlayerCache`] に保存し、毎ステップ全体の
+/// 1/[`CACHE_REFRESH_INTERVAL`] の敵だけ計算し直す。残りの敵は前回の向きで回転させるので、
+/// 計算し直すまでのわずかな間だけ向きが古くなる。
+fn rotate_to_player_cached(
+    time: Res<Time>,
+    mut query: Query<
+        (Entity, &RotateToPlayer, &mut Transform, &mut ToPlayerCache),
+        Without<Player>,
+    >,
+    player_transform: Single<&Transform, With<Player>>,
+    mut tick: Local<u32>,
+) {
+    let player_translation = player_transform.translation.xy();
+    let chunk = *tick % CACHE_REFRESH_INTERVAL;
+    *tick = tick.wrapping_add(1);
+
+    for (entity, config, mut enemy_transform, mut cache) in &mut query {
+        // 担当のステップか、まだ計算していない敵だけ向きを求める
+        if cache.0.is_none() || entity.index() % CACHE_REFRESH_INTERVAL == chunk {
+            cache.0 = (player_translation - enemy_transform.translation.xy()).try_normalize();
+        }
+        let Some(to_player) = cache.0 else {
+            continue;
+        };
+
+        let enemy_forward = (enemy_transform.rotation * Vec3::Y).xy();
+        let forward_dot_player = enemy_forward.dot(to_player);
+        if (forward_dot_player - 1.0).abs() < f32::EPSILON {
+            continue;
+        }
+
+        let enemy_right = (enemy_transform.rotation * Vec3::X).xy();
+        let rotation_sign = -f32::copysign(1.0, enemy_right.dot(to_player));
+        let max_angle = ops::acos(forward_dot_player.clamp(-1.0, 1.0));
+        let rotation_angle =
+            rotation_sign * (config.rotation_speed * time.delta_secs()).min(max_angle);
+
+        enemy_transform.rotate_z(rotation_angle);
+    }
+}
+
+/// 回転システムを始める前の時刻を記録する
+fn start_measurement(mut commands: Commands) {
+    commands.insert_resource(MeasurementStart(Instant::now()));
+}
+
+/// 回転システムが終わるまでの時間を診断に加える
+///
+/// 並列に実行されるほかのシステムの時間も含まれうるが、実装どうしの比較には十分な精度になる。
+fn finish_measurement(start: Option<Res<MeasurementStart>>, mut diagnostics: Diagnostics) {
+    let Some(start) = start else {
+        return;
+    };
+    diagnostics.add_measurement(&ROTATION_TIME, || start.0.elapsed().as_secs_f64() * 1000.0);
+}
+
+/// `V` キーで回転システムの実装を切り替える
+fn switch_variant(keyboard_input: Res<ButtonInput<KeyCode>>, mut variant: ResMut<RotationVariant>) {
+    if keyboard_input.just_pressed(VARIANT_KEY) {
+        *variant = match *variant {
+            RotationVariant::Naive => RotationVariant::Cached,
+            RotationVariant::Cached => RotationVariant::Naive,
+        };
+        info!("rotation variant: {:?}", *variant);
+    }
+}
+
+/// `R` キーで操作の記録を始め、もう一度押すと止める
+fn toggle_recording(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut recorder: ResMut<InputRecorder>,
+    player: Single<&Transform, With<Player>>,
+) {
+    if !keyboard_input.just_pressed(RECORD_KEY) {
+        return;
+    }
+    *recorder = match std::mem::take(&mut *recorder) {
+        InputRecorder::Recording { start, inputs } => InputRecorder::Stopped { start, inputs },
+        _ => InputRecorder::Recording {
+            start: **player,
+            inputs: Vec::new(),
+        },
+    };
+}
+
+/// `P` キーで記録した操作を繰り返し再生し、もう一度押すと止める
+fn toggle_replay(keyboard_input: Res<ButtonInput<KeyCode>>, mut recorder: ResMut<InputRecorder>) {
+    if !keyboard_input.just_pressed(REPLAY_KEY) {
+        return;
+    }
+    *recorder = match std::mem::take(&mut *recorder) {
+        InputRecorder::Stopped { start, inputs } | InputRecorder::Recording { start, inputs }
+            if !inputs.is_empty() =>
+        {
+            InputRecorder::Replaying {
+                start,
+                inputs,
+                cursor: 0,
+            }
+        }
+        InputRecorder::Replaying { start, inputs, .. } => InputRecorder::Stopped { start, inputs },
+        other => other,
+    };
+}
+
+/// 記録中はキー入力から決めた操作を保存し、再生中は記録した操作で上書きする
+///
+/// 再生は記録を始めたときの位置と向きから繰り返すので、毎回同じ経路をたどる。
+fn record_or_replay_input(
+    mut recorder: ResMut<InputRecorder>,
+    mut input: ResMut<PlayerInput>,
+    mut player: Single<&mut Transform, With<Player>>,
+) {
+    match &mut *recorder {
+        InputRecorder::Recording { inputs, .. } => inputs.push(*input),
+        InputRecorder::Replaying {
+            start,
+            inputs,
+            cursor,
+        } => {
+            if *cursor == 0 {
+                **player = *start;
+            }
+            *input = inputs[*cursor];
+            *cursor = (*cursor + 1) % inputs.len();
+        }
+        InputRecorder::Idle | InputRecorder::Stopped { .. } => {}
+    }
+}
+
+/// 敵の数・実装・処理時間・FPS・記録の状態を表示する
+fn update_hud(
+    stress: Res<StressTest>,
+    variant: Res<RotationVariant>,
+    recorder: Res<InputRecorder>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut hud: Single<&mut Text, With<StressHud>>,
+) {
+    let rotation_ms = diagnostics
+        .get(&ROTATION_TIME)
+        .and_then(|diagnostic| diagnostic.average())
+        .unwrap_or(0.0);
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+    hud.0 = format!(
+        "enemies {}\n\
+         variant {:?} (V to switch)\n\
+         rotation {rotation_ms:.3} ms/tick\n\
+         FPS {fps:.0}\n\
+         input {} (R: record, P: replay)",
+        stress.enemy_count,
+        *variant,
+        recorder.label(),
+    );
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use crate::{wrap::ScreenEdges, Player, BOUNDS};
+
+// 境界からこの距離より近づくと、その側の画面の端を暗くし始める
+const WARNING_DISTANCE: f32 = 300.0;
+// 画面の端から暗くする幅 (ピクセル) と、重ねて濃淡を付ける帯の数
+const VIGNETTE_WIDTH: f32 = 120.0;
+const VIGNETTE_BANDS: usize = 4;
+// 境界に触れているときの、画面の端での暗さ (不透明度) と色
+const MAX_ALPHA: f32 = 0.45;
+const VIGNETTE_COLOR: Color = Color::srgb(0.25, 0.05, 0.1);
+
+/// 宇宙船が遊ぶ範囲の境界に近づくと、近い側の画面の端を暗くするプラグイン
+///
+/// 境界で止めるモードでは、見えない壁に急に止められたように感じないよう、近づくにつれて少しずつ暗くする。
+/// 回り込むモードでは境界で止まらないので暗くしない。
+pub struct EdgeVignettePlugin;
+
+impl Plugin for EdgeVignettePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_vignette)
+            .add_systems(Update, update_vignette);
+    }
+}
+
+/// 画面の端を暗くする帯を示すコンポーネント
+///
+/// 同じ側の帯は画面の端から始まって幅だけが違うので、重なるほど端に近い部分が濃くなる。
+#[derive(Component)]
+struct VignetteBand {
+    side: Vec2, // 暗くする側 (右なら `Vec2::X`、下なら `Vec2::NEG_Y`)
+}
+
+/// 画面の上下左右の端に、幅の違う帯を重ねて配置する (最初はすべて透明)
+fn spawn_vignette(mut commands: Commands) {
+    for side in [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y] {
+        for band in 1..=VIGNETTE_BANDS {
+            let width = Val::Px(VIGNETTE_WIDTH * band as f32 / VIGNETTE_BANDS as f32);
+            let mut node = Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            };
+            // UI は下向きが `+Y` なので、ワールドの上 (`+Y`) は画面の上端になる
+            if side.x != 0.0 {
+                node.width = width;
+                node.height = Val::Percent(100.0);
+                node.top = Val::Px(0.0);
+                if side.x > 0.0 {
+                    node.right = Val::Px(0.0);
+                } else {
+                    node.left = Val::Px(0.0);
+                }
+            } else {
+                node.width = Val::Percent(100.0);
+                node.height = width;
+                node.left = Val::Px(0.0);
+                if side.y > 0.0 {
+                    node.top = Val::Px(0.0);
+                } else {
+                    node.bottom = Val::Px(0.0);
+                }
+            }
+            commands.spawn((
+                node,
+                BackgroundColor(Color::NONE),
+                // スコアボードやミニマップより奥に描く
+                ZIndex(-1),
+                VignetteBand { side },
+            ));
+        }
+    }
+}
+
+/// 宇宙船と各側の境界との距離に合わせて、その側の帯の濃さを変える
+fn update_vignette(
+    edges: Res<ScreenEdges>,
+    player: Single<&Transform, With<Player>>,
+    mut bands: Query<(&VignetteBand, &mut BackgroundColor)>,
+) {
+    let extents = BOUNDS / 2.0;
+    let position = player.translation.xy();
+    for (band, mut color) in &mut bands {
+        let proximity = if *edges == ScreenEdges::Clamp {
+            // その側の境界までの距離 (境界に触れていれば 0.0)
+            let distance = (extents - position * band.side).dot(band.side.abs());
+            1.0 - (distance / WARNING_DISTANCE).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let alpha = MAX_ALPHA / VIGNETTE_BANDS as f32 * proximity;
+        color.set_if_neq(BackgroundColor(VIGNETTE_COLOR.with_alpha(alpha)));
+    }
+}
@@ -0,0 +1,272 @@
+use announcer::{Announce, VoiceLine};
+use bevy::prelude::*;
+
+use crate::{
+    asteroids::{self, AsteroidAssets, AsteroidSize},
+    steering::{Behavior, SteeringAgent},
+    stress, Enemy, RotateToPlayer, SnapToPlayer, BOUNDS,
+};
+
+// 次の波を出すまでの時間 (秒)
+const WAVE_INTERVAL: f32 = 8.0;
+// 最初の波の敵の数
+const FIRST_WAVE_SIZE: usize = 4;
+// 波ごとに増やす敵の数
+const WAVE_GROWTH: usize = 2;
+// 1 つの波の敵の数の上限
+const MAX_WAVE_SIZE: usize = 24;
+// 徐々に向く敵の回転速度 (最初の波と、波ごとに上げる量、上限。度/秒)
+const BASE_ROTATION_DEGREES: f32 = 45.0;
+const ROTATION_GROWTH_DEGREES: f32 = 15.0;
+const MAX_ROTATION_DEGREES: f32 = 180.0;
+// 敵の動く速さ (最初の波と、波ごとに上げる量、上限。メートル/秒)
+const BASE_ENEMY_SPEED: f32 = 40.0;
+const ENEMY_SPEED_GROWTH: f32 = 5.0;
+const MAX_ENEMY_SPEED: f32 = 100.0;
+// 敵が 1 秒あたりに変えられる速度の大きさ
+const ENEMY_STEERING_FORCE: f32 = 120.0;
+// 最初の波の大きな小惑星の数と、上限 (波ごとに 1 つずつ増やす)
+const FIRST_ASTEROID_COUNT: usize = 2;
+const MAX_ASTEROID_COUNT: usize = 8;
+// 小惑星が画面の中心へ向かう向きからずれる角度の幅 (ラジアン)
+const ASTEROID_AIM_SPREAD: f32 = 1.0;
+// 敵を出す位置の、画面の端からの距離
+const EDGE_MARGIN: f32 = 40.0;
+// 波が来たことを知らせるボイスの優先度・再生時間 (秒)・クールダウン (秒)
+const WAVE_VOICE_PRIORITY: u8 = 1;
+const WAVE_VOICE_DURATION: f32 = 1.0;
+const WAVE_VOICE_COOLDOWN: f32 = 2.0;
+
+/// 画面の端から、だんだん大きくなる敵と小惑星の波を出すプラグイン
+///
+/// 次の波は一定の間隔で来るが、敵と小惑星をすべて倒すとすぐに来る。
+/// 波が来るたびに、`audio/announcer` の [`Announce`] でボイスと字幕を流す。
+///
+/// 負荷試験モード (`--stress`) では、敵の数を揃えて比べられるように波を出さない。
+pub struct WavesPlugin;
+
+impl Plugin for WavesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EnemySpawner>()
+            .add_systems(Startup, spawn_wave_counter.run_if(not(stress::stress_mode)))
+            .add_systems(FixedUpdate, spawn_waves.run_if(not(stress::stress_mode)))
+            .add_systems(Update, update_wave_counter.run_if(not(stress::stress_mode)));
+    }
+}
+
+/// 敵の波を出すタイマーと、これまでに出した波の数
+#[derive(Resource)]
+pub struct EnemySpawner {
+    timer: Timer,                    // 次の波までの時間
+    wave: u32,                       // これまでに出した波の数 (0 ならまだ出していない)
+    snap_image: Handle<Image>,       // プレイヤーへ即座に向く敵の画像
+    rotate_image: Handle<Image>,     // プレイヤーへ徐々に向く敵の画像
+    wave_voice: Handle<AudioSource>, // 波が来たことを知らせるボイス
+}
+
+impl FromWorld for EnemySpawner {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        EnemySpawner {
+            timer: Timer::from_seconds(WAVE_INTERVAL, TimerMode::Repeating),
+            wave: 0,
+            snap_image: asset_server.load("textures/simplespace/enemy_A.png"),
+            rotate_image: asset_server.load("textures/simplespace/enemy_B.png"),
+            wave_voice: asset_server.load("voices/wave_incoming.wav"),
+        }
+    }
+}
+
+impl EnemySpawner {
+    /// `wave` 番目 (1 から数える) の波の敵の数
+    fn wave_size(wave: u32) -> usize {
+        (FIRST_WAVE_SIZE + (wave as usize - 1) * WAVE_GROWTH).min(MAX_WAVE_SIZE)
+    }
+
+    /// `wave` 番目の波の大きな小惑星の数
+    fn asteroid_count(wave: u32) -> usize {
+        (FIRST_ASTEROID_COUNT + wave as usize - 1).min(MAX_ASTEROID_COUNT)
+    }
+
+    /// `wave` 番目の波の `index` 番目の敵の動き方 (3 種類を順に割り当てる)
+    fn steering(index: usize, wave: u32) -> SteeringAgent {
+        let speed =
+            (BASE_ENEMY_SPEED + (wave - 1) as f32 * ENEMY_SPEED_GROWTH).min(MAX_ENEMY_SPEED);
+        // うろつき始める向きを、黄金角 (約 2.4 ラジアン) ずつずらす
+        let agent =
+            SteeringAgent::new(speed, ENEMY_STEERING_FORCE).with_wander_angle(index as f32 * 2.4);
+        match index % 3 {
+            // ふらつきながら追いかけてくる
+            0 => agent
+                .with(Behavior::Seek, 1.0)
+                .with(Behavior::Wander { jitter: 2.0 }, 0.5),
+            // 近くまで来て、少し離れたところで止まる
+            1 => agent
+                .with(
+                    Behavior::Arrive {
+                        slowing_radius: 250.0,
+                    },
+                    1.0,
+                )
+                .with(
+                    Behavior::Flee {
+                        panic_radius: 150.0,
+                    },
+                    1.5,
+                ),
+            // うろつき、近づかれたら逃げる
+            _ => agent.with(Behavior::Wander { jitter: 3.0 }, 1.0).with(
+                Behavior::Flee {
+                    panic_radius: 200.0,
+                },
+                2.0,
+            ),
+        }
+    }
+
+    /// これまでに出した波の数 (0 ならまだ出していない)
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// 最初の波からやり直す (次の `FixedUpdate` ですぐに最初の波を出す)
+    pub fn restart(&mut self) {
+        self.wave = 0;
+        self.timer.reset();
+    }
+
+    /// `wave` 番目の波の、徐々に向く敵の回転速度 (ラジアン/秒)
+    fn rotation_speed(wave: u32) -> f32 {
+        let degrees = BASE_ROTATION_DEGREES + (wave - 1) as f32 * ROTATION_GROWTH_DEGREES;
+        degrees.min(MAX_ROTATION_DEGREES).to_radians()
+    }
+}
+
+/// 残っている敵の数を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct WaveCounter;
+
+/// 起動してすぐに最初の波を出し、その後は [`WAVE_INTERVAL`] ごとか、敵と小惑星をすべて倒したときに次の波を出す
+///
+/// 波の敵は画面の端に沿って等間隔に並べ、即座に向く敵と徐々に向く敵を交互に出す。
+/// 動き方は向き方とは別に、[`SteeringAgent`] の操舵行動で 3 種類を順に割り当てる。
+/// 並べ始める位置は波ごとにずらして、毎回違うところから敵が来るようにする。
+/// 大きな小惑星は敵の間の端から、画面の中心のあたりへ向けて漂わせる。
+fn spawn_waves(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spawner: ResMut<EnemySpawner>,
+    asteroid_assets: Res<AsteroidAssets>,
+    enemies: Query<(), With<Enemy>>,
+    mut announce: EventWriter<Announce>,
+) {
+    spawner.timer.tick(time.delta());
+    if spawner.wave > 0 && !spawner.timer.just_finished() && !enemies.is_empty() {
+        return;
+    }
+    // 倒しきって早めに出すときも、次の波までの間隔を数え直す
+    spawner.timer.reset();
+    spawner.wave += 1;
+    let wave = spawner.wave;
+    let size = EnemySpawner::wave_size(wave);
+    // 黄金比の端数ずつずらすと、波を重ねても同じ位置に偏らない
+    let offset = (wave as f32 * 0.618_034).fract();
+
+    for index in 0..size {
+        let position = edge_position((offset + index as f32 / size as f32).fract());
+        let transform = Transform::from_translation(position.extend(0.0));
+        if (index + wave as usize).is_multiple_of(2) {
+            commands.spawn((
+                Sprite::from_image(spawner.snap_image.clone()),
+                transform,
+                SnapToPlayer,
+                EnemySpawner::steering(index, wave),
+            ));
+        } else {
+            commands.spawn((
+                Sprite::from_image(spawner.rotate_image.clone()),
+                transform,
+                RotateToPlayer {
+                    rotation_speed: EnemySpawner::rotation_speed(wave),
+                },
+                EnemySpawner::steering(index, wave),
+            ));
+        }
+    }
+
+    let asteroid_count = EnemySpawner::asteroid_count(wave);
+    for index in 0..asteroid_count {
+        // 敵と重ならないように、敵の並びの半分だけずらした位置から出す
+        let t = (offset + (index as f32 + 0.5) / asteroid_count as f32).fract();
+        let position = edge_position(t);
+        let aim = ((index as f32 * 0.618_034).fract() - 0.5) * ASTEROID_AIM_SPREAD;
+        let direction = Vec2::from_angle(aim).rotate(-position.normalize());
+        asteroids::spawn_asteroid(
+            &mut commands,
+            &asteroid_assets,
+            AsteroidSize::Large,
+            position,
+            direction * AsteroidSize::Large.speed(),
+        );
+    }
+    info!("wave {wave}: spawned {size} enemies and {asteroid_count} asteroids");
+    announce.send(Announce(VoiceLine {
+        id: "wave_incoming",
+        subtitle: format!("Wave {wave} incoming!"),
+        sound: Some(spawner.wave_voice.clone()),
+        priority: WAVE_VOICE_PRIORITY,
+        duration: WAVE_VOICE_DURATION,
+        cooldown: WAVE_VOICE_COOLDOWN,
+    }));
+}
+
+/// 画面の端を一周する位置 (`t` は 0.0 から 1.0 で、左上から時計回り)
+fn edge_position(t: f32) -> Vec2 {
+    let half = BOUNDS / 2.0 - Vec2::splat(EDGE_MARGIN);
+    let size = half * 2.0;
+    let perimeter = 2.0 * (size.x + size.y);
+    let mut distance = t * perimeter;
+    // 上の辺、右の辺、下の辺、左の辺の順にたどる
+    if distance < size.x {
+        return Vec2::new(-half.x + distance, half.y);
+    }
+    distance -= size.x;
+    if distance < size.y {
+        return Vec2::new(half.x, half.y - distance);
+    }
+    distance -= size.y;
+    if distance < size.x {
+        return Vec2::new(half.x - distance, -half.y);
+    }
+    distance -= size.x;
+    Vec2::new(-half.x, -half.y + distance)
+}
+
+/// 画面右上に残っている敵の数を表示するテキストを配置する
+fn spawn_wave_counter(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            right: Val::Px(10.0),
+            ..default()
+        },
+        WaveCounter,
+    ));
+}
+
+/// 画面に残っている敵の数を表示する (波の数は `game.rs` のスコアボードに表示する)
+fn update_wave_counter(
+    enemies: Query<(), With<Enemy>>,
+    mut counter: Single<&mut Text, With<WaveCounter>>,
+) {
+    let text = format!("Enemies: {}", enemies.iter().count());
+    if counter.0 != text {
+        counter.0 = text;
+    }
+}
@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+
+use crate::{Bullet, BOUNDS};
+
+// 画面の端で回り込むモードで起動する起動引数
+const WRAP_ARG: &str = "--wrap";
+// 画面の端の扱いを切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::KeyW;
+// 回り込むモードで、弾が消えるまでの時間 (秒)
+const BULLET_LIFETIME: f32 = 1.2;
+// 端をまたいでいるかの判定に使う、見た目の半径 (拡大縮小する前)
+const SHIP_RADIUS: f32 = 50.0;
+const BULLET_RADIUS: f32 = 8.0;
+const ENEMY_RADIUS: f32 = 40.0;
+
+/// 画面の端を、境界で止めるか反対側へ回り込ませるかを切り替えるプラグイン
+///
+/// 回り込むモードでは、宇宙船・弾・敵がアステロイドのように反対の端から現れる。
+/// 端をまたいでいるスプライトやメッシュは、反対側にも [`Ghost`] を描いて切れ目なく見せる。
+pub struct WrapPlugin;
+
+impl Plugin for WrapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ScreenEdges::from_args())
+            .add_systems(Update, toggle_screen_edges)
+            // 移動が終わった位置に合わせて、位置の伝播の前にゴーストを動かす
+            .add_systems(
+                PostUpdate,
+                update_ghosts.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// 画面の端の扱い
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenEdges {
+    /// 境界で止める (弾は境界の外に出たら消える)
+    #[default]
+    Clamp,
+    /// 反対の端へ回り込む (弾は [`BULLET_LIFETIME`] で消える)
+    Wrap,
+}
+
+impl ScreenEdges {
+    /// 起動引数に `--wrap` があれば回り込むモードにする
+    fn from_args() -> ScreenEdges {
+        if std::env::args().any(|arg| arg == WRAP_ARG) {
+            ScreenEdges::Wrap
+        } else {
+            ScreenEdges::Clamp
+        }
+    }
+}
+
+/// 回り込むモードで、画面の端で反対側へ回り込むエンティティ
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Wraps {
+    pub radius: f32, // 見た目の半径 (拡大縮小する前)
+}
+
+/// 回り込むモードで、弾が消えるまでの時間
+#[derive(Component)]
+pub struct BulletLifetime(Timer);
+
+impl Default for BulletLifetime {
+    fn default() -> Self {
+        BulletLifetime(Timer::from_seconds(BULLET_LIFETIME, TimerMode::Once))
+    }
+}
+
+/// 端をまたいでいるスプライトやメッシュの、反対側に描く複製
+///
+/// 見た目だけの複製なので、当たり判定には使わない。
+#[derive(Component)]
+struct Ghost {
+    source: Entity, // 複製元のエンティティ
+    side: IVec2,    // 複製を置く向き (`BOUNDS` の何個分ずらすか)
+}
+
+/// 宇宙船の見た目の大きさ
+pub fn ship_wraps() -> Wraps {
+    Wraps {
+        radius: SHIP_RADIUS,
+    }
+}
+
+/// 弾の見た目の大きさ
+pub fn bullet_wraps() -> Wraps {
+    Wraps {
+        radius: BULLET_RADIUS,
+    }
+}
+
+/// 敵の見た目の大きさ
+pub fn enemy_wraps() -> Wraps {
+    Wraps {
+        radius: ENEMY_RADIUS,
+    }
+}
+
+/// 回り込むモードかどうかを判定する条件関数
+pub fn wrapping(edges: Res<ScreenEdges>) -> bool {
+    *edges == ScreenEdges::Wrap
+}
+
+/// `W` キーで、境界で止めるモードと回り込むモードを切り替える
+fn toggle_screen_edges(keyboard_input: Res<ButtonInput<KeyCode>>, mut edges: ResMut<ScreenEdges>) {
+    if !keyboard_input.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+    *edges = match *edges {
+        ScreenEdges::Clamp => ScreenEdges::Wrap,
+        ScreenEdges::Wrap => ScreenEdges::Clamp,
+    };
+    info!("screen edges: {:?}", *edges);
+}
+
+/// 中心が画面の端を越えたエンティティを、反対の端へ移す
+pub fn wrap_around_edges(mut query: Query<&mut Transform, With<Wraps>>) {
+    let extents = BOUNDS / 2.0;
+    for mut transform in &mut query {
+        let position = transform.translation.xy();
+        let wrapped = (position + extents).rem_euclid(BOUNDS) - extents;
+        // 動いていない敵まで変更扱いにしないように、越えたときだけ書き込む
+        if wrapped != position {
+            transform.translation = wrapped.extend(transform.translation.z);
+        }
+    }
+}
+
+/// 回り込むモードでは画面の外に出ない弾を、撃ってから一定時間で消す
+pub fn expire_bullets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bullets: Query<(Entity, &mut BulletLifetime), With<Bullet>>,
+) {
+    for (bullet, mut lifetime) in &mut bullets {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(bullet).despawn();
+        }
+    }
+}
+
+/// 端をまたいでいるエンティティのゴーストを追加・移動し、いらなくなったゴーストを消す
+///
+/// ゴーストはまたいでいる向きごとに 1 つ (角では縦・横・斜めの 3 つ) 置く。
+/// スプライトは色や向きが変わることがあるので毎フレーム写し、メッシュ (小惑星) は追加するときにハンドルを写す。
+#[allow(clippy::type_complexity)]
+fn update_ghosts(
+    mut commands: Commands,
+    edges: Res<ScreenEdges>,
+    sources: Query<
+        (
+            Entity,
+            &Transform,
+            &Wraps,
+            Option<&Sprite>,
+            Option<(&Mesh2d, &MeshMaterial2d<ColorMaterial>)>,
+        ),
+        Without<Ghost>,
+    >,
+    mut ghosts: Query<(Entity, &Ghost, &mut Transform, Option<&mut Sprite>)>,
+) {
+    let wrapping = *edges == ScreenEdges::Wrap;
+    let mut existing = Vec::new();
+    for (entity, ghost, mut transform, sprite) in &mut ghosts {
+        let source = sources
+            .get(ghost.source)
+            .ok()
+            .filter(|(_, source, wraps, ..)| {
+                wrapping && ghost_sides(source, wraps).any(|side| side == ghost.side)
+            });
+        let Some((_, source_transform, _, source_sprite, _)) = source else {
+            // 複製元が消えたか、端から離れたか、境界で止めるモードに戻した
+            commands.entity(entity).despawn();
+            continue;
+        };
+        *transform = ghost_transform(source_transform, ghost.side);
+        if let (Some(mut sprite), Some(source_sprite)) = (sprite, source_sprite) {
+            sprite.clone_from(source_sprite);
+        }
+        existing.push((ghost.source, ghost.side));
+    }
+
+    if !wrapping {
+        return;
+    }
+    for (source, transform, wraps, sprite, mesh) in &sources {
+        for side in ghost_sides(transform, wraps) {
+            if existing.contains(&(source, side)) {
+                continue;
+            }
+            let mut ghost =
+                commands.spawn((ghost_transform(transform, side), Ghost { source, side }));
+            if let Some(sprite) = sprite {
+                ghost.insert(sprite.clone());
+            }
+            if let Some((mesh, material)) = mesh {
+                ghost.insert((mesh.clone(), material.clone()));
+            }
+        }
+    }
+}
+
+/// 端をまたいでいるときに、ゴーストを置く向きを返す (またいでいなければ空)
+///
+/// 右の端をまたいでいるなら、はみ出した部分は左の端に現れるので `x` は -1 になる。
+fn ghost_sides(transform: &Transform, wraps: &Wraps) -> impl Iterator<Item = IVec2> {
+    let extents = BOUNDS / 2.0;
+    let radius = wraps.radius * transform.scale.x;
+    let position = transform.translation.xy();
+    let side = |position: f32, extent: f32| {
+        if position + radius > extent {
+            -1
+        } else if position - radius < -extent {
+            1
+        } else {
+            0
+        }
+    };
+    let x = side(position.x, extents.x);
+    let y = side(position.y, extents.y);
+    // 角では斜めにも置く (片方だけのときに横や縦と重ならないように、両方またいでいるときだけ)
+    let corner = if x != 0 && y != 0 {
+        IVec2::new(x, y)
+    } else {
+        IVec2::ZERO
+    };
+    [IVec2::new(x, 0), IVec2::new(0, y), corner]
+        .into_iter()
+        .filter(|side| *side != IVec2::ZERO)
+}
+
+/// 複製元の `Transform` を、`side` の向きに画面 1 つ分ずらす
+fn ghost_transform(source: &Transform, side: IVec2) -> Transform {
+    let offset = side.as_vec2() * BOUNDS;
+    Transform {
+        translation: source.translation + offset.extend(0.0),
+        ..*source
+    }
+}
@@ -0,0 +1,199 @@
+use bevy::{
+    color::palettes::css::{ORANGE, WHITE, YELLOW},
+    input::{common_conditions::input_just_pressed, mouse::AccumulatedMouseScroll},
+    prelude::*,
+};
+
+mod streaming;
+mod terrain;
+
+use streaming::{Chunks, LoadRadius, StreamingAnchor, StreamingStats};
+use terrain::{chunk_at, chunk_origin, CHUNK_SIZE};
+
+// カメラの移動の速さ (ピクセル/秒) と、`Shift` キーを押している間に掛ける倍率
+const CAMERA_SPEED: f32 = 600.0;
+const FAST_MULTIPLIER: f32 = 4.0;
+// ズームの範囲 (投影の倍率、大きいほど広く映る) と、ホイール 1 目盛りで掛ける倍率
+const ZOOM_RANGE: (f32, f32) = (0.25, 8.0);
+const ZOOM_STEP: f32 = 1.25;
+// 読み込む半径 (チャンク数) の範囲
+const RADIUS_RANGE: (i32, i32) = (1, 12);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(streaming::StreamingPlugin)
+        .insert_resource(ClearColor(Color::srgb(0.05, 0.05, 0.08)))
+        .insert_resource(ShowBorders(true))
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                (
+                    move_camera,
+                    zoom_camera,
+                    change_radius,
+                    toggle_borders.run_if(input_just_pressed(KeyCode::KeyB)),
+                ),
+                (draw_borders, update_info),
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// チャンクの境目を描くかどうか
+#[derive(Resource)]
+struct ShowBorders(bool);
+
+/// 操作方法と読み込みの統計を表示するテキスト
+#[derive(Component)]
+struct InfoText;
+
+/// チャンクを読み込む中心になるカメラと、説明のテキストを配置する
+fn setup(mut commands: Commands) {
+    commands.spawn((Camera2d, StreamingAnchor));
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+}
+
+/// `WASD` / 矢印キーでカメラを動かす (`Shift` キーを押している間は速く動かす)
+fn move_camera(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    camera: Single<(&mut Transform, &OrthographicProjection), With<StreamingAnchor>>,
+) {
+    let mut direction = Vec2::ZERO;
+    if keyboard_input.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        direction.y += 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        direction.y -= 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        direction.x -= 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        direction.x += 1.0;
+    }
+    let mut speed = CAMERA_SPEED;
+    if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        speed *= FAST_MULTIPLIER;
+    }
+
+    // ズームアウトしているときも画面の上では同じ速さに見えるように、投影の倍率を掛ける
+    let (mut transform, projection) = camera.into_inner();
+    let offset = direction.normalize_or_zero() * speed * projection.scale * time.delta_secs();
+    transform.translation += offset.extend(0.0);
+}
+
+/// マウスホイールでズームする (1 目盛りごとに `ZOOM_STEP` 倍)
+///
+/// ズームアウトすると、読み込む半径の外でチャンクが消えていく様子が見える。
+fn zoom_camera(
+    scroll: Res<AccumulatedMouseScroll>,
+    mut projection: Single<&mut OrthographicProjection, With<StreamingAnchor>>,
+) {
+    if scroll.delta.y != 0.0 {
+        projection.scale = (projection.scale * ZOOM_STEP.powf(-scroll.delta.y.signum()))
+            .clamp(ZOOM_RANGE.0, ZOOM_RANGE.1);
+    }
+}
+
+/// `-` / `=` キーで読み込む半径を変える
+fn change_radius(keyboard_input: Res<ButtonInput<KeyCode>>, mut radius: ResMut<LoadRadius>) {
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        **radius = (**radius - 1).max(RADIUS_RANGE.0);
+    }
+    if keyboard_input.just_pressed(KeyCode::Equal) {
+        **radius = (**radius + 1).min(RADIUS_RANGE.1);
+    }
+}
+
+/// `B` キーでチャンクの境目の表示を切り替える
+fn toggle_borders(mut show_borders: ResMut<ShowBorders>) {
+    show_borders.0 = !show_borders.0;
+}
+
+/// チャンクの境目 (作っている途中のチャンクは黄色)、カメラのいるチャンク、読み込む半径を描く
+fn draw_borders(
+    show_borders: Res<ShowBorders>,
+    chunks: Res<Chunks>,
+    radius: Res<LoadRadius>,
+    camera: Single<&Transform, With<StreamingAnchor>>,
+    mut gizmos: Gizmos,
+) {
+    if !show_borders.0 {
+        return;
+    }
+    let chunk_center = |coord: IVec2| chunk_origin(coord) + CHUNK_SIZE / 2.0;
+    for (coord, loaded) in chunks.iter() {
+        let color = if loaded {
+            WHITE.with_alpha(0.25)
+        } else {
+            YELLOW.with_alpha(0.8)
+        };
+        // 隣のチャンクの枠と重ならないように少し小さく描く
+        gizmos.rect_2d(
+            Isometry2d::from_translation(chunk_center(coord)),
+            Vec2::splat(CHUNK_SIZE - 2.0),
+            color,
+        );
+    }
+
+    let center = chunk_at(camera.translation.truncate());
+    gizmos.rect_2d(
+        Isometry2d::from_translation(chunk_center(center)),
+        Vec2::splat(CHUNK_SIZE),
+        ORANGE,
+    );
+    gizmos.circle_2d(
+        Isometry2d::from_translation(chunk_center(center)),
+        **radius as f32 * CHUNK_SIZE,
+        ORANGE.with_alpha(0.6),
+    );
+}
+
+/// 操作方法と、読み込みの統計を表示する
+fn update_info(
+    chunks: Res<Chunks>,
+    stats: Res<StreamingStats>,
+    radius: Res<LoadRadius>,
+    camera: Single<&Transform, With<StreamingAnchor>>,
+    mut text: Single<&mut Text, With<InfoText>>,
+) {
+    let (loaded, loading) = chunks
+        .iter()
+        .fold((0, 0), |(loaded, loading), (_, is_loaded)| {
+            if is_loaded {
+                (loaded + 1, loading)
+            } else {
+                (loaded, loading + 1)
+            }
+        });
+    let position = camera.translation.truncate();
+    text.0 = format!(
+        "WASD / Arrows: move (Shift: fast)   Wheel: zoom   -/=: radius   B: chunk borders\n\
+         Chunk: {}   Radius: {} chunks\n\
+         Loaded: {loaded}   Loading: {loading}   Generated: {}   Unloaded: {}   Cancelled: {}\n\
+         Generation: {:.2} ms / chunk (background)",
+        chunk_at(position),
+        **radius,
+        stats.generated,
+        stats.unloaded,
+        stats.cancelled,
+        stats.average_generation_ms,
+    );
+}
@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+    utils::{HashMap, Instant},
+};
+
+use crate::terrain::{chunk_at, chunk_origin, ChunkTiles};
+
+// 読み込む半径 (チャンク数) の初期値
+const DEFAULT_RADIUS: i32 = 4;
+// 1 フレームに始める生成タスクの最大数 (一度にたくさんのタスクを積んで近くのチャンクが遅れないようにする)
+const MAX_TASKS_PER_FRAME: usize = 8;
+// 読み込む半径より何チャンク外に出たら消すか (境目を行き来しても、読み込みと削除を繰り返さないようにする)
+const UNLOAD_MARGIN: i32 = 1;
+// 生成時間の平均に、新しい値をどれだけの割合で混ぜるか (指数移動平均)
+const AVERAGE_WEIGHT: f64 = 0.1;
+
+/// [`StreamingAnchor`] の周りのチャンクをバックグラウンドで作り、離れたチャンクを消すプラグイン
+///
+/// アンカーから半径 [`LoadRadius`] チャンク以内 (円形) でまだないチャンクを、近い順に
+/// [`AsyncComputeTaskPool`] のタスクで作る。タスクはタイルの生成からメッシュの組み立てまでを行い、
+/// メインスレッドは毎フレーム終わったタスクを取り出してエンティティを追加するだけなので、移動中も止まらない。
+/// 半径より `UNLOAD_MARGIN` チャンク以上離れたチャンクはエンティティを消し、作っている途中のタスクは捨てる。
+pub struct StreamingPlugin;
+
+impl Plugin for StreamingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LoadRadius(DEFAULT_RADIUS))
+            .init_resource::<Chunks>()
+            .init_resource::<StreamingStats>()
+            .add_systems(Startup, setup_chunk_material)
+            .add_systems(
+                Update,
+                (request_chunks, receive_chunks, unload_chunks).chain(),
+            );
+    }
+}
+
+/// この位置の周りのチャンクを読み込む (カメラに付ける)
+#[derive(Component)]
+pub struct StreamingAnchor;
+
+/// 読み込む半径 (チャンク数)
+#[derive(Resource, Deref, DerefMut)]
+pub struct LoadRadius(pub i32);
+
+/// 作っている途中か、作り終えたチャンク
+enum Chunk {
+    Loading(Task<GeneratedChunk>),
+    Loaded(Entity),
+}
+
+/// チャンクの座標ごとの状態
+#[derive(Resource, Default)]
+pub struct Chunks(HashMap<IVec2, Chunk>);
+
+impl Chunks {
+    /// チャンクの座標と、作り終えているかどうか
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, bool)> + '_ {
+        self.0
+            .iter()
+            .map(|(&coord, chunk)| (coord, matches!(chunk, Chunk::Loaded(_))))
+    }
+}
+
+/// タスクで作ったチャンク
+struct GeneratedChunk {
+    mesh: Mesh,
+    elapsed: Duration, // タイルの生成とメッシュの組み立てにかかった時間
+}
+
+/// 読み込みの統計
+#[derive(Resource, Default)]
+pub struct StreamingStats {
+    pub generated: usize,           // 作り終えたチャンクの数 (起動してからの合計)
+    pub unloaded: usize,            // 消したチャンクの数
+    pub cancelled: usize,           // 作り終える前に離れて捨てたタスクの数
+    pub average_generation_ms: f64, // 1 チャンクを作るのにかかった時間の平均 (ミリ秒)
+}
+
+/// すべてのチャンクで共有するマテリアル (色はメッシュの頂点カラーで付ける)
+#[derive(Resource)]
+struct ChunkMaterial(Handle<ColorMaterial>);
+
+fn setup_chunk_material(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    commands.insert_resource(ChunkMaterial(materials.add(Color::WHITE)));
+}
+
+/// 2 つのチャンクの座標の距離の 2 乗
+fn distance_squared(a: IVec2, b: IVec2) -> i32 {
+    (a - b).length_squared()
+}
+
+/// 半径の中でまだないチャンクを、アンカーに近い順に最大 `MAX_TASKS_PER_FRAME` 個まで作り始める
+fn request_chunks(
+    anchor: Single<&Transform, With<StreamingAnchor>>,
+    radius: Res<LoadRadius>,
+    mut chunks: ResMut<Chunks>,
+) {
+    let center = chunk_at(anchor.translation.truncate());
+    let radius = **radius;
+    let mut missing: Vec<IVec2> = (-radius..=radius)
+        .flat_map(|y| (-radius..=radius).map(move |x| IVec2::new(x, y)))
+        .filter(|offset| offset.length_squared() <= radius * radius)
+        .map(|offset| center + offset)
+        .filter(|coord| !chunks.0.contains_key(coord))
+        .collect();
+    missing.sort_by_key(|&coord| distance_squared(coord, center));
+
+    let pool = AsyncComputeTaskPool::get();
+    for coord in missing.into_iter().take(MAX_TASKS_PER_FRAME) {
+        let task = pool.spawn(async move {
+            let started = Instant::now();
+            let mesh = ChunkTiles::generate(coord).build_mesh();
+            GeneratedChunk {
+                mesh,
+                elapsed: started.elapsed(),
+            }
+        });
+        chunks.0.insert(coord, Chunk::Loading(task));
+    }
+}
+
+/// 終わったタスクを取り出して、チャンクのエンティティを追加する (終わっていなければすぐに次へ進む)
+fn receive_chunks(
+    mut commands: Commands,
+    mut chunks: ResMut<Chunks>,
+    mut stats: ResMut<StreamingStats>,
+    material: Res<ChunkMaterial>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (&coord, chunk) in chunks.0.iter_mut() {
+        let Chunk::Loading(task) = chunk else {
+            continue;
+        };
+        let Some(generated) = block_on(future::poll_once(task)) else {
+            continue;
+        };
+        let entity = commands
+            .spawn((
+                Mesh2d(meshes.add(generated.mesh)),
+                MeshMaterial2d(material.0.clone()),
+                Transform::from_translation(chunk_origin(coord).extend(0.0)),
+            ))
+            .id();
+        *chunk = Chunk::Loaded(entity);
+
+        let elapsed_ms = generated.elapsed.as_secs_f64() * 1000.0;
+        stats.average_generation_ms = if stats.generated == 0 {
+            elapsed_ms
+        } else {
+            stats.average_generation_ms * (1.0 - AVERAGE_WEIGHT) + elapsed_ms * AVERAGE_WEIGHT
+        };
+        stats.generated += 1;
+    }
+}
+
+/// 半径より `UNLOAD_MARGIN` チャンク以上離れたチャンクを消す
+///
+/// 作っている途中のタスクは `Task` を捨てると止まる。
+fn unload_chunks(
+    mut commands: Commands,
+    anchor: Single<&Transform, With<StreamingAnchor>>,
+    radius: Res<LoadRadius>,
+    mut chunks: ResMut<Chunks>,
+    mut stats: ResMut<StreamingStats>,
+) {
+    let center = chunk_at(anchor.translation.truncate());
+    let limit = **radius + UNLOAD_MARGIN;
+    chunks.0.retain(|&coord, chunk| {
+        if distance_squared(coord, center) <= limit * limit {
+            return true;
+        }
+        match chunk {
+            Chunk::Loaded(entity) => {
+                commands.entity(*entity).despawn();
+                stats.unloaded += 1;
+            }
+            Chunk::Loading(_) => stats.cancelled += 1,
+        }
+        false
+    });
+}
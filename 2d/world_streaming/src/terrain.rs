@@ -0,0 +1,172 @@
+//! チャンクの地形の生成と、チャンクのメッシュの組み立て (ECS に依存しない部分)
+//!
+//! 地形はワールド座標だけから決まるので、どのチャンクをどの順番で作っても境目がつながる。
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+};
+
+// 1 つのチャンクの一辺のタイルの数と、タイルの一辺の長さ (ピクセル)
+pub const CHUNK_TILES: usize = 32;
+pub const TILE_SIZE: f32 = 8.0;
+// チャンクの一辺の長さ (ピクセル)
+pub const CHUNK_SIZE: f32 = CHUNK_TILES as f32 * TILE_SIZE;
+
+// 地形の高さを決めるノイズの細かさ (1 タイルあたりの周波数) と重ねる数
+const NOISE_FREQUENCY: f32 = 0.02;
+const NOISE_OCTAVES: usize = 5;
+// 森を置くかどうかを決めるノイズの細かさと、森にする値
+const FOREST_FREQUENCY: f32 = 0.08;
+const FOREST_THRESHOLD: f32 = 0.6;
+
+/// タイルの種類
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    DeepWater,
+    Water,
+    Sand,
+    Grass,
+    Forest,
+    Rock,
+    Snow,
+}
+
+impl Tile {
+    /// 高さ (0〜1) と森のノイズの値から、タイルの種類を決める
+    fn from_height(height: f32, forest: f32) -> Tile {
+        match height {
+            h if h < 0.35 => Tile::DeepWater,
+            h if h < 0.45 => Tile::Water,
+            h if h < 0.48 => Tile::Sand,
+            h if h < 0.65 && forest > FOREST_THRESHOLD => Tile::Forest,
+            h if h < 0.65 => Tile::Grass,
+            h if h < 0.78 => Tile::Rock,
+            _ => Tile::Snow,
+        }
+    }
+
+    /// タイルの色 (線形 RGBA、頂点カラーに使う)
+    fn color(self) -> [f32; 4] {
+        let color = match self {
+            Tile::DeepWater => Color::srgb(0.1, 0.2, 0.5),
+            Tile::Water => Color::srgb(0.2, 0.4, 0.8),
+            Tile::Sand => Color::srgb(0.85, 0.8, 0.55),
+            Tile::Grass => Color::srgb(0.35, 0.65, 0.3),
+            Tile::Forest => Color::srgb(0.15, 0.4, 0.2),
+            Tile::Rock => Color::srgb(0.5, 0.48, 0.45),
+            Tile::Snow => Color::srgb(0.95, 0.95, 0.97),
+        };
+        color.to_linear().to_f32_array()
+    }
+}
+
+/// チャンク 1 つ分のタイル (左下から行ごとに並ぶ)
+pub struct ChunkTiles {
+    tiles: Vec<Tile>,
+}
+
+impl ChunkTiles {
+    /// チャンクの座標 `coord` のタイルを作る
+    pub fn generate(coord: IVec2) -> Self {
+        let origin = coord * CHUNK_TILES as i32;
+        let mut tiles = Vec::with_capacity(CHUNK_TILES * CHUNK_TILES);
+        for y in 0..CHUNK_TILES {
+            for x in 0..CHUNK_TILES {
+                let tile = (origin + IVec2::new(x as i32, y as i32)).as_vec2();
+                let height = fractal_noise(tile * NOISE_FREQUENCY, NOISE_OCTAVES);
+                // 森は高さとは別のノイズで決める (座標をずらして高さのノイズと似ないようにする)
+                let forest = value_noise(tile * FOREST_FREQUENCY + Vec2::splat(1000.0));
+                tiles.push(Tile::from_height(height, forest));
+            }
+        }
+        ChunkTiles { tiles }
+    }
+
+    /// チャンクの中のタイル (x, y) の種類
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        self.tiles[y * CHUNK_TILES + x]
+    }
+
+    /// タイルごとに頂点カラーで塗った四角形を並べたメッシュを作る
+    ///
+    /// 頂点の位置はチャンクの左下を原点にする (チャンクのエンティティを `chunk_origin` に置く)。
+    /// タイルごとにメッシュやエンティティを作ると数が増えすぎるので、チャンク全体を 1 つのメッシュにまとめる。
+    pub fn build_mesh(&self) -> Mesh {
+        let quads = CHUNK_TILES * CHUNK_TILES;
+        let mut positions = Vec::with_capacity(quads * 4);
+        let mut colors = Vec::with_capacity(quads * 4);
+        let mut indices = Vec::with_capacity(quads * 6);
+        for y in 0..CHUNK_TILES {
+            for x in 0..CHUNK_TILES {
+                let min = Vec2::new(x as f32, y as f32) * TILE_SIZE;
+                let max = min + TILE_SIZE;
+                let start = positions.len() as u32;
+                positions.extend([
+                    [min.x, min.y, 0.0],
+                    [max.x, min.y, 0.0],
+                    [max.x, max.y, 0.0],
+                    [min.x, max.y, 0.0],
+                ]);
+                colors.extend([self.tile(x, y).color(); 4]);
+                indices.extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+            }
+        }
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            // メインワールドでは使わないので、GPU に送ったあとは捨てる
+            RenderAssetUsages::RENDER_WORLD,
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+}
+
+/// チャンクの左下のワールド座標
+pub fn chunk_origin(coord: IVec2) -> Vec2 {
+    coord.as_vec2() * CHUNK_SIZE
+}
+
+/// ワールド座標 `position` を含むチャンクの座標
+pub fn chunk_at(position: Vec2) -> IVec2 {
+    (position / CHUNK_SIZE).floor().as_ivec2()
+}
+
+/// 細かさを倍にしながらバリューノイズを重ねた値 (0〜1)
+fn fractal_noise(point: Vec2, octaves: usize) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 1.0;
+    let mut total = 0.0;
+    let mut point = point;
+    for _ in 0..octaves {
+        sum += value_noise(point) * amplitude;
+        total += amplitude;
+        amplitude *= 0.5;
+        point *= 2.0;
+    }
+    sum / total
+}
+
+/// 0 から 1 の値を返す滑らかなノイズ (バリューノイズ)
+///
+/// 整数の格子点ごとにハッシュで乱数を決め、その間をスムーズステップで補間する。
+fn value_noise(point: Vec2) -> f32 {
+    let cell = point.floor();
+    let t = point - cell;
+    let t = t * t * (3.0 - 2.0 * t);
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let bottom = hash(x, y).lerp(hash(x + 1, y), t.x);
+    let top = hash(x, y + 1).lerp(hash(x + 1, y + 1), t.x);
+    bottom.lerp(top, t.y)
+}
+
+/// 整数の座標から 0 から 1 の擬似乱数を作る
+fn hash(x: i32, y: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    (h & 0x00ff_ffff) as f32 / 0x00ff_ffff as f32
+}
@@ -0,0 +1,198 @@
+use bevy::{prelude::*, render::primitives::Aabb, ui::RelativeCursorPosition};
+
+use crate::ModelBounds;
+
+// 分解を進めるキー
+const EXPLODE_KEY: KeyCode = KeyCode::BracketRight;
+// 分解を戻すキー
+const COLLAPSE_KEY: KeyCode = KeyCode::BracketLeft;
+// キーを押し続けたときに 1 秒で変わる分解の割合
+const KEY_SPEED: f32 = 0.5;
+// 分解を最大にしたときに部品を動かす距離 (モデルの外接球の半径に掛ける倍率)
+const EXPLODE_DISTANCE: f32 = 1.0;
+// スライダーの大きさ (ピクセル)
+const SLIDER_SIZE: Vec2 = Vec2::new(240.0, 14.0);
+// スライダーの溝の色
+const TRACK_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+// スライダーの塗りつぶしの色
+const FILL_COLOR: Color = Color::srgb(0.9, 0.6, 0.2);
+// スライダーのラベルのフォントサイズ
+const LABEL_FONT_SIZE: f32 = 16.0;
+
+/// 部品 (サブメッシュ) をモデルの中心から外側へ離して表示する分解表示を追加するプラグイン
+///
+/// 画面右上のスライダーをドラッグするか `[` / `]` キーで、分解の割合 (0 から 1) を変える。
+/// 部品ごとに、モデルの中心から部品の中心へ向かう向きに、割合に比例した距離だけ動かす。
+pub struct ExplodePlugin;
+
+impl Plugin for ExplodePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExplodeAmount>()
+            .add_systems(Startup, spawn_slider)
+            .add_systems(
+                Update,
+                (
+                    collect_parts.run_if(resource_exists_and_changed::<ModelBounds>),
+                    (drag_slider, step_with_keys),
+                    (explode_parts, update_slider),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// 分解の割合 (0 で元の形、1 で最大まで離す)
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ExplodeAmount(f32);
+
+/// 分解表示で動かす部品 (`Mesh3d` を持つエンティティ)
+#[derive(Component)]
+struct ExplodePart {
+    base_translation: Vec3, // 分解していないときの位置 (親のローカル座標)
+    local_offset: Vec3,     // 分解を最大にしたときに動かす量 (親のローカル座標)
+}
+
+/// 分解のスライダーの溝 (クリック・ドラッグを受け付ける)
+#[derive(Component)]
+struct ExplodeSlider;
+
+/// 分解のスライダーの塗りつぶし
+#[derive(Component)]
+struct ExplodeFill;
+
+/// 分解のスライダーのラベル
+#[derive(Component)]
+struct ExplodeLabel;
+
+/// 画面右上に分解のスライダーとラベルを配置する
+fn spawn_slider(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            right: Val::Px(12.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: LABEL_FONT_SIZE,
+                    ..default()
+                },
+                ExplodeLabel,
+            ));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(SLIDER_SIZE.x),
+                        height: Val::Px(SLIDER_SIZE.y),
+                        ..default()
+                    },
+                    BackgroundColor(TRACK_COLOR),
+                    Interaction::default(),
+                    RelativeCursorPosition::default(),
+                    ExplodeSlider,
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(FILL_COLOR),
+                    ExplodeFill,
+                ));
+        });
+}
+
+/// モデルの各部品が分解したときに動く向きと距離を求める
+///
+/// 向きはモデルの中心から部品の `Aabb` の中心へ向かうワールド座標の向きで、
+/// `Transform` は親の座標系で書き込むので、親の `GlobalTransform` の逆変換で親のローカル座標に直しておく。
+/// 中心にある部品 (向きが決まらない部品) は動かさない。
+#[allow(clippy::type_complexity)]
+fn collect_parts(
+    mut commands: Commands,
+    bounds: Res<ModelBounds>,
+    meshes: Query<(Entity, &Transform, &Aabb, &GlobalTransform, Option<&Parent>), With<Mesh3d>>,
+    global_transforms: Query<&GlobalTransform>,
+) {
+    for (entity, transform, aabb, global_transform, parent) in &meshes {
+        let centroid = global_transform.transform_point(aabb.center.into());
+        let world_offset =
+            (centroid - bounds.center).normalize_or_zero() * bounds.radius * EXPLODE_DISTANCE;
+        let local_offset = match parent.and_then(|parent| global_transforms.get(parent.get()).ok())
+        {
+            Some(parent_transform) => parent_transform
+                .affine()
+                .inverse()
+                .transform_vector3(world_offset),
+            None => world_offset,
+        };
+        commands.entity(entity).insert(ExplodePart {
+            base_translation: transform.translation,
+            local_offset,
+        });
+    }
+}
+
+/// スライダーを押している間、カーソルの横の位置を分解の割合にする
+fn drag_slider(
+    slider: Single<(&Interaction, &RelativeCursorPosition), With<ExplodeSlider>>,
+    mut amount: ResMut<ExplodeAmount>,
+) {
+    let (interaction, cursor) = *slider;
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+    // `normalized` はノードの左上を (0, 0)、右下を (1, 1) とした位置
+    if let Some(position) = cursor.normalized {
+        let value = position.x.clamp(0.0, 1.0);
+        if **amount != value {
+            **amount = value;
+        }
+    }
+}
+
+/// `[` / `]` キーを押している間、分解の割合を少しずつ変える
+fn step_with_keys(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut amount: ResMut<ExplodeAmount>,
+) {
+    let mut direction = 0.0;
+    if keyboard_input.pressed(EXPLODE_KEY) {
+        direction += 1.0;
+    }
+    if keyboard_input.pressed(COLLAPSE_KEY) {
+        direction -= 1.0;
+    }
+    if direction != 0.0 {
+        **amount = (**amount + direction * KEY_SPEED * time.delta_secs()).clamp(0.0, 1.0);
+    }
+}
+
+/// 分解の割合に合わせて部品を動かす (割合が変わったときと部品が増えたときだけ書き込む)
+fn explode_parts(amount: Res<ExplodeAmount>, mut parts: Query<(Ref<ExplodePart>, &mut Transform)>) {
+    for (part, mut transform) in &mut parts {
+        if amount.is_changed() || part.is_added() {
+            transform.translation = part.base_translation + part.local_offset * **amount;
+        }
+    }
+}
+
+/// スライダーの塗りつぶしとラベルを分解の割合に合わせる
+fn update_slider(
+    amount: Res<ExplodeAmount>,
+    mut fill: Single<&mut Node, With<ExplodeFill>>,
+    mut label: Single<&mut Text, With<ExplodeLabel>>,
+) {
+    if !amount.is_changed() {
+        return;
+    }
+    fill.width = Val::Percent(**amount * 100.0);
+    label.0 = format!("Explode: {:.0}%", **amount * 100.0);
+}
@@ -0,0 +1,241 @@
+use std::path::Path;
+
+use bevy::{
+    asset::{io::AssetSourceBuilder, AssetPath},
+    gltf::Gltf,
+    prelude::*,
+    render::primitives::Aabb,
+    scene::SceneInstanceReady,
+};
+
+mod explode;
+mod orbit;
+mod overlays;
+
+use explode::ExplodePlugin;
+use orbit::{OrbitCamera, OrbitCameraPlugin};
+use overlays::OverlaysPlugin;
+
+// 起動引数で glTF を指定しなかったときに表示するモデル (`assets` からの相対パス)
+const DEFAULT_MODEL: &str = "models/toy_car.gltf";
+// 起動引数で指定した glTF のディレクトリを登録するアセットソースの名前
+const MODEL_SOURCE: &str = "model";
+// 説明のテキストのフォントサイズ
+const HELP_FONT_SIZE: f32 = 16.0;
+
+fn main() {
+    let model = ModelSource::from_args();
+
+    let mut app = App::new();
+    // アセットソースは `AssetPlugin` (`DefaultPlugins`) より前に登録する必要がある
+    if let Some(dir) = &model.dir {
+        app.register_asset_source(
+            MODEL_SOURCE,
+            AssetSourceBuilder::platform_default(dir, None),
+        );
+    }
+    app.add_plugins((
+        DefaultPlugins.set(overlays::render_plugin()),
+        OrbitCameraPlugin,
+        ExplodePlugin,
+        OverlaysPlugin,
+    ))
+    .insert_resource(model)
+    .add_systems(Startup, setup)
+    .add_systems(Update, (frame_model, update_status_text));
+    app.run();
+}
+
+/// 表示する glTF の場所
+#[derive(Resource)]
+struct ModelSource {
+    dir: Option<String>, // 起動引数で指定したファイルのディレクトリ (`None` なら `assets` のモデル)
+    path: String,        // アセットサーバーで読み込むパス
+}
+
+impl ModelSource {
+    /// 起動引数 (`cargo run -- path/to/model.glb`) から表示する glTF を決める
+    ///
+    /// 指定したファイルは `assets` の外にあってもよいので、そのディレクトリを
+    /// `model://` のアセットソースとして登録して読み込む。
+    fn from_args() -> ModelSource {
+        let Some(arg) = std::env::args().skip(1).find(|arg| !arg.starts_with("--")) else {
+            return ModelSource {
+                dir: None,
+                path: DEFAULT_MODEL.to_string(),
+            };
+        };
+        let file = Path::new(&arg);
+        let dir = std::path::absolute(file)
+            .ok()
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .unwrap_or_default();
+        let name = file
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        info!("loading {} from {}", name, dir.display());
+        ModelSource {
+            dir: Some(dir.to_string_lossy().into_owned()),
+            path: AssetPath::from(name).with_source(MODEL_SOURCE).to_string(),
+        }
+    }
+}
+
+/// 読み込んだモデルの中心と大きさ (外接球)
+///
+/// モデルのメッシュの `Aabb` がそろった時点で `frame_model` が挿入する。
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ModelBounds {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+/// 読み込み中の glTF (読み込みの失敗を表示するために持っておく)
+#[derive(Resource)]
+struct ModelHandle(Handle<Gltf>);
+
+/// シーンの生成が終わり、大きさを測るのを待っているモデルを示すコンポーネント
+#[derive(Component)]
+struct FramePending;
+
+/// 読み込みの状態を表示するテキスト
+#[derive(Component)]
+struct StatusText;
+
+/// カメラ・ライト・モデル・説明のテキストを配置する
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, model: Res<ModelSource>) {
+    commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 1.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+            OrbitCamera::default(),
+        ))
+        // カメラの子にしたライトで、どの向きから見てもモデルを照らす
+        .with_child((
+            DirectionalLight {
+                illuminance: light_consts::lux::OVERCAST_DAY,
+                ..default()
+            },
+            Transform::from_rotation(Quat::from_euler(EulerRot::YXZ, 0.4, 0.3, 0.0)),
+        ));
+    commands.insert_resource(AmbientLight {
+        brightness: 300.0,
+        ..default()
+    });
+
+    commands.insert_resource(ModelHandle(asset_server.load(&model.path)));
+    commands
+        .spawn(SceneRoot(
+            asset_server.load(GltfAssetLabel::Scene(0).from_asset(model.path.clone())),
+        ))
+        // シーンのエンティティがすべて生成されたら大きさを測る
+        .observe(
+            |trigger: Trigger<SceneInstanceReady>, mut commands: Commands| {
+                commands.entity(trigger.entity()).insert(FramePending);
+            },
+        );
+
+    commands.spawn((
+        Text::new(
+            "Left drag: orbit  Right / middle drag: pan  Wheel: zoom  F: frame model\n\
+             W: wireframe  N: normals  [ / ]: explode",
+        ),
+        TextFont {
+            font_size: HELP_FONT_SIZE,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+    commands.spawn((
+        Text::new(format!("Loading {}", model.path)),
+        TextFont {
+            font_size: HELP_FONT_SIZE,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+}
+
+/// 生成したモデルのメッシュをすべて囲む球を求め、`ModelBounds` にする
+///
+/// `Aabb` はメッシュのローカル座標の箱なので、8 つの角を `GlobalTransform` でワールド座標に移してから囲む。
+/// `Aabb` と `GlobalTransform` は `PostUpdate` で計算されるので、シーンが生成された次のフレームで測る。
+fn frame_model(
+    mut commands: Commands,
+    roots: Query<Entity, With<FramePending>>,
+    children: Query<&Children>,
+    meshes: Query<(Option<&Aabb>, &GlobalTransform), With<Mesh3d>>,
+) {
+    for root in &roots {
+        let mut min = Vec3::INFINITY;
+        let mut max = Vec3::NEG_INFINITY;
+        for entity in children.iter_descendants(root) {
+            let Ok((aabb, global_transform)) = meshes.get(entity) else {
+                continue;
+            };
+            // まだ計算されていないメッシュがあれば次のフレームで測り直す
+            let Some(aabb) = aabb else {
+                return;
+            };
+            let (center, half) = (Vec3::from(aabb.center), Vec3::from(aabb.half_extents));
+            for corner in 0..8 {
+                let sign = Vec3::new(
+                    if corner & 1 == 0 { -1.0 } else { 1.0 },
+                    if corner & 2 == 0 { -1.0 } else { 1.0 },
+                    if corner & 4 == 0 { -1.0 } else { 1.0 },
+                );
+                let point = global_transform.transform_point(center + half * sign);
+                min = min.min(point);
+                max = max.max(point);
+            }
+        }
+
+        let bounds = if min.x <= max.x {
+            ModelBounds {
+                center: (min + max) / 2.0,
+                radius: ((max - min).length() / 2.0).max(0.001),
+            }
+        } else {
+            warn!("the model has no meshes");
+            ModelBounds {
+                center: Vec3::ZERO,
+                radius: 1.0,
+            }
+        };
+        info!(
+            "model center {:.3}, radius {:.3}",
+            bounds.center, bounds.radius
+        );
+        commands.insert_resource(bounds);
+        commands.entity(root).remove::<FramePending>();
+    }
+}
+
+/// 読み込みに失敗したときはその旨を、読み込めたらモデルの大きさを表示する
+fn update_status_text(
+    asset_server: Res<AssetServer>,
+    model: Res<ModelSource>,
+    handle: Res<ModelHandle>,
+    bounds: Option<Res<ModelBounds>>,
+    mut text: Single<&mut Text, With<StatusText>>,
+) {
+    if let Some(bounds) = bounds {
+        if bounds.is_changed() {
+            text.0 = format!("{}  (radius {:.3})", model.path, bounds.radius);
+        }
+    } else if asset_server.load_state(&handle.0).is_failed() && !text.0.starts_with("Failed") {
+        text.0 = format!("Failed to load {} (see the log for details)", model.path);
+    }
+}
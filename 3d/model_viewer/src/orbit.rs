@@ -0,0 +1,142 @@
+use bevy::{
+    input::{
+        common_conditions::input_just_pressed,
+        mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
+    },
+    prelude::*,
+};
+
+use crate::ModelBounds;
+
+// モデル全体を画面に収めるキー
+const FRAME_KEY: KeyCode = KeyCode::KeyF;
+// マウスを 1 ピクセル動かしたときの回転 (ラジアン)
+const ORBIT_SENSITIVITY: f32 = 0.005;
+// マウスを 1 ピクセル動かしたときの平行移動 (注視点までの距離に対する割合)
+const PAN_SENSITIVITY: f32 = 0.0015;
+// ホイールを 1 行回したときのズーム (距離に掛ける `exp` の指数)
+const ZOOM_SENSITIVITY: f32 = 0.1;
+// ピクセル単位のスクロールを行単位に直すときの 1 行のピクセル数
+const PIXELS_PER_LINE: f32 = 100.0;
+// 真上・真下を越えて回り込まないようにする上下の回転の限界 (ラジアン)
+const PITCH_LIMIT: f32 = 1.54;
+// モデルを画面に収めるときの余白 (外接球の半径に掛ける倍率)
+const FRAME_MARGIN: f32 = 1.2;
+
+/// 注視点の周りを回るカメラを追加するプラグイン
+///
+/// 左ドラッグで注視点の周りを回り、右ドラッグ (または中ボタンのドラッグ) で注視点ごと平行移動し、
+/// ホイールで近づいたり離れたりする。モデルを読み込んだときと `F` キーで、モデル全体が画面に収まる位置に戻る。
+pub struct OrbitCameraPlugin;
+
+impl Plugin for OrbitCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                frame_camera.run_if(
+                    resource_exists_and_changed::<ModelBounds>
+                        .or(resource_exists::<ModelBounds>.and(input_just_pressed(FRAME_KEY))),
+                ),
+                orbit_camera,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// 注視点の周りを回るカメラ
+#[derive(Component, Debug)]
+pub struct OrbitCamera {
+    pub focus: Vec3,   // 注視点
+    pub yaw: f32,      // 左右の回転 (ラジアン)
+    pub pitch: f32,    // 上下の回転 (ラジアン、負の値で見下ろす)
+    pub distance: f32, // 注視点からの距離
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        OrbitCamera {
+            focus: Vec3::ZERO,
+            yaw: 0.6,
+            pitch: -0.4,
+            distance: 4.0,
+        }
+    }
+}
+
+impl OrbitCamera {
+    /// 注視点・回転・距離から求めたカメラの `Transform`
+    fn transform(&self) -> Transform {
+        let rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0);
+        Transform {
+            translation: self.focus + rotation * Vec3::Z * self.distance,
+            rotation,
+            ..default()
+        }
+    }
+}
+
+/// モデルの外接球が視野角に収まる距離まで下がり、注視点をモデルの中心にする
+///
+/// 小さなモデルや大きなモデルでも欠けないように、ニアクリップとファークリップも大きさに合わせる。
+fn frame_camera(
+    bounds: Res<ModelBounds>,
+    camera: Single<(&mut OrbitCamera, &mut Transform, &mut Projection)>,
+) {
+    let (mut orbit, mut transform, mut projection) = camera.into_inner();
+    let Projection::Perspective(perspective) = &mut *projection else {
+        return;
+    };
+    perspective.near = bounds.radius * 0.01;
+    perspective.far = bounds.radius * 100.0;
+    // 縦と横の視野角の狭い方 (縦長のウィンドウでは横) に収める
+    let half_fov = (perspective.fov / 2.0)
+        .min(((perspective.fov / 2.0).tan() * perspective.aspect_ratio).atan());
+
+    orbit.focus = bounds.center;
+    orbit.distance = bounds.radius / half_fov.sin() * FRAME_MARGIN;
+    *transform = orbit.transform();
+}
+
+/// マウスのドラッグとホイールでカメラを動かす
+///
+/// UI (分解表示のスライダー) の上にカーソルがある間は、UI の操作を優先してカメラを動かさない。
+fn orbit_camera(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    interactions: Query<&Interaction>,
+    camera: Single<(&mut OrbitCamera, &mut Transform)>,
+) {
+    if interactions
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+    let (mut orbit, mut transform) = camera.into_inner();
+    let before = (orbit.focus, orbit.yaw, orbit.pitch, orbit.distance);
+
+    if mouse_buttons.pressed(MouseButton::Left) {
+        orbit.yaw -= motion.delta.x * ORBIT_SENSITIVITY;
+        orbit.pitch =
+            (orbit.pitch - motion.delta.y * ORBIT_SENSITIVITY).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+    } else if mouse_buttons.any_pressed([MouseButton::Right, MouseButton::Middle]) {
+        // カーソルの動きにモデルがついてくるように、カメラをカーソルと逆向きに動かす
+        let pan = (transform.left() * motion.delta.x + transform.up() * motion.delta.y)
+            * orbit.distance
+            * PAN_SENSITIVITY;
+        orbit.focus += pan;
+    }
+
+    let lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_LINE,
+    };
+    orbit.distance *= (-lines * ZOOM_SENSITIVITY).exp();
+
+    if (orbit.focus, orbit.yaw, orbit.pitch, orbit.distance) != before {
+        *transform = orbit.transform();
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::{
+    color::palettes::css::{LIME, WHITE},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    render::{mesh::VertexAttributeValues, RenderPlugin},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::{
+    pbr::wireframe::{WireframeConfig, WireframePlugin},
+    render::settings::{WgpuFeatures, WgpuSettings},
+};
+
+use crate::ModelBounds;
+
+// ワイヤーフレーム表示を切り替えるキー
+#[cfg(not(target_arch = "wasm32"))]
+const WIREFRAME_KEY: KeyCode = KeyCode::KeyW;
+// 法線の表示を切り替えるキー
+const NORMALS_KEY: KeyCode = KeyCode::KeyN;
+// 法線の線の長さ (モデルの外接球の半径に掛ける倍率)
+const NORMAL_LENGTH: f32 = 0.05;
+// 1 フレームに描く法線の最大数 (頂点の多いモデルで重くなりすぎないようにする)
+const MAX_NORMALS: usize = 20_000;
+
+/// ワイヤーフレームと法線の表示を追加するプラグイン
+///
+/// `W` キーですべてのメッシュをワイヤーフレームで重ねて表示し、
+/// `N` キーで各頂点の法線をギズモの線で表示する。
+pub struct OverlaysPlugin;
+
+impl Plugin for OverlaysPlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(not(target_arch = "wasm32"))] // WebGL ではワイヤーフレームを描けない
+        app.add_plugins(WireframePlugin)
+            .insert_resource(WireframeConfig {
+                global: false,
+                default_color: WHITE.into(),
+            })
+            .add_systems(
+                Update,
+                toggle_wireframe.run_if(input_just_pressed(WIREFRAME_KEY)),
+            );
+
+        app.init_resource::<NormalsVisible>().add_systems(
+            Update,
+            (
+                toggle_normals.run_if(input_just_pressed(NORMALS_KEY)),
+                draw_normals.run_if(normals_visible.and(resource_exists::<ModelBounds>)),
+            ),
+        );
+    }
+}
+
+/// ワイヤーフレームの描画に必要な GPU の機能 (`POLYGON_MODE_LINE`) を有効にした `RenderPlugin`
+pub fn render_plugin() -> RenderPlugin {
+    #[cfg(not(target_arch = "wasm32"))]
+    return RenderPlugin {
+        render_creation: WgpuSettings {
+            features: WgpuFeatures::POLYGON_MODE_LINE,
+            ..default()
+        }
+        .into(),
+        ..default()
+    };
+    #[cfg(target_arch = "wasm32")]
+    RenderPlugin::default()
+}
+
+/// 法線を表示するかどうか
+#[derive(Resource, Default, Deref, DerefMut)]
+struct NormalsVisible(bool);
+
+/// 法線を表示するかどうかを判定する条件関数
+fn normals_visible(visible: Res<NormalsVisible>) -> bool {
+    **visible
+}
+
+/// `W` キーでワイヤーフレーム表示を切り替える
+#[cfg(not(target_arch = "wasm32"))]
+fn toggle_wireframe(mut config: ResMut<WireframeConfig>) {
+    config.global = !config.global;
+}
+
+/// `N` キーで法線の表示を切り替える
+fn toggle_normals(mut visible: ResMut<NormalsVisible>) {
+    **visible = !**visible;
+}
+
+/// 各頂点の位置から法線の向きに線を描く
+///
+/// 頂点の位置は `GlobalTransform` でワールド座標に移し、法線は拡大縮小が均一でなくても面に垂直になるように、
+/// 変換行列の逆行列の転置 (法線行列) で向きを変える。
+fn draw_normals(
+    mut gizmos: Gizmos,
+    bounds: Res<ModelBounds>,
+    mesh_assets: Res<Assets<Mesh>>,
+    meshes: Query<(&Mesh3d, &GlobalTransform)>,
+) {
+    let length = bounds.radius * NORMAL_LENGTH;
+    let mut remaining = MAX_NORMALS;
+    for (mesh, global_transform) in &meshes {
+        let Some(mesh) = mesh_assets.get(&mesh.0) else {
+            continue;
+        };
+        let (
+            Some(VertexAttributeValues::Float32x3(positions)),
+            Some(VertexAttributeValues::Float32x3(normals)),
+        ) = (
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+        )
+        else {
+            continue;
+        };
+        let affine = global_transform.affine();
+        let normal_matrix = Mat3::from(affine.matrix3).inverse().transpose();
+        for (position, normal) in positions.iter().zip(normals).take(remaining) {
+            let start = affine.transform_point3(Vec3::from(*position));
+            let direction = (normal_matrix * Vec3::from(*normal)).normalize_or_zero();
+            gizmos.line(start, start + direction * length, LIME);
+            remaining -= 1;
+        }
+        if remaining == 0 {
+            break;
+        }
+    }
+}
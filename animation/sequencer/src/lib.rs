@@ -0,0 +1,37 @@
+//! 位置・大きさ・向き・不透明度を、イージングをかけて目標の値まで変化させるトゥイーンと、
+//! トゥイーンを順番や同時に再生するタイムラインをまとめたプラグイン。
+//!
+//! タイムラインはコードで組み立てる (`seq().move_to(..).wait(0.5).fade_out(..).build()`)。
+//! 組み立てた [`Timeline`] をエンティティに追加すると、手順ごとに [`Tweens`] へトゥイーンを渡して再生し、
+//! `call` の手順ではワンショットのシステムを実行する。
+//! 短い演出 (レベルの始まりにブロックを落とす、など) を、専用のシステムを書かずに作るためのもの。
+
+mod timeline;
+mod tween;
+
+use bevy::prelude::*;
+
+pub use timeline::{advance_timelines, seq, Sequence, Timeline};
+pub use tween::{animate_tweens, Tween, TweenId, TweenTarget, Tweens};
+
+/// タイムラインとトゥイーンを進めるシステムのセット
+///
+/// 値を戻してからタイムラインを追加し直すシステムは、このセットより前に実行すると
+/// 古いトゥイーンに上書きされずに済む。
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SequencerSet;
+
+/// タイムラインとトゥイーンを毎フレーム進めるプラグイン
+pub struct SequencerPlugin;
+
+impl Plugin for SequencerPlugin {
+    fn build(&self, app: &mut App) {
+        // タイムラインが始めたトゥイーンを、同じフレームのうちに動かし始める
+        app.add_systems(
+            Update,
+            (advance_timelines, animate_tweens)
+                .chain()
+                .in_set(SequencerSet),
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::{ecs::system::SystemId, prelude::*};
+use sequencer::{seq, SequencerPlugin, SequencerSet, Tweens};
+
+// 並べるカードの数と、カードの間隔・大きさ
+const CARD_COUNT: usize = 5;
+const CARD_SPACING: f32 = 150.0;
+const CARD_SIZE: Vec2 = Vec2::new(90.0, 90.0);
+// カードを落とし始める高さ (並べる位置からの距離)
+const DROP_HEIGHT: f32 = 400.0;
+// 隣のカードが動き始めるまでの遅れ (秒)
+const STAGGER: f32 = 0.15;
+// 見出しの位置
+const TITLE_Y: f32 = 220.0;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, SequencerPlugin))
+        .init_resource::<Callbacks>()
+        .init_resource::<FinishedCards>()
+        .add_systems(Startup, (setup, play).chain())
+        .add_systems(
+            Update,
+            (
+                play.run_if(|keyboard_input: Res<ButtonInput<KeyCode>>| {
+                    keyboard_input.just_pressed(KeyCode::Space)
+                })
+                .before(SequencerSet),
+                update_status.run_if(resource_changed::<FinishedCards>),
+            ),
+        )
+        .run();
+}
+
+/// カードの並べる位置
+#[derive(Component)]
+struct Card {
+    index: usize,
+    home: Vec3,
+}
+
+/// 見出しのテキスト
+#[derive(Component)]
+struct Title;
+
+/// 状態を表示するテキスト
+#[derive(Component)]
+struct StatusText;
+
+/// 演出の最後まで進んだカードの数
+#[derive(Resource, Default)]
+struct FinishedCards(usize);
+
+/// タイムラインの `call` の手順で実行するワンショットのシステム
+#[derive(Resource)]
+struct Callbacks {
+    card_finished: SystemId<In<Entity>>,
+}
+
+impl FromWorld for Callbacks {
+    fn from_world(world: &mut World) -> Self {
+        Callbacks {
+            card_finished: world.register_system(card_finished),
+        }
+    }
+}
+
+/// カメラ・カード・見出し・状態の表示を配置する
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    let left = -CARD_SPACING * (CARD_COUNT - 1) as f32 / 2.0;
+    for index in 0..CARD_COUNT {
+        let home = Vec3::new(left + CARD_SPACING * index as f32, 0.0, 0.0);
+        commands.spawn((
+            Sprite::from_color(
+                Color::hsl(360.0 * index as f32 / CARD_COUNT as f32, 0.7, 0.6),
+                CARD_SIZE,
+            ),
+            Transform::from_translation(home),
+            Card { index, home },
+        ));
+    }
+
+    commands.spawn((
+        Text2d::new("Sequencer"),
+        TextFont {
+            font_size: 48.0,
+            ..default()
+        },
+        Transform::from_xyz(0.0, TITLE_Y, 0.0),
+        Title,
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+}
+
+/// カードと見出しを最初の状態に戻し、タイムラインを再生し直す
+fn play(
+    mut commands: Commands,
+    callbacks: Res<Callbacks>,
+    mut finished: ResMut<FinishedCards>,
+    mut cards: Query<(Entity, &Card, &mut Transform, &mut Sprite)>,
+    title: Single<(Entity, &mut TextColor), With<Title>>,
+) {
+    finished.0 = 0;
+
+    for (entity, card, mut transform, mut sprite) in &mut cards {
+        // 再生中のトゥイーンが残っていると戻した値を上書きしてしまうので、先に止める
+        commands.entity(entity).insert(Tweens::default());
+        *transform = Transform::from_translation(card.home + Vec3::Y * DROP_HEIGHT);
+        sprite.color.set_alpha(0.0);
+
+        // 落ちながら現れる → 傾ける → 少し待つ → 弾むのと同時に、終わったことを知らせる
+        let timeline = seq()
+            .wait(STAGGER * card.index as f32)
+            .parallel([
+                seq().move_to(card.home, 0.6).ease(EaseFunction::BounceOut),
+                seq().fade_in(0.3),
+            ])
+            .rotate_to(Quat::from_rotation_z(FRAC_PI_4), 0.4)
+            .ease(EaseFunction::CubicInOut)
+            .wait(0.3)
+            .parallel([
+                seq()
+                    .scale_to(Vec3::splat(1.3), 0.15)
+                    .ease(EaseFunction::QuadraticOut)
+                    .scale_to(Vec3::ONE, 0.3)
+                    .ease(EaseFunction::BackOut),
+                seq().call(callbacks.card_finished),
+            ])
+            .build();
+        commands.entity(entity).insert(timeline);
+    }
+
+    // 見出しはカードが落ち始めてから現れ、少しのあいだ表示して消える
+    let (title, mut color) = title.into_inner();
+    color.0.set_alpha(0.0);
+    commands.entity(title).insert((
+        Tweens::default(),
+        seq().wait(0.5).fade_in(0.8).wait(1.5).fade_out(0.8).build(),
+    ));
+}
+
+/// 演出の最後まで進んだカードを数える (タイムラインの `call` の手順から実行される)
+fn card_finished(In(entity): In<Entity>, mut finished: ResMut<FinishedCards>) {
+    finished.0 += 1;
+    info!("card {entity} finished its timeline");
+}
+
+/// 演出の最後まで進んだカードの数を表示する
+fn update_status(finished: Res<FinishedCards>, mut status: Single<&mut Text, With<StatusText>>) {
+    status.0 = format!(
+        "Finished cards: {} / {CARD_COUNT}\nSpace: replay",
+        finished.0
+    );
+}
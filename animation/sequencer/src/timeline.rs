@@ -0,0 +1,197 @@
+use bevy::{ecs::system::SystemId, prelude::*};
+
+use crate::tween::{Tween, TweenId, TweenTarget, Tweens};
+
+/// 空のシーケンスを作る (`seq().move_to(..).wait(0.5).fade_out(..)` のように手順をつなげていく)
+pub fn seq() -> Sequence {
+    Sequence::default()
+}
+
+/// シーケンスの 1 つの手順
+#[derive(Clone)]
+enum Step {
+    Tween(Tween),               // トゥイーンを再生し、終わるまで待つ
+    Wait(f32),                  // 指定した秒数だけ待つ
+    Parallel(Vec<Sequence>),    // 複数のトラックを同時に進め、すべて終わるまで待つ
+    Call(SystemId<In<Entity>>), // ワンショットのシステムをエンティティを渡して実行する
+}
+
+/// 順番に実行する手順の並び (コードで組み立て、[`Sequence::build`] でコンポーネントにする)
+#[derive(Clone, Default)]
+pub struct Sequence {
+    steps: Vec<Step>,
+}
+
+impl Sequence {
+    /// トゥイーンを再生する手順を追加する
+    pub fn tween(mut self, tween: Tween) -> Self {
+        self.steps.push(Step::Tween(tween));
+        self
+    }
+
+    /// 位置を `translation` まで動かす
+    pub fn move_to(self, translation: Vec3, duration: f32) -> Self {
+        self.tween(Tween::new(TweenTarget::Translation(translation), duration))
+    }
+
+    /// 大きさを `scale` まで変える
+    pub fn scale_to(self, scale: Vec3, duration: f32) -> Self {
+        self.tween(Tween::new(TweenTarget::Scale(scale), duration))
+    }
+
+    /// 向きを `rotation` まで回す
+    pub fn rotate_to(self, rotation: Quat, duration: f32) -> Self {
+        self.tween(Tween::new(TweenTarget::Rotation(rotation), duration))
+    }
+
+    /// 不透明度を `alpha` まで変える
+    pub fn fade_to(self, alpha: f32, duration: f32) -> Self {
+        self.tween(Tween::new(TweenTarget::Alpha(alpha), duration))
+    }
+
+    /// 不透明にする
+    pub fn fade_in(self, duration: f32) -> Self {
+        self.fade_to(1.0, duration)
+    }
+
+    /// 透明にする
+    pub fn fade_out(self, duration: f32) -> Self {
+        self.fade_to(0.0, duration)
+    }
+
+    /// 直前に追加したトゥイーンのイージングを変える (直前の手順がトゥイーンでなければ何もしない)
+    pub fn ease(mut self, ease: EaseFunction) -> Self {
+        if let Some(Step::Tween(tween)) = self.steps.last_mut() {
+            tween.ease = ease;
+        }
+        self
+    }
+
+    /// `seconds` 秒だけ待つ
+    pub fn wait(mut self, seconds: f32) -> Self {
+        self.steps.push(Step::Wait(seconds));
+        self
+    }
+
+    /// 複数のシーケンスを同時に進め、いちばん長いものが終わるまで待つ
+    pub fn parallel(mut self, tracks: impl IntoIterator<Item = Sequence>) -> Self {
+        self.steps
+            .push(Step::Parallel(tracks.into_iter().collect()));
+        self
+    }
+
+    /// ワンショットのシステムを実行する (システムにはタイムラインを再生しているエンティティが渡される)
+    pub fn call(mut self, system: SystemId<In<Entity>>) -> Self {
+        self.steps.push(Step::Call(system));
+        self
+    }
+
+    /// エンティティに追加して再生するタイムラインにする
+    pub fn build(self) -> Timeline {
+        Timeline {
+            root: Track::new(self),
+        }
+    }
+}
+
+/// シーケンスを再生するコンポーネント
+///
+/// トゥイーンの再生は [`Tweens`] に任せ、手順が終わったかどうかだけを見て次の手順へ進む。
+/// すべての手順が終わると、エンティティから取り除かれる。
+#[derive(Component)]
+#[require(Tweens)]
+pub struct Timeline {
+    root: Track,
+}
+
+/// 再生中の手順の状態
+enum StepState {
+    Pending,              // まだ始めていない
+    Tweening(TweenId),    // トゥイーンの終わりを待っている
+    Waiting(f32),         // 残りの待ち時間 (秒)
+    Parallel(Vec<Track>), // 同時に進めているトラック
+}
+
+/// シーケンスの再生位置
+struct Track {
+    steps: Vec<Step>,
+    index: usize,
+    state: StepState,
+}
+
+impl Track {
+    fn new(sequence: Sequence) -> Self {
+        Track {
+            steps: sequence.steps,
+            index: 0,
+            state: StepState::Pending,
+        }
+    }
+
+    /// 手順を進め、すべての手順が終わったら `true` を返す
+    ///
+    /// 終わった手順の次の手順は同じフレームのうちに始めるので、待ち時間のない手順は続けて実行される。
+    fn advance(
+        &mut self,
+        entity: Entity,
+        tweens: &mut Tweens,
+        delta: f32,
+        commands: &mut Commands,
+    ) -> bool {
+        while let Some(step) = self.steps.get(self.index) {
+            let finished = match &mut self.state {
+                StepState::Pending => {
+                    self.state = match step {
+                        Step::Tween(tween) => StepState::Tweening(tweens.start(*tween)),
+                        Step::Wait(seconds) => StepState::Waiting(*seconds),
+                        Step::Parallel(tracks) => {
+                            StepState::Parallel(tracks.iter().cloned().map(Track::new).collect())
+                        }
+                        Step::Call(system) => {
+                            commands.run_system_with_input(*system, entity);
+                            self.index += 1;
+                            continue;
+                        }
+                    };
+                    continue;
+                }
+                // トゥイーンは `animate_tweens` が進め、終わったら一覧から取り除く
+                StepState::Tweening(id) => !tweens.is_running(*id),
+                StepState::Waiting(remaining) => {
+                    *remaining -= delta;
+                    *remaining <= 0.0
+                }
+                StepState::Parallel(tracks) => {
+                    // 終わったトラックがあっても、残りのトラックは進め続ける
+                    let mut all_finished = true;
+                    for track in tracks {
+                        all_finished &= track.advance(entity, tweens, delta, commands);
+                    }
+                    all_finished
+                }
+            };
+            if !finished {
+                return false;
+            }
+            self.index += 1;
+            self.state = StepState::Pending;
+        }
+        true
+    }
+}
+
+/// タイムラインの手順を進め、すべて終わったタイムラインを取り除く
+pub fn advance_timelines(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Timeline, &mut Tweens)>,
+) {
+    for (entity, mut timeline, mut tweens) in &mut query {
+        if timeline
+            .root
+            .advance(entity, &mut tweens, time.delta_secs(), &mut commands)
+        {
+            commands.entity(entity).remove::<Timeline>();
+        }
+    }
+}
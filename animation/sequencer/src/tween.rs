@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+
+/// トゥイーンで変える値と、その目標の値
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TweenTarget {
+    Translation(Vec3), // 位置
+    Scale(Vec3),       // 大きさ
+    Rotation(Quat),    // 向き
+    Alpha(f32),        // スプライトかテキストの不透明度
+}
+
+impl TweenTarget {
+    /// 目標と同じ種類の、いまの値を読み取る (色を持たないエンティティの不透明度は 1 とみなす)
+    fn current(
+        &self,
+        transform: &Transform,
+        sprite: Option<&Sprite>,
+        text_color: Option<&TextColor>,
+    ) -> TweenTarget {
+        match self {
+            TweenTarget::Translation(_) => TweenTarget::Translation(transform.translation),
+            TweenTarget::Scale(_) => TweenTarget::Scale(transform.scale),
+            TweenTarget::Rotation(_) => TweenTarget::Rotation(transform.rotation),
+            TweenTarget::Alpha(_) => TweenTarget::Alpha(
+                sprite
+                    .map(|sprite| sprite.color.alpha())
+                    .or(text_color.map(|color| color.0.alpha()))
+                    .unwrap_or(1.0),
+            ),
+        }
+    }
+}
+
+/// 1 つの値を、指定した時間とイージングで目標の値まで変化させるトゥイーン
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tween {
+    pub target: TweenTarget,
+    pub duration: f32, // 変化にかける時間 (秒)
+    pub ease: EaseFunction,
+}
+
+impl Tween {
+    /// 一定の速さで変化するトゥイーンを作る
+    pub fn new(target: TweenTarget, duration: f32) -> Self {
+        Tween {
+            target,
+            duration,
+            ease: EaseFunction::Linear,
+        }
+    }
+
+    pub fn with_ease(mut self, ease: EaseFunction) -> Self {
+        self.ease = ease;
+        self
+    }
+}
+
+/// 開始したトゥイーンを見分ける番号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TweenId(u32);
+
+/// 再生中のトゥイーン
+struct Running {
+    id: TweenId,
+    tween: Tween,
+    from: Option<TweenTarget>, // 最初に更新したときの値 (開始した時点ではまだ読み取っていない)
+    elapsed: f32,
+}
+
+/// エンティティで再生中のトゥイーンの一覧
+///
+/// 種類の違うトゥイーン (位置と不透明度など) は同時に再生できる。
+/// 同じ種類のトゥイーンが重なったときは、あとから開始したほうの値が残る。
+#[derive(Component, Default)]
+pub struct Tweens {
+    running: Vec<Running>,
+    next_id: u32,
+}
+
+impl Tweens {
+    /// トゥイーンを開始する (変化の始まりの値は、次に更新したときのエンティティの値になる)
+    pub fn start(&mut self, tween: Tween) -> TweenId {
+        let id = TweenId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        self.running.push(Running {
+            id,
+            tween,
+            from: None,
+            elapsed: 0.0,
+        });
+        id
+    }
+
+    /// トゥイーンがまだ再生中か
+    pub fn is_running(&self, id: TweenId) -> bool {
+        self.running.iter().any(|running| running.id == id)
+    }
+
+    /// 再生中のトゥイーンがないか
+    pub fn is_empty(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    /// 再生中のトゥイーンをすべて止める (値はその時点のまま残る)
+    pub fn clear(&mut self) {
+        self.running.clear();
+    }
+}
+
+/// 再生中のトゥイーンを進め、イージングをかけた値をエンティティに書き込む
+pub fn animate_tweens(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut Tweens,
+        &mut Transform,
+        Option<&mut Sprite>,
+        Option<&mut TextColor>,
+    )>,
+) {
+    for (mut tweens, mut transform, mut sprite, mut text_color) in &mut query {
+        if tweens.is_empty() {
+            continue;
+        }
+        for running in &mut tweens.running {
+            let from = *running.from.get_or_insert_with(|| {
+                running
+                    .tween
+                    .target
+                    .current(&transform, sprite.as_deref(), text_color.as_deref())
+            });
+            running.elapsed += time.delta_secs();
+            let progress = if running.tween.duration > 0.0 {
+                (running.elapsed / running.tween.duration).min(1.0)
+            } else {
+                1.0
+            };
+            let t = EasingCurve::new(0.0, 1.0, running.tween.ease).sample_clamped(progress);
+
+            match (from, running.tween.target) {
+                (TweenTarget::Translation(from), TweenTarget::Translation(to)) => {
+                    transform.translation = from.lerp(to, t);
+                }
+                (TweenTarget::Scale(from), TweenTarget::Scale(to)) => {
+                    transform.scale = from.lerp(to, t);
+                }
+                (TweenTarget::Rotation(from), TweenTarget::Rotation(to)) => {
+                    transform.rotation = from.slerp(to, t);
+                }
+                (TweenTarget::Alpha(from), TweenTarget::Alpha(to)) => {
+                    let alpha = from.lerp(to, t).clamp(0.0, 1.0);
+                    if let Some(sprite) = sprite.as_deref_mut() {
+                        sprite.color.set_alpha(alpha);
+                    }
+                    if let Some(text_color) = text_color.as_deref_mut() {
+                        text_color.0.set_alpha(alpha);
+                    }
+                }
+                _ => unreachable!("the start value is read from the same kind of target"),
+            }
+        }
+        tweens
+            .running
+            .retain(|running| running.elapsed < running.tween.duration);
+    }
+}
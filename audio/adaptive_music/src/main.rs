@@ -0,0 +1,230 @@
+use bevy::{audio::Volume, prelude::*};
+
+// 敵の最大数
+const MAX_ENEMIES: u32 = 10;
+// プレイヤーの最大体力
+const MAX_HEALTH: u32 = 5;
+// この体力以下になると緊張度が上がる
+const LOW_HEALTH: u32 = 2;
+// 緊張度が目標値に追従する速さ (1 秒あたり)
+const INTENSITY_SMOOTHING: f32 = 0.5;
+// レイヤーがフェードイン・アウトする緊張度の幅
+const FADE_RANGE: f32 = 0.15;
+// レイヤーの音量が変化する速さ (1 秒あたり)
+const VOLUME_FADE_SPEED: f32 = 0.8;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .init_resource::<Gameplay>()
+        .init_resource::<Intensity>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                start_stems,
+                change_gameplay,
+                update_intensity,
+                fade_layers,
+                update_status,
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// 音楽のレイヤー (ステム)
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    Bass,  // 常に鳴っている土台
+    Drums, // 敵が増えてくると加わる
+    Lead,  // 激しい戦闘や体力が少ないときに加わる
+}
+
+impl Layer {
+    const ALL: [Layer; 3] = [Layer::Bass, Layer::Drums, Layer::Lead];
+
+    fn path(self) -> &'static str {
+        match self {
+            Layer::Bass => "music/bass.wav",
+            Layer::Drums => "music/drums.wav",
+            Layer::Lead => "music/lead.wav",
+        }
+    }
+
+    /// このレイヤーが鳴り始める緊張度
+    fn threshold(self) -> f32 {
+        match self {
+            Layer::Bass => 0.0,
+            Layer::Drums => 0.3,
+            Layer::Lead => 0.65,
+        }
+    }
+
+    /// 緊張度に対するこのレイヤーの目標音量
+    fn target_volume(self, intensity: f32) -> f32 {
+        if self.threshold() <= 0.0 {
+            return 1.0;
+        }
+        ((intensity - self.threshold()) / FADE_RANGE + 0.5).clamp(0.0, 1.0)
+    }
+}
+
+/// 全ステムのハンドルと、再生を開始したかどうか
+#[derive(Resource)]
+struct Stems {
+    handles: Vec<(Layer, Handle<AudioSource>)>,
+    started: bool,
+}
+
+/// 緊張度の元になるゲームの状態 (このサンプルではキー操作で変更する)
+#[derive(Resource, Debug)]
+struct Gameplay {
+    enemies: u32,
+    health: u32,
+}
+
+impl Default for Gameplay {
+    fn default() -> Self {
+        Self {
+            enemies: 0,
+            health: MAX_HEALTH,
+        }
+    }
+}
+
+impl Gameplay {
+    /// 敵の数と体力から目標の緊張度 (0.0 ~ 1.0) を計算する
+    fn intensity(&self) -> f32 {
+        let enemies = self.enemies as f32 / MAX_ENEMIES as f32;
+        let danger = if self.health <= LOW_HEALTH { 0.4 } else { 0.0 };
+        (enemies * 0.8 + danger).min(1.0)
+    }
+}
+
+/// 音楽に反映される現在の緊張度 (ゲームの状態に少しずつ追従する)
+#[derive(Resource, Default, Deref, DerefMut)]
+struct Intensity(f32);
+
+/// 状態を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct StatusText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2d);
+
+    commands.insert_resource(Stems {
+        handles: Layer::ALL
+            .iter()
+            .map(|&layer| (layer, asset_server.load(layer.path())))
+            .collect(),
+        started: false,
+    });
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+}
+
+/// すべてのステムの読み込みが終わったら、同じフレームでまとめて再生を開始する
+///
+/// 読み込みが終わった順に再生するとステム同士がずれるため、全部揃うまで待つ。
+/// 再生開始後は音量だけを変更し、再生位置には触れない。
+fn start_stems(
+    mut commands: Commands,
+    mut stems: ResMut<Stems>,
+    asset_server: Res<AssetServer>,
+    intensity: Res<Intensity>,
+) {
+    if stems.started
+        || !stems
+            .handles
+            .iter()
+            .all(|(_, handle)| asset_server.is_loaded_with_dependencies(handle))
+    {
+        return;
+    }
+    stems.started = true;
+
+    for (layer, handle) in &stems.handles {
+        commands.spawn((
+            AudioPlayer(handle.clone()),
+            PlaybackSettings::LOOP.with_volume(Volume::new(layer.target_volume(**intensity))),
+            *layer,
+        ));
+    }
+}
+
+/// キー操作でゲームの状態を変更する
+fn change_gameplay(keyboard_input: Res<ButtonInput<KeyCode>>, mut gameplay: ResMut<Gameplay>) {
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        gameplay.enemies = (gameplay.enemies + 1).min(MAX_ENEMIES);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        gameplay.enemies = gameplay.enemies.saturating_sub(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        gameplay.health = gameplay.health.saturating_sub(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        gameplay.health = (gameplay.health + 1).min(MAX_HEALTH);
+    }
+}
+
+/// 緊張度をゲームの状態から計算した値に少しずつ近づける
+fn update_intensity(gameplay: Res<Gameplay>, mut intensity: ResMut<Intensity>, time: Res<Time>) {
+    let target = gameplay.intensity();
+    let max_step = INTENSITY_SMOOTHING * time.delta_secs();
+    **intensity += (target - **intensity).clamp(-max_step, max_step);
+}
+
+/// 緊張度に合わせて各レイヤーの音量をフェードさせる
+fn fade_layers(sinks: Query<(&AudioSink, &Layer)>, intensity: Res<Intensity>, time: Res<Time>) {
+    let max_step = VOLUME_FADE_SPEED * time.delta_secs();
+    for (sink, layer) in &sinks {
+        let target = layer.target_volume(**intensity);
+        let volume = sink.volume();
+        sink.set_volume(volume + (target - volume).clamp(-max_step, max_step));
+    }
+}
+
+/// 現在の状態と各レイヤーの音量を表示する
+fn update_status(
+    mut status: Single<&mut Text, With<StatusText>>,
+    gameplay: Res<Gameplay>,
+    intensity: Res<Intensity>,
+    stems: Res<Stems>,
+    sinks: Query<(&AudioSink, &Layer)>,
+) {
+    let mut lines = vec![
+        "Up/Down: enemies, Left/Right: health".to_string(),
+        String::new(),
+        format!("Enemies: {} / {}", gameplay.enemies, MAX_ENEMIES),
+        format!("Health: {} / {}", gameplay.health, MAX_HEALTH),
+        format!("Intensity: {:.2}", **intensity),
+        String::new(),
+    ];
+    if !stems.started {
+        lines.push("Loading stems...".to_string());
+    }
+    for layer in Layer::ALL {
+        let Some((sink, _)) = sinks.iter().find(|(_, l)| **l == layer) else {
+            continue;
+        };
+        let bar = "#".repeat((sink.volume() * 20.0).round() as usize);
+        lines.push(format!(
+            "{:<6} {:<20} {:.2}",
+            format!("{layer:?}"),
+            bar,
+            sink.volume()
+        ));
+    }
+    status.0 = lines.join("\n");
+}
@@ -0,0 +1,239 @@
+//! 優先度付きのボイス (アナウンス) を 1 つずつ再生し、字幕を表示するプラグイン。
+//!
+//! - 再生中のボイスより優先度の高いボイスが来ると、再生中のものを中断して割り込む。
+//! - 優先度が同じか低いボイスは待ち行列に入り、再生中のボイスが終わってから再生される。
+//! - ボイスごとにクールダウンを持ち、同じボイスが短時間に連続して流れないようにする。
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+// 待ち行列に積めるボイスの最大数 (溢れた分は優先度の低いものから捨てる)
+const MAX_QUEUED_LINES: usize = 4;
+// 字幕のフォントサイズと色
+const SUBTITLE_FONT_SIZE: f32 = 24.0;
+const SUBTITLE_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+const SUBTITLE_BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+/// アナウンスの再生と字幕表示を行うプラグイン
+pub struct AnnouncerPlugin;
+
+impl Plugin for AnnouncerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Announce>()
+            .init_resource::<Announcer>()
+            .init_resource::<AnnouncerSettings>()
+            .add_systems(Startup, spawn_subtitle)
+            .add_systems(
+                Update,
+                (receive_announcements, advance_announcer, update_subtitle).chain(),
+            );
+    }
+}
+
+/// 1 つのボイス (アナウンス) の定義
+#[derive(Debug, Clone)]
+pub struct VoiceLine {
+    pub id: &'static str,                   // クールダウンの管理に使う識別子
+    pub subtitle: String,                   // 字幕として表示する文字列
+    pub sound: Option<Handle<AudioSource>>, // 再生する音声 (なければ字幕だけ表示)
+    pub priority: u8,                       // 大きいほど優先度が高い
+    pub duration: f32,                      // 再生時間 (秒)。字幕もこの時間だけ表示する
+    pub cooldown: f32, // 再生後、同じボイスを再び流せるようになるまでの時間 (秒)
+}
+
+/// ボイスの再生を要求するイベント
+#[derive(Event, Debug, Clone)]
+pub struct Announce(pub VoiceLine);
+
+/// アナウンサーの設定
+#[derive(Resource, Debug)]
+pub struct AnnouncerSettings {
+    pub subtitles: bool, // 字幕を表示するかどうか
+}
+
+impl Default for AnnouncerSettings {
+    fn default() -> Self {
+        AnnouncerSettings { subtitles: true }
+    }
+}
+
+/// 再生中のボイス
+#[derive(Debug)]
+struct Playing {
+    line: VoiceLine,
+    audio: Option<Entity>, // 中断するときに削除するオーディオエンティティ
+    timer: Timer,
+}
+
+/// アナウンスの再生状態を管理するリソース
+#[derive(Resource, Default, Debug)]
+pub struct Announcer {
+    playing: Option<Playing>,
+    queue: Vec<VoiceLine>,
+    cooldowns: HashMap<&'static str, f64>, // ボイスごとの再び再生できるようになる時刻
+}
+
+impl Announcer {
+    /// 再生中のボイスの識別子
+    pub fn current(&self) -> Option<&'static str> {
+        self.playing.as_ref().map(|playing| playing.line.id)
+    }
+
+    /// 待ち行列に入っているボイスの識別子
+    pub fn queued(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.queue.iter().map(|line| line.id)
+    }
+
+    /// 指定したボイスのクールダウンの残り時間 (秒)
+    pub fn cooldown_remaining(&self, id: &str, now: f64) -> f64 {
+        self.cooldowns
+            .get(id)
+            .map_or(0.0, |ready_at| (ready_at - now).max(0.0))
+    }
+
+    fn is_cooling_down(&self, id: &str, now: f64) -> bool {
+        self.cooldown_remaining(id, now) > 0.0
+    }
+
+    /// 待ち行列にボイスを積む。優先度の高い順に並べ、溢れた分は捨てる
+    fn enqueue(&mut self, line: VoiceLine) {
+        if self.queue.iter().any(|queued| queued.id == line.id) {
+            return;
+        }
+        // 同じ優先度なら先に来たものを先に再生する
+        let index = self
+            .queue
+            .iter()
+            .position(|queued| queued.priority < line.priority)
+            .unwrap_or(self.queue.len());
+        self.queue.insert(index, line);
+        self.queue.truncate(MAX_QUEUED_LINES);
+    }
+}
+
+/// 字幕のテキストを示すコンポーネント
+#[derive(Component)]
+struct Subtitle;
+
+/// ボイスの音声を再生しているエンティティを示すコンポーネント
+#[derive(Component)]
+struct VoicePlayback;
+
+/// 画面下部に字幕用のテキストを配置する
+fn spawn_subtitle(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: SUBTITLE_FONT_SIZE,
+            ..default()
+        },
+        TextColor(SUBTITLE_COLOR),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(40.0),
+            justify_self: JustifySelf::Center,
+            padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(SUBTITLE_BACKGROUND_COLOR),
+        Visibility::Hidden,
+        Subtitle,
+    ));
+}
+
+/// 再生要求を受け取り、割り込み・待ち行列・破棄のいずれかに振り分ける
+fn receive_announcements(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut events: EventReader<Announce>,
+    mut announcer: ResMut<Announcer>,
+    playbacks: Query<(), With<VoicePlayback>>,
+) {
+    let now = time.elapsed_secs_f64();
+    for Announce(line) in events.read() {
+        if announcer.is_cooling_down(line.id, now) {
+            debug!("{} is cooling down", line.id);
+            continue;
+        }
+
+        let current_priority = announcer
+            .playing
+            .as_ref()
+            .map(|playing| playing.line.priority);
+        match current_priority {
+            // 何も再生していなければすぐに再生する
+            None => play(&mut commands, &mut announcer, line.clone(), now),
+            // 再生中のボイスより優先度が高ければ中断して割り込む
+            Some(priority) if line.priority > priority => {
+                if let Some(interrupted) = announcer.playing.take() {
+                    debug!("{} interrupted {}", line.id, interrupted.line.id);
+                    if let Some(audio) = interrupted.audio.filter(|e| playbacks.contains(*e)) {
+                        commands.entity(audio).despawn();
+                    }
+                }
+                play(&mut commands, &mut announcer, line.clone(), now);
+            }
+            // それ以外は再生中のボイスが終わるまで待つ
+            Some(_) => announcer.enqueue(line.clone()),
+        }
+    }
+}
+
+/// 再生中のボイスの時間を進め、終わったら待ち行列の先頭を再生する
+fn advance_announcer(mut commands: Commands, time: Res<Time>, mut announcer: ResMut<Announcer>) {
+    if let Some(playing) = announcer.playing.as_mut() {
+        if !playing.timer.tick(time.delta()).finished() {
+            return;
+        }
+        announcer.playing = None;
+    }
+
+    // 待っている間にクールダウン中になったボイスは飛ばす
+    let now = time.elapsed_secs_f64();
+    while !announcer.queue.is_empty() {
+        let line = announcer.queue.remove(0);
+        if !announcer.is_cooling_down(line.id, now) {
+            play(&mut commands, &mut announcer, line, now);
+            break;
+        }
+    }
+}
+
+/// ボイスを再生し、クールダウンを開始する
+fn play(commands: &mut Commands, announcer: &mut Announcer, line: VoiceLine, now: f64) {
+    let audio = line.sound.clone().map(|sound| {
+        commands
+            .spawn((AudioPlayer(sound), PlaybackSettings::DESPAWN, VoicePlayback))
+            .id()
+    });
+    announcer
+        .cooldowns
+        .insert(line.id, now + f64::from(line.cooldown));
+    announcer.playing = Some(Playing {
+        timer: Timer::from_seconds(line.duration, TimerMode::Once),
+        line,
+        audio,
+    });
+}
+
+/// 再生中のボイスの字幕を表示する
+fn update_subtitle(
+    announcer: Res<Announcer>,
+    settings: Res<AnnouncerSettings>,
+    subtitle: Single<(&mut Text, &mut Visibility), With<Subtitle>>,
+) {
+    if !announcer.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let (mut text, mut visibility) = subtitle.into_inner();
+    match announcer.playing.as_ref() {
+        Some(playing) if settings.subtitles => {
+            text.0.clone_from(&playing.line.subtitle);
+            *visibility = Visibility::Inherited;
+        }
+        _ => *visibility = Visibility::Hidden,
+    }
+}
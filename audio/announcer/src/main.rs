@@ -0,0 +1,135 @@
+use announcer::{Announce, Announcer, AnnouncerPlugin, AnnouncerSettings, VoiceLine};
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, AnnouncerPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (request_lines, toggle_subtitles, update_status))
+        .run();
+}
+
+/// キーに割り当てたボイスの一覧を保持するリソース
+#[derive(Resource)]
+struct VoiceLines(Vec<(KeyCode, VoiceLine)>);
+
+/// 再生状態を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct StatusText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2d);
+
+    // 優先度: 撃破報告 < ウェーブ開始 < シールド警告 < ボス接近
+    let lines = vec![
+        (
+            KeyCode::Digit1,
+            VoiceLine {
+                id: "enemy_down",
+                subtitle: "Enemy down!".to_string(),
+                sound: Some(asset_server.load("voices/enemy_down.wav")),
+                priority: 0,
+                duration: 0.3,
+                cooldown: 2.0,
+            },
+        ),
+        (
+            KeyCode::Digit2,
+            VoiceLine {
+                id: "wave_incoming",
+                subtitle: "Wave incoming! Get ready.".to_string(),
+                sound: Some(asset_server.load("voices/wave_incoming.wav")),
+                priority: 1,
+                duration: 1.0,
+                cooldown: 5.0,
+            },
+        ),
+        (
+            KeyCode::Digit3,
+            VoiceLine {
+                id: "shield_low",
+                subtitle: "Warning! Shield low.".to_string(),
+                sound: Some(asset_server.load("voices/shield_low.wav")),
+                priority: 2,
+                duration: 1.3,
+                cooldown: 4.0,
+            },
+        ),
+        (
+            KeyCode::Digit4,
+            VoiceLine {
+                id: "boss_approaching",
+                subtitle: "A huge enemy is approaching!".to_string(),
+                sound: Some(asset_server.load("voices/boss_approaching.wav")),
+                priority: 3,
+                duration: 2.4,
+                cooldown: 10.0,
+            },
+        ),
+    ];
+    commands.insert_resource(VoiceLines(lines));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+}
+
+/// 数字キーで対応するボイスの再生を要求する
+fn request_lines(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    lines: Res<VoiceLines>,
+    mut announce: EventWriter<Announce>,
+) {
+    for (key, line) in &lines.0 {
+        if keyboard_input.just_pressed(*key) {
+            announce.send(Announce(line.clone()));
+        }
+    }
+}
+
+/// `S` キーで字幕の表示・非表示を切り替える
+fn toggle_subtitles(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<AnnouncerSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyS) {
+        settings.subtitles = !settings.subtitles;
+    }
+}
+
+/// 再生中のボイス・待ち行列・クールダウンを表示する
+fn update_status(
+    time: Res<Time>,
+    announcer: Res<Announcer>,
+    lines: Res<VoiceLines>,
+    settings: Res<AnnouncerSettings>,
+    mut text: Single<&mut Text, With<StatusText>>,
+) {
+    let now = time.elapsed_secs_f64();
+    let mut status = String::from("1-4: request a voice line (higher number = higher priority)\n");
+    status += &format!(
+        "S: subtitles {}\n\n",
+        if settings.subtitles { "on" } else { "off" }
+    );
+    status += &format!("Playing: {}\n", announcer.current().unwrap_or("-"));
+    status += &format!(
+        "Queued: {}\n\n",
+        announcer.queued().collect::<Vec<_>>().join(", ")
+    );
+    for (key, line) in &lines.0 {
+        status += &format!(
+            "{key:?} {} (priority {}) cooldown {:.1}s\n",
+            line.id,
+            line.priority,
+            announcer.cooldown_remaining(line.id, now)
+        );
+    }
+    text.0 = status;
+}
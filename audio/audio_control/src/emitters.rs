@@ -0,0 +1,317 @@
+use std::time::Duration;
+
+use bevy::{
+    audio::{SpatialScale, Volume},
+    color::palettes::css::{DIM_GRAY, GOLD, SKY_BLUE},
+    prelude::*,
+};
+
+// エミッターの表示と音の有効・無効を切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::KeyE;
+// エミッターの数
+const EMITTER_COUNT: usize = 100;
+// エミッターを並べる格子の列数
+const EMITTER_COLUMNS: usize = 10;
+// エミッターの間隔 (ピクセル)
+const EMITTER_SPACING: f32 = 60.0;
+// 同時に鳴らす音 (オーディオシンク) の最大数
+const MAX_VOICES: usize = 8;
+// リスナーからこの距離より遠いエミッターは鳴らさない (ピクセル)
+const AUDIBLE_RANGE: f32 = 200.0;
+// 鳴っているエミッターを近く扱う距離 (近さの順位が少し入れ替わっただけで鳴らし直さないようにする)
+const HYSTERESIS: f32 = 30.0;
+// リスナーの移動速度 (ピクセル/秒)
+const LISTENER_SPEED: f32 = 250.0;
+// リスナーの両耳の間隔 (ピクセル)
+const EAR_GAP: f32 = 40.0;
+// 空間オーディオの縮尺 (100 ピクセルを 1 メートルとみなす)
+const AUDIO_SCALE: f32 = 1.0 / 100.0;
+// さえずりの周期 (秒)
+const CHIRP_PERIOD: f32 = 0.8;
+// 1 回のさえずりの長さ (秒)
+const CHIRP_LENGTH: f32 = 0.12;
+
+/// 多数の音源 (エミッター) のうち、リスナーに近いものだけを鳴らすプラグイン
+///
+/// `E` キーで有効にすると、100 個のエミッターが格子状に並び、それぞれが違う高さの音でさえずる。
+/// 実際に `AudioPlayer` を生成するのは聞こえる範囲にある近い順の `MAX_VOICES` 個だけで、
+/// リスナーが移動すると遠くなったエミッターの音を止め、空いた枠を近づいたエミッターに割り当て直す。
+pub struct EmittersPlugin;
+
+impl Plugin for EmittersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EmittersEnabled>()
+            .add_systems(Startup, setup_emitters)
+            .add_systems(
+                Update,
+                (
+                    toggle_emitters,
+                    move_listener.run_if(emitters_enabled),
+                    assign_voices,
+                    chirp,
+                    color_emitters,
+                    update_voice_text,
+                    draw_audible_range.run_if(emitters_enabled),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// エミッターが有効かどうか (無効な間はどのエミッターも鳴らさない)
+#[derive(Resource, Default, Deref, DerefMut)]
+struct EmittersEnabled(bool);
+
+/// 位置を持つ音源を示すコンポーネント (音そのものは子の `Voice` が鳴らす)
+#[derive(Component)]
+struct Emitter {
+    sound: Handle<Pitch>, // さえずりの音 (エミッターごとに高さが違う)
+    phase: f32,           // さえずりの周期のずれ (秒)
+}
+
+/// エミッターに割り当てた `Voice` のエンティティ (割り当てられていないエミッターは持たない)
+#[derive(Component)]
+struct Voiced(Entity);
+
+/// エミッターの音を実際に鳴らすエンティティ (エミッターの子として生成する)
+#[derive(Component)]
+struct Voice;
+
+/// リスナー (矢印キーで動かす) を示すコンポーネント
+#[derive(Component)]
+struct Listener;
+
+/// 鳴っている音の数を表示するテキスト
+#[derive(Component)]
+struct VoiceText;
+
+/// エミッターの見た目 (鳴っているかどうかで色を変える)
+#[derive(Resource)]
+struct EmitterMaterials {
+    silent: Handle<ColorMaterial>,
+    voiced: Handle<ColorMaterial>,
+}
+
+/// エミッターが有効かどうかを判定する条件関数
+fn emitters_enabled(enabled: Res<EmittersEnabled>) -> bool {
+    **enabled
+}
+
+/// カメラ・リスナー・エミッター・説明のテキストを配置する
+fn setup_emitters(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    commands.spawn(Camera2d);
+
+    let emitter_materials = EmitterMaterials {
+        silent: materials.add(Color::from(DIM_GRAY)),
+        voiced: materials.add(Color::from(GOLD)),
+    };
+    let emitter_mesh = meshes.add(Circle::new(8.0));
+
+    // エミッターを格子状に並べ、左上から順に少しずつ高い音 (2 オクターブ分) を割り当てる
+    let rows = EMITTER_COUNT.div_ceil(EMITTER_COLUMNS);
+    let top_left =
+        Vec2::new(-((EMITTER_COLUMNS - 1) as f32), (rows - 1) as f32) * EMITTER_SPACING / 2.0;
+    for i in 0..EMITTER_COUNT {
+        let cell = Vec2::new(
+            (i % EMITTER_COLUMNS) as f32,
+            -((i / EMITTER_COLUMNS) as f32),
+        );
+        let frequency = 440.0 * 2f32.powf(2.0 * i as f32 / EMITTER_COUNT as f32);
+        commands.spawn((
+            Mesh2d(emitter_mesh.clone()),
+            MeshMaterial2d(emitter_materials.silent.clone()),
+            Transform::from_translation((top_left + cell * EMITTER_SPACING).extend(0.0)),
+            Visibility::Hidden,
+            Emitter {
+                sound: pitches.add(Pitch::new(frequency, Duration::from_secs(1))),
+                phase: (i as f32 * 0.37) % CHIRP_PERIOD,
+            },
+        ));
+    }
+    commands.insert_resource(emitter_materials);
+
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(12.0))),
+        MeshMaterial2d(materials.add(Color::from(SKY_BLUE))),
+        Transform::from_xyz(0.0, 0.0, 1.0),
+        Visibility::Hidden,
+        SpatialListener::new(EAR_GAP),
+        Listener,
+    ));
+
+    commands.spawn((
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        VoiceText,
+    ));
+}
+
+/// `E` キーでエミッターとリスナーの表示を切り替える (音は `assign_voices` が止める)
+#[allow(clippy::type_complexity)]
+fn toggle_emitters(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut enabled: ResMut<EmittersEnabled>,
+    mut visibilities: Query<&mut Visibility, Or<(With<Emitter>, With<Listener>)>>,
+) {
+    if !keyboard_input.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+    **enabled = !**enabled;
+    for mut visibility in &mut visibilities {
+        visibility.toggle_visible_hidden();
+    }
+}
+
+/// 矢印キーでリスナーを動かす
+fn move_listener(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut listener: Single<&mut Transform, With<Listener>>,
+) {
+    let mut direction = Vec2::ZERO;
+    if keyboard_input.pressed(KeyCode::ArrowLeft) {
+        direction.x -= 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::ArrowRight) {
+        direction.x += 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::ArrowDown) {
+        direction.y -= 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::ArrowUp) {
+        direction.y += 1.0;
+    }
+    let delta = direction.normalize_or_zero() * LISTENER_SPEED * time.delta_secs();
+    listener.translation += delta.extend(0.0);
+}
+
+/// 聞こえる範囲にある近い順の `MAX_VOICES` 個のエミッターにだけ `Voice` を割り当てる
+///
+/// 先に割り当てを外してから新しく割り当てるので、オーディオシンクの数は常に `MAX_VOICES` 以下になる。
+/// 鳴っているエミッターは `HYSTERESIS` だけ近いものとして扱い、境目で鳴らし直しを繰り返さないようにする。
+fn assign_voices(
+    mut commands: Commands,
+    enabled: Res<EmittersEnabled>,
+    listener: Single<&Transform, With<Listener>>,
+    emitters: Query<(Entity, &Transform, &Emitter, Option<&Voiced>)>,
+) {
+    let listener_position = listener.translation.truncate();
+
+    let mut nearest: Vec<(f32, Entity)> = Vec::new();
+    if **enabled {
+        for (entity, transform, _, voiced) in &emitters {
+            let distance = transform.translation.truncate().distance(listener_position);
+            if distance > AUDIBLE_RANGE {
+                continue;
+            }
+            let bias = if voiced.is_some() { HYSTERESIS } else { 0.0 };
+            nearest.push((distance - bias, entity));
+        }
+        nearest.sort_by(|a, b| a.0.total_cmp(&b.0));
+        nearest.truncate(MAX_VOICES);
+    }
+    let should_play = |entity: Entity| nearest.iter().any(|(_, nearest)| *nearest == entity);
+
+    // 遠くなったエミッターの音を止める
+    for (entity, _, _, voiced) in &emitters {
+        if let Some(Voiced(voice)) = voiced {
+            if !should_play(entity) {
+                commands.entity(*voice).despawn_recursive();
+                commands.entity(entity).remove::<Voiced>();
+            }
+        }
+    }
+
+    // 空いた枠を近づいたエミッターに割り当てる
+    for (entity, _, emitter, voiced) in &emitters {
+        if voiced.is_none() && should_play(entity) {
+            let voice = commands
+                .spawn((
+                    AudioPlayer(emitter.sound.clone()),
+                    PlaybackSettings::LOOP
+                        .with_spatial(true)
+                        .with_spatial_scale(SpatialScale::new_2d(AUDIO_SCALE))
+                        .with_volume(Volume::new(0.0)), // 音量は `chirp` が決める
+                    Transform::default(), // 親のエミッターと同じ位置で鳴らす
+                    Voice,
+                ))
+                .id();
+            commands
+                .entity(entity)
+                .add_child(voice)
+                .insert(Voiced(voice));
+        }
+    }
+}
+
+/// 鳴っている音の音量を周期的に上げ下げして、さえずっているように聞かせる
+fn chirp(
+    time: Res<Time>,
+    voices: Query<(&SpatialAudioSink, &Parent), With<Voice>>,
+    emitters: Query<&Emitter>,
+) {
+    for (sink, parent) in &voices {
+        let Ok(emitter) = emitters.get(parent.get()) else {
+            continue;
+        };
+        let t = (time.elapsed_secs() + emitter.phase) % CHIRP_PERIOD;
+        let volume = if t < CHIRP_LENGTH {
+            (std::f32::consts::PI * t / CHIRP_LENGTH).sin()
+        } else {
+            0.0
+        };
+        sink.set_volume(volume);
+    }
+}
+
+/// 鳴っているエミッターを明るい色で表示する
+fn color_emitters(
+    materials: Res<EmitterMaterials>,
+    mut emitters: Query<(&mut MeshMaterial2d<ColorMaterial>, Has<Voiced>), With<Emitter>>,
+) {
+    for (mut material, voiced) in &mut emitters {
+        let target = if voiced {
+            &materials.voiced
+        } else {
+            &materials.silent
+        };
+        if material.0 != *target {
+            material.0 = target.clone();
+        }
+    }
+}
+
+/// 鳴っている音の数と操作方法を表示する
+fn update_voice_text(
+    enabled: Res<EmittersEnabled>,
+    voices: Query<(), With<Voice>>,
+    mut text: Single<&mut Text, With<VoiceText>>,
+) {
+    text.0 = if **enabled {
+        format!(
+            "Voices: {} / {MAX_VOICES} (of {EMITTER_COUNT} emitters)\nArrow keys: move listener, E: hide emitters",
+            voices.iter().count()
+        )
+    } else {
+        format!("Press E to show {EMITTER_COUNT} positional emitters")
+    };
+}
+
+/// リスナーの聞こえる範囲を円で描く
+fn draw_audible_range(mut gizmos: Gizmos, listener: Single<&Transform, With<Listener>>) {
+    gizmos.circle_2d(
+        listener.translation.truncate(),
+        AUDIBLE_RANGE,
+        Color::from(SKY_BLUE),
+    );
+}
@@ -1,8 +1,12 @@
 use bevy::{math::ops, prelude::*};
 
+mod emitters;
+
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        // `E` キーで 100 個の音源を表示し、リスナーに近いものだけを鳴らす
+        .add_plugins(emitters::EmittersPlugin)
         .add_systems(Startup, setup)
         .add_systems(Update, (update_speed, pause, volume))
         .run();
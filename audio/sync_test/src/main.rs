@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use bevy::{audio::Pitch, prelude::*};
+use settings::{Settings, SettingsPlugin};
+
+// メトロノームのテンポ (1 分あたりの拍数)
+const BPM: f64 = 120.0;
+// 1 小節あたりの拍数 (小節の頭は音と色を変える)
+const BEATS_PER_BAR: u64 = 4;
+// クリック音の周波数 (小節の頭 / それ以外)
+const ACCENT_FREQUENCY: f32 = 1760.0;
+const CLICK_FREQUENCY: f32 = 880.0;
+// クリック音の長さ
+const CLICK_DURATION: Duration = Duration::from_millis(30);
+// 四角形を光らせる時間 (秒)
+const FLASH_DURATION: f64 = 0.08;
+// 遅延補正の調整幅 (ミリ秒、Shift を押しながらだと細かく調整)
+const LATENCY_STEP_MS: f32 = 5.0;
+const LATENCY_FINE_STEP_MS: f32 = 1.0;
+
+// 四角形のサイズ
+const SQUARE_SIZE: f32 = 200.0;
+// 消灯時・拍の点灯時・小節頭の点灯時の色
+const IDLE_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const BEAT_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+const ACCENT_COLOR: Color = Color::srgb(1.0, 0.5, 0.5);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, SettingsPlugin))
+        .init_resource::<Metronome>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (adjust_latency, play_click, flash_square, update_info_text),
+        )
+        .run();
+}
+
+/// メトロノームの状態を管理するリソース
+#[derive(Resource, Default)]
+struct Metronome {
+    last_clicked_beat: Option<u64>, // 最後にクリック音を鳴らした拍の番号
+    accent_click: Handle<Pitch>,    // 小節頭のクリック音
+    click: Handle<Pitch>,           // 通常のクリック音
+}
+
+/// 拍に合わせて光る四角形を示すコンポーネント
+#[derive(Component)]
+struct BeatSquare;
+
+/// 現在の設定を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct InfoText;
+
+/// 1 拍の長さ (秒)
+fn beat_length() -> f64 {
+    60.0 / BPM
+}
+
+fn setup(
+    mut commands: Commands,
+    mut metronome: ResMut<Metronome>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    commands.spawn(Camera2d);
+
+    // クリック音は正弦波 (`Pitch`) で生成するため、音声ファイルは不要
+    metronome.accent_click = pitches.add(Pitch::new(ACCENT_FREQUENCY, CLICK_DURATION));
+    metronome.click = pitches.add(Pitch::new(CLICK_FREQUENCY, CLICK_DURATION));
+
+    commands.spawn((
+        Sprite::from_color(IDLE_COLOR, Vec2::splat(SQUARE_SIZE)),
+        BeatSquare,
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+}
+
+/// 拍の境界を越えたフレームでクリック音を鳴らす
+///
+/// 音はフレーム単位でしか鳴らせないため、最大 1 フレーム分の揺らぎがある。
+fn play_click(mut commands: Commands, time: Res<Time>, mut metronome: ResMut<Metronome>) {
+    let beat = (time.elapsed_secs_f64() / beat_length()) as u64;
+    if metronome.last_clicked_beat == Some(beat) {
+        return;
+    }
+    metronome.last_clicked_beat = Some(beat);
+
+    let click = if beat.is_multiple_of(BEATS_PER_BAR) {
+        metronome.accent_click.clone()
+    } else {
+        metronome.click.clone()
+    };
+    commands.spawn((AudioPlayer(click), PlaybackSettings::DESPAWN));
+}
+
+/// 遅延補正を考慮した時刻で四角形を光らせる
+///
+/// 音は出力デバイスの遅延分だけ遅れて聞こえるので、映像も同じだけ遅らせる。
+fn flash_square(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut square: Single<&mut Sprite, With<BeatSquare>>,
+) {
+    let visual_time = time.elapsed_secs_f64() - settings.audio_latency_secs();
+    if visual_time < 0.0 {
+        return;
+    }
+
+    let beat = (visual_time / beat_length()) as u64;
+    let phase = visual_time % beat_length();
+    square.color = match (phase < FLASH_DURATION, beat.is_multiple_of(BEATS_PER_BAR)) {
+        (true, true) => ACCENT_COLOR,
+        (true, false) => BEAT_COLOR,
+        (false, _) => IDLE_COLOR,
+    };
+}
+
+/// 左右キーで遅延補正を調整する (値は共有設定として自動保存される)
+fn adjust_latency(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    let step = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        LATENCY_FINE_STEP_MS
+    } else {
+        LATENCY_STEP_MS
+    };
+
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        settings.audio_latency_ms += step;
+    } else if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        settings.audio_latency_ms -= step;
+    } else if keyboard_input.just_pressed(KeyCode::Digit0) {
+        settings.audio_latency_ms = 0.0;
+    }
+}
+
+/// 現在のテンポと遅延補正を表示する
+fn update_info_text(settings: Res<Settings>, mut text: Single<&mut Text, With<InfoText>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    text.0 = format!(
+        "BPM: {BPM}\n\
+         Audio latency offset: {:+.0} ms\n\
+         Left/Right: adjust (hold Shift for 1 ms steps), 0: reset\n\
+         Adjust until the flash and the click feel simultaneous.",
+        settings.audio_latency_ms
+    );
+}
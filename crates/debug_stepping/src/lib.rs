@@ -0,0 +1,1803 @@
+//! ステップ実行でシステムを 1 つずつ進めて調べるための、デバッグ用のステッピング UI。
+//!
+//! [`SteppingPlugin`] に調べたいスケジュールを追加すると、`` ` `` キーでステッピングを切り替えられる。
+//! UI の配色・フォント・文字の大きさは [`SteppingTheme`] (ビルダーの [`SteppingPlugin::with_theme`] かリソース)、
+//! 文字列は [`SteppingLabels`] のリソースを書き換えると変えられる。
+//! ステッピングは `bevy_debug_stepping` フィーチャーを付けてビルドしたときだけ有効になる。
+
+use std::{
+    collections::VecDeque,
+    fs, io,
+    path::PathBuf,
+    sync::Mutex,
+    thread::ThreadId,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    app::MainScheduleOrder,
+    core::FrameCount,
+    ecs::{
+        component::{ComponentId, Components},
+        entity::EntityHashMap,
+        schedule::*,
+        system::{BoxedSystem, SystemChangeTick},
+    },
+    input::{
+        keyboard::{Key, KeyboardInput},
+        mouse::{MouseScrollUnit, MouseWheel},
+        ButtonState,
+    },
+    prelude::*,
+    scene::SceneFilter,
+    text::TextLayoutInfo,
+    ui::RelativeCursorPosition,
+    utils::{HashMap, HashSet},
+};
+use serde::Serialize;
+
+#[cfg(feature = "remote_control")]
+mod remote;
+mod session;
+mod style;
+mod system_timings;
+
+pub use style::{SteppingLabels, SteppingTheme};
+pub use system_timings::{system_timing_layer, SystemRun, SystemTiming, SystemTimings};
+
+use style::{SteppingColor, SteppingFont, SteppingLabel, StylePlugin};
+
+/// [`SteppingPlugin::with_remote_control`] で待ち受ける、既定のポート番号
+pub const DEFAULT_REMOTE_PORT: u16 = 7878;
+
+/// 独立した [`Schedule`] を定義し、デバッグ用のステッピング処理を行う。
+/// スケジュールを独立させることで、他のスケジュールを調査できるようにする。
+#[derive(Debug, Hash, PartialEq, Eq, Clone, ScheduleLabel)]
+struct DebugSchedule;
+
+/// ステッピング UI を追加するためのプラグイン
+#[derive(Default)]
+pub struct SteppingPlugin {
+    schedule_labels: Vec<InternedScheduleLabel>,
+    top: Val,
+    left: Val,
+    history: HistorySettings,
+    trace_path: Option<PathBuf>, // ステップ実行したフレームの記録を書き出すファイル
+    remote_port: Option<u16>,    // コマンドで操作するために待ち受ける localhost のポート
+    theme: SteppingTheme,        // UI の配色・フォント・文字の大きさの最初の値
+    session_path: Option<PathBuf>, // ブレークポイントなどを次の起動に持ち越すファイル
+    break_conditions: Mutex<Vec<BoxedSystem>>, // 条件付きブレークポイントを調べるシステム (`build` で取り出す)
+}
+
+/// 1 フレーム戻るために記録するフレーム数と、記録するコンポーネント・リソースの種類
+struct HistorySettings {
+    frames: usize, // 0 なら記録しない
+    components: SceneFilter,
+    resources: SceneFilter,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        HistorySettings {
+            frames: 0,
+            components: SceneFilter::deny_all(),
+            resources: SceneFilter::deny_all(),
+        }
+    }
+}
+
+impl SteppingPlugin {
+    /// ステッピング対象のスケジュールを追加する
+    pub fn add_schedule(mut self, label: impl ScheduleLabel) -> SteppingPlugin {
+        self.schedule_labels.push(label.intern());
+        self
+    }
+
+    /// ステッピング UI の位置を設定する
+    pub fn at(self, left: Val, top: Val) -> SteppingPlugin {
+        SteppingPlugin { top, left, ..self }
+    }
+
+    /// ステップ実行のフレームの始まりの状態を `frames` フレーム分まで記録し、1 フレームずつ戻れるようにする
+    pub fn with_history(mut self, frames: usize) -> SteppingPlugin {
+        self.history.frames = frames;
+        self
+    }
+
+    /// フレームの記録に含めるコンポーネントを追加する
+    ///
+    /// 記録にはリフレクションを使うので、`#[reflect(Component)]` を付けて型を登録しておく必要がある。
+    pub fn record_component<C: Component>(mut self) -> SteppingPlugin {
+        self.history.components = self.history.components.allow::<C>();
+        self
+    }
+
+    /// フレームの記録に含めるリソースを追加する (`#[reflect(Resource)]` を付けて型を登録しておく)
+    pub fn record_resource<R: Resource>(mut self) -> SteppingPlugin {
+        self.history.resources = self.history.resources.allow::<R>();
+        self
+    }
+
+    /// ステップ実行したフレームごとに実行したシステムと時刻を記録し、
+    /// アプリの終了時か `X` キーで `chrome://tracing` の形式の JSON として `path` に書き出す
+    ///
+    /// システムの実行は [`SystemTimings`] の記録を使うので、`system_timings` フィーチャーが必要。
+    pub fn with_trace_export(mut self, path: impl Into<PathBuf>) -> SteppingPlugin {
+        self.trace_path = Some(path.into());
+        self
+    }
+
+    /// `127.0.0.1:<port>` で TCP の接続を待ち受け、1 行ずつ送られたコマンド (`enable`・`step`・
+    /// `continue`・`break <system>` など) でステッピングを操作できるようにする
+    ///
+    /// ほかの端末から telnet やこのクレートの CLI (`cargo run -- step`) で操作したり、テストから操作したりするため。
+    /// `remote_control` フィーチャーが必要。
+    pub fn with_remote_control(mut self, port: u16) -> SteppingPlugin {
+        self.remote_port = Some(port);
+        self
+    }
+
+    /// アプリの終了時にブレークポイント・常に実行するシステム・ステッピングが有効かを `path` に保存し、
+    /// 次の起動時に読み込んで元に戻す
+    ///
+    /// 再コンパイルのたびにブレークポイントを設定し直さなくて済むように。システムは名前で覚えるので、
+    /// 名前を変えたシステムの設定は戻らない。
+    pub fn with_saved_session(mut self, path: impl Into<PathBuf>) -> SteppingPlugin {
+        self.session_path = Some(path.into());
+        self
+    }
+
+    /// `condition` (`run_if` と同じ形の、`bool` を返すシステム) が `true` になったら、
+    /// ステッピングを有効にして今のカーソルで止める条件付きブレークポイントを追加する
+    ///
+    /// ```ignore
+    /// SteppingPlugin::default().break_when(|score: Res<Score>| **score >= 10)
+    /// ```
+    ///
+    /// 条件は毎フレーム `DebugSchedule` で調べ、`false` から `true` に変わったときだけ止める
+    /// (`true` のままの間は止め続けないので、そこから先へステップ実行できる)。
+    pub fn break_when<M>(mut self, condition: impl Condition<M>) -> SteppingPlugin {
+        let condition = IntoSystem::into_system(condition);
+        let name = condition.name();
+        let system = condition.pipe(
+            move |In(now): In<bool>,
+                  mut was: Local<bool>,
+                  mut stepping: ResMut<Stepping>,
+                  mut state: ResMut<State>| {
+                let became_true = now && !*was;
+                *was = now;
+                if became_true {
+                    halt_on_condition(&name, &mut stepping, &mut state);
+                }
+            },
+        );
+        self.break_conditions
+            .get_mut()
+            .unwrap()
+            .push(Box::new(IntoSystem::into_system(system)));
+        self
+    }
+
+    /// UI の配色・パネルの背景・フォント・文字の大きさを指定する (既定は [`SteppingTheme::light`])
+    ///
+    /// ゲームの画面の明るさに合わせて UI を読みやすくするため。実行中に変えるときは [`SteppingTheme`] のリソースを書き換える。
+    pub fn with_theme(mut self, theme: SteppingTheme) -> SteppingPlugin {
+        self.theme = theme;
+        self
+    }
+}
+
+impl Plugin for SteppingPlugin {
+    fn build(&self, app: &mut App) {
+        // アプリの起動時に UI を構築する
+        app.add_plugins(StylePlugin)
+            .insert_resource(self.theme.clone())
+            .add_systems(Startup, build_stepping_hint);
+        if cfg!(not(feature = "bevy_debug_stepping")) {
+            return;
+        }
+
+        // デバッグ用の独立したスケジュールを作成し、メインスケジュールの実行順序に追加
+        app.init_schedule(DebugSchedule);
+        let mut order = app.world_mut().resource_mut::<MainScheduleOrder>();
+        order.insert_after(Update, DebugSchedule);
+
+        // ステッピングリソースを作成し、追加されたスケジュールを登録
+        let mut stepping = Stepping::new();
+        for label in &self.schedule_labels {
+            stepping.add_schedule(*label);
+        }
+        app.insert_resource(stepping);
+
+        // UI の状態管理用リソースを挿入
+        app.insert_resource(State {
+            ui_top: self.top,
+            ui_left: self.left,
+            headings: Vec::new(),
+            systems: Vec::new(),
+            collapsed: HashSet::new(),
+            conditional: HashSet::new(),
+            breakpoints: HashSet::new(),
+            always_run: HashSet::new(),
+            filter: String::new(),
+            filtering: false,
+            show_internal: false,
+            running: false,
+            typed_frames: 0,
+            frames_left: None,
+            at_frame_start: true,
+            capture: false,
+            rewind: false,
+            export_trace: false,
+        })
+        .insert_resource(History {
+            frames: VecDeque::new(),
+            capacity: self.history.frames,
+            components: self.history.components.clone(),
+            resources: self.history.resources.clone(),
+        })
+        .add_systems(
+            DebugSchedule,
+            (
+                build_ui.run_if(not(initialized)),
+                track_frame_start,
+                track_skipped_systems.run_if(initialized),
+                type_filter,
+                handle_input,
+                continue_until_breakpoint,
+                continue_frames,
+                handle_buttons,
+                update_history,
+                toggle_breakpoint.run_if(initialized),
+                update_ui.run_if(initialized),
+                update_filter_status.run_if(initialized),
+                update_frame_count.run_if(initialized),
+                update_system_timings.run_if(initialized),
+                update_system_access.run_if(initialized),
+                scroll_list.run_if(initialized),
+                follow_cursor.run_if(initialized),
+            )
+                .chain(),
+        );
+
+        // ステップ実行したフレームのシステムの実行を、フレームの始まりから終わりまで記録する
+        // (`SystemTimings` は `LogPlugin` が `system_timings` フィーチャーを付けたときだけ追加する)
+        if let Some(path) = &self.trace_path {
+            if app.world().contains_resource::<SystemTimings>() {
+                app.insert_resource(TraceSession {
+                    path: path.clone(),
+                    frame_start: None,
+                    frames: Vec::new(),
+                })
+                .add_systems(First, begin_trace_frame)
+                .add_systems(Last, end_trace_frame);
+            } else {
+                warn!("exporting stepping traces needs the `system_timings` feature");
+            }
+        }
+
+        // 前の起動のブレークポイントとステッピングの有効・無効を戻し、終了時に保存する
+        if let Some(path) = &self.session_path {
+            session::restore_and_save(app, path.clone());
+        }
+
+        // 条件付きブレークポイントは、システムの一覧ができてから、キー入力の処理の前に調べる
+        for system in self.break_conditions.lock().unwrap().drain(..) {
+            app.add_systems(
+                DebugSchedule,
+                system
+                    .after(build_ui)
+                    .before(handle_input)
+                    .run_if(initialized),
+            );
+        }
+
+        // ほかの端末やテストから、localhost の TCP ソケットに送ったコマンドで操作できるようにする
+        if let Some(port) = self.remote_port {
+            #[cfg(feature = "remote_control")]
+            remote::listen(app, port);
+            #[cfg(not(feature = "remote_control"))]
+            warn!("remote stepping control on port {port} needs the `remote_control` feature");
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        if cfg!(not(feature = "bevy_debug_stepping")) {
+            return;
+        }
+
+        // 実行条件はスケジュールの初期化 (最初の実行) のときに実行用のデータへ移されて読めなくなるので、
+        // その前に、実行条件のあるシステムと、実行条件のあるセットに含まれるシステムを集めておく
+        let mut conditional = HashSet::new();
+        for label in &self.schedule_labels {
+            let Some(schedule) = app.get_schedule(*label) else {
+                continue;
+            };
+            let graph = schedule.graph();
+            for (node_id, _, conditions) in graph.systems() {
+                if !conditions.is_empty() {
+                    conditional.insert((*label, node_id));
+                }
+            }
+
+            // セットはほかのセットを含むこともあるので、含まれるノードを順にたどる
+            let mut pending: Vec<NodeId> = graph
+                .system_sets()
+                .filter(|(_, _, conditions)| !conditions.is_empty())
+                .map(|(node_id, _, _)| node_id)
+                .collect();
+            let mut visited = HashSet::new();
+            while let Some(node_id) = pending.pop() {
+                if !visited.insert(node_id) {
+                    continue;
+                }
+                if node_id.is_system() {
+                    conditional.insert((*label, node_id));
+                }
+                pending.extend(graph.hierarchy().graph().neighbors(node_id));
+            }
+        }
+        app.world_mut().resource_mut::<State>().conditional = conditional;
+    }
+}
+
+/// ステッピング UI の状態を管理するリソース
+#[derive(Resource, Debug)]
+struct State {
+    systems: Vec<SystemRow>,                               // システムの一覧の行
+    breakpoints: HashSet<(InternedScheduleLabel, NodeId)>, // ブレークポイントを設定したシステム
+    always_run: HashSet<(InternedScheduleLabel, NodeId)>, // 右クリックで常に実行するようにしたシステム (内部のシステムは含まない)
+    filter: String,       // 名前にこの文字列を含むシステムだけを表示する
+    filtering: bool,      // 絞り込みの文字列を入力しているか
+    show_internal: bool,  // Bevy 内部のシステムも表示するか
+    running: bool, // 「Continue」ボタンで、ブレークポイントに着くまでフレームを進め続けているか
+    at_frame_start: bool, // ステップ実行のフレームの始まりにいるか (まだどのシステムも実行していない)
+    capture: bool,        // フレームの始まりの状態を記録する
+    rewind: bool,         // 1 つ前の記録まで戻す (フレームの途中なら、フレームを終えてから戻す)
+    export_trace: bool,   // ステップ実行したフレームの記録を書き出す
+
+    typed_frames: usize, // `Shift` と数字キーで入力中の、まとめて進めるフレーム数 (0 なら入力なし)
+    frames_left: Option<usize>, // まとめて進めている残りのフレーム数 (進めていなければ `None`)
+
+    headings: Vec<ScheduleHeading>, // スケジュール名の見出し
+    collapsed: HashSet<InternedScheduleLabel>, // 折りたたんだスケジュール
+    conditional: HashSet<(InternedScheduleLabel, NodeId)>, // 実行条件 (`run_if`) のあるシステム (属するセットの条件を含む)
+
+    ui_top: Val,
+    ui_left: Val,
+}
+
+/// ステッピング UI のシステムの一覧のスケジュール名の見出し
+#[derive(Debug)]
+struct ScheduleHeading {
+    schedule: InternedScheduleLabel,
+    text_index: usize, // 見出しのスパンの番号
+}
+
+/// ステッピング UI のシステムの一覧の 1 行
+#[derive(Debug)]
+struct SystemRow {
+    schedule: InternedScheduleLabel,
+    system: NodeId,
+    text_index: usize, // 行の印のスパンの番号 (続けてシステム名と処理時間のスパン)
+    name: String,
+    internal: bool,         // Bevy 内部のシステム (ステップ実行せず、常に実行する)
+    conditional: bool,      // 実行条件のあるシステム
+    last_run: Option<bool>, // 最後にステップ実行したときに実行条件を満たしたか (まだなら `None`)
+}
+
+impl State {
+    /// システムの行を一覧に表示するかどうか (絞り込みでは大文字と小文字を区別しない)
+    fn is_shown(&self, row: &SystemRow) -> bool {
+        let filter = self.filter.to_lowercase();
+        (self.show_internal || !row.internal) && row.name.to_lowercase().contains(&filter)
+    }
+
+    /// システムの行を今表示するかどうか
+    ///
+    /// 折りたたんだスケジュールの行は隠すが、カーソルのある行だけは残して、次に実行するシステムがわかるようにする。
+    fn is_visible(&self, row: &SystemRow, cursor: Option<(InternedScheduleLabel, NodeId)>) -> bool {
+        self.is_shown(row)
+            && (!self.collapsed.contains(&row.schedule)
+                || cursor == Some((row.schedule, row.system)))
+    }
+
+    /// スケジュールの折りたたみを切り替える
+    fn toggle_collapsed(&mut self, schedule: InternedScheduleLabel) {
+        if !self.collapsed.remove(&schedule) {
+            self.collapsed.insert(schedule);
+        }
+    }
+
+    /// ステップ実行せずに常に実行するかを切り替える (ブレークポイントとは両立しないので、設定するときは外す)
+    fn toggle_always_run(
+        &mut self,
+        stepping: &mut Stepping,
+        schedule: InternedScheduleLabel,
+        system: NodeId,
+    ) {
+        if self.always_run.remove(&(schedule, system)) {
+            debug!("cleared always run");
+            stepping.clear_node(schedule, system);
+        } else {
+            debug!("set always run");
+            self.breakpoints.remove(&(schedule, system));
+            self.always_run.insert((schedule, system));
+            stepping.always_run_node(schedule, system);
+        }
+    }
+
+    /// `frames` フレームを止まらずに進め始める (ブレークポイントでも止まらない)
+    ///
+    /// フレームの途中なら、そのフレームの残りを進めてから数え始める。
+    fn step_frames(&mut self, frames: usize) {
+        self.running = false;
+        self.frames_left = Some(frames);
+    }
+
+    /// ステップ実行の操作をしたことを記録する (フレームの始まりなら、進める前の状態を記録させる)
+    fn advance(&mut self) {
+        if self.at_frame_start {
+            self.capture = true;
+            self.at_frame_start = false;
+        }
+    }
+}
+
+/// ステップ実行のフレームの始まりの状態の記録 (新しいものが後ろ)
+///
+/// 記録は [`DynamicScene`] で、登録したコンポーネントとリソースだけを含む。
+#[derive(Resource)]
+struct History {
+    frames: VecDeque<DynamicScene>,
+    capacity: usize,
+    components: SceneFilter,
+    resources: SceneFilter,
+}
+
+/// UI が初期化されているかどうかを判定する条件関数
+fn initialized(state: Res<State>) -> bool {
+    !state.systems.is_empty()
+}
+
+// 平均の処理時間がこれより長いシステムは、処理時間を目立つ色で表示する
+const SLOW_SYSTEM_THRESHOLD: Duration = Duration::from_micros(500);
+// システムの一覧の高さの上限 (これより長い一覧はスクロールする)
+const LIST_MAX_HEIGHT: Val = Val::Vh(35.0);
+// マウスホイール 1 行分のスクロール量 (論理ピクセル)
+const SCROLL_LINE_HEIGHT: f32 = 20.0;
+// カーソルのある行を見える範囲に入れるとき、上下に空けておく幅 (論理ピクセル)
+const FOLLOW_MARGIN: f32 = 24.0;
+// `Shift` と一緒に押して、まとめて進めるフレーム数を入力する数字キー (0 から 9 の順)
+const DIGIT_KEYS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+// まとめて進めるフレーム数の上限
+const MAX_STEP_FRAMES: usize = 9999;
+
+#[derive(Component)]
+struct SteppingUi;
+
+/// ステッピング UI のシステムの一覧 (子のテキストスパンが 1 行ずつのシステム)
+#[derive(Component)]
+struct SystemList;
+
+/// システムの一覧を入れる、縦にスクロールする枠
+#[derive(Component)]
+struct SystemListViewport;
+
+/// 絞り込みの文字列と、表示しているシステムの数を表示するテキスト
+#[derive(Component)]
+struct FilterStatus;
+
+/// カーソルのあるシステムが読み書きするコンポーネントとリソースを表示するテキスト
+#[derive(Component)]
+struct SystemAccess;
+
+/// 入力中の、またはまとめて進めている残りのフレーム数を表示するテキスト
+#[derive(Component)]
+struct FrameCountText;
+
+/// ステッピング UI のボタン
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum SteppingButton {
+    StepBack,   // 1 つ前のフレームの始まりに戻る (`B` キーと同じ)
+    StepSystem, // システムを 1 つ実行する (`S` キーと同じ)
+    StepFrame,  // フレームの残りのシステムを実行する (`Space` キーと同じ)
+    Continue,   // ブレークポイントに着くまでフレームを進め続ける
+    Disable,    // ステッピングを無効にする (`` ` `` キーと同じ)
+    Filter,     // システムの絞り込みの文字列を入力する (`F` キーと同じ)
+    Internal,   // Bevy 内部のシステムの表示を切り替える (`I` キーと同じ)
+}
+
+impl SteppingButton {
+    // ステップ実行を操作するボタン (1 行目)
+    const CONTROLS: [SteppingButton; 5] = [
+        SteppingButton::StepBack,
+        SteppingButton::StepSystem,
+        SteppingButton::StepFrame,
+        SteppingButton::Continue,
+        SteppingButton::Disable,
+    ];
+    // システムの一覧の表示を変えるボタン (2 行目)
+    const VIEW: [SteppingButton; 2] = [SteppingButton::Filter, SteppingButton::Internal];
+
+    /// 文字列テーブルのボタンの名前のキー
+    fn label_key(self) -> &'static str {
+        match self {
+            SteppingButton::StepBack => "stepping_step_back",
+            SteppingButton::StepSystem => "stepping_step_system",
+            SteppingButton::StepFrame => "stepping_step_frame",
+            SteppingButton::Continue => "stepping_continue",
+            SteppingButton::Disable => "stepping_disable",
+            SteppingButton::Filter => "stepping_filter",
+            SteppingButton::Internal => "stepping_internal",
+        }
+    }
+}
+
+/// ステッピング UI を構築するシステム
+fn build_ui(
+    mut commands: Commands,
+    schedules: Res<Schedules>,
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+) {
+    let mut text_spans = Vec::new();
+    let mut always_run = Vec::new();
+
+    let Ok(schedule_order) = stepping.schedules() else {
+        return;
+    };
+
+    // スケジュール内のシステムをリスト化
+    for label in schedule_order {
+        let schedule = schedules.get(*label).unwrap();
+        // 見出しの文字 (折りたたみの印とスケジュール名) は `update_ui` が書き込む
+        state.headings.push(ScheduleHeading {
+            schedule: *label,
+            text_index: text_spans.len() + 1,
+        });
+        text_spans.push((
+            TextSpan::default(),
+            TextFont::default(),
+            SteppingFont::Heading,
+            SteppingColor::Text,
+        ));
+
+        let Ok(systems) = schedule.systems() else {
+            return;
+        };
+
+        for (node_id, system) in systems {
+            // Bevy 内部のシステムはステップ実行せずに常に実行し、一覧では隠せるようにしておく
+            let internal = system.name().starts_with("bevy");
+            if internal {
+                always_run.push((*label, node_id));
+            }
+
+            let conditional = state.conditional.contains(&(*label, node_id));
+            state.systems.push(SystemRow {
+                schedule: *label,
+                system: node_id,
+                text_index: text_spans.len() + 1,
+                name: system.name().to_string(),
+                internal,
+                conditional,
+                last_run: None,
+            });
+
+            // 行の印とシステム名は、表示するかどうかに合わせて `update_ui` が書き込む
+            text_spans.push((
+                TextSpan::default(),
+                TextFont::default(),
+                SteppingFont::List,
+                SteppingColor::Text,
+            ));
+
+            text_spans.push((
+                TextSpan::default(),
+                TextFont::default(),
+                SteppingFont::List,
+                SteppingColor::Text,
+            ));
+
+            // 処理時間 (行の終わりの改行を含む) は `update_system_timings` が書き込む
+            text_spans.push((
+                TextSpan::default(),
+                TextFont::default(),
+                SteppingFont::List,
+                SteppingColor::Text,
+            ));
+        }
+    }
+
+    for (label, node) in always_run.drain(..) {
+        stepping.always_run_node(label, node);
+    }
+
+    commands
+        .spawn((
+            SteppingUi,
+            Node {
+                position_type: PositionType::Absolute,
+                top: state.ui_top,
+                left: state.ui_left,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor::default(),
+            SteppingColor::Panel,
+            Visibility::Hidden,
+        ))
+        .with_children(|p| {
+            // 上にマウスで操作するボタンと、まとめて進めるフレーム数を並べる
+            p.spawn(Node {
+                column_gap: Val::Px(6.0),
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_children(|row| {
+                for button in SteppingButton::CONTROLS {
+                    spawn_button(row, button);
+                }
+                row.spawn((
+                    Text::default(),
+                    TextFont::default(),
+                    SteppingColor::Text,
+                    SteppingFont::Label,
+                    FrameCountText,
+                ));
+            });
+
+            // 次の行に、一覧の表示を変えるボタンと絞り込みの状態を並べる
+            p.spawn(Node {
+                column_gap: Val::Px(6.0),
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_children(|row| {
+                for button in SteppingButton::VIEW {
+                    spawn_button(row, button);
+                }
+                row.spawn((
+                    Text::default(),
+                    TextFont::default(),
+                    SteppingColor::Text,
+                    SteppingFont::Label,
+                    FilterStatus,
+                ));
+            });
+
+            // その下にシステムの一覧を表示する (長い一覧は枠の中でスクロールする)
+            p.spawn((
+                SystemListViewport,
+                Node {
+                    max_height: LIST_MAX_HEIGHT,
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                ScrollPosition::default(),
+                // マウスホイールを回したときに、カーソルが枠の上にあるかを調べる
+                RelativeCursorPosition::default(),
+            ))
+            .with_children(|viewport| {
+                viewport
+                    .spawn((
+                        Text::default(),
+                        SystemList,
+                        // クリックした行を求めるため、ノード上のカーソル位置を記録する
+                        RelativeCursorPosition::default(),
+                    ))
+                    .with_children(|list| {
+                        for span in text_spans {
+                            list.spawn(span);
+                        }
+                    });
+            });
+
+            // 一覧の下に、カーソルのあるシステムが読み書きするものを表示する
+            p.spawn((
+                Text::default(),
+                TextFont::default(),
+                SteppingColor::Text,
+                SteppingFont::Label,
+                SystemAccess,
+            ));
+        });
+}
+
+/// ステッピング UI のボタンを 1 つ配置する
+fn spawn_button(row: &mut ChildBuilder, button: SteppingButton) {
+    row.spawn((
+        Button,
+        button,
+        Node {
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor::default(),
+        SteppingColor::Button,
+    ))
+    .with_child((
+        Text::default(),
+        SteppingLabel(button.label_key()),
+        SteppingFont::Label,
+        TextFont::default(),
+        SteppingColor::Text,
+    ));
+}
+
+/// ステッピングのヒントをコンソールに表示する
+fn build_stepping_hint(mut commands: Commands, labels: Res<SteppingLabels>) {
+    let hint_key = if cfg!(feature = "bevy_debug_stepping") {
+        "stepping_hint"
+    } else {
+        "stepping_unsupported"
+    };
+    info!("{}", labels.get(hint_key));
+    // 画面上のヒントは、あとから `SteppingLabels` を書き換えたときにも合わせる
+    commands.spawn((
+        Text::new(labels.get(hint_key)),
+        SteppingLabel(hint_key),
+        SteppingFont::Label,
+        TextFont::default(),
+        SteppingColor::Text,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(5.0),
+            left: Val::Px(5.0),
+            ..default()
+        },
+    ));
+}
+
+/// ユーザー入力を処理し、ステッピングを制御する
+fn handle_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+) {
+    // 絞り込みの文字列の入力中は、キーを文字として扱う
+    if state.filtering {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Slash) {
+        info!("{:#?}", stepping);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Backquote) {
+        if stepping.is_enabled() {
+            stepping.disable();
+            debug!("disabled stepping");
+        } else {
+            stepping.enable();
+            debug!("enabled stepping");
+        }
+    }
+
+    if !stepping.is_enabled() {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyF) {
+        state.filtering = true;
+    } else if keyboard_input.just_pressed(KeyCode::KeyI) {
+        state.show_internal = !state.show_internal;
+    } else if keyboard_input.just_pressed(KeyCode::KeyH) {
+        // カーソルのあるスケジュール (フレームの始まりなら、最初に実行するスケジュール) を折りたたむ
+        let schedule = stepping
+            .cursor()
+            .map(|(schedule, _)| schedule)
+            .or_else(|| state.headings.first().map(|heading| heading.schedule));
+        if let Some(schedule) = schedule {
+            state.toggle_collapsed(schedule);
+        }
+    }
+
+    // `Shift` を押しながら数字キーでフレーム数を入力し、`Shift` を離すとその数だけフレームを進める
+    if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        for (digit, key) in DIGIT_KEYS.into_iter().enumerate() {
+            if keyboard_input.just_pressed(key) {
+                state.typed_frames = (state.typed_frames * 10 + digit).min(MAX_STEP_FRAMES);
+            }
+        }
+    } else if state.typed_frames > 0 {
+        let frames = std::mem::take(&mut state.typed_frames);
+        debug!("stepping {frames} frame(s)");
+        state.step_frames(frames);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        debug!("continue");
+        state.advance();
+        stepping.continue_frame();
+    } else if keyboard_input.just_pressed(KeyCode::KeyS) {
+        debug!("stepping frame");
+        state.advance();
+        stepping.step_frame();
+    } else if keyboard_input.just_pressed(KeyCode::KeyB) {
+        debug!("step back");
+        state.running = false;
+        state.rewind = true;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyX) {
+        state.export_trace = true;
+    }
+}
+
+/// 絞り込みの文字列を入力する (`Enter` キーで確定、`Esc` キーで消して終える、`Backspace` キーで 1 文字消す)
+///
+/// 入力を始めたキー (`F`) が文字として入らないように、[`handle_input`] より先に実行する。
+fn type_filter(
+    mut events: EventReader<KeyboardInput>,
+    stepping: Res<Stepping>,
+    mut state: ResMut<State>,
+) {
+    if !stepping.is_enabled() && state.filtering {
+        state.filtering = false;
+    }
+    for event in events.read() {
+        if !state.filtering || event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(text) => {
+                state
+                    .filter
+                    .extend(text.chars().filter(|c| !c.is_control()));
+            }
+            Key::Backspace => {
+                state.filter.pop();
+            }
+            Key::Enter => {
+                state.filtering = false;
+            }
+            Key::Escape => {
+                state.filter.clear();
+                state.filtering = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// ステップ実行のフレームが終わったかどうかを見て、次のフレームの始まりにいることを記録する
+///
+/// カーソルはフレームの最後のシステムを実行したあとに `None` になる。
+/// ステッピングが無効になったら、記録したフレームはもう続きにならないので捨てる。
+fn track_frame_start(
+    stepping: Res<Stepping>,
+    mut state: ResMut<State>,
+    mut history: ResMut<History>,
+) {
+    if !stepping.is_enabled() {
+        state.at_frame_start = true;
+        state.rewind = false;
+        if !history.frames.is_empty() {
+            history.frames.clear();
+        }
+        return;
+    }
+    if stepping.cursor().is_none() {
+        state.at_frame_start = true;
+    }
+}
+
+/// 前回からステップ実行したシステムのうち、実行条件のあるシステムが実際に実行されたかを記録する
+///
+/// システムは実行されると最後に実行した時刻 (`get_last_run`) が更新されるが、実行条件を満たさずに飛ばされると更新されない。
+/// その時刻がこのシステムの前回の実行より新しければ、実行条件を満たしたとみなす。
+/// ステップ実行した範囲は、前回のカーソル位置から今のカーソル位置の手前まで (`None` ならフレームの始まりか終わり)。
+fn track_skipped_systems(
+    schedules: Res<Schedules>,
+    stepping: Res<Stepping>,
+    mut state: ResMut<State>,
+    ticks: SystemChangeTick,
+    mut last_cursor: Local<Option<(InternedScheduleLabel, NodeId)>>,
+) {
+    if !stepping.is_enabled() {
+        *last_cursor = None;
+        return;
+    }
+
+    let mut ran = HashMap::new();
+    for label in stepping.schedules().into_iter().flatten() {
+        let Some(Ok(systems)) = schedules.get(*label).map(Schedule::systems) else {
+            continue;
+        };
+        for (node_id, system) in systems {
+            let last_run = system.get_last_run();
+            ran.insert(
+                (*label, node_id),
+                last_run.is_newer_than(ticks.last_run(), ticks.this_run()),
+            );
+        }
+    }
+    let ran_since_last_check = |row: &SystemRow| ran.get(&(row.schedule, row.system)).copied();
+
+    let cursor = stepping.cursor();
+    let position = |cursor: Option<(InternedScheduleLabel, NodeId)>| {
+        cursor.and_then(|cursor| {
+            state
+                .systems
+                .iter()
+                .position(|row| (row.schedule, row.system) == cursor)
+        })
+    };
+    let start = position(*last_cursor).unwrap_or(0);
+    let mut end = position(cursor).unwrap_or(state.systems.len());
+    // どちらもフレームの始まりなら、何もステップ実行していないか、フレームをまるごと実行したかのどちらか
+    if last_cursor.is_none()
+        && cursor.is_none()
+        && !state
+            .systems
+            .iter()
+            .any(|row| !row.internal && ran_since_last_check(row) == Some(true))
+    {
+        end = start;
+    }
+    *last_cursor = cursor;
+
+    // 変わった行だけを書き換えて、ほかのシステムに状態が変わったと伝えないようにする
+    let updates: Vec<(usize, bool)> = (start..end)
+        .filter_map(|index| {
+            let row = &state.systems[index];
+            let ran = ran_since_last_check(row)?;
+            (row.conditional && row.last_run != Some(ran)).then_some((index, ran))
+        })
+        .collect();
+    for (index, ran) in updates {
+        state.systems[index].last_run = Some(ran);
+    }
+}
+
+/// フレームの始まりの状態を記録し、1 フレーム戻る操作があれば記録した状態を書き戻す
+///
+/// 書き戻すのは、記録したときにあって今も残っているエンティティのコンポーネントと、リソースだけ。
+/// あとから消えたエンティティは作り直さず、あとから増えたエンティティもそのまま残る。
+fn update_history(world: &mut World) {
+    world.resource_scope(|world, mut history: Mut<History>| {
+        let (capture, rewind, at_frame_start) = {
+            let mut state = world.resource_mut::<State>();
+            let capture = std::mem::take(&mut state.capture);
+            if history.capacity == 0 && std::mem::take(&mut state.rewind) {
+                debug!("frame history is disabled");
+            }
+            (capture, state.rewind, state.at_frame_start)
+        };
+        if history.capacity == 0 {
+            return;
+        }
+
+        if capture {
+            let entities: Vec<Entity> = world.iter_entities().map(|entity| entity.id()).collect();
+            let scene = DynamicSceneBuilder::from_world(world)
+                .with_component_filter(history.components.clone())
+                .with_resource_filter(history.resources.clone())
+                .extract_entities(entities.into_iter())
+                .remove_empty_entities()
+                .extract_resources()
+                .build();
+            history.frames.push_back(scene);
+            if history.frames.len() > history.capacity {
+                history.frames.pop_front();
+            }
+        }
+
+        if !rewind {
+            return;
+        }
+        if !at_frame_start {
+            // フレームの途中では戻れないので、残りのシステムを実行してフレームを終えてから戻す
+            world.resource_mut::<Stepping>().continue_frame();
+            return;
+        }
+        world.resource_mut::<State>().rewind = false;
+        let Some(mut scene) = history.frames.pop_back() else {
+            debug!("no recorded frame to step back to");
+            return;
+        };
+        scene
+            .entities
+            .retain(|entity| world.entities().contains(entity.entity));
+        let mut entity_map: EntityHashMap<Entity> = scene
+            .entities
+            .iter()
+            .map(|entity| (entity.entity, entity.entity))
+            .collect();
+        match scene.write_to_world(world, &mut entity_map) {
+            Ok(()) => debug!("stepped back ({} frame(s) left)", history.frames.len()),
+            Err(error) => warn!("could not step back: {error}"),
+        }
+    });
+}
+
+/// ステッピング UI のシステムの行をクリックすると、そのシステムのブレークポイントを切り替える
+/// (右クリックでは、ステップ実行せずに常に実行するかを切り替える)
+///
+/// テキストのレイアウト (`TextLayoutInfo`) の文字のうち、クリックした高さにいちばん近い文字の
+/// スパンから行を求める。スケジュール名の見出しをクリックしたときは、そのスケジュールを折りたたむ。
+fn toggle_breakpoint(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+    ui: Single<(&RelativeCursorPosition, &ComputedNode, &TextLayoutInfo), With<SystemList>>,
+) {
+    let left = mouse_input.just_pressed(MouseButton::Left);
+    let right = mouse_input.just_pressed(MouseButton::Right);
+    if !stepping.is_enabled() || !(left || right) {
+        return;
+    }
+    let (cursor, node, layout) = *ui;
+    if !cursor.mouse_over() {
+        return;
+    }
+    let Some(normalized) = cursor.normalized else {
+        return;
+    };
+
+    // 文字の位置はノードの左上からの物理ピクセルで表される
+    let cursor_y = normalized.y * node.size().y;
+    let Some(span_index) = layout
+        .glyphs
+        .iter()
+        .min_by(|a, b| {
+            (a.position.y - cursor_y)
+                .abs()
+                .total_cmp(&(b.position.y - cursor_y).abs())
+        })
+        .map(|glyph| glyph.span_index)
+    else {
+        return;
+    };
+
+    let heading = state
+        .headings
+        .iter()
+        .find(|heading| heading.text_index == span_index)
+        .map(|heading| heading.schedule);
+    if let Some(schedule) = heading {
+        if left {
+            state.toggle_collapsed(schedule);
+        }
+        return;
+    }
+
+    // 行はカーソル位置の印のスパンと、システム名・処理時間のスパンからなる
+    // (隠した行のスパンは空なので、文字が見つかることはない)
+    let Some(row) = state
+        .systems
+        .iter()
+        .find(|row| (row.text_index..=row.text_index + 2).contains(&span_index))
+    else {
+        return;
+    };
+    // 常に実行するシステムにはブレークポイントを設定できない (設定すると常に実行しなくなる)
+    if row.internal {
+        return;
+    }
+    let (schedule, system) = (row.schedule, row.system);
+    if right {
+        state.toggle_always_run(&mut stepping, schedule, system);
+    } else if state.breakpoints.remove(&(schedule, system)) {
+        debug!("cleared breakpoint");
+        stepping.clear_breakpoint_node(schedule, system);
+    } else {
+        debug!("set breakpoint");
+        state.always_run.remove(&(schedule, system));
+        state.breakpoints.insert((schedule, system));
+        stepping.set_breakpoint_node(schedule, system);
+    }
+}
+
+/// ステッピング UI のボタンの色をカーソルに合わせて変え、押されたボタンの操作を行う
+fn handle_buttons(
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+    mut buttons: Query<(&Interaction, &SteppingButton, &mut SteppingColor), Changed<Interaction>>,
+) {
+    for (interaction, button, mut color) in &mut buttons {
+        color.set_if_neq(match interaction {
+            Interaction::None => SteppingColor::Button,
+            Interaction::Hovered | Interaction::Pressed => SteppingColor::ButtonHovered,
+        });
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            SteppingButton::StepBack => {
+                debug!("step back");
+                state.running = false;
+                state.rewind = true;
+            }
+            SteppingButton::StepSystem => {
+                debug!("stepping frame");
+                state.advance();
+                stepping.step_frame();
+            }
+            SteppingButton::StepFrame => {
+                debug!("continue");
+                state.advance();
+                stepping.continue_frame();
+            }
+            SteppingButton::Continue => {
+                debug!("continue until breakpoint");
+                state.running = true;
+                state.advance();
+                stepping.continue_frame();
+            }
+            SteppingButton::Disable => {
+                state.running = false;
+                stepping.disable();
+                debug!("disabled stepping");
+            }
+            SteppingButton::Filter => {
+                state.filtering = true;
+            }
+            SteppingButton::Internal => {
+                state.show_internal = !state.show_internal;
+            }
+        }
+    }
+}
+
+/// 「Continue」ボタンが押されたあと、ブレークポイントで止まるまで毎フレーム続けて実行する
+///
+/// 残りのシステムを実行するフレーム (`continue_frame`) はブレークポイントの手前で止まり、
+/// カーソルがそのシステムを指したままになるので、それを見て止める。
+/// ボタンを押したフレームにはまだ前のカーソル位置が残っているため、[`handle_buttons`] より先に実行する。
+fn continue_until_breakpoint(mut stepping: ResMut<Stepping>, mut state: ResMut<State>) {
+    if !state.running {
+        return;
+    }
+    if !stepping.is_enabled() {
+        state.running = false;
+        return;
+    }
+    if let Some(cursor) = stepping.cursor() {
+        if state.breakpoints.contains(&cursor) {
+            debug!("stopped at breakpoint");
+            state.running = false;
+            return;
+        }
+    }
+    state.advance();
+    stepping.continue_frame();
+}
+
+/// 条件付きブレークポイント ([`SteppingPlugin::break_when`]) の条件が成り立ったら、ステッピングを有効にして止める
+///
+/// 進め続けている (「Continue」やまとめて進めるフレーム) ときもやめて、今のカーソルで止まる。
+/// 無効だったときは、次のフレームの始まりで止まる。
+fn halt_on_condition(name: &str, stepping: &mut Stepping, state: &mut State) {
+    info!("stepping break condition became true: {name}");
+    if !stepping.is_enabled() {
+        stepping.enable();
+    }
+    state.running = false;
+    state.frames_left = None;
+}
+
+/// `Shift` と数字キー (またはリモートの `frames <n>`) で指定した数だけ、フレームを止まらずに進める
+///
+/// フレームの始まりに着くたびに 1 フレーム数え、数え終わったフレームの始まりで止まる。
+/// `continue_frame` はブレークポイントの手前で止まるが、次のフレームでまた呼ぶので、ブレークポイントでは止まらない。
+fn continue_frames(mut stepping: ResMut<Stepping>, mut state: ResMut<State>) {
+    let Some(frames_left) = state.frames_left else {
+        return;
+    };
+    if !stepping.is_enabled() {
+        state.frames_left = None;
+        return;
+    }
+    let frames_left = if stepping.cursor().is_none() {
+        if frames_left == 0 {
+            debug!("finished stepping frames");
+            state.frames_left = None;
+            return;
+        }
+        frames_left - 1
+    } else {
+        frames_left
+    };
+    state.frames_left = Some(frames_left);
+    state.advance();
+    stepping.continue_frame();
+}
+
+fn update_ui(
+    mut commands: Commands, // エンティティの操作 (UI の可視性を変更するため)
+    state: Res<State>, // 現在の UI の状態 (システムリストや UI の位置情報など)
+    stepping: Res<Stepping>, // ステッピングの状態 (有効かどうか、現在のカーソル位置など)
+    ui: Single<(Entity, &Visibility), With<SteppingUi>>, // ステッピング UI のエンティティと可視状態
+    list: Single<Entity, With<SystemList>>, // システムの一覧のテキスト
+    mut writer: TextUiWriter, // UI のテキストを更新するためのライター
+    mut colors: Query<&mut SteppingColor>, // 実行条件で飛ばされたシステムの名前の色を変えるため
+) {
+    // ステッピング UI を有効・無効の状態にする
+    let (ui, vis) = *ui;
+    match (vis, stepping.is_enabled()) {
+        // ステッピングが有効になったら UI を表示
+        (Visibility::Hidden, true) => {
+            commands.entity(ui).insert(Visibility::Inherited);
+        }
+        // すでに可視の場合や変更が不要な場合は何もしない
+        (Visibility::Hidden, false) | (_, true) => (),
+        // ステッピングが無効になったら UI を非表示
+        (_, false) => {
+            commands.entity(ui).insert(Visibility::Hidden);
+        }
+    }
+
+    // ステッピングが無効ならこれ以上処理しない
+    if !stepping.is_enabled() {
+        return;
+    }
+
+    // ステッピングのカーソル位置を取得
+    // (ステッピングが有効でも選択されたシステムがない場合は `None`)
+    let cursor = stepping.cursor();
+
+    // スケジュール名の見出しに、折りたたんでいるかどうかの印と、隠しているシステムの数を付ける
+    for heading in &state.headings {
+        let heading_text = if state.collapsed.contains(&heading.schedule) {
+            let hidden = state
+                .systems
+                .iter()
+                .filter(|row| row.schedule == heading.schedule && !state.is_visible(row, cursor))
+                .count();
+            format!("[+] {:?} ({hidden})\n", heading.schedule)
+        } else {
+            format!("[-] {:?}\n", heading.schedule)
+        };
+        let mut text = writer.text(*list, heading.text_index);
+        if *text != heading_text {
+            *text = heading_text;
+        }
+    }
+
+    // 各システムの UI を更新
+    for row in &state.systems {
+        // 絞り込みに合わないシステムと、折りたたんだスケジュールのシステムは、印と名前と処理時間を空にして行ごと隠す
+        if !state.is_visible(row, cursor) {
+            for index in row.text_index..row.text_index + 3 {
+                let mut text = writer.text(*list, index);
+                if !text.is_empty() {
+                    text.clear();
+                }
+            }
+            continue;
+        }
+        // 実行条件のあるシステムには、最後にステップ実行したときの結果を付け、飛ばされたシステムは薄く表示する
+        let name_text = match (row.conditional, row.last_run) {
+            (false, _) => row.name.clone(),
+            (true, None) => format!("{}  [run_if]", row.name),
+            (true, Some(true)) => format!("{}  [run_if: true]", row.name),
+            (true, Some(false)) => format!("{}  [run_if: false]", row.name),
+        };
+        let Some((entity, _, mut text, _, _)) = writer.get(*list, row.text_index + 1) else {
+            continue;
+        };
+        if *text != name_text {
+            *text = name_text;
+        }
+        if let Ok(mut color) = colors.get_mut(entity) {
+            color.set_if_neq(if row.last_run == Some(false) {
+                SteppingColor::Skipped
+            } else {
+                SteppingColor::Text
+            });
+        }
+
+        // ブレークポイントを設定したシステムには "*" を、常に実行するシステムには "~" を表示する
+        let breakpoint = if state.breakpoints.contains(&(row.schedule, row.system)) {
+            "*"
+        } else if row.internal || state.always_run.contains(&(row.schedule, row.system)) {
+            "~"
+        } else {
+            " "
+        };
+        // 現在のカーソル位置にあるシステムには "->" を表示し、それ以外はスペースを表示
+        let arrow = if cursor == Some((row.schedule, row.system)) {
+            "-> "
+        } else {
+            "   "
+        };
+        // UI の対応するテキストを更新
+        *writer.text(*list, row.text_index) = format!("{breakpoint}{arrow}");
+    }
+}
+
+/// 絞り込みの文字列と、一覧に表示しているシステムの数を表示する
+fn update_filter_status(
+    state: Res<State>,
+    labels: Res<SteppingLabels>,
+    mut status: Single<&mut Text, With<FilterStatus>>,
+) {
+    if !state.is_changed() && !labels.is_changed() {
+        return;
+    }
+    let shown = state
+        .systems
+        .iter()
+        .filter(|row| state.is_shown(row))
+        .count();
+    // 入力中はカーソルの代わりに "_" を付ける
+    let filter = if state.filtering {
+        format!("{}_", state.filter)
+    } else {
+        state.filter.clone()
+    };
+    let internal = labels.get(if state.show_internal {
+        "stepping_internal_shown"
+    } else {
+        "stepping_internal_hidden"
+    });
+    status.0 = labels.format(
+        "stepping_filter_status",
+        &[
+            ("filter", &filter),
+            ("shown", &shown),
+            ("total", &state.systems.len()),
+            ("internal", &internal),
+        ],
+    );
+}
+
+/// 入力中のフレーム数と、まとめて進めている残りのフレーム数を表示する
+fn update_frame_count(
+    state: Res<State>,
+    labels: Res<SteppingLabels>,
+    mut text: Single<&mut Text, With<FrameCountText>>,
+) {
+    if !state.is_changed() && !labels.is_changed() {
+        return;
+    }
+    let count = if state.typed_frames > 0 {
+        labels.format("stepping_frames_typed", &[("count", &state.typed_frames)])
+    } else if let Some(frames_left) = state.frames_left {
+        labels.format("stepping_frames_left", &[("count", &frames_left)])
+    } else {
+        String::new()
+    };
+    if text.0 != count {
+        text.0 = count;
+    }
+}
+
+/// 表示しているシステムの行に、最後に実行したときの処理時間と平均を書き込む
+///
+/// 処理時間は `system_timings` フィーチャーを付けてビルドしたときだけ計るので、
+/// 付けていないとき (`SystemTimings` リソースがないとき) やまだ実行していないシステムは改行だけを書く。
+fn update_system_timings(
+    state: Res<State>,
+    stepping: Res<Stepping>,
+    timings: Option<Res<SystemTimings>>,
+    list: Single<Entity, With<SystemList>>,
+    mut writer: TextUiWriter,
+    mut colors: Query<&mut SteppingColor>,
+) {
+    if !stepping.is_enabled() {
+        return;
+    }
+    let cursor = stepping.cursor();
+    for row in state
+        .systems
+        .iter()
+        .filter(|row| state.is_visible(row, cursor))
+    {
+        let timing = timings.as_ref().and_then(|timings| timings.get(&row.name));
+        let Some((entity, _, mut text, _, _)) = writer.get(*list, row.text_index + 2) else {
+            continue;
+        };
+        let timing_text = match timing {
+            Some(timing) => format!(
+                "  {:.2} ms (avg {:.2} ms)\n",
+                timing.last.as_secs_f64() * 1000.0,
+                timing.average.as_secs_f64() * 1000.0,
+            ),
+            None => "\n".to_string(),
+        };
+        if *text != timing_text {
+            *text = timing_text;
+        }
+
+        let slow = timing.is_some_and(|timing| timing.average > SLOW_SYSTEM_THRESHOLD);
+        if let Ok(mut color) = colors.get_mut(entity) {
+            color.set_if_neq(if slow {
+                SteppingColor::Slow
+            } else {
+                SteppingColor::Text
+            });
+        }
+    }
+}
+
+/// カーソルのあるシステム (次に実行するシステム) が読み書きするコンポーネントとリソースを表示する
+///
+/// システムのパラメーターから求めたアクセス (`System::component_access`) を使うので、
+/// そのフレームに実際に読み書きしたものではなく、読み書きすると宣言したものを表示する。
+fn update_system_access(
+    stepping: Res<Stepping>,
+    schedules: Res<Schedules>,
+    components: &Components,
+    labels: Res<SteppingLabels>,
+    mut access_text: Single<&mut Text, With<SystemAccess>>,
+    mut last_cursor: Local<Option<(InternedScheduleLabel, NodeId)>>,
+) {
+    if !stepping.is_enabled() {
+        return;
+    }
+    // カーソルが動いたときと、言語が変わったときだけ書き直す
+    let cursor = stepping.cursor();
+    if cursor == *last_cursor && !labels.is_changed() && !access_text.0.is_empty() {
+        return;
+    }
+    *last_cursor = cursor;
+
+    let system = cursor.and_then(|(label, node_id)| {
+        schedules
+            .get(label)?
+            .systems()
+            .ok()?
+            .find(|(id, _)| *id == node_id)
+    });
+    access_text.0 = match system {
+        Some((_, system)) => describe_access(&**system, components, &labels),
+        None => labels.get("stepping_access_no_cursor").to_string(),
+    };
+}
+
+/// システムが読み書きすると宣言したコンポーネントとリソースの一覧を作る
+fn describe_access(
+    system: &dyn System<In = (), Out = ()>,
+    components: &Components,
+    labels: &SteppingLabels,
+) -> String {
+    let access = system.component_access();
+    // `&mut World` を受け取る排他システムは、World 全体を読み書きする
+    if access.has_write_all() {
+        return labels.format("stepping_access_world", &[("system", &system.name())]);
+    }
+
+    let name = |id: ComponentId| {
+        components
+            .get_name(id)
+            .map_or_else(|| format!("{id:?}"), short_type_name)
+    };
+    let join = |ids: &[ComponentId]| {
+        if ids.is_empty() {
+            "-".to_string()
+        } else {
+            ids.iter()
+                .map(|id| name(*id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+
+    // `EntityRef` などのすべてのコンポーネントを読むアクセスは、読まないコンポーネントの一覧になる
+    let (ids, inverted) = access.component_reads_and_writes();
+    let ids: Vec<ComponentId> = ids.collect();
+    let (component_reads, component_writes) = if inverted {
+        let all_except = labels.format("stepping_access_all_except", &[("names", &join(&ids))]);
+        let writes = if access.has_any_component_write() {
+            all_except.clone()
+        } else {
+            "-".to_string()
+        };
+        (all_except, writes)
+    } else {
+        let (writes, reads): (Vec<ComponentId>, Vec<ComponentId>) = ids
+            .into_iter()
+            .partition(|id| access.has_component_write(*id));
+        (join(&reads), join(&writes))
+    };
+    let resource_reads: Vec<ComponentId> = access.resource_reads().collect();
+    let resource_writes: Vec<ComponentId> = access.resource_writes().collect();
+
+    labels.format(
+        "stepping_access",
+        &[
+            ("system", &system.name()),
+            ("component_reads", &component_reads),
+            ("component_writes", &component_writes),
+            ("resource_reads", &join(&resource_reads)),
+            ("resource_writes", &join(&resource_writes)),
+        ],
+    )
+}
+
+/// 型の名前からモジュールのパスを取り除く (`bevy_ecs::event::Events<bevy_input::keyboard::KeyboardInput>` → `Events<KeyboardInput>`)
+fn short_type_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            // `::` の手前までの名前はモジュールなので捨てる
+            chars.next();
+            segment.clear();
+        } else if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else {
+            short.push_str(&segment);
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(&segment);
+    short
+}
+
+/// ステップ実行したフレームの記録 (`with_trace_export` を指定したときだけ追加する)
+#[derive(Resource)]
+struct TraceSession {
+    path: PathBuf,
+    frame_start: Option<(u32, Instant)>, // 記録中のフレームの番号と始まった時刻
+    frames: Vec<TracedFrame>,
+}
+
+/// ステップ実行したシステムのあった 1 フレームの記録
+struct TracedFrame {
+    number: u32,
+    start: Instant,
+    end: Instant,
+    runs: Vec<SystemRun>,
+}
+
+/// ステッピングが有効な間は、フレームの始まりからシステムの実行を記録する
+fn begin_trace_frame(
+    stepping: Res<Stepping>,
+    timings: Res<SystemTimings>,
+    frame: Res<FrameCount>,
+    mut session: ResMut<TraceSession>,
+) {
+    if !stepping.is_enabled() {
+        if timings.is_recording() {
+            timings.stop_recording();
+        }
+        return;
+    }
+    if timings.is_recording() {
+        // 前のフレームの終わりから今までに実行したシステムは、どのフレームにも含めない
+        timings.take_recorded_runs();
+    } else {
+        timings.start_recording();
+    }
+    session.frame_start = Some((frame.0, Instant::now()));
+}
+
+/// フレームの終わりに、ステップ実行するシステムの実行だけをフレームの記録に加える
+///
+/// `X` キーが押されたときと、アプリを終了するときに、それまでの記録をファイルに書き出す。
+fn end_trace_frame(
+    mut state: ResMut<State>,
+    timings: Res<SystemTimings>,
+    mut session: ResMut<TraceSession>,
+    mut exit: EventReader<AppExit>,
+) {
+    if let Some((number, start)) = session.frame_start.take() {
+        let stepped: HashSet<&str> = state
+            .systems
+            .iter()
+            .filter(|row| !row.internal)
+            .map(|row| row.name.as_str())
+            .collect();
+        let runs: Vec<SystemRun> = timings
+            .take_recorded_runs()
+            .into_iter()
+            .filter(|run| stepped.contains(run.name.as_str()))
+            .collect();
+        // 止まっていたフレーム (ステップ実行したシステムがない) は記録しない
+        if !runs.is_empty() {
+            session.frames.push(TracedFrame {
+                number,
+                start,
+                end: Instant::now(),
+                runs,
+            });
+        }
+    }
+
+    let exiting = exit.read().count() > 0;
+    if !std::mem::take(&mut state.export_trace) && !exiting {
+        return;
+    }
+    if session.frames.is_empty() {
+        debug!("no stepped frames to export");
+        return;
+    }
+    match write_trace(&session) {
+        Ok(()) => info!(
+            "exported {} stepped frame(s) to {}",
+            session.frames.len(),
+            session.path.display()
+        ),
+        Err(error) => warn!("could not export the stepping trace: {error}"),
+    }
+}
+
+/// Trace Event Format の JSON (`chrome://tracing` や Perfetto で開ける)
+#[derive(Serialize)]
+struct ChromeTrace {
+    #[serde(rename = "traceEvents")]
+    trace_events: Vec<TraceEvent>,
+}
+
+/// 始まりと長さを持つイベント (`"ph": "X"`) 1 つ (時刻と長さはマイクロ秒)
+#[derive(Serialize)]
+struct TraceEvent {
+    name: String,
+    cat: &'static str,
+    ph: &'static str,
+    ts: f64,
+    dur: f64,
+    pid: u32,
+    tid: u64,
+    args: TraceArgs,
+}
+
+#[derive(Serialize)]
+struct TraceArgs {
+    frame: u32, // ステップ実行したフレームの番号 (`FrameCount`)
+}
+
+/// 記録したフレームを Trace Event Format の JSON にして書き出す
+///
+/// 時刻は最初のフレームの始まりから数える。フレームはスレッド 0 に、
+/// システムは実行したスレッドごとに 1 から番号を振ったスレッドに並べる。
+fn write_trace(session: &TraceSession) -> io::Result<()> {
+    let Some(origin) = session.frames.first().map(|frame| frame.start) else {
+        return Ok(());
+    };
+    let micros = |duration: Duration| duration.as_secs_f64() * 1_000_000.0;
+
+    let mut threads: HashMap<ThreadId, u64> = HashMap::new();
+    let mut trace_events = Vec::new();
+    for frame in &session.frames {
+        trace_events.push(TraceEvent {
+            name: format!("frame {}", frame.number),
+            cat: "frame",
+            ph: "X",
+            ts: micros(frame.start.duration_since(origin)),
+            dur: micros(frame.end.duration_since(frame.start)),
+            pid: 1,
+            tid: 0,
+            args: TraceArgs {
+                frame: frame.number,
+            },
+        });
+        for run in &frame.runs {
+            let next = threads.len() as u64 + 1;
+            let tid = *threads.entry(run.thread).or_insert(next);
+            trace_events.push(TraceEvent {
+                name: run.name.clone(),
+                cat: "system",
+                ph: "X",
+                ts: micros(run.start.duration_since(origin)),
+                dur: micros(run.duration),
+                pid: 1,
+                tid,
+                args: TraceArgs {
+                    frame: frame.number,
+                },
+            });
+        }
+    }
+
+    let json = serde_json::to_vec(&ChromeTrace { trace_events })?;
+    if let Some(dir) = session.path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&session.path, json)
+}
+
+/// マウスホイールと `PageUp` / `PageDown` キーでシステムの一覧をスクロールする
+///
+/// スクロールできる範囲には、`bevy_ui` のレイアウトが `ScrollPosition` を収めてくれる。
+fn scroll_list(
+    mut wheel_events: EventReader<MouseWheel>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    stepping: Res<Stepping>,
+    state: Res<State>,
+    viewport: Single<
+        (&mut ScrollPosition, &ComputedNode, &RelativeCursorPosition),
+        With<SystemListViewport>,
+    >,
+) {
+    let (mut scroll, node, cursor) = viewport.into_inner();
+    // カーソルが一覧の上になければ、ホイールはゲームの操作として読み捨てる
+    let over_list = stepping.is_enabled() && cursor.mouse_over();
+    for event in wheel_events.read() {
+        if !over_list {
+            continue;
+        }
+        let dy = match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        scroll.offset_y -= dy;
+    }
+
+    if !stepping.is_enabled() || state.filtering {
+        return;
+    }
+    // 1 ページは枠の高さより少し短くして、前のページの最後の行が残るようにする
+    let page =
+        (node.size().y * node.inverse_scale_factor() - FOLLOW_MARGIN).max(SCROLL_LINE_HEIGHT);
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        scroll.offset_y = (scroll.offset_y - page).max(0.0);
+    } else if keyboard_input.just_pressed(KeyCode::PageDown) {
+        scroll.offset_y += page;
+    }
+}
+
+/// ステップ実行でカーソルが別のシステムに移ったら、その行が枠の中に見えるように一覧をスクロールする
+///
+/// カーソルが動いたときだけスクロールするので、止まっている間は手で自由にスクロールできる。
+/// 行の位置はテキストのレイアウト (`TextLayoutInfo`) の文字の位置から求める。
+fn follow_cursor(
+    stepping: Res<Stepping>,
+    state: Res<State>,
+    list: Single<(&ComputedNode, &TextLayoutInfo), With<SystemList>>,
+    viewport: Single<(&mut ScrollPosition, &ComputedNode), With<SystemListViewport>>,
+    mut followed: Local<Option<(InternedScheduleLabel, NodeId)>>,
+) {
+    let cursor = stepping.cursor();
+    if !stepping.is_enabled() || cursor.is_none() || cursor == *followed {
+        return;
+    }
+    let Some(row) = state
+        .systems
+        .iter()
+        .find(|row| cursor == Some((row.schedule, row.system)))
+    else {
+        return;
+    };
+
+    // 文字の位置は物理ピクセル、`ScrollPosition` は論理ピクセルで表される
+    let (list_node, layout) = *list;
+    let scale = list_node.inverse_scale_factor();
+    let Some((top, bottom)) = layout
+        .glyphs
+        .iter()
+        .filter(|glyph| (row.text_index..=row.text_index + 2).contains(&glyph.span_index))
+        .map(|glyph| {
+            (
+                (glyph.position.y - glyph.size.y / 2.0) * scale,
+                (glyph.position.y + glyph.size.y / 2.0) * scale,
+            )
+        })
+        .reduce(|(top, bottom), (glyph_top, glyph_bottom)| {
+            (top.min(glyph_top), bottom.max(glyph_bottom))
+        })
+    else {
+        // 隠していた行を表示したばかりで、まだ文字が並んでいなければ次のフレームでもう一度調べる
+        return;
+    };
+    *followed = cursor;
+
+    let (mut scroll, viewport_node) = viewport.into_inner();
+    let height = viewport_node.size().y * viewport_node.inverse_scale_factor();
+    if top - FOLLOW_MARGIN < scroll.offset_y {
+        scroll.offset_y = (top - FOLLOW_MARGIN).max(0.0);
+    } else if bottom + FOLLOW_MARGIN > scroll.offset_y + height {
+        scroll.offset_y = bottom + FOLLOW_MARGIN - height;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,82 @@
+//! ステッピングのリモート操作 (`remote_control` フィーチャー) にコマンドを送る小さなクライアント。
+//!
+//! ```sh
+//! cargo run -- step                         # コマンドを 1 つ送り、返事を表示して終わる
+//! cargo run -- break check_for_collisions   # 引数はまとめて 1 行のコマンドになる
+//! cargo run -- --port 7879 status           # 待ち受けているポートを指定する
+//! cargo run                                 # 標準入力から 1 行ずつ送る
+//! ```
+//!
+//! 返事が `error:` で始まったときは、終了コードを失敗にする。
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{Ipv4Addr, TcpStream},
+    process::ExitCode,
+};
+
+use debug_stepping::DEFAULT_REMOTE_PORT;
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let port = match args.iter().position(|arg| arg == "--port") {
+        Some(index) => {
+            let port = args.get(index + 1).and_then(|port| port.parse().ok());
+            args.drain(index..(index + 2).min(args.len()));
+            match port {
+                Some(port) => port,
+                None => {
+                    eprintln!("--port needs a port number");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        None => DEFAULT_REMOTE_PORT,
+    };
+
+    let stream = match TcpStream::connect((Ipv4Addr::LOCALHOST, port)) {
+        Ok(stream) => stream,
+        Err(error) => {
+            eprintln!("could not connect to 127.0.0.1:{port}: {error}");
+            eprintln!("(is the app running with the `remote_control` feature?)");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // 引数があればそれを 1 つのコマンドとして送り、なければ標準入力の行を順に送る
+    let commands: Box<dyn Iterator<Item = String>> = if args.is_empty() {
+        Box::new(io::stdin().lines().map_while(Result::ok))
+    } else {
+        Box::new(std::iter::once(args.join(" ")))
+    };
+    let mut failed = false;
+    // 空の行にはサーバーが返事をしないので送らない
+    for command in commands.filter(|command| !command.trim().is_empty()) {
+        match send(&stream, &command) {
+            Ok(response) => {
+                println!("{response}");
+                failed |= response.starts_with("error:");
+            }
+            Err(error) => {
+                eprintln!("connection closed: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// コマンドを 1 行送り、返事の 1 行を待つ
+fn send(stream: &TcpStream, command: &str) -> io::Result<String> {
+    let mut writer = stream;
+    writer.write_all(format!("{command}\n").as_bytes())?;
+    let mut response = String::new();
+    if BufReader::new(stream).read_line(&mut response)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(response.trim_end().to_string())
+}
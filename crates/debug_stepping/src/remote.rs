@@ -0,0 +1,238 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+};
+
+use bevy::{ecs::schedule::Stepping, prelude::*};
+
+use crate::{handle_input, DebugSchedule, State, MAX_STEP_FRAMES};
+
+// 1 回の読み込みで受け取るバイト数
+const READ_CHUNK: usize = 1024;
+// `help` で返すコマンドの一覧
+const HELP: &str =
+    "commands: enable, disable, step, frame, frames <n>, continue, break <system>, unbreak <system>, status";
+
+/// ステッピングを操作するコマンドを受け付ける、localhost の TCP ソケット
+///
+/// ソケットはノンブロッキングにして、毎フレーム [`handle_remote_commands`] が届いた分だけ読む。
+#[derive(Resource)]
+struct RemoteControl {
+    listener: TcpListener,
+    clients: Vec<RemoteClient>,
+}
+
+/// 接続しているクライアント
+struct RemoteClient {
+    stream: TcpStream,
+    pending: Vec<u8>, // まだ改行が届いていないコマンドの途中までのバイト列
+}
+
+/// `127.0.0.1:<port>` で接続を待ち受け、コマンドを処理するシステムを追加する
+pub(crate) fn listen(app: &mut App, port: u16) {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+        Ok(listener) => listener,
+        Err(error) => {
+            warn!("could not listen for remote stepping control on port {port}: {error}");
+            return;
+        }
+    };
+    if let Err(error) = listener.set_nonblocking(true) {
+        warn!("could not listen for remote stepping control on port {port}: {error}");
+        return;
+    }
+    info!("remote stepping control listening on 127.0.0.1:{port}");
+    // ボタンやキーの操作と同じ順番で扱えるように、キー入力の処理の前に実行する
+    app.insert_resource(RemoteControl {
+        listener,
+        clients: Vec::new(),
+    })
+    .add_systems(DebugSchedule, handle_remote_commands.before(handle_input));
+}
+
+/// 新しい接続を受け付け、届いたコマンドを 1 行ずつ実行して結果を 1 行で返す
+///
+/// 切断したクライアントと、書き込みに失敗したクライアントは捨てる。
+fn handle_remote_commands(
+    mut remote: ResMut<RemoteControl>,
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+) {
+    let remote = &mut *remote;
+    loop {
+        match remote.listener.accept() {
+            Ok((stream, address)) => {
+                if stream.set_nonblocking(true).is_err() {
+                    continue;
+                }
+                debug!("remote stepping client connected from {address}");
+                remote.clients.push(RemoteClient {
+                    stream,
+                    pending: Vec::new(),
+                });
+            }
+            Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+            Err(error) => {
+                warn!("remote stepping control stopped accepting connections: {error}");
+                break;
+            }
+        }
+    }
+
+    remote.clients.retain_mut(|client| {
+        let Some(lines) = client.read_lines() else {
+            debug!("remote stepping client disconnected");
+            return false;
+        };
+        for line in lines {
+            let response = match run_command(&line, &mut stepping, &mut state) {
+                Ok(message) => format!("ok{message}\n"),
+                Err(message) => format!("error: {message}\n"),
+            };
+            if client.stream.write_all(response.as_bytes()).is_err() {
+                return false;
+            }
+        }
+        true
+    });
+}
+
+impl RemoteClient {
+    /// 届いた分を読み、改行までそろったコマンドを返す (切断したら `None`)
+    fn read_lines(&mut self) -> Option<Vec<String>> {
+        let mut buffer = [0; READ_CHUNK];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return None,
+                Ok(read) => self.pending.extend_from_slice(&buffer[..read]),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return None,
+            }
+        }
+
+        let mut lines = Vec::new();
+        while let Some(end) = self.pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            // telnet は行の終わりに "\r\n" を送るので、前後の空白ごと取り除く
+            let line = String::from_utf8_lossy(&line).trim().to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        Some(lines)
+    }
+}
+
+/// 1 行のコマンドを実行する (成功したら `ok` に続けて返す文字列、失敗したらその理由)
+///
+/// ステップ実行のコマンドは次のフレームで実行されるので、進んだ先のカーソルは `status` で調べる。
+fn run_command(line: &str, stepping: &mut Stepping, state: &mut State) -> Result<String, String> {
+    let (command, argument) = line
+        .split_once(char::is_whitespace)
+        .map_or((line, ""), |(command, argument)| (command, argument.trim()));
+    match command {
+        "enable" => {
+            stepping.enable();
+            Ok(String::new())
+        }
+        "disable" => {
+            state.running = false;
+            stepping.disable();
+            Ok(String::new())
+        }
+        "step" | "frame" | "continue" => {
+            if !stepping.is_enabled() {
+                return Err("stepping is disabled (send `enable` first)".to_string());
+            }
+            state.advance();
+            if command == "step" {
+                stepping.step_frame();
+            } else {
+                stepping.continue_frame();
+            }
+            // `continue` は「Continue」ボタンと同じく、ブレークポイントに着くまで進め続ける
+            if command == "continue" {
+                state.running = true;
+            }
+            Ok(String::new())
+        }
+        "frames" => {
+            if !stepping.is_enabled() {
+                return Err("stepping is disabled (send `enable` first)".to_string());
+            }
+            let frames = argument
+                .parse::<usize>()
+                .ok()
+                .filter(|frames| (1..=MAX_STEP_FRAMES).contains(frames))
+                .ok_or_else(|| format!("usage: frames <1-{MAX_STEP_FRAMES}>"))?;
+            state.step_frames(frames);
+            Ok(String::new())
+        }
+        "break" | "unbreak" => {
+            if argument.is_empty() {
+                return Err(format!("usage: {command} <system>"));
+            }
+            let matches = set_breakpoints(argument, command == "break", stepping, state)?;
+            Ok(format!(" {matches} system(s)"))
+        }
+        "status" => Ok(status(stepping, state)),
+        "help" => Ok(format!(" {HELP}")),
+        _ => Err(format!(
+            "unknown command `{command}` (send `help` for the list)"
+        )),
+    }
+}
+
+/// 名前が一致するシステム (パスを省いた名前でもよい) のブレークポイントを設定・解除し、その数を返す
+fn set_breakpoints(
+    name: &str,
+    set: bool,
+    stepping: &mut Stepping,
+    state: &mut State,
+) -> Result<usize, String> {
+    if state.systems.is_empty() {
+        return Err("the system list is not ready yet".to_string());
+    }
+    let suffix = format!("::{name}");
+    // 常に実行するシステムにはブレークポイントを設定できないので除く
+    let nodes: Vec<_> = state
+        .systems
+        .iter()
+        .filter(|row| !row.internal && (row.name == name || row.name.ends_with(&suffix)))
+        .map(|row| (row.schedule, row.system))
+        .collect();
+    if nodes.is_empty() {
+        return Err(format!("no stepped system named `{name}`"));
+    }
+    for (schedule, system) in &nodes {
+        if set {
+            state.always_run.remove(&(*schedule, *system));
+            state.breakpoints.insert((*schedule, *system));
+            stepping.set_breakpoint_node(*schedule, *system);
+        } else {
+            state.breakpoints.remove(&(*schedule, *system));
+            stepping.clear_breakpoint_node(*schedule, *system);
+        }
+    }
+    Ok(nodes.len())
+}
+
+/// ステッピングが有効か、止まっているシステム (カーソル)、ブレークポイントまで進め続けているかと、まとめて進める残りのフレーム数
+fn status(stepping: &Stepping, state: &State) -> String {
+    let cursor = stepping
+        .cursor()
+        .and_then(|cursor| {
+            state
+                .systems
+                .iter()
+                .find(|row| (row.schedule, row.system) == cursor)
+        })
+        .map_or("none", |row| row.name.as_str());
+    format!(
+        " enabled={} cursor={cursor} running={} frames_left={}",
+        stepping.is_enabled(),
+        state.running,
+        state.frames_left.unwrap_or(0)
+    )
+}
@@ -0,0 +1,160 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    ecs::schedule::{InternedScheduleLabel, NodeId, Stepping},
+    prelude::*,
+    utils::HashSet,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{build_ui, handle_input, initialized, DebugSchedule, State};
+
+/// ステッピングの設定を保存するファイルと、起動時に読み込んでまだ戻していない設定
+#[derive(Resource)]
+struct SessionFile {
+    path: PathBuf,
+    pending: Option<SavedSession>,
+}
+
+/// 次の起動に持ち越すステッピングの設定 (RON 形式で保存する)
+///
+/// 再コンパイルすると `NodeId` は変わりうるので、システムはスケジュール名とシステム名で覚えておく。
+#[derive(Serialize, Deserialize, Debug)]
+struct SavedSession {
+    enabled: bool,                 // ステッピングが有効だったか
+    breakpoints: Vec<SavedSystem>, // ブレークポイントを設定したシステム
+    always_run: Vec<SavedSystem>,  // 右クリックで常に実行するようにしたシステム
+}
+
+/// スケジュール名とシステム名で表した 1 つのシステム
+#[derive(Serialize, Deserialize, Debug)]
+struct SavedSystem {
+    schedule: String,
+    system: String,
+}
+
+/// 保存した設定を読み込み、起動時に戻すシステムと、終了時に保存するシステムを追加する
+pub(crate) fn restore_and_save(app: &mut App, path: PathBuf) {
+    let pending = match fs::read_to_string(&path) {
+        Ok(text) => match ron::from_str::<SavedSession>(&text) {
+            Ok(session) => Some(session),
+            Err(error) => {
+                warn!(
+                    "could not read the stepping session {}: {error}",
+                    path.display()
+                );
+                None
+            }
+        },
+        // 初めての起動ではファイルがない
+        Err(_) => None,
+    };
+    app.insert_resource(SessionFile { path, pending })
+        // システムの一覧ができてから、キー入力の処理の前に戻す
+        .add_systems(
+            DebugSchedule,
+            restore_session
+                .after(build_ui)
+                .before(handle_input)
+                .run_if(initialized),
+        )
+        .add_systems(Last, save_session);
+}
+
+/// 保存したブレークポイントと常に実行するシステムを一覧のシステムに設定し、ステッピングが有効だったなら有効にする
+///
+/// 名前の変わったシステムや、削除したシステムは読み飛ばす。
+fn restore_session(
+    mut file: ResMut<SessionFile>,
+    mut stepping: ResMut<Stepping>,
+    mut state: ResMut<State>,
+) {
+    let Some(session) = file.pending.take() else {
+        return;
+    };
+    let mut missing = 0;
+    for (saved, always_run) in session
+        .breakpoints
+        .iter()
+        .map(|saved| (saved, false))
+        .chain(session.always_run.iter().map(|saved| (saved, true)))
+    {
+        let Some((schedule, system)) = find_system(&state, saved) else {
+            missing += 1;
+            continue;
+        };
+        if always_run {
+            state.always_run.insert((schedule, system));
+            stepping.always_run_node(schedule, system);
+        } else {
+            state.breakpoints.insert((schedule, system));
+            stepping.set_breakpoint_node(schedule, system);
+        }
+    }
+    if missing > 0 {
+        debug!("{missing} saved stepping system(s) no longer exist");
+    }
+    if session.enabled {
+        stepping.enable();
+    }
+}
+
+/// アプリを終了するときに、ブレークポイント・常に実行するシステム・ステッピングが有効かを保存する
+fn save_session(
+    file: Res<SessionFile>,
+    stepping: Res<Stepping>,
+    state: Res<State>,
+    mut exit: EventReader<AppExit>,
+) {
+    if exit.read().count() == 0 {
+        return;
+    }
+    // まだ一覧ができていない (最初のフレームで終了した) ときは、前の設定を残す
+    if state.systems.is_empty() {
+        return;
+    }
+    let session = SavedSession {
+        enabled: stepping.is_enabled(),
+        breakpoints: saved_systems(&state, &state.breakpoints),
+        always_run: saved_systems(&state, &state.always_run),
+    };
+    let result = ron::ser::to_string_pretty(&session, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|text| fs::write(&file.path, text).map_err(|error| error.to_string()));
+    match result {
+        Ok(()) => info!("saved the stepping session to {}", file.path.display()),
+        Err(error) => warn!(
+            "could not save the stepping session {}: {error}",
+            file.path.display()
+        ),
+    }
+}
+
+/// 一覧のシステムのうち `nodes` に含まれるものを、名前で表す (一覧の順に並べて、ファイルの差分を小さくする)
+fn saved_systems(
+    state: &State,
+    nodes: &HashSet<(InternedScheduleLabel, NodeId)>,
+) -> Vec<SavedSystem> {
+    state
+        .systems
+        .iter()
+        .filter(|row| nodes.contains(&(row.schedule, row.system)))
+        .map(|row| SavedSystem {
+            schedule: format!("{:?}", row.schedule),
+            system: row.name.clone(),
+        })
+        .collect()
+}
+
+/// 名前で表したシステムを一覧から探す (常に実行する Bevy 内部のシステムは除く)
+fn find_system(state: &State, saved: &SavedSystem) -> Option<(InternedScheduleLabel, NodeId)> {
+    state
+        .systems
+        .iter()
+        .find(|row| {
+            !row.internal
+                && row.name == saved.system
+                && format!("{:?}", row.schedule) == saved.schedule
+        })
+        .map(|row| (row.schedule, row.system))
+}
@@ -0,0 +1,304 @@
+use std::fmt::Display;
+
+use bevy::{prelude::*, ui::UiSystem, utils::HashMap};
+
+/// ステッピング UI の色・フォント・文字列を、リソースの値に合わせるプラグイン
+pub(crate) struct StylePlugin;
+
+impl Plugin for StylePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SteppingTheme>()
+            .init_resource::<SteppingLabels>()
+            // ステップ実行するスケジュールに入れると止まってしまうので、`PostUpdate` で塗る
+            .add_systems(
+                PostUpdate,
+                (apply_colors, apply_fonts, apply_labels).before(UiSystem::Prepare),
+            );
+    }
+}
+
+/// ステッピング UI の見た目 (配色・パネルの背景・フォント・文字の大きさ)
+///
+/// [`SteppingPlugin::with_theme`](crate::SteppingPlugin::with_theme) で最初の値を指定する。
+/// 実行中にこのリソースを書き換えると、UI 全体が塗り直される (アプリの配色の切り替えに合わせるときなど)。
+/// 既定は明るい画面向けの [`SteppingTheme::light`]、暗い画面向けには [`SteppingTheme::dark`] がある。
+#[derive(Resource, Debug, Clone)]
+pub struct SteppingTheme {
+    pub text: Color,                  // 文字
+    pub button: Color,                // ボタンの背景
+    pub button_hovered: Color,        // カーソルが乗っているボタンの背景
+    pub slow: Color,                  // 処理時間がしきい値を超えたシステム
+    pub skipped: Color,               // 実行条件で飛ばされたシステム
+    pub panel: Color,                 // パネルの背景 (不透明度でゲームの画面の透け具合が決まる)
+    pub font: Option<String>,         // 文字のフォントの `assets` からのパス (なければ既定)
+    pub heading_font: Option<String>, // スケジュール名の見出しのフォント (なければ `font`)
+    pub font_size: f32,               // システムの一覧と見出しの文字の大きさ
+    pub label_font_size: f32,         // ボタンや状態の表示の文字の大きさ
+}
+
+impl SteppingTheme {
+    /// 明るい画面向けの配色 (半透明の白いパネルに暗い文字)
+    pub fn light() -> SteppingTheme {
+        SteppingTheme {
+            text: Color::srgb(0.2, 0.2, 0.2),
+            button: Color::srgba(1.0, 1.0, 1.0, 0.6),
+            button_hovered: Color::srgba(0.6, 0.6, 1.0, 0.8),
+            slow: Color::srgb(0.8, 0.1, 0.1),
+            skipped: Color::srgb(0.55, 0.55, 0.55),
+            panel: Color::srgba(1.0, 1.0, 1.0, 0.33),
+            font: None,
+            heading_font: None,
+            font_size: 20.0,
+            label_font_size: 14.0,
+        }
+    }
+
+    /// 暗い画面向けの配色 (半透明の黒いパネルに明るい文字)
+    pub fn dark() -> SteppingTheme {
+        SteppingTheme {
+            text: Color::srgb(0.88, 0.88, 0.88),
+            button: Color::srgba(0.25, 0.25, 0.3, 0.8),
+            button_hovered: Color::srgba(0.35, 0.35, 0.6, 0.9),
+            slow: Color::srgb(1.0, 0.45, 0.4),
+            skipped: Color::srgb(0.5, 0.5, 0.5),
+            panel: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            ..SteppingTheme::light()
+        }
+    }
+
+    /// パネルの背景の不透明度 (0.0 で透明、1.0 で不透明) を変える
+    pub fn with_panel_opacity(mut self, opacity: f32) -> SteppingTheme {
+        self.panel.set_alpha(opacity.clamp(0.0, 1.0));
+        self
+    }
+
+    /// 文字のフォントをアプリの `assets` からのパスで指定する
+    pub fn with_font(mut self, path: impl Into<String>) -> SteppingTheme {
+        self.font = Some(path.into());
+        self
+    }
+
+    /// スケジュール名の見出しのフォントをアプリの `assets` からのパスで指定する
+    pub fn with_heading_font(mut self, path: impl Into<String>) -> SteppingTheme {
+        self.heading_font = Some(path.into());
+        self
+    }
+
+    /// システムの一覧と、ボタンや状態の表示の文字の大きさを変える
+    pub fn with_font_size(mut self, font_size: f32, label_font_size: f32) -> SteppingTheme {
+        self.font_size = font_size;
+        self.label_font_size = label_font_size;
+        self
+    }
+
+    fn color(&self, color: SteppingColor) -> Color {
+        match color {
+            SteppingColor::Text => self.text,
+            SteppingColor::Button => self.button,
+            SteppingColor::ButtonHovered => self.button_hovered,
+            SteppingColor::Slow => self.slow,
+            SteppingColor::Skipped => self.skipped,
+            SteppingColor::Panel => self.panel,
+        }
+    }
+}
+
+impl Default for SteppingTheme {
+    fn default() -> Self {
+        SteppingTheme::light()
+    }
+}
+
+/// ステッピング UI のエンティティの色の種類
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SteppingColor {
+    Text,
+    Button,
+    ButtonHovered,
+    Slow,
+    Skipped,
+    Panel,
+}
+
+/// ステッピング UI のテキストのフォントと文字の大きさの種類
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SteppingFont {
+    Heading, // スケジュール名の見出し
+    List,    // システムの一覧の行
+    Label,   // ボタンや状態の表示 ([`SteppingLabels`] のフォントがあればそちらを使う)
+}
+
+/// ステッピング UI の文字列と、その言語のフォント
+///
+/// 既定は英語の文字列で、フォントは [`SteppingTheme`] のもの。アプリの言語に合わせるときは、
+/// [`SteppingLabels::KEYS`] の各キーの文字列を [`SteppingLabels::set`] で置き換える。
+/// 文字列の `{name}` には UI が値を埋め込む。
+#[derive(Resource, Debug, Clone)]
+pub struct SteppingLabels {
+    texts: HashMap<&'static str, String>,
+    font: Option<Handle<Font>>, // ボタンや状態の表示のフォント (システムの一覧には使わない)
+}
+
+impl SteppingLabels {
+    // UI が使う文字列のキー
+    pub const KEYS: [&'static str; 18] = [
+        "stepping_hint",
+        "stepping_unsupported",
+        "stepping_step_back",
+        "stepping_step_system",
+        "stepping_step_frame",
+        "stepping_continue",
+        "stepping_disable",
+        "stepping_filter",
+        "stepping_internal",
+        "stepping_filter_status",
+        "stepping_internal_shown",
+        "stepping_internal_hidden",
+        "stepping_access",
+        "stepping_access_world",
+        "stepping_access_all_except",
+        "stepping_access_no_cursor",
+        "stepping_frames_typed",
+        "stepping_frames_left",
+    ];
+
+    /// キーに対応する文字列 (知らないキーならキーそのもの)
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.texts.get(key).map_or(key, String::as_str)
+    }
+
+    /// キーの文字列を置き換える ([`SteppingLabels::KEYS`] にないキーは無視する)
+    pub fn set(&mut self, key: &str, text: impl Into<String>) {
+        if let Some(value) = self.texts.get_mut(key) {
+            *value = text.into();
+        }
+    }
+
+    /// ボタンや状態の表示に使うフォントを、[`SteppingTheme`] のフォントの代わりに使う
+    ///
+    /// 文字列の言語に合わせたフォント (日本語なら日本語のグリフのあるフォント) を指定するため。
+    pub fn set_font(&mut self, font: Handle<Font>) {
+        self.font = Some(font);
+    }
+
+    /// キーに対応する文字列の `{name}` を値に置き換える
+    pub(crate) fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+}
+
+impl Default for SteppingLabels {
+    fn default() -> Self {
+        let english = [
+            "Press ` to toggle stepping mode (S: step system, Space: step frame, B: step back, Shift + number keys: step that many frames, F: filter systems, I: toggle internal systems, H: collapse the current schedule, PageUp/PageDown or mouse wheel: scroll the list, X: export trace, click a system: toggle breakpoint, right-click a system: always run it, click a schedule: collapse it, or use the panel buttons)",
+            "Bevy was compiled without stepping support. Run with `--features=bevy_debug_stepping` to enable stepping.",
+            "Step Back",
+            "Step System",
+            "Step Frame",
+            "Continue",
+            "Disable",
+            "Filter",
+            "Internal",
+            "Filter: {filter}  ({shown} / {total} systems, internal systems {internal})",
+            "shown",
+            "hidden",
+            "{system}\n  components read: {component_reads}\n  components written: {component_writes}\n  resources read: {resource_reads}\n  resources written: {resource_writes}",
+            "{system}\n  exclusive system: reads and writes the whole World",
+            "all except {names}",
+            "No system under the cursor (start of the frame)",
+            "Step {count} frame(s): release Shift to start",
+            "{count} frame(s) left",
+        ];
+        SteppingLabels {
+            texts: SteppingLabels::KEYS
+                .into_iter()
+                .zip(english.map(String::from))
+                .collect(),
+            font: None,
+        }
+    }
+}
+
+/// [`SteppingLabels`] のキーで内容を指定するテキスト
+#[derive(Component)]
+pub(crate) struct SteppingLabel(pub &'static str);
+
+/// [`SteppingColor`] を付けたエンティティを [`SteppingTheme`] の色で塗る
+///
+/// 配色が変わったときはすべてのエンティティを、そうでなければ色の種類が追加・変更されたエンティティだけを塗り直す。
+fn apply_colors(
+    theme: Res<SteppingTheme>,
+    mut colored: Query<(
+        Ref<SteppingColor>,
+        Option<&mut TextColor>,
+        Option<&mut BackgroundColor>,
+    )>,
+) {
+    let refresh_all = theme.is_changed();
+    for (color, text_color, background_color) in &mut colored {
+        if !refresh_all && !color.is_changed() {
+            continue;
+        }
+        let value = theme.color(*color);
+        if let Some(mut text_color) = text_color {
+            text_color.0 = value;
+        }
+        if let Some(mut background_color) = background_color {
+            background_color.0 = value;
+        }
+    }
+}
+
+/// [`SteppingFont`] を付けたテキストのフォントと文字の大きさを、[`SteppingTheme`] と [`SteppingLabels`] に合わせる
+fn apply_fonts(
+    theme: Res<SteppingTheme>,
+    labels: Res<SteppingLabels>,
+    asset_server: Res<AssetServer>,
+    mut texts: Query<(Ref<SteppingFont>, &mut TextFont)>,
+) {
+    let refresh_all = theme.is_changed() || labels.is_changed();
+    let load = |path: &Option<String>| {
+        path.as_ref()
+            .map(|path| asset_server.load(path.clone()))
+            .unwrap_or_default()
+    };
+    let font = load(&theme.font);
+    let heading_font = match theme.heading_font {
+        Some(_) => load(&theme.heading_font),
+        None => font.clone(),
+    };
+    for (kind, mut text_font) in &mut texts {
+        if !refresh_all && !kind.is_added() {
+            continue;
+        }
+        match *kind {
+            SteppingFont::Heading => {
+                text_font.font = heading_font.clone();
+                text_font.font_size = theme.font_size;
+            }
+            SteppingFont::List => {
+                text_font.font = font.clone();
+                text_font.font_size = theme.font_size;
+            }
+            SteppingFont::Label => {
+                text_font.font = labels.font.clone().unwrap_or_else(|| font.clone());
+                text_font.font_size = theme.label_font_size;
+            }
+        }
+    }
+}
+
+/// [`SteppingLabel`] の文字列を [`SteppingLabels`] に合わせる
+fn apply_labels(labels: Res<SteppingLabels>, mut texts: Query<(Ref<SteppingLabel>, &mut Text)>) {
+    let refresh_all = labels.is_changed();
+    for (label, mut text) in &mut texts {
+        if !refresh_all && !label.is_added() {
+            continue;
+        }
+        text.0 = labels.get(label.0).to_string();
+    }
+}
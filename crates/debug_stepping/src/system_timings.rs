@@ -0,0 +1,202 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    log::{
+        tracing_subscriber::{layer::Context, registry::LookupSpan, Layer},
+        BoxedLayer,
+    },
+    prelude::*,
+    utils::tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id},
+        Subscriber,
+    },
+};
+
+// Bevy がシステムを実行するたびに入る span の名前 (`trace` フィーチャーが有効なときだけ作られる)
+const SYSTEM_SPAN: &str = "system";
+// 平均 (指数移動平均) に新しい処理時間を混ぜる割合
+const AVERAGE_WEIGHT: f64 = 0.1;
+
+/// 1 つのシステムの処理時間
+#[derive(Debug, Clone, Copy)]
+pub struct SystemTiming {
+    pub last: Duration,    // 最後に実行したときの処理時間
+    pub average: Duration, // 処理時間の指数移動平均
+}
+
+/// 記録した 1 回のシステムの実行
+#[derive(Debug, Clone)]
+pub struct SystemRun {
+    pub name: String,
+    pub thread: ThreadId, // システムを実行したスレッド
+    pub start: Instant,
+    pub duration: Duration,
+}
+
+/// システムの名前 (`System::name`) ごとの処理時間
+///
+/// 値は [`system_timing_layer`] が作る tracing のレイヤーが、システムを実行したスレッドから書き込む。
+/// 同じシステムを複数のスケジュールに追加したときは、同じ名前の 1 つの値にまとめられる。
+/// [`SystemTimings::start_recording`] を呼ぶと、1 回ごとの実行も順に記録する。
+#[derive(Resource, Clone, Default)]
+pub struct SystemTimings(Arc<Mutex<Timings>>);
+
+#[derive(Default)]
+struct Timings {
+    systems: HashMap<String, SystemTiming>,
+    runs: Option<Vec<SystemRun>>, // 記録中なら、実行したシステムを終わった順に並べる
+}
+
+impl SystemTimings {
+    /// システムの処理時間 (まだ 1 度も実行していなければ `None`)
+    pub fn get(&self, name: &str) -> Option<SystemTiming> {
+        self.0.lock().ok()?.systems.get(name).copied()
+    }
+
+    /// 1 回ごとのシステムの実行の記録を (それまでの記録を捨てて) 始める
+    pub fn start_recording(&self) {
+        if let Ok(mut timings) = self.0.lock() {
+            timings.runs = Some(Vec::new());
+        }
+    }
+
+    /// 記録をやめて、まだ取り出していない記録を捨てる
+    pub fn stop_recording(&self) {
+        if let Ok(mut timings) = self.0.lock() {
+            timings.runs = None;
+        }
+    }
+
+    /// 記録しているかどうか
+    pub fn is_recording(&self) -> bool {
+        self.0.lock().is_ok_and(|timings| timings.runs.is_some())
+    }
+
+    /// 記録を続けたまま、それまでに記録した実行を取り出す
+    pub fn take_recorded_runs(&self) -> Vec<SystemRun> {
+        self.0
+            .lock()
+            .ok()
+            .and_then(|mut timings| timings.runs.as_mut().map(std::mem::take))
+            .unwrap_or_default()
+    }
+
+    fn record(&self, name: &str, start: Instant, elapsed: Duration) {
+        let Ok(mut timings) = self.0.lock() else {
+            return;
+        };
+        if let Some(runs) = &mut timings.runs {
+            runs.push(SystemRun {
+                name: name.to_string(),
+                thread: thread::current().id(),
+                start,
+                duration: elapsed,
+            });
+        }
+        match timings.systems.get_mut(name) {
+            Some(timing) => {
+                timing.last = elapsed;
+                timing.average =
+                    timing.average.mul_f64(1.0 - AVERAGE_WEIGHT) + elapsed.mul_f64(AVERAGE_WEIGHT);
+            }
+            None => {
+                timings.systems.insert(
+                    name.to_string(),
+                    SystemTiming {
+                        last: elapsed,
+                        average: elapsed,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// `LogPlugin::custom_layer` に渡す、システムの処理時間を計る tracing のレイヤーを作る
+///
+/// Bevy は `trace` フィーチャーが有効なときだけシステムごとの span を作るので、
+/// `system_timings` フィーチャー (`bevy/trace` を有効にする) を付けてビルドしたときだけ計る。
+/// 計った値は [`SystemTimings`] リソースとしてアプリに追加する。
+pub fn system_timing_layer(app: &mut App) -> Option<BoxedLayer> {
+    if cfg!(not(feature = "system_timings")) {
+        return None;
+    }
+    let timings = SystemTimings::default();
+    app.insert_resource(timings.clone());
+    Some(Box::new(SystemTimingLayer { timings }))
+}
+
+/// システムの span に入ってから出るまでの時間を、システムの名前ごとに記録するレイヤー
+struct SystemTimingLayer {
+    timings: SystemTimings,
+}
+
+/// システムの span に付けておく、システムの名前
+struct SystemName(String);
+
+/// システムの span に入った時刻
+struct EnteredAt(Instant);
+
+impl<S> Layer<S> for SystemTimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != SYSTEM_SPAN {
+            return;
+        }
+        let mut visitor = NameVisitor(None);
+        attrs.record(&mut visitor);
+        if let (Some(name), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SystemName(name));
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if extensions.get_mut::<SystemName>().is_some() {
+            extensions.replace(EnteredAt(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        if let (Some(SystemName(name)), Some(EnteredAt(entered_at))) = (
+            extensions.get::<SystemName>(),
+            extensions.get::<EnteredAt>(),
+        ) {
+            self.timings.record(name, *entered_at, entered_at.elapsed());
+        }
+    }
+}
+
+/// span の `name` フィールドからシステムの名前を読み取る
+struct NameVisitor(Option<String>);
+
+impl Visit for NameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        // 文字列以外の型で記録されたときは、`Debug` の表現から引用符を外して使う
+        if field.name() == "name" && self.0.is_none() {
+            self.0 = Some(format!("{value:?}").trim_matches('"').to_string());
+        }
+    }
+}
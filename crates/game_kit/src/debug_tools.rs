@@ -0,0 +1,91 @@
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+// オーバーレイの表示・非表示を切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::F12;
+// オーバーレイのフォントサイズ
+const OVERLAY_FONT_SIZE: f32 = 14.0;
+// オーバーレイの文字色と背景色
+const OVERLAY_TEXT_COLOR: Color = Color::srgb(0.6, 1.0, 0.6);
+const OVERLAY_BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+/// 画面右上に FPS・フレーム時間・エンティティ数 (と固定ステップ数) を表示するプラグイン
+///
+/// `F12` キーで表示・非表示を切り替える。
+pub struct DebugToolsPlugin;
+
+impl Plugin for DebugToolsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.add_systems(Startup, spawn_overlay)
+            .add_systems(Update, (toggle_overlay, update_overlay));
+    }
+}
+
+/// デバッグ用のオーバーレイのテキストを示すコンポーネント
+#[derive(Component)]
+struct DebugOverlay;
+
+fn spawn_overlay(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: OVERLAY_FONT_SIZE,
+            ..default()
+        },
+        TextColor(OVERLAY_TEXT_COLOR),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            right: Val::Px(5.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(OVERLAY_BACKGROUND_COLOR),
+        // 他の UI より手前に表示する
+        GlobalZIndex(i32::MAX),
+        DebugOverlay,
+    ));
+}
+
+/// `F12` キーでオーバーレイの表示・非表示を切り替える
+fn toggle_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlay: Single<&mut Visibility, With<DebugOverlay>>,
+) {
+    if keyboard_input.just_pressed(TOGGLE_KEY) {
+        overlay.toggle_visible_hidden();
+    }
+}
+
+/// 計測値をオーバーレイに表示する
+fn update_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    entities: Query<()>,
+    #[cfg(feature = "frame_pacing")] tick_stats: Option<Res<frame_pacing::FixedTickStats>>,
+    mut overlay: Single<&mut Text, With<DebugOverlay>>,
+) {
+    let smoothed = |path| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or(0.0)
+    };
+    #[cfg_attr(not(feature = "frame_pacing"), allow(unused_mut))]
+    let mut text = format!(
+        "FPS {:.0}\nframe {:.2} ms\nentities {}",
+        smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+        smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+        entities.iter().count(),
+    );
+    // 固定ステップの計測は `FramePacingPlugin` が追加されているときだけ表示する
+    #[cfg(feature = "frame_pacing")]
+    if let Some(stats) = tick_stats {
+        text += &format!("\nfixed ticks {}", stats.ticks_this_frame);
+    }
+    overlay.0 = text;
+}
@@ -0,0 +1,188 @@
+//! リポジトリの共有クレートのプラグインを 1 つにまとめたプラグイングループ。
+//!
+//! サブシステム (オーディオ・固定ステップの制限・計算式・共有設定・デバッグツール) は
+//! cargo のフィーチャーでクレートごと含めるかどうかを選び、含めたものは
+//! [`GameKitPlugins`] のビルダーメソッドで実行時に追加するかどうかを選ぶ。
+//!
+//! ```ignore
+//! App::new()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins(GameKitPlugins::default().without_audio().with_debug_tools())
+//!     .run();
+//! ```
+
+use bevy::{app::PluginGroupBuilder, prelude::*};
+
+#[cfg(feature = "debug_tools")]
+mod debug_tools;
+
+#[cfg(feature = "audio")]
+pub use announcer;
+#[cfg(feature = "debug_tools")]
+pub use debug_tools::DebugToolsPlugin;
+#[cfg(feature = "scripting")]
+pub use expressions;
+#[cfg(feature = "frame_pacing")]
+pub use frame_pacing;
+#[cfg(feature = "settings")]
+pub use settings;
+
+/// 共有クレートのプラグインをまとめて追加するプラグイングループ
+///
+/// [`Default`] ではフィーチャーで含めたサブシステムをすべて追加し、デバッグツールだけは追加しない。
+/// [`GameKitPlugins::minimal`] から必要なものだけを `with_*` で足すこともできる。
+/// 個々のプラグインの差し替えや順番の変更には、通常のプラグイングループと同じく
+/// `.set()` / `.disable::<T>()` も使える。
+#[derive(Debug, Clone, Copy)]
+pub struct GameKitPlugins {
+    #[cfg(feature = "audio")]
+    audio: bool,
+    #[cfg(feature = "frame_pacing")]
+    frame_pacing: bool,
+    #[cfg(feature = "scripting")]
+    scripting: bool,
+    #[cfg(feature = "settings")]
+    settings: bool,
+    #[cfg(feature = "debug_tools")]
+    debug_tools: bool,
+}
+
+// フィーチャーの組み合わせによっては derive で済むが、値の意味を明示するため手で書く
+#[allow(clippy::derivable_impls)]
+impl Default for GameKitPlugins {
+    fn default() -> Self {
+        GameKitPlugins {
+            #[cfg(feature = "audio")]
+            audio: true,
+            #[cfg(feature = "frame_pacing")]
+            frame_pacing: true,
+            #[cfg(feature = "scripting")]
+            scripting: true,
+            #[cfg(feature = "settings")]
+            settings: true,
+            // デバッグツールは画面に表示が出るため、明示的に有効にしたときだけ追加する
+            #[cfg(feature = "debug_tools")]
+            debug_tools: false,
+        }
+    }
+}
+
+impl GameKitPlugins {
+    /// どのサブシステムも追加しない構成
+    pub fn minimal() -> GameKitPlugins {
+        GameKitPlugins {
+            #[cfg(feature = "audio")]
+            audio: false,
+            #[cfg(feature = "frame_pacing")]
+            frame_pacing: false,
+            #[cfg(feature = "scripting")]
+            scripting: false,
+            #[cfg(feature = "settings")]
+            settings: false,
+            #[cfg(feature = "debug_tools")]
+            debug_tools: false,
+        }
+    }
+
+    /// アナウンスと字幕 (`AnnouncerPlugin`) を追加する
+    #[cfg(feature = "audio")]
+    pub fn with_audio(mut self) -> Self {
+        self.audio = true;
+        self
+    }
+
+    /// アナウンスと字幕 (`AnnouncerPlugin`) を追加しない
+    #[cfg(feature = "audio")]
+    pub fn without_audio(mut self) -> Self {
+        self.audio = false;
+        self
+    }
+
+    /// 固定ステップ数の制限 (`FramePacingPlugin`) を追加する
+    #[cfg(feature = "frame_pacing")]
+    pub fn with_frame_pacing(mut self) -> Self {
+        self.frame_pacing = true;
+        self
+    }
+
+    /// 固定ステップ数の制限 (`FramePacingPlugin`) を追加しない
+    #[cfg(feature = "frame_pacing")]
+    pub fn without_frame_pacing(mut self) -> Self {
+        self.frame_pacing = false;
+        self
+    }
+
+    /// 計算式のアセット (`ExpressionsPlugin`) を追加する
+    #[cfg(feature = "scripting")]
+    pub fn with_scripting(mut self) -> Self {
+        self.scripting = true;
+        self
+    }
+
+    /// 計算式のアセット (`ExpressionsPlugin`) を追加しない
+    #[cfg(feature = "scripting")]
+    pub fn without_scripting(mut self) -> Self {
+        self.scripting = false;
+        self
+    }
+
+    /// 共有設定とプロファイル (`SettingsPlugin`) を追加する
+    #[cfg(feature = "settings")]
+    pub fn with_settings(mut self) -> Self {
+        self.settings = true;
+        self
+    }
+
+    /// 共有設定とプロファイル (`SettingsPlugin`) を追加しない
+    #[cfg(feature = "settings")]
+    pub fn without_settings(mut self) -> Self {
+        self.settings = false;
+        self
+    }
+
+    /// デバッグ用のオーバーレイ ([`DebugToolsPlugin`]) を追加する
+    #[cfg(feature = "debug_tools")]
+    pub fn with_debug_tools(mut self) -> Self {
+        self.debug_tools = true;
+        self
+    }
+
+    /// デバッグ用のオーバーレイ ([`DebugToolsPlugin`]) を追加しない
+    #[cfg(feature = "debug_tools")]
+    pub fn without_debug_tools(mut self) -> Self {
+        self.debug_tools = false;
+        self
+    }
+}
+
+impl PluginGroup for GameKitPlugins {
+    // フィーチャーをすべて外した場合は `self` を使わない
+    #[allow(unused_mut, unused_variables)]
+    fn build(self) -> PluginGroupBuilder {
+        let mut group = PluginGroupBuilder::start::<Self>();
+
+        // 設定は他のサブシステムが読むことがあるため最初に追加する
+        #[cfg(feature = "settings")]
+        if self.settings {
+            group = group.add(settings::SettingsPlugin);
+        }
+        #[cfg(feature = "frame_pacing")]
+        if self.frame_pacing {
+            group = group.add(frame_pacing::FramePacingPlugin);
+        }
+        #[cfg(feature = "scripting")]
+        if self.scripting {
+            group = group.add(expressions::ExpressionsPlugin);
+        }
+        #[cfg(feature = "audio")]
+        if self.audio {
+            group = group.add(announcer::AnnouncerPlugin);
+        }
+        #[cfg(feature = "debug_tools")]
+        if self.debug_tools {
+            group = group.add(DebugToolsPlugin);
+        }
+
+        group
+    }
+}
@@ -0,0 +1,263 @@
+use bevy::prelude::*;
+use game_kit::GameKitPlugins;
+
+// 構成の一覧のフォントサイズ
+const FONT_SIZE: f32 = 20.0;
+// 追加された・追加されていない・コンパイルされていないサブシステムの色
+const ADDED_COLOR: Color = Color::srgb(0.5, 1.0, 0.5);
+const SKIPPED_COLOR: Color = Color::srgb(1.0, 0.8, 0.4);
+const NOT_COMPILED_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+// 操作キーと、そのキーで試すサブシステム
+const DEMO_KEYS: [(KeyCode, &str); 3] = [
+    (KeyCode::Space, "audio"),
+    (KeyCode::KeyE, "scripting"),
+    (KeyCode::KeyS, "settings"),
+];
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let has_arg = |name: &str| args.iter().any(|arg| arg == name);
+
+    // `--minimal` ならサブシステムを何も追加せず、それ以外はすべて追加する
+    let kit = if has_arg("--minimal") {
+        GameKitPlugins::minimal()
+    } else {
+        full_kit()
+    };
+    #[cfg(feature = "audio")]
+    let kit = if has_arg("--no-audio") {
+        kit.without_audio()
+    } else {
+        kit
+    };
+
+    let mut app = App::new();
+    app.add_plugins((DefaultPlugins, kit));
+    // どのプラグインが追加されたかは、ビルドが終わった `App` に問い合わせて確かめる
+    let composition = Composition::inspect(&app);
+    for (name, status) in &composition.0 {
+        info!("{name}: {status:?}");
+    }
+    app.insert_resource(composition)
+        .add_systems(Startup, setup)
+        .add_systems(Update, report_missing);
+    // 各サブシステムのデモは、そのプラグインが登録するリソースがあるときだけ実行する
+    #[cfg(feature = "audio")]
+    app.add_systems(
+        Update,
+        announce.run_if(resource_exists::<Events<game_kit::announcer::Announce>>),
+    );
+    #[cfg(feature = "scripting")]
+    app.add_systems(
+        Update,
+        evaluate_formula.run_if(resource_exists::<Assets<game_kit::expressions::Formula>>),
+    );
+    #[cfg(feature = "settings")]
+    app.add_systems(
+        Update,
+        show_settings.run_if(resource_exists::<game_kit::settings::Settings>),
+    );
+    app.run();
+}
+
+/// すべてのサブシステムを追加する構成
+fn full_kit() -> GameKitPlugins {
+    let kit = GameKitPlugins::default();
+    #[cfg(feature = "debug_tools")]
+    let kit = kit.with_debug_tools();
+    kit
+}
+
+/// サブシステムの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Added,       // プラグインが追加されている
+    Skipped,     // コンパイルされているが、ビルダーで外された
+    NotCompiled, // フィーチャーが無効でコンパイルされていない
+}
+
+impl Status {
+    #[allow(dead_code)] // フィーチャーをすべて外すと使われない
+    fn of<T: Plugin>(app: &App) -> Status {
+        if app.is_plugin_added::<T>() {
+            Status::Added
+        } else {
+            Status::Skipped
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Status::Added => ADDED_COLOR,
+            Status::Skipped => SKIPPED_COLOR,
+            Status::NotCompiled => NOT_COMPILED_COLOR,
+        }
+    }
+}
+
+/// サブシステムごとの状態の一覧
+#[derive(Resource)]
+struct Composition(Vec<(&'static str, Status)>);
+
+impl Composition {
+    #[allow(unused_variables)] // フィーチャーをすべて外すと `app` を使わない
+    fn inspect(app: &App) -> Composition {
+        let settings = Status::NotCompiled;
+        #[cfg(feature = "settings")]
+        let settings = Status::of::<game_kit::settings::SettingsPlugin>(app);
+
+        let frame_pacing = Status::NotCompiled;
+        #[cfg(feature = "frame_pacing")]
+        let frame_pacing = Status::of::<game_kit::frame_pacing::FramePacingPlugin>(app);
+
+        let scripting = Status::NotCompiled;
+        #[cfg(feature = "scripting")]
+        let scripting = Status::of::<game_kit::expressions::ExpressionsPlugin>(app);
+
+        let audio = Status::NotCompiled;
+        #[cfg(feature = "audio")]
+        let audio = Status::of::<game_kit::announcer::AnnouncerPlugin>(app);
+
+        let debug_tools = Status::NotCompiled;
+        #[cfg(feature = "debug_tools")]
+        let debug_tools = Status::of::<game_kit::DebugToolsPlugin>(app);
+
+        Composition(vec![
+            ("settings", settings),
+            ("frame_pacing", frame_pacing),
+            ("scripting", scripting),
+            ("audio", audio),
+            ("debug_tools", debug_tools),
+        ])
+    }
+
+    fn has_added(&self, name: &str) -> bool {
+        self.0
+            .iter()
+            .any(|(subsystem, status)| *subsystem == name && *status == Status::Added)
+    }
+}
+
+/// デモの操作結果を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct DemoText;
+
+/// 構成の一覧と操作方法を表示する
+fn setup(mut commands: Commands, composition: Res<Composition>) {
+    commands.spawn(Camera2d);
+    commands
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(16.0)),
+            row_gap: Val::Px(6.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("GameKitPlugins"),
+                TextFont {
+                    font_size: FONT_SIZE * 1.5,
+                    ..default()
+                },
+            ));
+            for (name, status) in &composition.0 {
+                parent.spawn((
+                    Text::new(format!("{name:<14}{status:?}")),
+                    TextFont {
+                        font_size: FONT_SIZE,
+                        ..default()
+                    },
+                    TextColor(status.color()),
+                ));
+            }
+            parent.spawn((
+                Text::new(
+                    "Space: announce (audio)  E: evaluate formula (scripting)  \
+                     S: show settings (settings)  F12: debug overlay (debug_tools)",
+                ),
+                TextFont {
+                    font_size: FONT_SIZE * 0.7,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: FONT_SIZE,
+                    ..default()
+                },
+                DemoText,
+            ));
+        });
+}
+
+/// 追加されていないサブシステムのキーが押されたら、そのことを表示する
+fn report_missing(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    composition: Res<Composition>,
+    mut text: Single<&mut Text, With<DemoText>>,
+) {
+    for (key, subsystem) in DEMO_KEYS {
+        if keyboard_input.just_pressed(key) && !composition.has_added(subsystem) {
+            text.0 = format!("{subsystem} is not added to this app");
+        }
+    }
+}
+
+/// `Space` キーで字幕だけのアナウンスを流す
+#[cfg(feature = "audio")]
+fn announce(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut announce: EventWriter<game_kit::announcer::Announce>,
+    mut text: Single<&mut Text, With<DemoText>>,
+) {
+    use game_kit::announcer::{Announce, VoiceLine};
+
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        announce.send(Announce(VoiceLine {
+            id: "hello",
+            subtitle: "Hello from the game kit!".to_string(),
+            sound: None,
+            priority: 0,
+            duration: 2.0,
+            cooldown: 0.5,
+        }));
+        text.0 = "announced (see the subtitle)".to_string();
+    }
+}
+
+/// `E` キーで計算式を文字列から作って評価する
+#[cfg(feature = "scripting")]
+fn evaluate_formula(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut text: Single<&mut Text, With<DemoText>>,
+) {
+    use game_kit::expressions::{Formula, Variables};
+
+    if keyboard_input.just_pressed(KeyCode::KeyE) {
+        let source = "base * (1 + combo * 0.1)";
+        let variables = Variables::new().with("base", 10.0).with("combo", 5.0);
+        text.0 = match Formula::parse(source).map(|formula| formula.eval(&variables)) {
+            Ok(Ok(value)) => format!("{source} = {value} (base=10, combo=5)"),
+            Ok(Err(error)) => error.to_string(),
+            Err(error) => error.to_string(),
+        };
+    }
+}
+
+/// `S` キーで共有設定とプロファイルを表示する
+#[cfg(feature = "settings")]
+fn show_settings(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<game_kit::settings::Settings>,
+    profile: Res<game_kit::settings::Profile>,
+    mut text: Single<&mut Text, With<DemoText>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyS) {
+        text.0 = format!(
+            "profile: {}, audio latency: {} ms",
+            profile.name(),
+            settings.audio_latency_ms
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! 多くのサンプルがそれぞれ宣言し直していた、小さな共通のコンポーネントとシステム。
+//!
+//! 速度 ([`Velocity`])・寿命 ([`Lifetime`] / [`DespawnTimer`])・メインのカメラの印 ([`MainCamera`]) を
+//! 1 か所にまとめ、新しいサンプルは `use prelude_kit::*;` で使い始められるようにする。
+//!
+//! ```ignore
+//! App::new()
+//!     .add_plugins((DefaultPlugins, PreludeKitPlugin))
+//!     .add_systems(Update, apply_velocity)
+//!     .run();
+//! ```
+
+use bevy::prelude::*;
+
+/// 寿命の来たエンティティを消すシステムと、共通のコンポーネントの型を登録するプラグイン
+///
+/// [`apply_velocity`] はサンプルによって実行したいスケジュール (`Update` か `FixedUpdate` か) が
+/// 違うので、このプラグインでは追加しない。
+pub struct PreludeKitPlugin;
+
+impl Plugin for PreludeKitPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Velocity>()
+            .register_type::<MainCamera>()
+            .add_systems(
+                Update,
+                (cleanup_expired, cleanup_despawn_timers).in_set(CleanupSystems),
+            );
+    }
+}
+
+/// 寿命の来たエンティティを消すシステムのセット
+///
+/// 寿命の残りを使って見た目を変えるシステムは `.before(CleanupSystems)` にしておくと、
+/// 消える直前のフレームでも同じ値を読める。
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CleanupSystems;
+
+/// 1 秒あたりの移動量 (ワールド座標)
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct Velocity(pub Vec2);
+
+/// ゲームの時間 (`Time`) で数える寿命。切れたら [`cleanup_expired`] がエンティティを消す
+///
+/// 仮想時間を止めている間 (一時停止中) は減らない。破片やパーティクルなど、ゲームの中のものに使う。
+#[derive(Component, Debug, Clone, Deref, DerefMut)]
+pub struct Lifetime(pub Timer);
+
+impl Lifetime {
+    pub fn from_seconds(seconds: f32) -> Lifetime {
+        Lifetime(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+/// 実時間 (`Time<Real>`) で数える寿命。切れたら [`cleanup_despawn_timers`] がエンティティを消す
+///
+/// 一時停止中も減るので、メッセージやトーストなど、ゲームの外の表示に使う。
+#[derive(Component, Debug, Clone, Deref, DerefMut)]
+pub struct DespawnTimer(pub Timer);
+
+impl DespawnTimer {
+    pub fn from_seconds(seconds: f32) -> DespawnTimer {
+        DespawnTimer(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+/// ワールドを映すメインのカメラを示すコンポーネント (UI 専用のカメラや黒帯を塗るカメラには付けない)
+#[derive(Component, Reflect, Debug, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct MainCamera;
+
+/// [`Velocity`] を `Transform` の位置に反映する
+pub fn apply_velocity(time: Res<Time>, mut query: Query<(&Velocity, &mut Transform)>) {
+    for (velocity, mut transform) in &mut query {
+        transform.translation += (velocity.0 * time.delta_secs()).extend(0.0);
+    }
+}
+
+/// [`Lifetime`] を進め、切れたエンティティを子ごと消す
+pub fn cleanup_expired(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Lifetime)>,
+) {
+    for (entity, mut lifetime) in &mut query {
+        if lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// [`DespawnTimer`] を実時間で進め、切れたエンティティを子ごと消す
+pub fn cleanup_despawn_timers(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut query: Query<(Entity, &mut DespawnTimer)>,
+) {
+    for (entity, mut timer) in &mut query {
+        if timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! 複数のサンプルで共有するユーザー設定。
+//!
+//! 設定はユーザーの設定ディレクトリ (`~/.config/bevy_for_study/settings.ron` など) に
+//! RON 形式で保存されるため、あるサンプルで調整した値を別のサンプルから読み取れる。
+//! セーブデータなどプレイヤーごとのデータは [`Profile`] のディレクトリに保存する。
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+mod profile;
+
+pub use profile::Profile;
+
+/// 設定ファイルを置くディレクトリ名
+const APP_DIR: &str = "bevy_for_study";
+/// 設定ファイル名
+const SETTINGS_FILE: &str = "settings.ron";
+
+/// 共有設定を読み込んで [`Settings`] リソースとして登録するプラグイン
+///
+/// リソースが変更されると自動的にファイルへ書き戻す。
+/// [`Profile`] リソースが未登録ならデフォルトのプロファイルも登録する。
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load())
+            .init_resource::<Profile>()
+            .add_systems(Last, save_on_change);
+    }
+}
+
+/// サンプル間で共有するユーザー設定
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Settings {
+    /// オーディオ出力の遅延補正 (ミリ秒)
+    ///
+    /// 正の値は「音が映像より遅れて聞こえる」ことを表す。
+    /// リズム系のサンプルは判定や演出をこの値だけ遅らせる。
+    pub audio_latency_ms: f32,
+    /// 重い描画効果 (ブルームなど) を省くかどうか
+    ///
+    /// 性能の低いマシンで有効にする。有効なときは各サンプルが HDR やポストプロセスを使わない。
+    pub reduced_effects: bool,
+    /// 操作キーの割り当て (`サンプル名.操作名` → `KeyCode` のバリアント名)
+    ///
+    /// 例: `"breakout.paddle_left": "ArrowLeft"`。各サンプルは自分の名前で始まる項目だけを読み書きし、
+    /// 書かれていない操作はサンプルの既定のキーを使う。
+    pub key_bindings: BTreeMap<String, String>,
+}
+
+impl Settings {
+    /// オーディオ遅延補正を秒単位で返す
+    pub fn audio_latency_secs(&self) -> f64 {
+        f64::from(self.audio_latency_ms) / 1000.0
+    }
+
+    /// 設定ファイルを読み込む。存在しない・壊れている場合はデフォルト値を返す
+    pub fn load() -> Settings {
+        settings_path()
+            .map(|path| read_ron(&path))
+            .unwrap_or_default()
+    }
+
+    /// 設定ファイルへ書き込む。失敗しても警告を出すだけでゲームは止めない
+    pub fn save(&self) {
+        if let Some(path) = settings_path() {
+            write_ron(&path, self);
+        }
+    }
+}
+
+/// 設定ファイルのパスを返す (設定ディレクトリが取得できない環境では `None`)
+pub fn settings_path() -> Option<PathBuf> {
+    app_dir().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+/// このリポジトリのサンプルが共有するディレクトリを返す
+fn app_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR))
+}
+
+/// RON ファイルを読み込む。存在しない・壊れている場合はデフォルト値を返す
+fn read_ron<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let Ok(text) = fs::read_to_string(path) else {
+        return T::default();
+    };
+    match ron::from_str(&text) {
+        Ok(value) => value,
+        Err(err) => {
+            warn!("failed to parse {}: {err}", path.display());
+            T::default()
+        }
+    }
+}
+
+/// RON ファイルへ書き込む。失敗しても警告を出すだけでゲームは止めない
+fn write_ron<T: Serialize>(path: &Path, value: &T) {
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())
+        .and_then(|text| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+            }
+            fs::write(path, text).map_err(|err| err.to_string())
+        });
+    if let Err(err) = result {
+        warn!("failed to save {}: {err}", path.display());
+    }
+}
+
+/// [`Settings`] が変更されたフレームの最後にファイルへ保存するシステム
+fn save_on_change(settings: Res<Settings>) {
+    if settings.is_changed() && !settings.is_added() {
+        settings.save();
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{app_dir, read_ron, write_ron};
+
+/// プロファイルのディレクトリをまとめるディレクトリ名
+const PROFILES_DIR: &str = "profiles";
+/// デフォルトのプロファイル名
+const DEFAULT_PROFILE: &str = "default";
+
+/// 現在のプレイヤーのプロファイル
+///
+/// セーブデータ・ハイスコアなどプレイヤーごとのデータは
+/// `bevy_for_study/profiles/<name>/` 以下にファイル単位で保存する。
+/// どのファイルを読み書きするかは各サンプルが決める。
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    name: String,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile::new(DEFAULT_PROFILE)
+    }
+}
+
+impl Profile {
+    /// 指定した名前のプロファイルを作る
+    ///
+    /// 名前はディレクトリ名に使うため、英数字・`-`・`_` 以外の文字は `_` に置き換える。
+    pub fn new(name: &str) -> Profile {
+        let name = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Profile { name }
+    }
+
+    /// プロファイル名
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// このプロファイルのディレクトリ (設定ディレクトリが取得できない環境では `None`)
+    pub fn dir(&self) -> Option<PathBuf> {
+        app_dir().map(|dir| dir.join(PROFILES_DIR).join(&self.name))
+    }
+
+    /// プロファイル内のファイルを読み込む。存在しない・壊れている場合はデフォルト値を返す
+    pub fn load<T: DeserializeOwned + Default>(&self, file: &str) -> T {
+        self.dir()
+            .map(|dir| read_ron(&dir.join(file)))
+            .unwrap_or_default()
+    }
+
+    /// プロファイル内のファイルへ書き込む。失敗しても警告を出すだけでゲームは止めない
+    pub fn save<T: Serialize>(&self, file: &str, value: &T) {
+        if let Some(dir) = self.dir() {
+            write_ron(&dir.join(file), value);
+        }
+    }
+}
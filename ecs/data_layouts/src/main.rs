@@ -0,0 +1,471 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, Instant},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// エンティティを配置する領域の大きさの半分
+const ARENA_HALF_SIZE: Vec2 = Vec2::new(600.0, 320.0);
+// 敵の数の初期値・最小値・最大値 (上下キーで 2 倍・半分にする)
+const INITIAL_ENEMY_COUNT: usize = 2_000;
+const MIN_ENEMY_COUNT: usize = 250;
+const MAX_ENEMY_COUNT: usize = 64_000;
+// 最も近い敵を探す側 (シーカー) の数
+const SEEKER_COUNT: usize = 200;
+// 敵とシーカーの移動速度の上限
+const MAX_SPEED: f32 = 80.0;
+// キャッシュを作り直す間隔 (秒)
+const CACHE_REFRESH_SECS: f32 = 0.25;
+// 空間ハッシュの 1 セルの大きさ
+const CELL_SIZE: f32 = 64.0;
+// 計測値の平滑化の係数 (指数移動平均)
+const SMOOTHING: f64 = 0.05;
+
+// 敵・シーカー・線の色
+const ENEMY_COLOR: Color = Color::srgb(0.8, 0.3, 0.3);
+const SEEKER_COLOR: Color = Color::srgb(0.3, 0.6, 0.9);
+const TARGET_LINE_COLOR: Color = Color::srgba(0.9, 0.9, 0.3, 0.6);
+const ENEMY_SIZE: Vec2 = Vec2::new(3.0, 3.0);
+const SEEKER_SIZE: Vec2 = Vec2::new(8.0, 8.0);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(Agents {
+            rng: StdRng::seed_from_u64(0),
+            enemy_count: INITIAL_ENEMY_COUNT,
+        })
+        .init_resource::<NearestCache>()
+        .init_resource::<SpatialHash>()
+        .init_resource::<Timings>()
+        .init_resource::<Mismatches>()
+        .init_resource::<ShownApproach>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                (change_enemy_count, cycle_shown_approach),
+                move_agents,
+                // 3 つの方法は公平に比べられるよう、同じフレームの同じ位置に対して順番に実行する
+                (
+                    nearest_naive,
+                    nearest_cached,
+                    (rebuild_spatial_hash, nearest_spatial).chain(),
+                )
+                    .chain(),
+                count_mismatches,
+                (draw_targets, update_status),
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// 敵を示すコンポーネント
+#[derive(Component)]
+struct Enemy;
+
+/// 最も近い敵を探す側を示すコンポーネント
+#[derive(Component)]
+struct Seeker;
+
+/// 移動速度
+#[derive(Component, Deref, DerefMut)]
+struct Velocity(Vec2);
+
+/// 方法 1: 毎フレーム全ての敵を調べた結果を、シーカーごとのコンポーネントに持たせる
+#[derive(Component, Default, Deref, DerefMut)]
+struct NearestEnemy(Option<Entity>);
+
+/// 方法 2: 一定間隔で作り直す、シーカーごとの最も近い敵の表 (リソース)
+///
+/// 作り直すまでの間は探索をまったく行わない代わりに、結果が古くなる。
+#[derive(Resource)]
+struct NearestCache {
+    targets: HashMap<Entity, Entity>,
+    timer: Timer,
+}
+
+impl Default for NearestCache {
+    fn default() -> Self {
+        NearestCache {
+            targets: HashMap::default(),
+            timer: Timer::from_seconds(CACHE_REFRESH_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+/// 方法 3: 敵を格子状のセルに振り分けた空間ハッシュ (リソース)
+///
+/// 探索は近いセルから順に調べ、それより外側に近い敵がありえなくなった時点で打ち切る。
+#[derive(Resource, Default)]
+struct SpatialHash {
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+    results: HashMap<Entity, Entity>, // シーカーごとの探索結果
+}
+
+impl SpatialHash {
+    /// 位置が含まれるセル
+    fn cell(position: Vec2) -> IVec2 {
+        (position / CELL_SIZE).floor().as_ivec2()
+    }
+
+    /// 最も近い敵を探す
+    fn nearest(&self, position: Vec2) -> Option<Entity> {
+        let center = SpatialHash::cell(position);
+        // 領域の外側のセルまで調べても見つからなければ諦める
+        let max_ring = (ARENA_HALF_SIZE.max_element() * 2.0 / CELL_SIZE).ceil() as i32 + 1;
+        let mut best: Option<(Entity, f32)> = None;
+
+        for ring in 0..=max_ring {
+            // 中心から `ring` 個離れたセル (正方形の外周) だけを調べる
+            for y in -ring..=ring {
+                for x in -ring..=ring {
+                    if x.abs() != ring && y.abs() != ring {
+                        continue;
+                    }
+                    let Some(enemies) = self.cells.get(&(center + IVec2::new(x, y))) else {
+                        continue;
+                    };
+                    for &(entity, enemy_position) in enemies {
+                        let distance = position.distance_squared(enemy_position);
+                        if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                            best = Some((entity, distance));
+                        }
+                    }
+                }
+            }
+            // 次の外周のセルは、少なくとも `ring * CELL_SIZE` 離れている
+            let reach = ring as f32 * CELL_SIZE;
+            if best.is_some_and(|(_, distance)| distance <= reach * reach) {
+                break;
+            }
+        }
+        best.map(|(entity, _)| entity)
+    }
+}
+
+/// 敵の生成に使う乱数と、現在の敵の数
+#[derive(Resource)]
+struct Agents {
+    rng: StdRng,
+    enemy_count: usize,
+}
+
+/// 各方法の処理時間 (ミリ秒の移動平均)
+#[derive(Resource, Default)]
+struct Timings {
+    naive_ms: f64,
+    cached_ms: f64,
+    rebuild_ms: f64,
+    spatial_ms: f64,
+}
+
+/// 方法 1 の結果と食い違ったシーカーの数
+#[derive(Resource, Default)]
+struct Mismatches {
+    cached: usize,
+    spatial: usize,
+}
+
+/// 線で結んで表示する結果の方法
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum ShownApproach {
+    #[default]
+    Naive,
+    Cached,
+    Spatial,
+}
+
+/// 状態を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct StatusText;
+
+fn setup(mut commands: Commands, mut agents: ResMut<Agents>) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+
+    for _ in 0..SEEKER_COUNT {
+        let (transform, velocity) = random_motion(&mut agents.rng);
+        commands.spawn((
+            Sprite::from_color(SEEKER_COLOR, SEEKER_SIZE),
+            transform.with_translation(transform.translation.with_z(1.0)),
+            velocity,
+            Seeker,
+            NearestEnemy::default(),
+        ));
+    }
+    let count = agents.enemy_count;
+    spawn_enemies(&mut commands, &mut agents.rng, count);
+}
+
+/// 領域内のランダムな位置と速度
+fn random_motion(rng: &mut StdRng) -> (Transform, Velocity) {
+    let position = Vec2::new(
+        rng.gen_range(-ARENA_HALF_SIZE.x..ARENA_HALF_SIZE.x),
+        rng.gen_range(-ARENA_HALF_SIZE.y..ARENA_HALF_SIZE.y),
+    );
+    let velocity = Vec2::new(
+        rng.gen_range(-MAX_SPEED..MAX_SPEED),
+        rng.gen_range(-MAX_SPEED..MAX_SPEED),
+    );
+    (
+        Transform::from_translation(position.extend(0.0)),
+        Velocity(velocity),
+    )
+}
+
+fn spawn_enemies(commands: &mut Commands, rng: &mut StdRng, count: usize) {
+    let enemies: Vec<_> = (0..count)
+        .map(|_| {
+            let (transform, velocity) = random_motion(rng);
+            (
+                Sprite::from_color(ENEMY_COLOR, ENEMY_SIZE),
+                transform,
+                velocity,
+                Enemy,
+            )
+        })
+        .collect();
+    commands.spawn_batch(enemies);
+}
+
+/// 上下キーで敵の数を 2 倍・半分にする
+fn change_enemy_count(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut agents: ResMut<Agents>,
+    enemies: Query<Entity, With<Enemy>>,
+) {
+    let current = agents.enemy_count;
+    let target = if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        (current * 2).min(MAX_ENEMY_COUNT)
+    } else if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        (current / 2).max(MIN_ENEMY_COUNT)
+    } else {
+        return;
+    };
+
+    if target > current {
+        spawn_enemies(&mut commands, &mut agents.rng, target - current);
+    } else {
+        for entity in enemies.iter().take(current - target) {
+            commands.entity(entity).despawn();
+        }
+    }
+    agents.enemy_count = target;
+}
+
+/// スペースキーで線を引く結果の方法を切り替える
+fn cycle_shown_approach(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut shown: ResMut<ShownApproach>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        *shown = match *shown {
+            ShownApproach::Naive => ShownApproach::Cached,
+            ShownApproach::Cached => ShownApproach::Spatial,
+            ShownApproach::Spatial => ShownApproach::Naive,
+        };
+    }
+}
+
+/// 敵とシーカーを移動させ、領域の端で跳ね返す
+fn move_agents(mut agents: Query<(&mut Transform, &mut Velocity)>, time: Res<Time>) {
+    agents
+        .par_iter_mut()
+        .for_each(|(mut transform, mut velocity)| {
+            let mut position = transform.translation.truncate() + **velocity * time.delta_secs();
+            if position.x.abs() > ARENA_HALF_SIZE.x {
+                velocity.x = -velocity.x;
+                position.x = position.x.clamp(-ARENA_HALF_SIZE.x, ARENA_HALF_SIZE.x);
+            }
+            if position.y.abs() > ARENA_HALF_SIZE.y {
+                velocity.y = -velocity.y;
+                position.y = position.y.clamp(-ARENA_HALF_SIZE.y, ARENA_HALF_SIZE.y);
+            }
+            transform.translation = position.extend(transform.translation.z);
+        });
+}
+
+/// 処理時間を計測し、移動平均に反映する
+fn measure<T>(average: &mut f64, work: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = work();
+    let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+    *average += (elapsed - *average) * SMOOTHING;
+    result
+}
+
+/// すべての敵を調べて最も近いものを返す
+fn scan_nearest<'a>(
+    position: Vec2,
+    enemies: impl IntoIterator<Item = (Entity, &'a Transform)>,
+) -> Option<Entity> {
+    enemies
+        .into_iter()
+        .map(|(entity, transform)| {
+            (
+                entity,
+                position.distance_squared(transform.translation.truncate()),
+            )
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+/// 方法 1: シーカーごとにすべての敵を調べる (シーカー数 × 敵の数)
+fn nearest_naive(
+    mut seekers: Query<(&Transform, &mut NearestEnemy), With<Seeker>>,
+    enemies: Query<(Entity, &Transform), With<Enemy>>,
+    mut timings: ResMut<Timings>,
+) {
+    measure(&mut timings.naive_ms, || {
+        for (transform, mut nearest) in &mut seekers {
+            **nearest = scan_nearest(transform.translation.truncate(), &enemies);
+        }
+    });
+}
+
+/// 方法 2: 一定間隔でだけ全探索し、結果をリソースに残しておく
+fn nearest_cached(
+    seekers: Query<(Entity, &Transform), With<Seeker>>,
+    enemies: Query<(Entity, &Transform), With<Enemy>>,
+    mut cache: ResMut<NearestCache>,
+    mut timings: ResMut<Timings>,
+    time: Res<Time>,
+) {
+    // 最初のフレームか、間隔が経過したときだけ作り直す
+    let refresh = cache.targets.is_empty() || cache.timer.tick(time.delta()).just_finished();
+    measure(&mut timings.cached_ms, || {
+        if !refresh {
+            return;
+        }
+        cache.targets.clear();
+        for (seeker, transform) in &seekers {
+            if let Some(enemy) = scan_nearest(transform.translation.truncate(), &enemies) {
+                cache.targets.insert(seeker, enemy);
+            }
+        }
+    });
+}
+
+/// 方法 3 の準備: 敵の現在の位置で空間ハッシュを作り直す
+fn rebuild_spatial_hash(
+    enemies: Query<(Entity, &Transform), With<Enemy>>,
+    mut hash: ResMut<SpatialHash>,
+    mut timings: ResMut<Timings>,
+) {
+    measure(&mut timings.rebuild_ms, || {
+        // セルの `Vec` は使い回し、毎フレームのメモリ確保を避ける
+        for cell in hash.cells.values_mut() {
+            cell.clear();
+        }
+        for (entity, transform) in &enemies {
+            let position = transform.translation.truncate();
+            hash.cells
+                .entry(SpatialHash::cell(position))
+                .or_default()
+                .push((entity, position));
+        }
+    });
+}
+
+/// 方法 3: 空間ハッシュで近くのセルだけを調べる
+fn nearest_spatial(
+    seekers: Query<(Entity, &Transform), With<Seeker>>,
+    mut hash: ResMut<SpatialHash>,
+    mut timings: ResMut<Timings>,
+) {
+    measure(&mut timings.spatial_ms, || {
+        let hash = &mut *hash;
+        hash.results.clear();
+        for (seeker, transform) in &seekers {
+            if let Some(enemy) = hash.nearest(transform.translation.truncate()) {
+                hash.results.insert(seeker, enemy);
+            }
+        }
+    });
+}
+
+/// 方法 2・3 の結果が、毎フレーム全探索した結果と食い違っている数を数える
+fn count_mismatches(
+    seekers: Query<(Entity, &NearestEnemy)>,
+    cache: Res<NearestCache>,
+    hash: Res<SpatialHash>,
+    mut mismatches: ResMut<Mismatches>,
+) {
+    *mismatches = Mismatches::default();
+    for (seeker, nearest) in &seekers {
+        if cache.targets.get(&seeker).copied() != **nearest {
+            mismatches.cached += 1;
+        }
+        if hash.results.get(&seeker).copied() != **nearest {
+            mismatches.spatial += 1;
+        }
+    }
+}
+
+/// 選択中の方法で見つけた敵とシーカーを線で結ぶ
+fn draw_targets(
+    mut gizmos: Gizmos,
+    seekers: Query<(Entity, &Transform, &NearestEnemy)>,
+    transforms: Query<&Transform, With<Enemy>>,
+    cache: Res<NearestCache>,
+    hash: Res<SpatialHash>,
+    shown: Res<ShownApproach>,
+) {
+    for (seeker, transform, nearest) in &seekers {
+        let target = match *shown {
+            ShownApproach::Naive => **nearest,
+            ShownApproach::Cached => cache.targets.get(&seeker).copied(),
+            ShownApproach::Spatial => hash.results.get(&seeker).copied(),
+        };
+        // キャッシュが指す敵は、すでに despawn されていることもある
+        let Some(target) = target.and_then(|target| transforms.get(target).ok()) else {
+            continue;
+        };
+        gizmos.line_2d(
+            transform.translation.truncate(),
+            target.translation.truncate(),
+            TARGET_LINE_COLOR,
+        );
+    }
+}
+
+/// 計測結果を表示する
+fn update_status(
+    mut status: Single<&mut Text, With<StatusText>>,
+    agents: Res<Agents>,
+    timings: Res<Timings>,
+    mismatches: Res<Mismatches>,
+    shown: Res<ShownApproach>,
+) {
+    status.0 = format!(
+        "{SEEKER_COUNT} seekers x {} enemies   (Up/Down: x2 / x0.5, Space: show {:?})\n\n\
+         naive scan (component):   {:>7.3} ms\n\
+         cached (resource):        {:>7.3} ms   stale: {}/{SEEKER_COUNT}\n\
+         spatial hash (resource):  {:>7.3} ms   (rebuild {:.3} ms)   wrong: {}/{SEEKER_COUNT}",
+        agents.enemy_count,
+        *shown,
+        timings.naive_ms,
+        timings.cached_ms,
+        mismatches.cached,
+        timings.spatial_ms + timings.rebuild_ms,
+        timings.rebuild_ms,
+        mismatches.spatial,
+    );
+}
@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, tasks::ComputeTaskPool, utils::Instant};
+
+// 処理の対象にするエンティティの数
+const ENTITY_COUNT: usize = 100_000;
+// 1 エンティティあたりの計算の繰り返し回数 (重さの調整用)
+const WORK_ITERATIONS: usize = 16;
+// 計測値の平滑化の係数 (指数移動平均)
+const SMOOTHING: f64 = 0.05;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .init_resource::<WorkloadTimings>()
+        .init_resource::<HeatSpan>()
+        .init_resource::<WobbleSpan>()
+        .init_resource::<SharedAccess>()
+        .init_resource::<SharedCounter>()
+        .add_systems(Startup, setup)
+        // 同じ `Transform` を書き換えるため、この 2 つは並列には実行できない
+        .add_systems(Update, (transform_single, transform_parallel).chain())
+        // 互いに異なるコンポーネント・リソースだけを書き換えるので、別スレッドで同時に実行できる
+        .add_systems(
+            Update,
+            (
+                update_heat.run_if(resource_equals(SharedAccess(false))),
+                update_wobble.run_if(resource_equals(SharedAccess(false))),
+                // 同じリソースを `ResMut` で受け取ると、Bevy は同時に実行しない
+                update_heat_shared.run_if(resource_equals(SharedAccess(true))),
+                update_wobble_shared.run_if(resource_equals(SharedAccess(true))),
+            ),
+        )
+        .add_systems(PostUpdate, (toggle_shared_access, update_status))
+        .run();
+}
+
+/// 回転するだけのエンティティ
+#[derive(Component)]
+struct Spinner {
+    angular_speed: f32,
+}
+
+/// `update_heat` だけが書き換えるコンポーネント
+#[derive(Component, Default)]
+struct Heat(f32);
+
+/// `update_wobble` だけが書き換えるコンポーネント
+#[derive(Component, Default)]
+struct Wobble(f32);
+
+/// 単一スレッド・並列それぞれの処理時間 (ミリ秒の移動平均)
+#[derive(Resource, Default)]
+struct WorkloadTimings {
+    single_ms: f64,
+    parallel_ms: f64,
+}
+
+/// システムが実行されていた時間帯
+#[derive(Default, Clone, Copy)]
+struct Span {
+    start: Option<Instant>,
+    end: Option<Instant>,
+}
+
+impl Span {
+    /// 2 つの時間帯が重なっていた長さ
+    fn overlap(self, other: Span) -> Duration {
+        let (Some(a_start), Some(a_end), Some(b_start), Some(b_end)) =
+            (self.start, self.end, other.start, other.end)
+        else {
+            return Duration::ZERO;
+        };
+        a_end
+            .min(b_end)
+            .saturating_duration_since(a_start.max(b_start))
+    }
+}
+
+// 時間帯の記録先をシステムごとに分けておかないと、記録のためだけに両者が競合してしまう
+/// `update_heat` の実行時間帯
+#[derive(Resource, Default, Deref, DerefMut)]
+struct HeatSpan(Span);
+
+/// `update_wobble` の実行時間帯
+#[derive(Resource, Default, Deref, DerefMut)]
+struct WobbleSpan(Span);
+
+/// 2 つのシステムに同じリソースを書き換えさせるかどうか
+#[derive(Resource, Default, PartialEq, Eq)]
+struct SharedAccess(bool);
+
+/// 2 つのシステムが共有するリソース (書き換えるだけで中身に意味はない)
+#[derive(Resource, Default)]
+struct SharedCounter(u64);
+
+/// 状態を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct StatusText;
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+
+    // 描画はせず、計算の対象になるデータだけを持つエンティティ
+    commands.spawn_batch((0..ENTITY_COUNT).map(|index| {
+        (
+            Transform::from_xyz(index as f32, 0.0, 0.0),
+            Spinner {
+                angular_speed: 0.5 + (index % 100) as f32 * 0.01,
+            },
+            Heat::default(),
+            Wobble::default(),
+        )
+    }));
+}
+
+/// 1 エンティティ分の (わざと重い) 計算
+fn spin(transform: &mut Transform, spinner: &Spinner, dt: f32) {
+    let mut angle = spinner.angular_speed * dt;
+    for _ in 0..WORK_ITERATIONS {
+        angle = (angle.sin() + angle.cos()) * 0.5;
+    }
+    transform.rotate_z(angle * dt);
+    transform.translation.y = transform.rotation.to_axis_angle().1.sin();
+}
+
+/// 処理時間を計測し、移動平均に反映する
+fn measure(average: &mut f64, work: impl FnOnce()) {
+    let start = Instant::now();
+    work();
+    let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+    *average += (elapsed - *average) * SMOOTHING;
+}
+
+/// すべてのエンティティを 1 つのスレッドで順番に処理する
+fn transform_single(
+    mut query: Query<(&mut Transform, &Spinner)>,
+    mut timings: ResMut<WorkloadTimings>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    measure(&mut timings.single_ms, || {
+        for (mut transform, spinner) in &mut query {
+            spin(&mut transform, spinner, dt);
+        }
+    });
+}
+
+/// 同じ処理を `par_iter_mut` で複数のスレッドに分けて処理する
+///
+/// 各エンティティの処理が互いに独立しているので、分割しても結果は変わらない。
+fn transform_parallel(
+    mut query: Query<(&mut Transform, &Spinner)>,
+    mut timings: ResMut<WorkloadTimings>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    measure(&mut timings.parallel_ms, || {
+        query.par_iter_mut().for_each(|(mut transform, spinner)| {
+            spin(&mut transform, spinner, dt);
+        });
+    });
+}
+
+/// `Heat` を更新する (`Spinner` は読み取るだけなので `update_wobble` と競合しない)
+fn update_heat(mut query: Query<(&mut Heat, &Spinner)>, mut span: ResMut<HeatSpan>) {
+    span.start = Some(Instant::now());
+    for (mut heat, spinner) in &mut query {
+        heat.0 = (heat.0 + spinner.angular_speed).sin().abs();
+    }
+    span.end = Some(Instant::now());
+}
+
+/// `Wobble` を更新する
+fn update_wobble(mut query: Query<(&mut Wobble, &Spinner)>, mut span: ResMut<WobbleSpan>) {
+    span.start = Some(Instant::now());
+    for (mut wobble, spinner) in &mut query {
+        wobble.0 = (wobble.0 + spinner.angular_speed).cos().abs();
+    }
+    span.end = Some(Instant::now());
+}
+
+/// `update_heat` と同じ処理に、共有リソースの書き換えを加えたもの
+fn update_heat_shared(
+    query: Query<(&mut Heat, &Spinner)>,
+    span: ResMut<HeatSpan>,
+    mut counter: ResMut<SharedCounter>,
+) {
+    counter.0 += 1;
+    update_heat(query, span);
+}
+
+/// `update_wobble` と同じ処理に、共有リソースの書き換えを加えたもの
+fn update_wobble_shared(
+    query: Query<(&mut Wobble, &Spinner)>,
+    span: ResMut<WobbleSpan>,
+    mut counter: ResMut<SharedCounter>,
+) {
+    counter.0 += 1;
+    update_wobble(query, span);
+}
+
+/// スペースキーで共有リソースを使うかどうかを切り替える
+fn toggle_shared_access(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut shared: ResMut<SharedAccess>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        shared.0 = !shared.0;
+    }
+}
+
+/// 計測結果を表示する
+fn update_status(
+    mut status: Single<&mut Text, With<StatusText>>,
+    timings: Res<WorkloadTimings>,
+    heat_span: Res<HeatSpan>,
+    wobble_span: Res<WobbleSpan>,
+    shared: Res<SharedAccess>,
+) {
+    let speedup = if timings.parallel_ms > 0.0 {
+        timings.single_ms / timings.parallel_ms
+    } else {
+        0.0
+    };
+    let overlap = heat_span.overlap(**wobble_span);
+    let concurrency = if overlap > Duration::ZERO {
+        "ran concurrently"
+    } else {
+        "ran one after the other"
+    };
+
+    status.0 = format!(
+        "{ENTITY_COUNT} entities, {} compute threads\n\n\
+         iter_mut:     {:>7.2} ms\n\
+         par_iter_mut: {:>7.2} ms  (x{speedup:.1})\n\n\
+         Space: toggle shared ResMut between update_heat / update_wobble\n\
+         Shared ResMut: {}\n\
+         Overlap: {:.2} ms -> {concurrency}",
+        ComputeTaskPool::get().thread_num(),
+        timings.single_ms,
+        timings.parallel_ms,
+        if shared.0 { "yes" } else { "no" },
+        overlap.as_secs_f64() * 1000.0,
+    );
+}
@@ -0,0 +1,290 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::{
+    color::palettes::css::{DEEP_SKY_BLUE, DIM_GRAY, LIME, ORANGE, TOMATO, VIOLET},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+mod portal;
+
+use portal::{Portal, Traveler, PORTAL_WIDTH};
+
+// 1 秒あたりのシミュレーションのステップ数
+const TIMESTEP_HZ: f64 = 120.0;
+// 部屋の大きさ (半分の幅と高さ)
+const ROOM_HALF_SIZE: Vec2 = Vec2::new(600.0, 320.0);
+// 重力加速度 (ピクセル/秒²)
+const GRAVITY: f32 = 900.0;
+// プレイヤーの半径・歩く速さ・地面と空中での加速度・ジャンプの初速
+const PLAYER_RADIUS: f32 = 18.0;
+const PLAYER_SPEED: f32 = 320.0;
+const GROUND_ACCELERATION: f32 = 2400.0;
+const AIR_ACCELERATION: f32 = 800.0;
+const JUMP_SPEED: f32 = 520.0;
+// クリックで落とすボールの半径と、壁で跳ね返るときに残す速さの割合
+const BALL_RADIUS: f32 = 10.0;
+const BALL_RESTITUTION: f32 = 0.8;
+// ボールの最大数 (超えたら古いものから消す)
+const MAX_BALLS: usize = 40;
+// 移動の速さの上限 (ポータルを何度も通り抜けて落ち続けても、どこまでも速くならないようにする)
+const MAX_SPEED: f32 = 1400.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(portal::PortalPlugin)
+        .insert_resource(ClearColor(Color::srgb(0.08, 0.08, 0.1)))
+        .insert_resource(Time::<Fixed>::from_hz(TIMESTEP_HZ))
+        .add_systems(Startup, (setup, spawn_portals, spawn_player))
+        .add_systems(
+            FixedUpdate,
+            (
+                control_player,
+                apply_gravity,
+                portal::teleport,
+                bounce_off_walls,
+            )
+                .chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                drop_ball,
+                (clear_balls, reset_player).run_if(input_just_pressed(KeyCode::KeyR)),
+                draw_room,
+                update_info,
+            ),
+        )
+        .run();
+}
+
+/// キーボードで動かすプレイヤー
+#[derive(Component)]
+struct Player;
+
+/// クリックで落とすボール (生成した順番)
+#[derive(Component)]
+struct Ball(u32);
+
+/// 壁で跳ね返るときに残す速さの割合 (0 なら跳ね返らずに止まる)
+#[derive(Component)]
+struct Restitution(f32);
+
+/// 操作方法と、プレイヤーの速さを表示するテキスト
+#[derive(Component)]
+struct InfoText;
+
+/// 球のメッシュとマテリアル (ボールを落とすたびに作らないように使い回す)
+#[derive(Resource)]
+struct BallAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+/// カメラ・説明のテキストを配置し、ボールの見た目を用意する
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+    commands.insert_resource(BallAssets {
+        mesh: meshes.add(Circle::new(BALL_RADIUS)),
+        material: materials.add(Color::from(LIME)),
+    });
+}
+
+/// 2 組のポータルを配置する
+///
+/// 1 組目は床と右の壁、2 組目は天井と部屋の中に浮かせた斜めのポータル。
+/// ポータルの表側の法線はローカルの +Y なので、床は回転なし、右の壁は左向き (90°)、天井は下向き (180°)。
+fn spawn_portals(mut commands: Commands) {
+    let (w, h) = (ROOM_HALF_SIZE.x, ROOM_HALF_SIZE.y);
+    portal::spawn_pair(
+        &mut commands,
+        Isometry2d::new(Vec2::new(-w + PORTAL_WIDTH * 2.0, -h), Rot2::IDENTITY),
+        Isometry2d::new(Vec2::new(w, h - PORTAL_WIDTH), Rot2::radians(FRAC_PI_2)),
+        [DEEP_SKY_BLUE.into(), ORANGE.into()],
+    );
+    portal::spawn_pair(
+        &mut commands,
+        Isometry2d::new(Vec2::new(PORTAL_WIDTH, h), Rot2::radians(PI)),
+        Isometry2d::new(Vec2::new(-w / 2.0, 0.0), Rot2::degrees(-30.0)),
+        [VIOLET.into(), TOMATO.into()],
+    );
+}
+
+/// プレイヤーを部屋の左下に配置する
+fn spawn_player(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(PLAYER_RADIUS))),
+        MeshMaterial2d(materials.add(Color::WHITE)),
+        Transform::from_translation(player_start().extend(1.0)),
+        Traveler::new(Vec2::ZERO, PLAYER_RADIUS),
+        Restitution(0.0),
+        Player,
+    ));
+}
+
+fn player_start() -> Vec2 {
+    Vec2::new(
+        -ROOM_HALF_SIZE.x + PLAYER_RADIUS * 2.0,
+        -ROOM_HALF_SIZE.y + PLAYER_RADIUS,
+    )
+}
+
+/// 床に立っているかどうか
+fn on_floor(position: Vec2, radius: f32) -> bool {
+    position.y <= -ROOM_HALF_SIZE.y + radius + 0.5
+}
+
+/// `A` / `D` (矢印キー) で左右に動かし、床に立っているときに `W` / `Space` でジャンプする
+///
+/// 空中では、ポータルで得た勢いを入力で打ち消さないように、歩く速さより遅いときだけ加速する。
+fn control_player(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    player: Single<(&Transform, &mut Traveler), With<Player>>,
+) {
+    let (transform, mut traveler) = player.into_inner();
+    let mut direction = 0.0;
+    if keyboard_input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        direction -= 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        direction += 1.0;
+    }
+
+    let grounded = on_floor(transform.translation.truncate(), traveler.radius);
+    let acceleration = if grounded {
+        GROUND_ACCELERATION
+    } else {
+        AIR_ACCELERATION
+    } * time.delta_secs();
+    if grounded || (direction != 0.0 && traveler.velocity.x * direction < PLAYER_SPEED) {
+        let target = direction * PLAYER_SPEED;
+        traveler.velocity.x += (target - traveler.velocity.x).clamp(-acceleration, acceleration);
+    }
+    if grounded && keyboard_input.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp, KeyCode::Space]) {
+        traveler.velocity.y = JUMP_SPEED;
+    }
+}
+
+/// 重力で速度を更新し、位置を進める
+fn apply_gravity(time: Res<Time>, mut travelers: Query<(&mut Transform, &mut Traveler)>) {
+    let dt = time.delta_secs();
+    for (mut transform, mut traveler) in &mut travelers {
+        traveler.velocity.y -= GRAVITY * dt;
+        traveler.velocity = traveler.velocity.clamp_length_max(MAX_SPEED);
+        transform.translation += (traveler.velocity * dt).extend(0.0);
+    }
+}
+
+/// 部屋の壁・床・天井で跳ね返す (部屋の外に出ないように位置も戻す)
+fn bounce_off_walls(mut travelers: Query<(&mut Transform, &mut Traveler, &Restitution)>) {
+    for (mut transform, mut traveler, restitution) in &mut travelers {
+        let limit = ROOM_HALF_SIZE - traveler.radius;
+        for axis in 0..2 {
+            let position = transform.translation[axis];
+            if position.abs() <= limit[axis] {
+                continue;
+            }
+            transform.translation[axis] = position.clamp(-limit[axis], limit[axis]);
+            // 壁に向かって進んでいるときだけ速度を反転させる
+            if traveler.velocity[axis] * position > 0.0 {
+                traveler.velocity[axis] *= -restitution.0;
+            }
+        }
+    }
+}
+
+/// 左クリックした位置にボールを落とす (最大数を超えたら古いものから消す)
+fn drop_ball(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    assets: Res<BallAssets>,
+    balls: Query<(Entity, &Ball)>,
+    mut next_id: Local<u32>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let (camera, camera_transform) = *camera;
+    let Ok(position) = camera.viewport_to_world_2d(camera_transform, cursor) else {
+        return;
+    };
+
+    if balls.iter().len() >= MAX_BALLS {
+        if let Some((oldest, _)) = balls.iter().min_by_key(|(_, ball)| ball.0) {
+            commands.entity(oldest).despawn();
+        }
+    }
+    commands.spawn((
+        Mesh2d(assets.mesh.clone()),
+        MeshMaterial2d(assets.material.clone()),
+        Transform::from_translation(position.extend(0.5)),
+        Traveler::new(Vec2::ZERO, BALL_RADIUS),
+        Restitution(BALL_RESTITUTION),
+        Ball(*next_id),
+    ));
+    *next_id += 1;
+}
+
+/// `R` キーでボールをすべて消す
+fn clear_balls(mut commands: Commands, balls: Query<Entity, With<Ball>>) {
+    for entity in &balls {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// `R` キーでプレイヤーを最初の位置に戻す
+fn reset_player(player: Single<(&mut Transform, &mut Traveler), With<Player>>) {
+    let (mut transform, mut traveler) = player.into_inner();
+    transform.translation = player_start().extend(transform.translation.z);
+    traveler.velocity = Vec2::ZERO;
+}
+
+/// 部屋の枠を描く
+fn draw_room(mut gizmos: Gizmos) {
+    gizmos.rect_2d(Isometry2d::IDENTITY, ROOM_HALF_SIZE * 2.0, DIM_GRAY);
+}
+
+/// 操作方法と、プレイヤーの速さ・ボールとポータルの数を表示する
+fn update_info(
+    player: Single<&Traveler, With<Player>>,
+    balls: Query<(), With<Ball>>,
+    portals: Query<(), With<Portal>>,
+    mut text: Single<&mut Text, With<InfoText>>,
+) {
+    text.0 = format!(
+        "A/D: move   W/Space: jump   Left click: drop a ball   R: reset\n\
+         Speed: {:.0} px/s   Balls: {}   Portals: {} pairs",
+        player.velocity.length(),
+        balls.iter().len(),
+        portals.iter().len() / 2,
+    );
+}
@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+
+// ポータルの口の幅と、描くときの厚み (半分の大きさ)
+pub const PORTAL_WIDTH: f32 = 120.0;
+const PORTAL_HALF_THICKNESS: f32 = 8.0;
+// 出口のポータルの面から、移動するものの半径に加えてどれだけ離して出すか
+const EXIT_MARGIN: f32 = 2.0;
+// ワープしたあと、もう一度ワープできるようになるまでの時間 (秒)
+const COOLDOWN_SECONDS: f32 = 0.25;
+// 入口と出口をつなぐ線に流す点の数と、点が流れる速さ (1 秒に進む、線の長さに対する割合)
+const LINK_DOTS: usize = 16;
+const LINK_SPEED: f32 = 0.35;
+// ワープしたときに広がる輪の時間と最大の半径
+const FLASH_SECONDS: f32 = 0.4;
+const FLASH_RADIUS: f32 = 70.0;
+
+/// 2 つ 1 組のポータルで、触れたものを相方のポータルへ移すプラグイン
+///
+/// ポータルの表側 (ローカルの +Y が法線) から口に触れて進んでいる [`Traveler`] を、
+/// 相方のポータルの表側へ移す。速度は入口のポータルの向きで見た値を、出口のポータルの向きに
+/// 移し替えるので、床のポータルに落ちると壁のポータルから横向きに飛び出す。
+/// 移ったあとは `COOLDOWN_SECONDS` の間ワープしないので、出口ですぐに入り直さない。
+/// [`teleport`] は移動と壁の跳ね返りの間に実行するように、使う側で順番を決める。
+pub struct PortalPlugin;
+
+impl Plugin for PortalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (draw_portals, draw_flashes, fade_flashes));
+    }
+}
+
+/// ポータル (`Transform` の位置が口の中心、回転したローカルの +Y が表側の法線)
+#[derive(Component)]
+pub struct Portal {
+    pub partner: Entity, // つながっている相方のポータル
+    pub color: Color,
+}
+
+/// ポータルを通り抜けられるもの
+#[derive(Component)]
+pub struct Traveler {
+    pub velocity: Vec2,
+    pub radius: f32,
+    cooldown: f32, // 次にワープできるようになるまでの残り時間 (秒)
+}
+
+impl Traveler {
+    pub fn new(velocity: Vec2, radius: f32) -> Traveler {
+        Traveler {
+            velocity,
+            radius,
+            cooldown: 0.0,
+        }
+    }
+}
+
+/// ワープしたときに入口と出口で広がる輪
+#[derive(Component)]
+struct Flash {
+    timer: Timer,
+    color: Color,
+}
+
+/// 2 つのポータルをつないで配置する (`colors` はそれぞれのポータルの色)
+pub fn spawn_pair(commands: &mut Commands, a: Isometry2d, b: Isometry2d, colors: [Color; 2]) {
+    let first = commands.spawn_empty().id();
+    let second = commands.spawn_empty().id();
+    commands.entity(first).insert((
+        portal_transform(a),
+        Portal {
+            partner: second,
+            color: colors[0],
+        },
+    ));
+    commands.entity(second).insert((
+        portal_transform(b),
+        Portal {
+            partner: first,
+            color: colors[1],
+        },
+    ));
+}
+
+fn portal_transform(isometry: Isometry2d) -> Transform {
+    Transform::from_translation(isometry.translation.extend(0.0))
+        .with_rotation(Quat::from_rotation_z(isometry.rotation.as_radians()))
+}
+
+/// ポータルの `Transform` から、口の中心と向き
+fn portal_frame(transform: &Transform) -> Isometry2d {
+    let (angle, _, _) = transform.rotation.to_euler(EulerRot::ZYX);
+    Isometry2d::new(transform.translation.truncate(), Rot2::radians(angle))
+}
+
+/// 入口のポータルの向きで見たベクトルを、出口のポータルの向きに移し替える
+///
+/// ローカルで 180° 回すので、入口の面に向かう向き (法線の逆向き) は出口の面から離れる向き
+/// (法線の向き) になり、口に沿った向きは左右が入れ替わる (鏡に映したようにはならない)。
+fn through_portal(entry: Isometry2d, exit: Isometry2d, vector: Vec2) -> Vec2 {
+    exit.rotation * -(entry.rotation.inverse() * vector)
+}
+
+/// 表側から口に触れて、口に向かって進んでいる [`Traveler`] を相方のポータルへ移す
+///
+/// 出口では、入口で口のどこに触れたかを左右反転した位置に、表側へ半径だけ離して出す。
+pub fn teleport(
+    mut commands: Commands,
+    time: Res<Time>,
+    portals: Query<(&Portal, &Transform)>,
+    mut travelers: Query<(&mut Transform, &mut Traveler), Without<Portal>>,
+) {
+    for (mut transform, mut traveler) in &mut travelers {
+        traveler.cooldown = (traveler.cooldown - time.delta_secs()).max(0.0);
+        if traveler.cooldown > 0.0 {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        for (portal, portal_transform) in &portals {
+            let entry = portal_frame(portal_transform);
+            let local = entry.inverse_transform_point(position);
+            let approaching = (entry.rotation.inverse() * traveler.velocity).y < 0.0;
+            if local.x.abs() > PORTAL_WIDTH / 2.0 || local.y.abs() > traveler.radius || !approaching
+            {
+                continue;
+            }
+            let Ok((exit_portal, exit_transform)) = portals.get(portal.partner) else {
+                continue;
+            };
+            let exit = portal_frame(exit_transform);
+            let exit_position =
+                exit.transform_point(Vec2::new(-local.x, traveler.radius + EXIT_MARGIN));
+            traveler.velocity = through_portal(entry, exit, traveler.velocity);
+            traveler.cooldown = COOLDOWN_SECONDS;
+            transform.translation = exit_position.extend(transform.translation.z);
+
+            for (at, color) in [(position, portal.color), (exit_position, exit_portal.color)] {
+                commands.spawn((
+                    Transform::from_translation(at.extend(0.0)),
+                    Flash {
+                        timer: Timer::from_seconds(FLASH_SECONDS, TimerMode::Once),
+                        color,
+                    },
+                ));
+            }
+            break;
+        }
+    }
+}
+
+/// ポータルの口と表側の向き、2 つのポータルをつなぐ流れる点線を描く
+///
+/// 点は入口から出口へ流れ、色は入口の色から出口の色へ変わる (1 組につき 1 回だけ描く)。
+fn draw_portals(
+    time: Res<Time>,
+    portals: Query<(Entity, &Portal, &Transform)>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, portal, transform) in &portals {
+        let frame = portal_frame(transform);
+        gizmos.ellipse_2d(
+            frame,
+            Vec2::new(PORTAL_WIDTH / 2.0, PORTAL_HALF_THICKNESS),
+            portal.color,
+        );
+        gizmos.arrow_2d(
+            frame.translation,
+            frame.transform_point(Vec2::Y * PORTAL_HALF_THICKNESS * 3.0),
+            portal.color.with_alpha(0.6),
+        );
+
+        if entity > portal.partner {
+            continue;
+        }
+        let Ok((_, partner, partner_transform)) = portals.get(portal.partner) else {
+            continue;
+        };
+        let end = partner_transform.translation.truncate();
+        for i in 0..LINK_DOTS {
+            let t = (i as f32 / LINK_DOTS as f32 + time.elapsed_secs() * LINK_SPEED).fract();
+            let color = portal.color.mix(&partner.color, t).with_alpha(0.35);
+            gizmos.circle_2d(frame.translation.lerp(end, t), 2.5, color);
+        }
+    }
+}
+
+/// ワープしたときの輪を、広がりながら薄くなるように描く
+fn draw_flashes(flashes: Query<(&Transform, &Flash)>, mut gizmos: Gizmos) {
+    for (transform, flash) in &flashes {
+        let t = flash.timer.fraction();
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            FLASH_RADIUS * t,
+            flash.color.with_alpha(1.0 - t),
+        );
+    }
+}
+
+/// 輪の時間を進め、消え終わったものを消す
+fn fade_flashes(mut commands: Commands, time: Res<Time>, mut flashes: Query<(Entity, &mut Flash)>) {
+    for (entity, mut flash) in &mut flashes {
+        if flash.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
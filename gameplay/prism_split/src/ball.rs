@@ -0,0 +1,204 @@
+use bevy::{
+    math::bounding::{Aabb2d, BoundingCircle, BoundingVolume, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{Brick, Paddle, Score, ARENA_HALF_SIZE, PADDLE_SIZE};
+
+// ボールの半径と速さ
+pub const BALL_RADIUS: f32 = 8.0;
+pub const BALL_SPEED: f32 = 420.0;
+// 同時に出せるボールの数の初期値
+const DEFAULT_MAX_BALLS: usize = 8;
+// パドルで打ち返したときに、当たった位置で左右に振れる最大の角度 (ラジアン)
+const MAX_BOUNCE_ANGLE: f32 = 1.0;
+
+/// 複数のボールを動かし、壁・パドル・ブロックで跳ね返すプラグイン
+///
+/// ボールは 1 つとは限らないので、どのシステムも `Query` ですべてのボールを順に処理する。
+/// ボールが 1 つも残っていないときは、パドルの上に新しいボールを載せ、`Space` キーで打ち出す。
+pub struct BallPlugin;
+
+impl Plugin for BallPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MaxBalls(DEFAULT_MAX_BALLS))
+            .add_systems(Startup, setup_ball_assets)
+            .add_systems(
+                FixedUpdate,
+                (move_balls, bounce_balls, remove_lost_balls, serve_ball).chain(),
+            )
+            .add_systems(Update, launch_held_ball);
+    }
+}
+
+/// ボール
+#[derive(Component)]
+pub struct Ball {
+    pub velocity: Vec2,
+}
+
+/// パドルに載っていて、まだ打ち出していないボール
+#[derive(Component)]
+struct Held;
+
+/// 同時に出せるボールの数の上限 (プリズムはこれを超えてボールを増やさない)
+#[derive(Resource, Deref, DerefMut)]
+pub struct MaxBalls(pub usize);
+
+/// ボールのメッシュとマテリアル (分かれるたびに作らないように使い回す)
+#[derive(Resource)]
+pub struct BallAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+fn setup_ball_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(BallAssets {
+        mesh: meshes.add(Circle::new(BALL_RADIUS)),
+        material: materials.add(Color::WHITE),
+    });
+}
+
+/// ボールのエンティティのコンポーネント一式
+pub fn ball_bundle(assets: &BallAssets, position: Vec2, velocity: Vec2) -> impl Bundle {
+    (
+        Mesh2d(assets.mesh.clone()),
+        MeshMaterial2d(assets.material.clone()),
+        Transform::from_translation(position.extend(1.0)),
+        Ball { velocity },
+    )
+}
+
+/// 速度に合わせてボールを進める (パドルに載っているボールはパドルに付いていく)
+fn move_balls(
+    time: Res<Time>,
+    paddle: Single<&Transform, (With<Paddle>, Without<Ball>)>,
+    mut balls: Query<(&mut Transform, &Ball, Has<Held>)>,
+) {
+    for (mut transform, ball, held) in &mut balls {
+        if held {
+            transform.translation.x = paddle.translation.x;
+            continue;
+        }
+        transform.translation += (ball.velocity * time.delta_secs()).extend(0.0);
+    }
+}
+
+/// 円と箱がぶつかっていたら、箱から見たボールの向き (押し戻す向きの法線) を返す
+fn collision_normal(circle: BoundingCircle, bounds: Aabb2d) -> Option<Vec2> {
+    if !circle.intersects(&bounds) {
+        return None;
+    }
+    let offset = circle.center() - bounds.closest_point(circle.center());
+    // 中心が箱の中に入っているときは、箱の中心からの向きで決める
+    let offset = if offset == Vec2::ZERO {
+        circle.center() - bounds.center()
+    } else {
+        offset
+    };
+    Some(if offset.x.abs() > offset.y.abs() {
+        Vec2::new(offset.x.signum(), 0.0)
+    } else {
+        Vec2::new(0.0, offset.y.signum())
+    })
+}
+
+/// 壁・パドル・ブロックでボールを跳ね返し、当たったブロックを壊す
+///
+/// パドルでは当たった位置が端に近いほど大きく左右に振る。同じステップで 2 つのボールが
+/// 同じブロックに当たっても、ブロックは 1 回だけ数える。
+#[allow(clippy::type_complexity)]
+fn bounce_balls(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    paddle: Single<&Transform, (With<Paddle>, Without<Ball>)>,
+    bricks: Query<(Entity, &Transform), (With<Brick>, Without<Ball>)>,
+    mut balls: Query<(&Transform, &mut Ball), Without<Held>>,
+    mut destroyed: Local<Vec<Entity>>,
+) {
+    destroyed.clear();
+    for (transform, mut ball) in &mut balls {
+        let position = transform.translation.truncate();
+
+        // 左右の壁と天井 (床はなく、落ちたボールは `remove_lost_balls` が消す)
+        let limit = ARENA_HALF_SIZE - Vec2::splat(BALL_RADIUS);
+        if position.x.abs() > limit.x && ball.velocity.x * position.x > 0.0 {
+            ball.velocity.x = -ball.velocity.x;
+        }
+        if position.y > limit.y && ball.velocity.y > 0.0 {
+            ball.velocity.y = -ball.velocity.y;
+        }
+
+        let circle = BoundingCircle::new(position, BALL_RADIUS);
+        let paddle_bounds = Aabb2d::new(paddle.translation.truncate(), PADDLE_SIZE / 2.0);
+        if collision_normal(circle, paddle_bounds).is_some() && ball.velocity.y < 0.0 {
+            let offset = (position.x - paddle.translation.x) / (PADDLE_SIZE.x / 2.0);
+            let angle = offset.clamp(-1.0, 1.0) * MAX_BOUNCE_ANGLE;
+            ball.velocity = Rot2::radians(-angle) * Vec2::Y * ball.velocity.length();
+            continue;
+        }
+
+        for (entity, brick_transform) in &bricks {
+            let bounds = Aabb2d::new(
+                brick_transform.translation.truncate(),
+                brick_transform.scale.truncate() / 2.0,
+            );
+            let Some(normal) = collision_normal(circle, bounds) else {
+                continue;
+            };
+            // 箱に向かって進んでいる向きの成分だけを反転させる
+            let velocity = ball.velocity;
+            if velocity.dot(normal) < 0.0 {
+                ball.velocity = velocity - 2.0 * velocity.dot(normal) * normal;
+            }
+            if !destroyed.contains(&entity) {
+                destroyed.push(entity);
+                commands.entity(entity).despawn();
+                **score += 1;
+            }
+            break;
+        }
+    }
+}
+
+/// 床より下に落ちたボールを消す
+fn remove_lost_balls(mut commands: Commands, balls: Query<(Entity, &Transform), With<Ball>>) {
+    for (entity, transform) in &balls {
+        if transform.translation.y < -ARENA_HALF_SIZE.y - BALL_RADIUS {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// ボールが 1 つも残っていなければ、パドルの上に新しいボールを載せる
+fn serve_ball(
+    mut commands: Commands,
+    assets: Res<BallAssets>,
+    paddle: Single<&Transform, With<Paddle>>,
+    balls: Query<(), With<Ball>>,
+) {
+    if !balls.is_empty() {
+        return;
+    }
+    let position = paddle.translation.truncate() + Vec2::Y * (PADDLE_SIZE.y / 2.0 + BALL_RADIUS);
+    commands.spawn((ball_bundle(&assets, position, Vec2::ZERO), Held));
+}
+
+/// `Space` キーでパドルに載っているボールを真上に打ち出す
+fn launch_held_ball(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut balls: Query<(Entity, &mut Ball), With<Held>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for (entity, mut ball) in &mut balls {
+        ball.velocity = Vec2::Y * BALL_SPEED;
+        commands.entity(entity).remove::<Held>();
+    }
+}
@@ -0,0 +1,213 @@
+use bevy::{
+    color::palettes::css::{DIM_GRAY, GOLD, TOMATO},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+mod ball;
+mod prism;
+
+use ball::{Ball, MaxBalls};
+use prism::{prism_bundle, Prism, PrismAssets};
+
+// 部屋の大きさ (半分の幅と高さ)
+const ARENA_HALF_SIZE: Vec2 = Vec2::new(450.0, 300.0);
+// パドルの大きさ・高さ・動く速さ
+const PADDLE_SIZE: Vec2 = Vec2::new(120.0, 16.0);
+const PADDLE_Y: f32 = -260.0;
+const PADDLE_SPEED: f32 = 600.0;
+// ブロックの大きさと、ブロックどうしの隙間
+const BRICK_SIZE: Vec2 = Vec2::new(70.0, 22.0);
+const BRICK_GAP: f32 = 6.0;
+// 一番上の行の中心の高さ
+const TOP_ROW_Y: f32 = 240.0;
+// ボールの数の上限を変えられる範囲
+const MAX_BALLS_RANGE: (usize, usize) = (1, 32);
+
+// レベル (`#` がブロック、`P` がプリズム、`.` が何もないマス)
+// 文字の意味はブレイクアウトのレベルファイルに合わせている
+const LEVEL: &[&str] = &[
+    "###########",
+    "###########",
+    "##.#####.##",
+    "...........",
+    "..P.....P..",
+    "...........",
+    ".....P.....",
+];
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins((ball::BallPlugin, prism::PrismPlugin))
+        .insert_resource(ClearColor(Color::srgb(0.08, 0.08, 0.1)))
+        .init_resource::<Score>()
+        .add_systems(Startup, setup)
+        // プリズムのメッシュは `PrismPlugin` が Startup で用意するので、その後に並べる
+        .add_systems(PostStartup, spawn_level)
+        .add_systems(FixedUpdate, move_paddle)
+        .add_systems(
+            Update,
+            (
+                change_max_balls,
+                (clear_level, spawn_level)
+                    .chain()
+                    .run_if(input_just_pressed(KeyCode::KeyR)),
+                draw_arena,
+                update_info,
+            ),
+        )
+        .run();
+}
+
+/// プレイヤーが動かすパドル
+#[derive(Component)]
+struct Paddle;
+
+/// 壊せるブロック (大きさは `Transform` の `scale`)
+#[derive(Component)]
+struct Brick;
+
+/// 壊したブロックの数
+#[derive(Resource, Default, Deref, DerefMut)]
+struct Score(u32);
+
+/// 操作方法と、ボールの数・スコアを表示するテキスト
+#[derive(Component)]
+struct InfoText;
+
+/// カメラ・パドル・説明のテキストを配置する
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Sprite::from_color(Color::srgb(0.3, 0.3, 0.7), Vec2::ONE),
+        Transform {
+            translation: Vec3::new(0.0, PADDLE_Y, 0.0),
+            scale: PADDLE_SIZE.extend(1.0),
+            ..default()
+        },
+        Paddle,
+    ));
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+}
+
+/// `LEVEL` に合わせてブロックとプリズムを並べる (各行は中央揃え)
+fn spawn_level(mut commands: Commands, prism_assets: Res<PrismAssets>) {
+    for (row, line) in LEVEL.iter().enumerate() {
+        let columns = line.chars().count() as f32;
+        let left = -(columns - 1.0) / 2.0 * (BRICK_SIZE.x + BRICK_GAP);
+        let y = TOP_ROW_Y - row as f32 * (BRICK_SIZE.y + BRICK_GAP);
+        for (column, tile) in line.chars().enumerate() {
+            let position = Vec2::new(left + column as f32 * (BRICK_SIZE.x + BRICK_GAP), y);
+            match tile {
+                '#' => {
+                    commands.spawn((
+                        Sprite::from_color(Color::from(TOMATO), Vec2::ONE),
+                        Transform {
+                            translation: position.extend(0.0),
+                            scale: BRICK_SIZE.extend(1.0),
+                            ..default()
+                        },
+                        Brick,
+                    ));
+                }
+                'P' => {
+                    commands.spawn(prism_bundle(&prism_assets, position));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `R` キーでブロック・プリズム・ボールを消し、スコアを 0 に戻す
+#[allow(clippy::type_complexity)]
+fn clear_level(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    entities: Query<Entity, Or<(With<Brick>, With<Prism>, With<Ball>)>>,
+) {
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+    **score = 0;
+}
+
+/// `A` / `D` (矢印キー) でパドルを左右に動かす
+fn move_paddle(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut paddle: Single<&mut Transform, With<Paddle>>,
+) {
+    let mut direction = 0.0;
+    if keyboard_input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        direction -= 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        direction += 1.0;
+    }
+    let limit = ARENA_HALF_SIZE.x - PADDLE_SIZE.x / 2.0;
+    paddle.translation.x =
+        (paddle.translation.x + direction * PADDLE_SPEED * time.delta_secs()).clamp(-limit, limit);
+}
+
+/// `W` / `S` (上下の矢印キー) でボールの数の上限を増減する
+///
+/// 上限を下げても、すでに出ているボールは消さない (それ以上は分かれなくなるだけ)。
+fn change_max_balls(keyboard_input: Res<ButtonInput<KeyCode>>, mut max_balls: ResMut<MaxBalls>) {
+    let (min, max) = MAX_BALLS_RANGE;
+    if keyboard_input.any_just_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        **max_balls = (**max_balls + 1).min(max);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        **max_balls = max_balls.saturating_sub(1).max(min);
+    }
+}
+
+/// 部屋の枠を描く (床は開いているので、左右の壁と天井だけ)
+fn draw_arena(mut gizmos: Gizmos) {
+    let (w, h) = (ARENA_HALF_SIZE.x, ARENA_HALF_SIZE.y);
+    gizmos.linestrip_2d(
+        [
+            Vec2::new(-w, -h),
+            Vec2::new(-w, h),
+            Vec2::new(w, h),
+            Vec2::new(w, -h),
+        ],
+        DIM_GRAY,
+    );
+}
+
+/// 操作方法と、ボールの数・上限・スコアを表示する (ブロックをすべて壊したらクリアと出す)
+fn update_info(
+    score: Res<Score>,
+    max_balls: Res<MaxBalls>,
+    balls: Query<(), With<Ball>>,
+    bricks: Query<(), With<Brick>>,
+    text: Single<(&mut Text, &mut TextColor), With<InfoText>>,
+) {
+    let (mut text, mut color) = text.into_inner();
+    let cleared = bricks.is_empty();
+    text.0 = format!(
+        "A/D: move   Space: launch   W/S: max balls   R: reset\n\
+         Balls: {} / {}   Score: {}{}",
+        balls.iter().len(),
+        **max_balls,
+        **score,
+        if cleared { "   Clear!" } else { "" },
+    );
+    color.0 = if cleared { GOLD.into() } else { Color::WHITE };
+}
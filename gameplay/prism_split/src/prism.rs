@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::ball::{ball_bundle, Ball, BallAssets, MaxBalls, BALL_RADIUS};
+
+// プリズムの大きさ (三角形の外接円の半径)。当たり判定もこの半径の円で行う
+pub const PRISM_RADIUS: f32 = 16.0;
+// 分かれた 2 つのボールが、元の進む向きから左右に開く角度 (度)
+const SPLIT_DEGREES: f32 = 30.0;
+// 分かれたときに光る輪の時間と最大の半径
+const FLASH_SECONDS: f32 = 0.3;
+const FLASH_RADIUS: f32 = 40.0;
+
+/// 飛び込んだボールを 2 つに分けるプリズムを追加するプラグイン
+///
+/// ボールがプリズムに触れると、元のボールを消し、元の進む向きから ±`SPLIT_DEGREES` 度に
+/// 開いた同じ速さの 2 つのボールを出す。ボールの数が [`MaxBalls`] に届いているときは分けずに素通りさせる。
+/// 分かれたボールはプリズムから出るまで同じプリズムでは分かれないので、1 回の通過で増えるのは 1 つだけ。
+pub struct PrismPlugin;
+
+impl Plugin for PrismPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_prism_assets)
+            .add_systems(FixedUpdate, (leave_prisms, split_balls).chain())
+            .add_systems(Update, (draw_flashes, fade_flashes));
+    }
+}
+
+/// ボールを分けるプリズム (ブロックと違って壊れない)
+#[derive(Component)]
+pub struct Prism;
+
+/// プリズムの中を通り抜けている途中のボール (このプリズムでは分かれない)
+#[derive(Component)]
+struct Passing(Entity);
+
+/// ボールが分かれたときに広がる輪
+#[derive(Component)]
+struct Flash(Timer);
+
+/// プリズムのメッシュとマテリアル
+#[derive(Resource)]
+pub struct PrismAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+fn setup_prism_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(PrismAssets {
+        mesh: meshes.add(RegularPolygon::new(PRISM_RADIUS, 3)),
+        material: materials.add(Color::srgba(0.7, 0.9, 1.0, 0.8)),
+    });
+}
+
+/// プリズムのエンティティのコンポーネント一式
+pub fn prism_bundle(assets: &PrismAssets, position: Vec2) -> impl Bundle {
+    (
+        Mesh2d(assets.mesh.clone()),
+        MeshMaterial2d(assets.material.clone()),
+        Transform::from_translation(position.extend(0.0)),
+        Prism,
+    )
+}
+
+/// ボールがプリズムに触れているかどうか
+fn touching(ball: Vec2, prism: Vec2) -> bool {
+    ball.distance(prism) <= PRISM_RADIUS + BALL_RADIUS
+}
+
+/// 通り抜けていたプリズムから離れたボールを、またプリズムで分かれるようにする
+fn leave_prisms(
+    mut commands: Commands,
+    balls: Query<(Entity, &Transform, &Passing), With<Ball>>,
+    prisms: Query<&Transform, With<Prism>>,
+) {
+    for (entity, transform, passing) in &balls {
+        let still_inside = prisms.get(passing.0).is_ok_and(|prism| {
+            touching(
+                transform.translation.truncate(),
+                prism.translation.truncate(),
+            )
+        });
+        if !still_inside {
+            commands.entity(entity).remove::<Passing>();
+        }
+    }
+}
+
+/// プリズムに触れたボールを、左右に開いた 2 つのボールに分ける
+///
+/// ボールの数は分けるたびに 1 つずつ増えるので、同じステップで複数のボールが分かれるときも
+/// 1 つ分けるごとに数え直し、[`MaxBalls`] を超えないようにする。
+fn split_balls(
+    mut commands: Commands,
+    max_balls: Res<MaxBalls>,
+    assets: Res<BallAssets>,
+    balls: Query<(Entity, &Transform, &Ball), Without<Passing>>,
+    all_balls: Query<(), With<Ball>>,
+    prisms: Query<(Entity, &Transform), With<Prism>>,
+) {
+    let mut count = all_balls.iter().len();
+    for (entity, transform, ball) in &balls {
+        // パドルに載っている (止まっている) ボールは分けない
+        if ball.velocity == Vec2::ZERO {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let Some((prism, _)) = prisms
+            .iter()
+            .find(|(_, prism)| touching(position, prism.translation.truncate()))
+        else {
+            continue;
+        };
+
+        if count >= **max_balls {
+            commands.entity(entity).insert(Passing(prism));
+            continue;
+        }
+        commands.entity(entity).despawn();
+        for degrees in [SPLIT_DEGREES, -SPLIT_DEGREES] {
+            let velocity = Rot2::degrees(degrees) * ball.velocity;
+            commands.spawn((ball_bundle(&assets, position, velocity), Passing(prism)));
+        }
+        commands.spawn((
+            Transform::from_translation(position.extend(0.0)),
+            Flash(Timer::from_seconds(FLASH_SECONDS, TimerMode::Once)),
+        ));
+        count += 1;
+    }
+}
+
+/// 分かれたときの輪を、広がりながら薄くなるように描く
+fn draw_flashes(flashes: Query<(&Transform, &Flash)>, mut gizmos: Gizmos) {
+    for (transform, flash) in &flashes {
+        let t = flash.0.fraction();
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            FLASH_RADIUS * t,
+            Color::srgba(0.7, 0.9, 1.0, 1.0 - t),
+        );
+    }
+}
+
+/// 輪の時間を進め、消え終わったものを消す
+fn fade_flashes(mut commands: Commands, time: Res<Time>, mut flashes: Query<(Entity, &mut Flash)>) {
+    for (entity, mut flash) in &mut flashes {
+        if flash.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::{
+    color::palettes::css::{SADDLE_BROWN, WHITE},
+    prelude::*,
+};
+
+use crate::{
+    physics, spawn_body, Score, GROUND_Y, PROJECTILE_COLOR, PROJECTILE_DENSITY, PROJECTILE_RADIUS,
+    TIMESTEP_HZ,
+};
+
+// パチンコの弾を置く位置 (ゴムひもの中心)
+const ANCHOR: Vec2 = Vec2::new(-450.0, -170.0);
+// 弾をつかめる、`ANCHOR` からの距離
+const GRAB_RADIUS: f32 = 40.0;
+// 引っ張れる長さの最大と、これより短いときは打ち出さずに戻す長さ
+const MAX_PULL: f32 = 110.0;
+const MIN_PULL: f32 = 10.0;
+// 引っ張った長さ (ピクセル) を初速 (ピクセル/秒) にする倍率
+const LAUNCH_SPEED_SCALE: f32 = 9.0;
+// 軌道を予測する時間 (秒) と、点を打つ間隔 (ステップ数)
+const PREVIEW_SECONDS: f32 = 2.0;
+const DOT_INTERVAL: usize = 5;
+// パチンコの 2 本の先端 (`ANCHOR` からの位置)
+const PRONGS: [Vec2; 2] = [Vec2::new(-16.0, 12.0), Vec2::new(16.0, 12.0)];
+
+/// パチンコを引いて弾を打ち出す操作を追加するプラグイン
+///
+/// `ANCHOR` の近くを左ボタンで押して後ろへ引き、離すと引いた向きと逆向きに打ち出す。
+/// 引いている間は、重力だけを受けて進む弾の軌道を点線で描く。
+pub struct LaunchPlugin;
+
+impl Plugin for LaunchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Aim>()
+            .add_systems(Update, (drag_launch, draw_slingshot).chain());
+    }
+}
+
+/// 弾を引いている位置 (`ANCHOR` からのずれ。引いていないときは `None`)
+#[derive(Resource, Default)]
+struct Aim(Option<Vec2>);
+
+/// カーソルの位置をワールド座標で返す
+fn cursor_position(window: &Window, camera: (&Camera, &GlobalTransform)) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    camera.0.viewport_to_world_2d(camera.1, cursor).ok()
+}
+
+/// 引いたずれから初速を求める (引いた向きと逆向きに、引いた長さに比例した速さ)
+fn launch_velocity(pull: Vec2) -> Vec2 {
+    -pull * LAUNCH_SPEED_SCALE
+}
+
+/// 左ボタンで弾を引き、離したときに打ち出す
+#[allow(clippy::too_many_arguments)]
+fn drag_launch(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut aim: ResMut<Aim>,
+    mut score: ResMut<Score>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut gizmos: Gizmos,
+) {
+    let Some(cursor) = cursor_position(&window, *camera) else {
+        return;
+    };
+    if mouse_input.just_pressed(MouseButton::Left) && cursor.distance(ANCHOR) <= GRAB_RADIUS {
+        aim.0 = Some(Vec2::ZERO);
+    }
+    if aim.0.is_none() {
+        return;
+    }
+    let pull = (cursor - ANCHOR).clamp_length_max(MAX_PULL);
+    aim.0 = Some(pull);
+
+    if mouse_input.just_released(MouseButton::Left) {
+        aim.0 = None;
+        if pull.length() < MIN_PULL {
+            return;
+        }
+        let mut body = physics::Body::dynamic(
+            physics::Shape::Circle {
+                radius: PROJECTILE_RADIUS,
+            },
+            ANCHOR + pull,
+            PROJECTILE_DENSITY,
+        );
+        body.velocity = launch_velocity(pull);
+        spawn_body(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            body,
+            PROJECTILE_COLOR.into(),
+        );
+        score.shots += 1;
+        return;
+    }
+
+    // 重力だけを受けて進む軌道を、地面に届くまで点線で描く
+    let dt = 1.0 / TIMESTEP_HZ as f32;
+    let steps = (PREVIEW_SECONDS / dt) as usize;
+    let path = physics::trajectory(ANCHOR + pull, launch_velocity(pull), steps, dt);
+    for (i, position) in path
+        .into_iter()
+        .enumerate()
+        .take_while(|&(_, position)| position.y > GROUND_Y)
+        .step_by(DOT_INTERVAL)
+    {
+        // 先へ行くほど薄くする
+        let alpha = 1.0 - i as f32 / steps as f32;
+        gizmos.circle_2d(position, 3.0, WHITE.with_alpha(alpha));
+    }
+}
+
+/// パチンコの台とゴムひも、打ち出す前の弾を描く
+fn draw_slingshot(aim: Res<Aim>, mut gizmos: Gizmos) {
+    let fork = ANCHOR - Vec2::Y * 24.0;
+    gizmos.line_2d(Vec2::new(ANCHOR.x, GROUND_Y), fork, SADDLE_BROWN);
+    for prong in PRONGS {
+        gizmos.line_2d(fork, ANCHOR + prong, SADDLE_BROWN);
+    }
+
+    let projectile = ANCHOR + aim.0.unwrap_or(Vec2::ZERO);
+    for prong in PRONGS {
+        gizmos.line_2d(ANCHOR + prong, projectile, SADDLE_BROWN.with_alpha(0.8));
+    }
+    gizmos.circle_2d(projectile, PROJECTILE_RADIUS, PROJECTILE_COLOR);
+}
@@ -0,0 +1,266 @@
+use bevy::{
+    color::palettes::css::{GRAY, LIME, PERU, SADDLE_BROWN, SLATE_GRAY, TOMATO},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+mod launch;
+mod physics;
+
+use physics::Shape;
+
+// 1 秒あたりのシミュレーションのステップ数
+const TIMESTEP_HZ: f64 = 120.0;
+// 地面の上の面の高さと、地面の幅
+const GROUND_Y: f32 = -300.0;
+const GROUND_WIDTH: f32 = 2000.0;
+// 積み上げる箱の大きさ・密度と、上に渡す板の大きさ
+const BOX_SIZE: f32 = 40.0;
+const BOX_DENSITY: f32 = 0.002;
+const PLANK_SIZE: Vec2 = Vec2::new(180.0, 16.0);
+const PLANK_Y: f32 = GROUND_Y + BOX_SIZE * 3.0 + PLANK_SIZE.y / 2.0;
+// 的の半径と密度
+const TARGET_RADIUS: f32 = 16.0;
+const TARGET_DENSITY: f32 = 0.001;
+// 的が最初の位置からこれだけ動いたら倒したことにする (ピクセル)
+const KNOCK_DISTANCE: f32 = 24.0;
+// 打ち出す弾の半径・密度・色
+const PROJECTILE_RADIUS: f32 = 14.0;
+const PROJECTILE_DENSITY: f32 = 0.006;
+const PROJECTILE_COLOR: Srgba = TOMATO;
+// 的 1 つを倒したときの得点
+const TARGET_SCORE: u32 = 1000;
+// この高さより下に落ちた剛体は消す
+const KILL_Y: f32 = -800.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(launch::LaunchPlugin)
+        .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+        .insert_resource(Time::<Fixed>::from_hz(TIMESTEP_HZ))
+        .init_resource::<Score>()
+        .add_systems(Startup, (setup, spawn_level).chain())
+        .add_systems(
+            FixedUpdate,
+            (step_bodies, knock_targets, despawn_fallen).chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                reset_level.run_if(input_just_pressed(KeyCode::KeyR)),
+                update_info,
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// 倒した的の数と打ち出した回数
+#[derive(Resource, Default)]
+struct Score {
+    knocked: u32,
+    shots: u32,
+}
+
+/// `physics` で動かす剛体
+#[derive(Component)]
+struct RigidBody(physics::Body);
+
+/// 倒す的 (最初の位置から `KNOCK_DISTANCE` 以上動いたら倒れたことにする)
+#[derive(Component)]
+struct Target {
+    start: Vec2,
+    knocked: bool,
+}
+
+/// 操作方法と得点を表示するテキスト
+#[derive(Component)]
+struct InfoText;
+
+/// 剛体の形に合わせたメッシュと色で、剛体のエンティティを追加する
+fn spawn_body(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    body: physics::Body,
+    color: Color,
+) -> Entity {
+    let mesh = match body.shape {
+        Shape::Box { half_size } => meshes.add(Rectangle::from_size(half_size * 2.0)),
+        Shape::Circle { radius } => meshes.add(Circle::new(radius)),
+    };
+    commands
+        .spawn((
+            Mesh2d(mesh),
+            MeshMaterial2d(materials.add(color)),
+            body_transform(&body),
+            RigidBody(body),
+        ))
+        .id()
+}
+
+/// 剛体の位置と角度から `Transform` を作る
+fn body_transform(body: &physics::Body) -> Transform {
+    Transform::from_translation(body.position.extend(0.0))
+        .with_rotation(Quat::from_rotation_z(body.angle))
+}
+
+/// 的を置く位置 (板の上・塔の間の地面・低い塔の上)
+fn target_positions() -> [Vec2; 3] {
+    [
+        Vec2::new(310.0, PLANK_Y + PLANK_SIZE.y / 2.0 + TARGET_RADIUS),
+        Vec2::new(310.0, GROUND_Y + TARGET_RADIUS),
+        Vec2::new(490.0, GROUND_Y + BOX_SIZE * 2.0 + TARGET_RADIUS),
+    ]
+}
+
+/// カメラと説明のテキストを配置する
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::BLACK),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+}
+
+/// 地面と、箱を積んだ塔と板、その上や間に置いた的を配置する
+fn spawn_level(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let ground = physics::Body::fixed(
+        Shape::Box {
+            half_size: Vec2::new(GROUND_WIDTH / 2.0, 20.0),
+        },
+        Vec2::new(0.0, GROUND_Y - 20.0),
+    );
+    spawn_body(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        ground,
+        SLATE_GRAY.into(),
+    );
+
+    let crate_box = Shape::Box {
+        half_size: Vec2::splat(BOX_SIZE / 2.0),
+    };
+    let mut spawn_box = |commands: &mut Commands, shape: Shape, position: Vec2, color: Srgba| {
+        let body = physics::Body::dynamic(shape, position, BOX_DENSITY);
+        spawn_body(commands, &mut meshes, &mut materials, body, color.into());
+    };
+
+    // 板を渡す 2 本の塔 (箱 3 段) と、右側の低い塔 (箱 2 段)
+    for (x, height) in [(250.0, 3), (370.0, 3), (490.0, 2)] {
+        for level in 0..height {
+            let y = GROUND_Y + BOX_SIZE * (level as f32 + 0.5);
+            spawn_box(&mut commands, crate_box, Vec2::new(x, y), PERU);
+        }
+    }
+    spawn_box(
+        &mut commands,
+        Shape::Box {
+            half_size: PLANK_SIZE / 2.0,
+        },
+        Vec2::new(310.0, PLANK_Y),
+        SADDLE_BROWN,
+    );
+
+    for position in target_positions() {
+        let body = physics::Body::dynamic(
+            Shape::Circle {
+                radius: TARGET_RADIUS,
+            },
+            position,
+            TARGET_DENSITY,
+        );
+        let target = spawn_body(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            body,
+            LIME.into(),
+        );
+        commands.entity(target).insert(Target {
+            start: position,
+            knocked: false,
+        });
+    }
+}
+
+/// 全剛体を 1 ステップ進めて、`Transform` を剛体の位置と角度に合わせる
+fn step_bodies(time: Res<Time>, mut bodies: Query<(&mut Transform, &mut RigidBody)>) {
+    let mut states: Vec<physics::Body> = bodies.iter().map(|(_, body)| body.0).collect();
+    physics::step(&mut states, time.delta_secs());
+    for ((mut transform, mut body), state) in bodies.iter_mut().zip(states) {
+        body.0 = state;
+        *transform = body_transform(&state);
+    }
+}
+
+/// 最初の位置から `KNOCK_DISTANCE` 以上動いた的を倒したことにして、色を変えて得点を足す
+fn knock_targets(
+    mut targets: Query<(&RigidBody, &mut Target, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut score: ResMut<Score>,
+) {
+    for (body, mut target, material) in &mut targets {
+        if target.knocked || body.0.position.distance(target.start) < KNOCK_DISTANCE {
+            continue;
+        }
+        target.knocked = true;
+        score.knocked += 1;
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = GRAY.into();
+        }
+    }
+}
+
+/// 地面の外へ落ちた剛体を消す
+fn despawn_fallen(mut commands: Commands, bodies: Query<(Entity, &RigidBody)>) {
+    for (entity, body) in &bodies {
+        if body.0.position.y < KILL_Y {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// `R` キーで箱と的を最初の配置に戻し、得点を 0 にする
+fn reset_level(
+    mut commands: Commands,
+    bodies: Query<Entity, With<RigidBody>>,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<ColorMaterial>>,
+    mut score: ResMut<Score>,
+) {
+    for entity in &bodies {
+        commands.entity(entity).despawn();
+    }
+    *score = Score::default();
+    spawn_level(commands, meshes, materials);
+}
+
+/// 操作方法と、倒した的の数・打ち出した回数・得点を表示する
+fn update_info(score: Res<Score>, mut text: Single<&mut Text, With<InfoText>>) {
+    text.0 = format!(
+        "Drag back from the slingshot and release to launch / R: reset\n\
+         Targets: {}/{}  Shots: {}  Score: {}",
+        score.knocked,
+        target_positions().len(),
+        score.shots,
+        score.knocked * TARGET_SCORE,
+    );
+}
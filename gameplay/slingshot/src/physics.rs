@@ -0,0 +1,378 @@
+//! 箱と円だけを扱う小さな 2D 剛体シミュレーション (ECS に依存しない部分)
+//!
+//! 1 ステップごとに、重力で速度を更新し、重なっている組み合わせから接触点を作り、
+//! 接触点ごとの撃力 (インパルス) を繰り返し求めて速度を直してから (逐次インパルス法)、位置を進める。
+
+use bevy::math::{Mat2, Vec2};
+
+// 重力加速度 (ピクセル/秒²)
+pub const GRAVITY: Vec2 = Vec2::new(0.0, -980.0);
+// 撃力を求め直す回数。多いほど積み重ねた箱が安定する
+const ITERATIONS: usize = 16;
+// めり込みを押し戻す強さ (1 ステップで直すめり込みの割合)
+const BAUMGARTE: f32 = 0.2;
+// 押し戻さずに許すめり込みの深さ (ピクセル)。0 にすると接触が途切れて震える
+const SLOP: f32 = 0.5;
+// この速さより遅くぶつかったときは跳ね返らせない (積み重ねた箱が細かく跳ねないようにする)
+const RESTITUTION_THRESHOLD: f32 = 80.0;
+// 箱の頂点が相手の箱の中にあるかを調べるときの余裕 (ピクセル)
+const INSIDE_TOLERANCE: f32 = 0.5;
+
+/// 剛体の形
+#[derive(Clone, Copy, Debug)]
+pub enum Shape {
+    Box { half_size: Vec2 },
+    Circle { radius: f32 },
+}
+
+impl Shape {
+    /// 形を囲む円の半径 (大まかな当たり判定に使う)
+    fn bounding_radius(&self) -> f32 {
+        match *self {
+            Shape::Box { half_size } => half_size.length(),
+            Shape::Circle { radius } => radius,
+        }
+    }
+}
+
+/// 1 つの剛体の状態
+#[derive(Clone, Copy, Debug)]
+pub struct Body {
+    pub position: Vec2,
+    pub angle: f32,
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+    pub inv_mass: f32,    // 質量の逆数 (0 なら動かない)
+    pub inv_inertia: f32, // 慣性モーメントの逆数 (0 なら回らない)
+    pub shape: Shape,
+    pub friction: f32,
+    pub restitution: f32,
+}
+
+impl Body {
+    /// 密度 `density` (質量/ピクセル²) で動く剛体を作る
+    pub fn dynamic(shape: Shape, position: Vec2, density: f32) -> Self {
+        let (mass, inertia) = match shape {
+            Shape::Box { half_size } => {
+                let size = half_size * 2.0;
+                let mass = density * size.x * size.y;
+                (mass, mass * size.length_squared() / 12.0)
+            }
+            Shape::Circle { radius } => {
+                let mass = density * std::f32::consts::PI * radius * radius;
+                (mass, mass * radius * radius / 2.0)
+            }
+        };
+        Body {
+            inv_mass: 1.0 / mass,
+            inv_inertia: 1.0 / inertia,
+            ..Body::fixed(shape, position)
+        }
+    }
+
+    /// 動かない剛体 (地面など) を作る
+    pub fn fixed(shape: Shape, position: Vec2) -> Self {
+        Body {
+            position,
+            angle: 0.0,
+            velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            inv_mass: 0.0,
+            inv_inertia: 0.0,
+            shape,
+            friction: 0.6,
+            restitution: 0.1,
+        }
+    }
+
+    /// 点 `point` での速度 (重心の速度と回転による速度の和)
+    fn velocity_at(&self, point: Vec2) -> Vec2 {
+        let r = point - self.position;
+        self.velocity + self.angular_velocity * r.perp()
+    }
+
+    /// 点 `point` に撃力 `impulse` を加える
+    fn apply_impulse(&mut self, point: Vec2, impulse: Vec2) {
+        self.velocity += impulse * self.inv_mass;
+        self.angular_velocity += (point - self.position).perp_dot(impulse) * self.inv_inertia;
+    }
+}
+
+/// 2 つの剛体の接触点 1 つ分
+struct Contact {
+    a: usize,
+    b: usize,
+    point: Vec2,
+    normal: Vec2, // a から b へ向かう向き
+    depth: f32,   // めり込みの深さ
+    normal_mass: f32,
+    tangent_mass: f32,
+    bias: f32, // めり込みの押し戻しと跳ね返りのために目標にする、法線方向の離れる速さ
+    friction: f32,
+    normal_impulse: f32,  // これまでに加えた法線方向の撃力の合計
+    tangent_impulse: f32, // これまでに加えた接線方向の撃力の合計
+}
+
+/// 全剛体を `dt` 秒進める
+pub fn step(bodies: &mut [Body], dt: f32) {
+    for body in bodies.iter_mut().filter(|body| body.inv_mass > 0.0) {
+        body.velocity += GRAVITY * dt;
+    }
+
+    let mut contacts = find_contacts(bodies);
+    for contact in &mut contacts {
+        prepare(contact, bodies, dt);
+    }
+    for _ in 0..ITERATIONS {
+        for contact in &mut contacts {
+            solve(contact, bodies);
+        }
+    }
+
+    for body in bodies.iter_mut() {
+        body.position += body.velocity * dt;
+        body.angle += body.angular_velocity * dt;
+    }
+}
+
+/// 重なっているすべての組み合わせの接触点を求める
+fn find_contacts(bodies: &[Body]) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    let mut points = Vec::new();
+    for a in 0..bodies.len() {
+        for b in a + 1..bodies.len() {
+            let (body_a, body_b) = (&bodies[a], &bodies[b]);
+            if body_a.inv_mass == 0.0 && body_b.inv_mass == 0.0 {
+                continue;
+            }
+            let reach = body_a.shape.bounding_radius() + body_b.shape.bounding_radius();
+            if body_a.position.distance_squared(body_b.position) > reach * reach {
+                continue;
+            }
+            points.clear();
+            let Some(normal) = collide(body_a, body_b, &mut points) else {
+                continue;
+            };
+            for &(point, depth) in &points {
+                contacts.push(Contact {
+                    a,
+                    b,
+                    point,
+                    normal,
+                    depth,
+                    normal_mass: 0.0,
+                    tangent_mass: 0.0,
+                    bias: 0.0,
+                    friction: (body_a.friction * body_b.friction).sqrt(),
+                    normal_impulse: 0.0,
+                    tangent_impulse: 0.0,
+                });
+            }
+        }
+    }
+    contacts
+}
+
+/// 2 つの剛体が重なっていれば、a から b へ向かう法線を返し、接触点と深さを `points` に入れる
+fn collide(a: &Body, b: &Body, points: &mut Vec<(Vec2, f32)>) -> Option<Vec2> {
+    match (a.shape, b.shape) {
+        (Shape::Box { .. }, Shape::Box { .. }) => collide_boxes(a, b, points),
+        (Shape::Box { .. }, Shape::Circle { .. }) => collide_box_circle(a, b, points),
+        (Shape::Circle { .. }, Shape::Box { .. }) => collide_box_circle(b, a, points).map(|n| -n),
+        (Shape::Circle { radius: ra }, Shape::Circle { radius: rb }) => {
+            let offset = b.position - a.position;
+            let distance = offset.length();
+            if distance >= ra + rb {
+                return None;
+            }
+            let normal = offset.try_normalize().unwrap_or(Vec2::Y);
+            points.push((a.position + normal * ra, ra + rb - distance));
+            Some(normal)
+        }
+    }
+}
+
+/// 箱の 4 つの頂点
+fn corners(body: &Body, half_size: Vec2) -> [Vec2; 4] {
+    let rotation = Mat2::from_angle(body.angle);
+    [
+        Vec2::new(-half_size.x, -half_size.y),
+        Vec2::new(half_size.x, -half_size.y),
+        Vec2::new(half_size.x, half_size.y),
+        Vec2::new(-half_size.x, half_size.y),
+    ]
+    .map(|corner| body.position + rotation * corner)
+}
+
+/// 点が箱の中 (`INSIDE_TOLERANCE` の余裕を含む) にあるかどうか
+fn contains(body: &Body, half_size: Vec2, point: Vec2) -> bool {
+    let local = Mat2::from_angle(-body.angle) * (point - body.position);
+    local.abs().cmple(half_size + INSIDE_TOLERANCE).all()
+}
+
+/// 箱の、向き `axis` への投影の半分の長さ
+fn extent(body: &Body, half_size: Vec2, axis: Vec2) -> f32 {
+    let rotation = Mat2::from_angle(body.angle);
+    half_size.x * rotation.x_axis.dot(axis).abs() + half_size.y * rotation.y_axis.dot(axis).abs()
+}
+
+/// 箱どうしの当たり判定 (分離軸判定)
+///
+/// 両方の箱の辺の向き (4 本) に投影し、どれか 1 本でも投影が重ならなければ離れている。
+/// 重なりがいちばん浅い向きを法線にし、相手の箱の中に入っている頂点を接触点にする。
+fn collide_boxes(a: &Body, b: &Body, points: &mut Vec<(Vec2, f32)>) -> Option<Vec2> {
+    let (Shape::Box { half_size: half_a }, Shape::Box { half_size: half_b }) = (a.shape, b.shape)
+    else {
+        return None;
+    };
+    let rotation_a = Mat2::from_angle(a.angle);
+    let rotation_b = Mat2::from_angle(b.angle);
+    let axes = [
+        rotation_a.x_axis,
+        rotation_a.y_axis,
+        rotation_b.x_axis,
+        rotation_b.y_axis,
+    ];
+    let offset = b.position - a.position;
+    let mut best: Option<(Vec2, f32)> = None;
+    for axis in axes {
+        let distance = offset.dot(axis);
+        let overlap = extent(a, half_a, axis) + extent(b, half_b, axis) - distance.abs();
+        if overlap <= 0.0 {
+            return None;
+        }
+        if best.is_none_or(|(_, best_overlap)| overlap < best_overlap) {
+            let normal = if distance < 0.0 { -axis } else { axis };
+            best = Some((normal, overlap));
+        }
+    }
+    let (normal, overlap) = best?;
+
+    // b の頂点が a の中に入っている深さは、a の法線側の面から測る (a の頂点はその逆)
+    let face_a = a.position.dot(normal) + extent(a, half_a, normal);
+    let face_b = b.position.dot(normal) - extent(b, half_b, normal);
+    for corner in corners(b, half_b) {
+        if contains(a, half_a, corner) {
+            points.push((corner, (face_a - corner.dot(normal)).clamp(0.0, overlap)));
+        }
+    }
+    for corner in corners(a, half_a) {
+        if contains(b, half_b, corner) {
+            points.push((corner, (corner.dot(normal) - face_b).clamp(0.0, overlap)));
+        }
+    }
+    // 辺どうしが交差して、どちらの頂点も相手の中にないとき
+    if points.is_empty() {
+        points.push(((a.position + b.position) / 2.0, overlap));
+    }
+    Some(normal)
+}
+
+/// 箱 `a` と円 `b` の当たり判定 (箱の上で円の中心にいちばん近い点を求める)
+fn collide_box_circle(a: &Body, b: &Body, points: &mut Vec<(Vec2, f32)>) -> Option<Vec2> {
+    let (Shape::Box { half_size }, Shape::Circle { radius }) = (a.shape, b.shape) else {
+        return None;
+    };
+    let rotation = Mat2::from_angle(a.angle);
+    let local = rotation.transpose() * (b.position - a.position);
+    let closest = local.clamp(-half_size, half_size);
+
+    let (local_normal, depth) = if closest == local {
+        // 円の中心が箱の中にあるときは、いちばん近い辺から押し出す
+        let gap = half_size - local.abs();
+        if gap.x < gap.y {
+            (Vec2::new(local.x.signum(), 0.0), gap.x + radius)
+        } else {
+            (Vec2::new(0.0, local.y.signum()), gap.y + radius)
+        }
+    } else {
+        let offset = local - closest;
+        let distance = offset.length();
+        if distance >= radius {
+            return None;
+        }
+        (offset / distance, radius - distance)
+    };
+    let normal = rotation * local_normal;
+    points.push((b.position - normal * radius, depth));
+    Some(normal)
+}
+
+/// 接触点ごとに、撃力の計算に使う値を前もって求める
+fn prepare(contact: &mut Contact, bodies: &[Body], dt: f32) {
+    let (a, b) = (&bodies[contact.a], &bodies[contact.b]);
+    let r_a = contact.point - a.position;
+    let r_b = contact.point - b.position;
+    let tangent = contact.normal.perp();
+
+    // 撃力 1 あたりで、接触点の相対速度がその向きにどれだけ変わるかの逆数 (有効質量)
+    let effective_mass = |direction: Vec2| {
+        let rn_a = r_a.perp_dot(direction);
+        let rn_b = r_b.perp_dot(direction);
+        let k = a.inv_mass + b.inv_mass + a.inv_inertia * rn_a * rn_a + b.inv_inertia * rn_b * rn_b;
+        if k > 0.0 {
+            1.0 / k
+        } else {
+            0.0
+        }
+    };
+    contact.normal_mass = effective_mass(contact.normal);
+    contact.tangent_mass = effective_mass(tangent);
+
+    contact.bias = BAUMGARTE / dt * (contact.depth - SLOP).max(0.0);
+    let approach_speed =
+        -(b.velocity_at(contact.point) - a.velocity_at(contact.point)).dot(contact.normal);
+    if approach_speed > RESTITUTION_THRESHOLD {
+        let restitution = a.restitution.max(b.restitution);
+        contact.bias = contact.bias.max(restitution * approach_speed);
+    }
+}
+
+/// 接触点 1 つの撃力を求め直して、2 つの剛体の速度を直す
+///
+/// 法線方向の撃力の合計は引き寄せる向きにならないように 0 以上に、
+/// 接線方向 (摩擦) の撃力の合計は法線方向の撃力 × 摩擦係数を超えないように制限する。
+fn solve(contact: &mut Contact, bodies: &mut [Body]) {
+    let (a, b) = pair_mut(bodies, contact.a, contact.b);
+    let point = contact.point;
+
+    let relative = b.velocity_at(point) - a.velocity_at(point);
+    let impulse = contact.normal_mass * (contact.bias - relative.dot(contact.normal));
+    let total = (contact.normal_impulse + impulse).max(0.0);
+    let impulse = total - contact.normal_impulse;
+    contact.normal_impulse = total;
+    a.apply_impulse(point, -contact.normal * impulse);
+    b.apply_impulse(point, contact.normal * impulse);
+
+    let tangent = contact.normal.perp();
+    let relative = b.velocity_at(point) - a.velocity_at(point);
+    let impulse = -contact.tangent_mass * relative.dot(tangent);
+    let limit = contact.friction * contact.normal_impulse;
+    let total = (contact.tangent_impulse + impulse).clamp(-limit, limit);
+    let impulse = total - contact.tangent_impulse;
+    contact.tangent_impulse = total;
+    a.apply_impulse(point, -tangent * impulse);
+    b.apply_impulse(point, tangent * impulse);
+}
+
+/// 添字の違う 2 つの要素を同時に可変で借りる (`a < b`)
+fn pair_mut(bodies: &mut [Body], a: usize, b: usize) -> (&mut Body, &mut Body) {
+    let (left, right) = bodies.split_at_mut(b);
+    (&mut left[a], &mut right[0])
+}
+
+/// 位置 `position` から速度 `velocity` で飛び出した物体が、重力だけを受けて進む位置の列
+///
+/// `step` と同じ順番 (速度を更新してから位置を進める) で積分するので、ぶつかるまでは実際の軌道と重なる。
+pub fn trajectory(position: Vec2, velocity: Vec2, steps: usize, dt: f32) -> Vec<Vec2> {
+    let mut position = position;
+    let mut velocity = velocity;
+    let mut path = Vec::with_capacity(steps + 1);
+    path.push(position);
+    for _ in 0..steps {
+        velocity += GRAVITY * dt;
+        position += velocity * dt;
+        path.push(position);
+    }
+    path
+}
@@ -0,0 +1,80 @@
+use crate::rules::{Board, Move, Side, BOARD_SIZE};
+
+// 駒の価値
+const MAN_VALUE: i32 = 100;
+const KING_VALUE: i32 = 160;
+// キングでない駒が 1 行進むごとの加点 (成りに近い駒を少しだけ高く見る)
+const ADVANCE_VALUE: i32 = 3;
+// 勝ち負けが決まった局面の評価値 (駒の価値の合計より十分大きくする)
+const WIN_SCORE: i32 = 100_000;
+
+/// 手番の側から見た局面の評価値 (大きいほど手番の側が有利)
+fn evaluate(board: &Board) -> i32 {
+    board
+        .pieces()
+        .map(|(square, piece)| {
+            let value = if piece.king {
+                KING_VALUE
+            } else {
+                // 自陣の端から何行進んだか
+                let advanced = match piece.side {
+                    Side::Red => square.y,
+                    Side::Black => BOARD_SIZE - 1 - square.y,
+                };
+                MAN_VALUE + advanced * ADVANCE_VALUE
+            };
+            if piece.side == board.turn {
+                value
+            } else {
+                -value
+            }
+        })
+        .sum()
+}
+
+/// アルファベータ法で枝刈りしたミニマックス法 (ネガマックス法で書いたもの) で、手番の側から見た評価値を返す
+///
+/// 指せる手がない側は負け。残りの深さが大きいうちに決まる勝ちほど評価値を大きくして、早く勝てる手を選ぶ。
+fn negamax(board: &Board, depth: u32, mut alpha: i32, beta: i32) -> i32 {
+    let moves = board.legal_moves();
+    if moves.is_empty() {
+        return -(WIN_SCORE + depth as i32);
+    }
+    if depth == 0 {
+        return evaluate(board);
+    }
+
+    let mut best = -i32::MAX;
+    for mv in &moves {
+        let mut next = board.clone();
+        next.apply(mv);
+        let score = -negamax(&next, depth - 1, -beta, -alpha);
+        best = best.max(score);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+/// `depth` 手先まで読んで、手番の側がいちばん良い手を返す (指せる手がなければ `None`)
+///
+/// 盤面のコピーだけを使うので、`AsyncComputeTaskPool` のスレッドからそのまま呼び出せる。
+pub fn best_move(board: &Board, depth: u32) -> Option<Move> {
+    let mut best: Option<(i32, Move)> = None;
+    let mut alpha = -i32::MAX;
+    for mv in board.legal_moves() {
+        let mut next = board.clone();
+        next.apply(&mv);
+        let score = -negamax(&next, depth.saturating_sub(1), -i32::MAX, -alpha);
+        if best
+            .as_ref()
+            .is_none_or(|(best_score, _)| score > *best_score)
+        {
+            alpha = alpha.max(score);
+            best = Some((score, mv));
+        }
+    }
+    best.map(|(_, mv)| mv)
+}
@@ -0,0 +1,468 @@
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+
+mod ai;
+mod rules;
+
+use rules::{is_dark, Board, Move, Outcome, Side, BOARD_SIZE};
+
+// マスの大きさ
+const SQUARE_SIZE: f32 = 64.0;
+// 駒の半径と、キングの印の半径
+const PIECE_RADIUS: f32 = 24.0;
+const CROWN_RADIUS: f32 = 9.0;
+// AI が考え始めてから手を指すまでの最短の時間 (秒)。浅く読むとすぐに終わるので、指した手が見えるように少し待つ
+const AI_MIN_SECONDS: f32 = 0.4;
+// AI の強さを選ぶキーと、そのときに読む深さ (手数)
+const AI_LEVELS: [(KeyCode, u32); 3] = [
+    (KeyCode::Digit1, 2),
+    (KeyCode::Digit2, 5),
+    (KeyCode::Digit3, 8),
+];
+// 最初の AI の読みの深さ
+const DEFAULT_AI_DEPTH: u32 = 5;
+
+// 背景とマスの色
+const BACKGROUND_COLOR: Color = Color::srgb(0.1, 0.09, 0.08);
+const LIGHT_SQUARE_COLOR: Color = Color::srgb(0.86, 0.78, 0.62);
+const DARK_SQUARE_COLOR: Color = Color::srgb(0.45, 0.3, 0.2);
+// 駒の色
+const RED_PIECE_COLOR: Color = Color::srgb(0.8, 0.18, 0.16);
+const BLACK_PIECE_COLOR: Color = Color::srgb(0.12, 0.12, 0.13);
+const CROWN_COLOR: Color = Color::srgb(1.0, 0.8, 0.25);
+// 動かせる駒・選んだ駒・行き先・直前の手の強調の色
+const MOVABLE_COLOR: Color = Color::srgb(0.5, 0.9, 0.5);
+const SELECTED_COLOR: Color = Color::srgb(1.0, 1.0, 0.4);
+const TARGET_COLOR: Color = Color::srgb(0.4, 0.85, 1.0);
+const LAST_MOVE_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.5);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .init_state::<Phase>()
+        .enable_state_scoped_entities::<Phase>()
+        .insert_resource(ClearColor(BACKGROUND_COLOR))
+        .init_resource::<Game>()
+        .init_resource::<Selection>()
+        .insert_resource(AiDepth(DEFAULT_AI_DEPTH))
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            select_square
+                .after(restart)
+                .run_if(in_state(Phase::PlayerTurn)),
+        )
+        .add_systems(OnEnter(Phase::AiThinking), start_ai_task)
+        // 指し直したフレームで、捨てたタスクの手を新しい盤面に指さないように `restart` の後に実行する
+        .add_systems(
+            Update,
+            poll_ai_task
+                .after(restart)
+                .run_if(in_state(Phase::AiThinking)),
+        )
+        .add_systems(OnEnter(Phase::GameOver), show_result)
+        .add_systems(
+            Update,
+            (
+                sync_pieces.run_if(resource_changed::<Game>),
+                draw_highlights,
+                choose_ai_depth,
+                restart,
+                update_info,
+            ),
+        )
+        .run();
+}
+
+/// 対局の段階
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum Phase {
+    #[default]
+    PlayerTurn, // プレイヤー (赤) が指す手を選んでいる
+    AiThinking, // AI (黒) が別のスレッドで指す手を考えている
+    GameOver,   // 勝敗が決まった
+}
+
+/// 対局の状態 (駒の表示はこのリソースが変わったときに作り直す)
+#[derive(Resource)]
+struct Game {
+    board: Board,
+    moves: Vec<Move>,        // 手番の側が指せる手
+    last_move: Option<Move>, // 直前に指した手 (盤面に線で示す)
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        let board = Board::default();
+        Game {
+            moves: board.legal_moves(),
+            board,
+            last_move: None,
+        }
+    }
+}
+
+impl Game {
+    /// 手を指して、次の段階 (相手の手番か勝敗の表示) へ進む
+    fn play(&mut self, mv: Move, next_phase: &mut NextState<Phase>) {
+        self.board.apply(&mv);
+        self.moves = self.board.legal_moves();
+        self.last_move = Some(mv);
+        next_phase.set(match (self.board.outcome(), self.board.turn) {
+            (Some(_), _) => Phase::GameOver,
+            (None, Side::Red) => Phase::PlayerTurn,
+            (None, Side::Black) => Phase::AiThinking,
+        });
+    }
+}
+
+/// プレイヤーが選んでいる駒のマス (駒の表示を作り直さないように `Game` とは分けておく)
+#[derive(Resource, Default)]
+struct Selection(Option<IVec2>);
+
+/// AI が読む深さ (手数)
+#[derive(Resource)]
+struct AiDepth(u32);
+
+/// AI が手を考えているタスク
+///
+/// リソースを取り除くと `Task` が捨てられ、結果は使われない。
+#[derive(Resource)]
+struct AiTask {
+    task: Task<Option<Move>>,
+    delay: Timer, // 指すまでの最短の時間
+    started: f32, // 考え始めた時刻 (経過時間の表示に使う)
+}
+
+/// 盤面の駒の表示
+#[derive(Component)]
+struct PieceSprite;
+
+/// 駒の表示に使うメッシュとマテリアル
+#[derive(Resource)]
+struct PieceAssets {
+    piece: Handle<Mesh>,
+    crown: Handle<Mesh>,
+    red: Handle<ColorMaterial>,
+    black: Handle<ColorMaterial>,
+    crown_color: Handle<ColorMaterial>,
+}
+
+/// 手番・駒の数・操作方法を表示するテキスト
+#[derive(Component)]
+struct InfoText;
+
+/// マスの中心のワールド座標 (盤面の中心が原点)
+fn square_to_world(square: IVec2) -> Vec2 {
+    let offset = (BOARD_SIZE - 1) as f32 / 2.0;
+    (square.as_vec2() - Vec2::splat(offset)) * SQUARE_SIZE
+}
+
+/// ワールド座標にあるマス (盤面の外なら `None`)
+fn world_to_square(position: Vec2) -> Option<IVec2> {
+    let square = (position / SQUARE_SIZE + Vec2::splat(BOARD_SIZE as f32 / 2.0))
+        .floor()
+        .as_ivec2();
+    (square.cmpge(IVec2::ZERO).all() && square.cmplt(IVec2::splat(BOARD_SIZE)).all())
+        .then_some(square)
+}
+
+/// カメラ・盤面のマス・駒の表示に使うアセット・テキストを配置する
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn(Camera2d);
+
+    for x in 0..BOARD_SIZE {
+        for y in 0..BOARD_SIZE {
+            let square = IVec2::new(x, y);
+            let color = if is_dark(square) {
+                DARK_SQUARE_COLOR
+            } else {
+                LIGHT_SQUARE_COLOR
+            };
+            commands.spawn((
+                Sprite::from_color(color, Vec2::splat(SQUARE_SIZE)),
+                Transform::from_translation(square_to_world(square).extend(0.0)),
+            ));
+        }
+    }
+
+    commands.insert_resource(PieceAssets {
+        piece: meshes.add(Circle::new(PIECE_RADIUS)),
+        crown: meshes.add(Circle::new(CROWN_RADIUS)),
+        red: materials.add(RED_PIECE_COLOR),
+        black: materials.add(BLACK_PIECE_COLOR),
+        crown_color: materials.add(CROWN_COLOR),
+    });
+
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+}
+
+/// 盤面が変わったら、駒の表示をすべて作り直す (キングには金色の印を重ねる)
+fn sync_pieces(
+    mut commands: Commands,
+    game: Res<Game>,
+    assets: Res<PieceAssets>,
+    sprites: Query<Entity, With<PieceSprite>>,
+) {
+    for entity in &sprites {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (square, piece) in game.board.pieces() {
+        let material = match piece.side {
+            Side::Red => assets.red.clone(),
+            Side::Black => assets.black.clone(),
+        };
+        let mut entity = commands.spawn((
+            Mesh2d(assets.piece.clone()),
+            MeshMaterial2d(material),
+            Transform::from_translation(square_to_world(square).extend(1.0)),
+            PieceSprite,
+        ));
+        if piece.king {
+            entity.with_child((
+                Mesh2d(assets.crown.clone()),
+                MeshMaterial2d(assets.crown_color.clone()),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            ));
+        }
+    }
+}
+
+/// クリックで駒を選び、選んだ駒の行き先をクリックしてその手を指す
+///
+/// 指せる手のない駒は選べない (取れる駒があるときは、取れる駒しか選べない)。
+/// 続けて跳ぶ手は、最後に止まるマスをクリックする。同じマスに止まる跳び方が複数あるときは最初の手を指す。
+fn select_square(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut game: ResMut<Game>,
+    mut selection: ResMut<Selection>,
+    mut next_phase: ResMut<NextState<Phase>>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(square) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+        .and_then(world_to_square)
+    else {
+        selection.0 = None;
+        return;
+    };
+
+    if let Some(selected) = selection.0 {
+        let chosen = game
+            .moves
+            .iter()
+            .find(|mv| mv.from() == selected && mv.to() == square)
+            .cloned();
+        if let Some(mv) = chosen {
+            selection.0 = None;
+            game.play(mv, &mut next_phase);
+            return;
+        }
+    }
+    selection.0 = game
+        .moves
+        .iter()
+        .any(|mv| mv.from() == square)
+        .then_some(square);
+}
+
+/// AI の手番になったら、盤面のコピーを渡して `AsyncComputeTaskPool` で手を考え始める
+///
+/// 読みが深いと数秒かかることがあるが、別のスレッドで動くのでその間も画面は止まらない。
+fn start_ai_task(mut commands: Commands, time: Res<Time>, game: Res<Game>, depth: Res<AiDepth>) {
+    let board = game.board.clone();
+    let depth = depth.0;
+    let task = AsyncComputeTaskPool::get().spawn(async move { ai::best_move(&board, depth) });
+    commands.insert_resource(AiTask {
+        task,
+        delay: Timer::from_seconds(AI_MIN_SECONDS, TimerMode::Once),
+        started: time.elapsed_secs(),
+    });
+}
+
+/// 毎フレーム AI のタスクが終わったかを確かめ (終わっていなければ待たずに戻る)、終わっていれば AI の手を指す
+fn poll_ai_task(
+    mut commands: Commands,
+    time: Res<Time>,
+    ai_task: Option<ResMut<AiTask>>,
+    mut game: ResMut<Game>,
+    mut next_phase: ResMut<NextState<Phase>>,
+) {
+    let Some(mut ai_task) = ai_task else {
+        return;
+    };
+    if !ai_task.delay.tick(time.delta()).finished() {
+        return;
+    }
+    let Some(result) = block_on(future::poll_once(&mut ai_task.task)) else {
+        return;
+    };
+    commands.remove_resource::<AiTask>();
+    match result {
+        Some(mv) => game.play(mv, &mut next_phase),
+        // 指せる手がない局面は `Game::play` で勝敗の表示に進むので、ここには来ない
+        None => next_phase.set(Phase::GameOver),
+    }
+}
+
+/// 直前の手・動かせる駒・選んだ駒・選んだ駒の行き先を描く
+///
+/// 行き先へは、跳ぶマスをたどる線を引く (続けて跳ぶ手は折れ線になる)。
+fn draw_highlights(
+    phase: Res<State<Phase>>,
+    game: Res<Game>,
+    selection: Res<Selection>,
+    mut gizmos: Gizmos,
+) {
+    if let Some(last_move) = &game.last_move {
+        gizmos.linestrip_2d(
+            last_move.path.iter().map(|&square| square_to_world(square)),
+            LAST_MOVE_COLOR,
+        );
+    }
+    if *phase.get() != Phase::PlayerTurn {
+        return;
+    }
+
+    for mv in &game.moves {
+        gizmos.circle_2d(
+            square_to_world(mv.from()),
+            PIECE_RADIUS + 4.0,
+            MOVABLE_COLOR,
+        );
+    }
+    let Some(selected) = selection.0 else {
+        return;
+    };
+    gizmos.rect_2d(
+        Isometry2d::from_translation(square_to_world(selected)),
+        Vec2::splat(SQUARE_SIZE - 4.0),
+        SELECTED_COLOR,
+    );
+    for mv in game.moves.iter().filter(|mv| mv.from() == selected) {
+        gizmos.linestrip_2d(
+            mv.path.iter().map(|&square| square_to_world(square)),
+            TARGET_COLOR,
+        );
+        gizmos.circle_2d(square_to_world(mv.to()), PIECE_RADIUS * 0.5, TARGET_COLOR);
+    }
+}
+
+/// 数字キーで AI の読みの深さを変える (次の AI の手番から使う)
+fn choose_ai_depth(keyboard_input: Res<ButtonInput<KeyCode>>, mut depth: ResMut<AiDepth>) {
+    for (key, level_depth) in AI_LEVELS {
+        if keyboard_input.just_pressed(key) {
+            depth.0 = level_depth;
+        }
+    }
+}
+
+/// 手番・駒の数・AI の読みの深さと、操作方法を表示する
+///
+/// AI が考えている間は、経過時間と回る記号を表示して画面が止まっていないことを示す。
+fn update_info(
+    time: Res<Time>,
+    phase: Res<State<Phase>>,
+    game: Res<Game>,
+    depth: Res<AiDepth>,
+    ai_task: Option<Res<AiTask>>,
+    mut text: Single<&mut Text, With<InfoText>>,
+) {
+    let status = match (*phase.get(), &ai_task) {
+        (Phase::PlayerTurn, _) if game.moves.iter().any(|mv| !mv.captured.is_empty()) => {
+            "YOUR TURN (Red)  you must capture".to_string()
+        }
+        (Phase::PlayerTurn, _) => "YOUR TURN (Red)".to_string(),
+        (Phase::AiThinking, Some(ai_task)) => {
+            let elapsed = time.elapsed_secs() - ai_task.started;
+            let spinner = ['|', '/', '-', '\\'][(elapsed * 8.0) as usize % 4];
+            format!("AI THINKING (Black) {spinner} {elapsed:.1}s")
+        }
+        (Phase::AiThinking, None) => "AI THINKING (Black)".to_string(),
+        (Phase::GameOver, _) => "GAME OVER".to_string(),
+    };
+    let count = |side: Side| {
+        game.board
+            .pieces()
+            .filter(|(_, piece)| piece.side == side)
+            .count()
+    };
+    text.0 = format!(
+        "{status}\nRed {}  Black {}  quiet moves {}/{}\nAI depth {}\n\n\
+         Click a piece, then click where to move\n1/2/3: AI depth 2/5/8  R: restart",
+        count(Side::Red),
+        count(Side::Black),
+        game.board.quiet_plies,
+        rules::DRAW_PLIES,
+        depth.0
+    );
+}
+
+/// 勝敗を画面の中央に表示する
+fn show_result(mut commands: Commands, game: Res<Game>) {
+    let (message, color) = match game.board.outcome() {
+        Some(Outcome::Win(Side::Red)) => ("YOU WIN", RED_PIECE_COLOR),
+        Some(Outcome::Win(Side::Black)) => ("AI WINS", Color::WHITE),
+        _ => ("DRAW", Color::WHITE),
+    };
+    commands.spawn((
+        Text::new(format!("{message}\nPress R to play again")),
+        TextFont {
+            font_size: 40.0,
+            ..default()
+        },
+        TextColor(color),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(45.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        StateScoped(Phase::GameOver),
+    ));
+}
+
+/// `R` キーで盤面を戻し、プレイヤーの手番から指し直す
+///
+/// AI が考えている途中でも、タスクを捨てて戻す。
+fn restart(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut game: ResMut<Game>,
+    mut selection: ResMut<Selection>,
+    mut next_phase: ResMut<NextState<Phase>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        commands.remove_resource::<AiTask>();
+        *game = Game::default();
+        selection.0 = None;
+        next_phase.set(Phase::PlayerTurn);
+    }
+}
@@ -0,0 +1,260 @@
+use bevy::prelude::*;
+
+// 盤面のマス数 (縦横とも)
+pub const BOARD_SIZE: i32 = 8;
+// 駒を取らないまま続いたら引き分けにする手数 (2 人合わせて)
+pub const DRAW_PLIES: u32 = 80;
+
+/// 手番の側
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Red,   // 下側 (プレイヤー)。先手で、上 (+y) へ進む
+    Black, // 上側 (AI)。下 (-y) へ進む
+}
+
+impl Side {
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Red => Side::Black,
+            Side::Black => Side::Red,
+        }
+    }
+
+    /// 前に進む向き (y 方向)
+    fn forward(self) -> i32 {
+        match self {
+            Side::Red => 1,
+            Side::Black => -1,
+        }
+    }
+
+    /// 成ってキングになる行
+    fn king_row(self) -> i32 {
+        match self {
+            Side::Red => BOARD_SIZE - 1,
+            Side::Black => 0,
+        }
+    }
+}
+
+/// 盤面の駒
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub side: Side,
+    pub king: bool, // キングは斜め前後の 4 方向に進める
+}
+
+impl Piece {
+    /// 進める斜めの向き
+    fn directions(self) -> &'static [IVec2] {
+        const ALL: [IVec2; 4] = [
+            IVec2::new(-1, 1),
+            IVec2::new(1, 1),
+            IVec2::new(-1, -1),
+            IVec2::new(1, -1),
+        ];
+        match (self.king, self.side.forward()) {
+            (true, _) => &ALL,
+            (false, 1) => &ALL[..2],
+            (false, _) => &ALL[2..],
+        }
+    }
+}
+
+/// 1 手 (連続して跳ぶときは、跳んだマスをすべて含む)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub path: Vec<IVec2>,     // 動かす駒のマスから、止まるマスまで
+    pub captured: Vec<IVec2>, // 取る駒のマス (取る順)
+}
+
+impl Move {
+    pub fn from(&self) -> IVec2 {
+        self.path[0]
+    }
+
+    pub fn to(&self) -> IVec2 {
+        self.path[self.path.len() - 1]
+    }
+}
+
+/// 対局の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win(Side),
+    Draw,
+}
+
+/// 盤面と手番
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    squares: [[Option<Piece>; BOARD_SIZE as usize]; BOARD_SIZE as usize],
+    pub turn: Side,
+    pub quiet_plies: u32, // 駒を取らずに続いた手数
+}
+
+impl Default for Board {
+    /// 最初の配置 (両側の手前 3 行の暗いマスに駒を並べる)
+    fn default() -> Self {
+        let mut board = Board {
+            squares: [[None; BOARD_SIZE as usize]; BOARD_SIZE as usize],
+            turn: Side::Red,
+            quiet_plies: 0,
+        };
+        for y in 0..BOARD_SIZE {
+            let side = match y {
+                0..=2 => Side::Red,
+                5..=7 => Side::Black,
+                _ => continue,
+            };
+            for x in 0..BOARD_SIZE {
+                let square = IVec2::new(x, y);
+                if is_dark(square) {
+                    board.set(square, Some(Piece { side, king: false }));
+                }
+            }
+        }
+        board
+    }
+}
+
+/// 駒を置く暗いマスかどうか (左下の角が暗いマス)
+pub fn is_dark(square: IVec2) -> bool {
+    (square.x + square.y) % 2 == 0
+}
+
+/// マスが盤面の中にあるかどうか
+fn in_bounds(square: IVec2) -> bool {
+    square.cmpge(IVec2::ZERO).all() && square.cmplt(IVec2::splat(BOARD_SIZE)).all()
+}
+
+impl Board {
+    pub fn get(&self, square: IVec2) -> Option<Piece> {
+        in_bounds(square)
+            .then(|| self.squares[square.y as usize][square.x as usize])
+            .flatten()
+    }
+
+    fn set(&mut self, square: IVec2, piece: Option<Piece>) {
+        self.squares[square.y as usize][square.x as usize] = piece;
+    }
+
+    /// 駒のあるマスと駒
+    pub fn pieces(&self) -> impl Iterator<Item = (IVec2, Piece)> + '_ {
+        (0..BOARD_SIZE)
+            .flat_map(|y| (0..BOARD_SIZE).map(move |x| IVec2::new(x, y)))
+            .filter_map(|square| self.get(square).map(|piece| (square, piece)))
+    }
+
+    /// 手番の側が指せる手
+    ///
+    /// 取れる駒があるときは取る手しか指せない (取る手があれば、取らない手は返さない)。
+    /// 続けて跳べるときは最後まで跳ぶので、途中で止まる手は返さない。
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut jumps = Vec::new();
+        for (square, piece) in self.pieces().filter(|(_, piece)| piece.side == self.turn) {
+            // 跳んでいる間は、動かす駒の元のマスを空きとして扱う
+            let mut board = self.clone();
+            board.set(square, None);
+            board.collect_jumps(piece, &mut vec![square], &mut Vec::new(), &mut jumps);
+        }
+        if !jumps.is_empty() {
+            return jumps;
+        }
+
+        let mut steps = Vec::new();
+        for (square, piece) in self.pieces().filter(|(_, piece)| piece.side == self.turn) {
+            for &direction in piece.directions() {
+                let target = square + direction;
+                if in_bounds(target) && self.get(target).is_none() {
+                    steps.push(Move {
+                        path: vec![square, target],
+                        captured: Vec::new(),
+                    });
+                }
+            }
+        }
+        steps
+    }
+
+    /// `path` の最後のマスから跳べるところまで跳び、跳び終えた手を `out` に加える
+    ///
+    /// 同じ駒を 2 回取らないように、取った駒は `captured` で覚えておく (取った駒は手が終わるまで盤面に残る)。
+    /// キングでない駒が成る行に着いたら、そこで手を終える。
+    fn collect_jumps(
+        &self,
+        piece: Piece,
+        path: &mut Vec<IVec2>,
+        captured: &mut Vec<IVec2>,
+        out: &mut Vec<Move>,
+    ) {
+        let square = path[path.len() - 1];
+        let mut extended = false;
+        for &direction in piece.directions() {
+            let over = square + direction;
+            let landing = over + direction;
+            let capturable = self
+                .get(over)
+                .is_some_and(|other| other.side != piece.side && !captured.contains(&over));
+            if !capturable || !in_bounds(landing) || self.get(landing).is_some() {
+                continue;
+            }
+
+            extended = true;
+            path.push(landing);
+            captured.push(over);
+            if !piece.king && landing.y == piece.side.king_row() {
+                out.push(Move {
+                    path: path.clone(),
+                    captured: captured.clone(),
+                });
+            } else {
+                self.collect_jumps(piece, path, captured, out);
+            }
+            path.pop();
+            captured.pop();
+        }
+        if !extended && !captured.is_empty() {
+            out.push(Move {
+                path: path.clone(),
+                captured: captured.clone(),
+            });
+        }
+    }
+
+    /// 手を指して、手番を相手に渡す (手は `legal_moves` が返したものであること)
+    pub fn apply(&mut self, mv: &Move) {
+        let Some(mut piece) = self.get(mv.from()) else {
+            return;
+        };
+        self.set(mv.from(), None);
+        for &square in &mv.captured {
+            self.set(square, None);
+        }
+        if mv.to().y == piece.side.king_row() {
+            piece.king = true;
+        }
+        self.set(mv.to(), Some(piece));
+
+        self.quiet_plies = if mv.captured.is_empty() {
+            self.quiet_plies + 1
+        } else {
+            0
+        };
+        self.turn = self.turn.opponent();
+    }
+
+    /// 対局が終わっていれば結果を返す
+    ///
+    /// 手番の側が指せる手がなければ (駒がなくなったときも) 相手の勝ち、
+    /// 駒を取らないまま `DRAW_PLIES` 手続いたら引き分け。
+    pub fn outcome(&self) -> Option<Outcome> {
+        if self.legal_moves().is_empty() {
+            Some(Outcome::Win(self.turn.opponent()))
+        } else if self.quiet_plies >= DRAW_PLIES {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+}
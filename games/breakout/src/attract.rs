@@ -0,0 +1,230 @@
+use bevy::{
+    input::{mouse::AccumulatedMouseMotion, InputSystem},
+    prelude::*,
+};
+
+use crate::{
+    ball::{check_for_collisions, Ball},
+    boss,
+    game_over::RunOutcome,
+    is_single_player,
+    locale::Localized,
+    paddle::{clamp_paddle_x, move_paddle, Paddle, SecondPaddle, PADDLE_SPEED},
+    theme::{ThemeColor, Themed},
+    GameState, Level, Score,
+};
+
+// ゲームオーバー画面で何も操作しないままデモを始めるまでの時間 (秒)
+const IDLE_SECONDS: f32 = 15.0;
+// AI パドルが反応しない、ボールとの x 方向のずれ
+const AI_DEAD_ZONE: f32 = 8.0;
+// デモ中の表示のフォントサイズ
+const BANNER_FONT_SIZE: f32 = 28.0;
+
+/// ゲームオーバー画面を放置するとデモプレイを流すプラグイン (アトラクトモード)
+///
+/// このゲームにはタイトル画面がないため、メニューの代わりのゲームオーバー画面で `IDLE_SECONDS` 秒操作がないと、
+/// 1P パドルを AI (ボールの x 座標を追いかけるだけ) が操作するデモプレイを始める。
+/// デモ中に何かキーかマウスのボタンを押すか画面に触れると、その入力は捨ててゲームオーバー画面に戻る。
+/// デモのスコアはハイスコア・リーダーボード・ベストタイムに記録せず、戻ったゲームオーバー画面には直前のプレイの結果を表示する。
+pub struct AttractPlugin;
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Attract>()
+            .add_systems(OnEnter(GameState::GameOver), reset_idle_timer)
+            .add_systems(
+                Update,
+                (finish_demo.run_if(is_attract_mode), start_demo_when_idle)
+                    .chain()
+                    .run_if(is_single_player.and(in_state(GameState::GameOver))),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                spawn_demo_banner.run_if(is_attract_mode),
+            )
+            // ゲームオーバー画面 (`OnEnter(GameState::GameOver)`) が表示する前に直前のプレイの結果に戻す
+            .add_systems(
+                OnExit(GameState::Playing),
+                restore_last_run.run_if(is_attract_mode),
+            )
+            // ほかのシステムが入力を読む前に、デモを抜ける入力を捨てる
+            .add_systems(
+                PreUpdate,
+                leave_demo_on_input
+                    .after(InputSystem)
+                    .run_if(is_attract_mode.and(in_state(GameState::Playing))),
+            )
+            .add_systems(
+                FixedUpdate,
+                move_demo_paddle
+                    .after(move_paddle)
+                    .before(check_for_collisions)
+                    .run_if(is_attract_mode),
+            );
+    }
+}
+
+/// アトラクトモードの状態
+#[derive(Resource)]
+pub struct Attract {
+    idle: Timer,               // ゲームオーバー画面で操作がない時間
+    last_run: Option<LastRun>, // デモの前に遊んでいたプレイの結果 (デモ中だけ `Some`)
+}
+
+impl Default for Attract {
+    fn default() -> Self {
+        Attract {
+            idle: Timer::from_seconds(IDLE_SECONDS, TimerMode::Once),
+            last_run: None,
+        }
+    }
+}
+
+/// デモの前に遊んでいたプレイの結果 (ゲームオーバー画面に表示する値)
+struct LastRun {
+    score: usize,
+    level: u32,
+    outcome: RunOutcome,
+}
+
+/// デモプレイ中かどうかを判定する条件関数 (記録を残すシステムはデモ中に動かさない)
+pub fn is_attract_mode(attract: Res<Attract>) -> bool {
+    attract.last_run.is_some()
+}
+
+/// ゲームオーバー画面に入るたびに放置時間を数え直す
+fn reset_idle_timer(mut attract: ResMut<Attract>) {
+    attract.idle.reset();
+}
+
+/// デモからゲームオーバー画面に戻ったら、デモを終わったことにする
+///
+/// ゲームオーバー画面の表示 (`OnEnter(GameState::GameOver)`) まではデモ中として扱い、
+/// リーダーボードにデモのスコアを送らないようにする。
+fn finish_demo(mut attract: ResMut<Attract>) {
+    attract.last_run = None;
+    info!("attract mode finished");
+}
+
+/// 何も操作しないまま `IDLE_SECONDS` 秒経ったらデモを始める
+///
+/// ゲームオーバー中は仮想時間が止まっているので、実時間で数える。
+#[allow(clippy::too_many_arguments)]
+fn start_demo_when_idle(
+    time: Res<Time<Real>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    touches: Res<Touches>,
+    score: Res<Score>,
+    level: Res<Level>,
+    outcome: Res<RunOutcome>,
+    mut attract: ResMut<Attract>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.get_pressed().next().is_some()
+        || mouse_input.get_pressed().next().is_some()
+        || mouse_motion.delta != Vec2::ZERO
+        || touches.iter().next().is_some()
+    {
+        attract.idle.reset();
+        return;
+    }
+    if !attract.idle.tick(time.delta()).just_finished() {
+        return;
+    }
+    attract.last_run = Some(LastRun {
+        score: **score,
+        level: **level,
+        outcome: *outcome,
+    });
+    next_state.set(GameState::Playing);
+    info!("attract mode started");
+}
+
+/// 画面の中央にデモ中であることと抜け方を表示する (プレイが終わると `StateScoped` で消える)
+fn spawn_demo_banner(mut commands: Commands) {
+    commands.spawn((
+        Text::new("DEMO - Press any key"),
+        TextFont {
+            font_size: BANNER_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Title),
+        Localized::new("attract_demo"),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(60.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        StateScoped(GameState::Playing),
+    ));
+}
+
+/// キーかマウスのボタンが押されるか、画面に触れたらゲームオーバー画面に戻る
+///
+/// 押した入力は `ButtonInput` から消すので、同じフレームでゲームオーバー画面の `Enter` などとして扱われることはない。
+fn leave_demo_on_input(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_input: ResMut<ButtonInput<MouseButton>>,
+    mut touches: ResMut<Touches>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.get_just_pressed().next().is_none()
+        && mouse_input.get_just_pressed().next().is_none()
+        && !touches.any_just_pressed()
+    {
+        return;
+    }
+    keyboard_input.reset_all();
+    mouse_input.reset_all();
+    touches.reset_all();
+    next_state.set(GameState::GameOver);
+}
+
+/// デモの前のプレイの結果に戻す
+fn restore_last_run(
+    attract: Res<Attract>,
+    mut score: ResMut<Score>,
+    mut level: ResMut<Level>,
+    mut outcome: ResMut<RunOutcome>,
+) {
+    let Some(last_run) = &attract.last_run else {
+        return;
+    };
+    **score = last_run.score;
+    **level = last_run.level;
+    *outcome = last_run.outcome;
+}
+
+/// 1P パドルをボールの x 座標に向かって移動させる
+#[allow(clippy::type_complexity)]
+fn move_demo_paddle(
+    mut paddle_query: Query<
+        &mut Transform,
+        (
+            With<Paddle>,
+            Without<SecondPaddle>,
+            Without<boss::Stunned>,
+            Without<Ball>,
+        ),
+    >,
+    ball_query: Single<&Transform, With<Ball>>,
+    time: Res<Time>,
+) {
+    let target_x = ball_query.translation.x;
+    for mut paddle_transform in &mut paddle_query {
+        let offset = target_x - paddle_transform.translation.x;
+        if offset.abs() < AI_DEAD_ZONE {
+            continue;
+        }
+        let max_step = PADDLE_SPEED * time.delta_secs();
+        let new_paddle_position =
+            paddle_transform.translation.x + offset.clamp(-max_step, max_step);
+        paddle_transform.translation.x =
+            clamp_paddle_x(new_paddle_position, paddle_transform.scale.x);
+    }
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use bevy::{audio::Pitch, prelude::*};
+
+use crate::{
+    ball::{check_for_collisions, CollisionEvent},
+    cracks::BrickCracked,
+};
+
+// 硬いブロックにひびが入ったときの音の高さ (Hz) と長さ (壊れたときの衝突音より低く短い音にする)
+const CRACK_SOUND_FREQUENCY: f32 = 180.0;
+const CRACK_SOUND_DURATION: Duration = Duration::from_millis(60);
+
+/// ボールがぶつかったときに衝突音を鳴らすプラグイン
+///
+/// 硬いブロックが壊れずにひびが入ったときは、壊れたときの衝突音の代わりにひびの音を鳴らす。
+pub struct CollisionSoundPlugin;
+
+impl Plugin for CollisionSoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_collision_sound).add_systems(
+            FixedUpdate,
+            play_collision_sound.after(check_for_collisions),
+        );
+    }
+}
+
+// 衝突音のリソース（音源のハンドル）
+#[derive(Resource)]
+pub struct CollisionSound {
+    pub hit: Handle<AudioSource>, // ブロックが壊れたときや、パドル・壁に当たったときの音
+    crack: Handle<Pitch>,         // 硬いブロックにひびが入ったときの音
+}
+
+/// 衝突音を読み込み、ひびの音を用意する
+fn load_collision_sound(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    commands.insert_resource(CollisionSound {
+        hit: asset_server.load("sounds/breakout_collision.ogg"),
+        crack: pitches.add(Pitch::new(CRACK_SOUND_FREQUENCY, CRACK_SOUND_DURATION)),
+    });
+}
+
+/// 衝突音を再生する関数
+fn play_collision_sound(
+    mut commands: Commands, // コマンドを送信してエンティティを操作
+    mut collision_events: EventReader<CollisionEvent>, // 衝突イベントを読み取る
+    mut cracked_events: EventReader<BrickCracked>, // ひびが入ったイベントを読み取る
+    sound: Res<CollisionSound>, // 衝突音リソース
+) {
+    // 同じステップで複数のコライダーにぶつかっても、音は 1 回だけ再生する
+    // (イベントはすべて読み取り、次のステップに引き継がないようにする)
+    let collided = collision_events.read().count() > 0;
+    let cracked = cracked_events.read().count() > 0;
+    // 音声再生のためにエンティティを生成
+    if cracked {
+        commands.spawn((AudioPlayer(sound.crack.clone()), PlaybackSettings::DESPAWN));
+    } else if collided {
+        commands.spawn((AudioPlayer(sound.hit.clone()), PlaybackSettings::DESPAWN));
+    }
+}
@@ -0,0 +1,274 @@
+use bevy::{
+    math::bounding::{Aabb2d, BoundingCircle, BoundingVolume, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    bricks::{Brick, BrickDestroyed},
+    cracks::BrickCracked,
+    debug_overlay,
+    difficulty::Difficulty,
+    glow::Glow,
+    levels,
+    paddle::Paddle,
+    scoring, spin,
+    theme::{ThemeColor, Themed},
+    versus::Side,
+};
+
+// ボールの開始位置（z値は上に重ねて描画するために設定）
+pub const BALL_STARTING_POSITION: Vec3 = Vec3::new(0.0, -50.0, 1.0);
+// ボールの直径
+pub const BALL_DIAMETER: f32 = 30.;
+// ボールの初期速度 (難易度が Normal のとき。`difficulty.rs` が難易度に合わせて変える)
+pub const BALL_SPEED: f32 = 400.0;
+// ボールの初期方向（x, y方向の速度）
+pub const INITIAL_BALL_DIRECTION: Vec2 = Vec2::new(0.5, -0.5);
+
+/// ボールの生成・移動・衝突判定を行うプラグイン
+///
+/// 固定更新（64Hzで更新される）スケジュールで `apply_velocity` → `check_for_collisions` の順に実行する。
+/// パドルの移動 (`paddle.rs`) や衝突音 (`audio.rs`) はこの 2 つのシステムを基準に順序を決める。
+pub struct BallPlugin;
+
+impl Plugin for BallPlugin {
+    fn build(&self, app: &mut App) {
+        // 衝突イベントを追加 (ゲーム中で発生するイベント)
+        app.add_event::<CollisionEvent>()
+            .add_systems(Startup, spawn_ball)
+            .add_systems(FixedUpdate, (apply_velocity, check_for_collisions).chain());
+    }
+}
+
+// ボールを示すコンポーネント
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Ball;
+
+// 速度を示すコンポーネント（Vec2型でX軸とY軸の速度）は、共有クレートのものを使う
+// （移動はこのモジュールの `apply_velocity` が加速度と倍率を含めて行う）
+pub use prelude_kit::Velocity;
+
+// 加速度を示すコンポーネント（`apply_velocity` より前に各システムが力を足し込み、
+// `apply_velocity` が速度に反映したあと 0 に戻す）
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct Acceleration(pub Vec2);
+
+// 速度に掛けて移動量を増やす倍率を示すコンポーネント（速度そのものは変えないので、
+// 反射や打ち出しの速さの計算はそのまま使える。デイリーチャレンジの「速いボール」が付ける）
+#[derive(Component, Deref)]
+pub struct SpeedScale(pub f32);
+
+// 衝突判定用コンポーネント（ゲーム内で衝突判定を持つオブジェクト）
+#[derive(Component)]
+pub struct Collider;
+
+/// 衝突イベント（ボールが何にどの面からぶつかったかを伝える）
+///
+/// 1 回の固定ステップで複数のコライダーにぶつかったときは、コライダーごとに 1 つずつ発行する。
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CollisionEvent {
+    pub ball: Entity,     // ぶつかったボール
+    pub collider: Entity, // ぶつかった相手 (壁・パドル・ブロックなど)
+    pub side: Collision,  // ボールから見たコライダーの面
+    pub point: Vec2,      // コライダー上の衝突した点 (ワールド座標)
+}
+
+/// ボールを追加する
+fn spawn_ball(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    difficulty: Res<Difficulty>,
+) {
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::default())), // ボールの形状
+        MeshMaterial2d(materials.add(ColorMaterial::default())), // ボール専用のマテリアル
+        Themed(ThemeColor::Ball),              // ボールの色
+        Glow(1.0),                             // ボールの明るさ (`glow.rs` が速さに合わせて変える)
+        Transform::from_translation(BALL_STARTING_POSITION)
+            .with_scale(Vec2::splat(BALL_DIAMETER).extend(1.)), // ボールの位置とサイズ
+        Ball,                                  // ボールコンポーネント
+        Velocity(INITIAL_BALL_DIRECTION.normalize() * difficulty.ball_speed()), // ボールの速度
+        Acceleration::default(),               // ボールの加速度 (磁力などで曲げるときに使う)
+        spin::Spin::default(),                 // ボールの回転
+    ));
+}
+
+/// ボールを開始位置に戻し、速度と回転を初期化する (`speed` は難易度で決まるサーブの速さ)
+pub fn reset_ball(
+    mut transform: Mut<Transform>,
+    mut velocity: Mut<Velocity>,
+    mut spin: Mut<spin::Spin>,
+    speed: f32,
+) {
+    transform.translation = BALL_STARTING_POSITION;
+    velocity.0 = INITIAL_BALL_DIRECTION.normalize() * speed;
+    *spin = spin::Spin::default();
+}
+
+/// ボールの速度を適用し、位置を更新する関数
+///
+/// 加速度を持つエンティティは、先に加速度を速度に反映してから位置を更新する (半陰的オイラー法)。
+pub fn apply_velocity(
+    mut query: Query<(
+        &mut Transform,
+        &mut Velocity,
+        Option<&mut Acceleration>,
+        Option<&SpeedScale>,
+    )>,
+    time: Res<Time>,
+) {
+    for (mut transform, mut velocity, acceleration, speed_scale) in &mut query {
+        // 加速度に基づき速度を更新し、次のステップのために加速度を 0 に戻す
+        if let Some(mut acceleration) = acceleration {
+            velocity.0 += acceleration.0 * time.delta_secs();
+            acceleration.0 = Vec2::ZERO;
+        }
+
+        // 速度に基づき、ボールの位置を更新
+        let scale = speed_scale.map_or(1.0, |scale| **scale);
+        transform.translation.x += velocity.x * scale * time.delta_secs();
+        transform.translation.y += velocity.y * scale * time.delta_secs();
+    }
+}
+
+/// 衝突を検出し、必要な処理を行う関数
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn check_for_collisions(
+    mut commands: Commands,            // コマンドを送信してエンティティを操作
+    mut combo: ResMut<scoring::Combo>, // パドルで打ち返すとリセットするコンボ数
+    mut collision_checks: ResMut<debug_overlay::CollisionChecks>, // デバッグ表示用の衝突判定の回数
+    ball_query: Single<(Entity, &mut Velocity, &Transform), With<Ball>>, // ボールのクエリ
+    collider_query: Query<(Entity, &Transform, Option<&Brick>, Option<&Paddle>), With<Collider>>, // 衝突する可能性のあるエンティティ
+    brick_sides: Query<&Side, With<Brick>>, // 対戦モードでのブロックの持ち主
+    sturdy_bricks: Query<(), With<levels::Sturdy>>, // まだ壊れない硬いブロック
+    mut collision_events: EventWriter<CollisionEvent>, // 衝突イベントを発行
+    mut brick_destroyed_events: EventWriter<BrickDestroyed>, // ブロック破壊イベントを発行
+    mut brick_cracked_events: EventWriter<BrickCracked>, // ブロックにひびが入ったイベントを発行
+) {
+    let (ball_entity, mut ball_velocity, ball_transform) = ball_query.into_inner();
+    **collision_checks = 0;
+
+    // 衝突可能なすべてのエンティティと衝突をチェック
+    for (collider_entity, collider_transform, maybe_brick, maybe_paddle) in &collider_query {
+        **collision_checks += 1;
+        // ボールとコライダーの衝突判定
+        let ball_bounds =
+            BoundingCircle::new(ball_transform.translation.truncate(), BALL_DIAMETER / 2.);
+        let collider_bounds = Aabb2d::new(
+            collider_transform.translation.truncate(),
+            collider_transform.scale.truncate() / 2.,
+        );
+        let collision = ball_collision(ball_bounds, collider_bounds);
+
+        // 衝突があった場合
+        if let Some(collision) = collision {
+            // 衝突イベントを発行
+            collision_events.send(CollisionEvent {
+                ball: ball_entity,
+                collider: collider_entity,
+                side: collision,
+                point: collider_bounds.closest_point(ball_bounds.center()),
+            });
+
+            // パドルで打ち返したらコンボが途切れる
+            if maybe_paddle.is_some() {
+                combo.reset();
+            }
+
+            // ブロックに衝突した場合、ブロックを消去してイベントを発行
+            // (得点は通常モードでは `scoring.rs`、対戦モードでは `versus.rs` がイベントから加算する)
+            if maybe_brick.is_some() && sturdy_bricks.contains(collider_entity) {
+                // 硬いブロックは 1 回目の衝突では壊れず、ひびの入った普通のブロックになる
+                commands
+                    .entity(collider_entity)
+                    .remove::<levels::Sturdy>()
+                    .insert(Themed(ThemeColor::Brick));
+                brick_cracked_events.send(BrickCracked {
+                    brick: collider_entity,
+                    hits_taken: 1,
+                    hits_to_break: levels::STURDY_HITS,
+                });
+            } else if maybe_brick.is_some() {
+                // ひびのスプライト (子エンティティ) ごと消去する
+                commands.entity(collider_entity).despawn_recursive(); // ブロックを消去
+                let side = brick_sides.get(collider_entity).ok().copied();
+                brick_destroyed_events.send(BrickDestroyed {
+                    position: collider_transform.translation.truncate(),
+                    side,
+                });
+            }
+
+            // ボールの速度を反転させる（衝突の反射）
+            let mut reflect_x = false;
+            let mut reflect_y = false;
+
+            // 反射処理（衝突した方向によってボールの速度を反転）
+            match collision {
+                Collision::Left => reflect_x = ball_velocity.x > 0.0,
+                Collision::Right => reflect_x = ball_velocity.x < 0.0,
+                Collision::Top => reflect_y = ball_velocity.y < 0.0,
+                Collision::Bottom => reflect_y = ball_velocity.y > 0.0,
+            }
+
+            // x軸での反射
+            if reflect_x {
+                ball_velocity.x = -ball_velocity.x;
+            }
+
+            // y軸での反射
+            if reflect_y {
+                ball_velocity.y = -ball_velocity.y;
+            }
+        }
+    }
+}
+
+/// 衝突の種類を表す列挙型
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Collision {
+    Left,   // 左側の衝突
+    Right,  // 右側の衝突
+    Top,    // 上側の衝突
+    Bottom, // 下側の衝突
+}
+
+impl Collision {
+    /// 衝突した面の外向きの法線 (ボールはこの向きに跳ね返る)
+    pub fn normal(self) -> Vec2 {
+        match self {
+            Collision::Left => Vec2::NEG_X,
+            Collision::Right => Vec2::X,
+            Collision::Top => Vec2::Y,
+            Collision::Bottom => Vec2::NEG_Y,
+        }
+    }
+}
+
+// ボールとコライダーの衝突を判定し、衝突した側を返す
+pub fn ball_collision(ball: BoundingCircle, bounding_box: Aabb2d) -> Option<Collision> {
+    if !ball.intersects(&bounding_box) {
+        return None; // 衝突していない場合はNoneを返す
+    }
+
+    // 衝突した最寄の点を計算
+    let closest = bounding_box.closest_point(ball.center());
+    let offset = ball.center() - closest;
+    let side = if offset.x.abs() > offset.y.abs() {
+        // x軸方向の衝突判定
+        if offset.x < 0. {
+            Collision::Left
+        } else {
+            Collision::Right
+        }
+    } else if offset.y > 0. {
+        // y軸方向の衝突判定（上）
+        Collision::Top
+    } else {
+        // y軸方向の衝突判定（下）
+        Collision::Bottom
+    };
+
+    Some(side) // 衝突した側を返す
+}
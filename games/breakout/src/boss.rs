@@ -0,0 +1,402 @@
+use bevy::{
+    math::{
+        bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+        ops,
+    },
+    prelude::*,
+};
+
+use crate::{
+    audio::CollisionSound,
+    ball::{ball_collision, check_for_collisions, Ball, Collider, Velocity, BALL_DIAMETER},
+    bricks::Brick,
+    is_single_player,
+    paddle::Paddle,
+    theme::{ThemeColor, Themed},
+    walls::{BOTTOM_WALL, LEFT_WALL, RIGHT_WALL, TOP_WALL},
+    GameState, Score,
+};
+
+// ボスが登場する最初のレベル (以降のレベルではすべてボス戦になる)
+const BOSS_FIRST_LEVEL: u32 = 2;
+// ボス戦でボスのために確保する、アリーナ上部の領域の高さ
+pub const BOSS_AREA_HEIGHT: f32 = 110.0;
+// ボスのサイズ
+const BOSS_SIZE: Vec2 = Vec2::new(320.0, 60.0);
+// ボスを構成するセグメントの数
+const BOSS_SEGMENTS: usize = 5;
+// ボスの基本 HP と、レベルが 1 上がるごとの増加量
+const BOSS_BASE_HEALTH: u32 = 10;
+const BOSS_HEALTH_PER_LEVEL: u32 = 5;
+// ボスの左右の揺れ幅と周期 (秒)
+const BOSS_SWAY_AMPLITUDE: f32 = 200.0;
+const BOSS_SWAY_PERIOD: f32 = 8.0;
+// ボスが攻撃する間隔 (秒)
+const BOSS_ATTACK_INTERVAL: f32 = 2.0;
+// 同じ接触で何度もダメージを受けないようにする無敵時間 (秒)
+const BOSS_HIT_COOLDOWN: f32 = 0.2;
+// ボスの撃破演出の長さ (秒)
+const BOSS_DEATH_DURATION: f32 = 1.5;
+// ボスを倒したときのボーナス得点
+const BOSS_BONUS_SCORE: usize = 20;
+
+// 落下物のサイズと落下速度
+const HAZARD_SIZE: Vec2 = Vec2::new(14.0, 14.0);
+const HAZARD_SPEED: f32 = 220.0;
+// 落下物に当たったパドルが動けなくなる時間 (秒)
+const STUN_DURATION: f32 = 1.0;
+
+/// ボスブロック・落下物・HP バーを追加するプラグイン
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                sway_boss,
+                damage_boss.after(check_for_collisions),
+                boss_attack,
+                check_hazard_collisions,
+                despawn_fallen_hazards,
+                recover_stunned_paddles,
+                play_boss_death,
+            )
+                .run_if(is_single_player),
+        )
+        .add_systems(
+            Update,
+            (update_segments, update_health_bar).run_if(is_single_player),
+        );
+    }
+}
+
+/// ボスブロックを示すコンポーネント
+#[derive(Component)]
+pub struct Boss {
+    health: u32,
+    max_health: u32,
+    attack_timer: Timer,  // 次の攻撃までのタイマー
+    hit_cooldown: Timer,  // 被弾後の無敵時間
+    dying: Option<Timer>, // 撃破演出中であればその残り時間
+}
+
+/// ボスを構成するセグメント (見た目のみ) を示すコンポーネント
+#[derive(Component)]
+struct BossSegment(usize);
+
+/// ボスの落下物を示すコンポーネント
+#[derive(Component)]
+struct Hazard;
+
+/// 落下物に当たって動けなくなったパドルを示すコンポーネント
+#[derive(Component)]
+pub struct Stunned {
+    timer: Timer,
+    color: ThemeColor, // 回復したときに戻す元の色
+}
+
+/// ボスの HP バーのルート UI を示すコンポーネント
+#[derive(Component)]
+struct BossHealthBar;
+
+/// ボスの HP バーの残量部分を示すコンポーネント
+#[derive(Component)]
+struct BossHealthFill;
+
+/// 指定したレベルがボス戦かどうかを返す
+pub fn is_boss_level(level: u32) -> bool {
+    level >= BOSS_FIRST_LEVEL
+}
+
+/// ボスと HP バーを生成する
+///
+/// ボスは 1 つの大きな `Collider` で、子エンティティのセグメントが HP の残りを表す。
+pub fn spawn_boss(commands: &mut Commands, level: u32) {
+    let max_health = BOSS_BASE_HEALTH + (level - BOSS_FIRST_LEVEL) * BOSS_HEALTH_PER_LEVEL;
+    let boss_y = TOP_WALL - BOSS_AREA_HEIGHT / 2.0;
+
+    commands
+        .spawn((
+            Sprite::default(),
+            Themed(ThemeColor::BossFrame),
+            Transform {
+                translation: Vec3::new(0.0, boss_y, 0.0),
+                scale: BOSS_SIZE.extend(1.0),
+                ..default()
+            },
+            Boss {
+                health: max_health,
+                max_health,
+                attack_timer: Timer::from_seconds(BOSS_ATTACK_INTERVAL, TimerMode::Repeating),
+                hit_cooldown: Timer::from_seconds(BOSS_HIT_COOLDOWN, TimerMode::Once),
+                dying: None,
+            },
+            Collider,
+            StateScoped(GameState::Playing),
+        ))
+        .with_children(|parent| {
+            // 親のスケールが掛かるため、子の座標とサイズはボス全体を 1 とした割合で指定する
+            let segment_width = 1.0 / BOSS_SEGMENTS as f32;
+            for i in 0..BOSS_SEGMENTS {
+                parent.spawn((
+                    Sprite::default(),
+                    Themed(ThemeColor::BossSegment),
+                    Transform {
+                        translation: Vec3::new((i as f32 + 0.5) * segment_width - 0.5, 0.0, 0.1),
+                        scale: Vec3::new(segment_width * 0.85, 0.7, 1.0),
+                        ..default()
+                    },
+                    BossSegment(i),
+                ));
+            }
+        });
+
+    // 画面上部中央に HP バーを表示する
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                left: Val::Percent(30.0),
+                width: Val::Percent(40.0),
+                height: Val::Px(14.0),
+                ..default()
+            },
+            Themed(ThemeColor::HealthBarBackground),
+            BossHealthBar,
+            StateScoped(GameState::Playing),
+        ))
+        .with_child((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            Themed(ThemeColor::HealthBar),
+            BossHealthFill,
+        ));
+}
+
+/// ボスを左右に揺らす
+fn sway_boss(time: Res<Time>, mut bosses: Query<(&Boss, &mut Transform)>) {
+    let phase = time.elapsed_secs() / BOSS_SWAY_PERIOD * std::f32::consts::TAU;
+    let max_x = RIGHT_WALL - BOSS_SIZE.x / 2.0 - 20.0;
+    let min_x = LEFT_WALL + BOSS_SIZE.x / 2.0 + 20.0;
+    for (boss, mut transform) in &mut bosses {
+        if boss.dying.is_none() {
+            transform.translation.x = (ops::sin(phase) * BOSS_SWAY_AMPLITUDE).clamp(min_x, max_x);
+        }
+    }
+}
+
+/// ボールがボスに当たったら HP を減らす
+///
+/// 反射は `check_for_collisions` が行うため、ここでは接触判定だけを行う。
+/// 反射後もボールが重なったままのフレームがあるため、無敵時間で多重ヒットを防ぐ。
+fn damage_boss(
+    time: Res<Time>,
+    mut score: ResMut<Score>,
+    ball_transform: Single<&Transform, With<Ball>>,
+    mut bosses: Query<(&mut Boss, &Transform), With<Collider>>,
+) {
+    for (mut boss, boss_transform) in &mut bosses {
+        boss.hit_cooldown.tick(time.delta());
+        if !boss.hit_cooldown.finished() {
+            continue;
+        }
+
+        let collision = ball_collision(
+            BoundingCircle::new(ball_transform.translation.truncate(), BALL_DIAMETER / 2.),
+            Aabb2d::new(
+                boss_transform.translation.truncate(),
+                boss_transform.scale.truncate() / 2.,
+            ),
+        );
+        if collision.is_none() {
+            continue;
+        }
+
+        boss.hit_cooldown.reset();
+        boss.health = boss.health.saturating_sub(1);
+        **score += 1;
+        if boss.health == 0 {
+            boss.dying = Some(Timer::from_seconds(BOSS_DEATH_DURATION, TimerMode::Once));
+        }
+    }
+}
+
+/// 一定間隔でパドルの真上あたりに落下物を落とす
+fn boss_attack(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bosses: Query<(&mut Boss, &Transform)>,
+    paddles: Query<&Transform, (With<Paddle>, Without<Boss>)>,
+) {
+    for (mut boss, boss_transform) in &mut bosses {
+        if boss.dying.is_some() || !boss.attack_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        // 最も近いパドルを狙うが、ボスの横幅の外には落とさない
+        let boss_x = boss_transform.translation.x;
+        let target_x = paddles
+            .iter()
+            .map(|paddle| paddle.translation.x)
+            .min_by(|a, b| (a - boss_x).abs().total_cmp(&(b - boss_x).abs()))
+            .unwrap_or(boss_x);
+        let half_width = BOSS_SIZE.x / 2.0;
+        let hazard_x = target_x.clamp(boss_x - half_width, boss_x + half_width);
+        let hazard_y = boss_transform.translation.y - BOSS_SIZE.y / 2.0;
+
+        commands.spawn((
+            Sprite::default(),
+            Themed(ThemeColor::Hazard),
+            Transform {
+                translation: Vec3::new(hazard_x, hazard_y, 1.0),
+                scale: HAZARD_SIZE.extend(1.0),
+                ..default()
+            },
+            Hazard,
+            Velocity(Vec2::new(0.0, -HAZARD_SPEED)),
+            StateScoped(GameState::Playing),
+        ));
+    }
+}
+
+/// 落下物がパドルに当たったら、そのパドルをしばらく動けなくする
+#[allow(clippy::type_complexity)]
+fn check_hazard_collisions(
+    mut commands: Commands,
+    sound: Res<CollisionSound>,
+    hazards: Query<(Entity, &Transform), With<Hazard>>,
+    mut paddles: Query<(Entity, &Transform, &mut Themed), (With<Paddle>, Without<Stunned>)>,
+) {
+    for (hazard, hazard_transform) in &hazards {
+        let hazard_box = Aabb2d::new(
+            hazard_transform.translation.truncate(),
+            hazard_transform.scale.truncate() / 2.,
+        );
+        for (paddle, paddle_transform, mut themed) in &mut paddles {
+            let paddle_box = Aabb2d::new(
+                paddle_transform.translation.truncate(),
+                paddle_transform.scale.truncate() / 2.,
+            );
+            if !hazard_box.intersects(&paddle_box) {
+                continue;
+            }
+
+            commands.entity(hazard).despawn();
+            commands.entity(paddle).insert(Stunned {
+                timer: Timer::from_seconds(STUN_DURATION, TimerMode::Once),
+                color: themed.0,
+            });
+            themed.0 = ThemeColor::StunnedPaddle;
+            commands.spawn((AudioPlayer(sound.hit.clone()), PlaybackSettings::DESPAWN));
+            break;
+        }
+    }
+}
+
+/// 床まで落ちた落下物を削除する
+fn despawn_fallen_hazards(
+    mut commands: Commands,
+    hazards: Query<(Entity, &Transform), With<Hazard>>,
+) {
+    for (hazard, transform) in &hazards {
+        if transform.translation.y < BOTTOM_WALL {
+            commands.entity(hazard).despawn();
+        }
+    }
+}
+
+/// 動けなくなったパドルを時間経過で元に戻す
+fn recover_stunned_paddles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut paddles: Query<(Entity, &mut Stunned, &mut Themed)>,
+) {
+    for (paddle, mut stunned, mut themed) in &mut paddles {
+        if stunned.timer.tick(time.delta()).just_finished() {
+            themed.0 = stunned.color;
+            commands.entity(paddle).remove::<Stunned>();
+        }
+    }
+}
+
+/// ボスの撃破演出を再生し、終わったらレベルをクリアする
+///
+/// 演出中は当たり判定を外して点滅しながら縮み、最後にボス・残りのブロック・落下物を
+/// すべて削除する。盤面が空になるので次のフレームで `victory.rs` がレベルクリアとして扱う。
+fn play_boss_death(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut score: ResMut<Score>,
+    mut bosses: Query<(Entity, &mut Boss, &mut Transform, &mut Visibility)>,
+    bricks: Query<Entity, With<Brick>>,
+    hazards: Query<Entity, With<Hazard>>,
+    health_bars: Query<Entity, With<BossHealthBar>>,
+) {
+    for (entity, mut boss, mut transform, mut visibility) in &mut bosses {
+        let Some(timer) = boss.dying.as_mut() else {
+            continue;
+        };
+
+        if timer.elapsed().is_zero() {
+            commands.entity(entity).remove::<Collider>();
+        }
+        timer.tick(time.delta());
+
+        // 点滅しながら縮む
+        let remaining = 1.0 - timer.fraction();
+        transform.scale = (BOSS_SIZE * remaining.max(0.05)).extend(1.0);
+        *visibility = if ((timer.elapsed_secs() * 10.0) as u32).is_multiple_of(2) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        if !timer.finished() {
+            continue;
+        }
+
+        **score += BOSS_BONUS_SCORE;
+        commands.entity(entity).despawn_recursive();
+        for entity in bricks.iter().chain(&hazards) {
+            commands.entity(entity).despawn();
+        }
+        for entity in &health_bars {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// HP の減少に合わせてセグメントを暗くする
+fn update_segments(
+    bosses: Query<(&Boss, &Children), Changed<Boss>>,
+    mut segments: Query<(&BossSegment, &mut Themed)>,
+) {
+    for (boss, children) in &bosses {
+        let remaining = boss.health as f32 / boss.max_health as f32;
+        let alive_segments = (remaining * BOSS_SEGMENTS as f32).ceil() as usize;
+        for child in children {
+            if let Ok((segment, mut themed)) = segments.get_mut(*child) {
+                themed.set_if_neq(Themed(if segment.0 < alive_segments {
+                    ThemeColor::BossSegment
+                } else {
+                    ThemeColor::BossBrokenSegment
+                }));
+            }
+        }
+    }
+}
+
+/// HP バーの残量を更新する
+fn update_health_bar(bosses: Query<&Boss>, mut fills: Query<&mut Node, With<BossHealthFill>>) {
+    let Ok(boss) = bosses.get_single() else {
+        return;
+    };
+    for mut node in &mut fills {
+        node.width = Val::Percent(boss.health as f32 / boss.max_health as f32 * 100.0);
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{reset_ball, Ball, Velocity},
+    boss,
+    difficulty::Difficulty,
+    editor,
+    generator::{self, LayoutSeed},
+    is_single_player, levels, save, spin,
+    versus::Side,
+    walls::TOP_WALL,
+    GameState, Level, Score,
+};
+
+// ブロックのサイズ（幅、高さ）
+pub const BRICK_SIZE: Vec2 = Vec2::new(100., 30.);
+// パドルとブロックの間のギャップ
+pub const GAP_BETWEEN_PADDLE_AND_BRICKS: f32 = 270.0;
+// ブロック間のギャップ
+pub const GAP_BETWEEN_BRICKS: f32 = 5.0;
+// 天井とブロックの間の最低限のギャップ
+pub const GAP_BETWEEN_BRICKS_AND_CEILING: f32 = 20.0;
+// ブロックと画面の両端のギャップ
+pub const GAP_BETWEEN_BRICKS_AND_SIDES: f32 = 20.0;
+
+/// ブロックの配置とレベルの進行を行うプラグイン
+///
+/// 次のレベルへの移行 ([`advance_level`]) は `victory.rs` が勝利演出のあとに実行する。
+pub struct BricksPlugin;
+
+impl Plugin for BricksPlugin {
+    fn build(&self, app: &mut App) {
+        // ブロックが壊されたときのイベントを追加 (ドロップや対戦モードの得点に使う)
+        app.add_event::<BrickDestroyed>()
+            // プレイ開始時 (起動直後とリスタート時) に盤面を初期化する
+            .add_systems(
+                OnEnter(GameState::Playing),
+                start_game.run_if(is_single_player),
+            );
+    }
+}
+
+// ブロックを示すコンポーネント
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Brick;
+
+// ブロックが壊されたときのイベント
+#[derive(Event)]
+pub struct BrickDestroyed {
+    pub position: Vec2,     // 壊されたブロックの位置
+    pub side: Option<Side>, // 対戦モードでのブロックの持ち主
+}
+
+/// スコア・レベル・盤面・ボールを初期状態に戻す関数
+///
+/// 起動時は `Startup` より前に実行されるため、ボールがまだ存在しないこともある。
+/// レベルエディタから試遊しているときは、エディタで配置したブロックを並べる。
+/// 保存した続きから遊ぶときは、ブロックを並べずに `save.rs` の復元に任せる。
+#[allow(clippy::too_many_arguments)]
+fn start_game(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut level: ResMut<Level>,
+    mut ball_query: Query<(&mut Transform, &mut Velocity, &mut spin::Spin), With<Ball>>,
+    playtest: Option<Res<editor::Playtest>>,
+    continuing: Option<Res<save::PendingContinue>>,
+    seed: Res<LayoutSeed>,
+    difficulty: Res<Difficulty>,
+) {
+    **score = 0;
+    **level = 1;
+    match (playtest, continuing) {
+        (_, Some(_)) => {}
+        (Some(playtest), None) => levels::spawn_layout(
+            &mut commands,
+            &levels::BrickGrid::new(TOP_WALL),
+            &playtest.0,
+        ),
+        (None, None) => spawn_bricks(&mut commands, TOP_WALL, seed.for_level(1)),
+    }
+    for (transform, velocity, spin) in &mut ball_query {
+        reset_ball(transform, velocity, spin, difficulty.ball_speed());
+    }
+}
+
+/// ブロックを行列に並べる。`ceiling` はブロックを配置できる領域の上端の y 座標
+///
+/// マス目の計算は `levels.rs` の `BrickGrid` が行う。
+/// シード値があれば `generator.rs` がシード値から作った配置に、なければすべてのマスに普通のブロックを置く。
+fn spawn_bricks(commands: &mut Commands, ceiling: f32, seed: Option<u64>) {
+    let grid = levels::BrickGrid::new(ceiling);
+    let layout = match seed {
+        Some(seed) => generator::generate(seed, &grid),
+        None => levels::LevelLayout::filled(&grid),
+    };
+    levels::spawn_layout(commands, &grid, &layout);
+}
+
+/// 次のレベルに進み、盤面とボールを初期化する関数
+///
+/// 盤面が空になったあと、`victory.rs` の勝利演出が終わったときに実行される。
+pub fn advance_level(
+    mut commands: Commands,
+    mut level: ResMut<Level>,
+    seed: Res<LayoutSeed>,
+    difficulty: Res<Difficulty>,
+    ball_query: Single<(&mut Transform, &mut Velocity, &mut spin::Spin), With<Ball>>,
+) {
+    **level += 1;
+    info!("level {}", **level);
+
+    // ボス戦のレベルではボスの分だけブロックを置ける領域を下げる
+    if boss::is_boss_level(**level) {
+        boss::spawn_boss(&mut commands, **level);
+        spawn_bricks(
+            &mut commands,
+            TOP_WALL - boss::BOSS_AREA_HEIGHT,
+            seed.for_level(**level),
+        );
+    } else {
+        spawn_bricks(&mut commands, TOP_WALL, seed.for_level(**level));
+    }
+
+    let (ball_transform, ball_velocity, ball_spin) = ball_query.into_inner();
+    reset_ball(
+        ball_transform,
+        ball_velocity,
+        ball_spin,
+        difficulty.ball_speed(),
+    );
+}
@@ -0,0 +1,157 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    render::{
+        camera::{ScalingMode, Viewport},
+        view::RenderLayers,
+    },
+    window::{PrimaryWindow, WindowResized},
+};
+use prelude_kit::MainCamera;
+
+use crate::{
+    theme::{ThemeColor, Themed},
+    walls::{BOTTOM_WALL, LEFT_WALL, RIGHT_WALL, TOP_WALL, WALL_THICKNESS},
+};
+
+// 仮想解像度 (ワールド座標で常にこの範囲を映す)。縦横比は 3:2
+const VIRTUAL_SIZE: Vec2 = Vec2::new(990.0, 660.0);
+// 仮想解像度の外側 (黒帯) の色
+const LETTERBOX_COLOR: Color = Color::BLACK;
+// 黒帯を塗るカメラだけが映すレイヤー (このレイヤーには何も置かない)
+const LETTERBOX_LAYER: usize = 1;
+// アリーナの背景の z 座標 (すべてのスプライトより奥)
+const BACKGROUND_Z: f32 = -10.0;
+
+// アリーナ (壁の外側まで) が仮想解像度に収まっていることをコンパイル時に確かめる
+const _: () = assert!(
+    RIGHT_WALL - LEFT_WALL + WALL_THICKNESS <= VIRTUAL_SIZE.x
+        && TOP_WALL - BOTTOM_WALL + WALL_THICKNESS <= VIRTUAL_SIZE.y
+);
+
+/// 3:2 の仮想解像度でアリーナを映し、ウィンドウの形が違うときは上下か左右に黒帯を付けるプラグイン
+///
+/// 壁やブロックの座標は固定のまま、ウィンドウの大きさが変わるたびにカメラのビューポートを
+/// 「ウィンドウに収まる最大の 3:2 の長方形」に合わせる。マウスカーソルの位置は
+/// [`ArenaCursor`] でワールド座標に変換できる。
+pub struct ArenaCameraPlugin;
+
+impl Plugin for ArenaCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_cameras)
+            .add_systems(Update, fit_viewport_to_window);
+    }
+}
+
+/// ウィンドウの大きさ (物理ピクセル) に収まる最大の 3:2 のビューポートを、ウィンドウの中央に置く
+fn letterbox(window_size: UVec2) -> Viewport {
+    let window_size = window_size.as_vec2();
+    let scale = (window_size / VIRTUAL_SIZE).min_element();
+    let size = (VIRTUAL_SIZE * scale).floor().max(Vec2::ONE);
+    let position = ((window_size - size) / 2.0).floor().max(Vec2::ZERO);
+    Viewport {
+        physical_position: position.as_uvec2(),
+        physical_size: size.as_uvec2(),
+        ..default()
+    }
+}
+
+/// アリーナを映すカメラ・黒帯を塗るカメラ・アリーナの背景を配置する
+fn spawn_cameras(mut commands: Commands, window: Option<Single<&Window, With<PrimaryWindow>>>) {
+    // 先にウィンドウ全体を黒で塗る
+    // 同じウィンドウに描画するカメラはテクスチャを共有し、クリアは最初のカメラでしか行われないため、
+    // 背景色はクリアではなくスプライトで塗る
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: -1,
+            clear_color: ClearColorConfig::Custom(LETTERBOX_COLOR),
+            ..default()
+        },
+        RenderLayers::layer(LETTERBOX_LAYER),
+    ));
+
+    let viewport = window
+        .map(|window| window.physical_size())
+        .filter(|size| size.cmpgt(UVec2::ZERO).all())
+        .map(letterbox);
+    commands.spawn((
+        Camera2d,
+        Camera {
+            viewport,
+            clear_color: ClearColorConfig::None,
+            ..default()
+        },
+        OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed {
+                width: VIRTUAL_SIZE.x,
+                height: VIRTUAL_SIZE.y,
+            },
+            ..OrthographicProjection::default_2d()
+        },
+        // UI もこのカメラのビューポート (黒帯の内側) に配置する
+        IsDefaultUiCamera,
+        // アリーナを映すカメラ (黒帯を塗るカメラと区別する)
+        MainCamera,
+    ));
+
+    // 仮想解像度の範囲 (黒帯の内側) をテーマの背景色で塗る
+    commands.spawn((
+        Sprite {
+            custom_size: Some(VIRTUAL_SIZE),
+            ..default()
+        },
+        Themed(ThemeColor::Background),
+        Transform::from_xyz(0.0, 0.0, BACKGROUND_Z),
+    ));
+}
+
+/// ウィンドウの大きさが変わったら、ビューポートを計算し直す
+fn fit_viewport_to_window(
+    mut resized_events: EventReader<WindowResized>,
+    windows: Query<&Window>,
+    mut camera: Single<&mut Camera, With<MainCamera>>,
+) {
+    // 1 フレームに複数回届いた場合は最後の大きさだけを使う
+    let Some(event) = resized_events.read().last() else {
+        return;
+    };
+    let Ok(window) = windows.get(event.window) else {
+        return;
+    };
+    // 最小化されたときは大きさが 0 になるので、元に戻るまでそのままにする
+    let size = window.physical_size();
+    if size.cmpgt(UVec2::ZERO).all() {
+        camera.viewport = Some(letterbox(size));
+    }
+}
+
+/// マウスカーソルの位置をアリーナのワールド座標に変換するシステムパラメータ
+#[derive(SystemParam)]
+pub struct ArenaCursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<MainCamera>>,
+}
+
+impl ArenaCursor<'_, '_> {
+    /// カーソルのワールド座標 (カーソルがウィンドウの外か黒帯の上にあるときは `None`)
+    ///
+    /// `Window::cursor_position` はウィンドウの左上が原点だが、`viewport_to_world_2d` は
+    /// ビューポートの左上を原点とする座標を受け取るため、黒帯の幅の分だけずらしてから変換する。
+    pub fn world_position(&self) -> Option<Vec2> {
+        let cursor = self.windows.get_single().ok()?.cursor_position()?;
+        self.to_world(cursor)
+    }
+
+    /// ウィンドウの左上を原点とする位置 (タッチの位置など) のワールド座標 (黒帯の上にあるときは `None`)
+    pub fn to_world(&self, position: Vec2) -> Option<Vec2> {
+        let (camera, camera_transform) = self.cameras.get_single().ok()?;
+        let viewport = camera.logical_viewport_rect()?;
+        if !viewport.contains(position) {
+            return None;
+        }
+        camera
+            .viewport_to_world_2d(camera_transform, position - viewport.min)
+            .ok()
+    }
+}
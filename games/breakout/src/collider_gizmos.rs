@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{check_for_collisions, Ball, Collider, CollisionEvent, BALL_DIAMETER},
+    theme::{Theme, ThemeColor},
+};
+
+// ギズモの表示・非表示を切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::F4;
+// ギズモの線の太さ (ピクセル)
+const LINE_WIDTH: f32 = 2.0;
+// 衝突の法線の矢印の長さ
+const NORMAL_LENGTH: f32 = 40.0;
+// 衝突した点に描く円の半径
+const CONTACT_RADIUS: f32 = 3.0;
+
+/// `F4` キーで当たり判定をギズモで描画するプラグイン
+///
+/// すべての [`Collider`] の `Aabb2d`・ボールの `BoundingCircle`・直前の衝突の法線を、
+/// `check_for_collisions` と同じ大きさで描く。スプライトの見た目と当たり判定のずれを確かめるのに使う。
+pub struct ColliderGizmosPlugin;
+
+impl Plugin for ColliderGizmosPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<ColliderGizmos>()
+            .init_resource::<LastCollision>()
+            .add_systems(Startup, configure_gizmos)
+            .add_systems(
+                FixedUpdate,
+                record_last_collision.after(check_for_collisions),
+            )
+            .add_systems(
+                Update,
+                (toggle_gizmos, draw_colliders.run_if(gizmos_enabled)).chain(),
+            );
+    }
+}
+
+/// 当たり判定のギズモのグループ (ほかのギズモとは別に表示・非表示や線の太さを設定する)
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct ColliderGizmos;
+
+/// 衝突した点と、衝突した面の法線
+#[derive(Debug, Clone, Copy)]
+struct CollisionContact {
+    point: Vec2,
+    normal: Vec2,
+}
+
+/// 直前の衝突 ([`CollisionEvent`] から記録する)
+#[derive(Resource, Default, Deref, DerefMut)]
+struct LastCollision(Option<CollisionContact>);
+
+/// ギズモの線の太さを設定し、最初は非表示にする
+fn configure_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<ColliderGizmos>();
+    config.enabled = false;
+    config.line_width = LINE_WIDTH;
+}
+
+/// 最後に受け取った衝突イベントの、衝突した点と反射の法線を記録する
+///
+/// ギズモを非表示にしている間も記録し、表示した直後から直前の衝突を描けるようにする。
+fn record_last_collision(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut last_collision: ResMut<LastCollision>,
+) {
+    if let Some(event) = collision_events.read().last() {
+        **last_collision = Some(CollisionContact {
+            point: event.point,
+            normal: event.side.normal(),
+        });
+    }
+}
+
+/// `F4` キーでギズモの表示・非表示を切り替える
+fn toggle_gizmos(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    if keyboard_input.just_pressed(TOGGLE_KEY) {
+        let (config, _) = config_store.config_mut::<ColliderGizmos>();
+        config.enabled = !config.enabled;
+    }
+}
+
+/// ギズモが表示されているかどうかを判定する条件関数
+fn gizmos_enabled(config_store: Res<GizmoConfigStore>) -> bool {
+    config_store.config::<ColliderGizmos>().0.enabled
+}
+
+/// コライダーとボールの当たり判定、直前の衝突の法線を描く
+fn draw_colliders(
+    mut gizmos: Gizmos<ColliderGizmos>,
+    theme: Res<Theme>,
+    colliders: Query<&Transform, With<Collider>>,
+    ball: Option<Single<&Transform, With<Ball>>>,
+    last_collision: Res<LastCollision>,
+) {
+    // 当たり判定は `Transform` の位置と拡大率から求めている (`check_for_collisions` と同じ)
+    let collider_color = theme.color(ThemeColor::ColliderBounds);
+    for transform in &colliders {
+        gizmos.rect_2d(
+            transform.translation.truncate(),
+            transform.scale.truncate(),
+            collider_color,
+        );
+    }
+
+    if let Some(ball) = ball {
+        gizmos.circle_2d(
+            ball.translation.truncate(),
+            BALL_DIAMETER / 2.,
+            theme.color(ThemeColor::BallBounds),
+        );
+    }
+
+    if let Some(contact) = **last_collision {
+        let normal_color = theme.color(ThemeColor::CollisionNormal);
+        gizmos.circle_2d(contact.point, CONTACT_RADIUS, normal_color);
+        gizmos.arrow_2d(
+            contact.point,
+            contact.point + contact.normal * NORMAL_LENGTH,
+            normal_color,
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    ball::check_for_collisions,
+    theme::{ThemeColor, Themed},
+};
+
+// ひびの段階の数 (テクスチャアトラスのフレーム数)
+const CRACK_FRAMES: u32 = 3;
+// 1 フレームの大きさ (ピクセル)。ブロックの大きさ (100 × 30) の 2 倍の解像度で描く
+const FRAME_SIZE: UVec2 = UVec2::new(200, 60);
+// ひびの枝の数と、1 本の枝を作る線分の数 (段階が進むごとに `SEGMENTS_PER_FRAME` 本ずつ伸びる)
+const BRANCHES: usize = 7;
+const SEGMENTS_PER_FRAME: usize = 2;
+// 線分の長さの範囲 (ピクセル) と、1 つの線分ごとに向きが曲がる最大の角度 (ラジアン)
+const SEGMENT_LENGTH: (f32, f32) = (8.0, 16.0);
+const MAX_TURN: f32 = 0.6;
+// ひびの線の太さ (半径、ピクセル)
+const LINE_RADIUS: f32 = 1.2;
+// ひびの形を決める乱数のシード値 (毎回同じ形のアトラスを作る)
+const CRACK_SEED: u64 = 306;
+// 1 回当たったあとに、ひびが次の段階へ広がるまでの時間 (秒)
+const SPREAD_SECONDS: f32 = 0.06;
+
+/// 何回か当てないと壊れないブロックに、ひびの絵を重ねるプラグイン
+///
+/// ひびの段階をフレームにしたテクスチャアトラスを起動時に作っておく。ブロックが壊れずに
+/// 当たったとき ([`BrickCracked`]) は、ブロックの子エンティティにひびのスプライトを付け、
+/// 受けたダメージの割合に合わせてアトラスのフレームを 1 つずつ進めて、ひびが広がって見えるようにする。
+/// ひびの色はテーマの [`ThemeColor::Crack`] で、ブロックの色の変化はそのまま残す。
+pub struct CracksPlugin;
+
+impl Plugin for CracksPlugin {
+    fn build(&self, app: &mut App) {
+        // ブロックが壊れずに当たったときのイベントを追加 (ひびの表示と効果音に使う)
+        app.add_event::<BrickCracked>()
+            .add_systems(Startup, setup_crack_atlas)
+            .add_systems(FixedUpdate, add_cracks.after(check_for_collisions))
+            .add_systems(Update, spread_cracks);
+    }
+}
+
+/// ブロックが壊れずに当たったときのイベント
+#[derive(Event)]
+pub struct BrickCracked {
+    pub brick: Entity,      // 当たったブロック
+    pub hits_taken: u32,    // これまでに当たった回数
+    pub hits_to_break: u32, // 壊れるまでに当てる回数
+}
+
+impl BrickCracked {
+    /// 受けたダメージに合わせたひびのフレーム (壊れる直前にいちばん大きなひびになる)
+    fn frame(&self) -> usize {
+        let last = CRACK_FRAMES - 1;
+        let steps = self.hits_to_break.saturating_sub(1).max(1);
+        (self.hits_taken * last / steps).min(last) as usize
+    }
+}
+
+/// ひびのテクスチャアトラス
+#[derive(Resource)]
+struct CrackAtlas {
+    image: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+}
+
+/// ブロックに重ねたひびのスプライト (ブロックの子エンティティ)
+#[derive(Component)]
+struct CrackOverlay {
+    target: usize,       // 広げ終えるフレーム
+    spread_timer: Timer, // 次のフレームへ進めるまでの時間
+}
+
+/// ひびの段階を横に並べたテクスチャアトラスを作る
+///
+/// 衝突した点 (フレームの中心) から枝分かれしたひびを乱数で折れ線として決め、
+/// フレームが進むごとに各枝の線分を `SEGMENTS_PER_FRAME` 本ずつ多く描く。
+/// 色は白で描き、スプライトの色 (テーマの色) を掛けて塗る。
+fn setup_crack_atlas(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: FRAME_SIZE.x * CRACK_FRAMES,
+            height: FRAME_SIZE.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+
+    let mut rng = StdRng::seed_from_u64(CRACK_SEED);
+    let center = FRAME_SIZE.as_vec2() / 2.0;
+    let segments = SEGMENTS_PER_FRAME * CRACK_FRAMES as usize;
+    let branches: Vec<Vec<Vec2>> = (0..BRANCHES)
+        .map(|i| {
+            // 枝の向きは円周を等分した向きから少しずらす
+            let mut angle =
+                (i as f32 + rng.gen_range(-0.3..0.3)) * std::f32::consts::TAU / BRANCHES as f32;
+            let mut point = center;
+            let mut points = vec![point];
+            for _ in 0..segments {
+                angle += rng.gen_range(-MAX_TURN..MAX_TURN);
+                point +=
+                    Vec2::from_angle(angle) * rng.gen_range(SEGMENT_LENGTH.0..SEGMENT_LENGTH.1);
+                points.push(point);
+            }
+            points
+        })
+        .collect();
+
+    for frame in 0..CRACK_FRAMES {
+        let drawn = SEGMENTS_PER_FRAME * (frame as usize + 1);
+        for points in &branches {
+            for segment in points.windows(2).take(drawn) {
+                draw_line(&mut image, frame, segment[0], segment[1]);
+            }
+        }
+    }
+
+    commands.insert_resource(CrackAtlas {
+        image: images.add(image),
+        layout: layouts.add(TextureAtlasLayout::from_grid(
+            FRAME_SIZE,
+            CRACK_FRAMES,
+            1,
+            None,
+            None,
+        )),
+    });
+}
+
+/// フレーム `frame` の中に、点 `from` から点 `to` まで太さのある線を白で描く (フレームの外は描かない)
+fn draw_line(image: &mut Image, frame: u32, from: Vec2, to: Vec2) {
+    let width = image.width();
+    let steps = (from.distance(to) * 2.0).ceil().max(1.0) as usize;
+    let radius = LINE_RADIUS.ceil() as i32;
+    for step in 0..=steps {
+        let point = from.lerp(to, step as f32 / steps as f32);
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let pixel = point + Vec2::new(dx as f32, dy as f32);
+                if pixel.distance(point) > LINE_RADIUS
+                    || pixel.x < 0.0
+                    || pixel.y < 0.0
+                    || pixel.x >= FRAME_SIZE.x as f32
+                    || pixel.y >= FRAME_SIZE.y as f32
+                {
+                    continue;
+                }
+                let x = frame * FRAME_SIZE.x + pixel.x as u32;
+                let index = ((pixel.y as u32 * width + x) * 4) as usize;
+                image.data[index..index + 4].copy_from_slice(&[255, 255, 255, 255]);
+            }
+        }
+    }
+}
+
+/// 当たったブロックにひびを重ねる (すでにひびがあれば、広げる先のフレームを進める)
+///
+/// どのブロックも同じアトラスを使うので、向きを乱数で反転させて同じ形に見えないようにする。
+fn add_cracks(
+    mut commands: Commands,
+    mut cracked_events: EventReader<BrickCracked>,
+    atlas: Res<CrackAtlas>,
+    children: Query<&Children>,
+    mut overlays: Query<&mut CrackOverlay>,
+) {
+    let mut rng = rand::thread_rng();
+    for event in cracked_events.read() {
+        let existing = children
+            .get(event.brick)
+            .ok()
+            .and_then(|children| children.iter().find(|&&child| overlays.contains(child)));
+        if let Some(&overlay) = existing {
+            if let Ok(mut overlay) = overlays.get_mut(overlay) {
+                overlay.target = overlay.target.max(event.frame());
+            }
+            continue;
+        }
+        let Some(mut brick) = commands.get_entity(event.brick) else {
+            continue;
+        };
+        brick.with_children(|parent| {
+            parent.spawn((
+                Sprite {
+                    image: atlas.image.clone(),
+                    texture_atlas: Some(TextureAtlas {
+                        layout: atlas.layout.clone(),
+                        index: 0,
+                    }),
+                    // 親のブロックの `scale` がブロックの大きさなので、1 × 1 で描くとブロックに重なる
+                    custom_size: Some(Vec2::ONE),
+                    flip_x: rng.gen(),
+                    flip_y: rng.gen(),
+                    ..default()
+                },
+                Themed(ThemeColor::Crack),
+                // ブロックより少し手前に描く
+                Transform::from_xyz(0.0, 0.0, 0.1),
+                CrackOverlay {
+                    target: event.frame(),
+                    spread_timer: Timer::from_seconds(SPREAD_SECONDS, TimerMode::Repeating),
+                },
+            ));
+        });
+    }
+}
+
+/// ひびのフレームを `SPREAD_SECONDS` ごとに 1 つずつ進め、広げる先のフレームまで広げる
+fn spread_cracks(time: Res<Time>, mut overlays: Query<(&mut Sprite, &mut CrackOverlay)>) {
+    for (mut sprite, mut overlay) in &mut overlays {
+        let Some(index) = sprite.texture_atlas.as_ref().map(|atlas| atlas.index) else {
+            continue;
+        };
+        if index >= overlay.target {
+            continue;
+        }
+        if overlay.spread_timer.tick(time.delta()).just_finished() {
+            if let Some(atlas) = sprite.texture_atlas.as_mut() {
+                atlas.index = index + 1;
+            }
+        }
+    }
+}
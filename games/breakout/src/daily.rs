@@ -0,0 +1,305 @@
+use bevy::{prelude::*, utils::SystemTime};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    attract::is_attract_mode,
+    ball::{Ball, SpeedScale},
+    difficulty::{reset_paddle_width, Difficulty, PaddleWidth},
+    game_over::{reset_lives, spawn_game_over_screen, GameOverScreen, Lives, LIVES_FONT_SIZE},
+    generator::LayoutSeed,
+    locale::{Localized, LocalizedFont, Strings},
+    records::Records,
+    theme::{ThemeColor, Themed},
+    ui::{SCOREBOARD_FONT_SIZE, SCOREBOARD_TEXT_PADDING},
+    GameMode, GameState, Score,
+};
+
+// デイリーチャレンジの日付を指定する起動引数 (`--daily` だけのときは今日の日付)
+const DAILY_ARG: &str = "--daily";
+// 日付からシード値を作るときに混ぜる値 (`--seed` に日付と同じ数を指定しても同じ配置にならないようにする)
+const DAILY_SEED_SALT: u64 = 0xDA11_C4A1_1E46_E5ED;
+// 1 日の秒数
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+// 「小さいパドル」でパドルの幅に掛ける割合
+const SMALL_PADDLE_SCALE: f32 = 0.7;
+// 「速いボール」でボールの移動量に掛ける倍率
+const FAST_BALL_SCALE: f32 = 1.3;
+// 「少ない残機」での残機
+const FEWER_LIVES: u32 = 1;
+// デイリーチャレンジの表示のフォントサイズ
+const DAILY_FONT_SIZE: f32 = 16.0;
+
+/// `--daily` で起動すると、日付から決まるブロックの配置とモディファイアで遊ぶデイリーチャレンジにするプラグイン
+///
+/// シード値とモディファイア (小さいパドル・速いボール・少ない残機) は UTC の日付だけから決めるので、
+/// 同じ日ならだれが遊んでも同じ盤面・同じ条件になる。`--daily=YYYY-MM-DD` で過去の日付のチャレンジも遊べる。
+/// 条件をそろえるため難易度は Normal に固定し、シード値の入力や難易度の切り替えはできない。
+/// 得点はハイスコアとは別に、日付ごとの最高得点として [`Records`] に記録する。
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        if *app.world().resource::<GameMode>() != GameMode::Daily {
+            return;
+        }
+        let challenge = DailyChallenge::from_args();
+        info!(
+            "daily challenge {} (seed {}, modifiers {:?})",
+            challenge.date, challenge.seed, challenge.modifiers
+        );
+
+        // `GeneratorPlugin` と `DifficultyPlugin` が起動引数から決めた値を上書きする
+        app.insert_resource(LayoutSeed(Some(challenge.seed)))
+            .insert_resource(Difficulty::Normal)
+            .insert_resource(challenge)
+            .add_systems(Startup, spawn_daily_ui)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                apply_modifiers.after(reset_paddle_width).after(reset_lives),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                spawn_daily_result.after(spawn_game_over_screen),
+            )
+            .add_systems(
+                Update,
+                (
+                    speed_up_balls,
+                    update_daily_best.run_if(not(is_attract_mode)),
+                    update_daily_ui,
+                ),
+            );
+    }
+}
+
+/// デイリーチャレンジで遊んでいるかどうかを判定する条件関数
+pub fn is_daily(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::Daily
+}
+
+/// 難易度に重ねて遊びにくくする条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Modifier {
+    SmallPaddle, // パドルの幅が狭い
+    FastBall,    // ボールが速い
+    FewerLives,  // 残機が少ない
+}
+
+impl Modifier {
+    const ALL: [Modifier; 3] = [
+        Modifier::SmallPaddle,
+        Modifier::FastBall,
+        Modifier::FewerLives,
+    ];
+
+    /// 文字列テーブルのモディファイアの名前のキー
+    fn name_key(self) -> &'static str {
+        match self {
+            Modifier::SmallPaddle => "modifier_small_paddle",
+            Modifier::FastBall => "modifier_fast_ball",
+            Modifier::FewerLives => "modifier_fewer_lives",
+        }
+    }
+}
+
+/// その日のデイリーチャレンジ
+#[derive(Resource, Debug)]
+pub struct DailyChallenge {
+    pub date: String,         // 日付 (`YYYY-MM-DD`、UTC、最高得点のキー)
+    seed: u64,                // ブロックの配置のシード値
+    modifiers: Vec<Modifier>, // 適用するモディファイア (少なくとも 1 つ)
+}
+
+impl DailyChallenge {
+    /// 起動引数 (`--daily=YYYY-MM-DD`) の日付か、今日の日付のチャレンジを作る
+    fn from_args() -> DailyChallenge {
+        let days = std::env::args()
+            .find_map(|arg| {
+                let value = arg.strip_prefix(DAILY_ARG)?.strip_prefix('=')?;
+                let days = parse_date(value);
+                if days.is_none() {
+                    warn!("ignoring invalid daily date {value:?}, expected YYYY-MM-DD");
+                }
+                days
+            })
+            .unwrap_or_else(today);
+        DailyChallenge::for_day(days)
+    }
+
+    /// 1970-01-01 からの日数で表した日付のチャレンジを作る
+    ///
+    /// 日付だけから作った乱数で、シード値とモディファイアを決める。
+    fn for_day(days: i64) -> DailyChallenge {
+        let mut rng = StdRng::seed_from_u64(days as u64 ^ DAILY_SEED_SALT);
+        let seed = rng.gen();
+        let mut modifiers: Vec<Modifier> = Modifier::ALL
+            .into_iter()
+            .filter(|_| rng.gen_bool(0.5))
+            .collect();
+        if modifiers.is_empty() {
+            modifiers.push(Modifier::ALL[rng.gen_range(0..Modifier::ALL.len())]);
+        }
+
+        let (year, month, day) = civil_from_days(days);
+        DailyChallenge {
+            date: format!("{year:04}-{month:02}-{day:02}"),
+            seed,
+            modifiers,
+        }
+    }
+
+    fn has(&self, modifier: Modifier) -> bool {
+        self.modifiers.contains(&modifier)
+    }
+}
+
+/// 今日 (UTC) の 1970-01-01 からの日数
+fn today() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| (elapsed.as_secs() / SECONDS_PER_DAY) as i64)
+}
+
+/// `YYYY-MM-DD` 形式の日付を 1970-01-01 からの日数にする (存在しない日付なら `None`)
+fn parse_date(text: &str) -> Option<i64> {
+    let mut parts = text.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // 2 月 30 日などは次の月に繰り上がるので、日付に戻して確かめる
+    let days = days_from_civil(year, month, day);
+    (civil_from_days(days) == (year, month, day)).then_some(days)
+}
+
+/// グレゴリオ暦の日付を 1970-01-01 からの日数にする (3 月始まりの年として数える)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = (i64::from(month) + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// 1970-01-01 からの日数をグレゴリオ暦の日付 (年・月・日) にする
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// デイリーチャレンジの表示を示すコンポーネント
+#[derive(Component)]
+struct DailyUi;
+
+/// プレイを始めるたびに、難易度で決まる値に戻したパドルの幅と残機にモディファイアを適用する
+fn apply_modifiers(
+    daily: Res<DailyChallenge>,
+    mut width: ResMut<PaddleWidth>,
+    mut lives: ResMut<Lives>,
+) {
+    if daily.has(Modifier::SmallPaddle) {
+        **width *= SMALL_PADDLE_SCALE;
+    }
+    if daily.has(Modifier::FewerLives) {
+        **lives = (**lives).min(FEWER_LIVES);
+    }
+}
+
+/// 「速いボール」のときは、配置されたボールに移動量の倍率を付ける
+fn speed_up_balls(
+    mut commands: Commands,
+    daily: Res<DailyChallenge>,
+    balls: Query<Entity, Added<Ball>>,
+) {
+    if !daily.has(Modifier::FastBall) {
+        return;
+    }
+    for entity in &balls {
+        commands.entity(entity).insert(SpeedScale(FAST_BALL_SCALE));
+    }
+}
+
+/// 現在のスコアがその日の最高得点を超えたら更新する
+fn update_daily_best(daily: Res<DailyChallenge>, score: Res<Score>, mut records: ResMut<Records>) {
+    if **score > records.daily_best(&daily.date).unwrap_or(0) {
+        records.submit_daily_score(&daily.date, **score);
+    }
+}
+
+/// シード値の表示の下に、日付とモディファイアの表示を配置する
+fn spawn_daily_ui(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: DAILY_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Hint),
+        LocalizedFont,
+        DailyUi,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(SCOREBOARD_FONT_SIZE + 2.0 * LIVES_FONT_SIZE + DAILY_FONT_SIZE + 26.0),
+            left: SCOREBOARD_TEXT_PADDING,
+            ..default()
+        },
+    ));
+}
+
+/// 日付とモディファイアの表示を更新する (言語を切り替えたときに作り直す)
+fn update_daily_ui(
+    daily: Res<DailyChallenge>,
+    strings: Strings,
+    mut text: Single<&mut Text, With<DailyUi>>,
+) {
+    if !strings.is_changed() && !text.0.is_empty() {
+        return;
+    }
+    let modifiers = daily
+        .modifiers
+        .iter()
+        .map(|modifier| strings.get(modifier.name_key()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    text.0 = strings.format(
+        "daily_label",
+        &[("date", &daily.date), ("modifiers", &modifiers)],
+    );
+}
+
+/// ゲームオーバー画面に、その日の最高得点を追加する
+fn spawn_daily_result(
+    mut commands: Commands,
+    daily: Res<DailyChallenge>,
+    records: Res<Records>,
+    screen: Single<Entity, With<GameOverScreen>>,
+) {
+    commands.entity(*screen).with_child((
+        Text::default(),
+        TextFont {
+            font_size: DAILY_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::NewBest),
+        Localized::new("daily_result")
+            .with_arg("date", &daily.date)
+            .with_arg("best", records.daily_best(&daily.date).unwrap_or(0)),
+    ));
+}
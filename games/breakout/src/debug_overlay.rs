@@ -0,0 +1,158 @@
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+use crate::{
+    ball::{Ball, Velocity},
+    camera::ArenaCursor,
+    theme::{ThemeColor, Themed},
+};
+
+// オーバーレイの表示・非表示を切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+// オーバーレイのフォントサイズ
+const OVERLAY_FONT_SIZE: f32 = 14.0;
+// 固定ステップの実行回数を平均する時間 (秒)
+const TICK_RATE_WINDOW_SECS: f32 = 1.0;
+
+/// `F3` キーで FPS・固定ステップの実行頻度・エンティティ数・ボールの速さ・
+/// 1 ステップあたりの衝突判定の回数を表示するプラグイン
+///
+/// 衝突判定の回数は `check_for_collisions` が [`CollisionChecks`] に書き込む。
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.init_resource::<CollisionChecks>()
+            .init_resource::<TickRate>()
+            .add_systems(Startup, spawn_overlay)
+            .add_systems(FixedFirst, count_fixed_tick)
+            .add_systems(
+                Update,
+                (
+                    toggle_overlay,
+                    measure_tick_rate,
+                    update_overlay.run_if(overlay_visible),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// 直前の固定ステップで、ボールと衝突しうるエンティティを判定した回数
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct CollisionChecks(usize);
+
+/// 実時間 1 秒あたりの固定ステップの実行回数
+#[derive(Resource, Default)]
+struct TickRate {
+    ticks: u32,   // 計測中の区間で実行された固定ステップの数
+    elapsed: f32, // 計測中の区間の長さ (秒)
+    rate: f32,    // 直前の区間の実行頻度 (回/秒)
+}
+
+/// オーバーレイのルート UI を示すコンポーネント
+#[derive(Component)]
+struct DebugOverlay;
+
+/// オーバーレイのテキストを示すコンポーネント
+#[derive(Component)]
+struct DebugOverlayText;
+
+/// 画面左側にオーバーレイを配置する (最初は非表示)
+fn spawn_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(110.0),
+                left: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(4.0)),
+                ..default()
+            },
+            Themed(ThemeColor::Overlay),
+            Visibility::Hidden,
+            // 他の UI より手前に表示する
+            GlobalZIndex(i32::MAX),
+            DebugOverlay,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font_size: OVERLAY_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Text),
+            DebugOverlayText,
+        ));
+}
+
+/// `F3` キーでオーバーレイの表示・非表示を切り替える
+fn toggle_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlay: Single<&mut Visibility, With<DebugOverlay>>,
+) {
+    if keyboard_input.just_pressed(TOGGLE_KEY) {
+        overlay.toggle_visible_hidden();
+    }
+}
+
+/// オーバーレイが表示されているかどうかを判定する条件関数
+fn overlay_visible(overlay: Single<&Visibility, With<DebugOverlay>>) -> bool {
+    **overlay != Visibility::Hidden
+}
+
+/// 固定ステップが 1 回実行されるたびに数える
+fn count_fixed_tick(mut tick_rate: ResMut<TickRate>) {
+    tick_rate.ticks += 1;
+}
+
+/// 一定時間ごとに固定ステップの実行頻度を求める
+///
+/// 仮想時間が止まっている間 (ゲームオーバー中など) は固定ステップも止まるので、実時間で計る。
+fn measure_tick_rate(mut tick_rate: ResMut<TickRate>, time: Res<Time<Real>>) {
+    tick_rate.elapsed += time.delta_secs();
+    if tick_rate.elapsed >= TICK_RATE_WINDOW_SECS {
+        tick_rate.rate = tick_rate.ticks as f32 / tick_rate.elapsed;
+        tick_rate.ticks = 0;
+        tick_rate.elapsed = 0.0;
+    }
+}
+
+/// 計測値をオーバーレイに表示する
+#[allow(clippy::too_many_arguments)]
+fn update_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    tick_rate: Res<TickRate>,
+    fixed_time: Res<Time<Fixed>>,
+    collision_checks: Res<CollisionChecks>,
+    entities: Query<()>,
+    ball: Option<Single<&Velocity, With<Ball>>>,
+    cursor: ArenaCursor,
+    mut overlay_text: Single<&mut Text, With<DebugOverlayText>>,
+) {
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+    let ball_speed = ball.map_or(0.0, |velocity| velocity.length());
+    let cursor = cursor.world_position().map_or("-".to_string(), |position| {
+        format!("({:.0}, {:.0})", position.x, position.y)
+    });
+    overlay_text.0 = format!(
+        "FPS {fps:.0}\n\
+         fixed ticks {:.1}/s (target {:.0})\n\
+         entities {}\n\
+         ball speed {ball_speed:.1}\n\
+         collision checks/tick {}\n\
+         cursor {cursor}",
+        tick_rate.rate,
+        1.0 / fixed_time.timestep().as_secs_f64(),
+        entities.iter().count(),
+        **collision_checks,
+    );
+}
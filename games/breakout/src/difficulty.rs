@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::BALL_SPEED,
+    daily::is_daily,
+    game_over::{spawn_game_over_screen, GameOverScreen},
+    is_single_player,
+    locale::{LocalizedFont, Strings},
+    paddle::{Paddle, PADDLE_SIZE},
+    theme::{ThemeColor, Themed},
+    victory::LevelCleared,
+    GameState,
+};
+
+// 難易度を指定する起動引数 (`--difficulty=easy` / `normal` / `hard`)
+const DIFFICULTY_ARG: &str = "--difficulty=";
+// ゲームオーバー画面で難易度を切り替えるキー
+const CYCLE_KEY: KeyCode = KeyCode::Tab;
+// Hard でレベルをクリアするたびにパドルの幅に掛ける割合
+const HARD_PADDLE_SHRINK: f32 = 0.9;
+// Hard で縮めるパドルの幅の下限
+const HARD_MIN_PADDLE_WIDTH: f32 = 60.0;
+// ゲームオーバー画面の難易度の表示のフォントサイズ
+const DIFFICULTY_FONT_SIZE: f32 = 18.0;
+
+/// 難易度 (Easy / Normal / Hard) を追加するプラグイン
+///
+/// 難易度によって、ボールの速さ・パドルの幅・残機・ドロップの出やすさが変わる。
+/// 各システムは [`Difficulty`] リソースを見て値を決める。
+/// 難易度は `--difficulty=<easy|normal|hard>` で指定するか、ゲームオーバー画面の `Tab` キーで切り替え、
+/// 次のプレイの開始時 (`OnEnter(GameState::Playing)`) から反映される。
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        let difficulty = Difficulty::from_args();
+        app.insert_resource(difficulty)
+            .insert_resource(PaddleWidth(difficulty.paddle_width()))
+            .add_systems(
+                OnEnter(GameState::Playing),
+                reset_paddle_width.run_if(is_single_player),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                spawn_difficulty_ui
+                    .after(spawn_game_over_screen)
+                    .run_if(not(is_daily)),
+            )
+            .add_systems(
+                Update,
+                (
+                    (shrink_paddle, apply_paddle_width)
+                        .chain()
+                        .run_if(is_single_player),
+                    // デイリーチャレンジでは、だれでも同じ条件で遊べるように難易度を切り替えさせない
+                    (cycle_difficulty, update_difficulty_ui)
+                        .chain()
+                        .run_if(in_state(GameState::GameOver))
+                        .run_if(not(is_daily)),
+                ),
+            );
+    }
+}
+
+/// ゲームの難易度
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// 起動引数から難易度を決める (指定がないか知らない値なら `Normal`)
+    fn from_args() -> Difficulty {
+        let Some(value) =
+            std::env::args().find_map(|arg| arg.strip_prefix(DIFFICULTY_ARG).map(str::to_string))
+        else {
+            return Difficulty::default();
+        };
+        match value.as_str() {
+            "easy" => Difficulty::Easy,
+            "normal" => Difficulty::Normal,
+            "hard" => Difficulty::Hard,
+            _ => {
+                warn!("unknown difficulty {value:?}, using normal");
+                Difficulty::Normal
+            }
+        }
+    }
+
+    /// `Tab` キーで切り替えたときの次の難易度
+    fn next(self) -> Difficulty {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    /// 文字列テーブルの難易度の名前のキー
+    fn name_key(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty_easy",
+            Difficulty::Normal => "difficulty_normal",
+            Difficulty::Hard => "difficulty_hard",
+        }
+    }
+
+    /// サーブしたときのボールの速さ
+    pub fn ball_speed(self) -> f32 {
+        match self {
+            Difficulty::Easy => BALL_SPEED * 0.8,
+            Difficulty::Normal => BALL_SPEED,
+            Difficulty::Hard => BALL_SPEED * 1.25,
+        }
+    }
+
+    /// プレイ開始時のパドルの幅
+    pub fn paddle_width(self) -> f32 {
+        match self {
+            Difficulty::Easy => PADDLE_SIZE.x * 1.3,
+            Difficulty::Normal => PADDLE_SIZE.x,
+            Difficulty::Hard => PADDLE_SIZE.x * 0.85,
+        }
+    }
+
+    /// プレイ開始時の残機
+    pub fn starting_lives(self) -> u32 {
+        match self {
+            Difficulty::Easy => 5,
+            Difficulty::Normal => 3,
+            Difficulty::Hard => 2,
+        }
+    }
+
+    /// ドロップの出やすさ (`Normal` を 1 とした倍率)
+    pub fn drop_rate(self) -> f64 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.6,
+        }
+    }
+}
+
+/// 1 人用のルールでの現在のパドルの幅 (Hard ではレベルをクリアするたびに縮む)
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct PaddleWidth(f32);
+
+/// ゲームオーバー画面の難易度の表示を示すコンポーネント
+#[derive(Component)]
+struct DifficultyUi;
+
+/// パドルの幅を難易度の初期値に戻す
+pub fn reset_paddle_width(difficulty: Res<Difficulty>, mut width: ResMut<PaddleWidth>) {
+    **width = difficulty.paddle_width();
+}
+
+/// Hard ではレベルをクリアするたびにパドルを少し縮める
+fn shrink_paddle(
+    mut cleared_events: EventReader<LevelCleared>,
+    difficulty: Res<Difficulty>,
+    mut width: ResMut<PaddleWidth>,
+) {
+    for cleared in cleared_events.read() {
+        if *difficulty == Difficulty::Hard && !cleared.is_final_level {
+            **width = (**width * HARD_PADDLE_SHRINK).max(HARD_MIN_PADDLE_WIDTH);
+            info!("paddle shrunk to {:.0}", **width);
+        }
+    }
+}
+
+/// パドル (2P のパドルも含む) の幅を現在の幅に合わせる
+///
+/// 当たり判定は `Transform` の `scale` を大きさとして使うので、見た目と一緒に当たり判定も変わる。
+fn apply_paddle_width(width: Res<PaddleWidth>, mut paddles: Query<&mut Transform, With<Paddle>>) {
+    for mut transform in &mut paddles {
+        if transform.scale.x != **width {
+            transform.scale.x = **width;
+        }
+    }
+}
+
+/// ゲームオーバー画面に難易度の表示を追加する
+fn spawn_difficulty_ui(mut commands: Commands, screen: Single<Entity, With<GameOverScreen>>) {
+    commands.entity(*screen).with_child((
+        Text::default(),
+        TextFont {
+            font_size: DIFFICULTY_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Text),
+        LocalizedFont,
+        DifficultyUi,
+    ));
+}
+
+/// `Tab` キーで次のプレイの難易度を切り替える
+fn cycle_difficulty(keyboard_input: Res<ButtonInput<KeyCode>>, mut difficulty: ResMut<Difficulty>) {
+    if keyboard_input.just_pressed(CYCLE_KEY) {
+        *difficulty = difficulty.next();
+        info!("difficulty {:?}", *difficulty);
+    }
+}
+
+/// ゲームオーバー画面の難易度の表示を更新する
+fn update_difficulty_ui(
+    difficulty: Res<Difficulty>,
+    strings: Strings,
+    mut text: Single<&mut Text, With<DifficultyUi>>,
+) {
+    if !difficulty.is_changed() && !strings.is_changed() && !text.is_added() {
+        return;
+    }
+    let name = strings.get(difficulty.name_key());
+    text.0 = strings.format("difficulty_select", &[("difficulty", &name)]);
+}
@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use bevy::{
+    audio::Pitch,
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    ball::{apply_velocity, check_for_collisions, Acceleration, Velocity},
+    bricks::BrickDestroyed,
+    difficulty::Difficulty,
+    glow::{Glow, POWER_UP_GLOW},
+    is_single_player,
+    paddle::Paddle,
+    theme::{ThemeColor, Themed},
+    walls::{BOTTOM_WALL, WALL_THICKNESS},
+    GameState, Score,
+};
+
+// ブロックが壊れたときにコイン・ジェム・フォースフィールド・磁石が落ちる確率
+const COIN_DROP_CHANCE: f64 = 0.25;
+const GEM_DROP_CHANCE: f64 = 0.08;
+const FORCE_FIELD_DROP_CHANCE: f64 = 0.03;
+const MAGNET_DROP_CHANCE: f64 = 0.03;
+// ドロップが出現したときの上向きの初速
+const DROP_POP_SPEED: f32 = 150.0;
+// ドロップに掛かる重力加速度
+const DROP_GRAVITY: f32 = -600.0;
+// ドロップの直径
+const DROP_DIAMETER: f32 = 18.0;
+// フォースフィールドのドロップの大きさ (床に張るバリアを小さくした形)
+const FORCE_FIELD_DROP_SIZE: Vec2 = Vec2::new(30.0, 10.0);
+// 磁石のドロップ (輪の形) の内側の半径
+const MAGNET_DROP_INNER_RADIUS: f32 = 5.0;
+
+// 拾ったときの効果音の高さと長さ
+const COIN_PICKUP_FREQUENCY: f32 = 1320.0;
+const GEM_PICKUP_FREQUENCY: f32 = 1760.0;
+const FORCE_FIELD_PICKUP_FREQUENCY: f32 = 880.0;
+const MAGNET_PICKUP_FREQUENCY: f32 = 660.0;
+const PICKUP_SOUND_DURATION: Duration = Duration::from_millis(80);
+
+/// ブロックから落ちるコイン・ジェム・フォースフィールド・磁石を追加するプラグイン
+///
+/// 拾ったときは得点を加え、[`DropCollected`] を送る
+/// (フォースフィールドの効果は `force_field.rs`、磁石の効果は `magnet.rs` が発動する)。
+pub struct DropsPlugin;
+
+impl Plugin for DropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DropRng(StdRng::from_entropy()))
+            .add_event::<DropCollected>()
+            .add_systems(Startup, setup_drop_assets)
+            .add_systems(
+                FixedUpdate,
+                (
+                    apply_gravity.before(apply_velocity),
+                    (spawn_drops, collect_drops, despawn_fallen_drops)
+                        .chain()
+                        .after(check_for_collisions),
+                )
+                    .run_if(is_single_player),
+            );
+    }
+}
+
+/// 落下物の種類
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drop {
+    Coin,       // 少しだけ得点が入る
+    Gem,        // 多めに得点が入る
+    ForceField, // 得点の代わりに、ボールを 1 回だけ跳ね返すバリアを床に張る
+    Magnet,     // 得点の代わりに、しばらくの間パドルが落ちてくるボールを引き寄せる
+}
+
+impl Drop {
+    /// 拾ったときに入る得点
+    fn score(self) -> usize {
+        match self {
+            Drop::Coin => 5,
+            Drop::Gem => 20,
+            Drop::ForceField | Drop::Magnet => 0,
+        }
+    }
+}
+
+/// ドロップを拾ったときのイベント
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DropCollected(pub Drop);
+
+/// 重力を受けるエンティティの加速度 (y 方向)
+#[derive(Component, Deref)]
+pub struct Gravity(f32);
+
+/// ドロップの抽選に使う乱数生成器
+#[derive(Resource, Deref, DerefMut)]
+struct DropRng(StdRng);
+
+/// ドロップの見た目と効果音のハンドル
+#[derive(Resource)]
+struct DropAssets {
+    coin_mesh: Handle<Mesh>,
+    coin_material: Handle<ColorMaterial>,
+    coin_sound: Handle<Pitch>,
+    gem_mesh: Handle<Mesh>,
+    gem_material: Handle<ColorMaterial>,
+    gem_sound: Handle<Pitch>,
+    force_field_mesh: Handle<Mesh>,
+    force_field_material: Handle<ColorMaterial>,
+    force_field_sound: Handle<Pitch>,
+    magnet_mesh: Handle<Mesh>,
+    magnet_material: Handle<ColorMaterial>,
+    magnet_sound: Handle<Pitch>,
+}
+
+/// ドロップの見た目と効果音を用意する
+fn setup_drop_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    commands.insert_resource(DropAssets {
+        coin_mesh: meshes.add(Circle::new(DROP_DIAMETER / 2.0)),
+        // マテリアルの色は、ドロップに付けた `Themed` からテーマの色が設定される
+        coin_material: materials.add(ColorMaterial::default()),
+        coin_sound: pitches.add(Pitch::new(COIN_PICKUP_FREQUENCY, PICKUP_SOUND_DURATION)),
+        gem_mesh: meshes.add(Rhombus::new(DROP_DIAMETER, DROP_DIAMETER * 1.4)),
+        gem_material: materials.add(ColorMaterial::default()),
+        gem_sound: pitches.add(Pitch::new(GEM_PICKUP_FREQUENCY, PICKUP_SOUND_DURATION)),
+        force_field_mesh: meshes.add(Rectangle::from_size(FORCE_FIELD_DROP_SIZE)),
+        force_field_material: materials.add(ColorMaterial::default()),
+        force_field_sound: pitches.add(Pitch::new(
+            FORCE_FIELD_PICKUP_FREQUENCY,
+            PICKUP_SOUND_DURATION,
+        )),
+        magnet_mesh: meshes.add(Annulus::new(MAGNET_DROP_INNER_RADIUS, DROP_DIAMETER / 2.0)),
+        magnet_material: materials.add(ColorMaterial::default()),
+        magnet_sound: pitches.add(Pitch::new(MAGNET_PICKUP_FREQUENCY, PICKUP_SOUND_DURATION)),
+    });
+}
+
+/// 重力を加速度に加える (速度と位置の更新は `apply_velocity` が行う)
+fn apply_gravity(mut query: Query<(&mut Acceleration, &Gravity)>) {
+    for (mut acceleration, gravity) in &mut query {
+        acceleration.y += **gravity;
+    }
+}
+
+/// 壊されたブロックの位置から確率でドロップを出現させる
+fn spawn_drops(
+    mut commands: Commands,
+    mut events: EventReader<BrickDestroyed>,
+    mut rng: ResMut<DropRng>,
+    assets: Res<DropAssets>,
+    difficulty: Res<Difficulty>,
+) {
+    for event in events.read() {
+        // 乱数を難易度の倍率で割り、各ドロップの確率をまとめて倍率分だけ変える
+        let roll = rng.gen::<f64>() / difficulty.drop_rate();
+        let drop = if roll < FORCE_FIELD_DROP_CHANCE {
+            Drop::ForceField
+        } else if roll < FORCE_FIELD_DROP_CHANCE + MAGNET_DROP_CHANCE {
+            Drop::Magnet
+        } else if roll < FORCE_FIELD_DROP_CHANCE + MAGNET_DROP_CHANCE + GEM_DROP_CHANCE {
+            Drop::Gem
+        } else if roll
+            < FORCE_FIELD_DROP_CHANCE + MAGNET_DROP_CHANCE + GEM_DROP_CHANCE + COIN_DROP_CHANCE
+        {
+            Drop::Coin
+        } else {
+            continue;
+        };
+
+        let (mesh, material, color) = match drop {
+            Drop::Coin => (
+                assets.coin_mesh.clone(),
+                assets.coin_material.clone(),
+                ThemeColor::Coin,
+            ),
+            Drop::Gem => (
+                assets.gem_mesh.clone(),
+                assets.gem_material.clone(),
+                ThemeColor::Gem,
+            ),
+            Drop::ForceField => (
+                assets.force_field_mesh.clone(),
+                assets.force_field_material.clone(),
+                ThemeColor::ForceField,
+            ),
+            Drop::Magnet => (
+                assets.magnet_mesh.clone(),
+                assets.magnet_material.clone(),
+                ThemeColor::Magnet,
+            ),
+        };
+        commands.spawn((
+            Mesh2d(mesh),
+            MeshMaterial2d(material),
+            Themed(color),
+            Glow(POWER_UP_GLOW),
+            Transform::from_translation(event.position.extend(2.0)),
+            drop,
+            Velocity(Vec2::new(0.0, DROP_POP_SPEED)),
+            Acceleration::default(),
+            Gravity(DROP_GRAVITY),
+            StateScoped(GameState::Playing),
+        ));
+    }
+}
+
+/// パドルに重なったドロップを拾い、得点と効果音を与えて [`DropCollected`] を送る
+///
+/// ボールの衝突とは別に判定するため、ドロップはパドルを押し返したりしない。
+fn collect_drops(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut collected_events: EventWriter<DropCollected>,
+    assets: Res<DropAssets>,
+    drops: Query<(Entity, &Transform, &Drop)>,
+    paddles: Query<&Transform, With<Paddle>>,
+) {
+    for (entity, drop_transform, drop) in &drops {
+        let drop_circle =
+            BoundingCircle::new(drop_transform.translation.truncate(), DROP_DIAMETER / 2.0);
+        let picked_up = paddles.iter().any(|paddle_transform| {
+            drop_circle.intersects(&Aabb2d::new(
+                paddle_transform.translation.truncate(),
+                paddle_transform.scale.truncate() / 2.,
+            ))
+        });
+        if !picked_up {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        **score += drop.score();
+        collected_events.send(DropCollected(*drop));
+        let sound = match drop {
+            Drop::Coin => assets.coin_sound.clone(),
+            Drop::Gem => assets.gem_sound.clone(),
+            Drop::ForceField => assets.force_field_sound.clone(),
+            Drop::Magnet => assets.magnet_sound.clone(),
+        };
+        commands.spawn((AudioPlayer(sound), PlaybackSettings::DESPAWN));
+    }
+}
+
+/// 床に落ちたドロップを削除する
+fn despawn_fallen_drops(mut commands: Commands, drops: Query<(Entity, &Transform), With<Drop>>) {
+    let floor = BOTTOM_WALL + WALL_THICKNESS / 2.0 + DROP_DIAMETER / 2.0;
+    for (entity, transform) in &drops {
+        if transform.translation.y < floor {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use bevy::{asset::io::file::FileAssetReader, prelude::*};
+
+use crate::{
+    ball::Ball,
+    bricks::BRICK_SIZE,
+    camera::ArenaCursor,
+    is_single_player,
+    levels::{BrickGrid, BrickKind, LevelLayout},
+    locale::{Localized, LocalizedFont, Strings},
+    theme::{ThemeColor, Themed},
+    victory::PlayPhase,
+    walls::TOP_WALL,
+    GameState,
+};
+
+// エディタを開くキー
+const EDITOR_KEY: KeyCode = KeyCode::F2;
+// 配置を保存するキー (Ctrl と一緒に押す)
+const SAVE_KEY: KeyCode = KeyCode::KeyS;
+// 配置を保存して試遊するキー
+const PLAYTEST_KEY: KeyCode = KeyCode::Enter;
+// 試遊をやめて通常のゲームに戻るキー
+const QUIT_KEY: KeyCode = KeyCode::Escape;
+// 配置を保存するレベルファイルのパス (`assets` から)
+const CUSTOM_LEVEL_PATH: &str = "levels/custom.level.ron";
+// 選んでいるマスの枠の太さ
+const CURSOR_BORDER: f32 = 4.0;
+// 操作説明のフォントサイズ
+const HELP_FONT_SIZE: f32 = 18.0;
+
+/// レベルエディタを追加するプラグイン
+///
+/// `F2` キーで [`GameState::Editing`] に切り替え、ブロックのマス目をクリックして配置を編集する。
+/// 編集した配置は `assets/levels/custom.level.ron` に保存でき、`Enter` キーで保存してすぐに試遊できる。
+/// 試遊中 ([`Playtest`] があるとき) は、リスタートしても編集した配置で始まる。
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_custom_level)
+            .add_systems(
+                Update,
+                open_editor.run_if(
+                    is_single_player
+                        .and(in_state(PlayPhase::Running).or(in_state(GameState::GameOver))),
+                ),
+            )
+            .add_systems(
+                OnEnter(GameState::Editing),
+                (pause_time, init_layout, hide_ball, spawn_editor).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    move_cursor,
+                    edit_cell,
+                    update_cells,
+                    save_layout,
+                    playtest,
+                    quit_editor,
+                    update_status_ui,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Editing)),
+            )
+            .add_systems(OnExit(GameState::Editing), (unpause_time, show_ball));
+    }
+}
+
+/// エディタで試遊している配置 (あるときは `start_game` がこの配置でブロックを並べる)
+#[derive(Resource)]
+pub struct Playtest(pub LevelLayout);
+
+/// 保存済みのレベルファイルのハンドル
+#[derive(Resource)]
+struct CustomLevel(Handle<LevelLayout>);
+
+/// 編集中の配置
+#[derive(Resource)]
+struct EditorLayout(LevelLayout);
+
+/// エディタのマス目の 1 マスを示すコンポーネント
+#[derive(Component)]
+struct EditorCell {
+    column: usize,
+    row: usize,
+}
+
+/// 選んでいるマスの枠を示すコンポーネント
+#[derive(Component)]
+struct GridCursor {
+    cell: Option<(usize, usize)>, // カーソルが指しているマス (マス目の外なら `None`)
+}
+
+/// 保存の結果など、操作説明の下に表示する状態
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+enum EditorStatus {
+    #[default]
+    Unsaved, // まだ保存していない
+    Saved,         // レベルファイルに保存した
+    Empty,         // ブロックがないので試遊できない
+    Error(String), // 保存に失敗した
+}
+
+/// 状態を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct EditorStatusUi;
+
+/// 保存済みのレベルファイルを読み込む (まだ保存していなければ読み込みに失敗するだけ)
+fn load_custom_level(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CustomLevel(asset_server.load(CUSTOM_LEVEL_PATH)));
+}
+
+/// `F2` キーでエディタを開く
+fn open_editor(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(EDITOR_KEY) {
+        next_state.set(GameState::Editing);
+    }
+}
+
+/// 編集中はボールや落下物の動きを止める
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// 編集する配置を決める
+///
+/// 前回の編集内容があればそれを、なければ保存済みのレベルファイルを、それもなければ
+/// すべてのマスが埋まった配置を使う。
+fn init_layout(
+    mut commands: Commands,
+    editor_layout: Option<Res<EditorLayout>>,
+    custom_level: Res<CustomLevel>,
+    layouts: Res<Assets<LevelLayout>>,
+) {
+    commands.insert_resource(EditorStatus::default());
+    if editor_layout.is_some() {
+        return;
+    }
+    let layout = layouts
+        .get(&custom_level.0)
+        .cloned()
+        .unwrap_or_else(|| LevelLayout::filled(&BrickGrid::new(TOP_WALL)));
+    commands.insert_resource(EditorLayout(layout));
+}
+
+/// 編集中はボールを隠す
+fn hide_ball(mut ball: Single<&mut Visibility, With<Ball>>) {
+    **ball = Visibility::Hidden;
+}
+
+fn show_ball(mut ball: Single<&mut Visibility, With<Ball>>) {
+    **ball = Visibility::Inherited;
+}
+
+/// マス目・カーソル・操作説明を配置する
+fn spawn_editor(mut commands: Commands) {
+    let grid = BrickGrid::new(TOP_WALL);
+    for row in 0..grid.rows {
+        for column in 0..grid.columns {
+            commands.spawn((
+                Sprite::default(),
+                Themed(ThemeColor::EditorCell), // 色は `update_cells` が配置に合わせる
+                Transform {
+                    translation: grid.cell_center(column, row).extend(0.0),
+                    scale: BRICK_SIZE.extend(1.0),
+                    ..default()
+                },
+                EditorCell { column, row },
+                StateScoped(GameState::Editing),
+            ));
+        }
+    }
+
+    // マスの後ろに一回り大きなスプライトを置いて枠に見せる
+    commands.spawn((
+        Sprite::default(),
+        Themed(ThemeColor::EditorCursor),
+        Transform {
+            translation: Vec3::new(0.0, 0.0, -0.5),
+            scale: (BRICK_SIZE + Vec2::splat(CURSOR_BORDER * 2.0)).extend(1.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        GridCursor { cell: None },
+        StateScoped(GameState::Editing),
+    ));
+
+    let font = TextFont {
+        font_size: HELP_FONT_SIZE,
+        ..default()
+    };
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(50.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            StateScoped(GameState::Editing),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                font.clone(),
+                Themed(ThemeColor::Text),
+                Localized::new("editor_help"),
+            ));
+            parent.spawn((
+                Text::default(),
+                font,
+                Themed(ThemeColor::Hint),
+                LocalizedFont,
+                EditorStatusUi,
+            ));
+        });
+}
+
+/// マウスカーソルが指しているマスに枠を移動する
+fn move_cursor(
+    cursor: ArenaCursor,
+    grid_cursor: Single<(&mut GridCursor, &mut Transform, &mut Visibility)>,
+) {
+    let (mut grid_cursor, mut transform, mut visibility) = grid_cursor.into_inner();
+    let grid = BrickGrid::new(TOP_WALL);
+    let cell = cursor
+        .world_position()
+        .and_then(|position| grid.cell_at(position));
+    grid_cursor.cell = cell;
+    match cell {
+        Some((column, row)) => {
+            transform.translation = grid.cell_center(column, row).extend(-0.5);
+            *visibility = Visibility::Inherited;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+/// 左クリックでマスのブロックの種類を切り替え、右クリックでブロックを消す
+fn edit_cell(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    grid_cursor: Single<&GridCursor>,
+    mut layout: ResMut<EditorLayout>,
+) {
+    let Some((column, row)) = grid_cursor.cell else {
+        return;
+    };
+    let current = layout.0.get(column, row);
+    let kind = if mouse_input.just_pressed(MouseButton::Left) {
+        current.next()
+    } else if mouse_input.just_pressed(MouseButton::Right) {
+        BrickKind::Empty
+    } else {
+        return;
+    };
+    if kind != current {
+        layout.0.set(column, row, kind);
+    }
+}
+
+/// 配置が変わったら、マスの色を塗り直す
+fn update_cells(layout: Res<EditorLayout>, mut cells: Query<(&EditorCell, &mut Themed)>) {
+    for (cell, mut themed) in &mut cells {
+        let color = layout
+            .0
+            .get(cell.column, cell.row)
+            .color()
+            .unwrap_or(ThemeColor::EditorCell);
+        // 変わっていないマスは変更扱いにしない
+        themed.set_if_neq(Themed(color));
+    }
+}
+
+/// `Ctrl + S` キーで配置をレベルファイルに保存する
+fn save_layout(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    layout: Res<EditorLayout>,
+    mut status: ResMut<EditorStatus>,
+) {
+    let ctrl = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keyboard_input.just_pressed(SAVE_KEY) {
+        *status = write_layout(&layout.0);
+    }
+}
+
+/// `Enter` キーで配置を保存し、その配置で試遊を始める
+fn playtest(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    layout: Res<EditorLayout>,
+    mut status: ResMut<EditorStatus>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard_input.just_pressed(PLAYTEST_KEY) {
+        return;
+    }
+    // ブロックがないと始めた瞬間にクリアになるので、試遊させない
+    if layout.0.is_empty() {
+        *status = EditorStatus::Empty;
+        return;
+    }
+    *status = write_layout(&layout.0);
+    commands.insert_resource(Playtest(layout.0.clone()));
+    next_state.set(GameState::Playing);
+}
+
+/// `Esc` キーで試遊をやめ、通常のレベル 1 から遊び直す
+fn quit_editor(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(QUIT_KEY) {
+        commands.remove_resource::<Playtest>();
+        next_state.set(GameState::Playing);
+    }
+}
+
+/// 配置をレベルファイルに書き込む
+///
+/// アセットの読み込みと同じ `assets` ディレクトリに書き込むので、次回の起動時にも読み込まれる。
+fn write_layout(layout: &LevelLayout) -> EditorStatus {
+    let path = FileAssetReader::get_base_path()
+        .join("assets")
+        .join(CUSTOM_LEVEL_PATH);
+    let result = layout
+        .to_ron()
+        .map_err(|error| error.to_string())
+        .and_then(|contents| {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+            }
+            std::fs::write(&path, contents).map_err(|error| error.to_string())
+        });
+    match result {
+        Ok(()) => {
+            info!("saved level to {}", path.display());
+            EditorStatus::Saved
+        }
+        Err(error) => {
+            warn!("could not save level to {}: {error}", path.display());
+            EditorStatus::Error(error)
+        }
+    }
+}
+
+/// 状態か言語が変わったら、状態の表示を更新する
+fn update_status_ui(
+    status: Res<EditorStatus>,
+    strings: Strings,
+    mut status_text: Single<&mut Text, With<EditorStatusUi>>,
+) {
+    if !status.is_changed() && !strings.is_changed() {
+        return;
+    }
+    status_text.0 = match &*status {
+        EditorStatus::Unsaved => strings.get("editor_status_unsaved").to_string(),
+        EditorStatus::Saved => {
+            strings.format("editor_status_saved", &[("path", &CUSTOM_LEVEL_PATH)])
+        }
+        EditorStatus::Empty => strings.get("editor_status_empty").to_string(),
+        EditorStatus::Error(error) => strings.format("editor_status_error", &[("error", error)]),
+    };
+}
@@ -0,0 +1,236 @@
+use std::time::Duration;
+
+use bevy::{audio::Pitch, prelude::*};
+use prelude_kit::Lifetime;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    ball::{check_for_collisions, Ball, Velocity, BALL_DIAMETER},
+    drops::{Drop, DropCollected},
+    game_over::check_ball_lost,
+    glow::{Glow, POWER_UP_GLOW},
+    is_single_player,
+    theme::{ThemeColor, Themed},
+    walls::{BOTTOM_WALL, LEFT_WALL, RIGHT_WALL, WALL_THICKNESS},
+    GameState,
+};
+
+// バリアの中心の y 座標 (ボールを落とすと残機が減る床の高さ)
+const FORCE_FIELD_Y: f32 = BOTTOM_WALL;
+// バリアの厚さ
+const FORCE_FIELD_THICKNESS: f32 = 8.0;
+// バリアが脈打つ周期 (秒) と、最も細くなったときの厚さの割合
+const PULSE_PERIOD: f32 = 1.2;
+const PULSE_MIN_SCALE: f32 = 0.5;
+// ボールを跳ね返してから割れるまでの点滅の長さ (秒)
+const SHATTER_FLASH_SECS: f32 = 0.2;
+
+// 割れたときに飛び散る破片の数・大きさ・速さ・寿命 (秒)
+const SHARD_COUNT: usize = 16;
+const SHARD_SIZE: Vec2 = Vec2::new(24.0, 6.0);
+const SHARD_SPEED: std::ops::Range<f32> = 150.0..350.0;
+const SHARD_LIFETIME_SECS: f32 = 0.8;
+// 破片に掛かる重力加速度と、回転の速さの上限 (ラジアン/秒)
+const SHARD_GRAVITY: f32 = -900.0;
+const SHARD_MAX_SPIN: f32 = 10.0;
+
+// 割れたときの効果音 (2 つの高い音を同時に鳴らす)
+const SHATTER_FREQUENCIES: [f32; 2] = [2093.0, 2637.0];
+const SHATTER_SOUND_DURATION: Duration = Duration::from_millis(150);
+
+/// フォースフィールドのパワーアップを追加するプラグイン
+///
+/// フォースフィールドのドロップ (`drops.rs`) を拾うと、床にボールを 1 回だけ跳ね返すバリアを張る。
+/// ボールを跳ね返したバリアは点滅してから割れ、破片が飛び散る。
+pub struct ForceFieldPlugin;
+
+impl Plugin for ForceFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ShardRng(StdRng::from_entropy()))
+            .add_systems(Startup, setup_shatter_sounds)
+            .add_systems(Update, activate_force_field.run_if(is_single_player))
+            .add_systems(
+                FixedUpdate,
+                (
+                    // 残機を減らす判定より先に跳ね返す
+                    reflect_ball
+                        .after(check_for_collisions)
+                        .before(check_ball_lost),
+                    animate_force_field,
+                    move_shards,
+                )
+                    .run_if(is_single_player),
+            );
+    }
+}
+
+/// 床に張ったバリアを示すコンポーネント
+#[derive(Component)]
+pub struct ForceField {
+    state: ForceFieldState,
+}
+
+/// バリアの状態
+enum ForceFieldState {
+    Active,            // ボールを跳ね返せる (脈打ちながら光る)
+    Shattering(Timer), // ボールを跳ね返し、割れる前に点滅している
+}
+
+/// 割れたバリアの破片
+#[derive(Component)]
+struct Shard {
+    velocity: Vec2,
+    spin: f32, // 回転の速さ (ラジアン/秒)
+}
+
+/// 破片の飛び散り方を決める乱数生成器
+#[derive(Resource, Deref, DerefMut)]
+struct ShardRng(StdRng);
+
+/// 割れたときの効果音のハンドル
+#[derive(Resource)]
+struct ShatterSounds(Vec<Handle<Pitch>>);
+
+/// 割れたときの効果音を用意する
+fn setup_shatter_sounds(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    let sounds = SHATTER_FREQUENCIES
+        .iter()
+        .map(|&frequency| pitches.add(Pitch::new(frequency, SHATTER_SOUND_DURATION)))
+        .collect();
+    commands.insert_resource(ShatterSounds(sounds));
+}
+
+/// フォースフィールドのドロップを拾ったら、床にバリアを張る
+///
+/// すでにボールを跳ね返せるバリアがあるときは何もしない (重ねて張っても 1 回分のまま)。
+fn activate_force_field(
+    mut commands: Commands,
+    mut collected_events: EventReader<DropCollected>,
+    force_fields: Query<&ForceField>,
+) {
+    let collected = collected_events
+        .read()
+        .any(|DropCollected(drop)| *drop == Drop::ForceField);
+    let active = force_fields
+        .iter()
+        .any(|force_field| matches!(force_field.state, ForceFieldState::Active));
+    if !collected || active {
+        return;
+    }
+
+    // 左右の壁の内側いっぱいに張る
+    let width = RIGHT_WALL - LEFT_WALL - WALL_THICKNESS;
+    commands.spawn((
+        Sprite::default(),
+        Themed(ThemeColor::ForceField),
+        Glow(POWER_UP_GLOW),
+        Transform {
+            translation: Vec3::new((LEFT_WALL + RIGHT_WALL) / 2.0, FORCE_FIELD_Y, 0.5),
+            scale: Vec3::new(width, FORCE_FIELD_THICKNESS, 1.0),
+            ..default()
+        },
+        ForceField {
+            state: ForceFieldState::Active,
+        },
+        StateScoped(GameState::Playing),
+    ));
+}
+
+/// 下向きに進むボールがバリアに触れたら 1 回だけ跳ね返し、バリアを割れる状態にする
+///
+/// バリアは `Collider` を持たず、`check_for_collisions` とは別に判定する
+/// (跳ね返すのは上からの 1 回だけで、ブロックやパドルのように何度も当たらないため)。
+fn reflect_ball(
+    mut commands: Commands,
+    ball: Single<(&mut Transform, &mut Velocity), With<Ball>>,
+    mut force_fields: Query<&mut ForceField>,
+    sounds: Res<ShatterSounds>,
+) {
+    let (mut ball_transform, mut ball_velocity) = ball.into_inner();
+    let top = FORCE_FIELD_Y + FORCE_FIELD_THICKNESS / 2.0;
+    if ball_velocity.y >= 0.0 || ball_transform.translation.y - BALL_DIAMETER / 2.0 > top {
+        return;
+    }
+    let Some(mut force_field) = force_fields
+        .iter_mut()
+        .find(|force_field| matches!(force_field.state, ForceFieldState::Active))
+    else {
+        return;
+    };
+
+    ball_velocity.y = -ball_velocity.y;
+    // めり込んだ分を戻し、次のステップで床の判定に掛からないようにする
+    ball_transform.translation.y = top + BALL_DIAMETER / 2.0;
+    force_field.state =
+        ForceFieldState::Shattering(Timer::from_seconds(SHATTER_FLASH_SECS, TimerMode::Once));
+    for sound in &sounds.0 {
+        commands.spawn((AudioPlayer(sound.clone()), PlaybackSettings::DESPAWN));
+    }
+}
+
+/// バリアを脈打たせ、跳ね返したあとは点滅させてから破片に変える
+fn animate_force_field(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rng: ResMut<ShardRng>,
+    mut force_fields: Query<(Entity, &mut ForceField, &mut Transform, &mut Visibility)>,
+) {
+    for (entity, mut force_field, mut transform, mut visibility) in &mut force_fields {
+        match &mut force_field.state {
+            ForceFieldState::Active => {
+                let wave = (time.elapsed_secs() * std::f32::consts::TAU / PULSE_PERIOD).sin();
+                let scale = PULSE_MIN_SCALE + (1.0 - PULSE_MIN_SCALE) * (0.5 + 0.5 * wave);
+                transform.scale.y = FORCE_FIELD_THICKNESS * scale;
+            }
+            ForceFieldState::Shattering(timer) => {
+                timer.tick(time.delta());
+                transform.scale.y = FORCE_FIELD_THICKNESS;
+                *visibility = if ((timer.elapsed_secs() * 30.0) as u32).is_multiple_of(2) {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+                if timer.finished() {
+                    spawn_shards(&mut commands, &mut rng, &transform);
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// バリアの幅に沿って破片を並べ、上向きに飛び散らせる
+fn spawn_shards(commands: &mut Commands, rng: &mut StdRng, force_field: &Transform) {
+    let width = force_field.scale.x;
+    let left = force_field.translation.x - width / 2.0;
+    for index in 0..SHARD_COUNT {
+        let x = left + (index as f32 + 0.5) * width / SHARD_COUNT as f32;
+        let angle = rng.gen_range(-1.0..1.0);
+        commands.spawn((
+            Sprite {
+                custom_size: Some(SHARD_SIZE),
+                ..default()
+            },
+            Themed(ThemeColor::ForceField),
+            Transform::from_xyz(x, force_field.translation.y, force_field.translation.z),
+            Shard {
+                velocity: Vec2::from_angle(angle).rotate(Vec2::Y) * rng.gen_range(SHARD_SPEED),
+                spin: rng.gen_range(-SHARD_MAX_SPIN..SHARD_MAX_SPIN),
+            },
+            // 寿命が来たら `PreludeKitPlugin` が消す
+            Lifetime::from_seconds(SHARD_LIFETIME_SECS),
+            StateScoped(GameState::Playing),
+        ));
+    }
+}
+
+/// 破片を動かし、寿命に合わせて小さくする
+fn move_shards(time: Res<Time>, mut shards: Query<(&mut Shard, &Lifetime, &mut Transform)>) {
+    let dt = time.delta_secs();
+    for (mut shard, lifetime, mut transform) in &mut shards {
+        shard.velocity.y += SHARD_GRAVITY * dt;
+        transform.translation += (shard.velocity * dt).extend(0.0);
+        transform.rotate_z(shard.spin * dt);
+        transform.scale = Vec3::splat(1.0 - lifetime.fraction());
+    }
+}
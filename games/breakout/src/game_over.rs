@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{check_for_collisions, reset_ball, Ball, Velocity, BALL_DIAMETER},
+    difficulty::Difficulty,
+    is_single_player,
+    locale::Localized,
+    records::Records,
+    spin::Spin,
+    theme::{ThemeColor, Themed},
+    ui::{SCOREBOARD_FONT_SIZE, SCOREBOARD_TEXT_PADDING},
+    walls::BOTTOM_WALL,
+    GameState, Level, Score,
+};
+
+// 残機の表示のフォントサイズ
+pub const LIVES_FONT_SIZE: f32 = 20.0;
+// ゲームオーバー画面の見出しと本文のフォントサイズ
+const TITLE_FONT_SIZE: f32 = 60.0;
+const BODY_FONT_SIZE: f32 = 24.0;
+
+/// 残機とゲームオーバー画面を追加するプラグイン
+///
+/// ボールが下端から落ちると残機が 1 減り、0 になると [`GameState::GameOver`] に遷移する。
+/// 最後のレベルをクリアしたとき (`victory.rs`) も同じ画面を結果画面として使い、見出しだけを変える。
+/// ゲームオーバー中は仮想時間を止め、Enter キーで最初からやり直す。
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        // 残機はプレイ開始時に `reset_lives` が難易度に合わせて設定する
+        app.insert_resource(Lives(0))
+            .init_resource::<RunOutcome>()
+            .add_event::<BallLost>()
+            .add_systems(Startup, spawn_lives_ui.run_if(is_single_player))
+            .add_systems(OnEnter(GameState::Playing), reset_lives)
+            .add_systems(
+                FixedUpdate,
+                check_ball_lost
+                    .after(check_for_collisions)
+                    .run_if(is_single_player.and(in_state(GameState::Playing))),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_lives_ui.run_if(is_single_player),
+                    restart.run_if(in_state(GameState::GameOver)),
+                ),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (pause_time, spawn_game_over_screen),
+            )
+            .add_systems(OnExit(GameState::GameOver), unpause_time);
+    }
+}
+
+/// 残りの残機
+#[derive(Resource, Reflect, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct Lives(u32);
+
+/// ボールを落として残機が減ったときのイベント
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BallLost {
+    pub lives_left: u32, // 減ったあとの残機
+}
+
+/// ゲームが終わった理由 (結果画面の見出しを切り替える)
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    #[default]
+    OutOfLives, // 残機がなくなった
+    AllCleared, // 最後のレベルをクリアした
+}
+
+/// 残機の表示を示すコンポーネント
+#[derive(Component)]
+struct LivesUi;
+
+/// ゲームオーバー画面のルート UI を示すコンポーネント (ほかのモジュールが子要素を追加する)
+#[derive(Component)]
+pub struct GameOverScreen;
+
+/// ハイスコアの下に残機の表示を配置する
+fn spawn_lives_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Text::new("Lives: "),
+            TextFont {
+                font_size: LIVES_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Text),
+            LivesUi,
+            Localized::new("lives_label"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(SCOREBOARD_FONT_SIZE + LIVES_FONT_SIZE + 16.0),
+                left: SCOREBOARD_TEXT_PADDING,
+                ..default()
+            },
+        ))
+        .with_child((
+            TextSpan::default(),
+            TextFont {
+                font_size: LIVES_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Score),
+        ));
+}
+
+/// 残機を難易度で決まる初期値に戻す
+pub fn reset_lives(mut lives: ResMut<Lives>, difficulty: Res<Difficulty>) {
+    **lives = difficulty.starting_lives();
+}
+
+/// ボールが下端から落ちたら残機を減らし、ボールを開始位置に戻す
+pub fn check_ball_lost(
+    mut lives: ResMut<Lives>,
+    mut outcome: ResMut<RunOutcome>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut lost_events: EventWriter<BallLost>,
+    difficulty: Res<Difficulty>,
+    ball_query: Single<(&mut Transform, &mut Velocity, &mut Spin), With<Ball>>,
+) {
+    let (ball_transform, ball_velocity, ball_spin) = ball_query.into_inner();
+    if ball_transform.translation.y >= BOTTOM_WALL - BALL_DIAMETER {
+        return;
+    }
+
+    **lives = lives.saturating_sub(1);
+    info!("ball lost, {} lives left", **lives);
+    lost_events.send(BallLost {
+        lives_left: **lives,
+    });
+    if **lives == 0 {
+        *outcome = RunOutcome::OutOfLives;
+        next_state.set(GameState::GameOver);
+    }
+    reset_ball(
+        ball_transform,
+        ball_velocity,
+        ball_spin,
+        difficulty.ball_speed(),
+    );
+}
+
+/// 残機の表示を更新する
+fn update_lives_ui(
+    lives: Res<Lives>,
+    root: Single<Entity, (With<LivesUi>, With<Text>)>,
+    mut writer: TextUiWriter,
+) {
+    if lives.is_changed() {
+        *writer.text(*root, 1) = lives.to_string();
+    }
+}
+
+/// ゲームオーバー中はボールなどの動きを止める
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// 最終スコアを表示するゲームオーバー画面を配置する
+pub fn spawn_game_over_screen(
+    mut commands: Commands,
+    score: Res<Score>,
+    level: Res<Level>,
+    records: Res<Records>,
+    outcome: Res<RunOutcome>,
+) {
+    let (title_key, title_color) = match *outcome {
+        RunOutcome::OutOfLives => ("game_over", ThemeColor::Title),
+        RunOutcome::AllCleared => ("all_clear", ThemeColor::NewBest),
+    };
+    let body_font = TextFont {
+        font_size: BODY_FONT_SIZE,
+        ..default()
+    };
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            Themed(ThemeColor::Overlay),
+            GameOverScreen,
+            StateScoped(GameState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: TITLE_FONT_SIZE,
+                    ..default()
+                },
+                Themed(title_color),
+                Localized::new(title_key),
+            ));
+            parent.spawn((
+                Text::default(),
+                body_font.clone(),
+                Localized::new("game_over_summary")
+                    .with_arg("score", **score)
+                    .with_arg("level", **level)
+                    .with_arg("high_score", records.high_score),
+            ));
+            parent.spawn((
+                Text::new("Press Enter to play again"),
+                body_font,
+                Localized::new("play_again"),
+            ));
+        });
+}
+
+/// Enter キーで最初からやり直す
+fn restart(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        next_state.set(GameState::Playing);
+    }
+}
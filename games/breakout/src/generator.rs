@@ -0,0 +1,320 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    daily::is_daily,
+    game_over::{spawn_game_over_screen, GameOverScreen, LIVES_FONT_SIZE},
+    is_single_player,
+    levels::{BrickGrid, BrickKind, LevelLayout},
+    locale::{LocalizedFont, Strings},
+    theme::{ThemeColor, Themed},
+    ui::{SCOREBOARD_FONT_SIZE, SCOREBOARD_TEXT_PADDING},
+    GameState,
+};
+
+// シード値を指定する起動引数 (`--seed` だけのときはシード値をランダムに決める)
+const SEED_ARG: &str = "--seed";
+// シード値の表示のフォントサイズ
+const SEED_FONT_SIZE: f32 = 16.0;
+// ゲームオーバー画面で入力できるシード値の最大の桁数 (`u64::MAX` は 20 桁)
+const MAX_SEED_DIGITS: usize = 20;
+// レベルごとにシード値をずらす量 (黄金比から作った奇数で、近いレベルの配置が似ないようにする)
+const LEVEL_SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// シード値からブロックの配置を作るプラグイン
+///
+/// `--seed=<u64>` で起動すると、普通のブロックを敷き詰める代わりに、
+/// シード値から市松模様・ピラミッド・ランダムな隙間・左右対称のノイズのいずれかの配置を作る。
+/// 同じシード値なら同じ配置になるので、シード値を共有すれば同じ盤面で遊べる。
+/// シード値はスコアボードの下とゲームオーバー画面に表示し、ゲームオーバー画面では数字キーで入力し直せる。
+pub struct GeneratorPlugin;
+
+impl Plugin for GeneratorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LayoutSeed::from_args())
+            .add_systems(Startup, spawn_seed_ui.run_if(is_single_player))
+            // デイリーチャレンジのシード値は日付で決まるので、入力欄は出さない
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                spawn_seed_input
+                    .after(spawn_game_over_screen)
+                    .run_if(not(is_daily)),
+            )
+            .add_systems(
+                Update,
+                (
+                    (type_seed, update_seed_input_ui)
+                        .run_if(in_state(GameState::GameOver))
+                        .run_if(not(is_daily)),
+                    update_seed_ui.run_if(is_single_player),
+                ),
+            )
+            // 入力したシード値は次のプレイの盤面 (`OnEnter(GameState::Playing)`) から使う
+            .add_systems(
+                OnExit(GameState::GameOver),
+                apply_seed_input.run_if(not(is_daily)),
+            );
+    }
+}
+
+/// ブロックの配置を作るシード値 (`None` のときはすべてのマスに普通のブロックを置く)
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutSeed(pub Option<u64>);
+
+impl LayoutSeed {
+    /// 起動引数 (`--seed=<u64>` か `--seed`) からシード値を決める
+    fn from_args() -> LayoutSeed {
+        let seed = std::env::args().find_map(|arg| {
+            if arg == SEED_ARG {
+                Some(rand::random())
+            } else {
+                let value = arg.strip_prefix(SEED_ARG)?.strip_prefix('=')?;
+                match value.parse() {
+                    Ok(seed) => Some(seed),
+                    Err(error) => {
+                        warn!("ignoring invalid seed {value:?}: {error}");
+                        None
+                    }
+                }
+            }
+        });
+        if let Some(seed) = seed {
+            info!("brick layout seed {seed}");
+        }
+        LayoutSeed(seed)
+    }
+
+    /// 指定したレベルの配置に使うシード値 (レベル 1 はシード値そのもの)
+    pub fn for_level(&self, level: u32) -> Option<u64> {
+        self.0
+            .map(|seed| seed.wrapping_add(u64::from(level.saturating_sub(1)) * LEVEL_SEED_STEP))
+    }
+}
+
+/// 配置の模様
+#[derive(Debug, Clone, Copy)]
+enum Pattern {
+    Checkerboard,   // 市松模様
+    Pyramid,        // 下の行ほど幅の広いピラミッド (逆さまのこともある)
+    RandomGaps,     // 敷き詰めたブロックをところどころ抜いたもの
+    SymmetricNoise, // 左半分をランダムに決め、右半分に鏡写しにしたもの
+}
+
+/// シード値からブロックの配置を作る
+///
+/// マス目 (`grid`) は `BrickGrid::new` がアリーナの大きさとブロックの隙間の定数から決めるので、
+/// 作った配置は必ずアリーナに収まる。ブロックが 1 つもない配置にはならない。
+pub fn generate(seed: u64, grid: &BrickGrid) -> LevelLayout {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let pattern = match rng.gen_range(0..4) {
+        0 => Pattern::Checkerboard,
+        1 => Pattern::Pyramid,
+        2 => Pattern::RandomGaps,
+        _ => Pattern::SymmetricNoise,
+    };
+    // 硬いブロックの割合
+    let sturdy_ratio = rng.gen_range(0.0..0.3);
+    let (columns, rows) = (grid.columns, grid.rows);
+
+    let mut layout = LevelLayout::empty();
+
+    match pattern {
+        Pattern::Checkerboard => {
+            // 1 マスか 2 マスごとに塗り分け、どちらの色から始めるかも変える
+            let cell = rng.gen_range(1..=2);
+            let parity = rng.gen_range(0..2);
+            for row in 0..rows {
+                for column in 0..columns {
+                    if (column / cell + row / cell) % 2 == parity {
+                        layout.set(column, row, random_kind(&mut rng, sturdy_ratio));
+                    }
+                }
+            }
+        }
+        Pattern::Pyramid => {
+            let inverted = rng.gen_bool(0.5);
+            for row in 0..rows {
+                // 底辺 (逆さまなら一番上の行) から 1 段上がるごとに左右を 1 マスずつ狭める
+                let step = if inverted { row } else { rows - 1 - row };
+                for column in step..columns.saturating_sub(step) {
+                    layout.set(column, row, random_kind(&mut rng, sturdy_ratio));
+                }
+            }
+        }
+        Pattern::RandomGaps => {
+            let gap_ratio = rng.gen_range(0.15..0.4);
+            for row in 0..rows {
+                for column in 0..columns {
+                    if !rng.gen_bool(gap_ratio) {
+                        layout.set(column, row, random_kind(&mut rng, sturdy_ratio));
+                    }
+                }
+            }
+        }
+        Pattern::SymmetricNoise => {
+            let density = rng.gen_range(0.45..0.75);
+            for row in 0..rows {
+                // 列数が奇数のときは中央の列も左半分に含める
+                for column in 0..columns.div_ceil(2) {
+                    if rng.gen_bool(density) {
+                        let kind = random_kind(&mut rng, sturdy_ratio);
+                        layout.set(column, row, kind);
+                        layout.set(columns - 1 - column, row, kind);
+                    }
+                }
+            }
+        }
+    }
+
+    // 遊べる盤面にするため、空になったときは中央に 1 つ置く
+    if layout.is_empty() {
+        layout.set(columns / 2, rows / 2, BrickKind::Normal);
+    }
+    debug!("generated {pattern:?} layout from seed {seed}");
+    layout
+}
+
+/// `sturdy_ratio` の確率で硬いブロック、それ以外は普通のブロック
+fn random_kind(rng: &mut StdRng, sturdy_ratio: f64) -> BrickKind {
+    if rng.gen_bool(sturdy_ratio) {
+        BrickKind::Sturdy
+    } else {
+        BrickKind::Normal
+    }
+}
+
+/// シード値の表示を示すコンポーネント
+#[derive(Component)]
+struct SeedUi;
+
+/// ゲームオーバー画面のシード値の入力欄を示すコンポーネント
+#[derive(Component)]
+struct SeedInputUi;
+
+/// ゲームオーバー画面で入力中のシード値 (空なら敷き詰めた配置に戻す)
+#[derive(Resource, Default)]
+struct SeedInput(String);
+
+/// 残機の表示の下にシード値の表示を配置する
+fn spawn_seed_ui(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: SEED_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Hint),
+        LocalizedFont,
+        SeedUi,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(SCOREBOARD_FONT_SIZE + 2.0 * LIVES_FONT_SIZE + 20.0),
+            left: SCOREBOARD_TEXT_PADDING,
+            ..default()
+        },
+    ));
+}
+
+/// シード値の表示を更新する (シード値を使っていないときは何も表示しない)
+fn update_seed_ui(
+    seed: Res<LayoutSeed>,
+    strings: Strings,
+    mut text: Single<&mut Text, With<SeedUi>>,
+) {
+    if !seed.is_changed() && !strings.is_changed() {
+        return;
+    }
+    text.0 = match seed.0 {
+        Some(seed) => strings.format("layout_seed", &[("seed", &seed)]),
+        None => String::new(),
+    };
+}
+
+/// ゲームオーバー画面にシード値の入力欄を追加する (現在のシード値を最初から入れておく)
+fn spawn_seed_input(
+    mut commands: Commands,
+    seed: Res<LayoutSeed>,
+    screen: Single<Entity, With<GameOverScreen>>,
+) {
+    commands.insert_resource(SeedInput(
+        seed.0.map(|seed| seed.to_string()).unwrap_or_default(),
+    ));
+    commands.entity(*screen).with_child((
+        Text::default(),
+        TextFont {
+            font_size: SEED_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Text),
+        LocalizedFont,
+        SeedInputUi,
+    ));
+}
+
+/// 数字キーでシード値を入力し、`Backspace` キーで 1 桁消す
+///
+/// 物理キーと同じ `KeyboardInput` イベントを読むので、キーパッドの数字でも入力できる。
+fn type_seed(mut events: EventReader<KeyboardInput>, mut input: ResMut<SeedInput>) {
+    for event in events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(text) => {
+                for digit in text.chars().filter(char::is_ascii_digit) {
+                    let mut next = input.0.clone();
+                    next.push(digit);
+                    // `u64` に収まらない桁は入力させない
+                    if next.len() <= MAX_SEED_DIGITS && next.parse::<u64>().is_ok() {
+                        input.0 = next;
+                    }
+                }
+            }
+            Key::Backspace => {
+                input.0.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 入力中のシード値を表示する
+fn update_seed_input_ui(
+    input: Res<SeedInput>,
+    strings: Strings,
+    mut text: Single<&mut Text, With<SeedInputUi>>,
+) {
+    if !input.is_changed() && !strings.is_changed() {
+        return;
+    }
+    text.0 = if input.0.is_empty() {
+        strings.get("seed_input_empty").to_string()
+    } else {
+        strings.format("seed_input", &[("seed", &input.0)])
+    };
+}
+
+/// ゲームオーバー画面を閉じるときに、入力したシード値を次のプレイで使う
+fn apply_seed_input(
+    mut commands: Commands,
+    input: Option<Res<SeedInput>>,
+    mut seed: ResMut<LayoutSeed>,
+) {
+    let Some(input) = input else {
+        return;
+    };
+    let new_seed = LayoutSeed(input.0.parse().ok());
+    if *seed != new_seed {
+        *seed = new_seed;
+        if let Some(seed) = seed.0 {
+            info!("brick layout seed {seed}");
+        }
+    }
+    commands.remove_resource::<SeedInput>();
+}
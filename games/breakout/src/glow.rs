@@ -0,0 +1,135 @@
+use bevy::{core_pipeline::bloom::Bloom, prelude::*};
+use prelude_kit::MainCamera;
+use settings::Settings;
+
+use crate::{
+    ball::{Ball, Velocity},
+    theme::{apply_theme, Theme, Themed},
+};
+
+// ブルームの有無を切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::KeyG;
+// ボールの明るさの倍率 (テーマの色に掛ける)。速さが `BALL_GLOW_SPEEDS` の範囲で最小から最大まで変わる
+const BALL_GLOW: (f32, f32) = (1.5, 4.0);
+const BALL_GLOW_SPEEDS: (f32, f32) = (300.0, 700.0);
+// ドロップ (コイン・ジェム・フォースフィールド) と床に張ったバリアの明るさの倍率
+pub const POWER_UP_GLOW: f32 = 2.5;
+
+/// ボールとパワーアップを光らせるブルームを追加するプラグイン
+///
+/// アリーナのカメラで HDR とブルームを有効にし、[`Glow`] を付けたエンティティの色を
+/// テーマの色より明るく (1.0 を超える値に) して、にじんで光って見えるようにする。
+/// ボールは速いほど明るくなる。性能の低いマシン向けに、`G` キーで共有設定の
+/// `reduced_effects` を切り替えると、HDR とブルームを止めてテーマの色のまま描く。
+pub struct GlowPlugin;
+
+impl Plugin for GlowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_reduced_effects,
+                configure_cameras.run_if(resource_changed::<Settings>),
+                update_ball_glow,
+            )
+                .chain(),
+        )
+        // テーマの色が塗られたあとで明るくする
+        .add_systems(PostUpdate, apply_glow.after(apply_theme));
+    }
+}
+
+/// テーマの色に掛ける明るさの倍率 (1.0 を超えるとブルームで光る)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Glow(pub f32);
+
+/// `G` キーで重い描画効果を省くかどうかを切り替える (共有設定に保存される)
+fn toggle_reduced_effects(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+) {
+    if keyboard_input.just_pressed(TOGGLE_KEY) {
+        settings.reduced_effects = !settings.reduced_effects;
+        info!("reduced effects: {}", settings.reduced_effects);
+    }
+}
+
+/// 設定に合わせて、カメラの HDR とアリーナのカメラのブルームを切り替える
+///
+/// 同じウィンドウに描くカメラは HDR の設定が同じときだけ描画先のテクスチャを共有するので、
+/// 黒帯を塗るカメラも合わせて切り替える。
+fn configure_cameras(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut cameras: Query<(Entity, &mut Camera, Has<MainCamera>)>,
+) {
+    let enabled = !settings.reduced_effects;
+    for (entity, mut camera, is_arena) in &mut cameras {
+        camera.hdr = enabled;
+        if !is_arena {
+            continue;
+        }
+        if enabled {
+            commands.entity(entity).insert(Bloom::NATURAL);
+        } else {
+            commands.entity(entity).remove::<Bloom>();
+        }
+    }
+}
+
+/// ボールの速さに合わせて明るさを変える
+fn update_ball_glow(mut balls: Query<(&Velocity, &mut Glow), With<Ball>>) {
+    for (velocity, mut glow) in &mut balls {
+        let (slow, fast) = BALL_GLOW_SPEEDS;
+        let t = ((velocity.length() - slow) / (fast - slow)).clamp(0.0, 1.0);
+        glow.0 = BALL_GLOW.0.lerp(BALL_GLOW.1, t);
+    }
+}
+
+/// [`Glow`] を付けたエンティティを、テーマの色に明るさの倍率を掛けた色で塗る
+///
+/// 重い描画効果を省くときはテーマの色のまま塗る。ドロップは種類ごとにマテリアルを共有しているので、
+/// 色が変わらないときはマテリアルを書き換えない (書き換えるとマテリアルが GPU に送り直される)。
+#[allow(clippy::type_complexity)]
+fn apply_glow(
+    theme: Res<Theme>,
+    settings: Res<Settings>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut glowing: Query<(
+        &Themed,
+        &Glow,
+        Option<&mut Sprite>,
+        Option<&MeshMaterial2d<ColorMaterial>>,
+    )>,
+) {
+    for (themed, glow, sprite, material) in &mut glowing {
+        let intensity = if settings.reduced_effects {
+            1.0
+        } else {
+            glow.0
+        };
+        let base = theme.color(themed.0).to_linear();
+        let color: Color = LinearRgba::new(
+            base.red * intensity,
+            base.green * intensity,
+            base.blue * intensity,
+            base.alpha,
+        )
+        .into();
+        if let Some(mut sprite) = sprite {
+            if sprite.color != color {
+                sprite.color = color;
+            }
+        }
+        if let Some(handle) = material {
+            if materials
+                .get(handle)
+                .is_some_and(|material| material.color != color)
+            {
+                if let Some(material) = materials.get_mut(handle) {
+                    material.color = color;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use bevy::{
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant, Enum, FromReflect},
+};
+use settings::Settings;
+
+// 共有設定 (`Settings::key_bindings`) に書き込むときの項目名の接頭辞
+const SETTINGS_PREFIX: &str = "breakout.";
+
+/// 1P の操作キーの割り当てを [`InputMap`] リソースとして管理するプラグイン
+///
+/// 起動時に共有設定 ([`Settings`]) から読み込み、操作設定画面 (`options.rs`) で変更されたら書き戻す。
+/// 書き戻した共有設定は `SettingsPlugin` がフレームの最後にファイルへ保存する。
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputMap>()
+            .add_systems(Startup, load_bindings)
+            .add_systems(Last, store_bindings.run_if(resource_changed::<InputMap>));
+    }
+}
+
+/// 割り当てを変えられる操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputAction {
+    PaddleLeft,  // 1P パドルを左へ動かす
+    PaddleRight, // 1P パドルを右へ動かす
+    Launch,      // 止まっているボールを打ち出す
+    Pause,       // 一時停止・再開
+}
+
+impl InputAction {
+    /// 操作設定画面に並べる順番
+    pub const ALL: [InputAction; 4] = [
+        InputAction::PaddleLeft,
+        InputAction::PaddleRight,
+        InputAction::Launch,
+        InputAction::Pause,
+    ];
+
+    /// 共有設定の項目名 (`breakout.` の後ろ) と、文字列テーブルのキー (`action_` の後ろ) に使う名前
+    pub fn name(self) -> &'static str {
+        match self {
+            InputAction::PaddleLeft => "paddle_left",
+            InputAction::PaddleRight => "paddle_right",
+            InputAction::Launch => "launch",
+            InputAction::Pause => "pause",
+        }
+    }
+}
+
+/// 1P の操作キーの割り当て
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputMap {
+    pub paddle_left: KeyCode,
+    pub paddle_right: KeyCode,
+    pub launch: KeyCode,
+    pub pause: KeyCode,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        InputMap {
+            paddle_left: KeyCode::ArrowLeft,
+            paddle_right: KeyCode::ArrowRight,
+            launch: KeyCode::Space,
+            pause: KeyCode::KeyP,
+        }
+    }
+}
+
+impl InputMap {
+    /// 操作に割り当てたキー
+    pub fn get(&self, action: InputAction) -> KeyCode {
+        match action {
+            InputAction::PaddleLeft => self.paddle_left,
+            InputAction::PaddleRight => self.paddle_right,
+            InputAction::Launch => self.launch,
+            InputAction::Pause => self.pause,
+        }
+    }
+
+    fn slot(&mut self, action: InputAction) -> &mut KeyCode {
+        match action {
+            InputAction::PaddleLeft => &mut self.paddle_left,
+            InputAction::PaddleRight => &mut self.paddle_right,
+            InputAction::Launch => &mut self.launch,
+            InputAction::Pause => &mut self.pause,
+        }
+    }
+
+    /// 操作にキーを割り当てる
+    ///
+    /// そのキーがほかの操作に割り当てられていたら、その操作には入れ替わりに元のキーを割り当てる
+    /// (1 つのキーで 2 つの操作が同時に起きないようにする)。
+    pub fn bind(&mut self, action: InputAction, key: KeyCode) {
+        let previous = self.get(action);
+        if let Some(other) = InputAction::ALL
+            .into_iter()
+            .find(|&other| other != action && self.get(other) == key)
+        {
+            *self.slot(other) = previous;
+        }
+        *self.slot(action) = key;
+    }
+}
+
+/// キーの名前 (`KeyCode` のバリアント名。共有設定にはこの名前で保存する)
+fn key_name(key: KeyCode) -> String {
+    key.variant_name().to_string()
+}
+
+/// 画面に表示するキーの名前 (`KeyA` → `A`、`Digit1` → `1` のように接頭辞を省く)
+pub fn key_label(key: KeyCode) -> String {
+    let name = key_name(key);
+    ["Key", "Digit"]
+        .into_iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .filter(|rest| !rest.is_empty())
+        .map_or_else(|| name.clone(), str::to_string)
+}
+
+/// バリアント名からキーを作る (フィールドを持たないバリアントの名前でなければ `None`)
+fn parse_key(name: &str) -> Option<KeyCode> {
+    KeyCode::from_reflect(&DynamicEnum::new(name.to_string(), DynamicVariant::Unit))
+}
+
+/// 共有設定から割り当てを読み込む (書かれていない・読めない操作は既定のキーのまま)
+fn load_bindings(settings: Res<Settings>, mut input_map: ResMut<InputMap>) {
+    let mut loaded = InputMap::default();
+    for action in InputAction::ALL {
+        let Some(name) = settings
+            .key_bindings
+            .get(&format!("{SETTINGS_PREFIX}{}", action.name()))
+        else {
+            continue;
+        };
+        match parse_key(name) {
+            Some(key) => loaded.bind(action, key),
+            None => warn!("unknown key {name:?} for {}", action.name()),
+        }
+    }
+    input_map.set_if_neq(loaded);
+}
+
+/// 割り当てが変わったら共有設定に書き戻す (起動時の読み込みでは書き戻さない)
+fn store_bindings(input_map: Res<InputMap>, mut settings: ResMut<Settings>) {
+    if input_map.is_added() {
+        return;
+    }
+    for action in InputAction::ALL {
+        let key = format!("{SETTINGS_PREFIX}{}", action.name());
+        let value = key_name(input_map.get(action));
+        if settings.key_bindings.get(&key) != Some(&value) {
+            settings.key_bindings.insert(key, value);
+        }
+    }
+}
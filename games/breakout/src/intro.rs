@@ -0,0 +1,91 @@
+use bevy::{ecs::system::SystemId, prelude::*};
+use sequencer::{seq, SequencerPlugin, SequencerSet};
+
+use crate::ball::Collider;
+
+// ブロックを落とし始める高さ (並べる位置からの距離)
+const DROP_HEIGHT: f32 = 600.0;
+// ブロックが落ちきるまでの時間 (秒)
+const DROP_SECONDS: f32 = 0.45;
+// 落ち始めるときのブロックの大きさ (並べたときの大きさに対する割合) と、元の大きさに戻るまでの時間 (秒)
+const START_SCALE: f32 = 0.3;
+const GROW_SECONDS: f32 = 0.35;
+// 1 行下・1 列右のブロックが落ち始めるまでの遅れ (秒)
+const ROW_DELAY: f32 = 0.08;
+const COLUMN_DELAY: f32 = 0.03;
+
+/// レベルの始まりに、並べたブロックを上から順に落とす演出のプラグイン
+///
+/// 演出はタイムライン (`animation/sequencer` の `seq()`) で組み立て、落ちるのと同時に小さな大きさから元の大きさへ広げる。
+/// 落ちている間のブロックは `Collider` を外しておき、落ちきったときにワンショットのシステムで付け直す。
+pub struct IntroPlugin;
+
+impl Plugin for IntroPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(SequencerPlugin)
+            .init_resource::<IntroCallbacks>()
+            .add_systems(Update, drop_in_bricks.before(SequencerSet));
+    }
+}
+
+/// レベルの始まりに落とすブロックと、マス目の位置 (落ち始める順番に使う)
+#[derive(Component)]
+pub struct DropIn {
+    pub column: usize,
+    pub row: usize,
+}
+
+/// タイムラインの最後に実行するワンショットのシステム
+#[derive(Resource)]
+struct IntroCallbacks {
+    landed: SystemId<In<Entity>>,
+}
+
+impl FromWorld for IntroCallbacks {
+    fn from_world(world: &mut World) -> Self {
+        IntroCallbacks {
+            landed: world.register_system(brick_landed),
+        }
+    }
+}
+
+/// 並べたばかりのブロックを持ち上げて縮め、元の位置と大きさへ弾みながら戻るタイムラインを付ける
+fn drop_in_bricks(
+    mut commands: Commands,
+    callbacks: Res<IntroCallbacks>,
+    mut bricks: Query<(Entity, &DropIn, &mut Transform), Added<DropIn>>,
+) {
+    for (entity, drop_in, mut transform) in &mut bricks {
+        let home = transform.translation;
+        let size = transform.scale;
+        transform.translation.y += DROP_HEIGHT;
+        transform.scale = size * Vec3::new(START_SCALE, START_SCALE, 1.0);
+
+        let delay = ROW_DELAY * drop_in.row as f32 + COLUMN_DELAY * drop_in.column as f32;
+        commands
+            .entity(entity)
+            .remove::<(DropIn, Collider)>()
+            .insert(
+                seq()
+                    .wait(delay)
+                    .parallel([
+                        seq()
+                            .move_to(home, DROP_SECONDS)
+                            .ease(EaseFunction::BounceOut),
+                        seq()
+                            .scale_to(size, GROW_SECONDS)
+                            .ease(EaseFunction::BackOut),
+                    ])
+                    .call(callbacks.landed)
+                    .build(),
+            );
+    }
+}
+
+/// 落ちきったブロックに `Collider` を付け直し、ボールが当たるようにする
+fn brick_landed(In(entity): In<Entity>, mut commands: Commands) {
+    // 落ちている間にゲームオーバーになって消えていることもある
+    if let Some(mut brick) = commands.get_entity(entity) {
+        brick.try_insert(Collider);
+    }
+}
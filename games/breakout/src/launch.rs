@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+
+use crate::{
+    attract::is_attract_mode,
+    ball::{check_for_collisions, Ball, Velocity, INITIAL_BALL_DIRECTION},
+    difficulty::Difficulty,
+    game_over::{check_ball_lost, BallLost},
+    input_map::{key_label, InputMap},
+    is_single_player,
+    locale::{LocalizedFont, Strings},
+    save::restore_saved_run,
+    theme::{ThemeColor, Themed},
+    victory::PlayPhase,
+};
+
+// 打ち出しの案内のフォントサイズ
+const HINT_FONT_SIZE: f32 = 22.0;
+
+/// 1 人用のルールで、レベルの開始時とボールを落とした後に、打ち出しキーを押すまでボールを止めておくプラグイン
+///
+/// 止めている間はボールの速度を 0 にして、打ち出すときの速度を [`AwaitingLaunch`] に取っておく。
+/// 打ち出しキーは [`InputMap`] の `launch` (既定は `Space`) で、画面に触れても打ち出せる。
+/// 対戦モードとデモプレイではボールを止めない。
+pub struct LaunchPlugin;
+
+impl Plugin for LaunchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_launch_hint)
+            // 盤面を作り直すシステム (`OnEnter(GameState::Playing)` と `OnTransition`) の後に実行される。
+            // 保存した盤面から再開するときは、戻したボールの速度で打ち出せるように戻した後に止める
+            .add_systems(
+                OnEnter(PlayPhase::Running),
+                hold_ball
+                    .after(restore_saved_run)
+                    .run_if(is_single_player.and(not(is_attract_mode))),
+            )
+            .add_systems(
+                FixedUpdate,
+                hold_lost_ball
+                    .after(check_ball_lost)
+                    .after(check_for_collisions)
+                    .run_if(is_single_player.and(not(is_attract_mode))),
+            )
+            .add_systems(
+                Update,
+                (
+                    launch_ball.run_if(in_state(PlayPhase::Running)),
+                    update_launch_hint,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// 打ち出しを待っているボール (打ち出すときの速度)
+#[derive(Component)]
+pub struct AwaitingLaunch(Vec2);
+
+/// 打ち出しの案内のテキスト
+#[derive(Component)]
+struct LaunchHint;
+
+/// ボールを止めて、打ち出しを待つ状態にする
+///
+/// 打ち出すときの速度は今の速度にする。すでに止めていたボールはそのときの速度を引き継ぎ、
+/// 保存した盤面から再開したときなど、どちらも 0 のボールは難易度に合わせたサーブの速さで打ち出す。
+fn hold(
+    commands: &mut Commands,
+    entity: Entity,
+    velocity: &mut Velocity,
+    awaiting: Option<&AwaitingLaunch>,
+    difficulty: &Difficulty,
+) {
+    let serve = match awaiting {
+        _ if velocity.0 != Vec2::ZERO => velocity.0,
+        Some(AwaitingLaunch(serve)) => *serve,
+        None => INITIAL_BALL_DIRECTION.normalize() * difficulty.ball_speed(),
+    };
+    velocity.0 = Vec2::ZERO;
+    commands.entity(entity).insert(AwaitingLaunch(serve));
+}
+
+/// レベルが始まったら (保存した盤面から再開したときも) ボールを止める
+fn hold_ball(
+    mut commands: Commands,
+    difficulty: Res<Difficulty>,
+    mut balls: Query<(Entity, &mut Velocity, Option<&AwaitingLaunch>), With<Ball>>,
+) {
+    for (entity, mut velocity, awaiting) in &mut balls {
+        hold(&mut commands, entity, &mut velocity, awaiting, &difficulty);
+    }
+}
+
+/// ボールを落としたら、開始位置に戻ったボールを止める (残機がなくなったときはゲームオーバーになるので止めない)
+fn hold_lost_ball(
+    mut commands: Commands,
+    mut lost_events: EventReader<BallLost>,
+    difficulty: Res<Difficulty>,
+    mut balls: Query<(Entity, &mut Velocity, Option<&AwaitingLaunch>), With<Ball>>,
+) {
+    let lost = lost_events.read().any(|event| event.lives_left > 0);
+    if !lost {
+        return;
+    }
+    for (entity, mut velocity, awaiting) in &mut balls {
+        hold(&mut commands, entity, &mut velocity, awaiting, &difficulty);
+    }
+}
+
+/// 打ち出しキーか画面のタッチで、止めておいたボールを打ち出す
+fn launch_ball(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    touches: Res<Touches>,
+    input_map: Res<InputMap>,
+    mut balls: Query<(Entity, &mut Velocity, &AwaitingLaunch)>,
+) {
+    if !(keyboard_input.just_pressed(input_map.launch) || touches.any_just_pressed()) {
+        return;
+    }
+    for (entity, mut velocity, awaiting) in &mut balls {
+        velocity.0 = awaiting.0;
+        commands.entity(entity).remove::<AwaitingLaunch>();
+    }
+}
+
+/// 打ち出しの案内を配置する (ボールを止めている間だけ表示する)
+fn spawn_launch_hint(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: HINT_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Hint),
+        LocalizedFont,
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(30.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        LaunchHint,
+    ));
+}
+
+/// プレイ中にボールを止めている間だけ案内を表示し、割り当てたキーや言語が変わったら文字列を作り直す
+fn update_launch_hint(
+    strings: Strings,
+    input_map: Res<InputMap>,
+    state: Option<Res<State<PlayPhase>>>,
+    waiting: Query<(), With<AwaitingLaunch>>,
+    hint: Single<(&mut Text, &mut Visibility), With<LaunchHint>>,
+) {
+    let (mut text, mut visibility) = hint.into_inner();
+    let running = state.is_some_and(|state| *state.get() == PlayPhase::Running);
+    visibility.set_if_neq(if running && !waiting.is_empty() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    if strings.is_changed() || input_map.is_changed() || text.0.is_empty() {
+        text.0 = strings.format("launch_hint", &[("key", &key_label(input_map.launch))]);
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+use serde::{Deserialize, Serialize};
+use settings::Profile;
+
+use crate::{
+    attract::is_attract_mode,
+    daily::is_daily,
+    game_over::{spawn_game_over_screen, GameOverScreen},
+    generator::LayoutSeed,
+    locale::{LocalizedFont, Strings},
+    records::Records,
+    theme::{ThemeColor, Themed},
+    GameState, Level, Score,
+};
+
+// リーダーボードのエンドポイントを指定する環境変数と起動引数
+const ENDPOINT_ENV: &str = "BREAKOUT_LEADERBOARD_URL";
+const ENDPOINT_ARG: &str = "--leaderboard=";
+// 表示する上位の件数
+const TOP_COUNT: usize = 10;
+// 接続・送受信のタイムアウト
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(3);
+// リーダーボードの表示のフォントサイズ
+const LEADERBOARD_FONT_SIZE: f32 = 18.0;
+
+/// ゲームオーバー時にスコアをオンラインのリーダーボードへ送信し、上位 10 件を表示するプラグイン
+///
+/// エンドポイントが設定されていなければ何もしない。通信は [`IoTaskPool`] のタスクで行い、
+/// 結果は毎フレームポーリングするだけなので、応答を待つ間もゲームループは止まらない。
+/// 通信に失敗した場合はローカルのハイスコアを代わりに表示する。
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LeaderboardConfig::from_env_and_args())
+            .init_resource::<LeaderboardStatus>()
+            .init_resource::<LeaderboardRequest>()
+            // デイリーチャレンジの得点は通常の得点と比べられないので送信しない
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (
+                    submit_score.run_if(not(is_attract_mode)),
+                    spawn_leaderboard_ui.after(spawn_game_over_screen),
+                )
+                    .run_if(not(is_daily)),
+            )
+            .add_systems(
+                Update,
+                (poll_request, update_leaderboard_ui)
+                    .chain()
+                    .run_if(in_state(GameState::GameOver))
+                    .run_if(not(is_daily)),
+            )
+            .add_systems(OnExit(GameState::GameOver), cancel_request);
+    }
+}
+
+/// リーダーボードの接続先
+///
+/// `BREAKOUT_LEADERBOARD_URL=http://host:port/path` か `--leaderboard=http://...` で指定する。
+#[derive(Resource, Debug, Clone)]
+struct LeaderboardConfig {
+    endpoint: Option<String>,
+}
+
+impl LeaderboardConfig {
+    /// 起動引数を優先し、なければ環境変数から接続先を読み込む
+    fn from_env_and_args() -> LeaderboardConfig {
+        let from_args =
+            std::env::args().find_map(|arg| arg.strip_prefix(ENDPOINT_ARG).map(str::to_string));
+        LeaderboardConfig {
+            endpoint: from_args
+                .or_else(|| std::env::var(ENDPOINT_ENV).ok())
+                .filter(|url| !url.is_empty()),
+        }
+    }
+}
+
+/// リーダーボードの 1 件分 (送信するスコアと同じ形式)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ScoreEntry {
+    name: String,
+    score: usize,
+    level: u32,
+    // ブロックの配置のシード値 (普通の配置で遊んだときは `null`。古いエントリーにはないこともある)
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+/// リーダーボードの取得状況
+#[derive(Resource, Debug, Default)]
+enum LeaderboardStatus {
+    #[default]
+    Disabled, // 接続先が設定されていない
+    Loading,                 // 送信・取得中
+    Loaded(Vec<ScoreEntry>), // 上位のスコア
+    Offline(String),         // 通信に失敗した (理由)
+}
+
+/// 実行中の通信タスク
+#[derive(Resource, Default)]
+struct LeaderboardRequest(Option<Task<Result<Vec<ScoreEntry>, String>>>);
+
+/// リーダーボードの表示を示すコンポーネント
+#[derive(Component)]
+struct LeaderboardUi;
+
+/// 最終スコアを送信し、上位のスコアを取得するタスクを開始する
+fn submit_score(
+    config: Res<LeaderboardConfig>,
+    score: Res<Score>,
+    level: Res<Level>,
+    seed: Res<LayoutSeed>,
+    profile: Res<Profile>,
+    mut status: ResMut<LeaderboardStatus>,
+    mut request: ResMut<LeaderboardRequest>,
+) {
+    let Some(endpoint) = config.endpoint.clone() else {
+        *status = LeaderboardStatus::Disabled;
+        return;
+    };
+    let entry = ScoreEntry {
+        name: profile.name().to_string(),
+        score: **score,
+        level: **level,
+        seed: seed.0,
+    };
+
+    // 通信自体はブロッキングだが、I/O 用のスレッドで実行されるのでメインスレッドは待たない
+    request.0 = Some(IoTaskPool::get().spawn(async move { submit_and_fetch(&endpoint, &entry) }));
+    *status = LeaderboardStatus::Loading;
+}
+
+/// タスクが終わっていれば結果を取り出す (終わっていなければすぐに戻る)
+fn poll_request(mut request: ResMut<LeaderboardRequest>, mut status: ResMut<LeaderboardStatus>) {
+    let Some(task) = request.0.as_mut() else {
+        return;
+    };
+    let Some(result) = block_on(future::poll_once(task)) else {
+        return;
+    };
+    request.0 = None;
+    *status = match result {
+        Ok(entries) => LeaderboardStatus::Loaded(entries),
+        Err(error) => {
+            warn!("leaderboard unavailable: {error}");
+            LeaderboardStatus::Offline(error)
+        }
+    };
+}
+
+/// ゲームオーバー画面を閉じたら、応答を待っているタスクを破棄する
+fn cancel_request(mut request: ResMut<LeaderboardRequest>) {
+    request.0 = None;
+}
+
+/// ゲームオーバー画面にリーダーボードの表示を追加する
+fn spawn_leaderboard_ui(mut commands: Commands, screen: Single<Entity, With<GameOverScreen>>) {
+    commands.entity(*screen).with_child((
+        Text::default(),
+        TextFont {
+            font_size: LEADERBOARD_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Text),
+        TextLayout::new_with_justify(JustifyText::Center),
+        LeaderboardUi,
+        LocalizedFont,
+    ));
+}
+
+/// 取得状況に応じてリーダーボードの表示を更新する
+fn update_leaderboard_ui(
+    status: Res<LeaderboardStatus>,
+    records: Res<Records>,
+    profile: Res<Profile>,
+    strings: Strings,
+    mut text: Single<&mut Text, With<LeaderboardUi>>,
+) {
+    if !status.is_changed() && !strings.is_changed() && !text.is_added() {
+        return;
+    }
+    text.0 = match &*status {
+        LeaderboardStatus::Disabled => {
+            strings.format("leaderboard_disabled", &[("env", &ENDPOINT_ENV)])
+        }
+        LeaderboardStatus::Loading => strings.get("leaderboard_loading").to_string(),
+        LeaderboardStatus::Loaded(entries) if entries.is_empty() => {
+            strings.get("leaderboard_empty").to_string()
+        }
+        LeaderboardStatus::Loaded(entries) => {
+            let mut lines = vec![strings.format("leaderboard_top", &[("count", &TOP_COUNT)])];
+            for (rank, entry) in entries.iter().take(TOP_COUNT).enumerate() {
+                let marker = if entry.name == profile.name() {
+                    " <"
+                } else {
+                    ""
+                };
+                lines.push(format!(
+                    "{:>2}. {}  {}  (Lv {}){marker}",
+                    rank + 1,
+                    entry.name,
+                    entry.score,
+                    entry.level
+                ));
+            }
+            lines.join("\n")
+        }
+        // 通信できなければローカルの記録だけを表示する
+        LeaderboardStatus::Offline(error) => strings.format(
+            "leaderboard_offline",
+            &[("error", error), ("best", &records.high_score)],
+        ),
+    };
+}
+
+/// スコアを送信してから上位のスコアを取得する
+///
+/// サーバーは `POST <endpoint>/scores` で [`ScoreEntry`] の JSON を受け取り、
+/// `GET <endpoint>/scores?limit=10` でスコアの高い順の JSON 配列を返すものとする。
+fn submit_and_fetch(endpoint: &str, entry: &ScoreEntry) -> Result<Vec<ScoreEntry>, String> {
+    let endpoint = Endpoint::parse(endpoint)?;
+    let body = serde_json::to_string(entry).map_err(|error| error.to_string())?;
+    endpoint.request("POST", "/scores", Some(&body))?;
+    let response = endpoint.request("GET", &format!("/scores?limit={TOP_COUNT}"), None)?;
+    serde_json::from_str(&response).map_err(|error| format!("invalid response: {error}"))
+}
+
+/// `http://host[:port][/path]` 形式の接続先
+struct Endpoint {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Endpoint {
+    fn parse(url: &str) -> Result<Endpoint, String> {
+        let Some(rest) = url.strip_prefix("http://") else {
+            return Err("only http:// endpoints are supported".to_string());
+        };
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse().map_err(|_| format!("invalid port '{port}'"))?,
+            ),
+            None => (authority, 80),
+        };
+        // 末尾の `/` は除き、`/scores` などをそのまま続けられる形にしておく
+        let path = path.trim_end_matches('/');
+        Ok(Endpoint {
+            host: host.to_string(),
+            port,
+            path: if path.is_empty() {
+                String::new()
+            } else {
+                format!("/{path}")
+            },
+        })
+    }
+
+    /// HTTP リクエストを 1 回送り、成功したら本文を返す
+    ///
+    /// 応答を `Connection: close` で最後まで読むだけで済むよう、HTTP/1.0 で送信する。
+    fn request(&self, method: &str, path: &str, body: Option<&str>) -> Result<String, String> {
+        let address = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|error| error.to_string())?
+            .next()
+            .ok_or_else(|| format!("could not resolve {}", self.host))?;
+        let mut stream = TcpStream::connect_timeout(&address, NETWORK_TIMEOUT)
+            .map_err(|error| error.to_string())?;
+        stream
+            .set_read_timeout(Some(NETWORK_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(NETWORK_TIMEOUT)))
+            .map_err(|error| error.to_string())?;
+
+        let body = body.unwrap_or("");
+        let request = format!(
+            "{method} {}{path} HTTP/1.0\r\n\
+             Host: {}\r\n\
+             Connection: close\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\r\n{body}",
+            self.path,
+            self.host,
+            body.len()
+        );
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|error| error.to_string())?;
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .map_err(|error| error.to_string())?;
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or("malformed response")?;
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or("malformed status line")?;
+        if !(200..300).contains(&status) {
+            return Err(format!("HTTP {status}"));
+        }
+        Ok(body.to_string())
+    }
+}
@@ -0,0 +1,305 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    ball::Collider,
+    bricks::{
+        Brick, BRICK_SIZE, GAP_BETWEEN_BRICKS, GAP_BETWEEN_BRICKS_AND_CEILING,
+        GAP_BETWEEN_BRICKS_AND_SIDES, GAP_BETWEEN_PADDLE_AND_BRICKS,
+    },
+    intro::DropIn,
+    paddle::GAP_BETWEEN_PADDLE_AND_FLOOR,
+    portals,
+    theme::{ThemeColor, Themed},
+    walls::{BOTTOM_WALL, LEFT_WALL, RIGHT_WALL},
+    GameState,
+};
+
+/// ブロックの配置 (`*.level.ron`) を読み込めるようにするプラグイン
+pub struct LevelsPlugin;
+
+impl Plugin for LevelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelLayout>()
+            .init_asset_loader::<LevelLayoutLoader>();
+    }
+}
+
+/// ブロックの種類
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BrickKind {
+    #[default]
+    Empty, // ブロックを置かない
+    Normal, // 1 回当てると壊れる
+    Sturdy, // 1 回目の衝突で普通のブロックになり、2 回目で壊れる
+    Portal, // ボールを相方のポータルへ移す (上の行から順に 2 つずつ組にする)
+}
+
+impl BrickKind {
+    /// レベルファイルでの文字
+    fn to_char(self) -> char {
+        match self {
+            BrickKind::Empty => '.',
+            BrickKind::Normal => '#',
+            BrickKind::Sturdy => '=',
+            BrickKind::Portal => 'O',
+        }
+    }
+
+    /// レベルファイルの文字から種類を決める (知らない文字は `None`)
+    fn from_char(c: char) -> Option<BrickKind> {
+        match c {
+            '.' | ' ' => Some(BrickKind::Empty),
+            '#' => Some(BrickKind::Normal),
+            '=' => Some(BrickKind::Sturdy),
+            'O' => Some(BrickKind::Portal),
+            _ => None,
+        }
+    }
+
+    /// エディタでクリックしたときの次の種類
+    pub fn next(self) -> BrickKind {
+        match self {
+            BrickKind::Empty => BrickKind::Normal,
+            BrickKind::Normal => BrickKind::Sturdy,
+            BrickKind::Sturdy => BrickKind::Portal,
+            BrickKind::Portal => BrickKind::Empty,
+        }
+    }
+
+    /// ブロックの色 (空のマスは `None`)
+    pub fn color(self) -> Option<ThemeColor> {
+        match self {
+            BrickKind::Empty => None,
+            BrickKind::Normal => Some(ThemeColor::Brick),
+            BrickKind::Sturdy => Some(ThemeColor::SturdyBrick),
+            BrickKind::Portal => Some(ThemeColor::Portal),
+        }
+    }
+
+    /// 壊すブロックかどうか (ポータルや空きマスは壊さなくてもレベルをクリアできる)
+    fn is_brick(self) -> bool {
+        matches!(self, BrickKind::Normal | BrickKind::Sturdy)
+    }
+}
+
+// 硬いブロックが壊れるまでに当てる回数
+pub const STURDY_HITS: u32 = 2;
+
+/// 1 回目の衝突ではまだ壊れないブロックを示すコンポーネント
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Sturdy;
+
+/// ブロックを並べられるマス目
+///
+/// 列は左から、行は上から数える。
+#[derive(Debug, Clone, Copy)]
+pub struct BrickGrid {
+    pub columns: usize,
+    pub rows: usize,
+    top_left: Vec2, // 左上のマスの中心
+}
+
+impl BrickGrid {
+    /// `ceiling` (ブロックを配置できる領域の上端の y 座標) の下に収まるマス目
+    pub fn new(ceiling: f32) -> BrickGrid {
+        let paddle_y = BOTTOM_WALL + GAP_BETWEEN_PADDLE_AND_FLOOR;
+        let total_width_of_bricks = (RIGHT_WALL - LEFT_WALL) - 2. * GAP_BETWEEN_BRICKS_AND_SIDES; // ブロックの幅
+        let bottom_edge_of_bricks = paddle_y + GAP_BETWEEN_PADDLE_AND_BRICKS; // ブロックの下端位置
+        let total_height_of_bricks =
+            ceiling - bottom_edge_of_bricks - GAP_BETWEEN_BRICKS_AND_CEILING; // ブロックの高さ
+
+        assert!(total_width_of_bricks > 0.0); // 幅が0以下でないことを確認
+        assert!(total_height_of_bricks > 0.0); // 高さが0以下でないことを確認
+
+        // 利用可能なスペースに基づいて、ブロックを配置できる行数と列数を計算
+        let columns =
+            (total_width_of_bricks / (BRICK_SIZE.x + GAP_BETWEEN_BRICKS)).floor() as usize;
+        let rows = (total_height_of_bricks / (BRICK_SIZE.y + GAP_BETWEEN_BRICKS)).floor() as usize;
+
+        // 列数を丸めたため、ブロックの左右に配置されるスペースは下限値を表す
+        let center_of_bricks = (LEFT_WALL + RIGHT_WALL) / 2.0;
+        let left_edge_of_bricks = center_of_bricks
+            - (columns as f32 / 2.0 * BRICK_SIZE.x)
+            - (columns - 1) as f32 / 2.0 * GAP_BETWEEN_BRICKS;
+
+        // ブロックは下端から積み上げるので、左上のマスの位置は行数から決まる
+        BrickGrid {
+            columns,
+            rows,
+            top_left: Vec2::new(
+                left_edge_of_bricks + BRICK_SIZE.x / 2.,
+                bottom_edge_of_bricks
+                    + BRICK_SIZE.y / 2.
+                    + (rows - 1) as f32 * (BRICK_SIZE.y + GAP_BETWEEN_BRICKS),
+            ),
+        }
+    }
+
+    /// マスの中心の位置
+    pub fn cell_center(&self, column: usize, row: usize) -> Vec2 {
+        self.top_left
+            + Vec2::new(
+                column as f32 * (BRICK_SIZE.x + GAP_BETWEEN_BRICKS),
+                -(row as f32) * (BRICK_SIZE.y + GAP_BETWEEN_BRICKS),
+            )
+    }
+
+    /// 位置を含むマスの (列, 行)。マス目の外なら `None`
+    ///
+    /// ブロック間の隙間は、近い方のマスに含める。
+    pub fn cell_at(&self, position: Vec2) -> Option<(usize, usize)> {
+        let pitch = BRICK_SIZE + Vec2::splat(GAP_BETWEEN_BRICKS);
+        let offset = (position - self.top_left) * Vec2::new(1.0, -1.0) / pitch + Vec2::splat(0.5);
+        if offset.x < 0.0 || offset.y < 0.0 {
+            return None;
+        }
+        let (column, row) = (offset.x as usize, offset.y as usize);
+        (column < self.columns && row < self.rows).then_some((column, row))
+    }
+}
+
+/// ブロックの配置 (`*.level.ron`)
+///
+/// `rows` は上の行から順に並べた文字列で、`#` が普通のブロック、`=` が硬いブロック、
+/// `O` がポータル、`.` が空きマス。
+#[derive(Asset, TypePath, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LevelLayout {
+    rows: Vec<String>,
+}
+
+impl LevelLayout {
+    /// ブロックを 1 つも置いていない配置 (`set` でブロックを置いていく)
+    pub fn empty() -> LevelLayout {
+        LevelLayout { rows: Vec::new() }
+    }
+
+    /// すべてのマスに普通のブロックを置いた配置
+    pub fn filled(grid: &BrickGrid) -> LevelLayout {
+        LevelLayout {
+            rows: vec![BrickKind::Normal.to_char().to_string().repeat(grid.columns); grid.rows],
+        }
+    }
+
+    /// マスのブロックの種類 (配置の外や知らない文字は空きマスとして扱う)
+    pub fn get(&self, column: usize, row: usize) -> BrickKind {
+        self.rows
+            .get(row)
+            .and_then(|line| line.chars().nth(column))
+            .and_then(BrickKind::from_char)
+            .unwrap_or_default()
+    }
+
+    /// マスのブロックの種類を変更する (足りない行や列は空きマスで埋める)
+    pub fn set(&mut self, column: usize, row: usize, kind: BrickKind) {
+        if self.rows.len() <= row {
+            self.rows.resize(row + 1, String::new());
+        }
+        let mut line: Vec<char> = self.rows[row].chars().collect();
+        if line.len() <= column {
+            line.resize(column + 1, BrickKind::Empty.to_char());
+        }
+        line[column] = kind.to_char();
+        self.rows[row] = line.into_iter().collect();
+    }
+
+    /// 壊すブロックが 1 つも置かれていないかどうか (ポータルだけではレベルにならない)
+    pub fn is_empty(&self) -> bool {
+        self.rows
+            .iter()
+            .flat_map(|line| line.chars())
+            .all(|c| !BrickKind::from_char(c).unwrap_or_default().is_brick())
+    }
+
+    /// レベルファイルの内容
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        let config = ron::ser::PrettyConfig::default();
+        Ok(format!(
+            "// `#`: 普通のブロック, `=`: 硬いブロック, `O`: ポータル, `.`: 空きマス (上の行から順に並べる)\n{}\n",
+            ron::ser::to_string_pretty(self, config)?
+        ))
+    }
+}
+
+/// レベルファイルの読み込み時に発生するエラー
+#[derive(Debug, Error)]
+pub enum LevelLayoutLoaderError {
+    #[error("could not read level: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse level: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+/// RON 形式のレベルファイルを読み込むアセットローダー
+#[derive(Default)]
+struct LevelLayoutLoader;
+
+impl AssetLoader for LevelLayoutLoader {
+    type Asset = LevelLayout;
+    type Settings = ();
+    type Error = LevelLayoutLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<LevelLayout, LevelLayoutLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// 配置に従ってマス目にブロックを並べる (マス目からはみ出した分は無視する)
+///
+/// ポータルは上の行から (同じ行では左から) 順に 2 つずつ組にして置き、組にならない最後の 1 つは置かない。
+pub fn spawn_layout(commands: &mut Commands, grid: &BrickGrid, layout: &LevelLayout) {
+    let mut portals = Vec::new();
+    for row in 0..grid.rows {
+        for column in 0..grid.columns {
+            let kind = layout.get(column, row);
+            if kind == BrickKind::Portal {
+                portals.push(grid.cell_center(column, row));
+                continue;
+            }
+            let Some(color) = kind.color() else {
+                continue;
+            };
+
+            // 各ブロックのエンティティを生成
+            let mut brick = commands.spawn((
+                Sprite::default(),
+                Themed(color), // ブロックの色
+                Transform {
+                    translation: grid.cell_center(column, row).extend(0.0), // 位置
+                    scale: BRICK_SIZE.extend(1.0),                          // サイズ
+                    ..default()
+                },
+                Brick,                           // ブロックコンポーネント
+                Collider,                        // 衝突判定用コンポーネント
+                StateScoped(GameState::Playing), // ゲームオーバーになったら消す
+                DropIn { column, row },          // レベルの始まりに上から落とす
+            ));
+            if kind == BrickKind::Sturdy {
+                brick.insert(Sturdy);
+            }
+        }
+    }
+
+    if portals.len() % 2 == 1 {
+        warn!("the last portal has no partner and is left out");
+    }
+    for (pair, ends) in portals.chunks_exact(2).enumerate() {
+        portals::spawn_pair(commands, pair as u32, ends[0], ends[1]);
+    }
+}
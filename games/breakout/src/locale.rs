@@ -0,0 +1,320 @@
+use std::{collections::HashMap, fmt::Display};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use debug_stepping::SteppingLabels;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::theme::{ThemeColor, Themed};
+
+// 言語を切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::KeyL;
+// 言語切り替えのヒントのフォントサイズ
+const HINT_FONT_SIZE: f32 = 15.0;
+
+/// UI の文字列を日本語と英語で切り替えるプラグイン
+///
+/// 文字列は言語ごとの文字列テーブル (`assets/locale/*.strings.ron`) から読み込む。
+/// 固定の文字列は [`Localized`] を付けたテキストに自動で反映され、
+/// 値を埋め込む文字列は各システムが [`Strings`] を通して取得する。
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<StringTable>()
+            .init_asset_loader::<StringTableLoader>()
+            .init_resource::<Locale>()
+            .add_systems(PreStartup, load_string_tables)
+            .add_systems(Startup, spawn_language_hint)
+            .add_systems(
+                Update,
+                (
+                    toggle_locale,
+                    refresh_on_load,
+                    apply_locale,
+                    apply_stepping_labels,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// 表示に使う言語
+///
+/// 言語が切り替わったときと文字列テーブルの読み込みが終わったときに変更扱いになるので、
+/// 文字列を組み立てるシステムは `Res<Locale>` の `is_changed()` で作り直せばよい。
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    Japanese,
+}
+
+impl Locale {
+    /// 文字列テーブルのパス
+    fn table_path(self) -> &'static str {
+        match self {
+            Locale::English => "locale/en.strings.ron",
+            Locale::Japanese => "locale/ja.strings.ron",
+        }
+    }
+}
+
+/// 1 つの言語の文字列テーブル (`*.strings.ron`)
+#[derive(Asset, TypePath, Debug)]
+pub struct StringTable {
+    font: Option<Handle<Font>>, // この言語の表示に使うフォント (なければ既定のフォント)
+    strings: HashMap<String, String>, // キーごとの文字列
+}
+
+/// 文字列テーブルのファイルの形式
+#[derive(Deserialize)]
+struct StringTableFile {
+    #[serde(default)]
+    font: Option<String>, // `assets` からのフォントのパス
+    strings: HashMap<String, String>,
+}
+
+/// 文字列テーブルの読み込み時に発生するエラー
+#[derive(Debug, Error)]
+pub enum StringTableLoaderError {
+    #[error("could not read string table: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse string table: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+/// RON 形式の文字列テーブルを読み込むアセットローダー
+#[derive(Default)]
+struct StringTableLoader;
+
+impl AssetLoader for StringTableLoader {
+    type Asset = StringTable;
+    type Settings = ();
+    type Error = StringTableLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<StringTable, StringTableLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: StringTableFile = ron::de::from_bytes(&bytes)?;
+        Ok(StringTable {
+            // フォントは依存アセットとして読み込む (見つからなくてもテーブル自体は使える)
+            font: file.font.map(|path| load_context.load(path)),
+            strings: file.strings,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["strings.ron"]
+    }
+}
+
+/// 読み込んだ文字列テーブルのハンドル
+#[derive(Resource)]
+struct StringTables {
+    english: Handle<StringTable>,
+    japanese: Handle<StringTable>,
+}
+
+impl StringTables {
+    fn get(&self, locale: Locale) -> &Handle<StringTable> {
+        match locale {
+            Locale::English => &self.english,
+            Locale::Japanese => &self.japanese,
+        }
+    }
+}
+
+/// 現在の言語の文字列を取得するためのシステムパラメータ
+#[derive(SystemParam)]
+pub struct Strings<'w> {
+    locale: Res<'w, Locale>,
+    tables: Res<'w, StringTables>,
+    assets: Res<'w, Assets<StringTable>>,
+    asset_server: Res<'w, AssetServer>,
+}
+
+impl Strings<'_> {
+    /// キーに対応する文字列 (現在の言語になければ英語、それもなければキーそのもの)
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        [*self.locale, Locale::English]
+            .into_iter()
+            .filter_map(|locale| self.assets.get(self.tables.get(locale)))
+            .find_map(|table| table.strings.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// キーに対応する文字列の `{name}` を値に置き換える
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+
+    /// 現在の言語のフォント (読み込めていなければ既定のフォント)
+    pub fn font(&self) -> Handle<Font> {
+        self.assets
+            .get(self.tables.get(*self.locale))
+            .and_then(|table| table.font.clone())
+            .filter(|font| self.asset_server.is_loaded(font))
+            .unwrap_or_default()
+    }
+
+    /// 言語が切り替わったか、文字列テーブルが読み込まれたかどうか
+    pub fn is_changed(&self) -> bool {
+        self.locale.is_changed()
+    }
+}
+
+/// 現在の言語のフォントを使うテキストを示すコンポーネント
+#[derive(Component, Default)]
+pub struct LocalizedFont;
+
+/// 文字列テーブルのキーで内容を指定するテキスト ([`Text`] か [`TextSpan`] に付ける)
+#[derive(Component)]
+#[require(LocalizedFont)]
+pub struct Localized {
+    key: &'static str,
+    args: Vec<(&'static str, String)>, // `{name}` に埋め込む値
+}
+
+impl Localized {
+    pub fn new(key: &'static str) -> Localized {
+        Localized {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    /// 文字列の `{name}` に埋め込む値を追加する
+    pub fn with_arg(mut self, name: &'static str, value: impl Display) -> Localized {
+        self.args.push((name, value.to_string()));
+        self
+    }
+}
+
+/// 英語と日本語の文字列テーブルを読み込む
+fn load_string_tables(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(StringTables {
+        english: asset_server.load(Locale::English.table_path()),
+        japanese: asset_server.load(Locale::Japanese.table_path()),
+    });
+}
+
+/// 画面右下に言語切り替えのヒントを配置する
+fn spawn_language_hint(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: HINT_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Hint),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(5.0),
+            right: Val::Px(5.0),
+            ..default()
+        },
+        Localized::new("language_hint"),
+    ));
+}
+
+/// `L` キーで言語を切り替える
+fn toggle_locale(keyboard_input: Res<ButtonInput<KeyCode>>, mut locale: ResMut<Locale>) {
+    if keyboard_input.just_pressed(TOGGLE_KEY) {
+        *locale = match *locale {
+            Locale::English => Locale::Japanese,
+            Locale::Japanese => Locale::English,
+        };
+        info!("locale: {:?}", *locale);
+    }
+}
+
+/// 文字列テーブルやフォントの読み込みが終わったら、表示を作り直させる
+///
+/// 実行中に文字列テーブルを書き換えた場合 (ホットリロード) も同じように反映される。
+fn refresh_on_load(
+    mut table_events: EventReader<AssetEvent<StringTable>>,
+    mut font_events: EventReader<AssetEvent<Font>>,
+    mut locale: ResMut<Locale>,
+) {
+    // フォントが見つからなくても文字列だけは使えるよう、依存アセットの完了は待たない
+    let tables_loaded = table_events
+        .read()
+        .filter(|event| {
+            matches!(
+                event,
+                AssetEvent::Added { .. } | AssetEvent::Modified { .. }
+            )
+        })
+        .count();
+    let fonts_loaded = font_events
+        .read()
+        .filter(|event| matches!(event, AssetEvent::LoadedWithDependencies { .. }))
+        .count();
+    if tables_loaded + fonts_loaded > 0 {
+        locale.set_changed();
+    }
+}
+
+/// [`Localized`] の文字列と [`LocalizedFont`] のフォントを現在の言語に合わせる
+#[allow(clippy::type_complexity)]
+fn apply_locale(
+    strings: Strings,
+    mut texts: Query<(
+        Ref<LocalizedFont>,
+        Option<&Localized>,
+        Option<&mut Text>,
+        Option<&mut TextSpan>,
+        &mut TextFont,
+    )>,
+) {
+    let refresh_all = strings.is_changed();
+    let font = strings.font();
+    for (marker, localized, text, span, mut text_font) in &mut texts {
+        if !refresh_all && !marker.is_added() {
+            continue;
+        }
+        text_font.font = font.clone();
+
+        let Some(localized) = localized else {
+            continue;
+        };
+        let args: Vec<(&str, &dyn Display)> = localized
+            .args
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn Display))
+            .collect();
+        let value = strings.format(localized.key, &args);
+        if let Some(mut text) = text {
+            text.0 = value;
+        } else if let Some(mut span) = span {
+            span.0 = value;
+        }
+    }
+}
+
+/// ステッピング UI (`crates/debug_stepping`) の文字列とフォントを現在の言語に合わせる
+///
+/// ステッピング UI の文字列は、文字列テーブルの同じキー (`stepping_*`) から取る。
+fn apply_stepping_labels(strings: Strings, mut labels: ResMut<SteppingLabels>) {
+    if !strings.is_changed() {
+        return;
+    }
+    for key in SteppingLabels::KEYS {
+        labels.set(key, strings.get(key));
+    }
+    labels.set_font(strings.font());
+}
@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{apply_velocity, Acceleration, Ball, Velocity},
+    boss::Stunned,
+    drops::{Drop, DropCollected},
+    is_single_player,
+    paddle::{Paddle, SecondPaddle},
+    theme::{ThemeColor, Themed},
+    GameState,
+};
+
+// 磁力を使うキー (1P パドル)
+const MAGNET_KEY: KeyCode = KeyCode::ArrowDown;
+// 磁力でボールに与える加速度の大きさ
+const MAGNET_ACCELERATION: f32 = 600.0;
+// 磁場のパワーアップが続く時間 (秒) と、切れる前に点滅させ始める残り時間 (秒)
+const FIELD_SECONDS: f32 = 10.0;
+const FIELD_WARNING_SECONDS: f32 = 2.0;
+// 磁場がボールを引き寄せる高さ (パドルの中心から) と、加速度の大きさ (キーで使う磁力より弱くする)
+const FIELD_HEIGHT: f32 = 220.0;
+const FIELD_ACCELERATION: f32 = 250.0;
+
+/// 1P パドルがボールを引き寄せる磁力を追加するプラグイン
+///
+/// `↓` キーを押している間と、磁石のドロップ (`drops.rs`) を拾ってから `FIELD_SECONDS` 秒の間
+/// (磁場をまとっている間は、パドルから `FIELD_HEIGHT` より下で落ちてくるボールだけを弱く引き寄せる) に働く。
+/// 引き寄せる力は [`Acceleration`] としてボールに加え、`apply_velocity` が速度に反映する。
+/// 進行方向に垂直な成分だけを使い、ボールの速さは変えずに軌道だけを曲げる。
+/// 磁場の残り時間も固定ステップの経過時間で減らすので、同じ入力なら同じステップで切れる。
+pub struct MagnetPlugin;
+
+impl Plugin for MagnetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                (activate_field, expire_field).chain(),
+                (attract_ball, attract_with_field).before(apply_velocity),
+            )
+                .chain()
+                .run_if(is_single_player),
+        )
+        .add_systems(Update, show_field.run_if(is_single_player));
+    }
+}
+
+/// パドルがまとっている磁場 (残り時間)
+#[derive(Component)]
+struct MagnetField(Timer);
+
+/// ボールを `target` へ引き寄せる、進行方向に垂直な加速度 (`strength` は引き寄せる力の大きさ)
+///
+/// 垂直な力だけでも 1 ステップごとにわずかに速くなるので、進行方向の成分で打ち消して速さを保つ。
+fn curving_acceleration(
+    position: Vec2,
+    velocity: Vec2,
+    target: Vec2,
+    strength: f32,
+    dt: f32,
+) -> Vec2 {
+    let (Some(toward_target), Some(forward)) = (
+        (target - position).try_normalize(),
+        velocity.try_normalize(),
+    ) else {
+        return Vec2::ZERO;
+    };
+
+    let pull = toward_target * strength;
+    let sideways = pull - forward * pull.dot(forward);
+
+    // |v + (a_垂直 + t * 進行方向) * dt| = |v| となる t を求める
+    let speed = velocity.length();
+    let sideways_dv = sideways.length() * dt;
+    let along = ((speed * speed - sideways_dv * sideways_dv).max(0.0).sqrt() - speed) / dt;
+
+    sideways + forward * along
+}
+
+/// キーを押している間、パドルに向かう力のうち進行方向に垂直な成分をボールの加速度に加える
+///
+/// パドルがボスの攻撃で動けないときは使えない。
+#[allow(clippy::type_complexity)]
+fn attract_ball(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    paddle: Option<Single<&Transform, (With<Paddle>, Without<SecondPaddle>, Without<Stunned>)>>,
+    ball: Single<(&Transform, &Velocity, &mut Acceleration), With<Ball>>,
+) {
+    let Some(paddle) = paddle else {
+        return;
+    };
+    if !keyboard_input.pressed(MAGNET_KEY) {
+        return;
+    }
+
+    let (ball_transform, velocity, mut acceleration) = ball.into_inner();
+    acceleration.0 += curving_acceleration(
+        ball_transform.translation.truncate(),
+        velocity.0,
+        paddle.translation.truncate(),
+        MAGNET_ACCELERATION,
+        time.delta_secs(),
+    );
+}
+
+/// 磁石のドロップを拾ったら磁場をまとわせる (まとっている間に拾うと残り時間を元に戻す)
+fn activate_field(
+    mut commands: Commands,
+    mut collected_events: EventReader<DropCollected>,
+    mut fields: Query<&mut MagnetField>,
+) {
+    let collected = collected_events
+        .read()
+        .any(|DropCollected(drop)| *drop == Drop::Magnet);
+    if !collected {
+        return;
+    }
+    if let Ok(mut field) = fields.get_single_mut() {
+        field.0.reset();
+        return;
+    }
+    commands.spawn((
+        Sprite::default(),
+        Themed(ThemeColor::MagnetField),
+        // 位置と大きさは `show_field` がパドルに合わせる
+        Transform::from_xyz(0.0, 0.0, -0.5),
+        MagnetField(Timer::from_seconds(FIELD_SECONDS, TimerMode::Once)),
+        StateScoped(GameState::Playing),
+    ));
+}
+
+/// 磁場の残り時間を減らし、切れたら消す
+fn expire_field(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut fields: Query<(Entity, &mut MagnetField)>,
+) {
+    for (entity, mut field) in &mut fields {
+        if field.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// 磁場をまとっている間、パドルから `FIELD_HEIGHT` より下で落ちてくるボールを弱く引き寄せる
+///
+/// 打ち返したあとの上向きのボールは曲げないので、狙って打ったコースは変わらない。
+#[allow(clippy::type_complexity)]
+fn attract_with_field(
+    time: Res<Time>,
+    fields: Query<(), With<MagnetField>>,
+    paddle: Option<Single<&Transform, (With<Paddle>, Without<SecondPaddle>, Without<Stunned>)>>,
+    ball: Single<(&Transform, &Velocity, &mut Acceleration), With<Ball>>,
+) {
+    let Some(paddle) = paddle else {
+        return;
+    };
+    if fields.is_empty() {
+        return;
+    }
+
+    let (ball_transform, velocity, mut acceleration) = ball.into_inner();
+    if velocity.y >= 0.0 || ball_transform.translation.y > paddle.translation.y + FIELD_HEIGHT {
+        return;
+    }
+    acceleration.0 += curving_acceleration(
+        ball_transform.translation.truncate(),
+        velocity.0,
+        paddle.translation.truncate(),
+        FIELD_ACCELERATION,
+        time.delta_secs(),
+    );
+}
+
+/// 磁場のスプライトを、パドルの上から引き寄せる高さまでの範囲に重ねる (切れる前は点滅させる)
+#[allow(clippy::type_complexity)]
+fn show_field(
+    time: Res<Time>,
+    paddle: Option<Single<&Transform, (With<Paddle>, Without<SecondPaddle>)>>,
+    mut fields: Query<(&MagnetField, &mut Transform, &mut Visibility), Without<Paddle>>,
+) {
+    let Some(paddle) = paddle else {
+        return;
+    };
+    for (field, mut transform, mut visibility) in &mut fields {
+        transform.translation.x = paddle.translation.x;
+        transform.translation.y = paddle.translation.y + FIELD_HEIGHT / 2.0;
+        transform.scale = Vec3::new(paddle.scale.x, FIELD_HEIGHT, 1.0);
+
+        let blinking = field.0.remaining_secs() < FIELD_WARNING_SECONDS
+            && !((time.elapsed_secs() * 8.0) as u32).is_multiple_of(2);
+        visibility.set_if_neq(if blinking {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+}
@@ -0,0 +1,206 @@
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    input_map::{key_label, InputAction, InputMap},
+    locale::{Localized, LocalizedFont, Strings},
+    theme::{ThemeColor, Themed},
+    GameState,
+};
+
+// 操作設定画面を開くキー (割り当ては変えられない)
+const OPEN_KEY: KeyCode = KeyCode::F1;
+// 選んでいる操作のキーを待っている間に押すと、割り当てをやめるキー
+const CANCEL_KEY: KeyCode = KeyCode::Escape;
+// すべての割り当てを既定のキーに戻すキー
+const RESET_KEY: KeyCode = KeyCode::Backspace;
+// 見出しと項目のフォントサイズ
+const TITLE_FONT_SIZE: f32 = 40.0;
+const ROW_FONT_SIZE: f32 = 24.0;
+const HELP_FONT_SIZE: f32 = 16.0;
+
+/// `F1` キーで開く、1P の操作キー ([`InputMap`]) の割り当てを変える操作設定画面を追加するプラグイン
+///
+/// `↑` / `↓` で操作を選び、`Enter` を押したあとに押したキーをその操作に割り当てる。
+/// 開いている間は仮想時間を止め、キー入力はこの画面だけが使う (ほかのシステムが読む前に捨てる)。
+/// 画面の操作に使うキーは割り当てに関係なく決まったキーなので、割り当てを間違えても画面から戻せる。
+pub struct OptionsPlugin;
+
+impl Plugin for OptionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                open_options
+                    .run_if(not(in_state(GameState::Editing)))
+                    .run_if(not(any_with_component::<OptionsScreen>)),
+                update_rows.run_if(any_with_component::<OptionsScreen>),
+            )
+                .chain(),
+        )
+        // ほかのシステムが入力を読む前に、画面の操作を処理して入力を捨てる
+        .add_systems(
+            PreUpdate,
+            navigate_options
+                .after(InputSystem)
+                .run_if(any_with_component::<OptionsScreen>),
+        );
+    }
+}
+
+/// 操作設定画面
+#[derive(Component)]
+struct OptionsScreen {
+    selected: usize,   // 選んでいる操作 (`InputAction::ALL` の番号)
+    capturing: bool,   // 選んでいる操作に割り当てるキーを待っているか
+    paused_time: bool, // 画面を開いたときに仮想時間を止めたか (閉じるときに動かす)
+}
+
+/// 操作設定画面の 1 行 (`InputAction::ALL` の番号)
+#[derive(Component)]
+struct OptionsRow(usize);
+
+/// `F1` キーで操作設定画面を開く
+fn open_options(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if !keyboard_input.just_pressed(OPEN_KEY) {
+        return;
+    }
+    // ほかの機能 (一時停止・勝利演出など) が止めている時間は、閉じたときに動かさない
+    let paused_time = !time.is_paused();
+    time.pause();
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            Themed(ThemeColor::Overlay),
+            GlobalZIndex(1), // ゲームオーバー画面や一時停止の表示より手前に表示する
+            OptionsScreen {
+                selected: 0,
+                capturing: false,
+                paused_time,
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("CONTROLS"),
+                TextFont {
+                    font_size: TITLE_FONT_SIZE,
+                    ..default()
+                },
+                Themed(ThemeColor::Title),
+                Localized::new("options_title"),
+            ));
+            for index in 0..InputAction::ALL.len() {
+                parent.spawn((
+                    Text::default(),
+                    TextFont {
+                        font_size: ROW_FONT_SIZE,
+                        ..default()
+                    },
+                    Themed(ThemeColor::Text),
+                    LocalizedFont,
+                    OptionsRow(index),
+                ));
+            }
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: HELP_FONT_SIZE,
+                    ..default()
+                },
+                Themed(ThemeColor::Hint),
+                Localized::new("options_help"),
+            ));
+        });
+}
+
+/// 画面の操作を処理し、キー入力を捨てる
+///
+/// キーを待っている間は、`Esc` 以外の最初に押されたキーを選んでいる操作に割り当てる。
+/// `F1` キーは画面を開くキーなので割り当てられない。
+fn navigate_options(
+    mut commands: Commands,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut input_map: ResMut<InputMap>,
+    mut time: ResMut<Time<Virtual>>,
+    screen: Single<(Entity, &mut OptionsScreen)>,
+) {
+    let (entity, mut screen) = screen.into_inner();
+    let pressed: Vec<KeyCode> = keyboard_input.get_just_pressed().copied().collect();
+    keyboard_input.reset_all();
+
+    if screen.capturing {
+        if pressed.contains(&CANCEL_KEY) {
+            screen.capturing = false;
+        } else if let Some(&key) = pressed.iter().find(|&&key| key != OPEN_KEY) {
+            let action = InputAction::ALL[screen.selected];
+            input_map.bind(action, key);
+            info!("bound {} to {key:?}", action.name());
+            screen.capturing = false;
+        }
+        return;
+    }
+
+    let count = InputAction::ALL.len();
+    for key in pressed {
+        match key {
+            KeyCode::ArrowUp => screen.selected = (screen.selected + count - 1) % count,
+            KeyCode::ArrowDown => screen.selected = (screen.selected + 1) % count,
+            KeyCode::Enter => screen.capturing = true,
+            RESET_KEY => {
+                input_map.set_if_neq(InputMap::default());
+            }
+            OPEN_KEY | CANCEL_KEY => {
+                commands.entity(entity).despawn_recursive();
+                if screen.paused_time {
+                    time.unpause();
+                }
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 各行に操作の名前と割り当てたキーを表示し、選んでいる行を強調する
+fn update_rows(
+    strings: Strings,
+    input_map: Res<InputMap>,
+    screen: Single<&OptionsScreen>,
+    mut rows: Query<(&OptionsRow, &mut Text, &mut Themed)>,
+) {
+    for (row, mut text, mut themed) in &mut rows {
+        let action = InputAction::ALL[row.0];
+        let selected = row.0 == screen.selected;
+        let key = if selected && screen.capturing {
+            strings.get("options_press_key").to_string()
+        } else {
+            key_label(input_map.get(action))
+        };
+        let marker = if selected { ">" } else { " " };
+        let content = format!(
+            "{marker} {}: {key}",
+            strings.get(&format!("action_{}", action.name()))
+        );
+        if text.0 != content {
+            text.0 = content;
+        }
+        themed.set_if_neq(Themed(if selected {
+            ThemeColor::NewBest
+        } else {
+            ThemeColor::Text
+        }));
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{apply_velocity, check_for_collisions, Collider},
+    boss,
+    input_map::InputMap,
+    is_single_player, slow_motion,
+    theme::{ThemeColor, Themed},
+    versus,
+    walls::{BOTTOM_WALL, LEFT_WALL, RIGHT_WALL, WALL_THICKNESS},
+};
+
+// パドルのサイズ（横幅、縦幅）。1 人用のルールの横幅は `difficulty.rs` が難易度に合わせて変える
+pub const PADDLE_SIZE: Vec2 = Vec2::new(120.0, 20.0);
+// パドルと床の間のギャップ
+pub const GAP_BETWEEN_PADDLE_AND_FLOOR: f32 = 60.0;
+// パドルの移動速度
+pub const PADDLE_SPEED: f32 = 500.0;
+// パドルが壁にどれだけ近づけるか
+pub const PADDLE_PADDING: f32 = 10.0;
+// 1P パドルと 2P パドルの縦方向の間隔
+pub const GAP_BETWEEN_PADDLES: f32 = 40.0;
+
+/// パドルの生成・移動と、2P パドルの参加・離脱を行うプラグイン
+pub struct PaddlePlugin;
+
+impl Plugin for PaddlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_paddle)
+            // ボールの移動と衝突判定の間にパドルを動かす
+            .add_systems(
+                FixedUpdate,
+                move_paddle
+                    .after(apply_velocity)
+                    .before(check_for_collisions),
+            )
+            .add_systems(Update, toggle_second_paddle.run_if(is_single_player));
+    }
+}
+
+// パドルを示すコンポーネント（左右移動に使うキーを保持）
+#[derive(Component)]
+pub struct Paddle {
+    pub keys: PaddleKeys,
+}
+
+/// パドルを左右に動かすキー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaddleKeys {
+    Mapped,                                  // 操作設定で変えられる 1P のキー (`InputMap`)
+    Fixed { left: KeyCode, right: KeyCode }, // 決まったキー (2P と対戦モードの上側)
+}
+
+// 2P が操作するパドルを示すコンポーネント
+#[derive(Component)]
+pub struct SecondPaddle;
+
+/// パドルを追加する (1P は `InputMap` のキー、既定では矢印キーで操作)
+fn spawn_paddle(mut commands: Commands) {
+    let paddle_y = BOTTOM_WALL + GAP_BETWEEN_PADDLE_AND_FLOOR;
+
+    commands.spawn(paddle_bundle(
+        paddle_y,
+        ThemeColor::Paddle,
+        Paddle {
+            keys: PaddleKeys::Mapped,
+        },
+    ));
+}
+
+/// パドルエンティティのコンポーネント一式を作成する
+pub fn paddle_bundle(y: f32, color: ThemeColor, paddle: Paddle) -> impl Bundle {
+    (
+        Sprite::default(),
+        Themed(color),
+        Transform {
+            translation: Vec3::new(0.0, y, 0.0), // パドルの初期位置
+            scale: PADDLE_SIZE.extend(1.0),      // パドルのサイズ
+            ..default()
+        },
+        paddle,   // パドルコンポーネント
+        Collider, // 衝突判定用コンポーネント
+    )
+}
+
+/// `2` キーで 2P パドル (A/D キーで操作) の参加・離脱を切り替える関数
+fn toggle_second_paddle(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    second_paddle: Query<Entity, With<SecondPaddle>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Digit2) {
+        return;
+    }
+
+    // すでに 2P が参加していれば離脱させる
+    if let Ok(entity) = second_paddle.get_single() {
+        commands.entity(entity).despawn();
+        return;
+    }
+
+    // 2P パドルは 1P パドルの少し上に配置する
+    let paddle_y = BOTTOM_WALL + GAP_BETWEEN_PADDLE_AND_FLOOR + GAP_BETWEEN_PADDLES;
+    commands.spawn((
+        paddle_bundle(
+            paddle_y,
+            ThemeColor::SecondPaddle,
+            Paddle {
+                keys: PaddleKeys::Fixed {
+                    left: KeyCode::KeyA,
+                    right: KeyCode::KeyD,
+                },
+            },
+        ),
+        SecondPaddle,
+    ));
+}
+
+/// パドルの移動を処理する関数
+#[allow(clippy::type_complexity)]
+pub fn move_paddle(
+    keyboard_input: Res<ButtonInput<KeyCode>>, // キー入力をリソースとして取得
+    input_map: Res<InputMap>,                  // 1P パドルの操作キーの割り当て
+    mut paddle_query: Query<
+        (&Paddle, &mut Transform),
+        (Without<versus::PaddleAi>, Without<boss::Stunned>),
+    >, // プレイヤーが操作できるパドルの操作キーと変換情報
+    time: Res<Time>,                           // 時間の経過をリソースとして取得
+    virtual_time: Res<Time<Virtual>>,          // スローモーションの速さを打ち消すために使う
+) {
+    // スローモーション中も実時間で同じ速さで動くようにする
+    let delta_secs = slow_motion::unscaled_delta_secs(&time, &virtual_time);
+
+    for (paddle, mut paddle_transform) in &mut paddle_query {
+        let mut direction = 0.0; // パドルの移動方向を初期化
+        let (left_key, right_key) = match paddle.keys {
+            PaddleKeys::Mapped => (input_map.paddle_left, input_map.paddle_right),
+            PaddleKeys::Fixed { left, right } => (left, right),
+        };
+
+        // 左移動キーが押されていれば、左方向に移動
+        if keyboard_input.pressed(left_key) {
+            direction -= 1.0;
+        }
+
+        // 右移動キーが押されていれば、右方向に移動
+        if keyboard_input.pressed(right_key) {
+            direction += 1.0;
+        }
+
+        // プレイヤー入力に基づき新しいパドルの位置を計算
+        let new_paddle_position =
+            paddle_transform.translation.x + direction * PADDLE_SPEED * delta_secs;
+
+        // 新しいパドル位置を制限内に収める
+        paddle_transform.translation.x =
+            clamp_paddle_x(new_paddle_position, paddle_transform.scale.x);
+    }
+}
+
+/// 幅 `width` のパドルがアリーナから外れないように x 座標を制限する
+pub fn clamp_paddle_x(x: f32, width: f32) -> f32 {
+    let left_bound = LEFT_WALL + WALL_THICKNESS / 2.0 + width / 2.0 + PADDLE_PADDING;
+    let right_bound = RIGHT_WALL - WALL_THICKNESS / 2.0 - width / 2.0 - PADDLE_PADDING;
+    x.clamp(left_bound, right_bound)
+}
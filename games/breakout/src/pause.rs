@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use crate::{
+    input_map::InputMap,
+    locale::Localized,
+    theme::{ThemeColor, Themed},
+    victory::PlayPhase,
+};
+
+// 一時停止の表示のフォントサイズ
+const PAUSE_FONT_SIZE: f32 = 54.0;
+
+/// プレイ中に一時停止キー ([`InputMap`] の `pause`、既定は `P`) でゲームを止めるプラグイン
+///
+/// 止めている間は仮想時間を止め、画面を暗くして「PAUSED」を表示する。
+/// 勝利演出やリプレイなど、ほかの機能がすでに仮想時間を止めているときは一時停止しない
+/// (再開したときに、その機能が止めている時間まで動かしてしまわないようにする)。
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, toggle_pause.run_if(in_state(PlayPhase::Running)))
+            .add_systems(OnExit(PlayPhase::Running), resume);
+    }
+}
+
+/// 一時停止中の画面
+#[derive(Component)]
+struct PauseScreen;
+
+/// 一時停止キーで、一時停止と再開を切り替える
+fn toggle_pause(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut time: ResMut<Time<Virtual>>,
+    screen: Query<Entity, With<PauseScreen>>,
+) {
+    if !keyboard_input.just_pressed(input_map.pause) {
+        return;
+    }
+    if let Ok(entity) = screen.get_single() {
+        commands.entity(entity).despawn_recursive();
+        time.unpause();
+        return;
+    }
+    if time.is_paused() {
+        return;
+    }
+    time.pause();
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            Themed(ThemeColor::Overlay),
+            PauseScreen,
+        ))
+        .with_child((
+            Text::new("PAUSED"),
+            TextFont {
+                font_size: PAUSE_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Title),
+            Localized::new("paused"),
+        ));
+}
+
+/// 一時停止したままプレイが中断したら (レベルエディタを開いたときなど)、表示を消して仮想時間を動かす
+fn resume(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    screen: Query<Entity, With<PauseScreen>>,
+) {
+    for entity in &screen {
+        commands.entity(entity).despawn_recursive();
+        time.unpause();
+    }
+}
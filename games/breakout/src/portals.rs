@@ -0,0 +1,154 @@
+use bevy::{
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    ball::{check_for_collisions, Ball, BALL_DIAMETER},
+    bricks::BRICK_SIZE,
+    theme::{Theme, ThemeColor, Themed},
+    victory::PlayPhase,
+};
+
+// つながった 2 つのポータルの間に流す点の数と、点が流れる速さ (1 秒に進む、線の長さに対する割合)
+const LINK_DOTS: usize = 12;
+const LINK_SPEED: f32 = 0.4;
+// 流す点の半径
+const LINK_DOT_RADIUS: f32 = 2.5;
+
+/// レベルファイルの `O` のマスに置く、2 つ 1 組のポータルを追加するプラグイン
+///
+/// ポータルはブロックと同じ大きさで、[`Collider`](crate::ball::Collider) を持たないのでボールは跳ね返らない。
+/// ボールの中心がポータルに入ると、入ったときの位置関係と速度を保ったまま相方のポータルへ移す。
+/// 移った先のポータルからボールが出るまでは、どのポータルにも入らない (出口ですぐに入り直さない)。
+/// ポータルはブロックではないので、残っていてもレベルのクリアには関係なく、レベルが終わると消える。
+pub struct PortalsPlugin;
+
+impl Plugin for PortalsPlugin {
+    fn build(&self, app: &mut App) {
+        // 途中経過の保存で書き出せるように型を登録する
+        app.register_type::<Portal>()
+            .add_systems(FixedUpdate, teleport_ball.after(check_for_collisions))
+            .add_systems(Update, draw_links);
+    }
+}
+
+/// ポータルを示すコンポーネント (`pair` が同じ 2 つのポータルがつながる)
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Portal {
+    pub pair: u32,
+}
+
+/// ポータルの見た目とレベルの間だけ残すためのコンポーネント
+///
+/// レベルファイルから並べるときと、保存した盤面から戻すときに付ける。
+pub fn portal_bundle(pair: u32) -> impl Bundle {
+    (
+        Sprite::default(),
+        Themed(ThemeColor::Portal),
+        Portal { pair },
+        // 勝利演出が始まったら (次のレベルへ進むときも) 消す
+        StateScoped(PlayPhase::Running),
+    )
+}
+
+/// 位置 `a` と `b` につながった 1 組のポータルを置く
+pub fn spawn_pair(commands: &mut Commands, pair: u32, a: Vec2, b: Vec2) {
+    for position in [a, b] {
+        commands.spawn((
+            portal_bundle(pair),
+            Transform {
+                translation: position.extend(0.0),
+                scale: BRICK_SIZE.extend(1.0),
+                ..default()
+            },
+        ));
+    }
+}
+
+/// ポータルの当たり判定の箱
+fn portal_bounds(transform: &Transform) -> Aabb2d {
+    Aabb2d::new(
+        transform.translation.truncate(),
+        transform.scale.truncate() / 2.0,
+    )
+}
+
+/// 相方のポータル (`entity` と同じ組のもう一方)
+fn partner<'a>(
+    portals: &'a Query<(Entity, &Portal, &Transform), Without<Ball>>,
+    entity: Entity,
+    pair: u32,
+) -> Option<(Entity, &'a Transform)> {
+    portals
+        .iter()
+        .find(|(other, portal, _)| *other != entity && portal.pair == pair)
+        .map(|(other, _, transform)| (other, transform))
+}
+
+/// ボールの中心がポータルに入ったら、相方のポータルの同じ位置関係の場所へ移す (速度は変えない)
+///
+/// `leaving` は直前に出てきたポータルで、ボールがそこから出るまではワープしない。
+fn teleport_ball(
+    mut ball: Query<&mut Transform, With<Ball>>,
+    portals: Query<(Entity, &Portal, &Transform), Without<Ball>>,
+    mut leaving: Local<Option<Entity>>,
+) {
+    let Ok(mut ball_transform) = ball.get_single_mut() else {
+        return;
+    };
+    let center = ball_transform.translation.truncate();
+    let ball_bounds = BoundingCircle::new(center, BALL_DIAMETER / 2.0);
+
+    if let Some(exit) = *leaving {
+        let still_inside = portals
+            .get(exit)
+            .is_ok_and(|(_, _, transform)| ball_bounds.intersects(&portal_bounds(transform)));
+        if still_inside {
+            return;
+        }
+        *leaving = None;
+    }
+
+    for (entity, portal, transform) in &portals {
+        let bounds = portal_bounds(transform);
+        if center.cmplt(bounds.min).any() || center.cmpgt(bounds.max).any() {
+            continue;
+        }
+        let Some((exit, exit_transform)) = partner(&portals, entity, portal.pair) else {
+            continue;
+        };
+        let offset = center - transform.translation.truncate();
+        let position = exit_transform.translation.truncate() + offset;
+        ball_transform.translation = position.extend(ball_transform.translation.z);
+        *leaving = Some(exit);
+        break;
+    }
+}
+
+/// つながった 2 つのポータルの間に、流れる点線を描く (1 組につき 1 回だけ描く)
+fn draw_links(
+    time: Res<Time>,
+    theme: Res<Theme>,
+    portals: Query<(Entity, &Portal, &Transform), Without<Ball>>,
+    mut gizmos: Gizmos,
+) {
+    let color = theme.color(ThemeColor::Portal).with_alpha(0.5);
+    for (entity, portal, transform) in &portals {
+        let Some((other, partner_transform)) = partner(&portals, entity, portal.pair) else {
+            continue;
+        };
+        if entity > other {
+            continue;
+        }
+        let (start, end) = (
+            transform.translation.truncate(),
+            partner_transform.translation.truncate(),
+        );
+        for i in 0..LINK_DOTS {
+            let t = (i as f32 / LINK_DOTS as f32 + time.elapsed_secs() * LINK_SPEED).fract();
+            gizmos.circle_2d(start.lerp(end, t), LINK_DOT_RADIUS, color);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use settings::Profile;
+
+use crate::{
+    attract::is_attract_mode,
+    daily::{is_daily, DailyChallenge},
+    is_single_player,
+    locale::Localized,
+    theme::{ThemeColor, Themed},
+    ui::{SCOREBOARD_FONT_SIZE, SCOREBOARD_TEXT_PADDING},
+    GameMode, Score,
+};
+
+// 記録を保存するファイル名 (プロファイルのディレクトリ内)
+const RECORDS_FILE: &str = "breakout_records.ron";
+// ハイスコアの表示のフォントサイズ
+const HIGH_SCORE_FONT_SIZE: f32 = 20.0;
+
+/// ハイスコアとレベルごとのベストタイムを記録・保存するプラグイン
+///
+/// デイリーチャレンジの得点はハイスコアにせず、日付ごとの最高得点として別に記録する (`daily.rs`)。
+/// 記録は `settings` クレートの [`Profile`] のディレクトリに保存する。
+pub struct RecordsPlugin;
+
+impl Plugin for RecordsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profile>()
+            .add_systems(PreStartup, load_records)
+            .add_systems(Startup, spawn_high_score_ui.run_if(is_single_player))
+            .add_systems(
+                Update,
+                (
+                    // デモプレイ (`attract.rs`) とデイリーチャレンジのスコアはハイスコアにしない
+                    update_high_score.run_if(not(is_attract_mode).and(not(is_daily))),
+                    update_high_score_ui,
+                )
+                    .chain()
+                    .run_if(is_single_player),
+            )
+            .add_systems(Last, save_records);
+    }
+}
+
+/// プレイヤーの記録
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Records {
+    /// これまでの最高得点
+    pub high_score: usize,
+    /// レベルごとのタイムアタックのベストタイム (秒)
+    pub best_times: BTreeMap<u32, f64>,
+    /// デイリーチャレンジの日付 (`YYYY-MM-DD`) ごとの最高得点
+    pub daily_best: BTreeMap<String, usize>,
+}
+
+impl Records {
+    /// 指定したレベルのベストタイム
+    pub fn best_time(&self, level: u32) -> Option<f64> {
+        self.best_times.get(&level).copied()
+    }
+
+    /// タイムを登録し、ベストタイムを更新したら `true` を返す
+    pub fn submit_time(&mut self, level: u32, secs: f64) -> bool {
+        let is_best = self.best_time(level).is_none_or(|best| secs < best);
+        if is_best {
+            self.best_times.insert(level, secs);
+        }
+        is_best
+    }
+
+    /// 指定した日付のデイリーチャレンジの最高得点
+    pub fn daily_best(&self, date: &str) -> Option<usize> {
+        self.daily_best.get(date).copied()
+    }
+
+    /// デイリーチャレンジの得点を登録し、その日の最高得点を更新したら `true` を返す
+    pub fn submit_daily_score(&mut self, date: &str, score: usize) -> bool {
+        let is_best = self.daily_best(date).is_none_or(|best| score > best);
+        if is_best {
+            self.daily_best.insert(date.to_string(), score);
+        }
+        is_best
+    }
+}
+
+/// ハイスコアの表示を示すコンポーネント
+#[derive(Component)]
+struct HighScoreUi;
+
+/// 現在のプロファイルから記録を読み込む
+fn load_records(mut commands: Commands, profile: Res<Profile>) {
+    commands.insert_resource(profile.load::<Records>(RECORDS_FILE));
+}
+
+/// 記録が変更されたフレームの最後にプロファイルへ保存する
+fn save_records(records: Res<Records>, profile: Res<Profile>) {
+    if records.is_changed() && !records.is_added() {
+        profile.save(RECORDS_FILE, &*records);
+    }
+}
+
+/// スコアボードの下にハイスコアの表示を配置する (デイリーチャレンジではその日の最高得点を表示する)
+fn spawn_high_score_ui(mut commands: Commands, mode: Res<GameMode>) {
+    let label_key = if *mode == GameMode::Daily {
+        "daily_best_label"
+    } else {
+        "high_score_label"
+    };
+    commands
+        .spawn((
+            Text::new("High score: "),
+            TextFont {
+                font_size: HIGH_SCORE_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Text),
+            HighScoreUi,
+            Localized::new(label_key),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(SCOREBOARD_FONT_SIZE + 10.0),
+                left: SCOREBOARD_TEXT_PADDING,
+                ..default()
+            },
+        ))
+        .with_child((
+            TextSpan::default(),
+            TextFont {
+                font_size: HIGH_SCORE_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Score),
+        ));
+}
+
+/// 現在のスコアがハイスコアを超えたら更新する
+fn update_high_score(score: Res<Score>, mut records: ResMut<Records>) {
+    if **score > records.high_score {
+        records.high_score = **score;
+    }
+}
+
+/// ハイスコア (デイリーチャレンジではその日の最高得点) の表示を更新する
+fn update_high_score_ui(
+    records: Res<Records>,
+    daily: Option<Res<DailyChallenge>>,
+    root: Single<Entity, (With<HighScoreUi>, With<Text>)>,
+    mut writer: TextUiWriter,
+) {
+    if records.is_changed() {
+        let best = match daily {
+            Some(daily) => records.daily_best(&daily.date).unwrap_or(0),
+            None => records.high_score,
+        };
+        *writer.text(*root, 1) = best.to_string();
+    }
+}
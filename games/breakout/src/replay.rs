@@ -0,0 +1,425 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    attract::is_attract_mode,
+    ball::{check_for_collisions, Ball, BALL_DIAMETER},
+    bricks::Brick,
+    game_over::{check_ball_lost, BallLost},
+    is_single_player,
+    locale::{Localized, LocalizedFont, Strings},
+    paddle::Paddle,
+    theme::{ThemeColor, Themed},
+    victory::{LevelCleared, PlayPhase},
+    walls::{BOTTOM_WALL, LEFT_WALL, RIGHT_WALL, TOP_WALL, WALL_THICKNESS},
+    GameState,
+};
+
+// リプレイで見返す長さ (秒)
+const REPLAY_SECONDS: f32 = 5.0;
+// ボールを落としたりレベルをクリアしたりしてから、リプレイを選べる時間 (秒)
+const OFFER_SECONDS: f32 = 4.0;
+// リプレイを始め、再生中は終えるキー (`Shift` を押しながらだとスローモーションで再生する)
+const REPLAY_KEY: KeyCode = KeyCode::KeyR;
+// スローモーションの再生速度
+const SLOW_MOTION_SPEED: f32 = 0.25;
+// リプレイの背景とスプライトの z 座標 (ゲーム中のスプライトより手前に描く)
+const BACKDROP_Z: f32 = 20.0;
+const GHOST_Z: f32 = 21.0;
+// リプレイの案内と表示のフォントサイズ
+const REPLAY_FONT_SIZE: f32 = 24.0;
+
+/// ボールを落としたときやレベルをクリアしたときに、直前の数秒をリプレイで見返せるようにするプラグイン
+///
+/// 1 人用のプレイ中は固定タイムステップごとにボール・パドル・ブロックの位置と色を記録し、
+/// 直近 `REPLAY_SECONDS` 秒分をリングバッファに残しておく。ボールを落とすか ([`BallLost`])
+/// レベルをクリアすると ([`LevelCleared`]) その時点の記録を切り出し、プレイ中に `R` キーを押すと再生する
+/// (クリアのときは勝利演出のあと、次のレベルが始まってから選べる)。
+/// 再生中は仮想時間を止め、アリーナを覆う背景の上に記録したとおりのスプライトを実時間で動かす。
+/// シミュレーションをやり直すのではなく記録した状態を描くだけなので、跳ね返り方がおかしいときの確認にも使える。
+/// ボス・ドロップ・フォースフィールドは記録しない。
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Recorder>()
+            .init_resource::<ReplayOffer>()
+            .add_systems(Startup, spawn_offer_hint)
+            .add_systems(OnEnter(GameState::Playing), discard_clip)
+            .add_systems(OnEnter(PlayPhase::Running), clear_recorder)
+            // エディタを開くときなども含め、プレイが中断したらリプレイを終える
+            .add_systems(OnExit(PlayPhase::Running), stop_replay)
+            .add_systems(
+                FixedUpdate,
+                record_frame
+                    .after(check_for_collisions)
+                    // ボールを落としたフレームは、開始位置に戻す前のボールを記録する
+                    .before(check_ball_lost)
+                    .run_if(is_single_player.and(in_state(PlayPhase::Running))),
+            )
+            .add_systems(
+                Update,
+                (
+                    capture_clip.run_if(not(is_attract_mode)),
+                    (start_replay, offer_countdown)
+                        .chain()
+                        .run_if(in_state(PlayPhase::Running).and(not(is_replaying))),
+                    (play_replay, update_replay_label)
+                        .chain()
+                        .run_if(is_replaying),
+                    update_offer_hint,
+                )
+                    .chain()
+                    .run_if(is_single_player),
+            );
+    }
+}
+
+/// 1 フレーム分の盤面の状態
+#[derive(Clone)]
+struct Frame {
+    ball: Vec2,
+    paddles: Vec<Piece>,
+    bricks: Vec<Piece>,
+}
+
+/// 記録したスプライト 1 つ分の位置・大きさ・色
+#[derive(Clone, Copy)]
+struct Piece {
+    position: Vec2,
+    size: Vec2,
+    color: ThemeColor,
+}
+
+/// 直近 `REPLAY_SECONDS` 秒分の盤面の記録 (古いものから順に並ぶリングバッファ)
+#[derive(Resource, Default)]
+struct Recorder {
+    frames: VecDeque<Frame>,
+    timestep: f32, // 1 フレームの長さ (秒)
+}
+
+/// 切り出したリプレイと、リプレイを選べる残り時間
+#[derive(Resource, Default)]
+struct ReplayOffer {
+    clip: Option<Clip>,
+    remaining: f32,             // リプレイを選べる残り時間 (プレイ中の実時間で数える)
+    playback: Option<Playback>, // 再生中のリプレイ
+}
+
+/// 再生するフレームの並び
+#[derive(Clone)]
+struct Clip {
+    frames: Vec<Frame>,
+    timestep: f32,
+}
+
+/// 再生中のリプレイの状態
+struct Playback {
+    clip: Clip,
+    position: f32, // 再生位置 (フレーム数、小数は次のフレームまでの途中)
+    speed: f32,    // 再生速度
+}
+
+/// リプレイで描くエンティティ (再生が終わるとまとめて消す)
+#[derive(Component)]
+struct ReplayView;
+
+/// リプレイのボール
+#[derive(Component)]
+struct GhostBall;
+
+/// リプレイのパドルかブロック (`index` 番目の記録を描く)
+#[derive(Component)]
+enum GhostPiece {
+    Paddle(usize),
+    Brick(usize),
+}
+
+/// 再生中の表示
+#[derive(Component)]
+struct ReplayLabel;
+
+/// リプレイを選べることの案内
+#[derive(Component)]
+struct OfferHint;
+
+/// リプレイを再生中かどうかを判定する条件関数
+fn is_replaying(offer: Res<ReplayOffer>) -> bool {
+    offer.playback.is_some()
+}
+
+/// 画面の下にリプレイの案内を配置する (選べる間だけ表示する)
+fn spawn_offer_hint(mut commands: Commands) {
+    commands.spawn((
+        Text::new("Press R for instant replay (Shift+R: slow motion)"),
+        TextFont {
+            font_size: REPLAY_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Hint),
+        Localized::new("replay_offer"),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(48.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        OfferHint,
+    ));
+}
+
+/// 新しいプレイでは前のプレイのリプレイを選べないようにする
+fn discard_clip(mut offer: ResMut<ReplayOffer>) {
+    offer.clip = None;
+    offer.remaining = 0.0;
+}
+
+/// レベルが始まるたびに記録を空にする (前のレベルの盤面が混ざらないようにする)
+fn clear_recorder(mut recorder: ResMut<Recorder>) {
+    recorder.frames.clear();
+}
+
+/// 現在の盤面を記録し、`REPLAY_SECONDS` 秒より古いフレームを捨てる
+fn record_frame(
+    time: Res<Time>,
+    ball: Single<&Transform, With<Ball>>,
+    paddles: Query<(&Transform, &Themed), With<Paddle>>,
+    bricks: Query<(&Transform, &Themed), With<Brick>>,
+    mut recorder: ResMut<Recorder>,
+) {
+    let piece = |(transform, themed): (&Transform, &Themed)| Piece {
+        position: transform.translation.truncate(),
+        size: transform.scale.truncate(),
+        color: themed.0,
+    };
+    let timestep = time.delta_secs();
+    let capacity = (REPLAY_SECONDS / timestep).ceil() as usize;
+    recorder.timestep = timestep;
+    while recorder.frames.len() >= capacity {
+        recorder.frames.pop_front();
+    }
+    recorder.frames.push_back(Frame {
+        ball: ball.translation.truncate(),
+        paddles: paddles.iter().map(piece).collect(),
+        bricks: bricks.iter().map(piece).collect(),
+    });
+}
+
+/// ボールを落としたときとレベルをクリアしたときに、その時点までの記録をリプレイとして切り出す
+///
+/// 最後の残機を落としたときはゲームオーバーになるので、リプレイは切り出さない。
+fn capture_clip(
+    mut lost_events: EventReader<BallLost>,
+    mut cleared_events: EventReader<LevelCleared>,
+    recorder: Res<Recorder>,
+    mut offer: ResMut<ReplayOffer>,
+) {
+    let lost = lost_events.read().any(|lost| lost.lives_left > 0);
+    let cleared = cleared_events.read().any(|cleared| !cleared.is_final_level);
+    if !(lost || cleared) || recorder.frames.is_empty() {
+        return;
+    }
+    offer.clip = Some(Clip {
+        frames: recorder.frames.iter().cloned().collect(),
+        timestep: recorder.timestep,
+    });
+    offer.remaining = OFFER_SECONDS;
+}
+
+/// リプレイを選べる残り時間を減らす
+///
+/// レベルをクリアしたときは勝利演出の間は数えず、次のレベルが始まってから数える。
+fn offer_countdown(time: Res<Time<Real>>, mut offer: ResMut<ReplayOffer>) {
+    if offer.clip.is_some() {
+        offer.remaining = (offer.remaining - time.delta_secs()).max(0.0);
+    }
+}
+
+/// リプレイを選べる間に `R` キーが押されたら、仮想時間を止めてリプレイを始める
+fn start_replay(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut offer: ResMut<ReplayOffer>,
+    mut time: ResMut<Time<Virtual>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if offer.remaining <= 0.0 || !keyboard_input.just_pressed(REPLAY_KEY) {
+        return;
+    }
+    let Some(clip) = offer.clip.clone() else {
+        return;
+    };
+    let slow_motion = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let speed = if slow_motion { SLOW_MOTION_SPEED } else { 1.0 };
+
+    // アリーナの壁の内側を背景色で覆い、ゲーム中のスプライトを隠す
+    let arena = Rect::new(
+        LEFT_WALL + WALL_THICKNESS / 2.0,
+        BOTTOM_WALL - BALL_DIAMETER,
+        RIGHT_WALL - WALL_THICKNESS / 2.0,
+        TOP_WALL - WALL_THICKNESS / 2.0,
+    );
+    commands.spawn((
+        Sprite::from_color(Color::NONE, arena.size()),
+        Transform::from_translation(arena.center().extend(BACKDROP_Z)),
+        Themed(ThemeColor::Background),
+        ReplayView,
+    ));
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::default())),
+        MeshMaterial2d(materials.add(ColorMaterial::default())),
+        Themed(ThemeColor::Ball),
+        Transform::from_scale(Vec2::splat(BALL_DIAMETER).extend(1.0)),
+        GhostBall,
+        ReplayView,
+    ));
+    let max_paddles = clip.frames.iter().map(|frame| frame.paddles.len()).max();
+    let max_bricks = clip.frames.iter().map(|frame| frame.bricks.len()).max();
+    let pieces = (0..max_paddles.unwrap_or(0))
+        .map(GhostPiece::Paddle)
+        .chain((0..max_bricks.unwrap_or(0)).map(GhostPiece::Brick));
+    for ghost in pieces {
+        commands.spawn((
+            Sprite::default(),
+            Transform::default(),
+            Themed(ThemeColor::Brick),
+            ghost,
+            ReplayView,
+        ));
+    }
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: REPLAY_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Title),
+        LocalizedFont,
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(48.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        ReplayLabel,
+        ReplayView,
+    ));
+
+    time.pause();
+    offer.remaining = 0.0;
+    offer.playback = Some(Playback {
+        clip,
+        position: 0.0,
+        speed,
+    });
+    info!("instant replay started (x{speed})");
+}
+
+/// 再生位置を実時間で進め、その位置のフレームのとおりにスプライトを動かす
+///
+/// 最後のフレームまで再生するか、もう一度 `R` キーか `Esc` キーが押されたらリプレイを終える。
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn play_replay(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut offer: ResMut<ReplayOffer>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut ball: Single<&mut Transform, (With<GhostBall>, Without<GhostPiece>)>,
+    mut pieces: Query<(&GhostPiece, &mut Transform, &mut Themed, &mut Visibility)>,
+    views: Query<Entity, With<ReplayView>>,
+) {
+    let Some(playback) = &mut offer.playback else {
+        return;
+    };
+    playback.position += time.delta_secs() * playback.speed / playback.clip.timestep;
+    let index = playback.position as usize;
+    let Some(frame) = playback.clip.frames.get(index) else {
+        finish_replay(&mut commands, &mut offer, &mut virtual_time, &views);
+        return;
+    };
+    if keyboard_input.any_just_pressed([REPLAY_KEY, KeyCode::Escape]) {
+        finish_replay(&mut commands, &mut offer, &mut virtual_time, &views);
+        return;
+    }
+
+    ball.translation = frame.ball.extend(GHOST_Z);
+    for (ghost, mut transform, mut themed, mut visibility) in &mut pieces {
+        let piece = match *ghost {
+            GhostPiece::Paddle(i) => frame.paddles.get(i),
+            GhostPiece::Brick(i) => frame.bricks.get(i),
+        };
+        let Some(piece) = piece else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        transform.translation = piece.position.extend(GHOST_Z);
+        transform.scale = piece.size.extend(1.0);
+        themed.set_if_neq(Themed(piece.color));
+    }
+}
+
+/// 再生中の表示に再生速度を出す
+fn update_replay_label(
+    offer: Res<ReplayOffer>,
+    strings: Strings,
+    mut label: Query<&mut Text, With<ReplayLabel>>,
+) {
+    let Some(playback) = &offer.playback else {
+        return;
+    };
+    for mut text in &mut label {
+        let content = strings.format("replay_label", &[("speed", &playback.speed)]);
+        if text.0 != content {
+            text.0 = content;
+        }
+    }
+}
+
+/// プレイが中断したら、再生中のリプレイを終える
+fn stop_replay(
+    mut commands: Commands,
+    mut offer: ResMut<ReplayOffer>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    views: Query<Entity, With<ReplayView>>,
+) {
+    if offer.playback.is_some() {
+        finish_replay(&mut commands, &mut offer, &mut virtual_time, &views);
+    }
+}
+
+/// リプレイのエンティティを消し、止めていた仮想時間を動かす
+fn finish_replay(
+    commands: &mut Commands,
+    offer: &mut ReplayOffer,
+    virtual_time: &mut Time<Virtual>,
+    views: &Query<Entity, With<ReplayView>>,
+) {
+    for entity in views {
+        commands.entity(entity).despawn_recursive();
+    }
+    offer.playback = None;
+    virtual_time.unpause();
+    info!("instant replay finished");
+}
+
+/// リプレイを選べる間だけ案内を表示する
+fn update_offer_hint(
+    offer: Res<ReplayOffer>,
+    state: Option<Res<State<PlayPhase>>>,
+    mut hint: Single<&mut Visibility, With<OfferHint>>,
+) {
+    let running = state.is_some_and(|state| *state.get() == PlayPhase::Running);
+    let visible = running && offer.playback.is_none() && offer.remaining > 0.0;
+    hint.set_if_neq(if visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+}
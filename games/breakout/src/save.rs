@@ -0,0 +1,340 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    ecs::entity::EntityHashMap, input::common_conditions::input_just_pressed, prelude::*,
+    reflect::TypeRegistry, scene::serde::SceneDeserializer,
+};
+use serde::de::DeserializeSeed;
+use settings::Profile;
+use thiserror::Error;
+
+use crate::{
+    ball::{Ball, Collider, Velocity},
+    boss,
+    bricks::Brick,
+    game_over::{spawn_game_over_screen, GameOverScreen, Lives},
+    is_single_player,
+    levels::Sturdy,
+    locale::{Localized, LocalizedFont, Strings},
+    portals::{portal_bundle, Portal},
+    spin::Spin,
+    theme::{ThemeColor, Themed},
+    victory::{remember_level_start_score, PlayPhase},
+    GameState, Level, Score,
+};
+
+// 途中経過を保存するキー
+const SAVE_KEY: KeyCode = KeyCode::F6;
+// ゲームオーバー画面で保存した続きから遊ぶキー
+const CONTINUE_KEY: KeyCode = KeyCode::KeyC;
+// 途中経過を保存するファイル名 (プロファイルのディレクトリ内)
+const SAVE_FILE: &str = "breakout_save.scn.ron";
+// 保存結果のメッセージを表示しておく時間 (秒)
+const STATUS_SECONDS: f32 = 2.5;
+// 保存結果のメッセージのフォントサイズ
+const STATUS_FONT_SIZE: f32 = 18.0;
+
+/// 遊んでいる途中の盤面を保存し、ゲームオーバー画面から続きを遊べるようにするプラグイン
+///
+/// `F6` キーで、残っているブロック・ポータル・ボールの位置と速度・スコア・残機・レベルを
+/// リフレクションを使って [`DynamicScene`] に書き出し、プロファイルのディレクトリに保存する。
+/// このゲームにはタイトル画面がないため、「続きから遊ぶ」はゲームオーバー画面の `C` キーで選ぶ。
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        // シーンに書き出すコンポーネントとリソースの型を登録する
+        app.register_type::<Brick>()
+            .register_type::<Sturdy>()
+            .register_type::<Ball>()
+            .register_type::<Velocity>()
+            .register_type::<Spin>()
+            .register_type::<Score>()
+            .register_type::<Level>()
+            .register_type::<Lives>()
+            .init_resource::<SaveStatus>()
+            .add_systems(Startup, spawn_save_status_ui.run_if(is_single_player))
+            .add_systems(
+                Update,
+                (
+                    save_game.run_if(
+                        input_just_pressed(SAVE_KEY)
+                            .and(is_single_player)
+                            .and(in_state(PlayPhase::Running)),
+                    ),
+                    continue_saved_run.run_if(in_state(GameState::GameOver)),
+                    update_save_status_ui.run_if(is_single_player),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                spawn_continue_hint.after(spawn_game_over_screen),
+            )
+            // 盤面の初期化 (`OnEnter(GameState::Playing)`) のあとに保存した盤面で上書きする
+            .add_systems(
+                OnEnter(PlayPhase::Running),
+                restore_saved_run.before(remember_level_start_score),
+            );
+    }
+}
+
+/// 続きから遊ぶために読み込んだ盤面 (あるときは `start_game` がブロックを並べない)
+#[derive(Resource)]
+pub struct PendingContinue(DynamicScene);
+
+/// 直前の保存・読み込みの結果
+#[derive(Resource, Default)]
+struct SaveStatus {
+    message: Option<SaveMessage>,
+    timer: Timer, // メッセージを消すまでのタイマー
+}
+
+impl SaveStatus {
+    /// メッセージを表示し直す
+    fn show(&mut self, message: SaveMessage) {
+        self.message = Some(message);
+        self.timer = Timer::from_seconds(STATUS_SECONDS, TimerMode::Once);
+    }
+}
+
+/// 保存結果のメッセージ
+enum SaveMessage {
+    Saved,         // 保存した
+    BossLevel,     // ボス戦の途中は保存できない
+    Error(String), // 保存・読み込みに失敗した
+}
+
+/// 途中経過の保存・読み込みのエラー
+#[derive(Debug, Error)]
+enum SaveError {
+    #[error("no profile directory")]
+    NoProfileDir,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Serialize(#[from] ron::Error),
+    #[error(transparent)]
+    Deserialize(#[from] ron::error::SpannedError),
+}
+
+/// 保存結果のメッセージの表示を示すコンポーネント
+#[derive(Component)]
+struct SaveStatusUi;
+
+/// 保存ファイルのパス (設定ディレクトリが取得できない環境では `None`)
+fn save_path(profile: &Profile) -> Option<PathBuf> {
+    profile.dir().map(|dir| dir.join(SAVE_FILE))
+}
+
+/// 残っているブロック・ボール・スコア・残機・レベルをシーンにしてファイルへ書き出す
+///
+/// ボスはシーンに含めないため、ボス戦のレベルでは保存しない。
+fn save_game(world: &mut World) {
+    let message = if boss::is_boss_level(**world.resource::<Level>()) {
+        SaveMessage::BossLevel
+    } else {
+        match write_save(world) {
+            Ok(path) => {
+                info!("saved the run to {}", path.display());
+                SaveMessage::Saved
+            }
+            Err(error) => {
+                warn!("could not save the run: {error}");
+                SaveMessage::Error(error.to_string())
+            }
+        }
+    };
+    world.resource_mut::<SaveStatus>().show(message);
+}
+
+fn write_save(world: &mut World) -> Result<PathBuf, SaveError> {
+    let path = save_path(world.resource::<Profile>()).ok_or(SaveError::NoProfileDir)?;
+
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<Brick>, With<Ball>, With<Portal>)>>()
+        .iter(world)
+        .collect();
+    // 見た目 (`Sprite` や `Themed`) は保存せず、読み込むときに付け直す
+    let scene = DynamicSceneBuilder::from_world(world)
+        .deny_all()
+        .allow_component::<Transform>()
+        .allow_component::<Brick>()
+        .allow_component::<Sturdy>()
+        .allow_component::<Portal>()
+        .allow_component::<Ball>()
+        .allow_component::<Velocity>()
+        .allow_component::<Spin>()
+        .allow_resource::<Score>()
+        .allow_resource::<Level>()
+        .allow_resource::<Lives>()
+        .extract_entities(entities.into_iter())
+        .extract_resources()
+        .build();
+    let text = scene.serialize(&world.resource::<AppTypeRegistry>().read())?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// 保存ファイルを読み込んでシーンに戻す
+fn read_save(profile: &Profile, registry: &TypeRegistry) -> Result<DynamicScene, SaveError> {
+    let path = save_path(profile).ok_or(SaveError::NoProfileDir)?;
+    let text = fs::read_to_string(path)?;
+    let mut deserializer = ron::de::Deserializer::from_str(&text)?;
+    let scene = SceneDeserializer {
+        type_registry: registry,
+    }
+    .deserialize(&mut deserializer)
+    .map_err(|error| deserializer.span_error(error))?;
+    Ok(scene)
+}
+
+/// 保存ファイルがあれば、ゲームオーバー画面に続きから遊ぶ操作を表示する
+fn spawn_continue_hint(
+    mut commands: Commands,
+    profile: Res<Profile>,
+    screen: Single<Entity, With<GameOverScreen>>,
+) {
+    if !save_path(&profile).is_some_and(|path| path.exists()) {
+        return;
+    }
+    commands.entity(*screen).with_child((
+        Text::new("Press C to continue the saved run"),
+        TextFont {
+            font_size: STATUS_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Text),
+        Localized::new("continue_saved_run"),
+    ));
+}
+
+/// `C` キーで保存した盤面を読み込み、プレイ中に戻る
+fn continue_saved_run(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    profile: Res<Profile>,
+    registry: Res<AppTypeRegistry>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut status: ResMut<SaveStatus>,
+) {
+    if !keyboard_input.just_pressed(CONTINUE_KEY) {
+        return;
+    }
+    match read_save(&profile, &registry.read()) {
+        Ok(scene) => {
+            commands.insert_resource(PendingContinue(scene));
+            next_state.set(GameState::Playing);
+        }
+        Err(error) => {
+            warn!("could not load the saved run: {error}");
+            status.show(SaveMessage::Error(error.to_string()));
+        }
+    }
+}
+
+/// 読み込んだシーンを書き込み、保存したときの盤面に戻す
+///
+/// シーンのボールは今あるボールに書き込み、ブロックとポータルは新しいエンティティとして生成する。
+/// シーンには見た目や衝突判定のコンポーネントが含まれないので、生成したブロックに付け足す。
+pub fn restore_saved_run(world: &mut World) {
+    let Some(PendingContinue(scene)) = world.remove_resource::<PendingContinue>() else {
+        return;
+    };
+
+    let mut entity_map = EntityHashMap::default();
+    let live_ball = world
+        .query_filtered::<Entity, With<Ball>>()
+        .iter(world)
+        .next();
+    let saved_ball = scene.entities.iter().find(|entity| {
+        entity.components.iter().any(|component| {
+            component
+                .get_represented_type_info()
+                .is_some_and(|info| info.type_id() == std::any::TypeId::of::<Ball>())
+        })
+    });
+    if let (Some(live_ball), Some(saved_ball)) = (live_ball, saved_ball) {
+        entity_map.insert(saved_ball.entity, live_ball);
+    }
+
+    if let Err(error) = scene.write_to_world(world, &mut entity_map) {
+        warn!("could not restore the saved run: {error}");
+        return;
+    }
+
+    for entity in entity_map.into_values() {
+        let mut entity = world.entity_mut(entity);
+        if let Some(pair) = entity.get::<Portal>().map(|portal| portal.pair) {
+            entity.insert(portal_bundle(pair));
+            continue;
+        }
+        if !entity.contains::<Brick>() {
+            continue;
+        }
+        let color = if entity.contains::<Sturdy>() {
+            ThemeColor::SturdyBrick
+        } else {
+            ThemeColor::Brick
+        };
+        entity.insert((
+            Sprite::default(),
+            Themed(color),
+            Collider,
+            StateScoped(GameState::Playing),
+        ));
+    }
+    info!(
+        "continued the saved run at level {}",
+        **world.resource::<Level>()
+    );
+}
+
+/// 画面の下の中央に保存結果のメッセージの表示を配置する (右下の操作説明や左下のステッピングの説明と重ならないようにする)
+fn spawn_save_status_ui(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: STATUS_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Text),
+        LocalizedFont,
+        SaveStatusUi,
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(48.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        GlobalZIndex(1), // ゲームオーバー画面より手前に表示する
+    ));
+}
+
+/// 保存結果のメッセージを表示し、しばらくしたら消す
+fn update_save_status_ui(
+    time: Res<Time<Real>>,
+    mut status: ResMut<SaveStatus>,
+    strings: Strings,
+    mut text: Single<&mut Text, With<SaveStatusUi>>,
+) {
+    // タイマーを進めるだけでは変更として扱わない
+    let status_timer = &mut status.bypass_change_detection().timer;
+    if status_timer.tick(time.delta()).just_finished() {
+        status.message = None;
+    }
+    if !status.is_changed() && !strings.is_changed() {
+        return;
+    }
+    text.0 = match &status.message {
+        None => String::new(),
+        Some(SaveMessage::Saved) => strings.get("save_status_saved").to_string(),
+        Some(SaveMessage::BossLevel) => strings.get("save_status_boss_level").to_string(),
+        Some(SaveMessage::Error(error)) => strings.format("save_status_error", &[("error", error)]),
+    };
+}
@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+use expressions::{ExpressionsPlugin, Formula, Variables};
+
+use crate::{
+    ball::check_for_collisions, bricks::BrickDestroyed, is_single_player, GameState, Level, Score,
+};
+
+// ブロックの得点の計算式のパス
+const BRICK_SCORE_FORMULA: &str = "formulas/brick_score.expr";
+// ブロック 1 つの基本の得点 (計算式を使えないときはこの得点になる)
+const BASE_BRICK_SCORE: usize = 1;
+// 計算式を読み込み直すキー
+const RELOAD_KEY: KeyCode = KeyCode::F5;
+
+/// ブロックを壊したときの得点を、アセットの計算式 (`assets/formulas/brick_score.expr`) で決めるプラグイン
+///
+/// 計算式には基本の得点・コンボ数・レベルを変数として渡す。
+/// 計算式を書き換えて F5 キーを押せば、再コンパイルせずに得点の付け方を調整できる。
+pub struct ScoringPlugin;
+
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ExpressionsPlugin>() {
+            app.add_plugins(ExpressionsPlugin);
+        }
+        app.init_resource::<Combo>()
+            .add_systems(Startup, load_formula)
+            .add_systems(OnEnter(GameState::Playing), reset_combo)
+            .add_systems(
+                FixedUpdate,
+                score_bricks
+                    .after(check_for_collisions)
+                    .run_if(is_single_player),
+            )
+            .add_systems(Update, reload_formula);
+    }
+}
+
+/// パドルで打ち返してから壊したブロックの数
+#[derive(Resource, Default, Debug, Deref)]
+pub struct Combo(u32);
+
+impl Combo {
+    pub fn reset(&mut self) {
+        self.0 = 0;
+    }
+}
+
+/// ブロックの得点の計算式のハンドル
+#[derive(Resource)]
+struct BrickScoreFormula(Handle<Formula>);
+
+fn load_formula(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BrickScoreFormula(asset_server.load(BRICK_SCORE_FORMULA)));
+}
+
+fn reset_combo(mut combo: ResMut<Combo>) {
+    combo.reset();
+}
+
+/// F5 キーで計算式をファイルから読み込み直す
+fn reload_formula(keyboard_input: Res<ButtonInput<KeyCode>>, asset_server: Res<AssetServer>) {
+    if keyboard_input.just_pressed(RELOAD_KEY) {
+        info!("reloading {BRICK_SCORE_FORMULA}");
+        asset_server.reload(BRICK_SCORE_FORMULA);
+    }
+}
+
+/// 壊されたブロックごとに計算式で得点を求めて加算し、コンボを伸ばす
+///
+/// 計算式がまだ読み込まれていないときや評価に失敗したときは、基本の得点を加算する。
+fn score_bricks(
+    mut events: EventReader<BrickDestroyed>,
+    mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+    level: Res<Level>,
+    formula: Res<BrickScoreFormula>,
+    formulas: Res<Assets<Formula>>,
+) {
+    for event in events.read() {
+        // 対戦モードのブロックの得点は `versus.rs` が加算する
+        if event.side.is_some() {
+            continue;
+        }
+
+        let variables = Variables::new()
+            .with("base", BASE_BRICK_SCORE as f64)
+            .with("combo", **combo)
+            .with("level", **level);
+        let points = match formulas
+            .get(&formula.0)
+            .map(|formula| formula.eval(&variables))
+        {
+            // 得点が負にならないようにする
+            Some(Ok(points)) => points.round().max(0.0) as usize,
+            Some(Err(error)) => {
+                warn!("could not evaluate {BRICK_SCORE_FORMULA}: {error}");
+                BASE_BRICK_SCORE
+            }
+            None => BASE_BRICK_SCORE,
+        };
+        **score += points;
+        combo.0 += 1;
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::{
+    math::bounding::{Aabb2d, BoundingVolume},
+    prelude::*,
+};
+
+use crate::{
+    ball::{check_for_collisions, Ball, Velocity, BALL_DIAMETER},
+    boss::Boss,
+    bricks::{Brick, BrickDestroyed},
+    is_single_player, levels,
+};
+
+// スローモーションでいちばん遅くなったときの仮想時間の速さ
+const SLOWEST_SPEED: f32 = 0.2;
+// 通常の速さからいちばん遅くなるまでの時間と、元の速さに戻るまでの時間 (実時間の秒)
+const EASE_IN_SECONDS: f32 = 0.15;
+const EASE_OUT_SECONDS: f32 = 0.8;
+// 最後のブロックにぶつかる何秒前 (ゲーム内の時間) からスローモーションにするか
+const FINAL_HIT_LOOKAHEAD: f32 = 0.35;
+// 1 ステップで複数のブロックを壊したときに、いちばん遅いまま保つ時間 (実時間の秒)
+const MULTI_HIT_HOLD_SECONDS: f32 = 0.3;
+
+/// 見せ場で仮想時間をゆっくりにするスローモーションを追加するプラグイン
+///
+/// ボールが最後のブロックにぶつかる直前と、1 回の固定ステップでボールが 2 つ以上のブロックを
+/// まとめて壊したとき (ボールは 1 つなので、これを複数の衝突が重なった場面として扱う) に、
+/// `Time<Virtual>` の速さを `SLOWEST_SPEED` まで下げ、しばらくしてから元の速さに戻す。
+/// 速さの変化は実時間で進めるので、仮想時間が止まっている勝利演出やリプレイの間も元に戻っていく。
+/// UI は実時間で動き、パドルの移動は [`unscaled_delta_secs`] で遅くした分を打ち消すので、
+/// スローモーション中も操作の手応えは変わらない。
+pub struct SlowMotionPlugin;
+
+impl Plugin for SlowMotionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SlowMotion>()
+            .add_systems(
+                FixedUpdate,
+                (slow_before_final_hit, slow_on_multi_hit)
+                    .after(check_for_collisions)
+                    .run_if(is_single_player),
+            )
+            .add_systems(Update, apply_time_scale);
+    }
+}
+
+/// スローモーションの進み具合
+#[derive(Resource, Default)]
+struct SlowMotion {
+    progress: f32, // 0 なら通常の速さ、1 ならいちばん遅い
+    hold: f32,     // いちばん遅い方へ進め続ける残り時間 (実時間の秒)
+}
+
+impl SlowMotion {
+    /// 少なくとも `seconds` 秒 (実時間) の間、いちばん遅い方へ進める
+    fn hold_for(&mut self, seconds: f32) {
+        self.hold = self.hold.max(seconds);
+    }
+
+    /// 進み具合から求めた仮想時間の速さ (急に変わらないように滑らかに補間する)
+    fn speed(&self) -> f32 {
+        let t = self.progress * self.progress * (3.0 - 2.0 * self.progress);
+        1.0_f32.lerp(SLOWEST_SPEED, t)
+    }
+}
+
+/// 仮想時間の速さを打ち消した経過時間
+///
+/// `FixedUpdate` は仮想時間が遅いほど少ない回数しか実行されないので、
+/// 1 ステップの経過時間を速さで割ると、実時間で見た移動の速さが変わらなくなる。
+pub fn unscaled_delta_secs(time: &Time, virtual_time: &Time<Virtual>) -> f32 {
+    time.delta_secs() / virtual_time.relative_speed()
+}
+
+/// ブロックが 1 つだけ残っていて、ボールがこのまま進むとすぐにぶつかるときはスローモーションにする
+///
+/// 硬いブロックやボスが残っているときは、次の衝突でレベルが終わらないので何もしない。
+#[allow(clippy::type_complexity)]
+fn slow_before_final_hit(
+    ball: Single<(&Transform, &Velocity), With<Ball>>,
+    bricks: Query<(&Transform, Has<levels::Sturdy>), With<Brick>>,
+    bosses: Query<(), With<Boss>>,
+    mut slow_motion: ResMut<SlowMotion>,
+) {
+    let Ok((brick_transform, sturdy)) = bricks.get_single() else {
+        return;
+    };
+    if sturdy || !bosses.is_empty() {
+        return;
+    }
+    let (ball_transform, velocity) = ball.into_inner();
+    // ボールの中心が、ボールの半径だけ広げたブロックの箱に入るまでの時間を求める
+    let bounds = Aabb2d::new(
+        brick_transform.translation.truncate(),
+        brick_transform.scale.truncate() / 2.0,
+    )
+    .grow(Vec2::splat(BALL_DIAMETER / 2.0));
+    let time_to_hit = time_to_enter(ball_transform.translation.truncate(), velocity.0, bounds);
+    if time_to_hit.is_some_and(|t| t <= FINAL_HIT_LOOKAHEAD) {
+        // ぶつかるまで毎ステップ延ばし、ぶつかったあとで元の速さに戻し始める
+        slow_motion.hold_for(EASE_IN_SECONDS);
+    }
+}
+
+/// 1 回の固定ステップで 2 つ以上のブロックが壊れたら、少しの間スローモーションにする
+fn slow_on_multi_hit(
+    mut destroyed_events: EventReader<BrickDestroyed>,
+    mut slow_motion: ResMut<SlowMotion>,
+) {
+    if destroyed_events.read().count() >= 2 {
+        slow_motion.hold_for(EASE_IN_SECONDS + MULTI_HIT_HOLD_SECONDS);
+    }
+}
+
+/// 点 `position` から速度 `velocity` で進んだときに、箱 `bounds` に入るまでの時間 (入らなければ `None`)
+///
+/// x と y の向きごとに箱の範囲に入っている時間を求め、その重なりの始まりを返す (スラブ法)。
+fn time_to_enter(position: Vec2, velocity: Vec2, bounds: Aabb2d) -> Option<f32> {
+    let mut enter = 0.0_f32;
+    let mut exit = f32::INFINITY;
+    for axis in 0..2 {
+        let (p, v) = (position[axis], velocity[axis]);
+        let (min, max) = (bounds.min[axis], bounds.max[axis]);
+        if v == 0.0 {
+            if p < min || p > max {
+                return None;
+            }
+            continue;
+        }
+        let (t0, t1) = ((min - p) / v, (max - p) / v);
+        enter = enter.max(t0.min(t1));
+        exit = exit.min(t0.max(t1));
+    }
+    (enter <= exit).then_some(enter)
+}
+
+/// スローモーションの進み具合を実時間で進め、仮想時間の速さに反映する
+fn apply_time_scale(
+    real_time: Res<Time<Real>>,
+    mut slow_motion: ResMut<SlowMotion>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if slow_motion.progress == 0.0 && slow_motion.hold <= 0.0 {
+        return;
+    }
+    let dt = real_time.delta_secs();
+    if slow_motion.hold > 0.0 {
+        slow_motion.hold -= dt;
+        slow_motion.progress = (slow_motion.progress + dt / EASE_IN_SECONDS).min(1.0);
+    } else {
+        slow_motion.progress = (slow_motion.progress - dt / EASE_OUT_SECONDS).max(0.0);
+    }
+    virtual_time.set_relative_speed(slow_motion.speed());
+}
@@ -0,0 +1,217 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::{
+    attract::is_attract_mode,
+    locale::{LocalizedFont, Strings},
+    records::Records,
+    theme::{ThemeColor, Themed},
+    ui::SCOREBOARD_TEXT_PADDING,
+    victory::{detect_level_cleared, LevelCleared},
+    GameMode, GameState, Level,
+};
+
+// タイマーのフォントサイズ
+const TIMER_FONT_SIZE: f32 = 33.0;
+// クリアタイムのフォントサイズ
+const RESULT_FONT_SIZE: f32 = 20.0;
+// クリアタイムを表示し続ける時間 (秒)
+const RESULT_DISPLAY_SECS: f32 = 4.0;
+
+/// タイムアタックモードのタイマーを追加するプラグイン
+///
+/// [`GameMode::TimeAttack`] のときだけ動作する。レベルごとにタイマーを計り、
+/// 最後のブロック (とボス) が壊れた時点 ([`LevelCleared`]) のタイムを [`Records`] のベストタイムと比べる。
+/// 勝利演出の間は仮想時間が止まるので、タイマーも進まない。
+pub struct SpeedrunPlugin;
+
+impl Plugin for SpeedrunPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Speedrun>()
+            .add_systems(
+                Startup,
+                spawn_timer_ui.run_if(resource_equals(GameMode::TimeAttack)),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                reset_timer.run_if(resource_equals(GameMode::TimeAttack)),
+            )
+            .add_systems(
+                Update,
+                (
+                    (tick_timer, record_clear_time)
+                        .chain()
+                        .after(detect_level_cleared)
+                        .run_if(in_state(GameState::Playing).and(not(is_attract_mode))),
+                    update_timer_ui.after(record_clear_time),
+                )
+                    .run_if(resource_equals(GameMode::TimeAttack)),
+            );
+    }
+}
+
+/// 1 つのレベルのクリアタイム
+#[derive(Debug, Clone, Copy)]
+struct LevelResult {
+    level: u32,
+    secs: f64,
+    previous_best: Option<f64>,
+    new_best: bool,
+}
+
+/// 現在のレベルのタイマーと、直前にクリアしたレベルの結果
+#[derive(Resource)]
+struct Speedrun {
+    stopwatch: Stopwatch,
+    level: u32,
+    last_result: Option<LevelResult>,
+    result_timer: Timer,
+}
+
+impl Default for Speedrun {
+    fn default() -> Self {
+        Speedrun {
+            stopwatch: Stopwatch::new(),
+            level: 1,
+            last_result: None,
+            result_timer: Timer::from_seconds(RESULT_DISPLAY_SECS, TimerMode::Once),
+        }
+    }
+}
+
+/// タイマーの表示を示すコンポーネント
+#[derive(Component)]
+struct TimerUi;
+
+/// クリアタイムの表示を示すコンポーネント
+#[derive(Component)]
+struct ResultUi;
+
+/// 画面右上にタイマーとクリアタイムの表示を配置する
+fn spawn_timer_ui(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: SCOREBOARD_TEXT_PADDING,
+            right: SCOREBOARD_TEXT_PADDING,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: TIMER_FONT_SIZE,
+                    ..default()
+                },
+                Themed(ThemeColor::Score),
+                TimerUi,
+            ));
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: RESULT_FONT_SIZE,
+                    ..default()
+                },
+                Themed(ThemeColor::Text),
+                ResultUi,
+                LocalizedFont,
+            ));
+        });
+}
+
+/// リスタート時にタイマーと直前の結果を消す
+fn reset_timer(mut speedrun: ResMut<Speedrun>) {
+    *speedrun = Speedrun::default();
+}
+
+/// タイマーを進める (レベルが変わっていたらリセットする)
+fn tick_timer(mut speedrun: ResMut<Speedrun>, level: Res<Level>, time: Res<Time>) {
+    if speedrun.level != **level {
+        speedrun.level = **level;
+        speedrun.stopwatch.reset();
+    }
+    speedrun.stopwatch.tick(time.delta());
+    speedrun.result_timer.tick(time.delta());
+}
+
+/// レベルをクリアしたらクリアタイムを記録する
+fn record_clear_time(
+    mut speedrun: ResMut<Speedrun>,
+    mut records: ResMut<Records>,
+    mut cleared_events: EventReader<LevelCleared>,
+) {
+    let Some(&LevelCleared { level, .. }) = cleared_events.read().last() else {
+        return;
+    };
+
+    let secs = speedrun.stopwatch.elapsed_secs_f64();
+    let previous_best = records.best_time(level);
+    let new_best = records.submit_time(level, secs);
+    info!("level {level} cleared in {}", format_time(secs));
+
+    speedrun.last_result = Some(LevelResult {
+        level,
+        secs,
+        previous_best,
+        new_best,
+    });
+    speedrun.result_timer.reset();
+}
+
+/// タイマーとクリアタイムの表示を更新する
+fn update_timer_ui(
+    speedrun: Res<Speedrun>,
+    records: Res<Records>,
+    mut timer_text: Single<&mut Text, (With<TimerUi>, Without<ResultUi>)>,
+    result_text: Single<(&mut Text, &mut Themed), With<ResultUi>>,
+    strings: Strings,
+) {
+    timer_text.0 = format_time(speedrun.stopwatch.elapsed_secs_f64());
+
+    let (mut result_text, mut result_color) = result_text.into_inner();
+    match speedrun.last_result {
+        Some(result) if !speedrun.result_timer.finished() => {
+            let mut line = strings.format(
+                "level_clear",
+                &[
+                    ("level", &result.level),
+                    ("time", &format_time(result.secs)),
+                ],
+            );
+            if result.new_best {
+                line += "  ";
+                line += strings.get("new_best");
+            } else if let Some(best) = result.previous_best {
+                line += "  ";
+                line += &strings.format("previous_best", &[("time", &format_time(best))]);
+            }
+            result_text.0 = line;
+            // 毎フレーム色を塗り直さないよう、色の種類が変わったときだけ変更扱いにする
+            result_color.set_if_neq(Themed(if result.new_best {
+                ThemeColor::NewBest
+            } else {
+                ThemeColor::Text
+            }));
+        }
+        // 結果を表示していない間は、現在のレベルのベストタイムを表示する
+        _ => {
+            result_text.0 = match records.best_time(speedrun.level) {
+                Some(best) => strings.format("best_time", &[("time", &format_time(best))]),
+                None => strings.format("best_time", &[("time", &"--:--.---")]),
+            };
+            result_color.set_if_neq(Themed(ThemeColor::Text));
+        }
+    }
+}
+
+/// 秒数を `分:秒.ミリ秒` の形式にする
+fn format_time(secs: f64) -> String {
+    let millis = (secs * 1000.0).floor() as u64;
+    format!(
+        "{:02}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
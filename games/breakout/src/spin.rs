@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{apply_velocity, check_for_collisions, Ball, CollisionEvent, Velocity},
+    paddle::Paddle,
+};
+
+// パドルの速さ 1 あたりにボールに与える回転量
+const SPIN_PER_PADDLE_SPEED: f32 = 0.004;
+// 回転量の上限
+const MAX_SPIN: f32 = 3.0;
+// 回転量 1 あたりにボールの進行方向が曲がる速さ (rad/s)
+const MAGNUS_FACTOR: f32 = 0.5;
+// 回転が減衰する速さ (1 秒あたりの割合)
+const SPIN_DECAY: f32 = 1.5;
+// 回転量 1 あたりに反射の向きがずれる角度 (rad)
+const REFLECTION_SKEW: f32 = 0.1;
+// ボールの速度に占める y 成分の最小の割合 (横方向に往復し続けないようにする)
+const MIN_VERTICAL_RATIO: f32 = 0.3;
+
+/// ボールの回転 (スピン) を追加するプラグイン
+///
+/// パドルを動かしながらボールを打ち返すと回転がかかり、
+/// 回転したボールは飛行中に曲がり、反射の向きも少しずれる。
+pub struct SpinPlugin;
+
+impl Plugin for SpinPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                curve_ball.before(apply_velocity),
+                (track_paddle_velocity, spin_on_collision)
+                    .chain()
+                    .after(check_for_collisions),
+            ),
+        );
+    }
+}
+
+/// ボールの回転量 (正の値は反時計回り)
+#[derive(Component, Reflect, Default, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct Spin(f32);
+
+/// パドルの直近の x 方向の速度
+#[derive(Component, Default)]
+struct PaddleMotion {
+    previous_x: Option<f32>,
+    velocity_x: f32,
+}
+
+/// パドルの移動量から x 方向の速度を求める
+///
+/// パドルの移動は AI を含めて複数のシステムが行うため、すべての移動が終わった後に位置の差分から求める。
+fn track_paddle_velocity(
+    mut commands: Commands,
+    mut paddles: Query<(Entity, &Transform, Option<&mut PaddleMotion>), With<Paddle>>,
+    time: Res<Time>,
+) {
+    for (entity, transform, motion) in &mut paddles {
+        let x = transform.translation.x;
+        let Some(mut motion) = motion else {
+            commands.entity(entity).insert(PaddleMotion {
+                previous_x: Some(x),
+                velocity_x: 0.0,
+            });
+            continue;
+        };
+        if let Some(previous_x) = motion.previous_x {
+            motion.velocity_x = (x - previous_x) / time.delta_secs();
+        }
+        motion.previous_x = Some(x);
+    }
+}
+
+/// 衝突したボールに回転を反映する
+///
+/// パドルに当たった瞬間はパドルの速度に応じて回転を加え、
+/// 何かに当たるたびに反射後の進行方向を回転の向きに少しずらす。
+fn spin_on_collision(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut balls: Query<(&mut Velocity, &mut Spin), With<Ball>>,
+    paddles: Query<&PaddleMotion>,
+    mut touching_paddle: Local<Option<Entity>>,
+) {
+    // 押し付けている間に何度も回転が加わらないよう、当たり始めたステップだけ加える
+    let mut collided_ball = None;
+    let mut touched = None;
+    for event in collision_events.read() {
+        collided_ball = Some(event.ball);
+        if paddles.contains(event.collider) {
+            touched = Some(event.collider);
+        }
+    }
+    let newly_touched = touched.filter(|entity| *touching_paddle != Some(*entity));
+    *touching_paddle = touched;
+
+    let Some(Ok((mut ball_velocity, mut spin))) = collided_ball.map(|ball| balls.get_mut(ball))
+    else {
+        return;
+    };
+
+    if let Some(motion) = newly_touched.and_then(|entity| paddles.get(entity).ok()) {
+        // 右に動かしながら打ち返すと、ボールは進行方向から見て右へ曲がる
+        let direction = ball_velocity.y.signum();
+        **spin = (**spin - motion.velocity_x * SPIN_PER_PADDLE_SPEED * direction)
+            .clamp(-MAX_SPIN, MAX_SPIN);
+    }
+
+    ball_velocity.0 =
+        keep_vertical(Vec2::from_angle(**spin * REFLECTION_SKEW).rotate(ball_velocity.0));
+}
+
+/// 回転に応じてボールの進行方向を曲げ (マグヌス効果)、回転を減衰させる
+///
+/// 向きだけを変えるため、ボールの速さは変わらない。
+fn curve_ball(mut balls: Query<(&mut Velocity, &mut Spin), With<Ball>>, time: Res<Time>) {
+    let dt = time.delta_secs();
+    for (mut velocity, mut spin) in &mut balls {
+        if **spin == 0.0 {
+            continue;
+        }
+        let curve = Vec2::from_angle(**spin * MAGNUS_FACTOR * dt);
+        velocity.0 = keep_vertical(curve.rotate(velocity.0));
+
+        **spin *= (-SPIN_DECAY * dt).exp();
+        if spin.abs() < 0.01 {
+            **spin = 0.0;
+        }
+    }
+}
+
+/// 速さを保ったまま、y 成分が小さくなりすぎないように向きを補正する
+fn keep_vertical(velocity: Vec2) -> Vec2 {
+    let speed = velocity.length();
+    let min_y = speed * MIN_VERTICAL_RATIO;
+    if velocity.y.abs() >= min_y {
+        return velocity;
+    }
+    let y = min_y.copysign(velocity.y);
+    let x = (speed * speed - y * y).sqrt().copysign(velocity.x);
+    Vec2::new(x, y)
+}
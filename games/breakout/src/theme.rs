@@ -0,0 +1,431 @@
+use bevy::prelude::*;
+use debug_stepping::SteppingTheme;
+
+use crate::locale::{LocalizedFont, Strings};
+
+// テーマを切り替えるキー
+const TOGGLE_KEY: KeyCode = KeyCode::KeyT;
+// テーマ切り替えのヒントのフォントサイズ
+const HINT_FONT_SIZE: f32 = 15.0;
+// テーマ切り替えのヒントの下端の位置 (言語切り替えのヒントの上に並べる)
+const HINT_BOTTOM: Val = Val::Px(25.0);
+
+/// ゲーム中の色をまとめた [`Theme`] を管理し、`T` キーで配色を切り替えるプラグイン
+///
+/// 色を付けたいエンティティには [`Themed`] で色の種類を指定する。
+/// テーマが切り替わったときや [`Themed`] が変更されたときに、スプライト・メッシュのマテリアル・
+/// テキスト・UI の背景の色が書き換わる。
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Theme>()
+            .add_systems(Startup, spawn_theme_hint)
+            .add_systems(Update, (toggle_palette, update_theme_hint).chain())
+            // 各スケジュールで色の種類が変更されたあと、描画の前にまとめて反映する
+            .add_systems(PostUpdate, (apply_theme, apply_stepping_theme));
+    }
+}
+
+/// 組み込みの配色
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    #[default]
+    Default, // 標準の配色
+    HighContrast,     // 黒い背景に明るい色を置いた、見分けやすさを優先した配色
+    DeuteranopiaSafe, // 赤と緑の組み合わせを避けた、2 型色覚でも見分けやすい配色
+}
+
+impl Palette {
+    /// 次の配色 (切り替えの順番)
+    fn next(self) -> Palette {
+        match self {
+            Palette::Default => Palette::HighContrast,
+            Palette::HighContrast => Palette::DeuteranopiaSafe,
+            Palette::DeuteranopiaSafe => Palette::Default,
+        }
+    }
+
+    /// ヒントに表示する文字列のキー
+    fn hint_key(self) -> &'static str {
+        match self {
+            Palette::Default => "theme_hint_default",
+            Palette::HighContrast => "theme_hint_high_contrast",
+            Palette::DeuteranopiaSafe => "theme_hint_deuteranopia",
+        }
+    }
+}
+
+/// 現在の配色
+///
+/// 色を直接読むシステムは `Res<Theme>` の `is_changed()` で配色の切り替えを検出できる。
+#[derive(Resource, Debug, Clone)]
+pub struct Theme {
+    pub palette: Palette,
+    pub background: Color,
+    pub paddle: Color,
+    pub second_paddle: Color, // 2P パドル
+    pub top_paddle: Color,    // 対戦モードの上側のパドル
+    pub ball: Color,
+    pub brick: Color,
+    pub sturdy_brick: Color, // 2 回当てると壊れるブロック
+    pub crack: Color,        // ブロックに重ねるひび
+    pub portal: Color,       // ボールを相方へ移すポータル
+    pub top_brick: Color,    // 対戦モードの上側のブロック
+    pub wall: Color,
+    pub text: Color,
+    pub score: Color,
+    pub hint: Color,                    // 操作のヒント
+    pub title: Color,                   // ゲームオーバー画面の見出し
+    pub new_best: Color,                // タイムアタックでベストを更新したときの結果
+    pub overlay: Color,                 // ゲームオーバー画面の背景
+    pub stepping_text: Color,           // ステッピングの UI
+    pub stepping_button: Color,         // ステッピングの UI のボタン
+    pub stepping_button_hovered: Color, // カーソルが乗っているステッピングの UI のボタン
+    pub stepping_slow: Color,           // ステッピングの UI で、処理時間がしきい値を超えたシステム
+    pub stepping_skipped: Color,        // ステッピングの UI で、実行条件で飛ばされたシステム
+    pub stepping_panel: Color,          // ステッピングの UI のパネルの背景
+    pub boss_frame: Color,
+    pub boss_segment: Color,
+    pub boss_broken_segment: Color,
+    pub hazard: Color,
+    pub stunned_paddle: Color,
+    pub health_bar_background: Color,
+    pub health_bar: Color,
+    pub coin: Color,
+    pub gem: Color,
+    pub force_field: Color,      // ボールを 1 回だけ跳ね返すバリア
+    pub magnet: Color,           // パドルに磁場をまとわせるドロップ
+    pub magnet_field: Color,     // パドルがまとう磁場
+    pub editor_cell: Color,      // レベルエディタの空きマス
+    pub editor_cursor: Color,    // レベルエディタで選んでいるマスの枠
+    pub collider_bounds: Color,  // ギズモで描くコライダーの当たり判定
+    pub ball_bounds: Color,      // ギズモで描くボールの当たり判定
+    pub collision_normal: Color, // ギズモで描く直前の衝突の法線
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(Palette::default())
+    }
+}
+
+impl Theme {
+    pub fn new(palette: Palette) -> Theme {
+        match palette {
+            Palette::Default => Theme {
+                palette,
+                background: Color::srgb(0.9, 0.9, 0.9),
+                paddle: Color::srgb(0.3, 0.3, 0.7),
+                second_paddle: Color::srgb(0.3, 0.7, 0.3),
+                top_paddle: Color::srgb(0.7, 0.3, 0.3),
+                ball: Color::srgb(1.0, 0.5, 0.5),
+                brick: Color::srgb(0.5, 0.5, 1.0),
+                sturdy_brick: Color::srgb(0.25, 0.25, 0.7),
+                crack: Color::srgba(0.1, 0.1, 0.2, 0.8),
+                portal: Color::srgb(0.6, 0.3, 0.9),
+                top_brick: Color::srgb(1.0, 0.7, 0.5),
+                wall: Color::srgb(0.8, 0.8, 0.8),
+                text: Color::srgb(0.5, 0.5, 1.0),
+                score: Color::srgb(1.0, 0.5, 0.5),
+                hint: Color::srgb(0.4, 0.4, 0.4),
+                title: Color::srgb(1.0, 0.5, 0.5),
+                new_best: Color::srgb(1.0, 0.8, 0.2),
+                overlay: Color::srgba(0.0, 0.0, 0.0, 0.75),
+                stepping_text: Color::srgb(0.2, 0.2, 0.2),
+                stepping_button: Color::srgba(1.0, 1.0, 1.0, 0.6),
+                stepping_button_hovered: Color::srgba(0.6, 0.6, 1.0, 0.8),
+                stepping_slow: Color::srgb(0.8, 0.1, 0.1),
+                stepping_skipped: Color::srgb(0.55, 0.55, 0.55),
+                stepping_panel: Color::srgba(1.0, 1.0, 1.0, 0.33),
+                boss_frame: Color::srgb(0.3, 0.1, 0.3),
+                boss_segment: Color::srgb(0.8, 0.3, 0.8),
+                boss_broken_segment: Color::srgb(0.45, 0.25, 0.45),
+                hazard: Color::srgb(0.9, 0.2, 0.2),
+                stunned_paddle: Color::srgb(0.6, 0.6, 0.6),
+                health_bar_background: Color::srgb(0.2, 0.2, 0.2),
+                health_bar: Color::srgb(0.9, 0.2, 0.4),
+                coin: Color::srgb(1.0, 0.8, 0.2),
+                gem: Color::srgb(0.3, 0.9, 0.8),
+                force_field: Color::srgb(0.3, 0.75, 1.0),
+                magnet: Color::srgb(0.9, 0.3, 0.6),
+                magnet_field: Color::srgba(0.9, 0.3, 0.6, 0.25),
+                editor_cell: Color::srgba(0.5, 0.5, 1.0, 0.15),
+                editor_cursor: Color::srgb(1.0, 0.5, 0.5),
+                collider_bounds: Color::srgb(0.0, 0.7, 0.0),
+                ball_bounds: Color::srgb(0.9, 0.0, 0.0),
+                collision_normal: Color::srgb(0.9, 0.5, 0.0),
+            },
+            Palette::HighContrast => Theme {
+                palette,
+                background: Color::BLACK,
+                paddle: Color::srgb(1.0, 1.0, 0.0),
+                second_paddle: Color::srgb(0.0, 1.0, 1.0),
+                top_paddle: Color::srgb(1.0, 0.0, 1.0),
+                ball: Color::WHITE,
+                brick: Color::srgb(0.3, 0.6, 1.0),
+                sturdy_brick: Color::srgb(0.75, 0.85, 1.0),
+                crack: Color::BLACK,
+                portal: Color::srgb(0.8, 0.4, 1.0),
+                top_brick: Color::srgb(1.0, 0.5, 0.0),
+                wall: Color::WHITE,
+                text: Color::WHITE,
+                score: Color::srgb(1.0, 1.0, 0.0),
+                hint: Color::srgb(0.8, 0.8, 0.8),
+                title: Color::srgb(1.0, 1.0, 0.0),
+                new_best: Color::srgb(0.0, 1.0, 0.0),
+                overlay: Color::srgba(0.0, 0.0, 0.0, 0.9),
+                stepping_text: Color::srgb(1.0, 1.0, 0.0),
+                stepping_button: Color::srgb(0.2, 0.2, 0.2),
+                stepping_button_hovered: Color::srgb(0.2, 0.2, 0.8),
+                stepping_slow: Color::srgb(1.0, 0.35, 0.3),
+                stepping_skipped: Color::srgb(0.5, 0.5, 0.3),
+                stepping_panel: Color::srgba(0.0, 0.0, 0.0, 0.8),
+                boss_frame: Color::srgb(0.5, 0.5, 0.5),
+                boss_segment: Color::srgb(1.0, 0.0, 1.0),
+                boss_broken_segment: Color::srgb(0.3, 0.0, 0.3),
+                hazard: Color::srgb(1.0, 0.2, 0.2),
+                stunned_paddle: Color::srgb(0.4, 0.4, 0.4),
+                health_bar_background: Color::srgb(0.3, 0.3, 0.3),
+                health_bar: Color::srgb(1.0, 0.0, 1.0),
+                coin: Color::srgb(1.0, 1.0, 0.0),
+                gem: Color::srgb(0.0, 1.0, 1.0),
+                force_field: Color::srgb(0.5, 1.0, 1.0),
+                magnet: Color::srgb(1.0, 0.0, 1.0),
+                magnet_field: Color::srgba(1.0, 0.0, 1.0, 0.3),
+                editor_cell: Color::srgba(1.0, 1.0, 1.0, 0.15),
+                editor_cursor: Color::srgb(1.0, 1.0, 0.0),
+                collider_bounds: Color::srgb(0.0, 1.0, 0.0),
+                ball_bounds: Color::srgb(1.0, 0.0, 1.0),
+                collision_normal: Color::srgb(1.0, 1.0, 0.0),
+            },
+            // Okabe-Ito の配色を元に、赤・緑ではなく青・橙・黄の明るさの差で区別する
+            Palette::DeuteranopiaSafe => Theme {
+                palette,
+                background: Color::srgb(0.9, 0.9, 0.9),
+                paddle: Color::srgb(0.0, 0.45, 0.7),
+                second_paddle: Color::srgb(0.9, 0.6, 0.0),
+                top_paddle: Color::srgb(0.8, 0.4, 0.0),
+                ball: Color::srgb(0.8, 0.4, 0.0),
+                brick: Color::srgb(0.35, 0.7, 0.9),
+                sturdy_brick: Color::srgb(0.0, 0.45, 0.7),
+                crack: Color::srgba(0.1, 0.1, 0.1, 0.8),
+                portal: Color::srgb(0.8, 0.6, 0.7),
+                top_brick: Color::srgb(0.9, 0.6, 0.0),
+                wall: Color::srgb(0.6, 0.6, 0.6),
+                text: Color::srgb(0.0, 0.45, 0.7),
+                score: Color::srgb(0.8, 0.4, 0.0),
+                hint: Color::srgb(0.3, 0.3, 0.3),
+                title: Color::srgb(0.95, 0.9, 0.25),
+                new_best: Color::srgb(0.95, 0.9, 0.25),
+                overlay: Color::srgba(0.0, 0.0, 0.0, 0.75),
+                stepping_text: Color::srgb(0.1, 0.1, 0.1),
+                stepping_button: Color::srgba(1.0, 1.0, 1.0, 0.6),
+                stepping_button_hovered: Color::srgba(0.35, 0.7, 0.9, 0.8),
+                stepping_slow: Color::srgb(0.75, 0.1, 0.2),
+                stepping_skipped: Color::srgb(0.5, 0.55, 0.6),
+                stepping_panel: Color::srgba(1.0, 1.0, 1.0, 0.33),
+                boss_frame: Color::srgb(0.2, 0.2, 0.3),
+                boss_segment: Color::srgb(0.8, 0.6, 0.7),
+                boss_broken_segment: Color::srgb(0.45, 0.4, 0.45),
+                hazard: Color::srgb(0.8, 0.4, 0.0),
+                stunned_paddle: Color::srgb(0.5, 0.5, 0.5),
+                health_bar_background: Color::srgb(0.2, 0.2, 0.2),
+                health_bar: Color::srgb(0.95, 0.9, 0.25),
+                coin: Color::srgb(0.95, 0.9, 0.25),
+                gem: Color::srgb(0.0, 0.6, 0.5),
+                force_field: Color::srgb(0.35, 0.7, 0.9),
+                magnet: Color::srgb(0.8, 0.4, 0.0),
+                magnet_field: Color::srgba(0.8, 0.4, 0.0, 0.25),
+                editor_cell: Color::srgba(0.0, 0.45, 0.7, 0.15),
+                editor_cursor: Color::srgb(0.9, 0.6, 0.0),
+                collider_bounds: Color::srgb(0.0, 0.6, 0.5),
+                ball_bounds: Color::srgb(0.8, 0.4, 0.0),
+                collision_normal: Color::srgb(0.0, 0.45, 0.7),
+            },
+        }
+    }
+
+    /// 色の種類に対応する色
+    pub fn color(&self, color: ThemeColor) -> Color {
+        match color {
+            ThemeColor::Background => self.background,
+            ThemeColor::Paddle => self.paddle,
+            ThemeColor::SecondPaddle => self.second_paddle,
+            ThemeColor::TopPaddle => self.top_paddle,
+            ThemeColor::Ball => self.ball,
+            ThemeColor::Brick => self.brick,
+            ThemeColor::SturdyBrick => self.sturdy_brick,
+            ThemeColor::Crack => self.crack,
+            ThemeColor::Portal => self.portal,
+            ThemeColor::TopBrick => self.top_brick,
+            ThemeColor::Wall => self.wall,
+            ThemeColor::Text => self.text,
+            ThemeColor::Score => self.score,
+            ThemeColor::Hint => self.hint,
+            ThemeColor::Title => self.title,
+            ThemeColor::NewBest => self.new_best,
+            ThemeColor::Overlay => self.overlay,
+            ThemeColor::BossFrame => self.boss_frame,
+            ThemeColor::BossSegment => self.boss_segment,
+            ThemeColor::BossBrokenSegment => self.boss_broken_segment,
+            ThemeColor::Hazard => self.hazard,
+            ThemeColor::StunnedPaddle => self.stunned_paddle,
+            ThemeColor::HealthBarBackground => self.health_bar_background,
+            ThemeColor::HealthBar => self.health_bar,
+            ThemeColor::Coin => self.coin,
+            ThemeColor::Gem => self.gem,
+            ThemeColor::ForceField => self.force_field,
+            ThemeColor::Magnet => self.magnet,
+            ThemeColor::MagnetField => self.magnet_field,
+            ThemeColor::EditorCell => self.editor_cell,
+            ThemeColor::EditorCursor => self.editor_cursor,
+            ThemeColor::ColliderBounds => self.collider_bounds,
+            ThemeColor::BallBounds => self.ball_bounds,
+            ThemeColor::CollisionNormal => self.collision_normal,
+        }
+    }
+}
+
+/// [`Theme`] のどの色を使うか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeColor {
+    Background,
+    Paddle,
+    SecondPaddle,
+    TopPaddle,
+    Ball,
+    Brick,
+    SturdyBrick,
+    Crack,
+    Portal,
+    TopBrick,
+    Wall,
+    Text,
+    Score,
+    Hint,
+    Title,
+    NewBest,
+    Overlay,
+    BossFrame,
+    BossSegment,
+    BossBrokenSegment,
+    Hazard,
+    StunnedPaddle,
+    HealthBarBackground,
+    HealthBar,
+    Coin,
+    Gem,
+    ForceField,
+    Magnet,
+    MagnetField,
+    EditorCell,
+    EditorCursor,
+    ColliderBounds,
+    BallBounds,
+    CollisionNormal,
+}
+
+/// テーマの色で塗るエンティティを示すコンポーネント
+///
+/// [`Sprite`]・[`MeshMaterial2d<ColorMaterial>`]・[`TextColor`]・[`BackgroundColor`] のうち、
+/// エンティティが持っているものに色を設定する。状態に応じて色を変えるときは、色を直接書き換えずに
+/// このコンポーネントの値を変更する (テーマを切り替えても正しい色に戻せるようにするため)。
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Themed(pub ThemeColor);
+
+/// テーマ切り替えのヒントを示すコンポーネント
+#[derive(Component)]
+struct ThemeHint;
+
+/// 画面右下 (言語切り替えのヒントの上) にテーマ切り替えのヒントを配置する
+fn spawn_theme_hint(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: HINT_FONT_SIZE,
+            ..default()
+        },
+        Themed(ThemeColor::Hint),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: HINT_BOTTOM,
+            right: Val::Px(5.0),
+            ..default()
+        },
+        LocalizedFont,
+        ThemeHint,
+    ));
+}
+
+/// `T` キーで次の配色に切り替える
+fn toggle_palette(keyboard_input: Res<ButtonInput<KeyCode>>, mut theme: ResMut<Theme>) {
+    if keyboard_input.just_pressed(TOGGLE_KEY) {
+        *theme = Theme::new(theme.palette.next());
+        info!("palette: {:?}", theme.palette);
+    }
+}
+
+/// 配色か言語が変わったら、ヒントに現在の配色の名前を表示する
+fn update_theme_hint(
+    theme: Res<Theme>,
+    strings: Strings,
+    mut hint: Single<&mut Text, With<ThemeHint>>,
+) {
+    if theme.is_changed() || strings.is_changed() {
+        hint.0 = strings.get(theme.palette.hint_key()).to_string();
+    }
+}
+
+/// [`Themed`] を付けたエンティティを現在の配色で塗る
+///
+/// 配色が切り替わったときはすべてのエンティティを、そうでなければ [`Themed`] が追加・変更された
+/// エンティティだけを塗り直す。
+#[allow(clippy::type_complexity)]
+pub fn apply_theme(
+    theme: Res<Theme>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut themed: Query<(
+        Ref<Themed>,
+        Option<&mut Sprite>,
+        Option<&MeshMaterial2d<ColorMaterial>>,
+        Option<&mut TextColor>,
+        Option<&mut BackgroundColor>,
+    )>,
+) {
+    let refresh_all = theme.is_changed();
+
+    for (themed, sprite, material, text_color, background_color) in &mut themed {
+        if !refresh_all && !themed.is_changed() {
+            continue;
+        }
+        let color = theme.color(themed.0);
+        if let Some(mut sprite) = sprite {
+            sprite.color = color;
+        }
+        if let Some(material) = material.and_then(|material| materials.get_mut(material)) {
+            material.color = color;
+        }
+        if let Some(mut text_color) = text_color {
+            text_color.0 = color;
+        }
+        if let Some(mut background_color) = background_color {
+            background_color.0 = color;
+        }
+    }
+}
+
+/// 配色が切り替わったら、ステッピング UI (`crates/debug_stepping`) の配色も合わせる
+///
+/// フォントや文字の大きさは `SteppingPlugin::with_theme` で指定したものを残し、色だけを書き換える。
+fn apply_stepping_theme(theme: Res<Theme>, mut stepping_theme: ResMut<SteppingTheme>) {
+    if !theme.is_changed() {
+        return;
+    }
+    stepping_theme.text = theme.stepping_text;
+    stepping_theme.button = theme.stepping_button;
+    stepping_theme.button_hovered = theme.stepping_button_hovered;
+    stepping_theme.slow = theme.stepping_slow;
+    stepping_theme.skipped = theme.stepping_skipped;
+    stepping_theme.panel = theme.stepping_panel;
+}
@@ -0,0 +1,111 @@
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    ball::check_for_collisions,
+    boss,
+    camera::ArenaCursor,
+    is_single_player,
+    paddle::{clamp_paddle_x, move_paddle, Paddle, SecondPaddle, PADDLE_SPEED},
+    slow_motion,
+    victory::PlayPhase,
+    GameState,
+};
+
+// タッチしている位置とパドルの x 座標のずれがこれより小さければパドルを動かさない
+const DEAD_ZONE: f32 = 4.0;
+
+/// タッチ操作 (とマウスのドラッグ) でパドルを動かせるようにするプラグイン
+///
+/// 画面に触れている間 (マウスでは左ボタンを押している間)、1P パドルを触れている位置の x 座標へ
+/// キー操作と同じ最高速度で近づける。ゲームオーバー画面とレベルクリアの演出の間は、タップを `Enter` キーとして扱う。
+/// タブレットやスマートフォンのブラウザ (WebAssembly 版) で遊ぶための操作で、1 人用のルールでだけ使える。
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointerTarget>()
+            // ほかのシステムが `Enter` キーを読む前に、タップを `Enter` キーの入力にする
+            .add_systems(PreUpdate, tap_as_enter.after(InputSystem))
+            .add_systems(Update, track_pointer.run_if(is_single_player))
+            .add_systems(
+                FixedUpdate,
+                move_paddle_to_pointer
+                    .after(move_paddle)
+                    .before(check_for_collisions)
+                    .run_if(is_single_player.and(in_state(PlayPhase::Running))),
+            );
+    }
+}
+
+/// パドルを近づける x 座標 (画面に触れていないときは `None`)
+#[derive(Resource, Default)]
+struct PointerTarget(Option<f32>);
+
+/// 最初に触れた指 (なければ押している間のマウスカーソル) の位置を、パドルを近づける位置にする
+fn track_pointer(
+    touches: Res<Touches>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    cursor: ArenaCursor,
+    mut target: ResMut<PointerTarget>,
+) {
+    let position = touches.first_pressed_position().or_else(|| {
+        mouse_input
+            .pressed(MouseButton::Left)
+            .then(|| window.cursor_position())
+            .flatten()
+    });
+    target.0 = position
+        .and_then(|position| cursor.to_world(position))
+        .map(|world| world.x);
+}
+
+/// 1P パドルを触れている位置の x 座標に向かって移動させる
+#[allow(clippy::type_complexity)]
+fn move_paddle_to_pointer(
+    target: Res<PointerTarget>,
+    mut paddle_query: Query<
+        &mut Transform,
+        (With<Paddle>, Without<SecondPaddle>, Without<boss::Stunned>),
+    >,
+    time: Res<Time>,
+    virtual_time: Res<Time<Virtual>>,
+) {
+    let Some(target_x) = target.0 else {
+        return;
+    };
+    for mut paddle_transform in &mut paddle_query {
+        let offset = target_x - paddle_transform.translation.x;
+        if offset.abs() < DEAD_ZONE {
+            continue;
+        }
+        // キー操作と同じく、スローモーション中も実時間で同じ速さで動かす
+        let max_step = PADDLE_SPEED * slow_motion::unscaled_delta_secs(&time, &virtual_time);
+        let new_paddle_position =
+            paddle_transform.translation.x + offset.clamp(-max_step, max_step);
+        paddle_transform.translation.x =
+            clamp_paddle_x(new_paddle_position, paddle_transform.scale.x);
+    }
+}
+
+/// ゲームオーバー画面とレベルクリアの演出の間は、画面に触れたら `Enter` キーを押し、指を離したら離す
+///
+/// 押した `Enter` キーは指がすべて離れるまで押したままにする (途中で状態が変わっても必ず離す)。
+fn tap_as_enter(
+    touches: Res<Touches>,
+    state: Res<State<GameState>>,
+    phase: Option<Res<State<PlayPhase>>>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut holding: Local<bool>,
+) {
+    if *holding && touches.iter().next().is_none() {
+        keyboard_input.release(KeyCode::Enter);
+        *holding = false;
+    }
+    let in_menu = *state.get() == GameState::GameOver
+        || phase.is_some_and(|phase| *phase.get() == PlayPhase::Cleared);
+    if in_menu && !*holding && touches.any_just_pressed() {
+        keyboard_input.press(KeyCode::Enter);
+        *holding = true;
+    }
+}
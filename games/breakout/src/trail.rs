@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    ball::{apply_velocity, Ball, Velocity, BALL_DIAMETER, BALL_SPEED},
+    theme::Theme,
+};
+
+// 1 回の更新でボールがこれ以上移動したら、リセットなどによる瞬間移動とみなして軌跡を消す
+const TELEPORT_DISTANCE: f32 = BALL_DIAMETER * 3.0;
+// 残像をボールより奥に描画するための z 値
+const TRAIL_Z: f32 = 0.5;
+
+/// ボールの後ろに残像を描画するプラグイン
+///
+/// 残像は [`FixedUpdate`] ごとのボールの位置に置かれるため、ボールが速いほど間隔が広がり軌跡が長くなる。
+/// 見た目は [`TrailConfig`] で変更できる。
+pub struct TrailPlugin;
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TrailConfig>()
+            .init_resource::<TrailHistory>()
+            .add_systems(FixedUpdate, record_ball_positions.after(apply_velocity))
+            .add_systems(
+                Update,
+                (
+                    follow_theme,
+                    respawn_ghosts.run_if(resource_changed::<TrailConfig>),
+                    update_ghosts,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// 軌跡の設定
+///
+/// アプリに先に挿入しておけば、その値が使われる。実行中に変更すると残像が作り直される。
+/// 色はテーマを切り替えるとボールの色に合わせて変わる (アルファ値はそのまま)。
+#[derive(Resource, Debug, Clone)]
+pub struct TrailConfig {
+    /// 残像の数
+    pub length: usize,
+    /// 残像の色 (先頭の残像のアルファ値が最大になる)
+    pub color: Color,
+    /// この速さ以上で残像が最も濃くなる。遅いボールほど残像が薄くなり、速さの目安になる
+    pub full_opacity_speed: f32,
+}
+
+impl Default for TrailConfig {
+    fn default() -> Self {
+        Self {
+            length: 12,
+            color: Theme::default().ball.with_alpha(0.5),
+            full_opacity_speed: BALL_SPEED * 1.5,
+        }
+    }
+}
+
+/// ボールの最近の位置 (先頭が最新)
+#[derive(Resource, Default)]
+struct TrailHistory {
+    positions: VecDeque<Vec2>,
+}
+
+/// 残像のエンティティを示すコンポーネント (値は先頭からの順番)
+#[derive(Component)]
+struct TrailGhost(usize);
+
+/// ボールの位置を記録する
+fn record_ball_positions(
+    ball: Single<&Transform, With<Ball>>,
+    config: Res<TrailConfig>,
+    mut history: ResMut<TrailHistory>,
+) {
+    let position = ball.translation.truncate();
+    if history
+        .positions
+        .front()
+        .is_some_and(|last| last.distance(position) > TELEPORT_DISTANCE)
+    {
+        history.positions.clear();
+    }
+
+    history.positions.push_front(position);
+    // 先頭はボール自身の位置なので、残像の数より 1 つ多く保持する
+    history.positions.truncate(config.length + 1);
+}
+
+/// 設定に合わせて残像のエンティティを作り直す
+fn respawn_ghosts(
+    mut commands: Commands,
+    config: Res<TrailConfig>,
+    ghosts: Query<Entity, With<TrailGhost>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for entity in &ghosts {
+        commands.entity(entity).despawn();
+    }
+
+    let mesh = meshes.add(Circle::default());
+    for index in 0..config.length {
+        // 残像ごとに透明度が異なるため、マテリアルは個別に作る
+        commands.spawn((
+            Mesh2d(mesh.clone()),
+            MeshMaterial2d(materials.add(config.color)),
+            Transform::from_xyz(0.0, 0.0, TRAIL_Z),
+            Visibility::Hidden,
+            TrailGhost(index),
+        ));
+    }
+}
+
+/// テーマが切り替わったら、残像の色を新しいボールの色にする
+fn follow_theme(theme: Res<Theme>, mut config: ResMut<TrailConfig>) {
+    // 起動時は挿入された設定の色をそのまま使う
+    if theme.is_changed() && !theme.is_added() {
+        config.color = theme.ball.with_alpha(config.color.alpha());
+    }
+}
+
+/// 残像を記録した位置に移動し、古いものほど小さく薄くする
+fn update_ghosts(
+    config: Res<TrailConfig>,
+    history: Res<TrailHistory>,
+    ball_velocity: Single<&Velocity, With<Ball>>,
+    mut ghosts: Query<(
+        &TrailGhost,
+        &mut Transform,
+        &mut Visibility,
+        &MeshMaterial2d<ColorMaterial>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let speed_factor = (ball_velocity.length() / config.full_opacity_speed).clamp(0.0, 1.0);
+    let base_alpha = config.color.alpha();
+
+    for (ghost, mut transform, mut visibility, material) in &mut ghosts {
+        // 先頭 (index 0) はボール自身と重なるので 1 つずらす
+        let Some(position) = history.positions.get(ghost.0 + 1) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+
+        let fade = 1.0 - (ghost.0 + 1) as f32 / (config.length + 1) as f32;
+        transform.translation = position.extend(TRAIL_Z);
+        transform.scale = Vec2::splat(BALL_DIAMETER * (0.4 + 0.6 * fade)).extend(1.0);
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = config.color.with_alpha(base_alpha * fade * speed_factor);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use crate::{
+    is_single_player,
+    locale::Localized,
+    theme::{ThemeColor, Themed},
+    Score,
+};
+
+// スコアボードのフォントサイズ
+pub const SCOREBOARD_FONT_SIZE: f32 = 33.0;
+// スコアボードテキストの周囲のパディング
+pub const SCOREBOARD_TEXT_PADDING: Val = Val::Px(5.0);
+
+/// 1 人用のスコアボードを表示するプラグイン
+///
+/// 対戦モードのスコアボードは `versus.rs` が表示する。
+pub struct ScoreboardPlugin;
+
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_scoreboard.run_if(is_single_player))
+            .add_systems(Update, update_scoreboard.run_if(is_single_player));
+    }
+}
+
+#[derive(Component)]
+struct ScoreboardUi; // スコアボード用のUIコンポーネント
+
+/// スコアボードを追加する
+fn spawn_scoreboard(mut commands: Commands) {
+    commands
+        .spawn((
+            Text::new("Score: "), // スコアのラベル
+            TextFont {
+                font_size: SCOREBOARD_FONT_SIZE, // フォントサイズ
+                ..default()
+            },
+            Themed(ThemeColor::Text),      // フォントカラー
+            ScoreboardUi,                  // スコアボードUIコンポーネント
+            Localized::new("score_label"), // ラベルは現在の言語で表示する
+            Node {
+                position_type: PositionType::Absolute, // 絶対位置指定
+                top: SCOREBOARD_TEXT_PADDING,          // 上の余白
+                left: SCOREBOARD_TEXT_PADDING,         // 左の余白
+                ..default()
+            },
+        ))
+        .with_child((
+            TextSpan::default(), // 子要素としてスコア数値を表示
+            TextFont {
+                font_size: SCOREBOARD_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Score), // スコアの色
+        ));
+}
+
+/// スコアボードを更新する関数
+fn update_scoreboard(
+    score: Res<Score>,                                            // 現在のスコアを取得
+    score_root: Single<Entity, (With<ScoreboardUi>, With<Text>)>, // スコアボードのUIエンティティ
+    mut writer: TextUiWriter, // テキストを書き込むためのライター
+) {
+    // スコアボードにスコアを表示
+    *writer.text(*score_root, 1) = score.to_string();
+}
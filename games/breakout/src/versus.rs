@@ -0,0 +1,319 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{
+        check_for_collisions, Ball, Collider, Velocity, BALL_DIAMETER, BALL_SPEED,
+        INITIAL_BALL_DIRECTION,
+    },
+    bricks::{Brick, BrickDestroyed, BRICK_SIZE, GAP_BETWEEN_BRICKS, GAP_BETWEEN_BRICKS_AND_SIDES},
+    paddle::{
+        clamp_paddle_x, move_paddle, paddle_bundle, Paddle, PaddleKeys,
+        GAP_BETWEEN_PADDLE_AND_FLOOR, PADDLE_SPEED,
+    },
+    spin::Spin,
+    theme::{ThemeColor, Themed},
+    ui::{SCOREBOARD_FONT_SIZE, SCOREBOARD_TEXT_PADDING},
+    walls::{WallBundle, WallLocation, BOTTOM_WALL, LEFT_WALL, RIGHT_WALL, TOP_WALL},
+    GameMode,
+};
+
+// 各プレイヤーが守るブロックの行数
+const BRICK_ROWS_PER_SIDE: usize = 2;
+// パドルとそのプレイヤーが守るブロックの間のギャップ
+const GAP_BETWEEN_PADDLE_AND_OWN_BRICKS: f32 = 60.0;
+// ボールがゴールを割ったときに相手に入る得点
+const GOAL_SCORE: usize = 3;
+// AI パドルの速度 (プレイヤーのパドルに対する割合)
+const AI_SPEED_FACTOR: f32 = 0.75;
+// AI パドルが反応しない、ボールとの x 方向のずれ
+const AI_DEAD_ZONE: f32 = 10.0;
+
+/// 上側のパドル (AI または 2P) と対戦するモードを追加するプラグイン
+///
+/// [`GameMode::Versus`] のときだけシステムが動作する。
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VersusScore>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    move_ai_paddle
+                        .after(move_paddle)
+                        .before(check_for_collisions),
+                    score_destroyed_bricks.after(check_for_collisions),
+                    check_goals.after(check_for_collisions),
+                )
+                    .run_if(is_versus),
+            )
+            .add_systems(Startup, spawn_arena.run_if(is_versus))
+            .add_systems(Update, update_versus_scoreboard.run_if(is_versus));
+    }
+}
+
+/// 上側のパドルの操作方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopController {
+    Ai,    // ボールを追いかける AI
+    Human, // 2P (A/D キー)
+}
+
+/// 対戦モードでどちら側のプレイヤーに属するかを表すコンポーネント
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Bottom, // 下側 (1P)
+    Top,    // 上側 (AI または 2P)
+}
+
+impl Side {
+    /// 対戦相手の側を返す
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Bottom => Side::Top,
+            Side::Top => Side::Bottom,
+        }
+    }
+}
+
+/// AI が操作するパドルを示すコンポーネント
+#[derive(Component)]
+pub struct PaddleAi;
+
+/// 対戦モードのプレイヤーごとのスコア
+#[derive(Resource, Default, Debug)]
+pub struct VersusScore {
+    bottom: usize,
+    top: usize,
+}
+
+impl VersusScore {
+    /// 指定した側のプレイヤーに得点を加える
+    fn add(&mut self, side: Side, points: usize) {
+        match side {
+            Side::Bottom => self.bottom += points,
+            Side::Top => self.top += points,
+        }
+    }
+
+    fn get(&self, side: Side) -> usize {
+        match side {
+            Side::Bottom => self.bottom,
+            Side::Top => self.top,
+        }
+    }
+}
+
+/// 対戦モードのスコアボード用の UI コンポーネント
+#[derive(Component)]
+struct VersusScoreboardUi(Side);
+
+/// 対戦モードかどうかを判定する条件関数
+fn is_versus(mode: Res<GameMode>) -> bool {
+    matches!(*mode, GameMode::Versus(_))
+}
+
+/// 対戦用のアリーナ (左右の壁・上側のパドル・両陣営のブロック・スコアボード) を配置する
+///
+/// 1 人用の壁・ブロック・スコアボードの代わりに配置する。
+/// 上下の壁は置かず、そこがそれぞれのプレイヤーのゴール (失点ゾーン) になる。
+fn spawn_arena(mut commands: Commands, mode: Res<GameMode>) {
+    let GameMode::Versus(top_controller) = *mode else {
+        return;
+    };
+    let commands = &mut commands;
+
+    commands.spawn(WallBundle::new(WallLocation::Left));
+    commands.spawn(WallBundle::new(WallLocation::Right));
+
+    // 上側のパドル
+    let top_paddle_y = TOP_WALL - GAP_BETWEEN_PADDLE_AND_FLOOR;
+    let mut top_paddle = commands.spawn((
+        paddle_bundle(
+            top_paddle_y,
+            ThemeColor::TopPaddle,
+            Paddle {
+                keys: PaddleKeys::Fixed {
+                    left: KeyCode::KeyA,
+                    right: KeyCode::KeyD,
+                },
+            },
+        ),
+        Side::Top,
+    ));
+    if top_controller == TopController::Ai {
+        top_paddle.insert(PaddleAi);
+    }
+
+    // 各プレイヤーのパドルの手前に、そのプレイヤーが守るブロックを並べる
+    let bottom_paddle_y = BOTTOM_WALL + GAP_BETWEEN_PADDLE_AND_FLOOR;
+    spawn_brick_rows(
+        commands,
+        Side::Bottom,
+        bottom_paddle_y + GAP_BETWEEN_PADDLE_AND_OWN_BRICKS,
+        1.0,
+    );
+    spawn_brick_rows(
+        commands,
+        Side::Top,
+        top_paddle_y - GAP_BETWEEN_PADDLE_AND_OWN_BRICKS,
+        -1.0,
+    );
+
+    // スコアボード (上側は左上、下側は左下)
+    let top_label = match top_controller {
+        TopController::Ai => "CPU: ",
+        TopController::Human => "P2: ",
+    };
+    spawn_scoreboard(commands, Side::Top, top_label);
+    spawn_scoreboard(commands, Side::Bottom, "P1: ");
+}
+
+/// `first_row_y` から `direction` 方向 (上: 1.0, 下: -1.0) にブロックの行を並べる
+fn spawn_brick_rows(commands: &mut Commands, side: Side, first_row_y: f32, direction: f32) {
+    let total_width_of_bricks = (RIGHT_WALL - LEFT_WALL) - 2. * GAP_BETWEEN_BRICKS_AND_SIDES;
+    let n_columns = (total_width_of_bricks / (BRICK_SIZE.x + GAP_BETWEEN_BRICKS)).floor() as usize;
+    let row_width = n_columns as f32 * BRICK_SIZE.x + (n_columns - 1) as f32 * GAP_BETWEEN_BRICKS;
+    let offset_x = (LEFT_WALL + RIGHT_WALL) / 2.0 - row_width / 2.0 + BRICK_SIZE.x / 2.0;
+    let color = match side {
+        Side::Bottom => ThemeColor::Brick,
+        Side::Top => ThemeColor::TopBrick,
+    };
+
+    for row in 0..BRICK_ROWS_PER_SIDE {
+        for column in 0..n_columns {
+            let brick_position = Vec2::new(
+                offset_x + column as f32 * (BRICK_SIZE.x + GAP_BETWEEN_BRICKS),
+                first_row_y
+                    + direction
+                        * (BRICK_SIZE.y / 2.0 + row as f32 * (BRICK_SIZE.y + GAP_BETWEEN_BRICKS)),
+            );
+            commands.spawn((
+                Sprite::default(),
+                Themed(color),
+                Transform {
+                    translation: brick_position.extend(0.0),
+                    scale: BRICK_SIZE.extend(1.0),
+                    ..default()
+                },
+                Brick,
+                Collider,
+                side,
+            ));
+        }
+    }
+}
+
+/// 指定した側のスコアボードを配置する
+fn spawn_scoreboard(commands: &mut Commands, side: Side, label: &str) {
+    let mut node = Node {
+        position_type: PositionType::Absolute,
+        left: SCOREBOARD_TEXT_PADDING,
+        ..default()
+    };
+    match side {
+        Side::Top => node.top = SCOREBOARD_TEXT_PADDING,
+        // 左下にはステッピングのヒントがあるため、少し上に配置する
+        Side::Bottom => node.bottom = Val::Px(30.0),
+    }
+
+    commands
+        .spawn((
+            Text::new(label),
+            TextFont {
+                font_size: SCOREBOARD_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Text),
+            VersusScoreboardUi(side),
+            node,
+        ))
+        .with_child((
+            TextSpan::default(),
+            TextFont {
+                font_size: SCOREBOARD_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Score),
+        ));
+}
+
+/// AI パドルをボールの x 座標に向かって移動させる
+///
+/// ボールが自陣に向かっている間だけ追いかけ、それ以外は中央に戻る。
+fn move_ai_paddle(
+    mut paddle_query: Query<&mut Transform, (With<PaddleAi>, Without<Ball>)>,
+    ball_query: Single<(&Transform, &Velocity), With<Ball>>,
+    time: Res<Time>,
+) {
+    let (ball_transform, ball_velocity) = *ball_query;
+    let target_x = if ball_velocity.y > 0.0 {
+        ball_transform.translation.x
+    } else {
+        (LEFT_WALL + RIGHT_WALL) / 2.0
+    };
+
+    for mut paddle_transform in &mut paddle_query {
+        let offset = target_x - paddle_transform.translation.x;
+        if offset.abs() < AI_DEAD_ZONE {
+            continue;
+        }
+        let max_step = PADDLE_SPEED * AI_SPEED_FACTOR * time.delta_secs();
+        let new_paddle_position =
+            paddle_transform.translation.x + offset.clamp(-max_step, max_step);
+        paddle_transform.translation.x =
+            clamp_paddle_x(new_paddle_position, paddle_transform.scale.x);
+    }
+}
+
+/// 壊されたブロックの持ち主の相手に得点を与える
+fn score_destroyed_bricks(
+    mut events: EventReader<BrickDestroyed>,
+    mut versus_score: ResMut<VersusScore>,
+) {
+    for event in events.read() {
+        if let Some(side) = event.side {
+            versus_score.add(side.opponent(), 1);
+        }
+    }
+}
+
+/// ボールが上下どちらかのゴールを割ったら相手に得点を与え、ボールを中央から再開する
+///
+/// ボールは失点した側に向かって打ち出される。
+fn check_goals(
+    mut versus_score: ResMut<VersusScore>,
+    ball_query: Single<(&mut Transform, &mut Velocity, &mut Spin), With<Ball>>,
+) {
+    let (mut ball_transform, mut ball_velocity, mut ball_spin) = ball_query.into_inner();
+    let ball_y = ball_transform.translation.y;
+
+    let conceded = if ball_y < BOTTOM_WALL - BALL_DIAMETER {
+        Side::Bottom
+    } else if ball_y > TOP_WALL + BALL_DIAMETER {
+        Side::Top
+    } else {
+        return;
+    };
+    versus_score.add(conceded.opponent(), GOAL_SCORE);
+
+    let serve_direction = match conceded {
+        Side::Bottom => INITIAL_BALL_DIRECTION,
+        Side::Top => INITIAL_BALL_DIRECTION * Vec2::new(1.0, -1.0),
+    };
+    ball_transform.translation.x = (LEFT_WALL + RIGHT_WALL) / 2.0;
+    ball_transform.translation.y = (BOTTOM_WALL + TOP_WALL) / 2.0;
+    ball_velocity.0 = serve_direction.normalize() * BALL_SPEED;
+    *ball_spin = Spin::default();
+}
+
+/// 対戦モードのスコアボードを更新する
+fn update_versus_scoreboard(
+    versus_score: Res<VersusScore>,
+    scoreboards: Query<(Entity, &VersusScoreboardUi), With<Text>>,
+    mut writer: TextUiWriter,
+) {
+    for (entity, scoreboard) in &scoreboards {
+        *writer.text(entity, 1) = versus_score.get(scoreboard.0).to_string();
+    }
+}
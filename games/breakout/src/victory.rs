@@ -0,0 +1,391 @@
+use std::time::Duration;
+
+use bevy::{audio::Pitch, prelude::*};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    boss,
+    bricks::{advance_level, Brick},
+    game_over::RunOutcome,
+    is_single_player,
+    locale::{Localized, LocalizedFont, Strings},
+    theme::{ThemeColor, Themed},
+    walls::{BOTTOM_WALL, LEFT_WALL, RIGHT_WALL},
+    GameState, Level, Score,
+};
+
+// 最後のレベル (これをクリアすると結果画面に進む)
+const FINAL_LEVEL: u32 = 5;
+// 得点の集計が終わってから次のレベルへ自動で進むまでの時間 (秒)
+const CELEBRATION_HOLD_SECS: f32 = 2.5;
+// 得点を数え上げる時間 (秒)
+const TALLY_SECS: f32 = 1.5;
+
+// 紙吹雪の数
+const CONFETTI_COUNT: usize = 150;
+// 紙吹雪 1 枚の大きさ
+const CONFETTI_SIZE: Vec2 = Vec2::new(8.0, 14.0);
+// 紙吹雪を打ち上げる速さの範囲と、真上からの広がり (ラジアン)
+const CONFETTI_SPEED: std::ops::Range<f32> = 450.0..900.0;
+const CONFETTI_SPREAD: f32 = 0.5;
+// 紙吹雪に掛かる重力加速度と、空気抵抗で 1 秒あたりに残る速度の割合
+const CONFETTI_GRAVITY: f32 = -700.0;
+const CONFETTI_DRAG: f32 = 0.4;
+// 紙吹雪の回転の速さの上限 (ラジアン/秒)
+const CONFETTI_MAX_SPIN: f32 = 12.0;
+// 紙吹雪の色 (テーマを切り替えても合うよう、テーマの色から選ぶ)
+const CONFETTI_COLORS: [ThemeColor; 6] = [
+    ThemeColor::Brick,
+    ThemeColor::TopBrick,
+    ThemeColor::Ball,
+    ThemeColor::Coin,
+    ThemeColor::Gem,
+    ThemeColor::NewBest,
+];
+
+// ファンファーレの音 (鳴らし始める時刻 (秒)・周波数・長さ)
+const FANFARE: [(f32, f32, Duration); 4] = [
+    (0.0, 523.25, Duration::from_millis(120)),  // ド
+    (0.12, 659.25, Duration::from_millis(120)), // ミ
+    (0.24, 783.99, Duration::from_millis(120)), // ソ
+    (0.36, 1046.5, Duration::from_millis(500)), // 高いド
+];
+
+// 見出しと本文のフォントサイズ
+const TITLE_FONT_SIZE: f32 = 54.0;
+const BODY_FONT_SIZE: f32 = 24.0;
+
+/// 盤面のブロック (とボス) がすべてなくなったときの勝利演出を追加するプラグイン
+///
+/// 最後のブロックが消えると [`LevelCleared`] を送り、[`PlayPhase::Cleared`] に遷移する。
+/// 演出中は仮想時間を止め、紙吹雪・ファンファーレ・得点の集計を表示したあと、
+/// 次のレベルに進むか、最後のレベルなら結果画面 ([`GameState::GameOver`]) に遷移する。
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<PlayPhase>()
+            .enable_state_scoped_entities::<PlayPhase>()
+            .add_event::<LevelCleared>()
+            .insert_resource(ConfettiRng(StdRng::from_entropy()))
+            .init_resource::<LevelStartScore>()
+            .add_systems(Startup, setup_fanfare)
+            .add_systems(OnEnter(PlayPhase::Running), remember_level_start_score)
+            .add_systems(
+                Update,
+                detect_level_cleared.run_if(is_single_player.and(in_state(PlayPhase::Running))),
+            )
+            .add_systems(
+                OnEnter(PlayPhase::Cleared),
+                (pause_time, spawn_confetti, spawn_tally_ui),
+            )
+            .add_systems(
+                Update,
+                (
+                    play_fanfare,
+                    move_confetti,
+                    update_tally_ui,
+                    finish_celebration,
+                )
+                    .run_if(in_state(PlayPhase::Cleared)),
+            )
+            .add_systems(OnExit(PlayPhase::Cleared), unpause_time)
+            // 演出が終わって次のレベルへ進むときだけ盤面を作り直す (結果画面へ進むときは作らない)
+            .add_systems(
+                OnTransition {
+                    exited: PlayPhase::Cleared,
+                    entered: PlayPhase::Running,
+                },
+                advance_level,
+            );
+    }
+}
+
+/// プレイ中 ([`GameState::Playing`]) の段階
+#[derive(SubStates, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+// 元の状態 `GameState` がクレート内でしか見えないため、こちらも `pub(crate)` にする
+pub(crate) enum PlayPhase {
+    #[default]
+    Running, // ボールが動いている
+    Cleared, // レベルをクリアし、勝利演出を再生している
+}
+
+/// レベルの盤面が空になったときのイベント
+#[derive(Event, Debug, Clone, Copy)]
+pub struct LevelCleared {
+    pub level: u32,           // クリアしたレベル
+    pub level_score: usize,   // このレベルで獲得した得点
+    pub is_final_level: bool, // 最後のレベルだったかどうか
+}
+
+/// レベル開始時の得点 (レベルごとの得点を求めるのに使う)
+#[derive(Resource, Default, Deref)]
+pub struct LevelStartScore(usize);
+
+/// 紙吹雪の散らばり方を決める乱数生成器
+#[derive(Resource, Deref, DerefMut)]
+struct ConfettiRng(StdRng);
+
+/// ファンファーレの音のハンドル (`FANFARE` と同じ順番)
+#[derive(Resource)]
+struct FanfareSounds(Vec<Handle<Pitch>>);
+
+/// 再生中の勝利演出
+#[derive(Resource)]
+struct Celebration {
+    cleared: LevelCleared,
+    start_score: usize,  // レベル開始時の得点
+    elapsed: f32,        // 演出が始まってからの実時間 (秒)
+    notes_played: usize, // 鳴らし終えたファンファーレの音の数
+}
+
+/// 紙吹雪 1 枚の動き
+#[derive(Component)]
+struct Confetti {
+    velocity: Vec2,
+    spin: f32, // 回転の速さ (ラジアン/秒)
+}
+
+/// 得点の集計の表示を示すコンポーネント
+#[derive(Component)]
+struct TallyUi;
+
+/// 続けるためのキーの案内を示すコンポーネント
+#[derive(Component)]
+struct ContinueHint;
+
+/// ファンファーレの音を用意する
+fn setup_fanfare(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    let sounds = FANFARE
+        .iter()
+        .map(|&(_, frequency, duration)| pitches.add(Pitch::new(frequency, duration)))
+        .collect();
+    commands.insert_resource(FanfareSounds(sounds));
+}
+
+/// レベル開始時の得点を覚えておく
+pub fn remember_level_start_score(mut start_score: ResMut<LevelStartScore>, score: Res<Score>) {
+    start_score.0 = **score;
+}
+
+/// 最後のブロック (とボス) がなくなったら [`LevelCleared`] を送り、勝利演出に移る
+#[allow(clippy::type_complexity)]
+pub fn detect_level_cleared(
+    mut commands: Commands,
+    remaining: Query<(), Or<(With<Brick>, With<boss::Boss>)>>,
+    level: Res<Level>,
+    score: Res<Score>,
+    start_score: Res<LevelStartScore>,
+    mut cleared_events: EventWriter<LevelCleared>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+) {
+    if !remaining.is_empty() {
+        return;
+    }
+
+    let cleared = LevelCleared {
+        level: **level,
+        level_score: score.saturating_sub(**start_score),
+        is_final_level: **level >= FINAL_LEVEL,
+    };
+    info!("level {} cleared (+{})", cleared.level, cleared.level_score);
+    cleared_events.send(cleared);
+    commands.insert_resource(Celebration {
+        cleared,
+        start_score: **start_score,
+        elapsed: 0.0,
+        notes_played: 0,
+    });
+    next_phase.set(PlayPhase::Cleared);
+}
+
+/// 演出中はボールや落下物の動きを止める
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+    commands.remove_resource::<Celebration>();
+}
+
+/// アリーナの下の両端から紙吹雪を打ち上げる
+fn spawn_confetti(mut commands: Commands, mut rng: ResMut<ConfettiRng>) {
+    for index in 0..CONFETTI_COUNT {
+        // 左右交互に打ち上げ、それぞれアリーナの中央へ向けて傾ける
+        let (x, lean) = if index % 2 == 0 {
+            (LEFT_WALL + 40.0, -0.35)
+        } else {
+            (RIGHT_WALL - 40.0, 0.35)
+        };
+        let angle = lean + rng.gen_range(-CONFETTI_SPREAD..CONFETTI_SPREAD);
+        let speed = rng.gen_range(CONFETTI_SPEED);
+        let color = CONFETTI_COLORS[rng.gen_range(0..CONFETTI_COLORS.len())];
+        commands.spawn((
+            Sprite {
+                custom_size: Some(CONFETTI_SIZE),
+                ..default()
+            },
+            Themed(color),
+            Transform::from_xyz(x, BOTTOM_WALL, 5.0).with_rotation(Quat::from_rotation_z(
+                rng.gen_range(0.0..std::f32::consts::TAU),
+            )),
+            Confetti {
+                velocity: Vec2::from_angle(angle).rotate(Vec2::Y) * speed,
+                spin: rng.gen_range(-CONFETTI_MAX_SPIN..CONFETTI_MAX_SPIN),
+            },
+            StateScoped(PlayPhase::Cleared),
+        ));
+    }
+}
+
+/// 紙吹雪を動かし、画面の下に落ちたものを消す
+///
+/// 演出中は仮想時間が止まっているため、実時間で動かす。
+fn move_confetti(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut confetti: Query<(Entity, &mut Confetti, &mut Transform)>,
+) {
+    let dt = time.delta_secs();
+    let drag = CONFETTI_DRAG.powf(dt);
+    for (entity, mut piece, mut transform) in &mut confetti {
+        piece.velocity.y += CONFETTI_GRAVITY * dt;
+        piece.velocity *= drag;
+        transform.translation += (piece.velocity * dt).extend(0.0);
+        transform.rotate_z(piece.spin * dt);
+        if transform.translation.y < BOTTOM_WALL - CONFETTI_SIZE.y {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// ファンファーレの音を時刻に合わせて 1 つずつ鳴らす
+fn play_fanfare(
+    mut commands: Commands,
+    mut celebration: ResMut<Celebration>,
+    sounds: Res<FanfareSounds>,
+    time: Res<Time<Real>>,
+) {
+    celebration.elapsed += time.delta_secs();
+    while let Some(&(start, _, _)) = FANFARE.get(celebration.notes_played) {
+        if celebration.elapsed < start {
+            break;
+        }
+        let sound = sounds.0[celebration.notes_played].clone();
+        commands.spawn((AudioPlayer(sound), PlaybackSettings::DESPAWN));
+        celebration.notes_played += 1;
+    }
+}
+
+/// 見出し・得点の集計・続けるためのキーの案内を画面中央に配置する
+fn spawn_tally_ui(mut commands: Commands, celebration: Res<Celebration>) {
+    let body_font = TextFont {
+        font_size: BODY_FONT_SIZE,
+        ..default()
+    };
+    let title = if celebration.cleared.is_final_level {
+        Localized::new("all_clear")
+    } else {
+        Localized::new("level_cleared").with_arg("level", celebration.cleared.level)
+    };
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            StateScoped(PlayPhase::Cleared),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(12.0),
+                        padding: UiRect::axes(Val::Px(40.0), Val::Px(24.0)),
+                        ..default()
+                    },
+                    Themed(ThemeColor::Overlay),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::default(),
+                        TextFont {
+                            font_size: TITLE_FONT_SIZE,
+                            ..default()
+                        },
+                        Themed(ThemeColor::Title),
+                        title,
+                    ));
+                    panel.spawn((
+                        Text::default(),
+                        body_font.clone(),
+                        Themed(ThemeColor::Score),
+                        LocalizedFont,
+                        TallyUi,
+                    ));
+                    panel.spawn((
+                        Text::default(),
+                        body_font,
+                        Themed(ThemeColor::Hint),
+                        Visibility::Hidden,
+                        Localized::new("press_enter_to_continue"),
+                        ContinueHint,
+                    ));
+                });
+        });
+}
+
+/// このレベルの得点を 0 から数え上げ、合計の得点と一緒に表示する
+fn update_tally_ui(
+    celebration: Res<Celebration>,
+    mut tally_text: Single<&mut Text, With<TallyUi>>,
+    mut hint_visibility: Single<&mut Visibility, With<ContinueHint>>,
+    strings: Strings,
+) {
+    // だんだん遅くなるように数え上げる (ease-out)
+    let progress = (celebration.elapsed / TALLY_SECS).clamp(0.0, 1.0);
+    let eased = 1.0 - (1.0 - progress).powi(3);
+    let counted = (celebration.cleared.level_score as f32 * eased).round() as usize;
+    tally_text.0 = format!(
+        "{}\n{}",
+        strings.format("tally_level_score", &[("score", &counted)]),
+        strings.format(
+            "tally_total_score",
+            &[("score", &(celebration.start_score + counted))]
+        ),
+    );
+    if progress >= 1.0 {
+        hint_visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+/// 集計が終わってから一定時間たつか Enter キーが押されたら、次のレベルか結果画面へ進む
+fn finish_celebration(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    celebration: Res<Celebration>,
+    mut outcome: ResMut<RunOutcome>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // 集計が終わるまでは Enter キーで飛ばさない
+    let tallied = celebration.elapsed >= TALLY_SECS;
+    let timed_out = celebration.elapsed >= TALLY_SECS + CELEBRATION_HOLD_SECS;
+    if !(timed_out || (tallied && keyboard_input.just_pressed(KeyCode::Enter))) {
+        return;
+    }
+
+    if celebration.cleared.is_final_level {
+        *outcome = RunOutcome::AllCleared;
+        next_state.set(GameState::GameOver);
+    } else {
+        next_phase.set(PlayPhase::Running);
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::Collider,
+    is_single_player,
+    theme::{ThemeColor, Themed},
+};
+
+// 壁の厚さ
+pub const WALL_THICKNESS: f32 = 10.0;
+// 左の壁のx座標
+pub const LEFT_WALL: f32 = -450.;
+// 右の壁のx座標
+pub const RIGHT_WALL: f32 = 450.;
+// 下の壁のy座標
+pub const BOTTOM_WALL: f32 = -300.;
+// 上の壁のy座標
+pub const TOP_WALL: f32 = 300.;
+
+/// 1 人用のアリーナの壁を配置するプラグイン
+///
+/// 対戦モードの壁は `versus.rs` の `spawn_arena` が配置する。
+pub struct WallsPlugin;
+
+impl Plugin for WallsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_walls.run_if(is_single_player));
+    }
+}
+
+// ゲーム内の「壁」を構成するコンポーネントのバンドル
+// 複数のコンポーネントを一つにまとめることで、壁のオブジェクトを効率よく作成
+#[derive(Bundle)]
+pub struct WallBundle {
+    // 壁のスプライト（見た目）
+    sprite: Sprite,
+    // 壁の色 (テーマから設定)
+    themed: Themed,
+    // 壁の位置と回転を定義する変換（Transform）
+    transform: Transform,
+    // 壁の衝突判定を持つコンポーネント
+    collider: Collider,
+}
+
+/// アリーナのどの側に壁が位置しているかを表す列挙型
+pub enum WallLocation {
+    Left,  // 左側
+    Right, // 右側
+    Top,   // 上側 (下側には壁を置かず、ボールが落ちたら残機が減る)
+}
+
+impl WallLocation {
+    /// 壁の*中心*の位置を返す。`transform.translation()`で使用される
+    fn position(&self) -> Vec2 {
+        match self {
+            WallLocation::Left => Vec2::new(LEFT_WALL, 0.), // 左壁の中心位置
+            WallLocation::Right => Vec2::new(RIGHT_WALL, 0.), // 右壁の中心位置
+            WallLocation::Top => Vec2::new(0., TOP_WALL),   // 上壁の中心位置
+        }
+    }
+
+    /// 壁の(x, y)サイズを返す。`transform.scale()`で使用される
+    fn size(&self) -> Vec2 {
+        let arena_height = TOP_WALL - BOTTOM_WALL; // アリーナの高さ
+        let arena_width = RIGHT_WALL - LEFT_WALL; // アリーナの幅
+
+        // 定数が正しいか確認するためのアサーション
+        assert!(arena_height > 0.0);
+        assert!(arena_width > 0.0);
+
+        match self {
+            WallLocation::Left | WallLocation::Right => {
+                // 左右の壁のサイズ：幅はWALL_THICKNESS、高さはアリーナの高さ＋壁の厚さ
+                Vec2::new(WALL_THICKNESS, arena_height + WALL_THICKNESS)
+            }
+            WallLocation::Top => {
+                // 上の壁のサイズ：幅はアリーナの幅＋壁の厚さ、高さはWALL_THICKNESS
+                Vec2::new(arena_width + WALL_THICKNESS, WALL_THICKNESS)
+            }
+        }
+    }
+}
+
+impl WallBundle {
+    // この「ビルダーメソッド」は壁エンティティ間でロジックを再利用できるようにし、
+    // ロジックを変更したときにコードの可読性を向上させ、バグを減らします
+    pub fn new(location: WallLocation) -> WallBundle {
+        WallBundle {
+            sprite: Sprite::default(),        // 壁のスプライトを作成
+            themed: Themed(ThemeColor::Wall), // 壁の色はテーマから設定
+            transform: Transform {
+                // Vec2からVec3に変換し、z座標を0.0に設定してスプライトの順序を決定
+                // これによりスプライトが描画される順序が決まります
+                translation: location.position().extend(0.0),
+                // 2Dオブジェクトのzスケールは常に1.0に設定しないと
+                // 順序が予期しない方法で影響を受ける
+                // 詳細は https://github.com/bevyengine/bevy/issues/4149 を参照
+                scale: location.size().extend(1.0),
+                ..default() // その他のデフォルト値を使用
+            },
+            collider: Collider, // 衝突判定用のコンポーネントを追加
+        }
+    }
+}
+
+/// 壁を追加する (上の壁と左右の壁)
+fn spawn_walls(mut commands: Commands) {
+    commands.spawn(WallBundle::new(WallLocation::Left)); // 左の壁
+    commands.spawn(WallBundle::new(WallLocation::Right)); // 右の壁
+    commands.spawn(WallBundle::new(WallLocation::Top)); // 上の壁
+}
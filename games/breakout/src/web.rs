@@ -0,0 +1,84 @@
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    locale::Localized,
+    theme::{ThemeColor, Themed},
+};
+
+// 開始待ちの表示のフォントサイズ
+const START_FONT_SIZE: f32 = 36.0;
+
+/// ブラウザ (WebAssembly 版) で、最初の操作があるまでゲームを止めておくプラグイン
+///
+/// ブラウザはユーザーが操作するまで音声 (`AudioContext`) を再生させないため、そのままでは最初の衝突音などが鳴らない。
+/// 起動したら仮想時間を止めて「タップしてスタート」を表示し、最初のタップ・クリック・キー入力で再開する。
+/// 止まった音声を再開するのは `index.html` のスクリプトで、同じ操作のイベントで `AudioContext::resume` を呼ぶ。
+pub struct WebPlugin;
+
+impl Plugin for WebPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_start_screen).add_systems(
+            PreUpdate,
+            start_on_first_input
+                .after(InputSystem)
+                .run_if(any_with_component::<StartScreen>),
+        );
+    }
+}
+
+/// 開始待ちの画面
+#[derive(Component)]
+struct StartScreen;
+
+/// 仮想時間を止め、画面全体を覆う開始待ちの画面を表示する
+fn spawn_start_screen(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            Themed(ThemeColor::Overlay),
+            GlobalZIndex(2), // ゲームオーバー画面や保存の確認より手前に表示する
+            StartScreen,
+        ))
+        .with_child((
+            Text::new("Tap or press any key to start"),
+            TextFont {
+                font_size: START_FONT_SIZE,
+                ..default()
+            },
+            Themed(ThemeColor::Title),
+            Localized::new("tap_to_start"),
+        ));
+}
+
+/// 最初のタップ・クリック・キー入力で開始待ちの画面を消し、仮想時間を動かす
+///
+/// 開始に使った入力は捨てて、ゲームの操作 (`Enter` キーなど) として扱われないようにする。
+fn start_on_first_input(
+    mut commands: Commands,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_input: ResMut<ButtonInput<MouseButton>>,
+    mut touches: ResMut<Touches>,
+    mut time: ResMut<Time<Virtual>>,
+    screen: Single<Entity, With<StartScreen>>,
+) {
+    if keyboard_input.get_just_pressed().next().is_none()
+        && mouse_input.get_just_pressed().next().is_none()
+        && !touches.any_just_pressed()
+    {
+        return;
+    }
+    keyboard_input.reset_all();
+    mouse_input.reset_all();
+    touches.reset_all();
+    commands.entity(*screen).despawn_recursive();
+    time.unpause();
+    info!("started after the first user interaction");
+}
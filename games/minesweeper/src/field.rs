@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+/// 盤面の大きさと地雷の数の組み合わせ
+pub struct Preset {
+    pub name: &'static str,
+    pub width: i32,
+    pub height: i32,
+    pub mines: usize,
+    pub cell_size: f32, // 画面に収まるように、盤面が大きいほど小さくする
+}
+
+// 初級・中級・上級 (数字キーの 1・2・3 で選ぶ)
+pub const PRESETS: [Preset; 3] = [
+    Preset {
+        name: "Beginner",
+        width: 9,
+        height: 9,
+        mines: 10,
+        cell_size: 48.0,
+    },
+    Preset {
+        name: "Intermediate",
+        width: 16,
+        height: 16,
+        mines: 40,
+        cell_size: 36.0,
+    },
+    Preset {
+        name: "Expert",
+        width: 30,
+        height: 16,
+        mines: 99,
+        cell_size: 32.0,
+    },
+];
+
+/// マスの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellState {
+    Hidden,   // 開いていない
+    Flagged,  // 旗を立てた (開けない)
+    Revealed, // 開いた
+}
+
+/// ゲームの結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won,         // 地雷以外のマスをすべて開いた
+    Lost(IVec2), // 地雷のマスを開いた (そのマス)
+}
+
+/// 地雷の配置とマスの状態
+///
+/// 最初に開いたマスとその周りには地雷を置かないように、地雷は最初にマスを開いたときに置く。
+pub struct Field {
+    pub width: i32,
+    pub height: i32,
+    mine_count: usize,
+    mines: Vec<bool>,
+    states: Vec<CellState>,
+    placed: bool, // 地雷を置いたか (最初のマスを開いたか)
+    outcome: Option<Outcome>,
+}
+
+impl Field {
+    pub fn new(preset: &Preset) -> Field {
+        let cells = (preset.width * preset.height) as usize;
+        Field {
+            width: preset.width,
+            height: preset.height,
+            mine_count: preset.mines.min(cells - 1),
+            mines: vec![false; cells],
+            states: vec![CellState::Hidden; cells],
+            placed: false,
+            outcome: None,
+        }
+    }
+
+    pub fn in_bounds(&self, cell: IVec2) -> bool {
+        cell.cmpge(IVec2::ZERO).all() && cell.cmplt(IVec2::new(self.width, self.height)).all()
+    }
+
+    fn index(&self, cell: IVec2) -> usize {
+        (cell.y * self.width + cell.x) as usize
+    }
+
+    /// 盤面のすべてのマス
+    pub fn cells(&self) -> impl Iterator<Item = IVec2> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| IVec2::new(x, y)))
+    }
+
+    /// 周りの 8 マス (盤面の外は含まない)
+    pub fn neighbors(&self, cell: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| IVec2::new(dx, dy)))
+            .filter(|offset| *offset != IVec2::ZERO)
+            .map(move |offset| cell + offset)
+            .filter(|neighbor| self.in_bounds(*neighbor))
+    }
+
+    pub fn state(&self, cell: IVec2) -> CellState {
+        self.states[self.index(cell)]
+    }
+
+    pub fn is_mine(&self, cell: IVec2) -> bool {
+        self.mines[self.index(cell)]
+    }
+
+    /// 周りの 8 マスにある地雷の数
+    pub fn adjacent_mines(&self, cell: IVec2) -> usize {
+        self.neighbors(cell)
+            .filter(|&neighbor| self.is_mine(neighbor))
+            .count()
+    }
+
+    /// 周りの 8 マスに立てた旗の数
+    fn adjacent_flags(&self, cell: IVec2) -> usize {
+        self.neighbors(cell)
+            .filter(|&neighbor| self.state(neighbor) == CellState::Flagged)
+            .count()
+    }
+
+    /// 地雷を置いた (最初のマスを開いた) か
+    pub fn started(&self) -> bool {
+        self.placed
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    /// 残りの地雷の数 (地雷の数から旗の数を引いたもの。旗を立てすぎると負になる)
+    pub fn mines_left(&self) -> i32 {
+        let flags = self
+            .states
+            .iter()
+            .filter(|&&state| state == CellState::Flagged)
+            .count();
+        self.mine_count as i32 - flags as i32
+    }
+
+    /// `first` とその周りを除いたマスに地雷を置く
+    ///
+    /// 盤面が狭くて置ききれないときは、`first` だけを除く。
+    fn place_mines(&mut self, first: IVec2, rng: &mut impl Rng) {
+        let mut candidates: Vec<IVec2> = self
+            .cells()
+            .filter(|cell| (*cell - first).abs().max_element() > 1)
+            .collect();
+        if candidates.len() < self.mine_count {
+            candidates = self.cells().filter(|cell| *cell != first).collect();
+        }
+        for &cell in candidates.choose_multiple(rng, self.mine_count) {
+            let index = self.index(cell);
+            self.mines[index] = true;
+        }
+        self.placed = true;
+    }
+
+    /// マスを開く
+    ///
+    /// 周りに地雷がないマスを開いたときは、周りのマスも続けて開く (幅優先の塗りつぶし)。
+    /// 塗りつぶしは数字のマスで止まり、旗を立てたマスは開かない。
+    pub fn reveal(&mut self, cell: IVec2, rng: &mut impl Rng) {
+        if self.outcome.is_some() || self.state(cell) != CellState::Hidden {
+            return;
+        }
+        if !self.placed {
+            self.place_mines(cell, rng);
+        }
+        if self.is_mine(cell) {
+            let index = self.index(cell);
+            self.states[index] = CellState::Revealed;
+            self.outcome = Some(Outcome::Lost(cell));
+            return;
+        }
+
+        let mut queue = VecDeque::from([cell]);
+        while let Some(current) = queue.pop_front() {
+            let index = self.index(current);
+            if self.states[index] != CellState::Hidden {
+                continue;
+            }
+            self.states[index] = CellState::Revealed;
+            if self.adjacent_mines(current) == 0 {
+                queue.extend(
+                    self.neighbors(current)
+                        .filter(|&neighbor| self.state(neighbor) == CellState::Hidden),
+                );
+            }
+        }
+
+        let revealed = self
+            .states
+            .iter()
+            .filter(|&&state| state == CellState::Revealed)
+            .count();
+        if revealed == self.mines.len() - self.mine_count {
+            self.outcome = Some(Outcome::Won);
+        }
+    }
+
+    /// 開いていないマスに旗を立てる (立てていれば外す)
+    pub fn toggle_flag(&mut self, cell: IVec2) {
+        if self.outcome.is_some() {
+            return;
+        }
+        let index = self.index(cell);
+        self.states[index] = match self.states[index] {
+            CellState::Hidden => CellState::Flagged,
+            CellState::Flagged => CellState::Hidden,
+            CellState::Revealed => CellState::Revealed,
+        };
+    }
+
+    /// 数字のマスの周りに、数字と同じ数の旗を立てていれば、旗のない周りのマスをまとめて開く (コード)
+    ///
+    /// 旗の位置が間違っていれば、地雷を開いて負けになる。
+    pub fn chord(&mut self, cell: IVec2, rng: &mut impl Rng) {
+        if self.state(cell) != CellState::Revealed {
+            return;
+        }
+        let count = self.adjacent_mines(cell);
+        if count == 0 || self.adjacent_flags(cell) != count {
+            return;
+        }
+        let hidden: Vec<IVec2> = self
+            .neighbors(cell)
+            .filter(|&neighbor| self.state(neighbor) == CellState::Hidden)
+            .collect();
+        for neighbor in hidden {
+            self.reveal(neighbor, rng);
+        }
+    }
+}
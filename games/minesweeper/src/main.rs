@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+
+use bevy::{prelude::*, time::Stopwatch};
+use serde::{Deserialize, Serialize};
+use settings::Profile;
+
+mod field;
+
+use field::{CellState, Field, Outcome, PRESETS};
+
+// ベストタイムを保存するファイル名 (プロファイルのディレクトリ内)
+const RECORDS_FILE: &str = "minesweeper_records.ron";
+// 難易度を選ぶキー (`PRESETS` の順)
+const PRESET_KEYS: [KeyCode; 3] = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3];
+// マスどうしの隙間
+const CELL_GAP: f32 = 2.0;
+
+// 背景とマスの色
+const BACKGROUND_COLOR: Color = Color::srgb(0.12, 0.13, 0.15);
+const HIDDEN_COLOR: Color = Color::srgb(0.45, 0.5, 0.58);
+const REVEALED_COLOR: Color = Color::srgb(0.85, 0.86, 0.88);
+const EXPLODED_COLOR: Color = Color::srgb(0.9, 0.25, 0.2);
+// 旗・地雷・間違えた旗の印の色
+const FLAG_COLOR: Color = Color::srgb(0.95, 0.3, 0.2);
+const MINE_COLOR: Color = Color::srgb(0.1, 0.1, 0.1);
+const WRONG_FLAG_COLOR: Color = Color::srgb(0.6, 0.1, 0.6);
+// 周りの地雷の数 (1 から 8) の色
+const NUMBER_COLORS: [Color; 8] = [
+    Color::srgb(0.1, 0.3, 0.9),
+    Color::srgb(0.1, 0.55, 0.15),
+    Color::srgb(0.85, 0.15, 0.1),
+    Color::srgb(0.15, 0.1, 0.55),
+    Color::srgb(0.55, 0.1, 0.1),
+    Color::srgb(0.1, 0.5, 0.55),
+    Color::srgb(0.1, 0.1, 0.1),
+    Color::srgb(0.45, 0.45, 0.45),
+];
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ClearColor(BACKGROUND_COLOR))
+        .insert_resource(Game::new(0))
+        .init_resource::<PlayTime>()
+        .init_resource::<Profile>()
+        .add_systems(PreStartup, load_records)
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                new_game,
+                handle_clicks,
+                tick_play_time,
+                record_best_time,
+                update_cells.run_if(resource_changed::<Game>),
+                update_hud,
+            )
+                .chain(),
+        )
+        .add_systems(Last, save_records)
+        .run();
+}
+
+/// 対局中の盤面 (マスの表示はこのリソースが変わったときに作り直す)
+#[derive(Resource)]
+struct Game {
+    preset: usize, // `PRESETS` の番号
+    field: Field,
+}
+
+impl Game {
+    fn new(preset: usize) -> Game {
+        Game {
+            preset,
+            field: Field::new(&PRESETS[preset]),
+        }
+    }
+}
+
+/// 最初のマスを開いてからの時間 (勝ち負けが決まったら止める)
+#[derive(Resource, Default)]
+struct PlayTime(Stopwatch);
+
+/// 難易度ごとのベストタイム (秒)
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+struct Records {
+    best_times: BTreeMap<String, f32>,
+}
+
+/// 盤面のマスの表示
+#[derive(Component)]
+struct CellView(IVec2);
+
+/// マスに重ねる数字や印のテキスト
+#[derive(Component)]
+struct CellLabel;
+
+/// 残りの地雷の数・時間・ベストタイムを表示するテキスト
+#[derive(Component)]
+struct HudText;
+
+/// 現在のプロファイルからベストタイムを読み込む
+fn load_records(mut commands: Commands, profile: Res<Profile>) {
+    commands.insert_resource(profile.load::<Records>(RECORDS_FILE));
+}
+
+/// ベストタイムが変わったフレームの最後にプロファイルへ保存する
+fn save_records(records: Res<Records>, profile: Res<Profile>) {
+    if records.is_changed() && !records.is_added() {
+        profile.save(RECORDS_FILE, &*records);
+    }
+}
+
+/// マスの中心のワールド座標 (盤面の中心が原点)
+fn cell_to_world(game: &Game, cell: IVec2) -> Vec2 {
+    let size = IVec2::new(game.field.width, game.field.height).as_vec2();
+    (cell.as_vec2() - (size - 1.0) / 2.0) * PRESETS[game.preset].cell_size
+}
+
+/// ワールド座標にあるマス (盤面の外なら `None`)
+fn world_to_cell(game: &Game, position: Vec2) -> Option<IVec2> {
+    let size = IVec2::new(game.field.width, game.field.height).as_vec2();
+    let cell = (position / PRESETS[game.preset].cell_size + size / 2.0)
+        .floor()
+        .as_ivec2();
+    game.field.in_bounds(cell).then_some(cell)
+}
+
+/// カメラ・HUD・最初の盤面のマスを配置する
+fn setup(mut commands: Commands, game: Res<Game>) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        HudText,
+    ));
+    spawn_cells(&mut commands, &game);
+}
+
+/// 盤面のマスと、マスに重ねるテキストを配置する
+fn spawn_cells(commands: &mut Commands, game: &Game) {
+    let cell_size = PRESETS[game.preset].cell_size;
+    for cell in game.field.cells() {
+        commands
+            .spawn((
+                Sprite::from_color(HIDDEN_COLOR, Vec2::splat(cell_size - CELL_GAP)),
+                Transform::from_translation(cell_to_world(game, cell).extend(0.0)),
+                CellView(cell),
+            ))
+            .with_child((
+                Text2d::default(),
+                TextFont {
+                    font_size: cell_size * 0.6,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+                Transform::from_xyz(0.0, 0.0, 1.0),
+                CellLabel,
+            ));
+    }
+}
+
+/// `R` キーで同じ難易度を、数字キーで選んだ難易度を最初から始める
+fn new_game(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut game: ResMut<Game>,
+    mut play_time: ResMut<PlayTime>,
+    cells: Query<Entity, With<CellView>>,
+) {
+    let preset = if keyboard_input.just_pressed(KeyCode::KeyR) {
+        game.preset
+    } else if let Some(preset) = PRESET_KEYS
+        .iter()
+        .position(|&key| keyboard_input.just_pressed(key))
+    {
+        preset
+    } else {
+        return;
+    };
+
+    for entity in &cells {
+        commands.entity(entity).despawn_recursive();
+    }
+    *game = Game::new(preset);
+    *play_time = PlayTime::default();
+    spawn_cells(&mut commands, &game);
+}
+
+/// クリックしたマスを操作する
+///
+/// - 左クリック: 開いていないマスを開く。開いた数字のマスならコードする
+/// - 右クリック: 旗を立てる・外す
+/// - 中クリック: コードする (周りの旗が数字と同じ数なら、旗のない周りのマスをまとめて開く)
+fn handle_clicks(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut game: ResMut<Game>,
+) {
+    let (camera, camera_transform) = *camera;
+    let Some(cell) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+        .and_then(|position| world_to_cell(&game, position))
+    else {
+        return;
+    };
+    if game.field.outcome().is_some() {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    if mouse_input.just_pressed(MouseButton::Left) {
+        if game.field.state(cell) == CellState::Revealed {
+            game.field.chord(cell, &mut rng);
+        } else {
+            game.field.reveal(cell, &mut rng);
+        }
+    } else if mouse_input.just_pressed(MouseButton::Right) {
+        game.field.toggle_flag(cell);
+    } else if mouse_input.just_pressed(MouseButton::Middle) {
+        game.field.chord(cell, &mut rng);
+    }
+}
+
+/// 最初のマスを開いてから勝ち負けが決まるまで、時間を計る
+fn tick_play_time(time: Res<Time>, game: Res<Game>, mut play_time: ResMut<PlayTime>) {
+    if game.field.started() && game.field.outcome().is_none() {
+        play_time.0.tick(time.delta());
+    }
+}
+
+/// 勝ったら、その難易度のベストタイムを更新する
+fn record_best_time(game: Res<Game>, play_time: Res<PlayTime>, mut records: ResMut<Records>) {
+    if !game.is_changed() || game.field.outcome() != Some(Outcome::Won) {
+        return;
+    }
+    let name = PRESETS[game.preset].name;
+    let secs = play_time.0.elapsed_secs();
+    let is_best = records.best_times.get(name).is_none_or(|&best| secs < best);
+    if is_best {
+        info!("new best time for {name}: {secs:.2}s");
+        records.best_times.insert(name.to_string(), secs);
+    }
+}
+
+/// マスの色と、重ねるテキスト (周りの地雷の数・旗・地雷) を盤面に合わせる
+///
+/// 負けたときは、すべての地雷と、地雷のないマスに立てた旗 (`X`) を見せる。
+fn update_cells(
+    game: Res<Game>,
+    mut cells: Query<(&CellView, &mut Sprite, &Children)>,
+    mut labels: Query<(&mut Text2d, &mut TextColor), With<CellLabel>>,
+) {
+    let field = &game.field;
+    let lost = matches!(field.outcome(), Some(Outcome::Lost(_)));
+    for (view, mut sprite, children) in &mut cells {
+        let cell = view.0;
+        let (color, label, label_color) = match field.state(cell) {
+            CellState::Revealed if field.is_mine(cell) => {
+                (EXPLODED_COLOR, "*".to_string(), MINE_COLOR)
+            }
+            CellState::Revealed => match field.adjacent_mines(cell) {
+                0 => (REVEALED_COLOR, String::new(), MINE_COLOR),
+                count => (REVEALED_COLOR, count.to_string(), NUMBER_COLORS[count - 1]),
+            },
+            CellState::Flagged if lost && !field.is_mine(cell) => {
+                (HIDDEN_COLOR, "X".to_string(), WRONG_FLAG_COLOR)
+            }
+            CellState::Flagged => (HIDDEN_COLOR, "F".to_string(), FLAG_COLOR),
+            CellState::Hidden if lost && field.is_mine(cell) => {
+                (HIDDEN_COLOR, "*".to_string(), MINE_COLOR)
+            }
+            CellState::Hidden => (HIDDEN_COLOR, String::new(), MINE_COLOR),
+        };
+        sprite.color = color;
+        for &child in children.iter() {
+            if let Ok((mut text, mut text_color)) = labels.get_mut(child) {
+                text.0.clone_from(&label);
+                text_color.0 = label_color;
+            }
+        }
+    }
+}
+
+/// 難易度・残りの地雷の数・時間・ベストタイム・結果と、操作方法を表示する
+fn update_hud(
+    game: Res<Game>,
+    play_time: Res<PlayTime>,
+    records: Res<Records>,
+    mut text: Single<&mut Text, With<HudText>>,
+) {
+    let preset = &PRESETS[game.preset];
+    let best = records
+        .best_times
+        .get(preset.name)
+        .map_or("--".to_string(), |best| format!("{best:.2}s"));
+    let status = match game.field.outcome() {
+        Some(Outcome::Won) => "CLEARED!  Press R to play again",
+        Some(Outcome::Lost(_)) => "BOOM!  Press R to try again",
+        None => "",
+    };
+    text.0 = format!(
+        "{} {}x{}  Mines left {}  Time {:.1}s  Best {best}\n{status}\n\n\
+         Left click: reveal / chord   Right click: flag   Middle click: chord\n\
+         1/2/3: Beginner / Intermediate / Expert   R: restart",
+        preset.name,
+        preset.width,
+        preset.height,
+        game.field.mines_left(),
+        play_time.0.elapsed_secs(),
+    );
+}
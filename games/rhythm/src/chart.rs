@@ -0,0 +1,74 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// 譜面アセットを読み込めるようにするプラグイン
+pub struct ChartPlugin;
+
+impl Plugin for ChartPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Chart>().init_asset_loader::<ChartLoader>();
+    }
+}
+
+/// 譜面 (`*.chart.ron`)
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct Chart {
+    pub title: String,
+    pub bpm: f64,              // 伴奏のクリック音のテンポ
+    pub notes: Vec<ChartNote>, // 時刻順に並んだノーツ
+}
+
+/// 譜面上の 1 つのノーツ
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct ChartNote {
+    pub time: f64,   // 曲の開始からの時刻 (秒)
+    pub lane: usize, // レーン番号 (0 始まり)
+}
+
+impl Chart {
+    /// 最後のノーツの時刻 (ノーツがなければ 0)
+    pub fn last_note_time(&self) -> f64 {
+        self.notes.iter().map(|note| note.time).fold(0.0, f64::max)
+    }
+}
+
+/// 譜面の読み込み時に発生するエラー
+#[derive(Debug, Error)]
+pub enum ChartLoaderError {
+    #[error("could not read chart: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse chart: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+/// RON 形式の譜面を読み込むアセットローダー
+#[derive(Default)]
+struct ChartLoader;
+
+impl AssetLoader for ChartLoader {
+    type Asset = Chart;
+    type Settings = ();
+    type Error = ChartLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Chart, ChartLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut chart: Chart = ron::de::from_bytes(&bytes)?;
+        // 判定処理は時刻順に並んでいることを前提にしている
+        chart.notes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(chart)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["chart.ron"]
+    }
+}
@@ -0,0 +1,510 @@
+use std::time::Duration;
+
+use bevy::{audio::Pitch, prelude::*};
+use settings::{Settings, SettingsPlugin};
+
+mod chart;
+
+use chart::{Chart, ChartPlugin};
+
+// 読み込む譜面
+const CHART_PATH: &str = "charts/demo.chart.ron";
+
+// レーンごとの入力キー
+const LANE_KEYS: [KeyCode; 4] = [KeyCode::KeyD, KeyCode::KeyF, KeyCode::KeyJ, KeyCode::KeyK];
+// レーンの幅
+const LANE_WIDTH: f32 = 100.0;
+// 判定ラインの y 座標
+const HIT_LINE_Y: f32 = -250.0;
+// ノーツのスクロール速度 (ピクセル/秒)
+const SCROLL_SPEED: f32 = 400.0;
+// ノーツのサイズ
+const NOTE_SIZE: Vec2 = Vec2::new(90.0, 20.0);
+
+// 判定幅 (秒)。これより大きくずれた入力は無視し、通り過ぎたノーツは MISS になる
+const PERFECT_WINDOW: f64 = 0.045;
+const GOOD_WINDOW: f64 = 0.11;
+// 判定ごとの基本点
+const PERFECT_SCORE: u32 = 300;
+const GOOD_SCORE: u32 = 100;
+// コンボ 1 つあたりのボーナス点
+const COMBO_BONUS: u32 = 10;
+// 最後のノーツから結果画面に移るまでの時間 (秒)
+const OUTRO_DURATION: f64 = 2.0;
+
+// 伴奏のクリック音
+const CLICK_FREQUENCY: f32 = 660.0;
+const CLICK_DURATION: Duration = Duration::from_millis(30);
+
+// 背景色
+const BACKGROUND_COLOR: Color = Color::srgb(0.08, 0.08, 0.12);
+// レーンの色 (通常 / キーを押している間)
+const LANE_COLOR: Color = Color::srgb(0.15, 0.15, 0.2);
+const LANE_PRESSED_COLOR: Color = Color::srgb(0.3, 0.3, 0.4);
+// 判定ラインとノーツの色
+const HIT_LINE_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+const NOTE_COLOR: Color = Color::srgb(0.4, 0.8, 1.0);
+// 判定表示の色
+const PERFECT_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+const GOOD_COLOR: Color = Color::srgb(0.5, 1.0, 0.5);
+const MISS_COLOR: Color = Color::srgb(1.0, 0.4, 0.4);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, SettingsPlugin, ChartPlugin))
+        .init_state::<GameState>()
+        .enable_state_scoped_entities::<GameState>()
+        .insert_resource(ClearColor(BACKGROUND_COLOR))
+        .init_resource::<Judgements>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            start_when_loaded.run_if(in_state(GameState::Loading)),
+        )
+        .add_systems(OnEnter(GameState::Playing), start_song)
+        .add_systems(
+            Update,
+            (
+                play_backing_click,
+                scroll_notes,
+                judge_input,
+                judge_missed_notes,
+                highlight_lanes,
+                update_hud,
+                update_judgement_text,
+                finish_song,
+            )
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnEnter(GameState::Results), show_results)
+        .add_systems(Update, restart.run_if(in_state(GameState::Results)))
+        .run();
+}
+
+/// ゲームの進行状態
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum GameState {
+    #[default]
+    Loading, // 譜面の読み込み待ち
+    Playing, // 演奏中
+    Results, // 結果画面
+}
+
+/// 判定の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Judgement {
+    Perfect,
+    Good,
+    Miss,
+}
+
+impl Judgement {
+    /// 入力と譜面の時刻のずれ (秒) から判定を求める。判定幅の外なら `None`
+    fn from_offset(offset: f64) -> Option<Judgement> {
+        match offset.abs() {
+            x if x <= PERFECT_WINDOW => Some(Judgement::Perfect),
+            x if x <= GOOD_WINDOW => Some(Judgement::Good),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Judgement::Perfect => "PERFECT",
+            Judgement::Good => "GOOD",
+            Judgement::Miss => "MISS",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Judgement::Perfect => PERFECT_COLOR,
+            Judgement::Good => GOOD_COLOR,
+            Judgement::Miss => MISS_COLOR,
+        }
+    }
+}
+
+/// 判定結果とスコアを集計するリソース
+#[derive(Resource, Default, Debug)]
+struct Judgements {
+    perfect: u32,
+    good: u32,
+    miss: u32,
+    combo: u32,
+    max_combo: u32,
+    score: u32,
+    last: Option<Judgement>, // 直前の判定 (判定表示に使う)
+}
+
+impl Judgements {
+    /// 判定を 1 つ記録し、コンボとスコアを更新する
+    fn record(&mut self, judgement: Judgement) {
+        self.last = Some(judgement);
+        let base = match judgement {
+            Judgement::Perfect => {
+                self.perfect += 1;
+                PERFECT_SCORE
+            }
+            Judgement::Good => {
+                self.good += 1;
+                GOOD_SCORE
+            }
+            Judgement::Miss => {
+                self.miss += 1;
+                self.combo = 0;
+                return;
+            }
+        };
+        self.combo += 1;
+        self.max_combo = self.max_combo.max(self.combo);
+        self.score += base + self.combo * COMBO_BONUS;
+    }
+
+    /// 精度 (PERFECT を 1、GOOD を 0.5 として百分率で返す)
+    fn accuracy(&self) -> f32 {
+        let total = self.perfect + self.good + self.miss;
+        if total == 0 {
+            return 0.0;
+        }
+        (self.perfect as f32 + self.good as f32 * 0.5) / total as f32 * 100.0
+    }
+}
+
+/// 演奏中の曲を管理するリソース
+#[derive(Resource)]
+struct Song {
+    chart: Handle<Chart>,
+    click: Handle<Pitch>,
+    start_secs: f64,                // 曲が始まった時点の経過時間
+    last_clicked_beat: Option<u64>, // 最後に伴奏のクリック音を鳴らした拍
+}
+
+impl Song {
+    /// 曲の開始からの経過時間 (秒)
+    fn time(&self, time: &Time) -> f64 {
+        time.elapsed_secs_f64() - self.start_secs
+    }
+}
+
+/// ノーツを示すコンポーネント
+#[derive(Component)]
+struct Note {
+    lane: usize,
+    time: f64,
+}
+
+/// レーンの背景を示すコンポーネント
+#[derive(Component)]
+struct Lane(usize);
+
+/// スコアとコンボを表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct HudText;
+
+/// 直前の判定を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct JudgementText;
+
+/// レーン中央の x 座標
+fn lane_x(lane: usize) -> f32 {
+    (lane as f32 - (LANE_KEYS.len() - 1) as f32 / 2.0) * LANE_WIDTH
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    commands.spawn(Camera2d);
+
+    commands.insert_resource(Song {
+        chart: asset_server.load(CHART_PATH),
+        click: pitches.add(Pitch::new(CLICK_FREQUENCY, CLICK_DURATION)),
+        start_secs: 0.0,
+        last_clicked_beat: None,
+    });
+
+    // レーンと判定ライン
+    let lane_height = 1000.0;
+    for lane in 0..LANE_KEYS.len() {
+        commands.spawn((
+            Sprite::from_color(LANE_COLOR, Vec2::new(LANE_WIDTH - 4.0, lane_height)),
+            Transform::from_xyz(lane_x(lane), 0.0, 0.0),
+            Lane(lane),
+        ));
+    }
+    commands.spawn((
+        Sprite::from_color(
+            HIT_LINE_COLOR,
+            Vec2::new(LANE_WIDTH * LANE_KEYS.len() as f32, 4.0),
+        ),
+        Transform::from_xyz(0.0, HIT_LINE_Y, 1.0),
+    ));
+
+    commands.spawn((
+        Text::new("Loading..."),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        HudText,
+    ));
+}
+
+/// 譜面の読み込みが終わったら演奏を開始する
+fn start_when_loaded(
+    song: Res<Song>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if asset_server.is_loaded_with_dependencies(&song.chart) {
+        next_state.set(GameState::Playing);
+    }
+}
+
+/// 曲の開始時刻を記録し、譜面のノーツをすべて生成する
+fn start_song(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut song: ResMut<Song>,
+    charts: Res<Assets<Chart>>,
+    mut judgements: ResMut<Judgements>,
+) {
+    song.start_secs = time.elapsed_secs_f64();
+    song.last_clicked_beat = None;
+    *judgements = Judgements::default();
+
+    let Some(chart) = charts.get(&song.chart) else {
+        return;
+    };
+    info!("start: {} ({} notes)", chart.title, chart.notes.len());
+
+    // ノーツは画面外 (上) に生成し、`scroll_notes` で位置を更新する
+    for note in &chart.notes {
+        commands.spawn((
+            Sprite::from_color(NOTE_COLOR, NOTE_SIZE),
+            Transform::from_xyz(lane_x(note.lane), 1000.0, 2.0),
+            Note {
+                lane: note.lane,
+                time: note.time,
+            },
+            StateScoped(GameState::Playing),
+        ));
+    }
+
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 40.0,
+            ..default()
+        },
+        TextColor(Color::NONE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(30.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        JudgementText,
+        StateScoped(GameState::Playing),
+    ));
+}
+
+/// 譜面のテンポに合わせて伴奏のクリック音を鳴らす
+fn play_backing_click(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut song: ResMut<Song>,
+    charts: Res<Assets<Chart>>,
+) {
+    let Some(chart) = charts.get(&song.chart) else {
+        return;
+    };
+    let song_time = song.time(&time);
+    if song_time < 0.0 {
+        return;
+    }
+
+    let beat = (song_time / (60.0 / chart.bpm)) as u64;
+    if song.last_clicked_beat != Some(beat) {
+        song.last_clicked_beat = Some(beat);
+        commands.spawn((AudioPlayer(song.click.clone()), PlaybackSettings::DESPAWN));
+    }
+}
+
+/// ノーツを判定ラインに向かってスクロールさせる
+///
+/// 音は遅延補正の分だけ遅れて聞こえるため、映像も同じだけ遅らせる。
+fn scroll_notes(
+    time: Res<Time>,
+    song: Res<Song>,
+    settings: Res<Settings>,
+    mut notes: Query<(&Note, &mut Transform)>,
+) {
+    let visual_time = song.time(&time) - settings.audio_latency_secs();
+    for (note, mut transform) in &mut notes {
+        transform.translation.y = HIT_LINE_Y + ((note.time - visual_time) as f32) * SCROLL_SPEED;
+    }
+}
+
+/// キー入力を判定する
+///
+/// 押されたレーンで最も早い未判定ノーツとの時刻差を、遅延補正を差し引いて評価する。
+fn judge_input(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    song: Res<Song>,
+    settings: Res<Settings>,
+    notes: Query<(Entity, &Note)>,
+    mut judgements: ResMut<Judgements>,
+) {
+    let input_time = song.time(&time) - settings.audio_latency_secs();
+
+    for (lane, key) in LANE_KEYS.iter().enumerate() {
+        if !keyboard_input.just_pressed(*key) {
+            continue;
+        }
+
+        let nearest = notes
+            .iter()
+            .filter(|(_, note)| note.lane == lane)
+            .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time));
+        let Some((entity, note)) = nearest else {
+            continue;
+        };
+
+        if let Some(judgement) = Judgement::from_offset(input_time - note.time) {
+            judgements.record(judgement);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// 判定幅を過ぎても押されなかったノーツを MISS にする
+fn judge_missed_notes(
+    mut commands: Commands,
+    time: Res<Time>,
+    song: Res<Song>,
+    settings: Res<Settings>,
+    notes: Query<(Entity, &Note)>,
+    mut judgements: ResMut<Judgements>,
+) {
+    let input_time = song.time(&time) - settings.audio_latency_secs();
+
+    for (entity, note) in &notes {
+        if input_time - note.time > GOOD_WINDOW {
+            judgements.record(Judgement::Miss);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// キーを押しているレーンを明るくする
+fn highlight_lanes(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut lanes: Query<(&Lane, &mut Sprite)>,
+) {
+    for (lane, mut sprite) in &mut lanes {
+        sprite.color = if keyboard_input.pressed(LANE_KEYS[lane.0]) {
+            LANE_PRESSED_COLOR
+        } else {
+            LANE_COLOR
+        };
+    }
+}
+
+/// スコアとコンボの表示を更新する
+fn update_hud(judgements: Res<Judgements>, mut text: Single<&mut Text, With<HudText>>) {
+    if judgements.is_changed() {
+        text.0 = format!(
+            "Score: {}\nCombo: {}\nKeys: D F J K",
+            judgements.score, judgements.combo
+        );
+    }
+}
+
+/// 新しい判定が出たら表示し、それ以外のフレームでは徐々に透明にする
+fn update_judgement_text(
+    time: Res<Time>,
+    judgements: Res<Judgements>,
+    judgement_text: Single<(&mut Text, &mut TextColor), With<JudgementText>>,
+) {
+    let (mut text, mut color) = judgement_text.into_inner();
+    match judgements.last {
+        Some(judgement) if judgements.is_changed() => {
+            text.0 = judgement.label().to_string();
+            color.0 = judgement.color();
+        }
+        _ => {
+            let alpha = color.0.alpha();
+            color
+                .0
+                .set_alpha((alpha - time.delta_secs() * 2.0).max(0.0));
+        }
+    }
+}
+
+/// すべてのノーツを判定し終えたら結果画面に移る
+fn finish_song(
+    time: Res<Time>,
+    song: Res<Song>,
+    charts: Res<Assets<Chart>>,
+    notes: Query<(), With<Note>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(chart) = charts.get(&song.chart) else {
+        return;
+    };
+    if notes.is_empty() && song.time(&time) > chart.last_note_time() + OUTRO_DURATION {
+        next_state.set(GameState::Results);
+    }
+}
+
+/// 結果画面を表示する
+fn show_results(mut commands: Commands, judgements: Res<Judgements>) {
+    commands.spawn((
+        Text::new(format!(
+            "RESULTS\n\n\
+             Score: {}\n\
+             Max combo: {}\n\
+             Perfect: {}\n\
+             Good: {}\n\
+             Miss: {}\n\
+             Accuracy: {:.1}%\n\n\
+             Press Enter to play again",
+            judgements.score,
+            judgements.max_combo,
+            judgements.perfect,
+            judgements.good,
+            judgements.miss,
+            judgements.accuracy(),
+        )),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(25.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(BACKGROUND_COLOR.with_alpha(0.8)),
+        StateScoped(GameState::Results),
+    ));
+}
+
+/// Enter キーで最初から遊び直す
+fn restart(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        next_state.set(GameState::Playing);
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+// マップの大きさ (マス)
+pub const MAP_WIDTH: i32 = 64;
+pub const MAP_HEIGHT: i32 = 36;
+// BSP で分割を止める区画の大きさ (これより小さくなる分割はしない)
+const MIN_LEAF_SIZE: i32 = 10;
+// BSP の部屋の最小の大きさ (壁を含まない)
+const MIN_ROOM_SIZE: i32 = 4;
+// 洞窟の初期状態で壁にする割合
+const CAVE_WALL_CHANCE: f64 = 0.45;
+// 洞窟のセル・オートマトンを適用する回数
+const CAVE_STEPS: usize = 5;
+
+/// マスの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Wall,  // 通れず、見通せない
+    Floor, // 通れて、見通せる
+}
+
+/// ダンジョンの作り方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Generator {
+    #[default]
+    Bsp, // 区画を二分割していき、区画ごとの部屋を通路でつなぐ
+    Caves, // ランダムな壁をセル・オートマトンでならした洞窟
+}
+
+impl Generator {
+    pub fn next(self) -> Generator {
+        match self {
+            Generator::Bsp => Generator::Caves,
+            Generator::Caves => Generator::Bsp,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Generator::Bsp => "BSP rooms",
+            Generator::Caves => "Cellular caves",
+        }
+    }
+}
+
+/// マスの並び (外周は必ず壁)
+pub struct Map {
+    pub width: i32,
+    pub height: i32,
+    tiles: Vec<Tile>,
+}
+
+impl Map {
+    fn filled(tile: Tile) -> Map {
+        Map {
+            width: MAP_WIDTH,
+            height: MAP_HEIGHT,
+            tiles: vec![tile; (MAP_WIDTH * MAP_HEIGHT) as usize],
+        }
+    }
+
+    pub fn in_bounds(&self, cell: IVec2) -> bool {
+        cell.cmpge(IVec2::ZERO).all() && cell.cmplt(IVec2::new(self.width, self.height)).all()
+    }
+
+    pub fn index(&self, cell: IVec2) -> usize {
+        (cell.y * self.width + cell.x) as usize
+    }
+
+    /// マップのすべてのマス
+    pub fn cells(&self) -> impl Iterator<Item = IVec2> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| IVec2::new(x, y)))
+    }
+
+    /// マスの種類 (マップの外は壁として扱う)
+    pub fn tile(&self, cell: IVec2) -> Tile {
+        if self.in_bounds(cell) {
+            self.tiles[self.index(cell)]
+        } else {
+            Tile::Wall
+        }
+    }
+
+    fn set(&mut self, cell: IVec2, tile: Tile) {
+        if self.in_bounds(cell) {
+            let index = self.index(cell);
+            self.tiles[index] = tile;
+        }
+    }
+
+    pub fn is_floor(&self, cell: IVec2) -> bool {
+        self.tile(cell) == Tile::Floor
+    }
+
+    /// 床のマスをすべて集める
+    pub fn floor_cells(&self) -> Vec<IVec2> {
+        self.cells().filter(|&cell| self.is_floor(cell)).collect()
+    }
+
+    /// 周りの 8 マスのうち壁の数 (マップの外も壁として数える)
+    fn wall_neighbors(&self, cell: IVec2) -> usize {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| IVec2::new(dx, dy)))
+            .filter(|&offset| offset != IVec2::ZERO && self.tile(cell + offset) == Tile::Wall)
+            .count()
+    }
+}
+
+/// 作ったダンジョン
+pub struct Dungeon {
+    pub map: Map,
+    pub start: IVec2, // プレイヤーの開始位置
+}
+
+/// 指定した作り方でダンジョンを作る
+pub fn generate(generator: Generator, rng: &mut impl Rng) -> Dungeon {
+    match generator {
+        Generator::Bsp => generate_bsp(rng),
+        Generator::Caves => generate_caves(rng),
+    }
+}
+
+/// BSP (二分空間分割) で部屋と通路のダンジョンを作る
+///
+/// マップ全体の区画を、長いほうの辺に沿ってランダムな位置で二分割していき、
+/// 分割できなくなった区画 (葉) ごとに部屋を 1 つ置く。
+/// 分割した 2 つの区画の部屋どうしを L 字の通路でつなぐので、すべての部屋がつながる。
+fn generate_bsp(rng: &mut impl Rng) -> Dungeon {
+    let mut map = Map::filled(Tile::Wall);
+    let bounds = IRect::new(1, 1, MAP_WIDTH - 1, MAP_HEIGHT - 1);
+    let mut rooms = Vec::new();
+    split(&mut map, bounds, rng, &mut rooms);
+    Dungeon {
+        start: rooms[0].center(),
+        map,
+    }
+}
+
+/// 区画を分割して部屋を置き、置いた部屋のうち 1 つを返す (通路の端に使う)
+fn split(map: &mut Map, area: IRect, rng: &mut impl Rng, rooms: &mut Vec<IRect>) -> IRect {
+    let size = area.size();
+    let horizontal = if size.x >= MIN_LEAF_SIZE * 2 && size.y >= MIN_LEAF_SIZE * 2 {
+        // 両方の辺で分割できるときは、長いほうの辺を切る
+        if size.x == size.y {
+            rng.gen_bool(0.5)
+        } else {
+            size.x > size.y
+        }
+    } else if size.x >= MIN_LEAF_SIZE * 2 {
+        true
+    } else if size.y >= MIN_LEAF_SIZE * 2 {
+        false
+    } else {
+        let room = place_room(map, area, rng);
+        rooms.push(room);
+        return room;
+    };
+
+    let (first, second) = if horizontal {
+        let at = rng.gen_range(area.min.x + MIN_LEAF_SIZE..=area.max.x - MIN_LEAF_SIZE);
+        (
+            IRect::new(area.min.x, area.min.y, at, area.max.y),
+            IRect::new(at, area.min.y, area.max.x, area.max.y),
+        )
+    } else {
+        let at = rng.gen_range(area.min.y + MIN_LEAF_SIZE..=area.max.y - MIN_LEAF_SIZE);
+        (
+            IRect::new(area.min.x, area.min.y, area.max.x, at),
+            IRect::new(area.min.x, at, area.max.x, area.max.y),
+        )
+    };
+    let a = split(map, first, rng, rooms);
+    let b = split(map, second, rng, rooms);
+    dig_corridor(map, a.center(), b.center(), rng);
+    if rng.gen_bool(0.5) {
+        a
+    } else {
+        b
+    }
+}
+
+/// 区画の中に、周りに 1 マスの壁を残してランダムな大きさの部屋を掘る
+fn place_room(map: &mut Map, area: IRect, rng: &mut impl Rng) -> IRect {
+    let max_size = area.size() - 2;
+    let size = IVec2::new(
+        rng.gen_range(MIN_ROOM_SIZE.min(max_size.x)..=max_size.x),
+        rng.gen_range(MIN_ROOM_SIZE.min(max_size.y)..=max_size.y),
+    );
+    let min = IVec2::new(
+        rng.gen_range(area.min.x + 1..=area.max.x - 1 - size.x),
+        rng.gen_range(area.min.y + 1..=area.max.y - 1 - size.y),
+    );
+    let room = IRect::from_corners(min, min + size);
+    for y in room.min.y..room.max.y {
+        for x in room.min.x..room.max.x {
+            map.set(IVec2::new(x, y), Tile::Floor);
+        }
+    }
+    room
+}
+
+/// 2 つのマスを、横→縦か縦→横の L 字の通路でつなぐ
+fn dig_corridor(map: &mut Map, from: IVec2, to: IVec2, rng: &mut impl Rng) {
+    let corner = if rng.gen_bool(0.5) {
+        IVec2::new(to.x, from.y)
+    } else {
+        IVec2::new(from.x, to.y)
+    };
+    for (start, end) in [(from, corner), (corner, to)] {
+        let step = (end - start).signum();
+        let mut cell = start;
+        map.set(cell, Tile::Floor);
+        while cell != end {
+            cell += step;
+            map.set(cell, Tile::Floor);
+        }
+    }
+}
+
+/// セル・オートマトンで洞窟のダンジョンを作る
+///
+/// ランダムに壁を置いたあと、周りの 8 マスのうち 5 マス以上が壁なら壁、3 マス以下なら床にする (4 マスならそのまま) 規則を
+/// 何度か当てはめて、壁の塊と広い空洞にならす。
+/// つながっていない空洞は通れないので、いちばん広い空洞だけを残して、ほかは壁で埋める。
+fn generate_caves(rng: &mut impl Rng) -> Dungeon {
+    let mut map = Map::filled(Tile::Wall);
+    for cell in map.cells().collect::<Vec<_>>() {
+        if !on_border(cell) && !rng.gen_bool(CAVE_WALL_CHANCE) {
+            map.set(cell, Tile::Floor);
+        }
+    }
+
+    for _ in 0..CAVE_STEPS {
+        let mut next = Map::filled(Tile::Wall);
+        for cell in map.cells() {
+            let floor = match map.wall_neighbors(cell) {
+                0..=3 => true,
+                4 => map.is_floor(cell),
+                _ => false,
+            };
+            if floor && !on_border(cell) {
+                next.set(cell, Tile::Floor);
+            }
+        }
+        map = next;
+    }
+
+    // いちばん広い空洞を探し、それ以外の床を壁で埋める
+    let mut largest = Vec::new();
+    let mut seen = vec![false; map.tiles.len()];
+    for cell in map.floor_cells() {
+        if seen[map.index(cell)] {
+            continue;
+        }
+        let region = flood_region(&map, cell, &mut seen);
+        if region.len() > largest.len() {
+            largest = region;
+        }
+    }
+    let mut cave = Map::filled(Tile::Wall);
+    for &cell in &largest {
+        cave.set(cell, Tile::Floor);
+    }
+
+    // 空洞が小さすぎたときは作り直す
+    if largest.len() < (MAP_WIDTH * MAP_HEIGHT / 4) as usize {
+        return generate_caves(rng);
+    }
+    Dungeon {
+        start: *largest.choose(rng).unwrap(),
+        map: cave,
+    }
+}
+
+/// マップの外周のマスか
+fn on_border(cell: IVec2) -> bool {
+    cell.x == 0 || cell.y == 0 || cell.x == MAP_WIDTH - 1 || cell.y == MAP_HEIGHT - 1
+}
+
+/// `start` から上下左右にたどれる床のマスをすべて集める (幅優先の塗りつぶし)
+fn flood_region(map: &Map, start: IVec2, seen: &mut [bool]) -> Vec<IVec2> {
+    let mut region = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    seen[map.index(start)] = true;
+    while let Some(cell) = queue.pop_front() {
+        region.push(cell);
+        for step in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = cell + step;
+            if map.is_floor(next) && !seen[map.index(next)] {
+                seen[map.index(next)] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    region
+}
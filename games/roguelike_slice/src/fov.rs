@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use crate::dungeon::{Map, Tile};
+
+// 8 つの八分円それぞれで、(列, 行) をマップの (x, y) の差に直す係数 [xx, xy, yx, yy]
+const OCTANTS: [[i32; 4]; 8] = [
+    [1, 0, 0, 1],
+    [0, 1, 1, 0],
+    [0, -1, 1, 0],
+    [-1, 0, 0, 1],
+    [-1, 0, 0, -1],
+    [0, -1, -1, 0],
+    [0, 1, -1, 0],
+    [1, 0, 0, -1],
+];
+
+/// `origin` から `radius` マス以内で見えるマスを、再帰的なシャドウキャスティングで求める
+///
+/// 周りを 8 つの八分円に分け、それぞれで `origin` から 1 行ずつ外へ向かって光を走らせる。
+/// 壁に当たったら、壁の手前までの傾きの範囲で次の行から再帰し、壁の向こうは影として飛ばす。
+/// 返り値は [`Map::index`] の順に並べた、見えるかどうかの配列 (見える壁も含む)。
+pub fn field_of_view(map: &Map, origin: IVec2, radius: i32) -> Vec<bool> {
+    let mut visible = vec![false; (map.width * map.height) as usize];
+    visible[map.index(origin)] = true;
+    for transform in OCTANTS {
+        cast_light(map, &mut visible, origin, radius, 1, 1.0, 0.0, transform);
+    }
+    visible
+}
+
+/// 1 つの八分円の `row` 行目から、傾きが `start` から `end` の範囲に光を走らせる
+///
+/// 行の中のマスは、傾きの大きいほう (`start` の側) から小さいほうへ順に調べる。
+#[allow(clippy::too_many_arguments)]
+fn cast_light(
+    map: &Map,
+    visible: &mut [bool],
+    origin: IVec2,
+    radius: i32,
+    row: i32,
+    mut start: f32,
+    end: f32,
+    [xx, xy, yx, yy]: [i32; 4],
+) {
+    if start < end {
+        return;
+    }
+    let mut next_start = start;
+    for distance in row..=radius {
+        let mut blocked = false;
+        let dy = -distance;
+        for dx in -distance..=0 {
+            // マスの左下の角と右上の角の傾き
+            let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+            let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+            if start < right_slope {
+                continue;
+            }
+            if end > left_slope {
+                break;
+            }
+
+            let cell = origin + IVec2::new(dx * xx + dy * xy, dx * yx + dy * yy);
+            if map.in_bounds(cell) && dx * dx + dy * dy <= radius * radius {
+                visible[map.index(cell)] = true;
+            }
+
+            let opaque = map.tile(cell) == Tile::Wall;
+            if blocked {
+                if opaque {
+                    // 壁が続いている間は、影の始まりを壁の向こうへずらしていく
+                    next_start = right_slope;
+                } else {
+                    // 壁が切れたら、影の続きから光を走らせ直す
+                    blocked = false;
+                    start = next_start;
+                }
+            } else if opaque && distance < radius {
+                // 壁の手前までの範囲は、次の行から再帰して調べる
+                blocked = true;
+                cast_light(
+                    map,
+                    visible,
+                    origin,
+                    radius,
+                    distance + 1,
+                    start,
+                    left_slope,
+                    [xx, xy, yx, yy],
+                );
+                next_start = right_slope;
+            }
+        }
+        if blocked {
+            break;
+        }
+    }
+}
@@ -0,0 +1,503 @@
+use bevy::{prelude::*, utils::HashSet};
+use rand::seq::SliceRandom;
+
+mod dungeon;
+mod fov;
+mod message_log;
+
+use dungeon::{Generator, Map, Tile};
+use message_log::{MessageKind, MessageLog, MessageLogPlugin};
+
+// マスの大きさ
+const TILE_SIZE: f32 = 14.0;
+// マップの中心の位置 (下のメッセージ欄と重ならないように上へずらす)
+const MAP_OFFSET: Vec2 = Vec2::new(0.0, 72.0);
+// プレイヤーが見通せる距離 (マス)
+const FOV_RADIUS: i32 = 8;
+// 1 つのダンジョンに置く敵の数と、プレイヤーの開始位置から離す距離 (マス)
+const MONSTER_COUNT: usize = 14;
+const MIN_SPAWN_DISTANCE: i32 = 6;
+
+// 背景とマスの色 (見えているマス・一度見たマス)
+const BACKGROUND_COLOR: Color = Color::srgb(0.02, 0.02, 0.03);
+const LIT_WALL_COLOR: Color = Color::srgb(0.55, 0.45, 0.3);
+const LIT_FLOOR_COLOR: Color = Color::srgb(0.25, 0.22, 0.18);
+const EXPLORED_WALL_COLOR: Color = Color::srgb(0.18, 0.18, 0.24);
+const EXPLORED_FLOOR_COLOR: Color = Color::srgb(0.08, 0.08, 0.11);
+// プレイヤーの文字の色
+const PLAYER_COLOR: Color = Color::srgb(1.0, 0.95, 0.5);
+
+// プレイヤーの能力値
+const PLAYER_FIGHTER: Fighter = Fighter {
+    hp: 30,
+    max_hp: 30,
+    power: 5,
+    defense: 1,
+};
+
+/// 敵の種類
+struct MonsterKind {
+    name: &'static str,
+    glyph: &'static str,
+    color: Color,
+    fighter: Fighter,
+    weight: u32, // 出やすさ (大きいほど出やすい)
+}
+
+const MONSTER_KINDS: [MonsterKind; 2] = [
+    MonsterKind {
+        name: "goblin",
+        glyph: "g",
+        color: Color::srgb(0.4, 0.85, 0.35),
+        fighter: Fighter {
+            hp: 6,
+            max_hp: 6,
+            power: 3,
+            defense: 0,
+        },
+        weight: 3,
+    },
+    MonsterKind {
+        name: "orc",
+        glyph: "o",
+        color: Color::srgb(0.9, 0.35, 0.25),
+        fighter: Fighter {
+            hp: 10,
+            max_hp: 10,
+            power: 4,
+            defense: 1,
+        },
+        weight: 1,
+    },
+];
+
+// 8 方向の移動キー (矢印キー・WASD は上下左右、テンキーは斜めも)
+const MOVE_KEYS: [(KeyCode, IVec2); 16] = [
+    (KeyCode::ArrowUp, IVec2::new(0, 1)),
+    (KeyCode::ArrowDown, IVec2::new(0, -1)),
+    (KeyCode::ArrowLeft, IVec2::new(-1, 0)),
+    (KeyCode::ArrowRight, IVec2::new(1, 0)),
+    (KeyCode::KeyW, IVec2::new(0, 1)),
+    (KeyCode::KeyS, IVec2::new(0, -1)),
+    (KeyCode::KeyA, IVec2::new(-1, 0)),
+    (KeyCode::KeyD, IVec2::new(1, 0)),
+    (KeyCode::Numpad8, IVec2::new(0, 1)),
+    (KeyCode::Numpad2, IVec2::new(0, -1)),
+    (KeyCode::Numpad4, IVec2::new(-1, 0)),
+    (KeyCode::Numpad6, IVec2::new(1, 0)),
+    (KeyCode::Numpad7, IVec2::new(-1, 1)),
+    (KeyCode::Numpad9, IVec2::new(1, 1)),
+    (KeyCode::Numpad1, IVec2::new(-1, -1)),
+    (KeyCode::Numpad3, IVec2::new(1, -1)),
+];
+// その場で 1 ターン待つキー
+const WAIT_KEYS: [KeyCode; 2] = [KeyCode::Space, KeyCode::Numpad5];
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(MessageLogPlugin)
+        .init_state::<Turn>()
+        .insert_resource(ClearColor(BACKGROUND_COLOR))
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                new_dungeon,
+                player_turn.run_if(in_state(Turn::Player)),
+                update_field_of_view,
+            )
+                .chain(),
+        )
+        .add_systems(OnEnter(Turn::Monsters), monster_turn)
+        .add_systems(
+            PostUpdate,
+            (
+                sync_transforms,
+                update_tiles.run_if(resource_changed::<Level>),
+                update_monster_visibility,
+                update_hud,
+            )
+                .before(TransformSystem::TransformPropagate),
+        )
+        .run();
+}
+
+/// ターンの段階
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum Turn {
+    #[default]
+    Player, // プレイヤーの入力を待っている
+    Monsters, // 敵が 1 回ずつ行動する (入った直後に `Player` か `Dead` へ進む)
+    Dead,     // プレイヤーが倒れた
+}
+
+/// 現在のダンジョンのマップと視界
+#[derive(Resource)]
+struct Level {
+    generator: Generator,
+    map: Map,
+    visible: Vec<bool>,  // いま見えているマス (`Map::index` の順)
+    explored: Vec<bool>, // 一度でも見たマス
+}
+
+impl Level {
+    fn is_visible(&self, cell: IVec2) -> bool {
+        self.map.in_bounds(cell) && self.visible[self.map.index(cell)]
+    }
+}
+
+/// マップ上の位置
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct Position(IVec2);
+
+/// 体力と攻撃力・防御力
+#[derive(Component, Debug, Clone, Copy)]
+struct Fighter {
+    hp: i32,
+    max_hp: i32,
+    power: i32,
+    defense: i32,
+}
+
+impl Fighter {
+    /// `target` に攻撃したときのダメージ (防御力の分だけ減る)
+    fn damage_to(&self, target: &Fighter) -> i32 {
+        (self.power - target.defense).max(0)
+    }
+}
+
+/// プレイヤー
+#[derive(Component)]
+struct Player;
+
+/// 敵 (メッセージに使う名前)
+#[derive(Component)]
+struct Monster(&'static str);
+
+/// マップのマスの表示
+#[derive(Component)]
+struct TileView(IVec2);
+
+/// ダンジョンを作り直すときに消すエンティティ
+#[derive(Component)]
+struct LevelEntity;
+
+/// 体力・残りの敵の数・ダンジョンの作り方と、操作方法を表示するテキスト
+#[derive(Component)]
+struct HudText;
+
+/// マスの中心のワールド座標
+fn cell_to_world(map: &Map, cell: IVec2) -> Vec2 {
+    let size = IVec2::new(map.width, map.height).as_vec2();
+    (cell.as_vec2() - (size - 1.0) / 2.0) * TILE_SIZE + MAP_OFFSET
+}
+
+/// カメラ・HUD・最初のダンジョンを配置する
+fn setup(mut commands: Commands, mut log: ResMut<MessageLog>) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        HudText,
+    ));
+    spawn_level(&mut commands, Generator::default(), &mut log);
+}
+
+/// ダンジョンを作り、マスの表示・プレイヤー・敵を配置する
+///
+/// 敵はプレイヤーの開始位置から離れた床に、重ならないように置く。
+fn spawn_level(commands: &mut Commands, generator: Generator, log: &mut MessageLog) {
+    let mut rng = rand::thread_rng();
+    let dungeon = dungeon::generate(generator, &mut rng);
+    let map = dungeon.map;
+
+    for cell in map.cells() {
+        commands.spawn((
+            Sprite::from_color(BACKGROUND_COLOR, Vec2::splat(TILE_SIZE)),
+            Transform::from_translation(cell_to_world(&map, cell).extend(0.0)),
+            TileView(cell),
+            LevelEntity,
+        ));
+    }
+
+    commands.spawn((
+        glyph_bundle("@", PLAYER_COLOR, 2.0),
+        Position(dungeon.start),
+        PLAYER_FIGHTER,
+        Player,
+        LevelEntity,
+    ));
+
+    let mut spawns: Vec<IVec2> = map
+        .floor_cells()
+        .into_iter()
+        .filter(|cell| (*cell - dungeon.start).abs().max_element() >= MIN_SPAWN_DISTANCE)
+        .collect();
+    spawns.shuffle(&mut rng);
+    for &cell in spawns.iter().take(MONSTER_COUNT) {
+        let kind = MONSTER_KINDS
+            .choose_weighted(&mut rng, |kind| kind.weight)
+            .unwrap();
+        commands.spawn((
+            glyph_bundle(kind.glyph, kind.color, 1.0),
+            Position(cell),
+            kind.fighter,
+            Monster(kind.name),
+            LevelEntity,
+        ));
+    }
+
+    let visible = fov::field_of_view(&map, dungeon.start, FOV_RADIUS);
+    commands.insert_resource(Level {
+        generator,
+        explored: visible.clone(),
+        visible,
+        map,
+    });
+
+    log.clear();
+    log.push(
+        MessageKind::Info,
+        format!(
+            "You descend into the dungeon ({}). Bump into monsters to attack them.",
+            generator.label()
+        ),
+    );
+}
+
+/// マスの上に文字を描く `Text2d` (位置は `sync_transforms` が `Position` から決める)
+fn glyph_bundle(glyph: &str, color: Color, z: f32) -> impl Bundle {
+    (
+        Text2d::new(glyph),
+        TextFont {
+            font_size: TILE_SIZE + 2.0,
+            ..default()
+        },
+        TextColor(color),
+        Transform::from_xyz(0.0, 0.0, z),
+    )
+}
+
+/// `R` キーで同じ作り方の、`G` キーでもう一方の作り方のダンジョンを作り直す
+fn new_dungeon(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    level: Res<Level>,
+    mut log: ResMut<MessageLog>,
+    mut next_turn: ResMut<NextState<Turn>>,
+    entities: Query<Entity, With<LevelEntity>>,
+) {
+    let generator = if keyboard_input.just_pressed(KeyCode::KeyR) {
+        level.generator
+    } else if keyboard_input.just_pressed(KeyCode::KeyG) {
+        level.generator.next()
+    } else {
+        return;
+    };
+
+    for entity in &entities {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_level(&mut commands, generator, &mut log);
+    next_turn.set(Turn::Player);
+}
+
+/// プレイヤーの行動 (移動・攻撃・待機) を 1 つ受け付け、敵のターンへ進む
+///
+/// 敵のいるマスへ移動しようとすると、その敵を攻撃する (バンプ攻撃)。
+/// 壁に向かって動こうとしたときは、ターンを使わない。
+fn player_turn(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    level: Res<Level>,
+    mut log: ResMut<MessageLog>,
+    mut next_turn: ResMut<NextState<Turn>>,
+    player: Single<(&mut Position, &Fighter), With<Player>>,
+    mut monsters: Query<(Entity, &Position, &mut Fighter, &Monster), Without<Player>>,
+) {
+    if WAIT_KEYS
+        .iter()
+        .any(|&key| keyboard_input.just_pressed(key))
+    {
+        next_turn.set(Turn::Monsters);
+        return;
+    }
+    let Some(direction) = MOVE_KEYS
+        .iter()
+        .find(|(key, _)| keyboard_input.just_pressed(*key))
+        .map(|(_, direction)| *direction)
+    else {
+        return;
+    };
+
+    let (mut position, attacker) = player.into_inner();
+    let target = position.0 + direction;
+    let remaining = monsters.iter().count();
+    if let Some((entity, _, mut defender, name)) = monsters
+        .iter_mut()
+        .find(|(_, monster_position, _, _)| monster_position.0 == target)
+    {
+        let damage = attacker.damage_to(&defender);
+        defender.hp -= damage;
+        log.push(
+            MessageKind::Attack,
+            format!("You hit the {} for {damage} damage.", name.0),
+        );
+        if defender.hp <= 0 {
+            log.push(MessageKind::Death, format!("The {} dies!", name.0));
+            commands.entity(entity).despawn_recursive();
+            if remaining == 1 {
+                log.push(
+                    MessageKind::Info,
+                    "The dungeon falls silent. Press R or G for a new one.",
+                );
+            }
+        }
+    } else if level.map.tile(target) == Tile::Floor {
+        position.0 = target;
+    } else {
+        return;
+    }
+    next_turn.set(Turn::Monsters);
+}
+
+/// プレイヤーが動いたら、視界と一度見たマスを計算し直す
+fn update_field_of_view(
+    mut level: ResMut<Level>,
+    player: Query<&Position, (With<Player>, Changed<Position>)>,
+) {
+    let Ok(position) = player.get_single() else {
+        return;
+    };
+    let visible = fov::field_of_view(&level.map, position.0, FOV_RADIUS);
+    for (explored, &seen) in level.explored.iter_mut().zip(&visible) {
+        *explored |= seen;
+    }
+    level.visible = visible;
+}
+
+/// 敵が 1 回ずつ行動する
+///
+/// プレイヤーから見えている (つまりプレイヤーが見えている) 敵は、隣にいれば攻撃し、
+/// そうでなければプレイヤーに近づく。見えていない敵は動かない。
+fn monster_turn(
+    level: Res<Level>,
+    mut log: ResMut<MessageLog>,
+    mut next_turn: ResMut<NextState<Turn>>,
+    player: Single<(&Position, &mut Fighter), With<Player>>,
+    mut monsters: Query<(&mut Position, &Fighter, &Monster), Without<Player>>,
+) {
+    let (player_position, mut player_fighter) = player.into_inner();
+    let target = player_position.0;
+    let mut occupied: HashSet<IVec2> = monsters.iter().map(|(position, _, _)| position.0).collect();
+
+    for (mut position, fighter, name) in &mut monsters {
+        if !level.is_visible(position.0) {
+            continue;
+        }
+        if (target - position.0).abs().max_element() == 1 {
+            let damage = fighter.damage_to(&player_fighter);
+            player_fighter.hp -= damage;
+            log.push(
+                MessageKind::Hurt,
+                format!("The {} hits you for {damage} damage.", name.0),
+            );
+            if player_fighter.hp <= 0 {
+                log.push(MessageKind::Death, "You die... Press R to try again.");
+                next_turn.set(Turn::Dead);
+                return;
+            }
+            continue;
+        }
+
+        // 空いている周りの 8 マスのうち、プレイヤーにいちばん近いマスへ動く
+        let step = (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| IVec2::new(dx, dy)))
+            .map(|offset| position.0 + offset)
+            .filter(|cell| level.map.is_floor(*cell) && !occupied.contains(cell))
+            .min_by_key(|cell| (*cell - target).length_squared());
+        if let Some(cell) = step {
+            if (cell - target).length_squared() < (position.0 - target).length_squared() {
+                occupied.remove(&position.0);
+                occupied.insert(cell);
+                position.0 = cell;
+            }
+        }
+    }
+    next_turn.set(Turn::Player);
+}
+
+/// `Position` が変わったエンティティを、そのマスの上へ動かす
+fn sync_transforms(
+    level: Res<Level>,
+    mut query: Query<(&Position, &mut Transform), Changed<Position>>,
+) {
+    for (position, mut transform) in &mut query {
+        let z = transform.translation.z;
+        transform.translation = cell_to_world(&level.map, position.0).extend(z);
+    }
+}
+
+/// マスの色を、見えているか・一度見たかに合わせる (一度も見ていないマスは背景の色)
+fn update_tiles(level: Res<Level>, mut tiles: Query<(&TileView, &mut Sprite)>) {
+    for (view, mut sprite) in &mut tiles {
+        let cell = view.0;
+        let index = level.map.index(cell);
+        let wall = level.map.tile(cell) == Tile::Wall;
+        sprite.color = match (level.visible[index], level.explored[index], wall) {
+            (true, _, true) => LIT_WALL_COLOR,
+            (true, _, false) => LIT_FLOOR_COLOR,
+            (false, true, true) => EXPLORED_WALL_COLOR,
+            (false, true, false) => EXPLORED_FLOOR_COLOR,
+            (false, false, _) => BACKGROUND_COLOR,
+        };
+    }
+}
+
+/// 敵は視界の中にいるときだけ表示する
+fn update_monster_visibility(
+    level: Res<Level>,
+    mut monsters: Query<(&Position, &mut Visibility), With<Monster>>,
+) {
+    for (position, mut visibility) in &mut monsters {
+        visibility.set_if_neq(if level.is_visible(position.0) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+/// 体力・残りの敵の数・ダンジョンの作り方と、操作方法を表示する
+fn update_hud(
+    level: Res<Level>,
+    turn: Res<State<Turn>>,
+    player: Single<&Fighter, With<Player>>,
+    monsters: Query<(), With<Monster>>,
+    mut text: Single<&mut Text, With<HudText>>,
+) {
+    let status = if *turn.get() == Turn::Dead {
+        "  YOU DIED"
+    } else {
+        ""
+    };
+    text.0 = format!(
+        "HP {}/{}  Monsters left {}  Dungeon: {}{status}\n\
+         Arrows/WASD/Numpad: move or attack   Space/Numpad5: wait   \
+         PageUp/PageDown: scroll log   R: new dungeon   G: switch generator",
+        player.hp.max(0),
+        player.max_hp,
+        monsters.iter().count(),
+        level.generator.label(),
+    );
+}
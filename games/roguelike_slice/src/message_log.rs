@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+// 覚えておくメッセージの数 (古いものから捨てる)
+const LOG_CAPACITY: usize = 200;
+// メッセージ欄に一度に表示する行数
+const VISIBLE_LINES: usize = 8;
+// メッセージ欄の文字の大きさと背景の色
+const LOG_FONT_SIZE: f32 = 15.0;
+const PANEL_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+// 過去のメッセージを読んでいるときの見出しの色
+const SCROLL_HINT_COLOR: Color = Color::srgb(0.5, 0.5, 0.55);
+
+/// 画面の下にメッセージ欄を置き、`PageUp` / `PageDown` で過去のメッセージを読めるようにするプラグイン
+pub struct MessageLogPlugin;
+
+impl Plugin for MessageLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MessageLog>()
+            .add_systems(Startup, spawn_log_panel)
+            .add_systems(
+                Update,
+                (
+                    scroll_log,
+                    update_log_panel.run_if(resource_changed::<MessageLog>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// メッセージの種類 (種類ごとに色を変える)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Info,   // 案内
+    Attack, // プレイヤーの攻撃
+    Hurt,   // プレイヤーが受けた攻撃
+    Death,  // 敵やプレイヤーが倒れた
+}
+
+impl MessageKind {
+    fn color(self) -> Color {
+        match self {
+            MessageKind::Info => Color::srgb(0.8, 0.8, 0.85),
+            MessageKind::Attack => Color::srgb(0.95, 0.85, 0.4),
+            MessageKind::Hurt => Color::srgb(0.95, 0.45, 0.4),
+            MessageKind::Death => Color::srgb(0.75, 0.4, 0.95),
+        }
+    }
+}
+
+struct Message {
+    text: String,
+    kind: MessageKind,
+}
+
+/// ゲームの出来事を記録するメッセージの履歴
+#[derive(Resource, Default)]
+pub struct MessageLog {
+    messages: VecDeque<Message>,
+    scroll: usize, // 最新のメッセージから何行さかのぼって表示しているか
+}
+
+impl MessageLog {
+    /// メッセージを追加する (過去のメッセージを読んでいたときは最新の位置に戻す)
+    pub fn push(&mut self, kind: MessageKind, text: impl Into<String>) {
+        self.messages.push_back(Message {
+            text: text.into(),
+            kind,
+        });
+        if self.messages.len() > LOG_CAPACITY {
+            self.messages.pop_front();
+        }
+        self.scroll = 0;
+    }
+
+    /// 履歴をすべて消す
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.scroll = 0;
+    }
+
+    /// 表示する範囲を `lines` 行だけ過去 (正) か最新 (負) の側へずらす
+    fn scroll_by(&mut self, lines: isize) {
+        let max_scroll = self.messages.len().saturating_sub(VISIBLE_LINES);
+        self.scroll = self.scroll.saturating_add_signed(lines).min(max_scroll);
+    }
+
+    /// 表示する範囲のメッセージ (古い順)
+    fn visible(&self) -> impl Iterator<Item = &Message> {
+        let end = self.messages.len() - self.scroll;
+        self.messages.range(end.saturating_sub(VISIBLE_LINES)..end)
+    }
+}
+
+/// メッセージ欄のテキスト (子のテキストスパンが 1 行ずつのメッセージ)
+#[derive(Component)]
+struct LogPanel;
+
+/// 画面の下にメッセージ欄を配置する
+fn spawn_log_panel(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: LOG_FONT_SIZE,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.0),
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            // 過去のメッセージを読んでいるときの見出しの 1 行分も空けておく
+            height: Val::Px((VISIBLE_LINES + 1) as f32 * LOG_FONT_SIZE * 1.2 + 16.0),
+            padding: UiRect::axes(Val::Px(12.0), Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(PANEL_COLOR),
+        LogPanel,
+    ));
+}
+
+/// `PageUp` / `PageDown` で表示する範囲を 1 画面分ずつずらし、`End` で最新に戻す
+fn scroll_log(keyboard_input: Res<ButtonInput<KeyCode>>, mut log: ResMut<MessageLog>) {
+    let page = VISIBLE_LINES as isize;
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        log.scroll_by(page);
+    } else if keyboard_input.just_pressed(KeyCode::PageDown) {
+        log.scroll_by(-page);
+    } else if keyboard_input.just_pressed(KeyCode::End) {
+        log.scroll = 0;
+    }
+}
+
+/// メッセージの履歴が変わったら、表示する範囲のメッセージで欄を作り直す
+fn update_log_panel(
+    mut commands: Commands,
+    log: Res<MessageLog>,
+    panel: Single<Entity, With<LogPanel>>,
+) {
+    // テキストスパンは親の `TextFont` を引き継がないので、スパンごとに指定する
+    let font = TextFont {
+        font_size: LOG_FONT_SIZE,
+        ..default()
+    };
+    commands
+        .entity(*panel)
+        .despawn_descendants()
+        .with_children(|parent| {
+            for message in log.visible() {
+                parent.spawn((
+                    TextSpan(format!("{}\n", message.text)),
+                    font.clone(),
+                    TextColor(message.kind.color()),
+                ));
+            }
+            if log.scroll > 0 {
+                parent.spawn((
+                    TextSpan(format!(
+                        "-- {} newer line(s) below (PageDown / End) --",
+                        log.scroll
+                    )),
+                    font.clone(),
+                    TextColor(SCROLL_HINT_COLOR),
+                ));
+            }
+        });
+}
@@ -0,0 +1,477 @@
+use bevy::{
+    color::palettes::css::{DEEP_SKY_BLUE, TOMATO},
+    prelude::*,
+};
+
+mod rules;
+
+use rules::{resolve_step, Action, StepOutcome, ACTIONS_PER_TURN, GRID_SIZE, MAX_HEALTH};
+
+// 計画フェーズの制限時間 (秒)。時間切れになったら、足りない行動を `Wait` で埋めて解決に進む
+const PLANNING_SECONDS: f32 = 10.0;
+// 解決フェーズで 1 ステップを見せる時間 (秒)
+const STEP_SECONDS: f32 = 0.7;
+// マスの大きさと、マスどうしの隙間
+const CELL_SIZE: f32 = 64.0;
+const CELL_GAP: f32 = 4.0;
+// 駒がマスへ移るときの追従の速さ (大きいほど速く追いつく)
+const PIECE_FOLLOW_SPEED: f32 = 12.0;
+
+// 背景とマスの色
+const BACKGROUND_COLOR: Color = Color::srgb(0.08, 0.08, 0.1);
+const CELL_COLOR: Color = Color::srgb(0.16, 0.16, 0.2);
+// 動けなかった駒に付ける × 印の色
+const BLOCKED_COLOR: Color = Color::srgb(0.7, 0.7, 0.7);
+
+// 2 人の開始位置
+const START_CELLS: [IVec2; 2] = [IVec2::new(1, 3), IVec2::new(5, 3)];
+
+/// プレイヤーごとのキー割り当て
+struct Controls {
+    moves: [(KeyCode, IVec2); 4],
+    attack: KeyCode,
+    wait: KeyCode,
+    undo: KeyCode,
+}
+
+// 1P は左手 (WASD)、2P は右手 (矢印キー) で、同じキーボードを 2 人で使う
+const CONTROLS: [Controls; 2] = [
+    Controls {
+        moves: [
+            (KeyCode::KeyW, IVec2::Y),
+            (KeyCode::KeyA, IVec2::NEG_X),
+            (KeyCode::KeyS, IVec2::NEG_Y),
+            (KeyCode::KeyD, IVec2::X),
+        ],
+        attack: KeyCode::Space,
+        wait: KeyCode::KeyE,
+        undo: KeyCode::KeyQ,
+    },
+    Controls {
+        moves: [
+            (KeyCode::ArrowUp, IVec2::Y),
+            (KeyCode::ArrowLeft, IVec2::NEG_X),
+            (KeyCode::ArrowDown, IVec2::NEG_Y),
+            (KeyCode::ArrowRight, IVec2::X),
+        ],
+        attack: KeyCode::Enter,
+        wait: KeyCode::ShiftRight,
+        undo: KeyCode::Backspace,
+    },
+];
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .init_state::<Phase>()
+        .enable_state_scoped_entities::<Phase>()
+        .insert_resource(ClearColor(BACKGROUND_COLOR))
+        .init_resource::<Board>()
+        .init_resource::<Plans>()
+        .insert_resource(PlanningTimer(Timer::from_seconds(
+            PLANNING_SECONDS,
+            TimerMode::Once,
+        )))
+        .insert_resource(Resolution {
+            step: 0,
+            timer: Timer::from_seconds(STEP_SECONDS, TimerMode::Repeating),
+            last: None,
+        })
+        .add_systems(Startup, setup)
+        .add_systems(OnEnter(Phase::Planning), start_planning)
+        .add_systems(
+            Update,
+            (queue_actions, finish_planning)
+                .chain()
+                .run_if(in_state(Phase::Planning)),
+        )
+        .add_systems(OnEnter(Phase::Resolving), start_resolution)
+        .add_systems(
+            Update,
+            (resolve_actions, draw_outcome)
+                .chain()
+                .run_if(in_state(Phase::Resolving)),
+        )
+        .add_systems(OnEnter(Phase::GameOver), show_result)
+        .add_systems(Update, restart.run_if(in_state(Phase::GameOver)))
+        .add_systems(Update, (follow_board, update_hud))
+        .run();
+}
+
+/// ターンの段階
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum Phase {
+    #[default]
+    Planning, // 2 人がそれぞれ行動を予約している
+    Resolving, // 予約した行動を 1 ステップずつ同時に解決している
+    GameOver,  // どちらか (または両方) の体力がなくなった
+}
+
+/// 盤面の状態 (駒の表示はこのリソースを追いかけるだけで、勝敗の判定はすべてここで行う)
+#[derive(Resource)]
+struct Board {
+    positions: [IVec2; 2],
+    health: [u32; 2],
+    turn: u32,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board {
+            positions: START_CELLS,
+            health: [MAX_HEALTH; 2],
+            turn: 1,
+        }
+    }
+}
+
+/// 2 人が予約した行動 (相手には中身を見せず、数だけを表示する)
+#[derive(Resource, Default)]
+struct Plans([Vec<Action>; 2]);
+
+impl Plans {
+    fn ready(&self, player: usize) -> bool {
+        self.0[player].len() >= ACTIONS_PER_TURN
+    }
+}
+
+/// 計画フェーズの残り時間
+#[derive(Resource)]
+struct PlanningTimer(Timer);
+
+/// 解決フェーズの進み具合
+#[derive(Resource)]
+struct Resolution {
+    step: usize,               // 次に解決するステップ
+    timer: Timer,              // 1 ステップを見せる時間
+    last: Option<StepOutcome>, // 直前に解決したステップ (攻撃の表示に使う)
+}
+
+/// 盤面の駒 (何人目のプレイヤーか)
+#[derive(Component)]
+struct Piece(usize);
+
+/// ターン・体力・予約の状況を表示するテキスト
+#[derive(Component)]
+struct HudText;
+
+/// プレイヤーの色
+fn player_color(player: usize) -> Color {
+    [DEEP_SKY_BLUE, TOMATO][player].into()
+}
+
+/// マスの中心のワールド座標 (盤面の中心が原点)
+fn cell_to_world(cell: IVec2) -> Vec2 {
+    let offset = (GRID_SIZE - 1) as f32 / 2.0;
+    (cell.as_vec2() - Vec2::splat(offset)) * (CELL_SIZE + CELL_GAP)
+}
+
+/// カメラ・盤面のマス・2 人の駒・テキストを配置する
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    for x in 0..GRID_SIZE {
+        for y in 0..GRID_SIZE {
+            commands.spawn((
+                Sprite::from_color(CELL_COLOR, Vec2::splat(CELL_SIZE)),
+                Transform::from_translation(cell_to_world(IVec2::new(x, y)).extend(0.0)),
+            ));
+        }
+    }
+
+    for (player, cell) in START_CELLS.into_iter().enumerate() {
+        commands.spawn((
+            Sprite::from_color(player_color(player), Vec2::splat(CELL_SIZE * 0.6)),
+            Transform::from_translation(cell_to_world(cell).extend(1.0)),
+            Piece(player),
+        ));
+    }
+
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        HudText,
+    ));
+}
+
+/// 計画フェーズを始める (予約を空にし、制限時間を戻す)
+fn start_planning(mut plans: ResMut<Plans>, mut timer: ResMut<PlanningTimer>) {
+    *plans = Plans::default();
+    timer.0.reset();
+}
+
+/// 2 人のキー入力を、それぞれの予約の末尾に積む (取り消しキーで最後の 1 つを消す)
+///
+/// 予約がいっぱいになったプレイヤーは準備完了で、取り消さない限り入力を受け付けない。
+fn queue_actions(keyboard_input: Res<ButtonInput<KeyCode>>, mut plans: ResMut<Plans>) {
+    for (player, controls) in CONTROLS.iter().enumerate() {
+        let queue = &mut plans.0[player];
+        if keyboard_input.just_pressed(controls.undo) {
+            queue.pop();
+            continue;
+        }
+        if queue.len() >= ACTIONS_PER_TURN {
+            continue;
+        }
+
+        let action = controls
+            .moves
+            .iter()
+            .find(|(key, _)| keyboard_input.just_pressed(*key))
+            .map(|(_, direction)| Action::Move(*direction))
+            .or_else(|| {
+                [
+                    (controls.attack, Action::Attack),
+                    (controls.wait, Action::Wait),
+                ]
+                .into_iter()
+                .find(|(key, _)| keyboard_input.just_pressed(*key))
+                .map(|(_, action)| action)
+            });
+        if let Some(action) = action {
+            queue.push(action);
+        }
+    }
+}
+
+/// 2 人とも準備ができたか、制限時間が切れたら解決フェーズへ進む
+fn finish_planning(
+    time: Res<Time>,
+    mut timer: ResMut<PlanningTimer>,
+    mut plans: ResMut<Plans>,
+    mut next_phase: ResMut<NextState<Phase>>,
+) {
+    let timed_out = timer.0.tick(time.delta()).finished();
+    let both_ready = plans.ready(0) && plans.ready(1);
+    if !(timed_out || both_ready) {
+        return;
+    }
+    for queue in &mut plans.0 {
+        queue.resize(ACTIONS_PER_TURN, Action::Wait);
+    }
+    next_phase.set(Phase::Resolving);
+}
+
+/// 解決フェーズを始める
+fn start_resolution(mut resolution: ResMut<Resolution>) {
+    resolution.step = 0;
+    resolution.timer.reset();
+    resolution.last = None;
+}
+
+/// `STEP_SECONDS` ごとに、2 人の行動を 1 つずつ同時に解決する
+///
+/// 体力がなくなったプレイヤーは、そのターンの残りのステップでは何もしない。
+/// すべてのステップを見せ終えたら、体力がなくなったプレイヤーがいれば勝敗を、いなければ次のターンの計画へ進む。
+fn resolve_actions(
+    time: Res<Time>,
+    plans: Res<Plans>,
+    mut board: ResMut<Board>,
+    mut resolution: ResMut<Resolution>,
+    mut next_phase: ResMut<NextState<Phase>>,
+) {
+    if !resolution.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let step = resolution.step;
+    if step >= ACTIONS_PER_TURN {
+        if board.health.contains(&0) {
+            next_phase.set(Phase::GameOver);
+        } else {
+            board.turn += 1;
+            next_phase.set(Phase::Planning);
+        }
+        return;
+    }
+
+    // 体力がなくなったプレイヤーは、ターンの残りの行動をしない
+    let actions = [0, 1].map(|player| {
+        if board.health[player] == 0 {
+            Action::Wait
+        } else {
+            plans.0[player][step]
+        }
+    });
+    let outcome = resolve_step(board.positions, actions);
+    board.positions = outcome.positions;
+    for (health, hit) in board.health.iter_mut().zip(outcome.hit) {
+        if hit {
+            *health = health.saturating_sub(1);
+        }
+    }
+    resolution.last = Some(outcome);
+    resolution.step += 1;
+}
+
+/// 直前のステップの結果を描く
+///
+/// 攻撃したプレイヤーの攻撃範囲 (上下左右のマス) を枠で、攻撃を受けた駒を白い輪で、
+/// 衝突や盤面の端で動けなかった駒を × 印で示す。
+fn draw_outcome(board: Res<Board>, resolution: Res<Resolution>, mut gizmos: Gizmos) {
+    let Some(outcome) = resolution.last else {
+        return;
+    };
+    for player in 0..2 {
+        let color = player_color(player);
+        if outcome.attacked[player] {
+            for direction in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let cell = board.positions[player] + direction;
+                if rules::in_bounds(cell) {
+                    gizmos.rect_2d(
+                        Isometry2d::from_translation(cell_to_world(cell)),
+                        Vec2::splat(CELL_SIZE - 6.0),
+                        color,
+                    );
+                }
+            }
+        }
+        if outcome.blocked[player] {
+            let center = cell_to_world(board.positions[player]);
+            let half = Vec2::splat(CELL_SIZE * 0.2);
+            gizmos.line_2d(center - half, center + half, BLOCKED_COLOR);
+            gizmos.line_2d(
+                center + half.with_y(-half.y),
+                center - half.with_y(-half.y),
+                BLOCKED_COLOR,
+            );
+        }
+        if outcome.hit[player] {
+            gizmos.circle_2d(
+                cell_to_world(board.positions[player]),
+                CELL_SIZE * 0.45,
+                Color::WHITE,
+            );
+        }
+    }
+}
+
+/// 駒を盤面の位置へなめらかに移し、体力がなくなった駒を薄くする
+fn follow_board(
+    time: Res<Time>,
+    board: Res<Board>,
+    mut pieces: Query<(&Piece, &mut Transform, &mut Sprite)>,
+) {
+    let t = 1.0 - (-PIECE_FOLLOW_SPEED * time.delta_secs()).exp();
+    for (piece, mut transform, mut sprite) in &mut pieces {
+        let target = cell_to_world(board.positions[piece.0]);
+        let position = transform.translation.truncate().lerp(target, t);
+        transform.translation = position.extend(transform.translation.z);
+
+        let alpha = if board.health[piece.0] == 0 { 0.3 } else { 1.0 };
+        sprite.color = player_color(piece.0).with_alpha(alpha);
+    }
+}
+
+/// 予約した行動の表示 (計画中は数だけ、解決中は中身を公開して今のステップを `>` で示す)
+fn plan_summary(phase: Phase, queue: &[Action], step: usize) -> String {
+    match phase {
+        Phase::Planning => {
+            let filled = "#".repeat(queue.len());
+            let empty = "-".repeat(ACTIONS_PER_TURN - queue.len());
+            let ready = if queue.len() >= ACTIONS_PER_TURN {
+                "  READY"
+            } else {
+                ""
+            };
+            format!("[{filled}{empty}]{ready}")
+        }
+        Phase::Resolving | Phase::GameOver => queue
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let marker = if i + 1 == step { ">" } else { " " };
+                format!("{marker}{}", action.label())
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// ターン・残り時間・体力・予約の状況と、操作方法を表示する
+fn update_hud(
+    phase: Res<State<Phase>>,
+    board: Res<Board>,
+    plans: Res<Plans>,
+    timer: Res<PlanningTimer>,
+    resolution: Res<Resolution>,
+    mut text: Single<&mut Text, With<HudText>>,
+) {
+    let phase = *phase.get();
+    let header = match phase {
+        Phase::Planning => format!(
+            "Turn {}  PLANNING  {:.1}s",
+            board.turn,
+            timer.0.remaining_secs()
+        ),
+        Phase::Resolving => format!(
+            "Turn {}  RESOLVING  step {}/{}",
+            board.turn, resolution.step, ACTIONS_PER_TURN
+        ),
+        Phase::GameOver => format!("Turn {}  GAME OVER", board.turn),
+    };
+    let players = (0..2)
+        .map(|player| {
+            format!(
+                "P{}  HP {}/{}  {}",
+                player + 1,
+                board.health[player],
+                MAX_HEALTH,
+                plan_summary(phase, &plans.0[player], resolution.step)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.0 = format!(
+        "{header}\n{players}\n\n\
+         P1: WASD move  Space attack  E wait  Q undo\n\
+         P2: Arrows move  Enter attack  RShift wait  Backspace undo"
+    );
+}
+
+/// 勝敗を画面の中央に表示する (両方の体力が同時になくなったら引き分け)
+fn show_result(mut commands: Commands, board: Res<Board>) {
+    let (message, color) = match board.health {
+        [0, 0] => ("DRAW", Color::WHITE),
+        [_, 0] => ("P1 WINS", player_color(0)),
+        _ => ("P2 WINS", player_color(1)),
+    };
+    commands.spawn((
+        Text::new(format!("{message}\nPress Enter to play again")),
+        TextFont {
+            font_size: 40.0,
+            ..default()
+        },
+        TextColor(color),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(45.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        StateScoped(Phase::GameOver),
+    ));
+}
+
+/// 勝敗の表示中に `Enter` キーで盤面を戻し、最初のターンから遊び直す
+fn restart(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut board: ResMut<Board>,
+    mut next_phase: ResMut<NextState<Phase>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        *board = Board::default();
+        next_phase.set(Phase::Planning);
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+// 盤面のマス数 (縦横とも)
+pub const GRID_SIZE: i32 = 7;
+// 1 ターンに予約する行動の数
+pub const ACTIONS_PER_TURN: usize = 3;
+// 体力の初期値
+pub const MAX_HEALTH: u32 = 3;
+
+/// 予約できる行動
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Move(IVec2), // 上下左右に 1 マス動く
+    Attack,      // 上下左右に隣り合うマスを攻撃する
+    Wait,        // 何もしない
+}
+
+impl Action {
+    /// 画面に表示する名前
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Move(IVec2 { x: 0, y: 1 }) => "Up",
+            Action::Move(IVec2 { x: 0, y: -1 }) => "Down",
+            Action::Move(IVec2 { x: -1, y: 0 }) => "Left",
+            Action::Move(IVec2 { x: 1, y: 0 }) => "Right",
+            Action::Move(_) => "Move",
+            Action::Attack => "Attack",
+            Action::Wait => "Wait",
+        }
+    }
+}
+
+/// 1 ステップ (2 人の行動を 1 つずつ) を解決した結果
+#[derive(Debug, Clone, Copy)]
+pub struct StepOutcome {
+    pub positions: [IVec2; 2], // 解決後の位置
+    pub blocked: [bool; 2],    // 動こうとして動けなかったか
+    pub attacked: [bool; 2],   // 攻撃したか
+    pub hit: [bool; 2],        // 攻撃を受けたか
+}
+
+/// マスが盤面の中にあるかどうか
+pub fn in_bounds(cell: IVec2) -> bool {
+    cell.cmpge(IVec2::ZERO).all() && cell.cmplt(IVec2::splat(GRID_SIZE)).all()
+}
+
+/// 2 つのマスが上下左右に隣り合っているかどうか
+pub fn adjacent(a: IVec2, b: IVec2) -> bool {
+    let d = (a - b).abs();
+    d.x + d.y == 1
+}
+
+/// 2 人の行動を同時に解決する
+///
+/// 行動の順番で有利・不利が出ないように、先に 2 人の移動先をまとめて決め、次の規則で衝突を解決する。
+///
+/// - 盤面の外へは動けない。
+/// - 2 人が同じマスへ動こうとしたら、どちらも動けない。
+/// - 2 人が入れ替わろうとしたら (すれ違い)、どちらも動けない。
+/// - 動かない相手のマスへ動こうとしたら、動けない (相手が同時に出ていくマスへは入れる)。
+///
+/// 攻撃は移動が済んだ後の位置で判定するので、隣へ逃げた相手には当たらず、隣へ踏み込んできた相手には当たる。
+/// 2 人が同時に攻撃し合えば、両方に当たる。
+pub fn resolve_step(positions: [IVec2; 2], actions: [Action; 2]) -> StepOutcome {
+    let mut targets = positions;
+    let mut blocked = [false; 2];
+    for i in 0..2 {
+        if let Action::Move(direction) = actions[i] {
+            let target = positions[i] + direction;
+            if in_bounds(target) {
+                targets[i] = target;
+            } else {
+                blocked[i] = true;
+            }
+        }
+    }
+
+    let collided = targets[0] == targets[1];
+    let swapped = targets[0] == positions[1] && targets[1] == positions[0];
+    if collided || swapped {
+        // 動こうとした側だけを元のマスに戻す
+        for i in 0..2 {
+            if targets[i] != positions[i] {
+                targets[i] = positions[i];
+                blocked[i] = true;
+            }
+        }
+    }
+
+    let attacked = actions.map(|action| action == Action::Attack);
+    let in_reach = adjacent(targets[0], targets[1]);
+    StepOutcome {
+        positions: targets,
+        blocked,
+        attacked,
+        hit: [attacked[1] && in_reach, attacked[0] && in_reach],
+    }
+}
@@ -0,0 +1,329 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use settings::{Profile, SettingsPlugin};
+
+mod stable_id;
+
+use stable_id::{Link, StableId, StableIdPlugin, StableIdRegistry};
+
+// セーブデータのファイル名 (プロファイルのディレクトリ内)
+const SAVE_FILE: &str = "stable_ids.ron";
+// 扉とスイッチの組の数
+const PAIRS: usize = 4;
+// オブジェクトの大きさと間隔
+const OBJECT_SIZE: Vec2 = Vec2::new(60.0, 60.0);
+const OBJECT_SPACING: f32 = 160.0;
+// 扉とスイッチの y 座標
+const DOOR_Y: f32 = 120.0;
+const SWITCH_Y: f32 = -120.0;
+
+// 色
+const DOOR_CLOSED_COLOR: Color = Color::srgb(0.6, 0.35, 0.15);
+const DOOR_OPEN_COLOR: Color = Color::srgba(0.6, 0.35, 0.15, 0.25);
+const SWITCH_COLOR: Color = Color::srgb(0.3, 0.6, 0.9);
+const LINK_COLOR: Color = Color::srgb(0.4, 0.9, 0.4);
+const BROKEN_LINK_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, SettingsPlugin, StableIdPlugin))
+        .init_resource::<RemovedDoors>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                press_switches,
+                remove_door,
+                restore_door,
+                save,
+                load,
+                update_labels,
+                draw_links,
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// オブジェクトの種類
+#[derive(Component, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Door,   // スイッチで開閉する
+    Switch, // リンク先の扉を開閉する
+}
+
+/// 扉が開いているかどうか
+#[derive(Component, Deref, DerefMut)]
+struct Open(bool);
+
+/// オブジェクトのラベル (子エンティティ) を示すコンポーネント
+#[derive(Component)]
+struct Label;
+
+/// 1 つのオブジェクトの保存形式
+///
+/// エンティティ ID は保存せず、自分の ID もリンク先も [`StableId`] で表す。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SavedObject {
+    id: StableId,
+    kind: Kind,
+    position: Vec2,
+    open: bool,
+    target: Option<StableId>,
+}
+
+/// セーブデータ
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+struct SaveData {
+    next_id: u64,
+    objects: Vec<SavedObject>,
+}
+
+/// despawn した扉 (同じ ID で元に戻すために保持する)
+#[derive(Resource, Default, Deref, DerefMut)]
+struct RemovedDoors(Vec<SavedObject>);
+
+fn setup(mut commands: Commands, mut registry: ResMut<StableIdRegistry>) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::new(
+            "Space: press switches, D: despawn a door, R: respawn it\n\
+             S: save, L: load (switches are spawned before their doors)",
+        ),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+
+    // スイッチは隣の扉を指すようにずらして接続する
+    let doors: Vec<StableId> = (0..PAIRS).map(|_| registry.allocate()).collect();
+    let left = -OBJECT_SPACING * (PAIRS - 1) as f32 / 2.0;
+    for (index, door) in doors.iter().enumerate() {
+        let x = left + index as f32 * OBJECT_SPACING;
+        spawn_object(
+            &mut commands,
+            &SavedObject {
+                id: *door,
+                kind: Kind::Door,
+                position: Vec2::new(x, DOOR_Y),
+                open: false,
+                target: None,
+            },
+        );
+        spawn_object(
+            &mut commands,
+            &SavedObject {
+                id: registry.allocate(),
+                kind: Kind::Switch,
+                position: Vec2::new(x, SWITCH_Y),
+                open: false,
+                target: Some(doors[(index + 1) % PAIRS]),
+            },
+        );
+    }
+}
+
+/// 保存形式からオブジェクトを生成する
+///
+/// リンクは ID だけを持たせて生成し、参照先のエンティティは後で [`StableIdPlugin`] が解決する。
+fn spawn_object(commands: &mut Commands, object: &SavedObject) {
+    let color = match object.kind {
+        Kind::Door if object.open => DOOR_OPEN_COLOR,
+        Kind::Door => DOOR_CLOSED_COLOR,
+        Kind::Switch => SWITCH_COLOR,
+    };
+    let mut entity = commands.spawn((
+        Sprite::from_color(color, OBJECT_SIZE),
+        Transform::from_translation(object.position.extend(0.0)),
+        object.id,
+        object.kind,
+    ));
+    match object.kind {
+        Kind::Door => {
+            entity.insert(Open(object.open));
+        }
+        Kind::Switch => {
+            if let Some(target) = object.target {
+                entity.insert(Link::new(target));
+            }
+        }
+    }
+    entity.with_child((
+        Text2d::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Transform::from_xyz(0.0, -OBJECT_SIZE.y, 1.0),
+        Label,
+    ));
+}
+
+/// スペースキーですべてのスイッチを押し、リンク先の扉を開閉する
+fn press_switches(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    switches: Query<&Link>,
+    mut doors: Query<(&mut Open, &mut Sprite)>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for link in &switches {
+        // 参照先が存在しないリンクは何もしない
+        let Some(Ok((mut open, mut sprite))) = link.entity().map(|door| doors.get_mut(door)) else {
+            continue;
+        };
+        **open = !**open;
+        sprite.color = if **open {
+            DOOR_OPEN_COLOR
+        } else {
+            DOOR_CLOSED_COLOR
+        };
+    }
+}
+
+/// D キーで ID が最も小さい扉を despawn する
+fn remove_door(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    doors: Query<(Entity, &StableId, &Transform, &Open)>,
+    mut removed: ResMut<RemovedDoors>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyD) {
+        return;
+    }
+    let Some((entity, id, transform, open)) = doors.iter().min_by_key(|(_, id, _, _)| **id) else {
+        return;
+    };
+    removed.push(SavedObject {
+        id: *id,
+        kind: Kind::Door,
+        position: transform.translation.truncate(),
+        open: **open,
+        target: None,
+    });
+    commands.entity(entity).despawn_recursive();
+}
+
+/// R キーで最後に despawn した扉を同じ ID で spawn し直す
+fn restore_door(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut removed: ResMut<RemovedDoors>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    if let Some(door) = removed.pop() {
+        spawn_object(&mut commands, &door);
+    }
+}
+
+/// S キーでオブジェクトを保存する
+#[allow(clippy::type_complexity)]
+fn save(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    objects: Query<(&StableId, &Kind, &Transform, Option<&Open>, Option<&Link>)>,
+    registry: Res<StableIdRegistry>,
+    profile: Res<Profile>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyS) {
+        return;
+    }
+    let mut data = SaveData {
+        next_id: registry.next(),
+        objects: objects
+            .iter()
+            .map(|(id, kind, transform, open, link)| SavedObject {
+                id: *id,
+                kind: *kind,
+                position: transform.translation.truncate(),
+                open: open.is_some_and(|open| **open),
+                target: link.map(|link| link.target),
+            })
+            .collect(),
+    };
+    data.objects.sort_by_key(|object| object.id);
+    profile.save(SAVE_FILE, &data);
+    info!("saved {} objects", data.objects.len());
+}
+
+/// L キーで全オブジェクトを破棄し、保存したデータから生成し直す
+///
+/// リンクの解決順に依存しないことを確かめるため、わざとスイッチを扉より先に生成する。
+fn load(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    objects: Query<Entity, With<StableId>>,
+    mut registry: ResMut<StableIdRegistry>,
+    mut removed: ResMut<RemovedDoors>,
+    profile: Res<Profile>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyL) {
+        return;
+    }
+    let mut data: SaveData = profile.load(SAVE_FILE);
+    if data.objects.is_empty() {
+        warn!("no save data in profile '{}'", profile.name());
+        return;
+    }
+
+    for entity in &objects {
+        commands.entity(entity).despawn_recursive();
+    }
+    removed.clear();
+    registry.restore_next(data.next_id);
+
+    data.objects
+        .sort_by_key(|object| object.kind != Kind::Switch);
+    for object in &data.objects {
+        spawn_object(&mut commands, object);
+    }
+    info!("loaded {} objects", data.objects.len());
+}
+
+/// 各オブジェクトの ID・エンティティ・リンク先をラベルに表示する
+fn update_labels(
+    objects: Query<(Entity, &StableId, Option<&Link>, &Children)>,
+    mut labels: Query<&mut Text2d, With<Label>>,
+) {
+    for (entity, id, link, children) in &objects {
+        let mut text = format!("#{} ({entity})", id.0);
+        if let Some(link) = link {
+            match link.entity() {
+                Some(target) => text += &format!("\n-> #{} ({target})", link.target.0),
+                None => text += &format!("\n-> #{} (missing)", link.target.0),
+            }
+        }
+        for &child in children {
+            if let Ok(mut label) = labels.get_mut(child) {
+                label.0.clone_from(&text);
+            }
+        }
+    }
+}
+
+/// スイッチからリンク先の扉まで線を描く (参照先がない場合は赤い×印)
+fn draw_links(
+    mut gizmos: Gizmos,
+    switches: Query<(&Transform, &Link)>,
+    targets: Query<&Transform, With<StableId>>,
+) {
+    for (transform, link) in &switches {
+        let start = transform.translation.truncate();
+        match link.entity().and_then(|target| targets.get(target).ok()) {
+            Some(target) => {
+                gizmos.arrow_2d(start, target.translation.truncate(), LINK_COLOR);
+            }
+            None => {
+                let end = start + Vec2::new(0.0, OBJECT_SIZE.y);
+                gizmos.line_2d(start, end, BROKEN_LINK_COLOR);
+                gizmos.cross_2d(Isometry2d::from_translation(end), 10.0, BROKEN_LINK_COLOR);
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::{
+    ecs::{component::ComponentId, world::DeferredWorld},
+    prelude::*,
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+/// [`StableId`] の登録表と、[`Link`] の参照先を解決するパスを追加するプラグイン
+pub struct StableIdPlugin;
+
+impl Plugin for StableIdPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StableIdRegistry>()
+            .add_systems(PostUpdate, resolve_links);
+    }
+}
+
+/// エンティティの despawn・再 spawn やセーブ・ロードをまたいで変わらない ID
+///
+/// [`Entity`] は spawn するたびに変わるため、セーブデータや他のエンティティからの参照にはこの ID を使う。
+/// 追加・削除されると [`StableIdRegistry`] がフックで自動的に更新される。
+#[derive(
+    Component, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[component(on_insert = register_stable_id, on_replace = unregister_stable_id)]
+pub struct StableId(pub u64);
+
+/// [`StableId`] から現在のエンティティを引くための登録表
+#[derive(Resource, Debug, Default)]
+pub struct StableIdRegistry {
+    next: u64,
+    entities: HashMap<StableId, Entity>,
+}
+
+impl StableIdRegistry {
+    /// まだ使われていない ID を払い出す
+    pub fn allocate(&mut self) -> StableId {
+        self.next += 1;
+        StableId(self.next)
+    }
+
+    /// 次に払い出す ID の元になる値 (セーブデータに保存し、ロード時に [`Self::restore_next`] で戻す)
+    pub fn next(&self) -> u64 {
+        self.next
+    }
+
+    /// ロードしたデータの ID と重複しないように、払い出しの位置を戻す
+    pub fn restore_next(&mut self, next: u64) {
+        self.next = self.next.max(next);
+    }
+
+    /// ID に対応する現在のエンティティ
+    pub fn get(&self, id: StableId) -> Option<Entity> {
+        self.entities.get(&id).copied()
+    }
+}
+
+/// `StableId` が追加されたときに登録表へ登録するフック
+fn register_stable_id(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    let id = *world.get::<StableId>(entity).unwrap();
+    let mut registry = world.resource_mut::<StableIdRegistry>();
+    if let Some(previous) = registry.entities.insert(id, entity) {
+        if previous != entity {
+            warn!("stable id {id:?} is used by both {previous} and {entity}");
+        }
+    }
+    // 手動で指定された ID とも重複しないようにする
+    registry.next = registry.next.max(id.0);
+}
+
+/// `StableId` が削除・上書きされるときに登録表から外すフック
+fn unregister_stable_id(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    let id = *world.get::<StableId>(entity).unwrap();
+    let mut registry = world.resource_mut::<StableIdRegistry>();
+    if registry.entities.get(&id) == Some(&entity) {
+        registry.entities.remove(&id);
+    }
+}
+
+/// 他のエンティティへの参照
+///
+/// 保存されるのは `target` (StableId) だけで、`entity` は実行時に [`resolve_links`] が埋める。
+/// 参照先が despawn されると `entity` は `None` に戻り、同じ ID で再 spawn されると再び解決される。
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Link {
+    pub target: StableId,
+    entity: Option<Entity>,
+}
+
+impl Link {
+    /// まだ解決されていない参照を作る
+    pub fn new(target: StableId) -> Self {
+        Link {
+            target,
+            entity: None,
+        }
+    }
+
+    /// 解決済みの参照先 (参照先が存在しない場合は `None`)
+    pub fn entity(&self) -> Option<Entity> {
+        self.entity
+    }
+}
+
+/// すべての [`Link`] の参照先を登録表から解決し直す (フィックスアップ)
+///
+/// シーンやセーブデータからエンティティを生成した直後は参照先がまだ spawn されていないことがあるため、
+/// 生成のコマンドが適用された後の `PostUpdate` で毎フレーム解決する。
+/// 解決結果が変わったリンクだけを書き換えるので、変更検知 (`Changed<Link>`) も使える。
+fn resolve_links(mut links: Query<(Entity, &mut Link)>, registry: Res<StableIdRegistry>) {
+    for (entity, mut link) in &mut links {
+        let resolved = registry.get(link.target);
+        if link.entity == resolved {
+            continue;
+        }
+        match resolved {
+            Some(target) => debug!("{entity}: link to {:?} -> {target}", link.target),
+            None => warn!("{entity}: link target {:?} is missing", link.target),
+        }
+        link.entity = resolved;
+    }
+}
@@ -0,0 +1,404 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use settings::{Profile, SettingsPlugin};
+
+mod world_state;
+
+use world_state::{LevelId, ObjectId, ObjectKind, WorldState, WorldStatePlugin};
+
+// プレイヤーの大きさと移動速度
+const PLAYER_SIZE: Vec2 = Vec2::new(28.0, 28.0);
+const PLAYER_SPEED: f32 = 250.0;
+// オブジェクト 1 つ分の大きさ
+const TILE_SIZE: f32 = 40.0;
+// 扉・ブロックを調べられる距離
+const INTERACT_DISTANCE: f32 = TILE_SIZE * 1.2;
+
+// 色
+const PLAYER_COLOR: Color = Color::srgb(0.3, 0.7, 1.0);
+const COIN_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+const DOOR_CLOSED_COLOR: Color = Color::srgb(0.6, 0.35, 0.15);
+const DOOR_OPEN_COLOR: Color = Color::srgba(0.6, 0.35, 0.15, 0.25);
+const BRICK_COLOR: Color = Color::srgb(0.7, 0.3, 0.3);
+
+// 切り替えられるプロファイル名
+const PROFILES: [&str; 2] = ["default", "second"];
+
+/// レベル配置データの 1 要素
+struct ObjectSpec {
+    id: u32, // レベル内で一意な ID (セーブデータのキーになるので変更しない)
+    kind: ObjectKind,
+    tile: (i32, i32),
+}
+
+const fn spec(id: u32, kind: ObjectKind, x: i32, y: i32) -> ObjectSpec {
+    ObjectSpec {
+        id,
+        kind,
+        tile: (x, y),
+    }
+}
+
+// 草原: コインと、扉の奥のコイン
+const MEADOW: &[ObjectSpec] = &[
+    spec(1, ObjectKind::Coin, -4, 2),
+    spec(2, ObjectKind::Coin, -2, -3),
+    spec(3, ObjectKind::Coin, 3, 3),
+    spec(10, ObjectKind::Door, 5, 0),
+    spec(11, ObjectKind::Brick, 5, 1),
+    spec(12, ObjectKind::Brick, 5, -1),
+    spec(4, ObjectKind::Coin, 7, 0),
+];
+
+// 洞窟: ブロックの壁に囲まれたコイン
+const CAVE: &[ObjectSpec] = &[
+    spec(1, ObjectKind::Coin, 0, 0),
+    spec(10, ObjectKind::Brick, -1, 1),
+    spec(11, ObjectKind::Brick, 0, 1),
+    spec(12, ObjectKind::Brick, 1, 1),
+    spec(13, ObjectKind::Brick, -1, 0),
+    spec(14, ObjectKind::Brick, 1, 0),
+    spec(15, ObjectKind::Brick, -1, -1),
+    spec(16, ObjectKind::Door, 0, -1),
+    spec(17, ObjectKind::Brick, 1, -1),
+    spec(2, ObjectKind::Coin, -5, 3),
+    spec(3, ObjectKind::Coin, 5, -3),
+];
+
+impl LevelId {
+    fn objects(self) -> &'static [ObjectSpec] {
+        match self {
+            LevelId::Meadow => MEADOW,
+            LevelId::Cave => CAVE,
+        }
+    }
+
+    fn background(self) -> Color {
+        match self {
+            LevelId::Meadow => Color::srgb(0.15, 0.3, 0.15),
+            LevelId::Cave => Color::srgb(0.12, 0.1, 0.15),
+        }
+    }
+
+    fn player_start(self) -> Vec2 {
+        match self {
+            LevelId::Meadow => Vec2::new(-6.0, 0.0) * TILE_SIZE,
+            LevelId::Cave => Vec2::new(0.0, -4.0) * TILE_SIZE,
+        }
+    }
+}
+
+/// ゲームの状態
+///
+/// レベルを読み込み直すときは必ず `Loading` を経由させ、
+/// 前のレベルの `StateScoped` エンティティを確実に破棄する。
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum GameState {
+    #[default]
+    Loading,
+    Playing(LevelId),
+}
+
+/// `Loading` の次に入るレベル
+#[derive(Resource, Deref, DerefMut)]
+struct PendingLevel(LevelId);
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins((DefaultPlugins, SettingsPlugin, WorldStatePlugin))
+        .init_state::<GameState>()
+        .enable_state_scoped_entities::<GameState>()
+        .insert_resource(PendingLevel(LevelId::Meadow))
+        .add_systems(Startup, setup)
+        .add_systems(OnEnter(GameState::Loading), enter_pending_level)
+        .add_systems(
+            Update,
+            (
+                move_player,
+                collect_coins,
+                interact,
+                change_level,
+                switch_profile,
+                update_status,
+            )
+                .chain()
+                .run_if(in_state_playing),
+        );
+    for level in [LevelId::Meadow, LevelId::Cave] {
+        app.add_systems(OnEnter(GameState::Playing(level)), spawn_level);
+    }
+    app.run();
+}
+
+/// プレイヤーを示すコンポーネント
+#[derive(Component)]
+struct Player;
+
+/// プレイヤーが通り抜けられないオブジェクトを示すコンポーネント
+#[derive(Component)]
+struct Solid;
+
+/// 状態を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct StatusText;
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+}
+
+/// いずれかのレベルをプレイ中かどうかを判定する条件関数
+fn in_state_playing(state: Res<State<GameState>>) -> bool {
+    matches!(state.get(), GameState::Playing(_))
+}
+
+/// 読み込み待ちのレベルに遷移する
+fn enter_pending_level(pending: Res<PendingLevel>, mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Playing(**pending));
+}
+
+/// レベルのオブジェクトを配置する
+///
+/// [`WorldState`] で消費済みのコイン・ブロックは配置せず、開けた扉は開いた状態で配置する。
+fn spawn_level(
+    mut commands: Commands,
+    state: Res<State<GameState>>,
+    world_state: Res<WorldState>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    let GameState::Playing(level) = *state.get() else {
+        return;
+    };
+    let scoped = StateScoped(GameState::Playing(level));
+    clear_color.0 = level.background();
+
+    commands.spawn((
+        Sprite::from_color(PLAYER_COLOR, PLAYER_SIZE),
+        Transform::from_translation(level.player_start().extend(1.0)),
+        Player,
+        scoped.clone(),
+    ));
+
+    for spec in level.objects() {
+        let object = ObjectId { level, id: spec.id };
+        let consumed = world_state.is_consumed(spec.kind, object);
+        let position = Vec2::new(spec.tile.0 as f32, spec.tile.1 as f32) * TILE_SIZE;
+        let transform = Transform::from_translation(position.extend(0.0));
+
+        match spec.kind {
+            ObjectKind::Coin | ObjectKind::Brick if consumed => {}
+            ObjectKind::Coin => {
+                commands.spawn((
+                    Sprite::from_color(COIN_COLOR, Vec2::splat(TILE_SIZE * 0.4)),
+                    transform,
+                    object,
+                    spec.kind,
+                    scoped.clone(),
+                ));
+            }
+            ObjectKind::Brick => {
+                commands.spawn((
+                    Sprite::from_color(BRICK_COLOR, Vec2::splat(TILE_SIZE - 2.0)),
+                    transform,
+                    object,
+                    spec.kind,
+                    Solid,
+                    scoped.clone(),
+                ));
+            }
+            ObjectKind::Door => {
+                let color = if consumed {
+                    DOOR_OPEN_COLOR
+                } else {
+                    DOOR_CLOSED_COLOR
+                };
+                let mut door = commands.spawn((
+                    Sprite::from_color(color, Vec2::splat(TILE_SIZE - 2.0)),
+                    transform,
+                    object,
+                    spec.kind,
+                    scoped.clone(),
+                ));
+                if !consumed {
+                    door.insert(Solid);
+                }
+            }
+        }
+    }
+}
+
+/// 矢印キーでプレイヤーを動かす (通り抜けられないオブジェクトには軸ごとに止められる)
+fn move_player(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut player: Single<&mut Transform, With<Player>>,
+    solids: Query<&Transform, (With<Solid>, Without<Player>)>,
+    time: Res<Time>,
+) {
+    let mut direction = Vec2::ZERO;
+    if keyboard_input.pressed(KeyCode::ArrowLeft) {
+        direction.x -= 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::ArrowRight) {
+        direction.x += 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::ArrowUp) {
+        direction.y += 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::ArrowDown) {
+        direction.y -= 1.0;
+    }
+    let delta = direction.normalize_or_zero() * PLAYER_SPEED * time.delta_secs();
+
+    for axis in [Vec2::X, Vec2::Y] {
+        let candidate = player.translation.truncate() + delta * axis;
+        let player_box = Aabb2d::new(candidate, PLAYER_SIZE / 2.0);
+        let blocked = solids.iter().any(|solid| {
+            player_box.intersects(&Aabb2d::new(
+                solid.translation.truncate(),
+                Vec2::splat(TILE_SIZE / 2.0),
+            ))
+        });
+        if !blocked {
+            player.translation = candidate.extend(player.translation.z);
+        }
+    }
+}
+
+/// プレイヤーが触れたコインを拾い、ワールドの状態に記録する
+fn collect_coins(
+    mut commands: Commands,
+    player: Single<&Transform, With<Player>>,
+    objects: Query<(Entity, &Transform, &ObjectId, &ObjectKind)>,
+    mut world_state: ResMut<WorldState>,
+) {
+    let player_box = Aabb2d::new(player.translation.truncate(), PLAYER_SIZE / 2.0);
+    for (entity, transform, object, kind) in &objects {
+        if *kind != ObjectKind::Coin {
+            continue;
+        }
+        let coin_box = Aabb2d::new(
+            transform.translation.truncate(),
+            Vec2::splat(TILE_SIZE * 0.2),
+        );
+        if player_box.intersects(&coin_box) {
+            commands.entity(entity).despawn();
+            world_state.consume(ObjectKind::Coin, *object);
+        }
+    }
+}
+
+/// スペースキーで一番近い扉を開ける・ブロックを壊す
+#[allow(clippy::type_complexity)]
+fn interact(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player: Single<&Transform, With<Player>>,
+    mut objects: Query<(Entity, &Transform, &ObjectId, &ObjectKind, &mut Sprite), With<Solid>>,
+    mut world_state: ResMut<WorldState>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let player_position = player.translation.truncate();
+    let nearest = objects
+        .iter_mut()
+        .map(|item| {
+            let distance = item.1.translation.truncate().distance(player_position);
+            (distance, item)
+        })
+        .filter(|(distance, _)| *distance <= INTERACT_DISTANCE)
+        .min_by(|(a, _), (b, _)| a.total_cmp(b));
+    let Some((_, (entity, _, object, kind, mut sprite))) = nearest else {
+        return;
+    };
+
+    match kind {
+        ObjectKind::Door => {
+            sprite.color = DOOR_OPEN_COLOR;
+            commands.entity(entity).remove::<Solid>();
+        }
+        ObjectKind::Brick => commands.entity(entity).despawn(),
+        ObjectKind::Coin => return,
+    }
+    world_state.consume(*kind, *object);
+}
+
+/// 数字キーでレベルを移動し、R キーで現在のレベルを読み込み直す
+///
+/// Backspace キーで現在のプロファイルの記録を消してから読み込み直す。
+fn change_level(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut pending: ResMut<PendingLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut world_state: ResMut<WorldState>,
+) {
+    let GameState::Playing(current) = *state.get() else {
+        return;
+    };
+    let target = if keyboard_input.just_pressed(KeyCode::Digit1) {
+        LevelId::Meadow
+    } else if keyboard_input.just_pressed(KeyCode::Digit2) {
+        LevelId::Cave
+    } else if keyboard_input.just_pressed(KeyCode::KeyR) {
+        current
+    } else if keyboard_input.just_pressed(KeyCode::Backspace) {
+        *world_state = WorldState::default();
+        current
+    } else {
+        return;
+    };
+
+    **pending = target;
+    next_state.set(GameState::Loading);
+}
+
+/// P キーでプロファイルを切り替え、そのプロファイルの状態でレベルを読み込み直す
+fn switch_profile(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut profile: ResMut<Profile>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    let index = PROFILES
+        .iter()
+        .position(|name| *name == profile.name())
+        .map_or(0, |index| (index + 1) % PROFILES.len());
+    *profile = Profile::new(PROFILES[index]);
+    // 新しいプロファイルの状態はこのフレームの最後に読み込まれるため、
+    // レベルの再配置は次のフレームの状態遷移で行われる
+    next_state.set(GameState::Loading);
+}
+
+/// 現在のプロファイル・レベル・記録の内容を表示する
+fn update_status(
+    mut status: Single<&mut Text, With<StatusText>>,
+    state: Res<State<GameState>>,
+    profile: Res<Profile>,
+    world_state: Res<WorldState>,
+) {
+    let GameState::Playing(level) = *state.get() else {
+        return;
+    };
+    status.0 = format!(
+        "Profile: {}\nLevel: {:?}\nConsumed in this level: {}\n\n\
+         Arrows: move, Space: open door / break brick\n\
+         1/2: travel, R: reload, Backspace: reset, P: switch profile",
+        profile.name(),
+        level,
+        world_state.consumed_count(level),
+    );
+}
@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use settings::Profile;
+
+/// ワールドの状態を保存するファイル名 (プロファイルのディレクトリ内)
+const WORLD_STATE_FILE: &str = "world_state.ron";
+
+/// [`WorldState`] をプロファイルから読み込み、変更されたら保存するプラグイン
+///
+/// [`Profile`] が切り替わると、新しいプロファイルの状態を読み込み直す。
+pub struct WorldStatePlugin;
+
+impl Plugin for WorldStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profile>()
+            .init_resource::<WorldState>()
+            .add_systems(PreStartup, load_world_state)
+            .add_systems(
+                Last,
+                (
+                    load_world_state.run_if(resource_changed::<Profile>),
+                    save_world_state,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// レベルの識別子 (セーブデータのキーになるため、名前を変えると以前のデータが読めなくなる)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LevelId {
+    Meadow,
+    Cave,
+}
+
+/// レベル内に配置されるオブジェクトの種類
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Coin,  // 触れると拾う
+    Door,  // 調べると開く
+    Brick, // 調べると壊れる
+}
+
+/// レベル内のオブジェクトを表す安定した ID
+///
+/// エンティティ ID は読み込むたびに変わるため、セーブデータでは
+/// レベル配置データに書かれたこの ID でオブジェクトを特定する。
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectId {
+    pub level: LevelId,
+    pub id: u32,
+}
+
+/// 1 つのレベルで消費されたオブジェクトの記録
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct LevelFlags {
+    pub collected: BTreeSet<u32>, // 拾ったコイン
+    pub opened: BTreeSet<u32>,    // 開けた扉
+    pub destroyed: BTreeSet<u32>, // 壊したブロック
+}
+
+impl LevelFlags {
+    fn ids(&self, kind: ObjectKind) -> &BTreeSet<u32> {
+        match kind {
+            ObjectKind::Coin => &self.collected,
+            ObjectKind::Door => &self.opened,
+            ObjectKind::Brick => &self.destroyed,
+        }
+    }
+
+    fn ids_mut(&mut self, kind: ObjectKind) -> &mut BTreeSet<u32> {
+        match kind {
+            ObjectKind::Coin => &mut self.collected,
+            ObjectKind::Door => &mut self.opened,
+            ObjectKind::Brick => &mut self.destroyed,
+        }
+    }
+}
+
+/// レベルをまたいで保持されるワールドの状態
+///
+/// レベルを読み込み直してもこの記録に従ってオブジェクトを配置するため、
+/// 一度拾ったコインや壊したブロックは復活しない。
+#[derive(Resource, Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct WorldState {
+    pub levels: BTreeMap<LevelId, LevelFlags>,
+}
+
+impl WorldState {
+    /// オブジェクトが消費済み (拾った・開けた・壊した) かどうか
+    pub fn is_consumed(&self, kind: ObjectKind, object: ObjectId) -> bool {
+        self.levels
+            .get(&object.level)
+            .is_some_and(|flags| flags.ids(kind).contains(&object.id))
+    }
+
+    /// オブジェクトを消費済みとして記録する
+    pub fn consume(&mut self, kind: ObjectKind, object: ObjectId) {
+        self.levels
+            .entry(object.level)
+            .or_default()
+            .ids_mut(kind)
+            .insert(object.id);
+    }
+
+    /// 指定したレベルで消費されたオブジェクトの数
+    pub fn consumed_count(&self, level: LevelId) -> usize {
+        self.levels.get(&level).map_or(0, |flags| {
+            flags.collected.len() + flags.opened.len() + flags.destroyed.len()
+        })
+    }
+}
+
+/// 現在のプロファイルからワールドの状態を読み込む
+fn load_world_state(mut commands: Commands, profile: Res<Profile>) {
+    let world_state: WorldState = profile.load(WORLD_STATE_FILE);
+    info!(
+        "loaded world state for profile '{}': {:?}",
+        profile.name(),
+        world_state
+    );
+    commands.insert_resource(world_state);
+}
+
+/// ワールドの状態が変更されたフレームの最後にプロファイルへ保存する
+fn save_world_state(world_state: Res<WorldState>, profile: Res<Profile>) {
+    if world_state.is_changed() && !world_state.is_added() {
+        profile.save(WORLD_STATE_FILE, &*world_state);
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::{color::palettes::css::YELLOW, input::mouse::AccumulatedMouseScroll, prelude::*};
+
+use crate::{
+    body_radius, collect_particles, next_color, simulation, spawn_body, Body, ColorIndex, Settings,
+    Sun, PREDICT_DT, PREDICT_SECONDS,
+};
+
+// ドラッグした長さ (ピクセル) を初速 (ピクセル/秒) にする倍率
+const LAUNCH_SPEED_SCALE: f32 = 1.5;
+// 追加する天体の質量の初期値と範囲、ホイール 1 目盛りで掛ける倍率
+const DEFAULT_MASS: f32 = 5.0;
+const MASS_RANGE: (f32, f32) = (0.1, 500.0);
+const MASS_STEP: f32 = 1.5;
+
+/// ドラッグで初速を決めて天体を追加する操作を追加するプラグイン
+///
+/// 左ボタンを押した位置に天体を置き、離した位置へ向かう矢印の向きと長さを初速にする。
+/// ドラッグしている間は、追加する天体がほかの天体の引力を受けて進む軌道の予測を描く。
+pub struct LaunchPlugin;
+
+impl Plugin for LaunchPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(NewBodyMass(DEFAULT_MASS))
+            .init_resource::<Launch>()
+            .add_systems(Update, (change_mass, drag_launch).chain());
+    }
+}
+
+/// 次に追加する天体の質量
+#[derive(Resource, Deref, DerefMut)]
+pub struct NewBodyMass(f32);
+
+/// ドラッグを始めた位置 (ドラッグしていないときは `None`)
+#[derive(Resource, Default)]
+struct Launch(Option<Vec2>);
+
+/// カーソルの位置をワールド座標で返す
+pub fn cursor_position(window: &Window, camera: (&Camera, &GlobalTransform)) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    camera.0.viewport_to_world_2d(camera.1, cursor).ok()
+}
+
+/// マウスホイールで追加する天体の質量を変える (1 目盛りごとに `MASS_STEP` 倍)
+fn change_mass(scroll: Res<AccumulatedMouseScroll>, mut mass: ResMut<NewBodyMass>) {
+    if scroll.delta.y != 0.0 {
+        **mass =
+            (**mass * MASS_STEP.powf(scroll.delta.y.signum())).clamp(MASS_RANGE.0, MASS_RANGE.1);
+    }
+}
+
+/// 左ボタンのドラッグで初速を決め、離したときに天体を追加する
+#[allow(clippy::too_many_arguments)]
+fn drag_launch(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mass: Res<NewBodyMass>,
+    settings: Res<Settings>,
+    bodies: Query<(Entity, &Transform, &Body, Has<Sun>)>,
+    mut launch: ResMut<Launch>,
+    mut color_index: ResMut<ColorIndex>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut gizmos: Gizmos,
+) {
+    let Some(cursor) = cursor_position(&window, *camera) else {
+        return;
+    };
+    if mouse_input.just_pressed(MouseButton::Left) {
+        launch.0 = Some(cursor);
+    }
+    let Some(start) = launch.0 else {
+        return;
+    };
+    let velocity = (cursor - start) * LAUNCH_SPEED_SCALE;
+
+    if mouse_input.just_released(MouseButton::Left) {
+        let color = next_color(&mut color_index);
+        spawn_body(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            color,
+            start,
+            velocity,
+            **mass,
+        );
+        launch.0 = None;
+        return;
+    }
+
+    // 追加する天体と初速の矢印
+    gizmos.circle_2d(start, body_radius(**mass), YELLOW);
+    gizmos.arrow_2d(start, cursor, YELLOW);
+
+    // 今ある天体に追加する天体を加えて予測し、追加する天体の軌道だけを描く
+    let (_, mut particles) = collect_particles(&bodies, settings.sun_fixed);
+    particles.push(simulation::Particle {
+        position: start,
+        velocity,
+        mass: **mass,
+        fixed: false,
+    });
+    let steps = (PREDICT_SECONDS / PREDICT_DT) as usize;
+    if let Some(path) = simulation::predict(&particles, steps, PREDICT_DT).pop() {
+        gizmos.linestrip_2d(path, YELLOW.with_alpha(0.7));
+    }
+}
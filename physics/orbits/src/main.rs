@@ -0,0 +1,448 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    color::palettes::css::{GOLD, LIGHT_SKY_BLUE, ORANGE_RED, PALE_GREEN, PLUM, SANDY_BROWN},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+mod launch;
+mod simulation;
+
+use simulation::Particle;
+
+// 中心の天体 (恒星) の質量と色
+const SUN_MASS: f32 = 1000.0;
+const SUN_COLOR: Srgba = GOLD;
+// 最初に並べる惑星の (中心からの距離, 質量)
+const PLANETS: [(f32, f32); 4] = [(110.0, 4.0), (180.0, 10.0), (270.0, 6.0), (360.0, 20.0)];
+// 衛星を回す惑星 (`PLANETS` の添字) と、衛星の (惑星からの距離, 質量)。
+// 恒星の引力で引きはがされないように、惑星の重力が勝つ範囲 (ヒル球) の内側を回らせる
+const MOON_HOST: usize = 3;
+const MOON: (f32, f32) = (24.0, 0.2);
+// 天体の半径 (質量の立方根に掛ける値)
+const RADIUS_SCALE: f32 = 3.0;
+// 惑星の色 (追加した順に使い回す)
+const PALETTE: [Srgba; 5] = [LIGHT_SKY_BLUE, PALE_GREEN, SANDY_BROWN, PLUM, ORANGE_RED];
+// シミュレーションの 1 ステップの長さ (秒)
+const TIMESTEP_HZ: f64 = 120.0;
+// 選べる時間の速さ (倍率) と最初に使う速さ (`TIME_SCALES` の添字)
+const TIME_SCALES: [f32; 7] = [0.125, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+const DEFAULT_TIME_SCALE: usize = 3;
+// 軌道を予測する時間 (秒) と、予測の 1 ステップの長さ (秒)
+const PREDICT_SECONDS: f32 = 4.0;
+const PREDICT_DT: f32 = 1.0 / 60.0;
+// 軌跡に残す位置の数
+const TRAIL_LENGTH: usize = 240;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(launch::LaunchPlugin)
+        .insert_resource(ClearColor(Color::srgb(0.02, 0.02, 0.05)))
+        .insert_resource(Time::<Fixed>::from_hz(TIMESTEP_HZ))
+        .insert_resource(Settings {
+            sun_fixed: true,
+            predict: true,
+            trails: true,
+            time_scale: DEFAULT_TIME_SCALE,
+        })
+        .init_resource::<ColorIndex>()
+        .add_systems(Startup, (setup, spawn_system).chain())
+        .add_systems(
+            FixedUpdate,
+            (integrate, merge_collisions, record_trails).chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                (
+                    change_time_scale,
+                    toggle_pause.run_if(input_just_pressed(KeyCode::Space)),
+                    toggle_sun_fixed.run_if(input_just_pressed(KeyCode::KeyF)),
+                    toggle_prediction.run_if(input_just_pressed(KeyCode::KeyP)),
+                    toggle_trails.run_if(input_just_pressed(KeyCode::KeyT)),
+                    reset_system.run_if(input_just_pressed(KeyCode::KeyR)),
+                    remove_body.run_if(input_just_pressed(MouseButton::Right)),
+                ),
+                (draw_trails, draw_predictions, update_info),
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// 表示とシミュレーションの設定
+#[derive(Resource)]
+struct Settings {
+    sun_fixed: bool,   // 恒星を中心に固定するかどうか
+    predict: bool,     // 軌道の予測線を描くかどうか
+    trails: bool,      // 軌跡を描くかどうか
+    time_scale: usize, // `TIME_SCALES` の添字
+}
+
+/// 次に追加する天体に使う色 (`PALETTE` の添字)
+#[derive(Resource, Default)]
+struct ColorIndex(usize);
+
+/// 万有引力で動く天体
+#[derive(Component)]
+struct Body {
+    velocity: Vec2,
+    mass: f32,
+}
+
+/// 中心の恒星
+#[derive(Component)]
+struct Sun;
+
+/// 天体が通った位置 (古いものから順に並ぶ)
+#[derive(Component, Default)]
+struct Trail(VecDeque<Vec2>);
+
+/// 操作方法と状態を表示するテキスト
+#[derive(Component)]
+struct InfoText;
+
+/// 質量から天体の半径を求める (密度が同じなら半径は質量の立方根に比例する)
+fn body_radius(mass: f32) -> f32 {
+    RADIUS_SCALE * mass.cbrt()
+}
+
+/// 天体を追加する
+fn spawn_body(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    color: Color,
+    position: Vec2,
+    velocity: Vec2,
+    mass: f32,
+) -> Entity {
+    commands
+        .spawn((
+            Mesh2d(meshes.add(Circle::new(1.0))),
+            MeshMaterial2d(materials.add(color)),
+            Transform::from_translation(position.extend(0.0))
+                .with_scale(Vec3::splat(body_radius(mass))),
+            Body { velocity, mass },
+            Trail::default(),
+        ))
+        .id()
+}
+
+/// 天体の状態をシミュレーション用の値にして、エンティティと同じ順に並べる
+fn collect_particles<'a>(
+    bodies: impl IntoIterator<Item = (Entity, &'a Transform, &'a Body, bool)>,
+    sun_fixed: bool,
+) -> (Vec<Entity>, Vec<Particle>) {
+    bodies
+        .into_iter()
+        .map(|(entity, transform, body, is_sun)| {
+            let particle = Particle {
+                position: transform.translation.truncate(),
+                velocity: body.velocity,
+                mass: body.mass,
+                fixed: is_sun && sun_fixed,
+            };
+            (entity, particle)
+        })
+        .unzip()
+}
+
+/// カメラと説明のテキストを配置する
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        InfoText,
+    ));
+}
+
+/// 恒星と、その周りを円軌道で回る惑星と衛星を配置する
+fn spawn_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut color_index: ResMut<ColorIndex>,
+) {
+    let sun = spawn_body(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        SUN_COLOR.into(),
+        Vec2::ZERO,
+        Vec2::ZERO,
+        SUN_MASS,
+    );
+    commands.entity(sun).insert(Sun);
+
+    for (i, &(distance, mass)) in PLANETS.iter().enumerate() {
+        // 惑星ごとに出発する角度をずらす (黄金角)
+        let direction = Vec2::from_angle(i as f32 * 2.399_963);
+        let position = direction * distance;
+        let velocity = direction.perp() * simulation::circular_speed(SUN_MASS, distance);
+        let color = next_color(&mut color_index);
+        spawn_body(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            color,
+            position,
+            velocity,
+            mass,
+        );
+
+        // 惑星の速度に、惑星の周りを回る速度を足して衛星を回す
+        if i == MOON_HOST {
+            let (moon_distance, moon_mass) = MOON;
+            let moon_velocity =
+                velocity + direction.perp() * simulation::circular_speed(mass, moon_distance);
+            let color = next_color(&mut color_index);
+            spawn_body(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                color,
+                position + direction * moon_distance,
+                moon_velocity,
+                moon_mass,
+            );
+        }
+    }
+}
+
+/// `PALETTE` から次の色を選ぶ
+fn next_color(color_index: &mut ColorIndex) -> Color {
+    let color = PALETTE[color_index.0 % PALETTE.len()];
+    color_index.0 += 1;
+    color.into()
+}
+
+/// 全天体を半陰的オイラー法で 1 ステップ進める
+fn integrate(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut bodies: Query<(Entity, &mut Transform, &mut Body, Has<Sun>)>,
+) {
+    let (entities, mut particles) = collect_particles(bodies.iter(), settings.sun_fixed);
+    simulation::step(&mut particles, time.delta_secs());
+    for (entity, particle) in entities.into_iter().zip(particles) {
+        if let Ok((_, mut transform, mut body, _)) = bodies.get_mut(entity) {
+            transform.translation = particle.position.extend(transform.translation.z);
+            body.velocity = particle.velocity;
+        }
+    }
+}
+
+/// 重なった天体を 1 つにまとめる
+///
+/// 運動量と質量の和が変わらないように、質量で重み付けした平均の位置と速度にし、小さいほうを消す。
+/// 恒星が固定されているときは恒星の位置を動かさない。
+fn merge_collisions(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut bodies: Query<(Entity, &mut Transform, &mut Body, Has<Sun>)>,
+) {
+    let snapshot: Vec<(Entity, Vec2, f32, bool)> = bodies
+        .iter()
+        .map(|(entity, transform, body, is_sun)| {
+            (entity, transform.translation.truncate(), body.mass, is_sun)
+        })
+        .collect();
+    let mut removed = Vec::new();
+    for (i, &(a, a_position, a_mass, a_sun)) in snapshot.iter().enumerate() {
+        for &(b, b_position, b_mass, b_sun) in &snapshot[i + 1..] {
+            if removed.contains(&a) || removed.contains(&b) {
+                continue;
+            }
+            if a_position.distance(b_position) > body_radius(a_mass) + body_radius(b_mass) {
+                continue;
+            }
+            // 恒星か、質量の大きいほうが残る
+            let (keep, lose) = if a_sun || (!b_sun && a_mass >= b_mass) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            let Ok(
+                [(_, mut keep_transform, mut keep_body, keep_sun), (_, lose_transform, lose_body, _)],
+            ) = bodies.get_many_mut([keep, lose])
+            else {
+                continue;
+            };
+            let mass = keep_body.mass + lose_body.mass;
+            keep_body.velocity =
+                (keep_body.velocity * keep_body.mass + lose_body.velocity * lose_body.mass) / mass;
+            if !(keep_sun && settings.sun_fixed) {
+                let position = (keep_transform.translation * keep_body.mass
+                    + lose_transform.translation * lose_body.mass)
+                    / mass;
+                keep_transform.translation = position;
+            }
+            keep_body.mass = mass;
+            keep_transform.scale = Vec3::splat(body_radius(mass));
+            commands.entity(lose).despawn();
+            removed.push(lose);
+        }
+    }
+}
+
+/// 天体の位置を軌跡に追加する (古い位置から捨てる)
+fn record_trails(settings: Res<Settings>, mut trails: Query<(&Transform, &mut Trail)>) {
+    if !settings.trails {
+        return;
+    }
+    for (transform, mut trail) in &mut trails {
+        if trail.0.len() >= TRAIL_LENGTH {
+            trail.0.pop_front();
+        }
+        trail.0.push_back(transform.translation.truncate());
+    }
+}
+
+/// `-` / `=` キーで時間の速さを変える (`Time<Virtual>` の速さを変えると固定タイムステップの実行回数も変わる)
+fn change_time_scale(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        settings.time_scale = settings.time_scale.saturating_sub(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::Equal) {
+        settings.time_scale = (settings.time_scale + 1).min(TIME_SCALES.len() - 1);
+    }
+    if settings.is_changed() {
+        time.set_relative_speed(TIME_SCALES[settings.time_scale]);
+    }
+}
+
+/// `Space` キーでシミュレーションを一時停止・再開する
+fn toggle_pause(mut time: ResMut<Time<Virtual>>) {
+    if time.is_paused() {
+        time.unpause();
+    } else {
+        time.pause();
+    }
+}
+
+/// `F` キーで恒星を中心に固定するかどうかを切り替える
+fn toggle_sun_fixed(mut settings: ResMut<Settings>) {
+    settings.sun_fixed = !settings.sun_fixed;
+}
+
+/// `P` キーで軌道の予測線の表示を切り替える
+fn toggle_prediction(mut settings: ResMut<Settings>) {
+    settings.predict = !settings.predict;
+}
+
+/// `T` キーで軌跡の表示を切り替える (切り替えるときにそれまでの軌跡は捨てる)
+fn toggle_trails(mut settings: ResMut<Settings>, mut trails: Query<&mut Trail>) {
+    settings.trails = !settings.trails;
+    for mut trail in &mut trails {
+        trail.0.clear();
+    }
+}
+
+/// `R` キーで最初の配置に戻す
+fn reset_system(
+    mut commands: Commands,
+    bodies: Query<Entity, With<Body>>,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<ColorMaterial>>,
+    mut color_index: ResMut<ColorIndex>,
+) {
+    for entity in &bodies {
+        commands.entity(entity).despawn();
+    }
+    color_index.0 = 0;
+    spawn_system(commands, meshes, materials, color_index);
+}
+
+/// 右クリックでカーソルの下の天体を消す
+fn remove_body(
+    mut commands: Commands,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    bodies: Query<(Entity, &Transform), With<Body>>,
+) {
+    let Some(cursor) = launch::cursor_position(&window, *camera) else {
+        return;
+    };
+    let hit = bodies.iter().find(|(_, transform)| {
+        // 小さい天体もクリックしやすいように、少し大きめに判定する
+        transform.translation.truncate().distance(cursor) <= transform.scale.x.max(8.0)
+    });
+    if let Some((entity, _)) = hit {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// 軌跡を、古い位置ほど薄くなる線で描く
+fn draw_trails(settings: Res<Settings>, trails: Query<&Trail>, mut gizmos: Gizmos) {
+    if !settings.trails {
+        return;
+    }
+    for trail in &trails {
+        let count = trail.0.len() as f32;
+        gizmos.linestrip_gradient_2d(trail.0.iter().enumerate().map(|(i, &position)| {
+            let alpha = (i as f32 + 1.0) / count;
+            (position, Color::srgba(0.8, 0.8, 1.0, alpha * 0.6))
+        }));
+    }
+}
+
+/// 現在の状態から `PREDICT_SECONDS` 秒先までの軌道を予測して描く
+///
+/// 予測では衝突による合体を考えないので、ぶつかる天体の線は重なったまま進む。
+fn draw_predictions(
+    settings: Res<Settings>,
+    bodies: Query<(Entity, &Transform, &Body, Has<Sun>)>,
+    mut gizmos: Gizmos,
+) {
+    if !settings.predict {
+        return;
+    }
+    let (_, particles) = collect_particles(&bodies, settings.sun_fixed);
+    let steps = (PREDICT_SECONDS / PREDICT_DT) as usize;
+    for path in simulation::predict(&particles, steps, PREDICT_DT) {
+        gizmos.linestrip_2d(path, Color::srgba(1.0, 1.0, 1.0, 0.25));
+    }
+}
+
+/// 操作方法と、時間の速さ・天体の数・全エネルギーを表示する
+fn update_info(
+    settings: Res<Settings>,
+    time: Res<Time<Virtual>>,
+    bodies: Query<(Entity, &Transform, &Body, Has<Sun>)>,
+    new_mass: Res<launch::NewBodyMass>,
+    mut text: Single<&mut Text, With<InfoText>>,
+) {
+    let (_, particles) = collect_particles(&bodies, settings.sun_fixed);
+    let speed = if time.is_paused() {
+        "paused".to_string()
+    } else {
+        format!("x{}", TIME_SCALES[settings.time_scale])
+    };
+    text.0 = format!(
+        "Drag: launch a body / Wheel: its mass / Right click: remove / Space: pause / -/=: speed\n\
+         F: fix sun ({}) / P: prediction / T: trails / R: reset\n\
+         Speed: {}  Bodies: {}  New mass: {}  Energy: {:.0}",
+        if settings.sun_fixed { "on" } else { "off" },
+        speed,
+        particles.len(),
+        **new_mass,
+        simulation::total_energy(&particles),
+    );
+}
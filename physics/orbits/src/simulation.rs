@@ -0,0 +1,95 @@
+//! 天体どうしの万有引力の計算と、半陰的オイラー法による時間発展 (ECS に依存しない部分)
+
+use bevy::math::Vec2;
+
+// 万有引力定数 (画面のピクセルと秒に合わせた値)
+pub const GRAVITY: f32 = 5000.0;
+// 距離が 0 に近いときに引力が発散しないように、距離の 2 乗に足す値 (ソフトニング)
+const SOFTENING: f32 = 25.0;
+
+/// 1 つの天体の状態
+#[derive(Clone, Copy, Debug)]
+pub struct Particle {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub mass: f32,
+    pub fixed: bool, // 固定された天体は動かない (ほかの天体は引き寄せる)
+}
+
+/// すべての天体について、ほかのすべての天体から受ける引力による加速度を求める
+///
+/// 天体 i が天体 j から受ける加速度は `G m_j (r_j - r_i) / (|r_j - r_i|² + ε²)^(3/2)`。
+/// 作用・反作用の対になる 2 つを同時に足して、組み合わせごとに 1 回だけ計算する。
+pub fn accelerations(particles: &[Particle]) -> Vec<Vec2> {
+    let mut accelerations = vec![Vec2::ZERO; particles.len()];
+    for i in 0..particles.len() {
+        for j in i + 1..particles.len() {
+            let offset = particles[j].position - particles[i].position;
+            let distance_squared = offset.length_squared() + SOFTENING;
+            let pull = GRAVITY * offset / (distance_squared * distance_squared.sqrt());
+            accelerations[i] += pull * particles[j].mass;
+            accelerations[j] -= pull * particles[i].mass;
+        }
+    }
+    accelerations
+}
+
+/// 半陰的オイラー法 (シンプレクティック・オイラー法) で `dt` 秒進める
+///
+/// 先に速度を更新し、更新した速度で位置を進める。位置を先に進める (陽的) オイラー法と違い、
+/// 周回軌道のエネルギーが時間とともに増え続けることがなく、軌道が外へ広がっていかない。
+pub fn step(particles: &mut [Particle], dt: f32) {
+    let accelerations = accelerations(particles);
+    for (particle, acceleration) in particles.iter_mut().zip(accelerations) {
+        if particle.fixed {
+            particle.velocity = Vec2::ZERO;
+            continue;
+        }
+        particle.velocity += acceleration * dt;
+        particle.position += particle.velocity * dt;
+    }
+}
+
+/// 現在の状態から `steps` 回だけ `dt` 秒ずつ進めた、天体ごとの位置の列 (軌道の予測)
+pub fn predict(particles: &[Particle], steps: usize, dt: f32) -> Vec<Vec<Vec2>> {
+    let mut particles = particles.to_vec();
+    let mut paths: Vec<Vec<Vec2>> = particles
+        .iter()
+        .map(|particle| {
+            let mut path = Vec::with_capacity(steps + 1);
+            path.push(particle.position);
+            path
+        })
+        .collect();
+    for _ in 0..steps {
+        step(&mut particles, dt);
+        for (path, particle) in paths.iter_mut().zip(&particles) {
+            path.push(particle.position);
+        }
+    }
+    paths
+}
+
+/// 運動エネルギーと位置エネルギーの和 (積分の誤差で値がどれだけずれるかを見る)
+pub fn total_energy(particles: &[Particle]) -> f32 {
+    let kinetic: f32 = particles
+        .iter()
+        .map(|particle| 0.5 * particle.mass * particle.velocity.length_squared())
+        .sum();
+    let mut potential = 0.0;
+    for i in 0..particles.len() {
+        for j in i + 1..particles.len() {
+            let distance_squared = particles[i]
+                .position
+                .distance_squared(particles[j].position)
+                + SOFTENING;
+            potential -= GRAVITY * particles[i].mass * particles[j].mass / distance_squared.sqrt();
+        }
+    }
+    kinetic + potential
+}
+
+/// 質量 `central_mass` の天体の周りを、距離 `radius` で円を描いて回るための速さ
+pub fn circular_speed(central_mass: f32, radius: f32) -> f32 {
+    (GRAVITY * central_mass / radius).sqrt()
+}
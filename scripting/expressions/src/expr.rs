@@ -0,0 +1,417 @@
+//! 四則演算・比較・関数呼び出しだけを持つ小さな式言語の字句解析・構文解析・評価。
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::Variables;
+
+/// 構文解析した式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Variable(String),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// 二項演算子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+}
+
+impl BinaryOp {
+    /// 結合の強さ (大きいほど先に計算する)
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Equal | BinaryOp::NotEqual => 1,
+            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => 2,
+            BinaryOp::Add | BinaryOp::Sub => 3,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 4,
+            BinaryOp::Pow => 5,
+        }
+    }
+
+    /// 右結合かどうか (`2 ^ 3 ^ 2` は `2 ^ (3 ^ 2)`)
+    fn is_right_associative(self) -> bool {
+        self == BinaryOp::Pow
+    }
+
+    fn apply(self, a: f64, b: f64) -> f64 {
+        // 比較の結果は 1 (真) か 0 (偽) の数値で表す
+        let truth = |value: bool| if value { 1.0 } else { 0.0 };
+        match self {
+            BinaryOp::Add => a + b,
+            BinaryOp::Sub => a - b,
+            BinaryOp::Mul => a * b,
+            BinaryOp::Div => a / b,
+            BinaryOp::Rem => a % b,
+            BinaryOp::Pow => a.powf(b),
+            BinaryOp::Less => truth(a < b),
+            BinaryOp::LessEqual => truth(a <= b),
+            BinaryOp::Greater => truth(a > b),
+            BinaryOp::GreaterEqual => truth(a >= b),
+            BinaryOp::Equal => truth(a == b),
+            BinaryOp::NotEqual => truth(a != b),
+        }
+    }
+}
+
+/// 式の構文エラー (`position` は先頭からの文字数)
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{message} at column {}", position + 1)]
+pub struct ParseError {
+    pub message: String,
+    pub position: usize,
+}
+
+/// 式の評価エラー
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum EvalError {
+    #[error("unknown variable '{0}'")]
+    UnknownVariable(String),
+    #[error("unknown function '{0}'")]
+    UnknownFunction(String),
+    #[error("function '{name}' takes {expected} argument(s), got {got}")]
+    WrongArgumentCount {
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    #[error("result is not a finite number")]
+    NotFinite,
+}
+
+impl Expr {
+    /// 文字列を式として解析する
+    pub fn parse(source: &str) -> Result<Expr, ParseError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            index: 0,
+            end: source.chars().count(),
+        };
+        let expr = parser.expression(0)?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((token, position)) => Err(ParseError {
+                message: format!("unexpected {token}"),
+                position,
+            }),
+        }
+    }
+
+    /// 変数の値を使って式を評価する
+    ///
+    /// 結果が `NaN` や無限大になった場合 (0 除算など) はエラーにする。
+    pub fn eval(&self, variables: &Variables) -> Result<f64, EvalError> {
+        let value = self.eval_inner(variables)?;
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(EvalError::NotFinite)
+        }
+    }
+
+    fn eval_inner(&self, variables: &Variables) -> Result<f64, EvalError> {
+        match self {
+            Expr::Number(value) => Ok(*value),
+            Expr::Variable(name) => variables
+                .get(name)
+                .ok_or_else(|| EvalError::UnknownVariable(name.clone())),
+            Expr::Negate(operand) => Ok(-operand.eval_inner(variables)?),
+            Expr::Binary(op, a, b) => {
+                Ok(op.apply(a.eval_inner(variables)?, b.eval_inner(variables)?))
+            }
+            Expr::Call(name, args) => {
+                let values = args
+                    .iter()
+                    .map(|arg| arg.eval_inner(variables))
+                    .collect::<Result<Vec<_>, _>>()?;
+                call(name, &values)
+            }
+        }
+    }
+
+    /// 式の中で使われている変数の名前 (重複を除き、出現順)
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_variables(&mut names);
+        names
+    }
+
+    fn collect_variables<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Variable(name) => {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+            Expr::Negate(operand) => operand.collect_variables(names),
+            Expr::Binary(_, a, b) => {
+                a.collect_variables(names);
+                b.collect_variables(names);
+            }
+            Expr::Call(_, args) => {
+                for arg in args {
+                    arg.collect_variables(names);
+                }
+            }
+        }
+    }
+}
+
+/// 組み込み関数を呼び出す
+fn call(name: &str, args: &[f64]) -> Result<f64, EvalError> {
+    let expect = |expected: usize| {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(EvalError::WrongArgumentCount {
+                name: name.to_string(),
+                expected,
+                got: args.len(),
+            })
+        }
+    };
+    match name {
+        "abs" | "floor" | "ceil" | "round" | "sqrt" => {
+            expect(1)?;
+            let x = args[0];
+            Ok(match name {
+                "abs" => x.abs(),
+                "floor" => x.floor(),
+                "ceil" => x.ceil(),
+                "round" => x.round(),
+                _ => x.sqrt(),
+            })
+        }
+        "min" | "max" => {
+            expect(2)?;
+            Ok(if name == "min" {
+                args[0].min(args[1])
+            } else {
+                args[0].max(args[1])
+            })
+        }
+        "clamp" => {
+            expect(3)?;
+            Ok(args[0].max(args[1]).min(args[2]))
+        }
+        // 条件が 0 以外なら 2 番目、0 なら 3 番目の値
+        "if" => {
+            expect(3)?;
+            Ok(if args[0] != 0.0 { args[1] } else { args[2] })
+        }
+        _ => Err(EvalError::UnknownFunction(name.to_string())),
+    }
+}
+
+/// 字句
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Operator(BinaryOp),
+    Minus, // 二項演算子か単項演算子かは構文解析で決める
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "number {value}"),
+            Token::Identifier(name) => write!(f, "'{name}'"),
+            Token::Operator(op) => write!(f, "operator {op:?}"),
+            Token::Minus => write!(f, "'-'"),
+            Token::LeftParen => write!(f, "'('"),
+            Token::RightParen => write!(f, "')'"),
+            Token::Comma => write!(f, "','"),
+        }
+    }
+}
+
+/// 文字列を字句の列に分ける (各字句の開始位置も返す)
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let error = |message: String| ParseError {
+            message,
+            position: start,
+        };
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text
+                .parse()
+                .map_err(|_| error(format!("invalid number '{text}'")))?;
+            tokens.push((Token::Number(value), start));
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Identifier(chars[start..i].iter().collect()), start));
+            continue;
+        }
+
+        // 2 文字の演算子を優先する
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            ('<', Some('=')) => (Token::Operator(BinaryOp::LessEqual), 2),
+            ('>', Some('=')) => (Token::Operator(BinaryOp::GreaterEqual), 2),
+            ('=', Some('=')) => (Token::Operator(BinaryOp::Equal), 2),
+            ('!', Some('=')) => (Token::Operator(BinaryOp::NotEqual), 2),
+            ('<', _) => (Token::Operator(BinaryOp::Less), 1),
+            ('>', _) => (Token::Operator(BinaryOp::Greater), 1),
+            ('+', _) => (Token::Operator(BinaryOp::Add), 1),
+            ('-', _) => (Token::Minus, 1),
+            ('*', _) => (Token::Operator(BinaryOp::Mul), 1),
+            ('/', _) => (Token::Operator(BinaryOp::Div), 1),
+            ('%', _) => (Token::Operator(BinaryOp::Rem), 1),
+            ('^', _) => (Token::Operator(BinaryOp::Pow), 1),
+            ('(', _) => (Token::LeftParen, 1),
+            (')', _) => (Token::RightParen, 1),
+            (',', _) => (Token::Comma, 1),
+            _ => return Err(error(format!("unexpected character '{c}'"))),
+        };
+        tokens.push((token, start));
+        i += len;
+    }
+    Ok(tokens)
+}
+
+/// 優先順位を使った再帰下降の構文解析器
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    index: usize,
+    end: usize, // 入力の末尾の位置 (エラーの表示用)
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<(&Token, usize)> {
+        self.tokens
+            .get(self.index)
+            .map(|(token, position)| (token, *position))
+    }
+
+    fn next(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        match self.next() {
+            Some((token, _)) if token == expected => Ok(()),
+            Some((token, position)) => Err(ParseError {
+                message: format!("expected {expected}, found {token}"),
+                position,
+            }),
+            None => Err(self.unexpected_end(&format!("expected {expected}"))),
+        }
+    }
+
+    fn unexpected_end(&self, message: &str) -> ParseError {
+        ParseError {
+            message: format!("{message}, found end of input"),
+            position: self.end,
+        }
+    }
+
+    /// `min_precedence` 以上の強さの二項演算子だけをまとめて解析する
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some((Token::Operator(op), _)) => *op,
+                Some((Token::Minus, _)) => BinaryOp::Sub,
+                _ => break,
+            };
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.next();
+            let next_precedence = if op.is_right_associative() {
+                op.precedence()
+            } else {
+                op.precedence() + 1
+            };
+            let rhs = self.expression(next_precedence)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if let Some((Token::Minus, _)) = self.peek() {
+            self.next();
+            // `-2 ^ 2` は `-(2 ^ 2)` として扱う
+            let operand = self.expression(BinaryOp::Pow.precedence())?;
+            return Ok(Expr::Negate(Box::new(operand)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        match self.next() {
+            Some((Token::Number(value), _)) => Ok(Expr::Number(value)),
+            Some((Token::Identifier(name), _)) => {
+                if !matches!(self.peek(), Some((Token::LeftParen, _))) {
+                    return Ok(Expr::Variable(name));
+                }
+                self.next();
+                let mut args = Vec::new();
+                if !matches!(self.peek(), Some((Token::RightParen, _))) {
+                    loop {
+                        args.push(self.expression(0)?);
+                        if !matches!(self.peek(), Some((Token::Comma, _))) {
+                            break;
+                        }
+                        self.next();
+                    }
+                }
+                self.expect(Token::RightParen)?;
+                Ok(Expr::Call(name, args))
+            }
+            Some((Token::LeftParen, _)) => {
+                let expr = self.expression(0)?;
+                self.expect(Token::RightParen)?;
+                Ok(expr)
+            }
+            Some((token, position)) => Err(ParseError {
+                message: format!("unexpected {token}"),
+                position,
+            }),
+            None => Err(self.unexpected_end("expected a value")),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! アセットに書いた計算式 (`damage = base * (1 + combo * 0.1)` のようなもの) を実行時に評価するクレート。
+//!
+//! 式は `*.expr` ファイルに書き、[`Formula`] アセットとして読み込む。変数の値は
+//! ECS のコンポーネントやリソースから [`Variables`] に詰めて渡す。
+//! 式を書き換えて読み込み直せば、再コンパイルせずにゲームバランスを調整できる。
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use thiserror::Error;
+
+mod expr;
+
+pub use expr::{BinaryOp, EvalError, Expr, ParseError};
+
+/// [`Formula`] アセットを読み込めるようにするプラグイン
+pub struct ExpressionsPlugin;
+
+impl Plugin for ExpressionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Formula>()
+            .init_asset_loader::<FormulaLoader>();
+    }
+}
+
+/// 式に渡す変数の値
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Variables(HashMap<String, f64>);
+
+impl Variables {
+    pub fn new() -> Variables {
+        Variables::default()
+    }
+
+    /// 変数を追加した `Variables` を返す (組み立て用)
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<f64>) -> Variables {
+        self.set(name, value);
+        self
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<f64>) {
+        self.0.insert(name.into(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.0.get(name).copied()
+    }
+
+    /// 名前順に並べた変数と値
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        let mut entries: Vec<_> = self
+            .0
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries.into_iter()
+    }
+}
+
+/// 1 つの計算式 (`*.expr`)
+///
+/// ファイルの `#` で始まる行はコメントとして無視し、残りの行をつなげて 1 つの式として解析する。
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct Formula {
+    source: String,
+    expr: Expr,
+}
+
+impl Formula {
+    /// 文字列から式を作る (アセットを使わずに式を埋め込む場合など)
+    pub fn parse(source: &str) -> Result<Formula, ParseError> {
+        let source = strip_comments(source);
+        Ok(Formula {
+            expr: Expr::parse(&source)?,
+            source,
+        })
+    }
+
+    /// コメントを除いた式の文字列
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// 変数の値を使って式を評価する
+    pub fn eval(&self, variables: &Variables) -> Result<f64, EvalError> {
+        self.expr.eval(variables)
+    }
+}
+
+/// `#` で始まる行と空行を除き、残りを 1 行につなげる
+fn strip_comments(source: &str) -> String {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 式の読み込み時に発生するエラー
+#[derive(Debug, Error)]
+pub enum FormulaLoaderError {
+    #[error("could not read formula: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("formula is not valid UTF-8: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+    #[error("could not parse formula: {0}")]
+    Parse(#[from] ParseError),
+}
+
+/// テキスト形式の式を読み込むアセットローダー
+#[derive(Default)]
+struct FormulaLoader;
+
+impl AssetLoader for FormulaLoader {
+    type Asset = Formula;
+    type Settings = ();
+    type Error = FormulaLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Formula, FormulaLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(Formula::parse(&String::from_utf8(bytes)?)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["expr"]
+    }
+}
@@ -0,0 +1,246 @@
+use bevy::{asset::LoadState, prelude::*};
+use expressions::{ExpressionsPlugin, Formula, Variables};
+
+// ダメージの式のパス
+const DAMAGE_FORMULA: &str = "formulas/damage.expr";
+// キャラクターの数と表示の間隔
+const FIGHTER_COUNT: usize = 3;
+const ROW_HEIGHT: f32 = 120.0;
+// ダメージ 1 あたりのバーの長さと、バーの太さ・左端の x 座標
+const BAR_SCALE: f32 = 8.0;
+const BAR_THICKNESS: f32 = 24.0;
+const BAR_LEFT: f32 = -200.0;
+
+// 色
+const BAR_COLOR: Color = Color::srgb(0.9, 0.4, 0.3);
+const SELECTED_BAR_COLOR: Color = Color::srgb(1.0, 0.8, 0.3);
+const ERROR_COLOR: Color = Color::srgb(1.0, 0.3, 0.3);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ExpressionsPlugin))
+        .init_resource::<Selected>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                (select_fighter, edit_stats, reload_formula),
+                evaluate_damage,
+                (draw_bars, update_labels, update_status),
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// キャラクターのステータス
+///
+/// 型ではなく名前と値の組で持つため、式で使う変数を増やしてもこの構造体を変える必要はない。
+#[derive(Component, Deref, DerefMut)]
+struct Stats(Variables);
+
+/// 式を評価した結果 (評価できなければエラーの内容)
+#[derive(Component, Default)]
+struct Damage(Option<Result<f64, String>>);
+
+/// 何番目のキャラクターか
+#[derive(Component)]
+struct FighterIndex(usize);
+
+/// キャラクターのラベルを示すコンポーネント
+#[derive(Component)]
+struct FighterLabel;
+
+/// 操作中のキャラクター
+#[derive(Resource, Default, Deref, DerefMut)]
+struct Selected(usize);
+
+/// ダメージの式のハンドル
+#[derive(Resource)]
+struct DamageFormula(Handle<Formula>);
+
+/// 式と操作方法を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct StatusText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2d);
+    commands.insert_resource(DamageFormula(asset_server.load(DAMAGE_FORMULA)));
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+
+    for index in 0..FIGHTER_COUNT {
+        let y = (FIGHTER_COUNT as f32 / 2.0 - index as f32 - 0.5) * ROW_HEIGHT - 40.0;
+        let stats = Variables::new()
+            .with("base", 5.0 + index as f64 * 5.0)
+            .with("combo", 0.0)
+            .with("level", 1.0 + index as f64);
+        commands.spawn((
+            Stats(stats),
+            Damage::default(),
+            FighterIndex(index),
+            Transform::from_xyz(BAR_LEFT, y, 0.0),
+        ));
+        commands.spawn((
+            Text2d::default(),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            Transform::from_xyz(BAR_LEFT - 20.0, y, 0.0),
+            bevy::sprite::Anchor::CenterRight,
+            FighterIndex(index),
+            FighterLabel,
+        ));
+    }
+}
+
+/// 数字キーで操作するキャラクターを選ぶ
+fn select_fighter(keyboard_input: Res<ButtonInput<KeyCode>>, mut selected: ResMut<Selected>) {
+    let keys = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3];
+    if let Some(index) = keys
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key))
+    {
+        **selected = index;
+    }
+}
+
+/// 上下キーでコンボ数、左右キーで基礎攻撃力を変える
+fn edit_stats(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    selected: Res<Selected>,
+    mut fighters: Query<(&FighterIndex, &mut Stats)>,
+) {
+    let changes = [
+        (KeyCode::ArrowUp, "combo", 1.0),
+        (KeyCode::ArrowDown, "combo", -1.0),
+        (KeyCode::ArrowRight, "base", 1.0),
+        (KeyCode::ArrowLeft, "base", -1.0),
+    ];
+    for (index, mut stats) in &mut fighters {
+        if index.0 != **selected {
+            continue;
+        }
+        for (key, name, delta) in changes {
+            if keyboard_input.just_pressed(key) {
+                let value = stats.get(name).unwrap_or(0.0);
+                stats.set(name, (value + delta).max(0.0));
+            }
+        }
+    }
+}
+
+/// R キーで式をファイルから読み込み直す
+fn reload_formula(keyboard_input: Res<ButtonInput<KeyCode>>, asset_server: Res<AssetServer>) {
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        asset_server.reload(DAMAGE_FORMULA);
+    }
+}
+
+/// ステータスを変数として式に渡し、ダメージを求める
+fn evaluate_damage(
+    formula: Res<DamageFormula>,
+    formulas: Res<Assets<Formula>>,
+    mut fighters: Query<(&Stats, &mut Damage)>,
+) {
+    let Some(formula) = formulas.get(&formula.0) else {
+        return;
+    };
+    for (stats, mut damage) in &mut fighters {
+        damage.0 = Some(formula.eval(stats).map_err(|error| error.to_string()));
+    }
+}
+
+/// ダメージの大きさを横棒で表示する
+fn draw_bars(
+    mut gizmos: Gizmos,
+    selected: Res<Selected>,
+    fighters: Query<(&FighterIndex, &Damage, &Transform)>,
+) {
+    for (index, damage, transform) in &fighters {
+        let Some(Ok(value)) = damage.0 else {
+            continue;
+        };
+        let length = (value as f32 * BAR_SCALE).max(1.0);
+        let color = if index.0 == **selected {
+            SELECTED_BAR_COLOR
+        } else {
+            BAR_COLOR
+        };
+        let center = transform.translation.truncate() + Vec2::new(length / 2.0, 0.0);
+        gizmos.rect_2d(center, Vec2::new(length, BAR_THICKNESS), color);
+    }
+}
+
+/// キャラクターごとのステータスとダメージを表示する
+fn update_labels(
+    fighters: Query<(&FighterIndex, &Stats, &Damage)>,
+    mut labels: Query<(&FighterIndex, &mut Text2d, &mut TextColor), With<FighterLabel>>,
+) {
+    for (index, stats, damage) in &fighters {
+        let Some((_, mut text, mut color)) =
+            labels.iter_mut().find(|(label, _, _)| label.0 == index.0)
+        else {
+            continue;
+        };
+        let stats_text = stats
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (result, result_color) = match &damage.0 {
+            Some(Ok(value)) => (format!("damage {value:.1}"), Color::WHITE),
+            Some(Err(error)) => (error.clone(), ERROR_COLOR),
+            None => ("loading...".to_string(), Color::WHITE),
+        };
+        text.0 = format!("[{}] {stats_text}\n{result}", index.0 + 1);
+        color.0 = result_color;
+    }
+}
+
+/// 式の内容・読み込みエラー・操作方法を表示する
+fn update_status(
+    mut status: Single<(&mut Text, &mut TextColor), With<StatusText>>,
+    formula: Res<DamageFormula>,
+    formulas: Res<Assets<Formula>>,
+    asset_server: Res<AssetServer>,
+) {
+    let (text, color) = &mut *status;
+    // 構文エラーで読み込みに失敗した場合は、直前に読み込めた式のまま評価を続ける
+    let error = match asset_server.load_state(&formula.0) {
+        LoadState::Failed(error) => Some(error.to_string()),
+        _ => None,
+    };
+    let current = formulas
+        .get(&formula.0)
+        .map(|formula| {
+            format!(
+                "{}    (variables: {})",
+                formula.source(),
+                formula.expr().variables().join(", ")
+            )
+        })
+        .unwrap_or_else(|| "(not loaded)".to_string());
+
+    text.0 = format!(
+        "damage = {current}\n\
+         1/2/3: select, Up/Down: combo, Left/Right: base, R: reload {DAMAGE_FORMULA}"
+    );
+    if let Some(error) = &error {
+        text.0 += &format!("\n{error}");
+    }
+    color.0 = if error.is_some() {
+        ERROR_COLOR
+    } else {
+        Color::WHITE
+    };
+}
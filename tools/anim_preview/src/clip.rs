@@ -0,0 +1,191 @@
+use std::path::Path;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// アニメーションのクリップのアセットを読み込めるようにするプラグイン
+pub struct ClipPlugin;
+
+impl Plugin for ClipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AnimClip>()
+            .init_asset_loader::<RonClipLoader>()
+            .init_asset_loader::<AsepriteClipLoader>();
+    }
+}
+
+/// 1 つの画像から切り出すフレームを並べたアニメーションのクリップ
+///
+/// 画像は依存アセットとして読み込むので、画像だけを書き換えたときも `AssetEvent<Image>` でわかる。
+#[derive(Asset, TypePath, Debug)]
+pub struct AnimClip {
+    pub image: Handle<Image>,
+    pub frames: Vec<ClipFrame>,
+}
+
+/// クリップの 1 つのフレーム
+#[derive(Debug, Clone, Copy)]
+pub struct ClipFrame {
+    pub rect: URect,   // 画像の中のフレームの範囲 (ピクセル)
+    pub duration: f32, // 表示する時間 (秒)
+}
+
+impl AnimClip {
+    /// クリップ全体の長さ (秒)
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+}
+
+/// クリップの読み込み時に発生するエラー
+#[derive(Debug, Error)]
+pub enum ClipLoaderError {
+    #[error("could not read clip: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse clip: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("could not parse Aseprite sheet: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the clip has no frames")]
+    NoFrames,
+    #[error("fps must be greater than zero")]
+    InvalidFps,
+}
+
+/// クリップのファイルから見た相対パスで、画像を依存アセットとして読み込む
+fn load_image(load_context: &mut LoadContext, image: &str) -> Handle<Image> {
+    let dir = load_context.path().parent().unwrap_or(Path::new(""));
+    let path = dir.join(image);
+    load_context.load(path)
+}
+
+/// 格子状に並んだフレームを一定の速さで再生するクリップ (`*.anim.ron`)
+#[derive(Deserialize)]
+struct RonClip {
+    image: String,
+    frame_size: (u32, u32),
+    columns: u32,
+    frames: u32,
+    fps: f32,
+}
+
+/// RON 形式のクリップを読み込むアセットローダー
+#[derive(Default)]
+struct RonClipLoader;
+
+impl AssetLoader for RonClipLoader {
+    type Asset = AnimClip;
+    type Settings = ();
+    type Error = ClipLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<AnimClip, ClipLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let clip: RonClip = ron::de::from_bytes(&bytes)?;
+        if clip.frames == 0 || clip.columns == 0 {
+            return Err(ClipLoaderError::NoFrames);
+        }
+        if clip.fps <= 0.0 {
+            return Err(ClipLoaderError::InvalidFps);
+        }
+
+        let size = UVec2::new(clip.frame_size.0, clip.frame_size.1);
+        let frames = (0..clip.frames)
+            .map(|index| {
+                let min = UVec2::new(index % clip.columns, index / clip.columns) * size;
+                ClipFrame {
+                    rect: URect::from_corners(min, min + size),
+                    duration: 1.0 / clip.fps,
+                }
+            })
+            .collect();
+        Ok(AnimClip {
+            image: load_image(load_context, &clip.image),
+            frames,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
+
+/// Aseprite の「Export Sprite Sheet」で書き出した JSON (フレームは Array 形式)
+#[derive(Deserialize)]
+struct AsepriteSheet {
+    frames: Vec<AsepriteFrame>,
+    meta: AsepriteMeta,
+}
+
+#[derive(Deserialize)]
+struct AsepriteFrame {
+    frame: AsepriteRect,
+    duration: u32, // 表示する時間 (ミリ秒)
+}
+
+#[derive(Deserialize)]
+struct AsepriteRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteMeta {
+    image: String, // JSON から見た相対パスの画像
+}
+
+/// Aseprite の JSON を読み込むアセットローダー
+#[derive(Default)]
+struct AsepriteClipLoader;
+
+impl AssetLoader for AsepriteClipLoader {
+    type Asset = AnimClip;
+    type Settings = ();
+    type Error = ClipLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<AnimClip, ClipLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let sheet: AsepriteSheet = serde_json::from_slice(&bytes)?;
+        if sheet.frames.is_empty() {
+            return Err(ClipLoaderError::NoFrames);
+        }
+
+        let frames = sheet
+            .frames
+            .iter()
+            .map(|frame| {
+                let rect = &frame.frame;
+                ClipFrame {
+                    rect: URect::new(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h),
+                    // 0 ミリ秒のフレームがあっても再生が止まらないように、1 ミリ秒は表示する
+                    duration: frame.duration.max(1) as f32 / 1000.0,
+                }
+            })
+            .collect();
+        Ok(AnimClip {
+            image: load_image(load_context, &sheet.meta.image),
+            frames,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json", "json"]
+    }
+}
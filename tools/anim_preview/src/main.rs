@@ -0,0 +1,417 @@
+use bevy::{asset::AssetLoadFailedEvent, prelude::*};
+
+mod clip;
+
+use clip::{AnimClip, ClipPlugin};
+
+// 起動引数でファイルを指定しなかったときに開くクリップ (`assets` からの相対パス)
+const DEFAULT_CLIPS: [&str; 2] = ["clips/hopper.anim.ron", "clips/hopper.aseprite.json"];
+// プレビューの拡大率
+const PREVIEW_SCALE: f32 = 8.0;
+// オニオンスキンで前後に重ねる最大のフレーム数と、いちばん近いフレームの不透明度
+const MAX_ONION_DEPTH: usize = 3;
+const ONION_ALPHA: f32 = 0.35;
+// オニオンスキンの色 (前のフレームは赤、次のフレームは緑)
+const PREVIOUS_TINT: Color = Color::srgb(1.0, 0.45, 0.45);
+const NEXT_TINT: Color = Color::srgb(0.45, 1.0, 0.55);
+// 画面の下のタイムラインの位置と大きさ
+const TIMELINE_Y: f32 = -250.0;
+const TIMELINE_WIDTH: f32 = 640.0;
+const TIMELINE_HEIGHT: f32 = 24.0;
+const TIMELINE_COLOR: Color = Color::srgb(0.35, 0.35, 0.4);
+const TIMELINE_CURRENT_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+
+fn main() {
+    App::new()
+        .add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest()) // ドット絵をぼかさずに拡大する
+                .set(AssetPlugin {
+                    // ファイルの変更を監視し、書き換えたアセットを読み込み直す
+                    watch_for_changes_override: Some(true),
+                    ..default()
+                }),
+        )
+        .add_plugins(ClipPlugin)
+        .insert_resource(Preview::from_args())
+        .add_systems(Startup, (setup, open_clip).chain())
+        .add_systems(
+            Update,
+            (
+                handle_input,
+                open_clip.run_if(was_file_switched),
+                restart_on_reload,
+                report_load_errors,
+                play,
+                update_sprites,
+                draw_timeline,
+                update_status,
+            )
+                .chain(),
+        )
+        .run();
+}
+
+/// プレビューの状態
+#[derive(Resource)]
+struct Preview {
+    paths: Vec<String>,                 // `Tab` キーで切り替えるクリップのパス
+    current: usize,                     // 開いているクリップの番号
+    switched: bool,                     // 開くクリップを切り替えたか
+    rebuild: bool,                      // 読み込みが終わりしだいスプライトを作り直す
+    clip: Handle<AnimClip>,             // 開いているクリップ
+    layout: Handle<TextureAtlasLayout>, // フレームから作ったアトラスのレイアウト
+    frame: usize,                       // 表示しているフレーム
+    elapsed: f32,                       // 表示しているフレームの経過時間 (秒)
+    playing: bool,
+    onion_depth: usize, // 前後に重ねるフレーム数 (0 ならオニオンスキンなし)
+    message: String,    // 直前の読み込みの結果
+}
+
+impl Preview {
+    /// 起動引数で指定したクリップ (なければサンプルのクリップ) を開く
+    fn from_args() -> Preview {
+        let mut paths: Vec<String> = std::env::args().skip(1).collect();
+        if paths.is_empty() {
+            paths = DEFAULT_CLIPS.iter().map(|path| path.to_string()).collect();
+        }
+        Preview {
+            paths,
+            current: 0,
+            switched: true,
+            rebuild: false,
+            clip: Handle::default(),
+            layout: Handle::default(),
+            frame: 0,
+            elapsed: 0.0,
+            playing: true,
+            onion_depth: 1,
+            message: String::new(),
+        }
+    }
+
+    fn path(&self) -> &str {
+        &self.paths[self.current]
+    }
+
+    /// クリップを最初のフレームから再生し直す
+    fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.playing = true;
+    }
+}
+
+/// プレビューしているクリップのスプライト
+#[derive(Component)]
+struct Previewed;
+
+/// オニオンスキンのスプライト (表示しているフレームから `offset` だけ前後のフレームを表示する)
+#[derive(Component)]
+struct Ghost {
+    offset: isize,
+}
+
+/// 状態を表示するテキスト
+#[derive(Component)]
+struct StatusText;
+
+/// カメラ・スプライト・オニオンスキン・状態の表示を配置する
+fn setup(
+    mut commands: Commands,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut preview: ResMut<Preview>,
+) {
+    commands.spawn(Camera2d);
+
+    // レイアウトは読み込むたびに同じハンドルのまま中身を入れ替える
+    preview.layout = layouts.add(TextureAtlasLayout::new_empty(UVec2::ONE));
+
+    commands.spawn((
+        Sprite::default(),
+        Transform::from_scale(Vec3::splat(PREVIEW_SCALE)),
+        Visibility::Hidden,
+        Previewed,
+    ));
+    for depth in 1..=MAX_ONION_DEPTH as isize {
+        for offset in [-depth, depth] {
+            commands.spawn((
+                Sprite::default(),
+                // 近いフレームほど手前に重ねる
+                Transform::from_xyz(0.0, 0.0, -depth as f32).with_scale(Vec3::splat(PREVIEW_SCALE)),
+                Visibility::Hidden,
+                Ghost { offset },
+            ));
+        }
+    }
+
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+}
+
+/// 開くクリップを切り替えたかどうか
+fn was_file_switched(preview: Res<Preview>) -> bool {
+    preview.switched
+}
+
+/// 選んでいるクリップを読み込む
+fn open_clip(asset_server: Res<AssetServer>, mut preview: ResMut<Preview>) {
+    preview.switched = false;
+    // 一度読み込んだことのあるクリップは読み込み終わりのイベントが届かないことがあるので、自分で作り直す
+    preview.rebuild = true;
+    preview.clip = asset_server.load(preview.path().to_string());
+    preview.message = format!("loading {}", preview.path());
+    preview.restart();
+}
+
+/// キー入力で再生・コマ送り・オニオンスキン・クリップの切り替えを行う
+fn handle_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    clips: Res<Assets<AnimClip>>,
+    mut preview: ResMut<Preview>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        preview.current = (preview.current + 1) % preview.paths.len();
+        preview.switched = true;
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        // ファイルの監視が効かない環境でも、手動で読み込み直せるようにする
+        asset_server.reload(preview.path().to_string());
+    }
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        preview.playing = !preview.playing;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyO) {
+        preview.onion_depth = (preview.onion_depth + 1) % (MAX_ONION_DEPTH + 1);
+    }
+
+    // `←` / `→` キーで 1 フレームずつ戻す・進める (コマ送りの間は再生を止める)
+    let Some(frame_count) = clips.get(&preview.clip).map(|clip| clip.frames.len()) else {
+        return;
+    };
+    let step = if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        1
+    } else if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        frame_count - 1
+    } else {
+        return;
+    };
+    preview.playing = false;
+    preview.frame = (preview.frame + step) % frame_count;
+    preview.elapsed = 0.0;
+}
+
+/// クリップか画像が読み込み直されたら、アトラスのレイアウトを作り直して最初から再生する
+///
+/// 画像の大きさが変わってもよいように、レイアウトの大きさは読み込んだ画像から決める。
+#[allow(clippy::too_many_arguments)]
+fn restart_on_reload(
+    mut clip_events: EventReader<AssetEvent<AnimClip>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    clips: Res<Assets<AnimClip>>,
+    images: Res<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut preview: ResMut<Preview>,
+    mut previewed: Single<(&mut Sprite, &mut Visibility), With<Previewed>>,
+    mut ghosts: Query<&mut Sprite, (With<Ghost>, Without<Previewed>)>,
+) {
+    let Some(clip) = clips.get(&preview.clip) else {
+        // 読み込み中のイベントは、読み込み終わったあとに改めて届く
+        clip_events.clear();
+        image_events.clear();
+        return;
+    };
+    // 1 回の読み込み直しで複数のイベントが届くので、すべて読んでおく
+    let clip_changed = clip_events
+        .read()
+        .filter(|event| {
+            event.is_loaded_with_dependencies(&preview.clip) || event.is_modified(&preview.clip)
+        })
+        .count()
+        > 0;
+    let image_changed = image_events
+        .read()
+        .filter(|event| {
+            event.is_loaded_with_dependencies(&clip.image) || event.is_modified(&clip.image)
+        })
+        .count()
+        > 0;
+    if !clip_changed && !image_changed && !preview.rebuild {
+        return;
+    }
+    let Some(image) = images.get(&clip.image) else {
+        return;
+    };
+
+    let mut layout = TextureAtlasLayout::new_empty(image.size());
+    for frame in &clip.frames {
+        layout.add_texture(frame.rect);
+    }
+    layouts.insert(&preview.layout, layout);
+
+    let atlas = TextureAtlas {
+        layout: preview.layout.clone(),
+        index: 0,
+    };
+    let (sprite, visibility) = &mut *previewed;
+    **sprite = Sprite::from_atlas_image(clip.image.clone(), atlas.clone());
+    **visibility = Visibility::Inherited;
+    for mut ghost in &mut ghosts {
+        *ghost = Sprite::from_atlas_image(clip.image.clone(), atlas.clone());
+    }
+
+    preview.rebuild = false;
+    let what = if image_changed && !clip_changed {
+        "image"
+    } else {
+        "clip"
+    };
+    preview.message = format!("reloaded {what} ({} frames)", clip.frames.len());
+    info!("{}: {}", preview.path(), preview.message);
+    preview.restart();
+}
+
+/// 読み込みに失敗したら、理由を画面に表示する (直すまで直前のクリップを表示し続ける)
+fn report_load_errors(
+    mut clip_errors: EventReader<AssetLoadFailedEvent<AnimClip>>,
+    mut image_errors: EventReader<AssetLoadFailedEvent<Image>>,
+    mut preview: ResMut<Preview>,
+) {
+    let errors = clip_errors
+        .read()
+        .map(|event| event.error.to_string())
+        .chain(image_errors.read().map(|event| event.error.to_string()));
+    for error in errors {
+        warn!("{error}");
+        preview.message = format!("error: {error}");
+    }
+}
+
+/// 再生中はフレームの表示時間ごとに次のフレームへ進める
+fn play(time: Res<Time>, clips: Res<Assets<AnimClip>>, mut preview: ResMut<Preview>) {
+    if !preview.playing {
+        return;
+    }
+    let Some(clip) = clips.get(&preview.clip) else {
+        return;
+    };
+    preview.elapsed += time.delta_secs();
+    // 読み込み直してフレームが減ったときも、範囲の外を指さないようにする
+    let mut frame = preview.frame % clip.frames.len();
+    while preview.elapsed >= clip.frames[frame].duration {
+        preview.elapsed -= clip.frames[frame].duration;
+        frame = (frame + 1) % clip.frames.len();
+    }
+    preview.frame = frame;
+}
+
+/// 表示しているフレームをスプライトに反映し、オニオンスキンに前後のフレームを表示する
+fn update_sprites(
+    clips: Res<Assets<AnimClip>>,
+    preview: Res<Preview>,
+    mut previewed: Single<&mut Sprite, With<Previewed>>,
+    mut ghosts: Query<(&Ghost, &mut Sprite, &mut Visibility), Without<Previewed>>,
+) {
+    let Some(clip) = clips.get(&preview.clip) else {
+        return;
+    };
+    let frame_count = clip.frames.len();
+    let frame = preview.frame % frame_count;
+    if let Some(atlas) = &mut previewed.texture_atlas {
+        atlas.index = frame;
+    }
+
+    for (ghost, mut sprite, mut visibility) in &mut ghosts {
+        let depth = ghost.offset.unsigned_abs();
+        // フレームが少ないクリップでは、同じフレームを何度も重ねないようにする
+        let shown = depth <= preview.onion_depth && depth < frame_count;
+        visibility.set_if_neq(if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if !shown {
+            continue;
+        }
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            atlas.index = (frame as isize + ghost.offset).rem_euclid(frame_count as isize) as usize;
+        }
+        let tint = if ghost.offset < 0 {
+            PREVIOUS_TINT
+        } else {
+            NEXT_TINT
+        };
+        sprite.color = tint.with_alpha(ONION_ALPHA / depth as f32);
+    }
+}
+
+/// 画面の下に、フレームごとの表示時間の長さに合わせた区切りのタイムラインを描く
+fn draw_timeline(mut gizmos: Gizmos, clips: Res<Assets<AnimClip>>, preview: Res<Preview>) {
+    let Some(clip) = clips.get(&preview.clip) else {
+        return;
+    };
+    let total = clip.duration();
+    let mut left = -TIMELINE_WIDTH / 2.0;
+    for (index, frame) in clip.frames.iter().enumerate() {
+        let width = TIMELINE_WIDTH * frame.duration / total;
+        let center = Vec2::new(left + width / 2.0, TIMELINE_Y);
+        let color = if index == preview.frame % clip.frames.len() {
+            TIMELINE_CURRENT_COLOR
+        } else {
+            TIMELINE_COLOR
+        };
+        gizmos.rect_2d(center, Vec2::new(width - 2.0, TIMELINE_HEIGHT), color);
+        left += width;
+    }
+
+    // 再生位置を縦線で示す
+    let position = clip.frames[..preview.frame % clip.frames.len()]
+        .iter()
+        .map(|frame| frame.duration)
+        .sum::<f32>()
+        + preview.elapsed;
+    let x = -TIMELINE_WIDTH / 2.0 + TIMELINE_WIDTH * position / total;
+    gizmos.line_2d(
+        Vec2::new(x, TIMELINE_Y - TIMELINE_HEIGHT),
+        Vec2::new(x, TIMELINE_Y + TIMELINE_HEIGHT),
+        TIMELINE_CURRENT_COLOR,
+    );
+}
+
+/// 開いているファイル・フレーム・再生状態・直前の読み込みの結果を表示する
+fn update_status(
+    clips: Res<Assets<AnimClip>>,
+    preview: Res<Preview>,
+    mut status: Single<&mut Text, With<StatusText>>,
+) {
+    let frame = match clips.get(&preview.clip) {
+        Some(clip) => format!(
+            "frame {} / {} ({:.0} ms)",
+            preview.frame % clip.frames.len() + 1,
+            clip.frames.len(),
+            clip.frames[preview.frame % clip.frames.len()].duration * 1000.0
+        ),
+        None => "no clip".to_string(),
+    };
+    let state = if preview.playing { "playing" } else { "paused" };
+    status.0 = format!(
+        "{}\n{frame} - {state} - onion skin: {}\n{}\n\nSpace: play / pause, Left / Right: step, O: onion skin, R: reload, Tab: next file",
+        preview.path(),
+        preview.onion_depth,
+        preview.message,
+    );
+}
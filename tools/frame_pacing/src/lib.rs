@@ -0,0 +1,117 @@
+//! `FixedUpdate` の追いつき処理 (1 フレームに何回固定ステップが実行されたか) を計測し、
+//! 1 フレームあたりの固定ステップ数に上限を設けるプラグイン。
+//!
+//! 固定ステップの処理が重くなるとフレームが遅れ、遅れを取り戻すために次のフレームで
+//! さらに多くの固定ステップが実行され、ますます遅れる…という悪循環 (デススパイラル) に陥る。
+//! 上限を設けると、追いつけない分はゲーム内の時間がゆっくり進むだけで済む。
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+// 記録するフレーム数
+const HISTORY_LEN: usize = 240;
+// 上限に達したフレームがこれだけ続いたら、追いつけていないとみなす
+const FALLING_BEHIND_FRAMES: u32 = 30;
+
+/// 固定ステップの計測と、1 フレームあたりの固定ステップ数の制限を行うプラグイン
+pub struct FramePacingPlugin;
+
+impl Plugin for FramePacingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MaxFixedTicksPerFrame>()
+            .init_resource::<FixedTickStats>()
+            .add_systems(First, apply_tick_clamp)
+            .add_systems(FixedFirst, count_fixed_tick)
+            .add_systems(PostUpdate, record_frame);
+    }
+}
+
+/// 1 フレームで実行する固定ステップの最大数
+///
+/// `Time<Virtual>` の `max_delta` を「固定ステップの間隔 × この値」に設定することで制限する。
+/// 上限を超えた分の時間は捨てられ、ゲーム内の時間が現実の時間より遅れる。
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Deref, DerefMut)]
+pub struct MaxFixedTicksPerFrame(pub u32);
+
+impl Default for MaxFixedTicksPerFrame {
+    fn default() -> Self {
+        // 60 FPS 前後の環境で、数フレーム分の遅れまでは取り戻せる程度
+        MaxFixedTicksPerFrame(4)
+    }
+}
+
+/// 固定ステップの実行回数の記録
+#[derive(Resource, Debug, Default)]
+pub struct FixedTickStats {
+    /// 直前のフレームで実行された固定ステップの数
+    pub ticks_this_frame: u32,
+    /// 最近のフレームごとの固定ステップの数 (先頭が最新)
+    pub history: VecDeque<u32>,
+    /// 上限に達したフレームが連続している数
+    pub clamped_frames: u32,
+    /// 上限に達し続けていて、シミュレーションが追いつけていないかどうか
+    pub falling_behind: bool,
+    // 現在のフレームでここまでに実行された固定ステップの数
+    pending_ticks: u32,
+}
+
+impl FixedTickStats {
+    /// 記録されている中で最も多かった固定ステップの数
+    pub fn max_recent(&self) -> u32 {
+        self.history.iter().copied().max().unwrap_or(0)
+    }
+}
+
+/// 固定ステップの上限を仮想時間の最大の進み幅に反映する
+///
+/// 固定ステップの間隔は実行中に変更されることもあるため、毎フレーム計算し直す。
+fn apply_tick_clamp(
+    max_ticks: Res<MaxFixedTicksPerFrame>,
+    fixed_time: Res<Time<Fixed>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    let max_delta = fixed_time.timestep() * max_ticks.0.max(1);
+    if virtual_time.max_delta() != max_delta {
+        virtual_time.set_max_delta(max_delta);
+    }
+}
+
+/// 固定ステップが 1 回実行されるたびに数える
+fn count_fixed_tick(mut stats: ResMut<FixedTickStats>) {
+    stats.pending_ticks += 1;
+}
+
+/// フレームの終わりに、そのフレームで実行された固定ステップの数を記録する
+fn record_frame(
+    mut stats: ResMut<FixedTickStats>,
+    real_time: Res<Time<Real>>,
+    virtual_time: Res<Time<Virtual>>,
+) {
+    let ticks = std::mem::take(&mut stats.pending_ticks);
+    stats.ticks_this_frame = ticks;
+    stats.history.push_front(ticks);
+    stats.history.truncate(HISTORY_LEN);
+
+    // フレーム時間が上限を超え、仮想時間の進みが切り詰められたフレームを数える
+    let clamped = real_time.delta() > virtual_time.max_delta();
+    stats.clamped_frames = if clamped { stats.clamped_frames + 1 } else { 0 };
+
+    let falling_behind = stats.clamped_frames >= FALLING_BEHIND_FRAMES;
+    if falling_behind && !stats.falling_behind {
+        warn!(
+            "fixed update can't keep up: {} ticks per frame for {} frames (frame time {:.1} ms); simulation is running slower than real time",
+            ticks,
+            stats.clamped_frames,
+            duration_ms(real_time.delta()),
+        );
+    } else if !falling_behind && stats.falling_behind {
+        info!("fixed update caught up");
+    }
+    stats.falling_behind = falling_behind;
+}
+
+/// `Duration` をミリ秒の `f64` に変換する
+pub fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
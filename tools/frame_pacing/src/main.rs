@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::Instant};
+use frame_pacing::{duration_ms, FixedTickStats, FramePacingPlugin, MaxFixedTicksPerFrame};
+
+// グラフの位置と大きさ
+const GRAPH_ORIGIN: Vec2 = Vec2::new(-480.0, -250.0);
+const GRAPH_BAR_WIDTH: f32 = 4.0;
+const GRAPH_TICK_HEIGHT: f32 = 25.0;
+// 時間の進みを比べる四角形の速さ (ピクセル/秒) と往復する幅
+const MARKER_SPEED: f32 = 200.0;
+const MARKER_RANGE: f32 = 400.0;
+// 負荷を 1 回の操作で変える量
+const LOAD_STEP: Duration = Duration::from_millis(1);
+
+// 色
+const BAR_COLOR: Color = Color::srgb(0.3, 0.7, 1.0);
+const CLAMPED_BAR_COLOR: Color = Color::srgb(1.0, 0.4, 0.3);
+const LIMIT_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+const REAL_MARKER_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+const FIXED_MARKER_COLOR: Color = Color::srgb(0.3, 0.9, 0.4);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, FramePacingPlugin))
+        .init_resource::<Load>()
+        .add_systems(Startup, setup)
+        .add_systems(FixedUpdate, (simulate_fixed_work, move_fixed_marker))
+        .add_systems(
+            Update,
+            (
+                change_load,
+                simulate_frame_work,
+                move_real_marker,
+                draw_graph,
+                update_status,
+            ),
+        )
+        .run();
+}
+
+/// 人工的な負荷 (指定した時間だけ CPU を占有する)
+#[derive(Resource, Default)]
+struct Load {
+    per_fixed_tick: Duration, // 固定ステップ 1 回ごとの負荷
+    per_frame: Duration,      // フレームごとの負荷
+}
+
+/// 固定ステップで動く四角形 (ゲーム内の時間で動く)
+#[derive(Component)]
+struct FixedMarker;
+
+/// 現実の時間で動く四角形
+#[derive(Component)]
+struct RealMarker;
+
+/// 状態を表示するテキストを示すコンポーネント
+#[derive(Component)]
+struct StatusText;
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Sprite::from_color(REAL_MARKER_COLOR, Vec2::splat(30.0)),
+        Transform::from_xyz(0.0, 60.0, 0.0),
+        RealMarker,
+    ));
+    commands.spawn((
+        Sprite::from_color(FIXED_MARKER_COLOR, Vec2::splat(30.0)),
+        Transform::from_xyz(0.0, 20.0, 0.0),
+        FixedMarker,
+    ));
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StatusText,
+    ));
+}
+
+/// 指定した時間だけ CPU を占有する
+fn busy_wait(duration: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        std::hint::spin_loop();
+    }
+}
+
+/// 固定ステップごとの負荷
+fn simulate_fixed_work(load: Res<Load>) {
+    busy_wait(load.per_fixed_tick);
+}
+
+/// フレームごとの負荷
+fn simulate_frame_work(load: Res<Load>) {
+    busy_wait(load.per_frame);
+}
+
+/// キー操作で負荷と上限を変更する
+fn change_load(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut load: ResMut<Load>,
+    mut max_ticks: ResMut<MaxFixedTicksPerFrame>,
+) {
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        load.per_fixed_tick += LOAD_STEP;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        load.per_fixed_tick = load.per_fixed_tick.saturating_sub(LOAD_STEP);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        load.per_frame += LOAD_STEP * 5;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        load.per_frame = load.per_frame.saturating_sub(LOAD_STEP * 5);
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketRight) {
+        **max_ticks += 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketLeft) {
+        **max_ticks = (**max_ticks - 1).max(1);
+    }
+}
+
+/// 経過時間から、左右に往復する四角形の x 座標を求める
+fn marker_x(elapsed_secs: f32) -> f32 {
+    let distance = (elapsed_secs * MARKER_SPEED) % (MARKER_RANGE * 2.0);
+    (MARKER_RANGE - (distance - MARKER_RANGE).abs()) - MARKER_RANGE / 2.0
+}
+
+/// ゲーム内の時間 (固定ステップの経過時間) で四角形を動かす
+fn move_fixed_marker(mut marker: Single<&mut Transform, With<FixedMarker>>, time: Res<Time>) {
+    marker.translation.x = marker_x(time.elapsed_secs());
+}
+
+/// 現実の時間で四角形を動かす (上限で時間が切り捨てられると、固定ステップ側が遅れていく)
+fn move_real_marker(mut marker: Single<&mut Transform, With<RealMarker>>, time: Res<Time<Real>>) {
+    marker.translation.x = marker_x(time.elapsed_secs());
+}
+
+/// フレームごとの固定ステップ数を棒グラフで描く
+fn draw_graph(
+    mut gizmos: Gizmos,
+    stats: Res<FixedTickStats>,
+    max_ticks: Res<MaxFixedTicksPerFrame>,
+) {
+    for (index, &ticks) in stats.history.iter().enumerate() {
+        let x = GRAPH_ORIGIN.x + index as f32 * GRAPH_BAR_WIDTH;
+        let color = if ticks >= **max_ticks {
+            CLAMPED_BAR_COLOR
+        } else {
+            BAR_COLOR
+        };
+        gizmos.line_2d(
+            Vec2::new(x, GRAPH_ORIGIN.y),
+            Vec2::new(x, GRAPH_ORIGIN.y + ticks as f32 * GRAPH_TICK_HEIGHT),
+            color,
+        );
+    }
+
+    // 上限の線
+    let limit_y = GRAPH_ORIGIN.y + **max_ticks as f32 * GRAPH_TICK_HEIGHT;
+    let graph_width = stats.history.len() as f32 * GRAPH_BAR_WIDTH;
+    gizmos.line_2d(
+        Vec2::new(GRAPH_ORIGIN.x, limit_y),
+        Vec2::new(GRAPH_ORIGIN.x + graph_width, limit_y),
+        LIMIT_COLOR,
+    );
+}
+
+/// 計測結果を表示する
+fn update_status(
+    mut status: Single<&mut Text, With<StatusText>>,
+    stats: Res<FixedTickStats>,
+    max_ticks: Res<MaxFixedTicksPerFrame>,
+    load: Res<Load>,
+    real_time: Res<Time<Real>>,
+    virtual_time: Res<Time<Virtual>>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    let lag = real_time.elapsed_secs_f64() - virtual_time.elapsed_secs_f64();
+    let state = if stats.falling_behind {
+        "FALLING BEHIND (simulation slowed down to avoid a death spiral)"
+    } else if stats.clamped_frames > 0 {
+        "clamped"
+    } else {
+        "OK"
+    };
+    status.0 = format!(
+        "Up/Down: work per fixed tick, Left/Right: work per frame, [ / ]: max ticks per frame\n\n\
+         Fixed timestep: {:.2} ms\n\
+         Frame time: {:.2} ms\n\
+         Fixed ticks this frame: {} (max recent {})\n\
+         Max fixed ticks per frame: {}\n\
+         Overstep: {:.0}%\n\
+         Work per fixed tick: {} ms, per frame: {} ms\n\
+         Game time behind real time: {:.2} s\n\
+         Status: {}",
+        duration_ms(fixed_time.timestep()),
+        duration_ms(real_time.delta()),
+        stats.ticks_this_frame,
+        stats.max_recent(),
+        **max_ticks,
+        fixed_time.overstep_fraction() * 100.0,
+        load.per_fixed_tick.as_millis(),
+        load.per_frame.as_millis(),
+        lag,
+        state,
+    );
+}
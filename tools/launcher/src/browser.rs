@@ -0,0 +1,358 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+
+use crate::{
+    catalog::{Catalog, CatalogEntry},
+    launch::{Launch, LaunchStatuses},
+};
+
+// サンプルの一覧のアセット
+const CATALOG_PATH: &str = "examples.catalog.ron";
+// 一覧の 1 行の高さ (スクロール位置の計算に使うため固定)
+const ITEM_HEIGHT: f32 = 84.0;
+// サムネイルの大きさ (スクリーンショットと同じ 3:2)
+const THUMBNAIL_SIZE: Vec2 = Vec2::new(96.0, 64.0);
+// マウスホイール 1 行分のスクロール量
+const SCROLL_LINE_HEIGHT: f32 = 24.0;
+// 文字の大きさ
+const TITLE_FONT_SIZE: f32 = 28.0;
+const NAME_FONT_SIZE: f32 = 20.0;
+const DESCRIPTION_FONT_SIZE: f32 = 14.0;
+// 色
+const BACKGROUND_COLOR: Color = Color::srgb(0.08, 0.08, 0.1);
+const ITEM_COLOR: Color = Color::srgb(0.13, 0.13, 0.16);
+const HOVERED_ITEM_COLOR: Color = Color::srgb(0.18, 0.18, 0.22);
+const SELECTED_ITEM_COLOR: Color = Color::srgb(0.2, 0.3, 0.5);
+const PLACEHOLDER_COLOR: Color = Color::srgb(0.25, 0.25, 0.3);
+const TEXT_COLOR: Color = Color::srgb(0.95, 0.95, 0.95);
+const SUBTEXT_COLOR: Color = Color::srgb(0.65, 0.65, 0.7);
+const CATEGORY_COLOR: Color = Color::srgb(0.5, 0.8, 1.0);
+
+// 決定キーで何をするか (フッターの操作説明に使う)
+#[cfg(not(target_arch = "wasm32"))]
+const LAUNCH_VERB: &str = "launch";
+#[cfg(target_arch = "wasm32")]
+const LAUNCH_VERB: &str = "open";
+
+/// サンプルの一覧をスクロールできるリストで表示し、選んだものを起動するプラグイン
+pub struct BrowserPlugin;
+
+impl Plugin for BrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Selected>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    spawn_items,
+                    (select_with_keys, select_with_mouse).run_if(resource_exists::<Entries>),
+                    scroll_with_wheel,
+                    scroll_to_selected.run_if(resource_changed::<Selected>),
+                    highlight_items,
+                    update_status_line.run_if(resource_exists::<Entries>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// 読み込み中のサンプルの一覧
+#[derive(Resource)]
+struct CatalogHandle(Handle<Catalog>);
+
+/// 読み込み済みのサンプルの一覧 (リストの行と同じ順番)
+#[derive(Resource)]
+struct Entries(Vec<CatalogEntry>);
+
+/// 選択中の行の番号
+#[derive(Resource, Default)]
+struct Selected(usize);
+
+/// スクロールできるリストを示すコンポーネント
+#[derive(Component)]
+struct ExampleList;
+
+/// リストの行を示すコンポーネント (何番目のサンプルか)
+#[derive(Component)]
+struct ExampleItem(usize);
+
+/// 画面下部の、選択中のサンプルの状態と操作説明を表示するテキスト
+#[derive(Component)]
+struct StatusLine;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2d);
+    commands.insert_resource(CatalogHandle(asset_server.load(CATALOG_PATH)));
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(16.0)),
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(BACKGROUND_COLOR),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Examples"),
+                TextFont {
+                    font_size: TITLE_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(TEXT_COLOR),
+            ));
+            // 残りの高さをすべて使い、はみ出した行は縦にスクロールして見る
+            parent.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    flex_grow: 1.0,
+                    // flex_grow で伸びる子がはみ出さないよう、最小の高さを 0 にしておく
+                    min_height: Val::Px(0.0),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                ExampleList,
+            ));
+            parent.spawn((
+                Text::new("Loading catalog..."),
+                TextFont {
+                    font_size: DESCRIPTION_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(SUBTEXT_COLOR),
+                StatusLine,
+            ));
+        });
+}
+
+/// サンプルの一覧を読み込んだら、リストの行を作る
+fn spawn_items(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<Catalog>>,
+    catalog_handle: Res<CatalogHandle>,
+    catalogs: Res<Assets<Catalog>>,
+    asset_server: Res<AssetServer>,
+    list: Single<Entity, With<ExampleList>>,
+) {
+    let loaded = asset_events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(&catalog_handle.0));
+    if !loaded {
+        return;
+    }
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        return;
+    };
+
+    commands.entity(*list).with_children(|parent| {
+        for (index, entry) in catalog.entries.iter().enumerate() {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        height: Val::Px(ITEM_HEIGHT),
+                        // 親の高さに合わせて縮まないようにする
+                        flex_shrink: 0.0,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(12.0),
+                        padding: UiRect::axes(Val::Px(10.0), Val::Px(8.0)),
+                        border: UiRect::bottom(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BorderColor(BACKGROUND_COLOR),
+                    BackgroundColor(ITEM_COLOR),
+                    ExampleItem(index),
+                ))
+                .with_children(|item| {
+                    spawn_thumbnail(item, entry, &asset_server);
+                    item.spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    })
+                    .with_children(|texts| {
+                        texts
+                            .spawn((
+                                Text::new(entry.name.clone()),
+                                TextFont {
+                                    font_size: NAME_FONT_SIZE,
+                                    ..default()
+                                },
+                                TextColor(TEXT_COLOR),
+                            ))
+                            .with_child((
+                                TextSpan::new(format!("  {}", entry.category)),
+                                TextFont {
+                                    font_size: DESCRIPTION_FONT_SIZE,
+                                    ..default()
+                                },
+                                TextColor(CATEGORY_COLOR),
+                            ));
+                        texts.spawn((
+                            Text::new(entry.description.clone()),
+                            TextFont {
+                                font_size: DESCRIPTION_FONT_SIZE,
+                                ..default()
+                            },
+                            TextColor(SUBTEXT_COLOR),
+                        ));
+                    });
+                });
+        }
+    });
+    commands.insert_resource(Entries(catalog.entries.clone()));
+}
+
+/// スクリーンショットがあればサムネイルとして、なければ分類名を書いた四角形を表示する
+fn spawn_thumbnail(parent: &mut ChildBuilder, entry: &CatalogEntry, asset_server: &AssetServer) {
+    let node = Node {
+        width: Val::Px(THUMBNAIL_SIZE.x),
+        height: Val::Px(THUMBNAIL_SIZE.y),
+        flex_shrink: 0.0,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    match &entry.thumbnail {
+        // スクリーンショットは各サンプルのディレクトリにあるため、リポジトリのルートから読む
+        Some(path) => {
+            parent.spawn((
+                node,
+                ImageNode::new(asset_server.load(format!("repo://{path}"))),
+            ));
+        }
+        None => {
+            parent
+                .spawn((node, BackgroundColor(PLACEHOLDER_COLOR)))
+                .with_child((
+                    Text::new(entry.category.clone()),
+                    TextFont {
+                        font_size: DESCRIPTION_FONT_SIZE,
+                        ..default()
+                    },
+                    TextColor(SUBTEXT_COLOR),
+                ));
+        }
+    }
+}
+
+/// 上下キーで選択を動かし、`Enter` キーで選択中のサンプルを起動する
+fn select_with_keys(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    entries: Res<Entries>,
+    mut selected: ResMut<Selected>,
+    mut launch_events: EventWriter<Launch>,
+) {
+    let last = entries.0.len().saturating_sub(1);
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) && selected.0 < last {
+        selected.0 += 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) && selected.0 > 0 {
+        selected.0 -= 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        if let Some(entry) = entries.0.get(selected.0) {
+            launch_events.send(Launch(entry.clone()));
+        }
+    }
+}
+
+/// 行をクリックすると選択し、選択中の行をもう一度クリックすると起動する
+fn select_with_mouse(
+    items: Query<(&Interaction, &ExampleItem), Changed<Interaction>>,
+    entries: Res<Entries>,
+    mut selected: ResMut<Selected>,
+    mut launch_events: EventWriter<Launch>,
+) {
+    for (interaction, item) in &items {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if selected.0 == item.0 {
+            launch_events.send(Launch(entries.0[item.0].clone()));
+        } else {
+            selected.0 = item.0;
+        }
+    }
+}
+
+/// マウスホイールでリストをスクロールする
+///
+/// 範囲外へのスクロールは UI のレイアウト計算で自動的に切り詰められる。
+fn scroll_with_wheel(
+    mut wheel_events: EventReader<MouseWheel>,
+    mut list: Single<&mut ScrollPosition, With<ExampleList>>,
+) {
+    for event in wheel_events.read() {
+        let dy = match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        list.offset_y -= dy;
+    }
+}
+
+/// 選択中の行がリストの表示範囲に入るようにスクロールする
+fn scroll_to_selected(
+    selected: Res<Selected>,
+    mut list: Single<(&mut ScrollPosition, &ComputedNode), With<ExampleList>>,
+) {
+    let (scroll, node) = &mut *list;
+    // ComputedNode の大きさは物理ピクセル、ScrollPosition は論理ピクセル
+    let visible_height = node.size().y * node.inverse_scale_factor();
+    // まだレイアウトが計算されていない
+    if visible_height <= 0.0 {
+        return;
+    }
+    let top = selected.0 as f32 * ITEM_HEIGHT;
+    let bottom = top + ITEM_HEIGHT;
+    if top < scroll.offset_y {
+        scroll.offset_y = top;
+    } else if bottom > scroll.offset_y + visible_height {
+        scroll.offset_y = bottom - visible_height;
+    }
+}
+
+/// 選択中の行とカーソルが乗っている行の背景色を変える
+fn highlight_items(
+    selected: Res<Selected>,
+    mut items: Query<(&Interaction, &ExampleItem, &mut BackgroundColor)>,
+) {
+    for (interaction, item, mut background) in &mut items {
+        let color = if item.0 == selected.0 {
+            SELECTED_ITEM_COLOR
+        } else if *interaction == Interaction::Hovered {
+            HOVERED_ITEM_COLOR
+        } else {
+            ITEM_COLOR
+        };
+        background.set_if_neq(BackgroundColor(color));
+    }
+}
+
+/// 選択中のサンプルのディレクトリと起動状態、操作説明を表示する
+fn update_status_line(
+    entries: Res<Entries>,
+    selected: Res<Selected>,
+    statuses: Res<LaunchStatuses>,
+    mut status_line: Single<&mut Text, With<StatusLine>>,
+) {
+    let Some(entry) = entries.0.get(selected.0) else {
+        status_line.0 = "The catalog is empty".to_string();
+        return;
+    };
+    let status = statuses
+        .get(&entry.path)
+        .map(|status| status.label())
+        .unwrap_or_else(|| "not launched".to_string());
+    status_line.0 = format!(
+        "{} ({status})\nUp/Down: select   Enter or click again: {LAUNCH_VERB}   Wheel: scroll",
+        entry.path
+    );
+}
@@ -0,0 +1,69 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// サンプルの一覧のアセットを読み込めるようにするプラグイン
+pub struct CatalogPlugin;
+
+impl Plugin for CatalogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Catalog>()
+            .init_asset_loader::<CatalogLoader>();
+    }
+}
+
+/// ランチャーに並べるサンプルの一覧 (`*.catalog.ron`)
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct Catalog {
+    pub entries: Vec<CatalogEntry>,
+}
+
+/// 一覧の 1 つのサンプル
+#[derive(Deserialize, Debug, Clone)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub path: String,              // リポジトリのルートからのクレートのディレクトリ
+    pub category: String,          // 2d・audio などの分類 (ディレクトリ名と同じ)
+    pub description: String,       // 一覧に表示する 1〜2 行の説明
+    pub thumbnail: Option<String>, // リポジトリのルートからのスクリーンショットのパス
+    // wasm 版で開くページ (ネイティブでは読まない)
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pub web: Option<String>,
+}
+
+/// サンプルの一覧の読み込み時に発生するエラー
+#[derive(Debug, Error)]
+pub enum CatalogLoaderError {
+    #[error("could not read catalog: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse catalog: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+/// RON 形式のサンプルの一覧を読み込むアセットローダー
+#[derive(Default)]
+struct CatalogLoader;
+
+impl AssetLoader for CatalogLoader {
+    type Asset = Catalog;
+    type Settings = ();
+    type Error = CatalogLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Catalog, CatalogLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["catalog.ron"]
+    }
+}
@@ -0,0 +1,142 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::catalog::CatalogEntry;
+
+// wasm 版でページが登録されていないサンプルを開くときの、ソースコードの URL
+#[cfg(target_arch = "wasm32")]
+const SOURCE_URL: &str = "https://github.com/Ometeor-Zheero-OMZ/bevy_for_study/tree/main";
+
+/// 選んだサンプルを起動するプラグイン
+///
+/// ネイティブではサンプルのディレクトリで `cargo run` を子プロセスとして実行し、
+/// wasm ではプロセスを起動できないため、サンプルのページを新しいタブで開く。
+pub struct LaunchPlugin;
+
+impl Plugin for LaunchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Launch>()
+            .init_resource::<LaunchStatuses>()
+            .add_systems(Update, launch_examples);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Update, poll_children.after(launch_examples));
+    }
+}
+
+/// サンプルの起動を要求するイベント
+#[derive(Event)]
+pub struct Launch(pub CatalogEntry);
+
+/// サンプルの起動状態
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchStatus {
+    Running,        // 子プロセスが実行中 (ビルド中を含む)
+    Exited(i32),    // 子プロセスが終了した (終了コード。シグナルで終了した場合は -1)
+    Failed(String), // 起動できなかった
+    #[cfg(target_arch = "wasm32")]
+    Opened(String), // wasm 版でページを開いた (開いた URL)
+}
+
+impl LaunchStatus {
+    /// 画面に表示する文字列
+    pub fn label(&self) -> String {
+        match self {
+            LaunchStatus::Running => "running".to_string(),
+            LaunchStatus::Exited(0) => "exited".to_string(),
+            LaunchStatus::Exited(code) => format!("exited with code {code}"),
+            LaunchStatus::Failed(error) => format!("failed: {error}"),
+            #[cfg(target_arch = "wasm32")]
+            LaunchStatus::Opened(url) => format!("opened {url}"),
+        }
+    }
+}
+
+/// サンプルごと (ディレクトリのパスごと) の起動状態
+#[derive(Resource, Default)]
+pub struct LaunchStatuses {
+    statuses: HashMap<String, LaunchStatus>,
+    // 実行中の子プロセス (サンプルのパスと組にして持つ)
+    #[cfg(not(target_arch = "wasm32"))]
+    children: Vec<(String, std::process::Child)>,
+}
+
+impl LaunchStatuses {
+    /// サンプルの起動状態 (一度も起動していなければ `None`)
+    pub fn get(&self, path: &str) -> Option<&LaunchStatus> {
+        self.statuses.get(path)
+    }
+}
+
+/// 要求されたサンプルを `cargo run` で起動する
+#[cfg(not(target_arch = "wasm32"))]
+fn launch_examples(mut launch_events: EventReader<Launch>, mut statuses: ResMut<LaunchStatuses>) {
+    use std::process::{Command, Stdio};
+
+    for Launch(entry) in launch_events.read() {
+        // 同じサンプルを二重に起動すると、ビルドディレクトリのロック待ちになるだけなので起動しない
+        if statuses.get(&entry.path) == Some(&LaunchStatus::Running) {
+            info!("{} is already running", entry.name);
+            continue;
+        }
+        // ランチャーのクレートは tools/launcher にあるため、2 つ上がリポジトリのルート
+        let directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join(&entry.path);
+        // ビルドの進み具合やログはランチャーを起動した端末にそのまま流す
+        let status = match Command::new("cargo")
+            .arg("run")
+            .current_dir(&directory)
+            .stdin(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => {
+                info!("launched {} (pid {})", entry.name, child.id());
+                statuses.children.push((entry.path.clone(), child));
+                LaunchStatus::Running
+            }
+            Err(error) => {
+                error!("could not launch {}: {error}", entry.name);
+                LaunchStatus::Failed(error.to_string())
+            }
+        };
+        statuses.statuses.insert(entry.path.clone(), status);
+    }
+}
+
+/// 要求されたサンプルのページを新しいタブで開く
+#[cfg(target_arch = "wasm32")]
+fn launch_examples(mut launch_events: EventReader<Launch>, mut statuses: ResMut<LaunchStatuses>) {
+    for Launch(entry) in launch_events.read() {
+        let url = entry
+            .web
+            .clone()
+            .unwrap_or_else(|| format!("{SOURCE_URL}/{}", entry.path));
+        let Some(window) = web_sys::window() else {
+            continue;
+        };
+        // ポップアップがブロックされたときは、今のタブでページを開く
+        let opened = match window.open_with_url_and_target(&url, "_blank") {
+            Ok(Some(_)) => Ok(()),
+            _ => window.location().set_href(&url),
+        };
+        let status = match opened {
+            Ok(()) => LaunchStatus::Opened(url),
+            Err(error) => LaunchStatus::Failed(format!("{error:?}")),
+        };
+        statuses.statuses.insert(entry.path.clone(), status);
+    }
+}
+
+/// 実行中の子プロセスが終了したかどうかを調べる
+#[cfg(not(target_arch = "wasm32"))]
+fn poll_children(mut statuses: ResMut<LaunchStatuses>) {
+    let LaunchStatuses { statuses, children } = &mut *statuses;
+    children.retain_mut(|(path, child)| {
+        let status = match child.try_wait() {
+            Ok(None) => return true,
+            Ok(Some(exit_status)) => LaunchStatus::Exited(exit_status.code().unwrap_or(-1)),
+            Err(error) => LaunchStatus::Failed(error.to_string()),
+        };
+        statuses.insert(path.clone(), status);
+        false
+    });
+}
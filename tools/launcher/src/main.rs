@@ -0,0 +1,34 @@
+//! リポジトリのサンプルを一覧から選んで起動するランチャー
+
+use bevy::{asset::io::AssetSourceBuilder, prelude::*};
+
+mod browser;
+mod catalog;
+mod launch;
+
+use browser::BrowserPlugin;
+use catalog::CatalogPlugin;
+use launch::LaunchPlugin;
+
+// リポジトリのルート (このクレートのディレクトリから見た相対パス)
+const REPO_ROOT: &str = "../..";
+
+fn main() {
+    App::new()
+        // サムネイルは各サンプルの img ディレクトリにあるため、リポジトリのルートを
+        // "repo://" から始まるパスで読めるようにする (DefaultPlugins より先に登録する必要がある)
+        .register_asset_source(
+            "repo",
+            AssetSourceBuilder::platform_default(REPO_ROOT, None),
+        )
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Example Browser".to_string(),
+                resolution: (900.0, 700.0).into(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins((CatalogPlugin, LaunchPlugin, BrowserPlugin))
+        .run();
+}
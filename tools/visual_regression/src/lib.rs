@@ -0,0 +1,324 @@
+//! サンプルを決まったフレーム数だけ動かして画面を撮り、保存しておいた正解の画像 (ゴールデンイメージ) と
+//! 1 ピクセルずつ比べるプラグイン。
+//!
+//! 環境変数 `VISUAL_REGRESSION_GOLDEN` が設定されているときだけ働き、設定されていなければ何もしない。
+//! 働いているときは、ウィンドウを隠して画像に描き、比べ終わると結果に合わせた終了コードでアプリを終了する。
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{
+    asset::{LoadState, UntypedAssetId},
+    image::IntoDynamicImageError,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::screenshot::{Screenshot, ScreenshotCaptured},
+    },
+    time::TimeUpdateStrategy,
+    window::{PrimaryWindow, WindowRef},
+};
+use image::{Rgb, RgbImage};
+use thiserror::Error;
+
+// 設定を受け取る環境変数 (`src/main.rs` がサンプルを起動するときに設定する)
+pub const GOLDEN_VAR: &str = "VISUAL_REGRESSION_GOLDEN"; // 正解の画像のパス
+pub const FRAMES_VAR: &str = "VISUAL_REGRESSION_FRAMES"; // 画面を撮るまでのフレーム数
+pub const TOLERANCE_VAR: &str = "VISUAL_REGRESSION_TOLERANCE"; // 同じとみなす色の差
+pub const UPDATE_VAR: &str = "VISUAL_REGRESSION_UPDATE"; // 比べずに正解の画像を書き換える
+
+// テストとして動かすときの乱数の種
+const SEED: u64 = 0x5EED;
+const DEFAULT_FRAMES: u32 = 60;
+const DEFAULT_TOLERANCE: u8 = 2;
+// 1 フレームで進める時間 (実際にかかった時間によらず、毎回この時間だけ進める)
+const FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// テストとして動かすときの設定
+#[derive(Resource, Debug, Clone)]
+pub struct RegressionConfig {
+    pub golden: PathBuf,
+    pub frames: u32,   // アセットを読み込み終えてから画面を撮るまでのフレーム数
+    pub tolerance: u8, // 色の各成分の差がこれ以下なら同じピクセルとみなす
+    pub update: bool,  // 比べずに、撮った画像を正解の画像として保存する
+}
+
+impl RegressionConfig {
+    /// 環境変数から設定を読み取る (`VISUAL_REGRESSION_GOLDEN` がなければ `None`)
+    pub fn from_env() -> Option<RegressionConfig> {
+        let golden = env::var_os(GOLDEN_VAR)?;
+        Some(RegressionConfig {
+            golden: PathBuf::from(golden),
+            frames: env::var(FRAMES_VAR)
+                .ok()
+                .and_then(|frames| frames.parse().ok())
+                .unwrap_or(DEFAULT_FRAMES),
+            tolerance: env::var(TOLERANCE_VAR)
+                .ok()
+                .and_then(|tolerance| tolerance.parse().ok())
+                .unwrap_or(DEFAULT_TOLERANCE),
+            update: env::var_os(UPDATE_VAR).is_some(),
+        })
+    }
+}
+
+/// テストとして動かしているときの乱数の種 (普段は `None`)
+///
+/// 乱数を使うサンプルは、これが `Some` なら `StdRng::seed_from_u64` で乱数生成器を作り、毎回同じ画面にする。
+pub fn fixed_seed() -> Option<u64> {
+    env::var_os(GOLDEN_VAR).map(|_| SEED)
+}
+
+/// 環境変数が設定されているときだけ、サンプルをテストとして動かすプラグイン
+pub struct VisualRegressionPlugin;
+
+impl Plugin for VisualRegressionPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(config) = RegressionConfig::from_env() else {
+            return;
+        };
+        info!(
+            "running as a visual regression test against {}",
+            config.golden.display()
+        );
+        app.insert_resource(config)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME))
+            .init_resource::<Progress>()
+            .add_systems(PreStartup, setup_render_target)
+            .add_systems(First, retarget_cameras)
+            .add_systems(Last, (wait_for_assets, capture).chain());
+    }
+}
+
+/// テストの進み具合
+#[derive(Resource, Default)]
+struct Progress {
+    target: Handle<Image>, // カメラが描く画像 (ウィンドウの代わり)
+    ready: bool,           // アセットを読み込み終えたか (それまでは仮想時間を止めておく)
+    frames: u32,           // アセットを読み込み終えてから進めたフレーム数
+    requested: bool,       // 画面を撮るように頼んだか
+}
+
+/// ウィンドウを隠し、ウィンドウと同じ論理サイズの画像をカメラの描き先として用意する
+///
+/// 物理サイズではなく論理サイズにするので、画面の拡大率が違う環境でも同じ大きさの画像になる。
+fn setup_render_target(
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    mut images: ResMut<Assets<Image>>,
+    mut progress: ResMut<Progress>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let size = Extent3d {
+        width: (window.width() as u32).max(1),
+        height: (window.height() as u32).max(1),
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage |=
+        TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING;
+    progress.target = images.add(image);
+
+    window.visible = false;
+    time.pause();
+}
+
+/// ウィンドウに描くカメラを、用意した画像に描くように変える
+///
+/// UI は既定ではウィンドウに描くカメラを使うので、描き先を変えたカメラに `IsDefaultUiCamera` を付ける。
+fn retarget_cameras(
+    mut commands: Commands,
+    progress: Res<Progress>,
+    mut cameras: Query<(Entity, &mut Camera), Added<Camera>>,
+    default_ui_cameras: Query<(), With<IsDefaultUiCamera>>,
+) {
+    let mut has_ui_camera = !default_ui_cameras.is_empty();
+    for (entity, mut camera) in &mut cameras {
+        if !matches!(camera.target, RenderTarget::Window(WindowRef::Primary)) {
+            continue;
+        }
+        camera.target = RenderTarget::Image(progress.target.clone());
+        if !has_ui_camera {
+            commands.entity(entity).insert(IsDefaultUiCamera);
+            has_ui_camera = true;
+        }
+    }
+}
+
+/// スプライト・UI の画像・フォントを読み込み終えたら、仮想時間を進め始める
+///
+/// 読み込みにかかる時間は毎回違うので、読み込み終えてからのフレーム数で画面を撮る。
+fn wait_for_assets(
+    mut progress: ResMut<Progress>,
+    asset_server: Res<AssetServer>,
+    sprites: Query<&Sprite>,
+    image_nodes: Query<&ImageNode>,
+    fonts: Query<&TextFont>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if progress.ready {
+        return;
+    }
+    let loading =
+        |id: UntypedAssetId| matches!(asset_server.get_load_state(id), Some(LoadState::Loading));
+    let pending = sprites
+        .iter()
+        .any(|sprite| loading(sprite.image.id().untyped()))
+        || image_nodes
+            .iter()
+            .any(|node| loading(node.image.id().untyped()))
+        || fonts.iter().any(|font| loading(font.font.id().untyped()));
+    if !pending {
+        progress.ready = true;
+        time.unpause();
+    }
+}
+
+/// 決まったフレーム数だけ進めたら、描き先の画像を撮る
+fn capture(mut commands: Commands, mut progress: ResMut<Progress>, config: Res<RegressionConfig>) {
+    if !progress.ready || progress.requested {
+        return;
+    }
+    progress.frames += 1;
+    if progress.frames < config.frames {
+        return;
+    }
+    progress.requested = true;
+    commands
+        .spawn(Screenshot::image(progress.target.clone()))
+        .observe(compare_with_golden);
+}
+
+/// 撮った画像を正解の画像と比べて結果を表示し、結果に合わせた終了コードでアプリを終了する
+fn compare_with_golden(
+    trigger: Trigger<ScreenshotCaptured>,
+    config: Res<RegressionConfig>,
+    mut exit: EventWriter<AppExit>,
+) {
+    match check(&trigger.event().0, &config) {
+        Ok(Outcome::Saved) => {
+            info!("saved the golden image to {}", config.golden.display());
+            exit.send(AppExit::Success);
+        }
+        Ok(Outcome::Matched { max_difference }) => {
+            info!("the screenshot matches the golden image (max difference {max_difference})");
+            exit.send(AppExit::Success);
+        }
+        Err(error) => {
+            error!("visual regression: {error}");
+            exit.send(AppExit::error());
+        }
+    }
+}
+
+/// 比べた結果 (違っていたときは [`RegressionError::Mismatch`])
+enum Outcome {
+    Saved,
+    Matched { max_difference: u8 },
+}
+
+/// テストで発生するエラー
+#[derive(Debug, Error)]
+pub enum RegressionError {
+    #[error("could not convert the screenshot: {0}")]
+    Convert(#[from] IntoDynamicImageError),
+    #[error("could not read or write an image: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("could not create the golden directory: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("the screenshot is {actual:?} but the golden image is {expected:?}")]
+    SizeMismatch {
+        actual: (u32, u32),
+        expected: (u32, u32),
+    },
+    #[error("{pixels} pixel(s) differ by more than {tolerance} (max difference {max_difference}), see {diff}")]
+    Mismatch {
+        pixels: usize,
+        tolerance: u8,
+        max_difference: u8,
+        diff: String,
+    },
+}
+
+/// 撮った画像を正解の画像と比べる (正解の画像がないか、書き換えるときは保存する)
+///
+/// 違っていたときは、違うピクセルを赤くした画像を正解の画像の隣 (`<name>.diff.png`) に保存する。
+fn check(screenshot: &Image, config: &RegressionConfig) -> Result<Outcome, RegressionError> {
+    // HDR のカメラではアルファに明るさが入るので、色だけを比べる
+    let actual = screenshot.clone().try_into_dynamic()?.to_rgb8();
+    if config.update || !config.golden.exists() {
+        save(&actual, &config.golden)?;
+        return Ok(Outcome::Saved);
+    }
+
+    let expected = image::open(&config.golden)?.to_rgb8();
+    if actual.dimensions() != expected.dimensions() {
+        return Err(RegressionError::SizeMismatch {
+            actual: actual.dimensions(),
+            expected: expected.dimensions(),
+        });
+    }
+    let comparison = compare(&actual, &expected, config.tolerance);
+    if comparison.pixels == 0 {
+        return Ok(Outcome::Matched {
+            max_difference: comparison.max_difference,
+        });
+    }
+    let diff = config.golden.with_extension("diff.png");
+    save(&comparison.diff, &diff)?;
+    Err(RegressionError::Mismatch {
+        pixels: comparison.pixels,
+        tolerance: config.tolerance,
+        max_difference: comparison.max_difference,
+        diff: diff.display().to_string(),
+    })
+}
+
+fn save(image: &RgbImage, path: &Path) -> Result<(), RegressionError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    image.save(path)?;
+    Ok(())
+}
+
+/// 2 つの画像を比べた結果
+pub struct Comparison {
+    pub pixels: usize,      // 色の差が許容値を超えたピクセルの数
+    pub max_difference: u8, // 色の成分の差の最大値
+    pub diff: RgbImage,     // 違うピクセルを赤く、ほかを暗い灰色にした画像
+}
+
+/// 同じ大きさの 2 つの画像を 1 ピクセルずつ比べる
+pub fn compare(actual: &RgbImage, expected: &RgbImage, tolerance: u8) -> Comparison {
+    let mut pixels = 0;
+    let mut max_difference = 0;
+    let diff = RgbImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let (a, e) = (actual.get_pixel(x, y), expected.get_pixel(x, y));
+        let difference = (0..3).map(|c| a[c].abs_diff(e[c])).max().unwrap_or(0);
+        max_difference = max_difference.max(difference);
+        if difference > tolerance {
+            pixels += 1;
+            Rgb([255, 0, 0])
+        } else {
+            // 元の絵がわかるように、正解の画像の明るさを落として残す
+            let luma = (e[0] as u16 + e[1] as u16 + e[2] as u16) / 3 / 4;
+            Rgb([luma as u8; 3])
+        }
+    });
+    Comparison {
+        pixels,
+        max_difference,
+        diff,
+    }
+}
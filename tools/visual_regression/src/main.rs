@@ -0,0 +1,117 @@
+//! `cases.ron` に並べたサンプルを 1 つずつテストとして `cargo run` し、結果をまとめて表示する。
+//!
+//! ```sh
+//! cargo run                     # すべてのサンプルを正解の画像と比べる
+//! cargo run -- move_sprite      # 名前を指定したサンプルだけを比べる
+//! cargo run -- --update         # 比べずに正解の画像を撮り直す
+//! ```
+
+use std::{
+    fs,
+    path::Path,
+    process::{Command, ExitCode, Stdio},
+};
+
+use serde::Deserialize;
+use visual_regression::{FRAMES_VAR, GOLDEN_VAR, TOLERANCE_VAR, UPDATE_VAR};
+
+/// テストとして動かすサンプルの一覧 (`cases.ron`)
+#[derive(Deserialize)]
+struct Cases {
+    cases: Vec<Case>,
+}
+
+/// 一覧の 1 つのサンプル
+#[derive(Deserialize)]
+struct Case {
+    name: String,          // 正解の画像のファイル名 (`golden/<name>.png`)
+    path: String,          // リポジトリのルートからのクレートのディレクトリ
+    frames: Option<u32>,   // 画面を撮るまでのフレーム数 (なければプラグインの既定値)
+    tolerance: Option<u8>, // 同じとみなす色の差 (なければプラグインの既定値)
+}
+
+fn main() -> ExitCode {
+    let mut update = false;
+    let mut names = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--update" => update = true,
+            _ => names.push(arg),
+        }
+    }
+
+    // このクレートは tools/visual_regression にあるため、2 つ上がリポジトリのルート
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let root = manifest_dir.join("../..");
+    let cases: Cases = match fs::read_to_string(manifest_dir.join("cases.ron"))
+        .map_err(|error| error.to_string())
+        .and_then(|text| ron::from_str(&text).map_err(|error| error.to_string()))
+    {
+        Ok(cases) => cases,
+        Err(error) => {
+            eprintln!("could not read cases.ron: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = Vec::new();
+    let mut ran = 0;
+    for case in &cases.cases {
+        if !names.is_empty() && !names.contains(&case.name) {
+            continue;
+        }
+        ran += 1;
+        println!("--- {} ({})", case.name, case.path);
+
+        let mut command = Command::new("cargo");
+        command
+            .arg("run")
+            .current_dir(root.join(&case.path))
+            .env(
+                GOLDEN_VAR,
+                manifest_dir
+                    .join("golden")
+                    .join(format!("{}.png", case.name)),
+            )
+            .stdin(Stdio::null());
+        if let Some(frames) = case.frames {
+            command.env(FRAMES_VAR, frames.to_string());
+        }
+        if let Some(tolerance) = case.tolerance {
+            command.env(TOLERANCE_VAR, tolerance.to_string());
+        }
+        if update {
+            command.env(UPDATE_VAR, "1");
+        }
+
+        // ビルドの失敗も、画像が違ったとき (サンプルがエラーの終了コードで終わる) も失敗として数える
+        match command.status() {
+            Ok(status) if status.success() => println!("ok: {}", case.name),
+            Ok(status) => {
+                println!("FAILED: {} ({status})", case.name);
+                failed.push(case.name.as_str());
+            }
+            Err(error) => {
+                println!("FAILED: {} (could not run cargo: {error})", case.name);
+                failed.push(case.name.as_str());
+            }
+        }
+    }
+
+    println!();
+    if ran == 0 {
+        println!("no cases matched {names:?}");
+        return ExitCode::FAILURE;
+    }
+    if failed.is_empty() {
+        println!("{ran} case(s) passed");
+        ExitCode::SUCCESS
+    } else {
+        println!(
+            "{} of {ran} case(s) failed: {}",
+            failed.len(),
+            failed.join(", ")
+        );
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,428 @@
+//! ゲームパッドだけで文字を入力するための画面上のキーボード (ソフトウェアキーボード)。
+//!
+//! - QWERTY 配列のキーを格子状に並べ、十字キーか左スティックで選び、決定ボタンで入力する。
+//! - 入力した文字は物理キーボードと同じ [`KeyboardInput`] イベントとして送るため、
+//!   `KeyboardInput` の `logical_key` を読むテキスト入力欄は、変更なしでゲームパッドから使える。
+//! - マウスでキーをクリックしても入力できる。
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKeyCode},
+        ButtonState, InputSystem,
+    },
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+// キーボードの横方向のマス数 (幅の広いキーは複数のマスを使う)
+const COLUMNS: usize = 10;
+// 文字キーの配列 (上から順に 1 行ずつ)
+const CHARACTER_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl-", "zxcvbnm,._"];
+// 1 マスの大きさ (ピクセル)
+const KEY_SIZE: f32 = 44.0;
+// キーの間隔 (ピクセル)
+const KEY_GAP: f32 = 4.0;
+// キーの文字のフォントサイズ
+const KEY_FONT_SIZE: f32 = 20.0;
+// スティックを倒したとみなす大きさ
+const STICK_THRESHOLD: f32 = 0.5;
+// スティックや十字キーを倒し続けたとき、カーソルが続けて動き始めるまでの時間 (秒)
+const REPEAT_DELAY: f32 = 0.35;
+// カーソルが続けて動く間隔 (秒)
+const REPEAT_INTERVAL: f32 = 0.1;
+// キーの色 (通常・選択中・シフトが有効なシフトキー)
+const KEY_COLOR: Color = Color::srgb(0.2, 0.2, 0.25);
+const SELECTED_KEY_COLOR: Color = Color::srgb(0.35, 0.5, 0.9);
+const ACTIVE_KEY_COLOR: Color = Color::srgb(0.3, 0.35, 0.45);
+const PANEL_COLOR: Color = Color::srgba(0.05, 0.05, 0.08, 0.9);
+
+/// 画面上のキーボードを追加するプラグイン
+///
+/// | ゲームパッド | 動作 |
+/// | --- | --- |
+/// | 十字キー・左スティック | キーを選ぶ |
+/// | South (A) | 選んでいるキーを入力する |
+/// | East (B) | 1 文字消す (`Backspace`) |
+/// | Start | 入力を確定する (`Enter`) |
+/// | Select | キーボードの表示・非表示を切り替える |
+pub struct VirtualKeyboardPlugin;
+
+impl Plugin for VirtualKeyboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VirtualKeyboard>()
+            .add_systems(Startup, spawn_keyboard)
+            .add_systems(
+                PreUpdate,
+                (
+                    toggle_keyboard,
+                    (navigate, press_with_gamepad, press_with_mouse).run_if(keyboard_open),
+                )
+                    .chain()
+                    // 送ったイベントを同じフレームの `ButtonInput<KeyCode>` に反映させる
+                    // (ゲームパッドのボタンは 1 フレーム前の状態を読む)
+                    .before(InputSystem),
+            )
+            .add_systems(
+                Update,
+                (show_keyboard, update_key_colors, update_key_labels),
+            );
+    }
+}
+
+/// 画面上のキーボードの状態
+#[derive(Resource, Debug)]
+pub struct VirtualKeyboard {
+    /// キーボードを表示して入力を受け付けるかどうか
+    pub open: bool,
+    /// 英字を大文字で入力するかどうか
+    pub shift: bool,
+    cursor: UVec2,         // 選んでいるマス (x: 列, y: 行)
+    repeat: Option<Timer>, // 倒し続けているときに次にカーソルを動かすまでのタイマー
+}
+
+impl Default for VirtualKeyboard {
+    fn default() -> Self {
+        VirtualKeyboard {
+            open: true,
+            shift: false,
+            cursor: UVec2::ZERO,
+            repeat: None,
+        }
+    }
+}
+
+/// キーボードが表示されているかどうかを判定する条件関数
+///
+/// ゲームパッドで操作するほかのシステムは `.run_if(not(keyboard_open))` を付けると、
+/// 文字を入力している間は動かなくなる。
+pub fn keyboard_open(keyboard: Res<VirtualKeyboard>) -> bool {
+    keyboard.open
+}
+
+/// 画面上のキー 1 つ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VirtualKey {
+    Character(char), // 文字を入力する (英字はシフトで大文字になる)
+    Shift,           // 英字の大文字・小文字を切り替える
+    Space,
+    Backspace,
+    Enter,
+}
+
+impl VirtualKey {
+    /// キーに表示する文字列
+    fn label(self, shift: bool) -> String {
+        match self {
+            VirtualKey::Character(c) if shift => c.to_ascii_uppercase().to_string(),
+            VirtualKey::Character(c) => c.to_string(),
+            VirtualKey::Shift => "Shift".to_string(),
+            VirtualKey::Space => "Space".to_string(),
+            VirtualKey::Backspace => "Del".to_string(),
+            VirtualKey::Enter => "OK".to_string(),
+        }
+    }
+}
+
+/// キーの配列。各行は (キー, 使うマス数) を左から並べたもので、マス数の合計は `COLUMNS`
+fn layout() -> Vec<Vec<(VirtualKey, usize)>> {
+    let mut rows: Vec<Vec<(VirtualKey, usize)>> = CHARACTER_ROWS
+        .iter()
+        .map(|row| row.chars().map(|c| (VirtualKey::Character(c), 1)).collect())
+        .collect();
+    rows.push(vec![
+        (VirtualKey::Shift, 2),
+        (VirtualKey::Space, 4),
+        (VirtualKey::Backspace, 2),
+        (VirtualKey::Enter, 2),
+    ]);
+    rows
+}
+
+/// 指定した行で、列 `column` のマスを使っているキーの (添字, 先頭の列)
+fn key_at(row: &[(VirtualKey, usize)], column: u32) -> (usize, u32) {
+    let mut start = 0;
+    for (index, (_, span)) in row.iter().enumerate() {
+        let end = start + *span as u32;
+        if column < end {
+            return (index, start);
+        }
+        start = end;
+    }
+    (row.len() - 1, start - row[row.len() - 1].1 as u32)
+}
+
+/// キーボード全体 (表示・非表示を切り替える) を示すコンポーネント
+#[derive(Component)]
+struct KeyboardPanel;
+
+/// 画面上のキーを示すコンポーネント
+#[derive(Component)]
+struct KeyButton {
+    key: VirtualKey,
+    row: u32,    // 行
+    column: u32, // 先頭のマスの列
+}
+
+/// キーの文字を示すコンポーネント
+#[derive(Component)]
+struct KeyLabel(VirtualKey);
+
+/// 画面の下にキーボードを配置する
+fn spawn_keyboard(mut commands: Commands, keyboard: Res<VirtualKeyboard>) {
+    let panel = commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(16.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            KeyboardPanel,
+        ))
+        .id();
+    let grid = commands
+        .spawn((
+            Node {
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(COLUMNS as u16, KEY_SIZE),
+                row_gap: Val::Px(KEY_GAP),
+                column_gap: Val::Px(KEY_GAP),
+                padding: UiRect::all(Val::Px(12.0)),
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            BorderRadius::all(Val::Px(8.0)),
+        ))
+        .set_parent(panel)
+        .id();
+
+    for (row, keys) in layout().into_iter().enumerate() {
+        let mut column = 0;
+        for (key, span) in keys {
+            commands
+                .spawn((
+                    Button,
+                    Node {
+                        height: Val::Px(KEY_SIZE),
+                        grid_column: GridPlacement::span(span as u16),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(KEY_COLOR),
+                    BorderRadius::all(Val::Px(4.0)),
+                    KeyButton {
+                        key,
+                        row: row as u32,
+                        column,
+                    },
+                ))
+                .with_child((
+                    Text::new(key.label(keyboard.shift)),
+                    TextFont {
+                        font_size: KEY_FONT_SIZE,
+                        ..default()
+                    },
+                    KeyLabel(key),
+                ))
+                .set_parent(grid);
+            column += span as u32;
+        }
+    }
+}
+
+/// Select ボタンでキーボードの表示・非表示を切り替える
+fn toggle_keyboard(gamepads: Query<&Gamepad>, mut keyboard: ResMut<VirtualKeyboard>) {
+    if gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::Select))
+    {
+        keyboard.open = !keyboard.open;
+    }
+}
+
+/// 十字キーか左スティックでカーソルを動かす
+///
+/// 倒した瞬間に 1 マス動き、倒し続けると `REPEAT_DELAY` のあと `REPEAT_INTERVAL` ごとに動く。
+/// 端から先に動かすと反対側の端に回り込む。
+fn navigate(time: Res<Time>, gamepads: Query<&Gamepad>, mut keyboard: ResMut<VirtualKeyboard>) {
+    let direction = gamepads
+        .iter()
+        .map(|gamepad| gamepad.dpad() + gamepad.left_stick())
+        .find(|direction| direction.length() > STICK_THRESHOLD);
+    // タイマーを進めるだけでは変更として扱わない (キーの色を塗り直さない)
+    let repeat = &mut keyboard.bypass_change_detection().repeat;
+    let Some(direction) = direction else {
+        *repeat = None;
+        return;
+    };
+
+    let should_move = match repeat {
+        None => {
+            *repeat = Some(Timer::from_seconds(REPEAT_DELAY, TimerMode::Once));
+            true
+        }
+        Some(timer) => {
+            timer.tick(time.delta());
+            if timer.finished() {
+                *timer = Timer::from_seconds(REPEAT_INTERVAL, TimerMode::Once);
+                true
+            } else {
+                false
+            }
+        }
+    };
+    if !should_move {
+        return;
+    }
+
+    // 斜めに倒したときは大きいほうの向きにだけ動かす
+    let layout = layout();
+    let rows = layout.len() as u32;
+    let mut cursor = keyboard.cursor;
+    if direction.x.abs() > direction.y.abs() {
+        let row = &layout[cursor.y as usize];
+        let (index, _) = key_at(row, cursor.x);
+        let next = if direction.x > 0.0 {
+            (index + 1) % row.len()
+        } else {
+            (index + row.len() - 1) % row.len()
+        };
+        cursor.x = row[..next].iter().map(|(_, span)| *span as u32).sum();
+    } else if direction.y > 0.0 {
+        cursor.y = (cursor.y + rows - 1) % rows;
+    } else {
+        cursor.y = (cursor.y + 1) % rows;
+    }
+    // 幅の広いキーの上下に動いたときは、そのキーの先頭のマスに合わせる
+    cursor.x = key_at(&layout[cursor.y as usize], cursor.x).1;
+    keyboard.cursor = cursor;
+}
+
+/// South (A) で選んでいるキーを、East (B) で `Backspace` を、Start で `Enter` を入力する
+fn press_with_gamepad(
+    gamepads: Query<&Gamepad>,
+    mut keyboard: ResMut<VirtualKeyboard>,
+    window: Single<Entity, With<PrimaryWindow>>,
+    mut events: EventWriter<KeyboardInput>,
+) {
+    let layout = layout();
+    let row = &layout[keyboard.cursor.y as usize];
+    let selected = row[key_at(row, keyboard.cursor.x).0].0;
+
+    for gamepad in &gamepads {
+        if gamepad.just_pressed(GamepadButton::South) {
+            press(selected, &mut keyboard, *window, &mut events);
+        }
+        if gamepad.just_pressed(GamepadButton::East) {
+            press(VirtualKey::Backspace, &mut keyboard, *window, &mut events);
+        }
+        if gamepad.just_pressed(GamepadButton::Start) {
+            press(VirtualKey::Enter, &mut keyboard, *window, &mut events);
+        }
+    }
+}
+
+/// クリックしたキーを入力し、カーソルをそのキーに合わせる
+fn press_with_mouse(
+    buttons: Query<(&Interaction, &KeyButton), Changed<Interaction>>,
+    mut keyboard: ResMut<VirtualKeyboard>,
+    window: Single<Entity, With<PrimaryWindow>>,
+    mut events: EventWriter<KeyboardInput>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction == Interaction::Pressed {
+            keyboard.cursor = UVec2::new(button.column, button.row);
+            press(button.key, &mut keyboard, *window, &mut events);
+        }
+    }
+}
+
+/// キーを押して離した [`KeyboardInput`] イベントを送る (シフトキーは状態を切り替えるだけ)
+///
+/// 文字キーの物理キー (`key_code`) は `Unidentified` にして、
+/// `ButtonInput<KeyCode>` を見ているゲームのショートカットが文字の入力で反応しないようにする。
+fn press(
+    key: VirtualKey,
+    keyboard: &mut VirtualKeyboard,
+    window: Entity,
+    events: &mut EventWriter<KeyboardInput>,
+) {
+    let (key_code, logical_key) = match key {
+        VirtualKey::Character(c) => {
+            let c = if keyboard.shift {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            };
+            (
+                KeyCode::Unidentified(NativeKeyCode::Unidentified),
+                Key::Character(c.to_string().into()),
+            )
+        }
+        VirtualKey::Shift => {
+            keyboard.shift = !keyboard.shift;
+            return;
+        }
+        VirtualKey::Space => (KeyCode::Space, Key::Space),
+        VirtualKey::Backspace => (KeyCode::Backspace, Key::Backspace),
+        VirtualKey::Enter => (KeyCode::Enter, Key::Enter),
+    };
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        events.send(KeyboardInput {
+            key_code,
+            logical_key: logical_key.clone(),
+            state,
+            repeat: false,
+            window,
+        });
+    }
+}
+
+/// `open` に合わせてキーボードを表示・非表示にする
+fn show_keyboard(
+    keyboard: Res<VirtualKeyboard>,
+    mut panel: Single<&mut Visibility, With<KeyboardPanel>>,
+) {
+    if keyboard.is_changed() {
+        **panel = if keyboard.open {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// 選んでいるキーと、有効になっているシフトキーの色を変える
+fn update_key_colors(
+    keyboard: Res<VirtualKeyboard>,
+    mut buttons: Query<(&KeyButton, &mut BackgroundColor)>,
+) {
+    if !keyboard.is_changed() {
+        return;
+    }
+    for (button, mut color) in &mut buttons {
+        let selected = button.row == keyboard.cursor.y && button.column == keyboard.cursor.x;
+        color.0 = if selected {
+            SELECTED_KEY_COLOR
+        } else if button.key == VirtualKey::Shift && keyboard.shift {
+            ACTIVE_KEY_COLOR
+        } else {
+            KEY_COLOR
+        };
+    }
+}
+
+/// シフトの状態に合わせて英字のキーの大文字・小文字を切り替える
+fn update_key_labels(keyboard: Res<VirtualKeyboard>, mut labels: Query<(&KeyLabel, &mut Text)>) {
+    if !keyboard.is_changed() {
+        return;
+    }
+    for (label, mut text) in &mut labels {
+        let new_label = label.0.label(keyboard.shift);
+        if text.0 != new_label {
+            text.0 = new_label;
+        }
+    }
+}
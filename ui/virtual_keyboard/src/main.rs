@@ -0,0 +1,112 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use virtual_keyboard::{VirtualKeyboard, VirtualKeyboardPlugin};
+
+// 名前の最大の文字数
+const MAX_NAME_LENGTH: usize = 12;
+// 表示しておく登録済みの名前の数
+const MAX_ENTRIES: usize = 5;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, VirtualKeyboardPlugin))
+        .init_resource::<NameEntry>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (type_name, update_name_text).chain())
+        .run();
+}
+
+/// ハイスコアの名前入力欄の状態
+#[derive(Resource, Default)]
+struct NameEntry {
+    name: String,         // 入力中の名前
+    entries: Vec<String>, // 確定した名前 (新しい順)
+}
+
+/// 名前入力欄のテキストを示すコンポーネント
+#[derive(Component)]
+struct NameText;
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 28.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(24.0),
+            left: Val::Px(24.0),
+            ..default()
+        },
+        NameText,
+    ));
+}
+
+/// `KeyboardInput` イベントから名前を入力する
+///
+/// 物理キーボードと画面上のキーボードのどちらから来たイベントかは区別しない。
+fn type_name(mut events: EventReader<KeyboardInput>, mut entry: ResMut<NameEntry>) {
+    for event in events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(text) => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    if entry.name.chars().count() < MAX_NAME_LENGTH {
+                        entry.name.push(c);
+                    }
+                }
+            }
+            Key::Space if entry.name.chars().count() < MAX_NAME_LENGTH => {
+                entry.name.push(' ');
+            }
+            Key::Backspace => {
+                entry.name.pop();
+            }
+            Key::Enter => {
+                let name = std::mem::take(&mut entry.name).trim().to_string();
+                if !name.is_empty() {
+                    entry.entries.insert(0, name);
+                    entry.entries.truncate(MAX_ENTRIES);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 入力中の名前と確定した名前の一覧を表示する
+fn update_name_text(
+    entry: Res<NameEntry>,
+    keyboard: Res<VirtualKeyboard>,
+    mut text: Single<&mut Text, With<NameText>>,
+) {
+    if !entry.is_changed() && !keyboard.is_changed() {
+        return;
+    }
+    let blanks = "_".repeat(MAX_NAME_LENGTH.saturating_sub(entry.name.chars().count()));
+    let mut value = format!(
+        "NEW HIGH SCORE!\nEnter your name: {}{blanks}\n\n",
+        entry.name
+    );
+    if keyboard.open {
+        value += "D-pad / left stick: select key   A: type   B: delete   Start: OK   Select: hide keyboard\n";
+    } else {
+        value += "Select: show keyboard\n";
+    }
+    value += "(The physical keyboard and mouse work too)\n\n";
+    for name in &entry.entries {
+        value += &format!("{name}\n");
+    }
+    text.0 = value;
+}